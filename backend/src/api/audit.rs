@@ -0,0 +1,122 @@
+use axum::{
+    extract::{ConnectInfo, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+use crate::{
+    api::{error::error_response, AppState},
+    models::{ApiResponse, AuditEntry, ExportRequest, ExportResponse},
+};
+
+const DEFAULT_AUDIT_LIMIT: usize = 100;
+const MAX_AUDIT_LIMIT: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub limit: Option<usize>,
+}
+
+pub async fn list_audit(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<ApiResponse<Vec<AuditEntry>>>, StatusCode> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_AUDIT_LIMIT)
+        .clamp(1, MAX_AUDIT_LIMIT);
+    match state.config_store.list_audit(limit) {
+        Ok(entries) => Ok(Json(ApiResponse::success(entries))),
+        Err(e) => Ok(Json(error_response("Failed to read audit log", &e))),
+    }
+}
+
+/// Builds the audit record for an export request before any of its fields are consumed.
+/// Outcome fields and the exported tables are filled in by `record_outcome`.
+pub fn export_entry(
+    req: &ExportRequest,
+    kind: &str,
+    target_schema: Option<&str>,
+    client: Option<&ConnectInfo<SocketAddr>>,
+) -> AuditEntry {
+    AuditEntry {
+        id: None,
+        occurred_at: Utc::now().to_rfc3339(),
+        client_ip: client.map(|ConnectInfo(addr)| addr.ip().to_string()),
+        username: req.config.username.clone(),
+        host: req.config.host.clone(),
+        port: req.config.port,
+        kind: kind.to_string(),
+        source_schema: req.config.schema.clone(),
+        target_schema: target_schema.map(str::to_string),
+        tables: Vec::new(),
+        success: false,
+        file_path: None,
+        message: None,
+    }
+}
+
+/// Persists the outcome of an export over `tables`, the tables selected for it rather than
+/// those the request listed. Audit failures are logged but never fail the export.
+pub fn record_outcome(
+    state: &AppState,
+    mut entry: AuditEntry,
+    tables: Vec<String>,
+    response: &ApiResponse<ExportResponse>,
+) {
+    entry.tables = tables;
+    entry.success = response.success;
+    entry.file_path = response
+        .data
+        .as_ref()
+        .and_then(|data| data.file_path.clone());
+    entry.message = response.error.clone();
+    if let Err(e) = state.config_store.record_audit(&entry) {
+        tracing::warn!("Failed to write audit entry: {:#}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::{export_entry, record_outcome};
+    use crate::api::AppState;
+    use crate::config_store::ConfigStore;
+    use crate::db::driver::driver_override;
+    use crate::db::provider::InMemorySchemaProvider;
+    use crate::models::{ApiResponse, ErrorCode, ExportRequest};
+
+    #[test]
+    fn audit_records_the_selected_tables_not_the_requested_ones() {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+        let state = AppState {
+            config_store: Arc::new(store),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(InMemorySchemaProvider::new()),
+        };
+        let req: ExportRequest = serde_json::from_value(json!({
+            "config": { "username": "SYSDBA", "password": "x", "schema": "APP" },
+            "tables": ["ORDERS", "ORDERS_BAK"],
+            "exclude_patterns": ["_BAK$"],
+            "include_data": true,
+        }))
+        .unwrap();
+
+        let entry = export_entry(&req, "data", None, None);
+        let response = ApiResponse::failure(ErrorCode::OdbcError, "lost".to_string(), None);
+        record_outcome(&state, entry, vec!["ORDERS".to_string()], &response);
+
+        let entries = state.config_store.list_audit(10).unwrap();
+        assert_eq!(entries[0].tables, vec!["ORDERS".to_string()]);
+        assert!(!entries[0].success);
+    }
+}
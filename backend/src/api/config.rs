@@ -1,74 +1,222 @@
-use axum::{extract::State, http::StatusCode, Json};
-use std::env;
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
 
 use crate::{
-    api::AppState,
-    config_store::StoredConnection,
-    models::{ApiResponse, ConfigSource, ConnectionConfig, StoredConnectionResponse},
+    api::{error::error_response, AppState},
+    config_store::{
+        bundle::{decrypt_bundle, encrypt_bundle},
+        provider::{
+            effective_config, resolve_connection, EnvConfig, ProvidedConnection, SavedConfig,
+        },
+        StoredConnection,
+    },
+    db::driver::{check_driver, discover_candidates, validate_driver_path, DRIVER_PATH_SETTING},
+    logging::{self, LOG_LEVEL_SETTING},
+    models::{
+        ApiResponse, ConfigImportRequest, ConfigImportResponse, ConfigSource, ConnectionConfig,
+        DriverCheckResponse, DriverPathRequest, EffectiveConfigResponse, EncryptedConfigBundle,
+        ErrorCode, LogLevelRequest, StoredConnectionResponse,
+    },
 };
 
 pub async fn get_connection(
     State(state): State<AppState>,
 ) -> Result<Json<ApiResponse<StoredConnectionResponse>>, StatusCode> {
-    match state.config_store.get_default() {
-        Ok(Some(stored)) => Ok(Json(ApiResponse::success(to_response(stored)))),
-        Ok(None) => match env_connection_config() {
-            Ok(config) => Ok(Json(ApiResponse::success(StoredConnectionResponse {
-                config,
-                source: ConfigSource::Env,
-                updated_at: None,
-            }))),
-            Err(e) => Ok(Json(ApiResponse::error(format!(
-                "No saved connection and failed to read env: {}",
-                e
-            )))),
-        },
-        Err(e) => Ok(Json(ApiResponse::error(format!(
-            "Failed to read saved config: {}",
-            e
-        )))),
+    match default_connection(&state) {
+        Ok((source, provided)) => Ok(Json(ApiResponse::success(StoredConnectionResponse {
+            config: provided.config,
+            source,
+            updated_at: provided.updated_at,
+        }))),
+        Err(e) => Ok(Json(error_response("Failed to read connection config", &e))),
+    }
+}
+
+/// The connection `get_connection` would return, with the source of each field and the
+/// lower-precedence sources it hides; passwords are masked.
+pub async fn get_effective_config(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<EffectiveConfigResponse>>, StatusCode> {
+    let env = EnvConfig::from_env();
+    match effective_config(&[&SavedConfig(&state.config_store), &env]) {
+        Ok(effective) => Ok(Json(ApiResponse::success(effective))),
+        Err(e) => Ok(Json(error_response("Failed to read connection config", &e))),
     }
 }
 
+/// The saved connection of the active workspace, else the `DATABASE_*` environment.
+pub(crate) fn default_connection(
+    state: &AppState,
+) -> anyhow::Result<(ConfigSource, ProvidedConnection)> {
+    let env = EnvConfig::from_env();
+    resolve_connection(&[&SavedConfig(&state.config_store), &env])
+}
+
+/// Saves the connection of the active workspace. Saved `${scheme:reference}` credentials are
+/// resolved on later requests, so only clients on this machine may save them.
 pub async fn save_connection(
     State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
     Json(config): Json<ConnectionConfig>,
 ) -> Result<Json<ApiResponse<StoredConnectionResponse>>, StatusCode> {
+    if config.has_secret_references()
+        && client.is_some_and(|ConnectInfo(addr)| !addr.ip().is_loopback())
+    {
+        return Err(StatusCode::FORBIDDEN);
+    }
     if let Err(e) = config.validate() {
-        return Ok(Json(ApiResponse::error(format!(
-            "Invalid connection config: {}",
-            e
-        ))));
+        return Ok(Json(ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            format!("Invalid connection config: {}", e),
+            None,
+        )));
     }
 
     match state.config_store.upsert_default(&config) {
         Ok(stored) => Ok(Json(ApiResponse::success(to_response(stored)))),
-        Err(e) => Ok(Json(ApiResponse::error(format!(
-            "Failed to save connection: {}",
-            e
-        )))),
+        Err(e) => Ok(Json(error_response("Failed to save connection", &e))),
     }
 }
 
-fn env_connection_config() -> Result<ConnectionConfig, String> {
-    let host = env::var("DATABASE_HOST").map_err(|_| "DATABASE_HOST not set".to_string())?;
-    let port = env::var("DATABASE_PORT")
-        .map_err(|_| "DATABASE_PORT not set".to_string())
-        .and_then(|v| v.parse::<u16>().map_err(|_| "DATABASE_PORT is not a valid u16".to_string()))?;
-    let username =
-        env::var("DATABASE_USERNAME").map_err(|_| "DATABASE_USERNAME not set".to_string())?;
-    let password =
-        env::var("DATABASE_PASSWORD").map_err(|_| "DATABASE_PASSWORD not set".to_string())?;
-    let schema = env::var("DATABASE_SCHEMA").map_err(|_| "DATABASE_SCHEMA not set".to_string())?;
-
-    Ok(ConnectionConfig {
-        host,
-        port,
-        username,
-        password,
-        schema,
-        export_schema: None,
+/// Switches new connections to another ODBC driver without a restart. The path must be one
+/// of the discovered DM8 drivers and load; it is saved so later starts use it too. Loading a
+/// library runs its code, so only clients on this machine may switch.
+pub async fn save_driver(
+    State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
+    Json(req): Json<DriverPathRequest>,
+) -> Result<Json<ApiResponse<DriverCheckResponse>>, StatusCode> {
+    if client.is_some_and(|ConnectInfo(addr)| !addr.ip().is_loopback()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let path = req.path.trim().to_string();
+    if path.is_empty() {
+        return Ok(Json(ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            "Driver path is required".to_string(),
+            None,
+        )));
+    }
+
+    let candidate = path.clone();
+    let validation = tokio::task::spawn_blocking(move || {
+        validate_driver_path(&candidate, &discover_candidates())
     })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Err(e) = validation {
+        return Ok(Json(ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            format!("Invalid driver: {:#}", e),
+            None,
+        )));
+    }
+
+    if let Err(e) = state.config_store.set_setting(DRIVER_PATH_SETTING, &path) {
+        return Ok(Json(error_response("Failed to save driver path", &e)));
+    }
+    state.driver.set(path.clone());
+    tracing::info!("ODBC driver switched to {}", path);
+
+    let driver = state.driver;
+    let check = tokio::task::spawn_blocking(move || check_driver(driver))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(ApiResponse::success(check)))
+}
+
+/// Changes the backend log level immediately and keeps it for later starts. `RUST_LOG` still
+/// wins at startup when set.
+pub async fn save_log_level(
+    State(state): State<AppState>,
+    Json(req): Json<LogLevelRequest>,
+) -> Result<Json<ApiResponse<LogLevelRequest>>, StatusCode> {
+    let level = match logging::set_level(&req.level) {
+        Ok(level) => level,
+        Err(e) => {
+            return Ok(Json(ApiResponse::failure(
+                ErrorCode::InvalidRequest,
+                format!("{:#}", e),
+                None,
+            )))
+        }
+    };
+
+    if let Err(e) = state.config_store.set_setting(LOG_LEVEL_SETTING, &level) {
+        return Ok(Json(error_response("Failed to save log level", &e)));
+    }
+    tracing::info!("Log level set to {}", level);
+    Ok(Json(ApiResponse::success(LogLevelRequest { level })))
+}
+
+/// Header carrying the passphrase of `GET /api/config/export`, which keeps it out of URLs and
+/// access logs.
+pub const PASSPHRASE_HEADER: &str = "x-config-passphrase";
+
+/// Dumps the workspaces, connections, export profiles, projects and settings as a bundle
+/// encrypted with the passphrase of the `X-Config-Passphrase` header.
+pub async fn export_config(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<EncryptedConfigBundle>>, StatusCode> {
+    let passphrase = headers
+        .get(PASSPHRASE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let bundle = match state.config_store.export_bundle() {
+        Ok(bundle) => bundle,
+        Err(e) => return Ok(Json(error_response("Failed to read saved config", &e))),
+    };
+
+    // Key derivation takes seconds of CPU; keep it off the async workers.
+    let passphrase = passphrase.to_string();
+    let encrypted = tokio::task::spawn_blocking(move || encrypt_bundle(&bundle, &passphrase))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    match encrypted {
+        Ok(file) => Ok(Json(ApiResponse::success(file))),
+        Err(e) => Ok(Json(error_response("Failed to export config", &e))),
+    }
+}
+
+/// Restores a bundle written by [`export_config`] on this or another machine.
+pub async fn import_config(
+    State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
+    Json(req): Json<ConfigImportRequest>,
+) -> Result<Json<ApiResponse<ConfigImportResponse>>, StatusCode> {
+    // A bundle may ask for up to 10M key derivation rounds; keep them off the async workers.
+    let decrypted =
+        tokio::task::spawn_blocking(move || decrypt_bundle(&req.bundle, &req.passphrase))
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let bundle = match decrypted {
+        Ok(bundle) => bundle,
+        Err(e) => return Ok(Json(error_response("Failed to import config", &e))),
+    };
+    if bundle.has_secret_references()
+        && client.is_some_and(|ConnectInfo(addr)| !addr.ip().is_loopback())
+    {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    match state.config_store.import_bundle(&bundle) {
+        Ok(summary) => {
+            tracing::info!(
+                "Imported config bundle: {} workspace(s), {} profile(s), {} project(s)",
+                summary.workspaces,
+                summary.profiles,
+                summary.projects
+            );
+            Ok(Json(ApiResponse::success(summary)))
+        }
+        Err(e) => Ok(Json(error_response("Failed to import config", &e))),
+    }
 }
 
 fn to_response(stored: StoredConnection) -> StoredConnectionResponse {
@@ -88,6 +236,7 @@ mod tests {
     use tempfile::TempDir;
 
     use crate::config_store::ConfigStore;
+    use crate::db::{driver::driver_override, provider::InMemorySchemaProvider};
 
     #[tokio::test]
     async fn get_returns_env_when_no_saved() {
@@ -103,6 +252,9 @@ mod tests {
 
         let state = AppState {
             config_store: Arc::new(store),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(InMemorySchemaProvider::new()),
         };
 
         let response = get_connection(State(state.clone())).await.unwrap();
@@ -119,6 +271,9 @@ mod tests {
         let store = ConfigStore::new_with_path(db_path).unwrap();
         let state = AppState {
             config_store: Arc::new(store),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(InMemorySchemaProvider::new()),
         };
 
         let save_body = json!({
@@ -131,6 +286,7 @@ mod tests {
 
         let save_res = save_connection(
             State(state.clone()),
+            None,
             Json(serde_json::from_value(save_body.clone()).unwrap()),
         )
         .await
@@ -140,10 +296,143 @@ mod tests {
         assert_eq!(saved_data.source, ConfigSource::Sqlite);
         assert_eq!(saved_data.config.host, "sqlite-host");
 
-        let get_res = get_connection(State(state)).await.unwrap();
+        let get_res = get_connection(State(state.clone())).await.unwrap();
         let data = get_res.0.data.unwrap();
         assert_eq!(data.source, ConfigSource::Sqlite);
         assert_eq!(data.config.username, "user1");
         assert!(data.updated_at.is_some());
+
+        let remote = ConnectInfo(SocketAddr::from(([192, 168, 1, 20], 50000)));
+        let mut referenced = save_body;
+        referenced["password"] = json!("${file:/etc/shadow}");
+        let response = save_connection(
+            State(state),
+            Some(remote),
+            Json(serde_json::from_value(referenced).unwrap()),
+        )
+        .await;
+        assert_eq!(response.unwrap_err(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn exported_config_imports_with_the_same_passphrase() {
+        let dir = TempDir::new().unwrap();
+        let state = |file: &str| AppState {
+            config_store: Arc::new(ConfigStore::new_with_path(dir.path().join(file)).unwrap()),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(InMemorySchemaProvider::new()),
+        };
+        let log_level = |state: &AppState| state.config_store.get_setting(LOG_LEVEL_SETTING).unwrap();
+        let source = state("source.db");
+        let store = &source.config_store;
+        store.set_setting(LOG_LEVEL_SETTING, "warn").unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(PASSPHRASE_HEADER, "short".parse().unwrap());
+        let res = export_config(State(source.clone()), headers.clone())
+            .await
+            .unwrap();
+        assert_eq!(res.0.code, Some(ErrorCode::InvalidRequest));
+
+        headers.insert(PASSPHRASE_HEADER, "correct horse".parse().unwrap());
+        let bundle = export_config(State(source), headers)
+            .await
+            .unwrap()
+            .0
+            .data
+            .unwrap();
+
+        let target = state("target.db");
+        let wrong = ConfigImportRequest {
+            passphrase: "wrong horse".to_string(),
+            bundle: bundle.clone(),
+        };
+        let res = import_config(State(target.clone()), None, Json(wrong))
+            .await
+            .unwrap();
+        assert_eq!(res.0.code, Some(ErrorCode::InvalidRequest));
+        assert!(log_level(&target).is_none());
+
+        let right = ConfigImportRequest {
+            passphrase: "correct horse".to_string(),
+            bundle,
+        };
+        let summary = import_config(State(target.clone()), None, Json(right))
+            .await
+            .unwrap()
+            .0
+            .data
+            .unwrap();
+        assert_eq!(summary.settings, 1);
+        assert_eq!(log_level(&target).as_deref(), Some("warn"));
+    }
+
+    #[tokio::test]
+    async fn save_driver_rejects_missing_file_without_persisting() {
+        let dir = TempDir::new().unwrap();
+        let store = Arc::new(ConfigStore::new_with_path(dir.path().join("config.db")).unwrap());
+        let state = AppState {
+            config_store: store.clone(),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(InMemorySchemaProvider::new()),
+        };
+
+        let missing = dir.path().join("libdodbc.so").display().to_string();
+        let response = save_driver(
+            State(state.clone()),
+            None,
+            Json(DriverPathRequest { path: missing }),
+        )
+        .await
+        .unwrap();
+        assert!(!response.0.success);
+        assert_eq!(response.0.code, Some(ErrorCode::InvalidRequest));
+        assert!(response.0.error.unwrap().contains("does not exist"));
+        assert!(store.get_setting(DRIVER_PATH_SETTING).unwrap().is_none());
+
+        let remote = ConnectInfo(SocketAddr::from(([192, 168, 1, 20], 50000)));
+        let path = "/opt/dm8/libdodbc.so".to_string();
+        let response =
+            save_driver(State(state), Some(remote), Json(DriverPathRequest { path })).await;
+        assert_eq!(response.unwrap_err(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn save_log_level_persists_normalized_level() {
+        let dir = TempDir::new().unwrap();
+        let store = Arc::new(ConfigStore::new_with_path(dir.path().join("config.db")).unwrap());
+        let state = AppState {
+            config_store: store.clone(),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(InMemorySchemaProvider::new()),
+        };
+
+        let rejected = save_log_level(
+            State(state.clone()),
+            Json(LogLevelRequest {
+                level: "loud".into(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rejected.0.code, Some(ErrorCode::InvalidRequest));
+        assert!(store.get_setting(LOG_LEVEL_SETTING).unwrap().is_none());
+
+        let saved = save_log_level(
+            State(state),
+            Json(LogLevelRequest {
+                level: "WARN".into(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(saved.0.data.unwrap().level, "warn");
+        assert_eq!(
+            store.get_setting(LOG_LEVEL_SETTING).unwrap().as_deref(),
+            Some("warn")
+        );
     }
 }
@@ -3,59 +3,125 @@ use serde::{Deserialize, Serialize};
 use tracing::error;
 
 use crate::{
-    db::connection::ConnectionPool,
-    models::{ApiResponse, ConnectionConfig},
+    api::error::error_response,
+    db::{
+        backend::DatabaseKind,
+        connection::ConnectionPool,
+        diagnostics::{diagnostics_passed, run_diagnostics},
+    },
+    models::{ApiResponse, ConnectionConfig, DiagnosticStatus, DiagnosticStep},
 };
 
 #[derive(Debug, Deserialize)]
 pub struct TestConnectionRequest {
+    #[serde(default)]
+    pub db_type: DatabaseKind,
+    #[serde(default)]
     pub host: String,
+    #[serde(default)]
     pub port: u16,
     pub username: String,
     pub password: String,
     pub schema: String,
+    /// Pre-configured ODBC data source; `host` and `port` may be omitted when set.
+    #[serde(default)]
+    pub dsn: Option<String>,
+    #[serde(default)]
+    pub ssl: bool,
+    #[serde(default)]
+    pub ssl_cert_path: Option<String>,
+    #[serde(default)]
+    pub ssl_cert_password: Option<String>,
+    #[serde(default)]
+    pub ssl_cipher: Option<String>,
+    #[serde(default)]
+    pub login_timeout_secs: Option<u32>,
+    #[serde(default)]
+    pub query_timeout_secs: Option<u32>,
+    /// Run the step-by-step diagnostics instead of a single connect-and-query check.
+    #[serde(default)]
+    pub diagnostics: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct TestConnectionResponse {
     pub success: bool,
     pub message: String,
+    /// Outcome of each diagnostic step, when they were requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<Vec<DiagnosticStep>>,
 }
 
 pub async fn test_connection(
     Json(req): Json<TestConnectionRequest>,
 ) -> Result<Json<ApiResponse<TestConnectionResponse>>, StatusCode> {
     let config = ConnectionConfig {
+        db_type: req.db_type,
         host: req.host,
         port: req.port,
         username: req.username,
         password: req.password,
         schema: req.schema,
         export_schema: None,
+        dsn: req.dsn,
+        ssl: req.ssl,
+        ssl_cert_path: req.ssl_cert_path,
+        ssl_cert_password: req.ssl_cert_password,
+        ssl_cipher: req.ssl_cipher,
+        login_timeout_secs: req.login_timeout_secs,
+        query_timeout_secs: req.query_timeout_secs,
     };
 
+    if req.diagnostics {
+        let steps = tokio::task::spawn_blocking(move || run_diagnostics(&config))
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        return Ok(Json(ApiResponse::success(diagnostics_response(steps))));
+    }
+
     match ConnectionPool::new(config) {
         Ok(pool) => match pool.test_connection() {
             Ok(_) => Ok(Json(ApiResponse::success(TestConnectionResponse {
                 success: true,
                 message: "Connection successful".to_string(),
+                diagnostics: None,
             }))),
             Err(e) => {
-                let detailed_error = format!("{:#}", e);
-                error!("DM8 connection test failed: {}", detailed_error);
-                Ok(Json(ApiResponse::error(format!(
-                    "Connection test failed: {}",
-                    detailed_error
-                ))))
+                error!("DM8 connection test failed: {:#}", e);
+                Ok(Json(error_response("Connection test failed", &e)))
             }
         },
         Err(e) => {
-            let detailed_error = format!("{:#}", e);
-            error!("Failed to create DM8 connection pool: {}", detailed_error);
-            Ok(Json(ApiResponse::error(format!(
-                "Failed to create connection pool: {}",
-                detailed_error
-            ))))
+            error!("Failed to create DM8 connection pool: {:#}", e);
+            Ok(Json(error_response("Failed to create connection pool", &e)))
         }
     }
 }
+
+/// A failing diagnostic step is a result, not an API error: the response succeeds with
+/// `success: false` and a message naming the first failed step.
+fn diagnostics_response(steps: Vec<DiagnosticStep>) -> TestConnectionResponse {
+    let message = match steps
+        .iter()
+        .find(|step| step.status == DiagnosticStatus::Failed)
+    {
+        Some(step) => {
+            error!(
+                "DM8 connection diagnostics failed at step {}: {}",
+                step.step.as_str(),
+                step.message
+            );
+            format!(
+                "Connection failed at step {}: {}",
+                step.step.as_str(),
+                step.message
+            )
+        }
+        None => "Connection successful".to_string(),
+    };
+    TestConnectionResponse {
+        success: diagnostics_passed(&steps),
+        message,
+        diagnostics: Some(steps),
+    }
+}
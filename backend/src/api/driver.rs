@@ -0,0 +1,22 @@
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::{
+    api::AppState,
+    db::driver::check_driver,
+    models::{ApiResponse, DriverCheckResponse},
+};
+
+/// Loads the configured DM8 ODBC driver without connecting, so a headless server can tell a
+/// missing or broken driver from an unreachable database. A driver that fails to load is
+/// reported in the payload rather than as a request error.
+pub async fn check_driver_handler(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<DriverCheckResponse>>, StatusCode> {
+    let check = tokio::task::spawn_blocking(move || check_driver(state.driver))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(error) = &check.error {
+        tracing::warn!("ODBC driver check failed: {}", error);
+    }
+    Ok(Json(ApiResponse::success(check)))
+}
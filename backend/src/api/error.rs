@@ -0,0 +1,123 @@
+use crate::models::{ApiResponse, ErrorCode};
+
+/// Maps an anyhow error chain onto a stable `ErrorCode`.
+///
+/// Context messages are checked before the underlying error types so that e.g. an
+/// ODBC failure while connecting is reported as `connection_failed`, not `odbc_error`.
+pub fn classify_error(err: &anyhow::Error) -> ErrorCode {
+    let messages: Vec<String> = err.chain().map(|cause| cause.to_string()).collect();
+    let any_contains = |needle: &str| messages.iter().any(|m| m.contains(needle));
+
+    if any_contains("Invalid DM8 connection configuration")
+        || any_contains("Failed to resolve DM8 credentials")
+        || any_contains("Invalid exclude pattern")
+        || any_contains("matches exclude_patterns")
+        || any_contains("config bundle")
+        || any_contains("Invalid DATABASE_* environment")
+        || any_contains("No connection configured")
+    {
+        return ErrorCode::InvalidRequest;
+    }
+    if any_contains("failed to set schema") {
+        return ErrorCode::SchemaNotFound;
+    }
+    if any_contains("Failed to connect to DM8") || any_contains("Unable to open test connection") {
+        return ErrorCode::ConnectionFailed;
+    }
+    if any_contains("does not exist in schema") {
+        return ErrorCode::TableNotFound;
+    }
+    if err
+        .chain()
+        .any(|cause| cause.downcast_ref::<std::io::Error>().is_some())
+    {
+        return ErrorCode::IoError;
+    }
+    if err
+        .chain()
+        .any(|cause| cause.downcast_ref::<rusqlite::Error>().is_some())
+    {
+        return ErrorCode::ConfigStoreError;
+    }
+    if err
+        .chain()
+        .any(|cause| cause.downcast_ref::<odbc_api::Error>().is_some())
+    {
+        return ErrorCode::OdbcError;
+    }
+
+    ErrorCode::Internal
+}
+
+/// Builds an error response with a classified code, the full context chain as message and the
+/// root cause as details.
+pub fn error_response<T>(context: &str, err: &anyhow::Error) -> ApiResponse<T> {
+    ApiResponse::failure(
+        classify_error(err),
+        format!("{}: {:#}", context, err),
+        Some(err.root_cause().to_string()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_error, error_response};
+    use crate::export::selection::compile_exclude_patterns;
+    use crate::models::{ApiResponse, ErrorCode, IssueCode};
+    use anyhow::Context;
+
+    #[test]
+    fn classify_error_detects_missing_table() {
+        let err = anyhow::anyhow!("Table 'T1' does not exist in schema 'APP'")
+            .context("Failed to fetch table metadata for 'T1'");
+        assert_eq!(classify_error(&err), ErrorCode::TableNotFound);
+    }
+
+    #[test]
+    fn classify_error_prefers_connection_context_over_io() {
+        let io = std::io::Error::new(std::io::ErrorKind::Other, "socket closed");
+        let err = Err::<(), _>(io)
+            .context("Failed to connect to DM8 at localhost:5236 as SYSDBA")
+            .unwrap_err();
+        assert_eq!(classify_error(&err), ErrorCode::ConnectionFailed);
+    }
+
+    #[test]
+    fn classify_error_detects_io_errors() {
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let err = Err::<(), _>(io)
+            .context("Failed to create DDL export file at exports/a.sql")
+            .unwrap_err();
+        assert_eq!(classify_error(&err), ErrorCode::IoError);
+    }
+
+    #[test]
+    fn classify_error_treats_bad_exclude_patterns_as_invalid_request() {
+        let err = compile_exclude_patterns(&["re:(".to_string()]).unwrap_err();
+        assert_eq!(classify_error(&err), ErrorCode::InvalidRequest);
+    }
+
+    #[test]
+    fn classify_error_falls_back_to_internal() {
+        let err = anyhow::anyhow!("something unexpected");
+        assert_eq!(classify_error(&err), ErrorCode::Internal);
+    }
+
+    #[test]
+    fn error_response_carries_code_and_chain_details() {
+        let err = anyhow::anyhow!("root cause").context("Invalid DM8 connection configuration");
+        let response: ApiResponse<()> = error_response("Failed to create connection", &err);
+        assert!(!response.success);
+        assert_eq!(response.code, Some(ErrorCode::InvalidRequest));
+        assert_eq!(
+            response.error.as_deref(),
+            Some("Failed to create connection: Invalid DM8 connection configuration: root cause")
+        );
+        assert_eq!(response.details.as_deref(), Some("root cause"));
+        assert_eq!(response.issue_code, Some(IssueCode::InvalidRequest));
+        assert_eq!(
+            serde_json::to_value(response.issue_code).unwrap(),
+            serde_json::json!("E-INVALID-REQUEST")
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use axum::{extract::Json, http::StatusCode};
+
+use crate::{
+    api::{error::error_response, export::selected_tables},
+    db::{connection::ConnectionPool, schema::catalog_name},
+    export::estimate::estimate_tables,
+    models::{ApiResponse, ExportEstimate, ExportRequest},
+};
+
+/// Predicts rows, bytes and duration of exporting the selected tables without exporting them.
+pub async fn estimate_export(
+    Json(req): Json<ExportRequest>,
+) -> Result<Json<ApiResponse<ExportEstimate>>, StatusCode> {
+    let pool = match ConnectionPool::new(req.config.clone()) {
+        Ok(pool) => pool,
+        Err(e) => return Ok(Json(error_response("Failed to create connection", &e))),
+    };
+    let connection = match pool.get_connection() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(Json(error_response("Failed to get connection", &e))),
+    };
+    let tables: Vec<String> = match selected_tables(&connection, &req) {
+        Ok(tables) => tables
+            .iter()
+            .map(|table| catalog_name(table, req.preserve_case))
+            .collect(),
+        Err(e) => return Ok(Json(error_response("Failed to select tables", &e))),
+    };
+
+    let schema = catalog_name(&req.config.schema, req.preserve_case);
+    match estimate_tables(&connection, &schema, &tables, req.exact_counts) {
+        Ok(estimate) => Ok(Json(ApiResponse::success(estimate))),
+        Err(e) => Ok(Json(error_response("Failed to estimate export", &e))),
+    }
+}
@@ -1,12 +1,38 @@
-use axum::{extract::Json, http::StatusCode};
-use chrono::Local;
-use std::path::PathBuf;
+use axum::{
+    extract::{ConnectInfo, Json, Path as AxumPath, State},
+    http::StatusCode,
+};
+use chrono::{FixedOffset, Local};
+use odbc_api::Connection;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::{
+    api::{audit, error::error_response, AppState},
     db::connection::ConnectionPool,
-    export::data::export_schema_data,
-    export::ddl::{export_schema_ddl, TriggerTerminator},
-    models::{ApiResponse, ConnectionConfig, ExportRequest, ExportResponse},
+    db::retry::{RetryPolicy, RetryingConnection},
+    db::schema::{catalog_name, fetch_table_names},
+    export::compare::compare_ddl_files,
+    export::plan::{build_apply_plan, write_plan_files},
+    export::binary::export_schema_binary,
+    export::dmfldr::{export_schema_dmfldr, DelimitedFormat},
+    export::jsonl::export_schema_jsonl,
+    export::xlsx::{export_schema_xlsx, DEFAULT_XLSX_ROW_LIMIT},
+    export::data::{
+        export_schema_data, parse_timezone, DataExportOptions, LiteralStyle, DEFAULT_PREVIEW_ROWS,
+    },
+    export::ddl::{export_schema_ddl, DdlExportOptions, DdlStage, TriggerTerminator},
+    export::dialect::Dialect,
+    export::full::{combine_parts, ScriptPart},
+    export::job::{JobContext, EXPORTS_ROOT},
+    export::memory::DEFAULT_MAX_BATCH_BYTES,
+    export::sample::check_sample,
+    export::selection::{compile_exclude_patterns, exclude_tables},
+    models::{
+        ApiResponse, ApplyPlanResponse, CompareRequest, CompareResponse, DataFormat, ErrorCode,
+        ExportRequest, ExportResponse, ExportStats, JobEventKind, JobTimeline,
+    },
 };
 
 fn normalize_schema_value(value: Option<&str>) -> Option<String> {
@@ -16,7 +42,7 @@ fn normalize_schema_value(value: Option<&str>) -> Option<String> {
         .map(|v| v.to_string())
 }
 
-fn resolve_target_schema(source: &str, export_schema: Option<&str>) -> String {
+pub fn resolve_target_schema(source: &str, export_schema: Option<&str>) -> String {
     normalize_schema_value(export_schema).unwrap_or_else(|| source.trim().to_string())
 }
 
@@ -31,9 +57,10 @@ fn resolve_compat(value: Option<&str>) -> TriggerTerminator {
     }
 }
 
+/// File name of an export artifact; the file lives in the job's own output directory.
 fn format_export_filename(source: &str, target: &str, kind: &str, suffix: &str) -> String {
     format!(
-        "exports/{}_to_{}_{}_{}.sql",
+        "{}_to_{}_{}_{}.sql",
         source.trim(),
         target.trim(),
         kind,
@@ -41,14 +68,232 @@ fn format_export_filename(source: &str, target: &str, kind: &str, suffix: &str)
     )
 }
 
-fn format_error_chain(err: &anyhow::Error) -> String {
+pub fn ddl_options(req: &ExportRequest) -> DdlExportOptions {
+    DdlExportOptions {
+        drop_existing: req.drop_existing,
+        trigger_terminator: resolve_compat(req.export_compat.as_deref()),
+        include_storage: req.include_storage,
+        tablespace_map: req.tablespace_map.clone(),
+        include_grants: req.include_grants,
+        objects: req.ddl_objects.clone(),
+        only_referenced_sequences: req.only_referenced_sequences,
+        sequence_renames: req.sequence_renames.clone(),
+        table_renames: req.table_renames.clone(),
+        encoding: req.encoding,
+        line_ending: req.line_ending,
+        include_dependencies: req.include_dependencies,
+        skip_not_null_checks: req.skip_not_null_checks,
+        dialect: target_dialect(req),
+        preserve_case: req.preserve_case,
+        quote_style: req.quote_style,
+        comments_placement: req.comments_placement,
+        include_schema_ddl: req.include_schema_ddl,
+        schema_owner: req.schema_owner.clone(),
+        stage: DdlStage::Complete,
+        indexes_after_data: req.indexes_after_data,
+    }
+}
+
+/// The parsed `timezone` option.
+pub fn export_timezone(req: &ExportRequest) -> anyhow::Result<Option<FixedOffset>> {
+    req.timezone.as_deref().map(parse_timezone).transpose()
+}
+
+/// `output_dir` with surrounding blanks removed; blank values keep the default root.
+fn chosen_output_dir(output_dir: Option<&str>) -> Option<&str> {
+    output_dir.map(str::trim).filter(|dir| !dir.is_empty())
+}
+
+/// Whether `client` may choose `output_dir`. The directory comes from the desktop app's native
+/// folder picker, so only loopback clients may set one.
+pub(crate) fn output_dir_allowed(
+    output_dir: Option<&str>,
+    client: Option<&ConnectInfo<SocketAddr>>,
+) -> bool {
+    chosen_output_dir(output_dir).is_none()
+        || !client.is_some_and(|ConnectInfo(addr)| !addr.ip().is_loopback())
+}
+
+/// Checks that a chosen `output_dir` is an absolute path naming an existing directory.
+pub(crate) fn check_output_dir(output_dir: Option<&str>) -> anyhow::Result<()> {
+    let Some(dir) = chosen_output_dir(output_dir) else {
+        return Ok(());
+    };
+    let path = Path::new(dir);
+    if !path.is_absolute() {
+        anyhow::bail!("output_dir must be an absolute path: {}", dir);
+    }
+    if !path.is_dir() {
+        anyhow::bail!("output_dir is not an existing directory: {}", dir);
+    }
+    Ok(())
+}
+
+/// Tables an export covers: every table of the schema with `all_tables`, else the requested
+/// ones, without those matching `exclude_patterns`.
+pub fn selected_tables(
+    connection: &Connection<'_>,
+    req: &ExportRequest,
+) -> anyhow::Result<Vec<String>> {
+    let patterns = compile_exclude_patterns(&req.exclude_patterns)?;
+    let tables = if req.all_tables {
+        let schema = catalog_name(&req.config.schema, req.preserve_case);
+        fetch_table_names(connection, &schema)?
+    } else {
+        req.tables.clone()
+    };
+    let tables = exclude_tables(tables, &patterns);
+    if tables.is_empty() && !patterns.is_empty() {
+        anyhow::bail!("Every selected table matches exclude_patterns");
+    }
+    Ok(tables)
+}
+
+/// Selects the tables of a single-schema export and records the job's `Started` event with
+/// their count.
+fn start_tables(
+    connection: &Connection<'_>,
+    req: &ExportRequest,
+    job: &mut JobContext,
+) -> anyhow::Result<Vec<String>> {
+    let tables = selected_tables(connection, req)?;
+    job.record(
+        JobEventKind::Started,
+        None,
+        Some(format!(
+            "{} table(s) from {}",
+            tables.len(),
+            req.config.schema
+        )),
+    );
+    job.selections.push(tables.clone());
+    Ok(tables)
+}
+
+/// Dialect named by `export_compat`; DM8 when it names none.
+fn target_dialect(req: &ExportRequest) -> Dialect {
+    req.export_compat
+        .as_deref()
+        .and_then(Dialect::from_compat)
+        .unwrap_or_default()
+}
+
+/// Data export options of `req`; fails when its `timezone` does not parse.
+pub fn data_options(
+    req: &ExportRequest,
+    row_limit: Option<usize>,
+) -> anyhow::Result<DataExportOptions> {
+    Ok(DataExportOptions {
+        batch_size: req.batch_size.unwrap_or(1000),
+        max_rows_per_insert: req.max_rows_per_insert,
+        max_statement_bytes: req.max_statement_bytes,
+        commit_every_rows: req.commit_every_rows,
+        include_row_counts: req.include_row_counts,
+        row_limit,
+        only_referenced_sequences: req.only_referenced_sequences,
+        sequence_renames: req.sequence_renames.clone(),
+        table_renames: req.table_renames.clone(),
+        encoding: req.encoding,
+        line_ending: req.line_ending,
+        keyset_page_size: req.keyset_page_size,
+        keyset_resume_after: req.keyset_resume_after.clone(),
+        include_dependencies: req.include_dependencies,
+        restart_identities: req.restart_identities,
+        reseed_sequences: req.reseed_sequences,
+        literals: LiteralStyle {
+            escape_line_breaks: req.escape_line_breaks,
+            empty_string_as: req.empty_string_as,
+            dialect: target_dialect(req),
+            timezone: export_timezone(req)?,
+        },
+        preserve_case: req.preserve_case,
+        quote_style: req.quote_style,
+        skip_unreadable: req.skip_unreadable,
+        on_row_error: req.on_row_error,
+        sample: req.sample.clone(),
+        disable_triggers_during_load: req.disable_triggers_during_load,
+        pre_table_sql: req.pre_table_sql.clone(),
+        post_table_sql: req.post_table_sql.clone(),
+        max_batch_bytes: req
+            .adaptive_batch
+            .then(|| req.max_batch_bytes.unwrap_or(DEFAULT_MAX_BATCH_BYTES)),
+    })
+}
+
+pub fn retry_policy(req: &ExportRequest) -> RetryPolicy {
+    let defaults = RetryPolicy::default();
+    RetryPolicy {
+        max_attempts: req.retry_attempts.unwrap_or(defaults.max_attempts).max(1),
+        initial_backoff: req
+            .retry_backoff_ms
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.initial_backoff),
+        ..defaults
+    }
+}
+
+/// Default idle time before an export connection gets a heartbeat.
+const DEFAULT_KEEPALIVE_SECS: u64 = 60;
+
+pub fn keepalive_interval(req: &ExportRequest) -> Option<Duration> {
+    match req.keepalive_secs.unwrap_or(DEFAULT_KEEPALIVE_SECS) {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+pub fn format_error_chain(err: &anyhow::Error) -> String {
     format!("{:#}", err)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{format_error_chain, format_export_filename, resolve_compat, resolve_target_schema};
+    use axum::extract::ConnectInfo;
+    use std::net::SocketAddr;
+    use tempfile::TempDir;
+
+    use super::{
+        check_output_dir, data_options, format_error_chain, format_export_filename,
+        output_dir_allowed, resolve_compat, resolve_target_schema,
+    };
     use crate::export::ddl::TriggerTerminator;
+    use crate::models::ExportRequest;
+
+    #[test]
+    fn data_options_reject_an_invalid_timezone() {
+        let request = |timezone: &str| -> ExportRequest {
+            serde_json::from_value(serde_json::json!({
+                "config": { "username": "SYSDBA", "password": "x", "schema": "APP" },
+                "include_data": true,
+                "timezone": timezone,
+            }))
+            .unwrap()
+        };
+        assert!(data_options(&request("+08:00"), None).is_ok());
+        assert!(data_options(&request("Mars/Olympus"), None).is_err());
+    }
+
+    #[test]
+    fn output_dir_is_reserved_for_loopback_clients() {
+        let local = ConnectInfo("127.0.0.1:50000".parse::<SocketAddr>().unwrap());
+        let remote = ConnectInfo("192.0.2.7:50000".parse::<SocketAddr>().unwrap());
+        assert!(output_dir_allowed(Some("/data/exports"), Some(&local)));
+        assert!(output_dir_allowed(Some("/data/exports"), None));
+        assert!(!output_dir_allowed(Some("/data/exports"), Some(&remote)));
+        assert!(output_dir_allowed(Some("  "), Some(&remote)));
+        assert!(output_dir_allowed(None, Some(&remote)));
+    }
+
+    #[test]
+    fn check_output_dir_requires_an_existing_absolute_directory() {
+        let dir = TempDir::new().unwrap();
+        let existing = dir.path().display().to_string();
+        assert!(check_output_dir(Some(&existing)).is_ok());
+        assert!(check_output_dir(None).is_ok());
+        assert!(check_output_dir(Some("exports")).is_err());
+        let missing = dir.path().join("missing").display().to_string();
+        assert!(check_output_dir(Some(&missing)).is_err());
+    }
 
     #[test]
     fn resolve_target_schema_falls_back_to_source() {
@@ -65,7 +310,7 @@ mod tests {
     #[test]
     fn format_export_filename_includes_source_and_target() {
         let name = format_export_filename("SRC", "TGT", "ddl", "20260130_120000_000");
-        assert_eq!(name, "exports/SRC_to_TGT_ddl_20260130_120000_000.sql");
+        assert_eq!(name, "SRC_to_TGT_ddl_20260130_120000_000.sql");
     }
 
     #[test]
@@ -93,36 +338,62 @@ mod tests {
 }
 
 pub async fn export_ddl(
+    State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
     Json(req): Json<ExportRequest>,
 ) -> Result<Json<ApiResponse<ExportResponse>>, StatusCode> {
-    let config = ConnectionConfig {
-        host: req.config.host,
-        port: req.config.port,
-        username: req.config.username,
-        password: req.config.password,
-        schema: req.config.schema.clone(),
-        export_schema: req.config.export_schema.clone(),
-    };
+    let target_schema = resolve_target_schema(
+        &req.config.schema,
+        req.export_schema
+            .as_deref()
+            .or(req.config.export_schema.as_deref()),
+    );
+    if !output_dir_allowed(req.output_dir.as_deref(), client.as_ref()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let entry = audit::export_entry(&req, "ddl", Some(&target_schema), client.as_ref());
+    let mut job = JobContext::start(state.config_store.clone(), "ddl")
+        .with_output_root(req.output_dir.as_deref());
+    job.metadata = Some(state.metadata_cache.for_connection(&req.config));
+    let outcome = run_ddl_export(req, &mut job);
+    let response = finish_job(&mut job, outcome);
+    audit::record_outcome(&state, entry, job.selections.concat(), &response);
+    Ok(Json(response))
+}
+
+fn run_ddl_export(req: ExportRequest, job: &mut JobContext) -> ApiResponse<ExportResponse> {
+    if let Err(e) = check_output_dir(req.output_dir.as_deref()) {
+        return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None);
+    }
+    if req.ddl_objects.is_empty() {
+        return ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            "ddl_objects must select at least one object type".to_string(),
+            None,
+        );
+    }
+    let options = ddl_options(&req);
+    let retry = retry_policy(&req);
+    let keepalive = keepalive_interval(&req);
+    let config = req.config.clone();
 
     let pool = match ConnectionPool::new(config) {
         Ok(pool) => pool,
         Err(e) => {
-            return Ok(Json(ApiResponse::error(format!(
-                "Failed to create connection: {}",
-                e
-            ))))
+            return error_response("Failed to create connection", &e)
         }
     };
 
     let connection = match pool.get_connection() {
         Ok(conn) => conn,
         Err(e) => {
-            return Ok(Json(ApiResponse::error(format!(
-                "Failed to get connection: {}",
-                e
-            ))))
+            return error_response("Failed to get connection", &e)
         }
     };
+    let tables = match start_tables(&connection, &req, job) {
+        Ok(tables) => tables,
+        Err(e) => return error_response("Failed to select tables", &e),
+    };
 
     let source_schema = req.config.schema.clone();
     let target_schema = resolve_target_schema(
@@ -132,65 +403,102 @@ pub async fn export_ddl(
             .or(req.config.export_schema.as_deref()),
     );
     let date_suffix = Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
-    let output_path = PathBuf::from(format_export_filename(
+    let output_path = job.output_dir().join(format_export_filename(
         &source_schema,
         &target_schema,
         "ddl",
         &date_suffix,
     ));
 
+    job.capabilities = match pool.capabilities(&connection) {
+        Ok(capabilities) => capabilities,
+        Err(e) => return error_response("Failed to detect server capabilities", &e),
+    };
+    let mut session =
+        RetryingConnection::new(&pool, connection, retry).with_keepalive(keepalive);
+
     match export_schema_ddl(
-        &connection,
+        &mut session,
+        job,
         &source_schema,
         &target_schema,
-        &req.tables,
+        &tables,
         &output_path,
-        req.drop_existing,
-        resolve_compat(req.export_compat.as_deref()),
+        &options,
     ) {
-        Ok(_) => Ok(Json(ApiResponse::success(ExportResponse {
+        Ok(_) => ApiResponse::success(ExportResponse {
             success: true,
             message: "DDL exported successfully".to_string(),
             file_path: Some(output_path.to_string_lossy().to_string()),
-        }))),
-        Err(e) => Ok(Json(ApiResponse::error(format!(
-            "Failed to export DDL: {}",
-            format_error_chain(&e)
-        )))),
+            job_id: Some(job.job_id.clone()),
+            output_dir: Some(job.output_dir().to_string_lossy().to_string()),
+            memory: Vec::new(),
+            warnings: Vec::new(),
+            stats: ExportStats::default(),
+        }),
+        Err(e) => {
+            tracing::error!("DDL export failed: {}", format_error_chain(&e));
+            error_response("Failed to export DDL", &e)
+        }
     }
 }
 
 pub async fn export_data(
+    State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
     Json(req): Json<ExportRequest>,
 ) -> Result<Json<ApiResponse<ExportResponse>>, StatusCode> {
-    let config = ConnectionConfig {
-        host: req.config.host,
-        port: req.config.port,
-        username: req.config.username,
-        password: req.config.password,
-        schema: req.config.schema.clone(),
-        export_schema: req.config.export_schema.clone(),
+    let target_schema = resolve_target_schema(
+        &req.config.schema,
+        req.export_schema
+            .as_deref()
+            .or(req.config.export_schema.as_deref()),
+    );
+    if !output_dir_allowed(req.output_dir.as_deref(), client.as_ref()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let entry = audit::export_entry(&req, "data", Some(&target_schema), client.as_ref());
+    let mut job = JobContext::start(state.config_store.clone(), "data")
+        .with_output_root(req.output_dir.as_deref());
+    job.metadata = Some(state.metadata_cache.for_connection(&req.config));
+    let outcome = run_data_export(req, &mut job);
+    let response = finish_job(&mut job, outcome);
+    audit::record_outcome(&state, entry, job.selections.concat(), &response);
+    Ok(Json(response))
+}
+
+fn run_data_export(req: ExportRequest, job: &mut JobContext) -> ApiResponse<ExportResponse> {
+    if let Err(e) = check_output_dir(req.output_dir.as_deref()) {
+        return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None);
+    }
+    if let Some(Err(e)) = req.sample.as_ref().map(check_sample) {
+        return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None);
+    }
+    let options = match data_options(&req, None) {
+        Ok(options) => options,
+        Err(e) => return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None),
     };
+    let retry = retry_policy(&req);
+    let keepalive = keepalive_interval(&req);
+    let config = req.config.clone();
 
     let pool = match ConnectionPool::new(config) {
         Ok(pool) => pool,
         Err(e) => {
-            return Ok(Json(ApiResponse::error(format!(
-                "Failed to create connection: {}",
-                e
-            ))))
+            return error_response("Failed to create connection", &e)
         }
     };
 
     let connection = match pool.get_connection() {
         Ok(conn) => conn,
         Err(e) => {
-            return Ok(Json(ApiResponse::error(format!(
-                "Failed to get connection: {}",
-                e
-            ))))
+            return error_response("Failed to get connection", &e)
         }
     };
+    let tables = match start_tables(&connection, &req, job) {
+        Ok(tables) => tables,
+        Err(e) => return error_response("Failed to select tables", &e),
+    };
 
     let source_schema = req.config.schema.clone();
     let target_schema = resolve_target_schema(
@@ -200,31 +508,542 @@ pub async fn export_data(
             .or(req.config.export_schema.as_deref()),
     );
     let date_suffix = Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
-    let output_path = PathBuf::from(format_export_filename(
+    let output_path = job.output_dir().join(format_export_filename(
         &source_schema,
         &target_schema,
         "data",
         &date_suffix,
     ));
-    let batch_size = req.batch_size.unwrap_or(1000);
+    job.capabilities = match pool.capabilities(&connection) {
+        Ok(capabilities) => capabilities,
+        Err(e) => return error_response("Failed to detect server capabilities", &e),
+    };
+    let mut session =
+        RetryingConnection::new(&pool, connection, retry).with_keepalive(keepalive);
+
+    if req.data_format == DataFormat::Xlsx {
+        let workbook_path = output_path.with_extension("xlsx");
+        return match export_schema_xlsx(
+            &mut session,
+            job,
+            &source_schema,
+            &tables,
+            &workbook_path,
+            &options,
+            req.xlsx_row_limit.unwrap_or(DEFAULT_XLSX_ROW_LIMIT),
+        ) {
+            Ok(_) => ApiResponse::success(ExportResponse {
+                success: true,
+                message: "Workbook exported successfully".to_string(),
+                file_path: Some(workbook_path.to_string_lossy().to_string()),
+                job_id: Some(job.job_id.clone()),
+                output_dir: Some(job.output_dir().to_string_lossy().to_string()),
+                memory: Vec::new(),
+                warnings: Vec::new(),
+                stats: ExportStats::default(),
+            }),
+            Err(e) => {
+                tracing::error!("XLSX export failed: {}", format_error_chain(&e));
+                error_response("Failed to export workbook", &e)
+            }
+        };
+    }
+
+    if req.data_format == DataFormat::Binary {
+        let dump_dir = job.output_dir();
+        return match export_schema_binary(
+            &mut session,
+            job,
+            &source_schema,
+            &target_schema,
+            &tables,
+            &dump_dir,
+            &options,
+        ) {
+            Ok((_, manifest_path)) => ApiResponse::success(ExportResponse {
+                success: true,
+                message: "Binary dump exported successfully".to_string(),
+                file_path: Some(manifest_path.to_string_lossy().to_string()),
+                job_id: Some(job.job_id.clone()),
+                output_dir: Some(dump_dir.to_string_lossy().to_string()),
+                memory: Vec::new(),
+                warnings: Vec::new(),
+                stats: ExportStats::default(),
+            }),
+            Err(e) => {
+                tracing::error!("Binary dump failed: {}", format_error_chain(&e));
+                error_response("Failed to export binary dump", &e)
+            }
+        };
+    }
+
+    if req.data_format == DataFormat::Jsonl {
+        let export_dir = job.output_dir();
+        return match export_schema_jsonl(
+            &mut session,
+            job,
+            &source_schema,
+            &target_schema,
+            &tables,
+            &export_dir,
+            &options,
+        ) {
+            Ok((_, manifest_path)) => ApiResponse::success(ExportResponse {
+                success: true,
+                message: "JSON Lines exported successfully".to_string(),
+                file_path: Some(manifest_path.to_string_lossy().to_string()),
+                job_id: Some(job.job_id.clone()),
+                output_dir: Some(export_dir.to_string_lossy().to_string()),
+                memory: Vec::new(),
+                warnings: Vec::new(),
+                stats: ExportStats::default(),
+            }),
+            Err(e) => {
+                tracing::error!("JSON Lines export failed: {}", format_error_chain(&e));
+                error_response("Failed to export JSON Lines", &e)
+            }
+        };
+    }
+
+    if req.data_format == DataFormat::Dmfldr {
+        let export_dir = job.output_dir();
+        let defaults = DelimitedFormat::default();
+        let format = DelimitedFormat {
+            delimiter: req.dmfldr_delimiter.clone().unwrap_or(defaults.delimiter),
+            null_marker: req.dmfldr_null_marker.clone().unwrap_or(defaults.null_marker),
+        };
+        return match export_schema_dmfldr(
+            &mut session,
+            job,
+            &source_schema,
+            &target_schema,
+            &tables,
+            &export_dir,
+            &options,
+            &format,
+        ) {
+            Ok((_, manifest_path)) => ApiResponse::success(ExportResponse {
+                success: true,
+                message: "dmfldr files exported successfully".to_string(),
+                file_path: Some(manifest_path.to_string_lossy().to_string()),
+                job_id: Some(job.job_id.clone()),
+                output_dir: Some(export_dir.to_string_lossy().to_string()),
+                memory: Vec::new(),
+                warnings: Vec::new(),
+                stats: ExportStats::default(),
+            }),
+            Err(e) => {
+                tracing::error!("dmfldr export failed: {}", format_error_chain(&e));
+                error_response("Failed to export dmfldr files", &e)
+            }
+        };
+    }
 
     match export_schema_data(
-        &connection,
+        &mut session,
+        job,
         &source_schema,
         &target_schema,
-        &req.tables,
+        &tables,
         &output_path,
-        batch_size,
-        req.include_row_counts,
+        &options,
     ) {
-        Ok(_) => Ok(Json(ApiResponse::success(ExportResponse {
+        Ok(_) => ApiResponse::success(ExportResponse {
             success: true,
             message: "Data exported successfully".to_string(),
             file_path: Some(output_path.to_string_lossy().to_string()),
+            job_id: Some(job.job_id.clone()),
+            output_dir: Some(job.output_dir().to_string_lossy().to_string()),
+            memory: Vec::new(),
+            warnings: Vec::new(),
+            stats: ExportStats::default(),
+        }),
+        Err(e) => {
+            tracing::error!("Data export failed: {}", format_error_chain(&e));
+            error_response("Failed to export data", &e)
+        }
+    }
+}
+
+/// Exports a review bundle: the full DDL plus the first `preview_rows` rows of every table, so
+/// owners can check structure and sample content before scheduling the full export.
+pub async fn export_review(
+    State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
+    Json(req): Json<ExportRequest>,
+) -> Result<Json<ApiResponse<ExportResponse>>, StatusCode> {
+    let target_schema = resolve_target_schema(
+        &req.config.schema,
+        req.export_schema
+            .as_deref()
+            .or(req.config.export_schema.as_deref()),
+    );
+    if !output_dir_allowed(req.output_dir.as_deref(), client.as_ref()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let entry = audit::export_entry(&req, "review", Some(&target_schema), client.as_ref());
+    let mut job = JobContext::start(state.config_store.clone(), "review")
+        .with_output_root(req.output_dir.as_deref());
+    job.metadata = Some(state.metadata_cache.for_connection(&req.config));
+    let outcome = run_review_export(req, &mut job);
+    let response = finish_job(&mut job, outcome);
+    audit::record_outcome(&state, entry, job.selections.concat(), &response);
+    Ok(Json(response))
+}
+
+fn run_review_export(req: ExportRequest, job: &mut JobContext) -> ApiResponse<ExportResponse> {
+    if let Err(e) = check_output_dir(req.output_dir.as_deref()) {
+        return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None);
+    }
+    let preview_rows = req.preview_rows.unwrap_or(DEFAULT_PREVIEW_ROWS);
+    if preview_rows == 0 {
+        return ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            "preview_rows must be greater than zero".to_string(),
+            None,
+        );
+    }
+    let data = match data_options(&req, Some(preview_rows)) {
+        Ok(options) => options,
+        Err(e) => return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None),
+    };
+
+    let config = req.config.clone();
+
+    let pool = match ConnectionPool::new(config) {
+        Ok(pool) => pool,
+        Err(e) => {
+            return error_response("Failed to create connection", &e)
+        }
+    };
+
+    let connection = match pool.get_connection() {
+        Ok(conn) => conn,
+        Err(e) => {
+            return error_response("Failed to get connection", &e)
+        }
+    };
+    let tables = match start_tables(&connection, &req, job) {
+        Ok(tables) => tables,
+        Err(e) => return error_response("Failed to select tables", &e),
+    };
+
+    let source_schema = req.config.schema.clone();
+    let target_schema = resolve_target_schema(
+        &source_schema,
+        req.export_schema
+            .as_deref()
+            .or(req.config.export_schema.as_deref()),
+    );
+    let bundle_dir = job.output_dir();
+
+    job.capabilities = match pool.capabilities(&connection) {
+        Ok(capabilities) => capabilities,
+        Err(e) => return error_response("Failed to detect server capabilities", &e),
+    };
+    let mut session = RetryingConnection::new(&pool, connection, retry_policy(&req))
+        .with_keepalive(keepalive_interval(&req));
+
+    let outcome = export_schema_ddl(
+        &mut session,
+        job,
+        &source_schema,
+        &target_schema,
+        &tables,
+        &bundle_dir.join("ddl.sql"),
+        &ddl_options(&req),
+    )
+    .and_then(|_| {
+        export_schema_data(
+            &mut session,
+            job,
+            &source_schema,
+            &target_schema,
+            &tables,
+            &bundle_dir.join("data.sql"),
+            &data,
+        )
+    });
+
+    match outcome {
+        Ok(_) => ApiResponse::success(ExportResponse {
+            success: true,
+            message: format!(
+                "Review bundle exported with up to {} rows per table",
+                preview_rows
+            ),
+            file_path: Some(bundle_dir.to_string_lossy().to_string()),
+            job_id: Some(job.job_id.clone()),
+            output_dir: Some(job.output_dir().to_string_lossy().to_string()),
+            memory: Vec::new(),
+            warnings: Vec::new(),
+            stats: ExportStats::default(),
+        }),
+        Err(e) => {
+            tracing::error!("Review export failed: {}", format_error_chain(&e));
+            error_response("Failed to export review bundle", &e)
+        }
+    }
+}
+
+/// Exports DDL and data as one script: tables, constraints, indexes and sequences, then the
+/// rows, then foreign keys, triggers, views and grants, so the load neither fires triggers nor
+/// checks foreign keys.
+pub async fn export_full(
+    State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
+    Json(req): Json<ExportRequest>,
+) -> Result<Json<ApiResponse<ExportResponse>>, StatusCode> {
+    let target_schema = resolve_target_schema(
+        &req.config.schema,
+        req.export_schema
+            .as_deref()
+            .or(req.config.export_schema.as_deref()),
+    );
+    if !output_dir_allowed(req.output_dir.as_deref(), client.as_ref()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let entry = audit::export_entry(&req, "full", Some(&target_schema), client.as_ref());
+    let mut job = JobContext::start(state.config_store.clone(), "full")
+        .with_output_root(req.output_dir.as_deref());
+    job.metadata = Some(state.metadata_cache.for_connection(&req.config));
+    let outcome = run_full_export(req, &mut job);
+    let response = finish_job(&mut job, outcome);
+    audit::record_outcome(&state, entry, job.selections.concat(), &response);
+    Ok(Json(response))
+}
+
+fn run_full_export(req: ExportRequest, job: &mut JobContext) -> ApiResponse<ExportResponse> {
+    if let Err(e) = check_output_dir(req.output_dir.as_deref()) {
+        return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None);
+    }
+    if req.ddl_objects.is_empty() {
+        return ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            "ddl_objects must select at least one object type".to_string(),
+            None,
+        );
+    }
+    if req.data_format != DataFormat::Sql {
+        return ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            "A full export writes its data as SQL; use data_format \"sql\"".to_string(),
+            None,
+        );
+    }
+    if let Some(Err(e)) = req.sample.as_ref().map(check_sample) {
+        return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None);
+    }
+    let data = match data_options(&req, None) {
+        Ok(options) => options,
+        Err(e) => return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None),
+    };
+
+    let pool = match ConnectionPool::new(req.config.clone()) {
+        Ok(pool) => pool,
+        Err(e) => return error_response("Failed to create connection", &e),
+    };
+    let connection = match pool.get_connection() {
+        Ok(conn) => conn,
+        Err(e) => return error_response("Failed to get connection", &e),
+    };
+    let tables = match start_tables(&connection, &req, job) {
+        Ok(tables) => tables,
+        Err(e) => return error_response("Failed to select tables", &e),
+    };
+
+    let source_schema = req.config.schema.clone();
+    let target_schema = resolve_target_schema(
+        &source_schema,
+        req.export_schema
+            .as_deref()
+            .or(req.config.export_schema.as_deref()),
+    );
+    let date_suffix = Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+    let output_path = job.output_dir().join(format_export_filename(
+        &source_schema,
+        &target_schema,
+        "full",
+        &date_suffix,
+    ));
+    let (schema_title, deferred_title) = if req.indexes_after_data {
+        (
+            "建表 (表、约束、序列)",
+            "延后对象 (索引、外键、触发器、视图、权限)",
+        )
+    } else {
+        (
+            "建表 (表、约束、索引、序列)",
+            "延后对象 (外键、触发器、视图、权限)",
+        )
+    };
+    let parts = [
+        ScriptPart::new(schema_title, "schema", &output_path),
+        ScriptPart::new("数据", "data", &output_path),
+        ScriptPart::new(deferred_title, "deferred", &output_path),
+    ];
+    let before_data = DdlExportOptions {
+        stage: DdlStage::BeforeData,
+        ..ddl_options(&req)
+    };
+    let after_data = DdlExportOptions {
+        stage: DdlStage::AfterData,
+        ..ddl_options(&req)
+    };
+
+    job.capabilities = match pool.capabilities(&connection) {
+        Ok(capabilities) => capabilities,
+        Err(e) => return error_response("Failed to detect server capabilities", &e),
+    };
+    let mut session = RetryingConnection::new(&pool, connection, retry_policy(&req))
+        .with_keepalive(keepalive_interval(&req));
+
+    let outcome = export_schema_ddl(
+        &mut session,
+        job,
+        &source_schema,
+        &target_schema,
+        &tables,
+        &parts[0].path,
+        &before_data,
+    )
+    .and_then(|_| {
+        export_schema_data(
+            &mut session,
+            job,
+            &source_schema,
+            &target_schema,
+            &tables,
+            &parts[1].path,
+            &data,
+        )
+    })
+    .and_then(|_| {
+        export_schema_ddl(
+            &mut session,
+            job,
+            &source_schema,
+            &target_schema,
+            &tables,
+            &parts[2].path,
+            &after_data,
+        )
+    })
+    .and_then(|_| combine_parts(&parts, &output_path, req.encoding, req.line_ending));
+
+    match outcome {
+        Ok(_) => ApiResponse::success(ExportResponse {
+            success: true,
+            message: "DDL and data exported successfully".to_string(),
+            file_path: Some(output_path.to_string_lossy().to_string()),
+            job_id: Some(job.job_id.clone()),
+            output_dir: Some(job.output_dir().to_string_lossy().to_string()),
+            memory: Vec::new(),
+            warnings: Vec::new(),
+            stats: ExportStats::default(),
+        }),
+        Err(e) => {
+            tracing::error!("Full export failed: {}", format_error_chain(&e));
+            error_response("Failed to export DDL and data", &e)
+        }
+    }
+}
+
+/// Closes the job with the export outcome; failures still carry the job id so the timeline can
+/// be looked up.
+pub fn finish_job(
+    job: &mut JobContext,
+    mut response: ApiResponse<ExportResponse>,
+) -> ApiResponse<ExportResponse> {
+    let file_path = response.data.as_ref().and_then(|data| data.file_path.clone());
+    job.finish(
+        response.success,
+        file_path.as_deref(),
+        response.error.as_deref(),
+        response.issue_code,
+    );
+
+    if response.data.is_none() {
+        response.data = Some(ExportResponse {
+            success: false,
+            message: response.error.clone().unwrap_or_default(),
+            file_path: None,
+            job_id: Some(job.job_id.clone()),
+            // Partial artifacts of a failed run stay in the job directory for inspection.
+            output_dir: job
+                .output_dir()
+                .exists()
+                .then(|| job.output_dir().to_string_lossy().to_string()),
+            memory: Vec::new(),
+            warnings: Vec::new(),
+            stats: ExportStats::default(),
+        });
+    }
+    if let Some(data) = response.data.as_mut() {
+        data.memory = job.memory.clone();
+        data.warnings = job.warnings().to_vec();
+        data.stats = job.stats.clone();
+    }
+    response
+}
+
+pub async fn get_job_timeline(
+    State(state): State<AppState>,
+    AxumPath(job_id): AxumPath<String>,
+) -> Result<Json<ApiResponse<JobTimeline>>, StatusCode> {
+    match state.config_store.get_job_timeline(&job_id) {
+        Ok(Some(timeline)) => Ok(Json(ApiResponse::success(timeline))),
+        Ok(None) => Ok(Json(ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            format!("Export job '{}' not found", job_id),
+            None,
+        ))),
+        Err(e) => Ok(Json(error_response("Failed to read job timeline", &e))),
+    }
+}
+
+/// Where the compare and plan endpoints may read artifacts: `exports/` and the directories of
+/// the files export jobs produced elsewhere (a chosen `output_dir`).
+fn export_dirs(state: &AppState) -> anyhow::Result<Vec<PathBuf>> {
+    let mut dirs = vec![PathBuf::from(EXPORTS_ROOT)];
+    dirs.extend(state.config_store.job_output_dirs()?);
+    Ok(dirs)
+}
+
+pub async fn compare_exports(
+    State(state): State<AppState>,
+    Json(req): Json<CompareRequest>,
+) -> Result<Json<ApiResponse<CompareResponse>>, StatusCode> {
+    let compared = export_dirs(&state).and_then(|dirs| {
+        compare_ddl_files(Path::new(&req.left_path), Path::new(&req.right_path), &dirs)
+    });
+    match compared {
+        Ok(diff) => Ok(Json(ApiResponse::success(diff))),
+        Err(e) => Ok(Json(error_response("Failed to compare exports", &e))),
+    }
+}
+
+/// Plans the ALTER/DROP/CREATE steps turning the left DDL artifact into the right one and writes
+/// the apply and rollback scripts next to the right artifact.
+pub async fn plan_exports(
+    State(state): State<AppState>,
+    Json(req): Json<CompareRequest>,
+) -> Result<Json<ApiResponse<ApplyPlanResponse>>, StatusCode> {
+    let right = Path::new(&req.right_path);
+    let compared = export_dirs(&state)
+        .and_then(|dirs| compare_ddl_files(Path::new(&req.left_path), right, &dirs));
+    let diff = match compared {
+        Ok(diff) => diff,
+        Err(e) => return Ok(Json(error_response("Failed to compare exports", &e))),
+    };
+
+    let plan = build_apply_plan(&diff);
+    match write_plan_files(right, &plan) {
+        Ok((apply_path, rollback_path)) => Ok(Json(ApiResponse::success(ApplyPlanResponse {
+            plan,
+            apply_path,
+            rollback_path,
         }))),
-        Err(e) => Ok(Json(ApiResponse::error(format!(
-            "Failed to export data: {}",
-            format_error_chain(&e)
-        )))),
+        Err(e) => Ok(Json(error_response("Failed to write apply plan", &e))),
     }
 }
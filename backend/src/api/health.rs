@@ -0,0 +1,99 @@
+use std::{sync::OnceLock, time::Instant};
+
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::{
+    api::AppState,
+    db::driver::resolve_driver,
+    export::job::active_jobs,
+    models::{ApiResponse, ConfigDbInfo, HealthResponse},
+};
+
+static STARTED_AT: OnceLock<Instant> = OnceLock::new();
+
+/// Starts the uptime clock; the first call wins.
+pub fn mark_started() {
+    STARTED_AT.get_or_init(Instant::now);
+}
+
+/// Backend diagnostics for the desktop shell: build version, uptime, which ODBC driver new
+/// connections would load, whether the config database can be written and how many export
+/// jobs are running.
+pub async fn health_check(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<HealthResponse>>, StatusCode> {
+    let store = state.config_store.clone();
+    let writable = tokio::task::spawn_blocking(move || store.check_writable())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let config_db = ConfigDbInfo {
+        path: state.config_store.path().display().to_string(),
+        writable: writable.is_ok(),
+        error: writable.err().map(|e| format!("{:#}", e)),
+    };
+
+    let driver = resolve_driver(state.driver);
+    let healthy = config_db.writable && driver.exists != Some(false);
+    let uptime_secs = STARTED_AT
+        .get()
+        .map(|started| started.elapsed().as_secs())
+        .unwrap_or_default();
+
+    Ok(Json(ApiResponse::success(HealthResponse {
+        status: if healthy { "ok" } else { "degraded" }.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        uptime_secs,
+        driver,
+        config_db,
+        active_jobs: active_jobs(),
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use serde_json::{json, Value};
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    use crate::api::{create_router, AppState};
+    use crate::config_store::ConfigStore;
+    use crate::db::driver::driver_override;
+    use crate::db::provider::InMemorySchemaProvider;
+
+    #[tokio::test]
+    async fn health_reports_config_db_and_version() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("config.db");
+        let router = create_router(AppState {
+            config_store: Arc::new(ConfigStore::new_with_path(db_path.clone()).unwrap()),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(InMemorySchemaProvider::new()),
+        });
+
+        let response = router
+            .oneshot(Request::get("/api/health").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap())
+                .unwrap();
+
+        let data = &body["data"];
+        assert_eq!(data["version"], json!(env!("CARGO_PKG_VERSION")));
+        assert_eq!(
+            data["config_db"]["path"],
+            json!(db_path.display().to_string())
+        );
+        assert_eq!(data["config_db"]["writable"], json!(true));
+        assert!(data["active_jobs"].is_u64());
+        assert!(data["driver"]["source"].is_string());
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::{anyhow, Context};
+use axum::{
+    extract::{Json, Multipart},
+    http::StatusCode,
+};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use crate::{
+    api::error::error_response,
+    db::connection::ConnectionPool,
+    export::binary::{import_binary_dump, DEFAULT_IMPORT_BATCH_SIZE},
+    export::csv_import::{import_csv_file, CsvFormat, DEFAULT_CSV_BATCH_SIZE},
+    export::manifest::read_manifest,
+    models::{ApiResponse, BinaryImportRequest, CsvImportRequest, ImportReport},
+};
+
+/// Bulk-loads a binary dump into the target database with ODBC array inserts.
+pub async fn import_binary(
+    Json(req): Json<BinaryImportRequest>,
+) -> Result<Json<ApiResponse<ImportReport>>, StatusCode> {
+    let manifest_path = Path::new(&req.manifest_path);
+    let manifest = match read_manifest(manifest_path) {
+        Ok(manifest) => manifest,
+        Err(e) => return Ok(Json(error_response("Failed to load manifest", &e))),
+    };
+    let schema = req
+        .schema
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(&manifest.target_schema)
+        .to_uppercase();
+    let dump_dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+
+    // The loader writes, so the session must not be switched to read-only.
+    let pool = match ConnectionPool::new(req.config) {
+        Ok(pool) => pool.with_read_only(false),
+        Err(e) => return Ok(Json(error_response("Failed to create connection", &e))),
+    };
+
+    let connection = match pool.get_connection() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(Json(error_response("Failed to get connection", &e))),
+    };
+
+    match import_binary_dump(
+        &connection,
+        &manifest,
+        dump_dir,
+        &schema,
+        req.batch_size.unwrap_or(DEFAULT_IMPORT_BATCH_SIZE),
+    ) {
+        Ok(report) => Ok(Json(ApiResponse::success(report))),
+        Err(e) => Ok(Json(error_response("Failed to import binary dump", &e))),
+    }
+}
+
+/// Bulk-loads a CSV file on the server into one table with ODBC array inserts.
+pub async fn import_csv(
+    Json(req): Json<CsvImportRequest>,
+) -> Result<Json<ApiResponse<ImportReport>>, StatusCode> {
+    let Some(file_path) = req.file_path.clone().filter(|p| !p.trim().is_empty()) else {
+        return Ok(Json(error_response(
+            "Failed to import CSV",
+            &anyhow!("file_path is required"),
+        )));
+    };
+    Ok(Json(run_csv_import(req, Path::new(&file_path))))
+}
+
+/// Multipart variant of [`import_csv`]: a `request` part with the JSON request and a `file`
+/// part with the CSV content, which is streamed to a temporary file before loading.
+pub async fn import_csv_upload(
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<ImportReport>>, StatusCode> {
+    let upload_path = std::env::temp_dir().join(format!(
+        "dm8-csv-upload-{}-{}.csv",
+        std::process::id(),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    let result = receive_upload(&mut multipart, &upload_path).await;
+    let response = match result {
+        Ok(req) => run_csv_import(req, &upload_path),
+        Err(e) => error_response("Failed to receive CSV upload", &e),
+    };
+    let _ = fs::remove_file(&upload_path);
+    Ok(Json(response))
+}
+
+async fn receive_upload(
+    multipart: &mut Multipart,
+    upload_path: &Path,
+) -> anyhow::Result<CsvImportRequest> {
+    let mut request = None;
+    let mut received_file = false;
+    while let Some(mut field) = multipart.next_field().await? {
+        match field.name() {
+            Some("request") => {
+                let text = field.text().await?;
+                request = Some(
+                    serde_json::from_str::<CsvImportRequest>(&text)
+                        .context("Invalid import request")?,
+                );
+            }
+            Some("file") => {
+                let mut file = File::create(upload_path).with_context(|| {
+                    format!("Failed to create upload file {}", upload_path.display())
+                })?;
+                while let Some(chunk) = field.chunk().await? {
+                    file.write_all(&chunk)?;
+                }
+                received_file = true;
+            }
+            _ => {}
+        }
+    }
+    anyhow::ensure!(received_file, "Upload has no 'file' part");
+    request.ok_or_else(|| anyhow!("Upload has no 'request' part"))
+}
+
+fn run_csv_import(req: CsvImportRequest, path: &Path) -> ApiResponse<ImportReport> {
+    let delimiter = match req.delimiter.as_deref() {
+        None => b',',
+        Some(d) if d.len() == 1 => d.as_bytes()[0],
+        Some("\\t") => b'\t',
+        Some(d) => {
+            return error_response(
+                "Failed to import CSV",
+                &anyhow!("delimiter must be a single byte, got '{}'", d),
+            )
+        }
+    };
+    let format = CsvFormat {
+        delimiter,
+        has_header: req.has_header,
+        null_value: req.null_value.clone(),
+    };
+    let schema = req
+        .schema
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(&req.config.schema)
+        .to_uppercase();
+    let table = req.table.trim().to_uppercase();
+
+    // The loader writes, so the session must not be switched to read-only.
+    let pool = match ConnectionPool::new(req.config) {
+        Ok(pool) => pool.with_read_only(false),
+        Err(e) => return error_response("Failed to create connection", &e),
+    };
+    let connection = match pool.get_connection() {
+        Ok(conn) => conn,
+        Err(e) => return error_response("Failed to get connection", &e),
+    };
+
+    match import_csv_file(
+        &connection,
+        path,
+        &schema,
+        &table,
+        &req.columns,
+        &format,
+        req.batch_size.unwrap_or(DEFAULT_CSV_BATCH_SIZE),
+    ) {
+        Ok(report) => ApiResponse::success(report),
+        Err(e) => error_response("Failed to import CSV", &e),
+    }
+}
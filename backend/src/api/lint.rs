@@ -0,0 +1,47 @@
+use axum::{extract::Json, http::StatusCode};
+
+use crate::{
+    api::error::error_response,
+    db::connection::ConnectionPool,
+    export::lint::lint_tables,
+    models::{ApiResponse, ErrorCode, LintReport, LintRequest},
+};
+
+/// Scores the selected tables' export readiness without writing any files.
+pub async fn lint_schema(
+    Json(req): Json<LintRequest>,
+) -> Result<Json<ApiResponse<LintReport>>, StatusCode> {
+    if req.tables.is_empty() {
+        return Ok(Json(ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            "tables must list at least one table".to_string(),
+            None,
+        )));
+    }
+
+    let schema = req.config.schema.clone();
+    let pool = match ConnectionPool::new(req.config) {
+        Ok(pool) => pool,
+        Err(e) => return Ok(Json(error_response("Failed to create connection", &e))),
+    };
+
+    let connection = match pool.get_connection() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(Json(error_response("Failed to get connection", &e))),
+    };
+
+    let capabilities = match pool.capabilities(&connection) {
+        Ok(capabilities) => capabilities,
+        Err(e) => {
+            return Ok(Json(error_response(
+                "Failed to detect server capabilities",
+                &e,
+            )))
+        }
+    };
+
+    match lint_tables(&connection, &capabilities, &schema, &req.tables) {
+        Ok(report) => Ok(Json(ApiResponse::success(report))),
+        Err(e) => Ok(Json(error_response("Failed to lint tables", &e))),
+    }
+}
@@ -0,0 +1,73 @@
+use axum::{
+    extract::{ConnectInfo, Query},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+use crate::{
+    api::error::error_response,
+    logging::{log_dir, tail},
+    models::{ApiResponse, ErrorCode, LogTailResponse},
+};
+
+const DEFAULT_TAIL_LINES: usize = 500;
+const MAX_TAIL_LINES: usize = 5000;
+
+#[derive(Debug, Deserialize)]
+pub struct LogTailQuery {
+    pub lines: Option<usize>,
+}
+
+/// Recent backend log lines for support tickets; packaged desktop builds have no visible
+/// stderr. Only loopback clients may read them.
+pub async fn tail_logs(
+    client: Option<ConnectInfo<SocketAddr>>,
+    Query(query): Query<LogTailQuery>,
+) -> Result<Json<ApiResponse<LogTailResponse>>, StatusCode> {
+    if client.is_some_and(|ConnectInfo(addr)| !addr.ip().is_loopback()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let lines = query
+        .lines
+        .unwrap_or(DEFAULT_TAIL_LINES)
+        .clamp(1, MAX_TAIL_LINES);
+    let Some(dir) = log_dir() else {
+        return Ok(Json(ApiResponse::failure(
+            ErrorCode::IoError,
+            "Unable to determine home directory".to_string(),
+            None,
+        )));
+    };
+
+    let result = tokio::task::spawn_blocking(move || tail(&dir, lines))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    match result {
+        Ok(tail) => Ok(Json(ApiResponse::success(tail))),
+        Err(e) => Ok(Json(error_response("Failed to read backend logs", &e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        extract::{ConnectInfo, Query},
+        http::StatusCode,
+    };
+    use std::net::SocketAddr;
+
+    use super::{tail_logs, LogTailQuery};
+
+    #[tokio::test]
+    async fn tail_logs_rejects_remote_clients() {
+        let remote: SocketAddr = "192.0.2.7:40000".parse().unwrap();
+        let result = tail_logs(
+            Some(ConnectInfo(remote)),
+            Query(LogTailQuery { lines: Some(1) }),
+        )
+        .await;
+        assert_eq!(result.err(), Some(StatusCode::FORBIDDEN));
+    }
+}
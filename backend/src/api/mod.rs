@@ -2,34 +2,167 @@ pub mod connection;
 pub mod schema;
 pub mod export;
 pub mod config;
+pub mod error;
+pub mod driver;
+pub mod health;
+pub mod logs;
+pub mod audit;
+pub mod lint;
+pub mod verify;
+pub mod import;
+pub mod project;
+pub mod profile;
+pub mod workspace;
+pub mod server;
+pub mod estimate;
 
 use axum::{
-    routing::{get, post},
+    http::{HeaderValue, Request},
+    routing::{delete, get, post},
     Router,
 };
 use crate::config_store::ConfigStore;
+use crate::db::{
+    driver::DriverOverride, metadata_cache::MetadataCache, provider::SchemaProvider,
+};
 use std::sync::Arc;
-use tower_http::cors::CorsLayer;
+use tower_http::{
+    cors::{AllowOrigin, Any, CorsLayer},
+    trace::TraceLayer,
+};
+
+/// Origins of the app's own frontend: the Tauri webview and the Vite dev server. Browsers
+/// refuse cross-origin JSON requests from any other page, so a site open in the user's
+/// browser cannot save `${scheme:reference}` credentials through the loopback address.
+const ALLOWED_ORIGINS: [&str; 4] = [
+    "tauri://localhost",
+    "https://tauri.localhost",
+    "http://localhost:5173",
+    "http://127.0.0.1:5173",
+];
+
+/// Request span with the method and path only: the schema endpoints carry the connection
+/// password in their query string, which must not reach the logs.
+fn request_span<B>(request: &Request<B>) -> tracing::Span {
+    tracing::debug_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+    )
+}
+
+fn cors_layer() -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(
+            ALLOWED_ORIGINS.map(HeaderValue::from_static),
+        ))
+        .allow_methods(Any)
+        .allow_headers(Any)
+}
 
 #[derive(Clone)]
 pub struct AppState {
     pub config_store: Arc<ConfigStore>,
+    /// ODBC driver chosen through `POST /api/config/driver`.
+    pub driver: &'static DriverOverride,
+    pub metadata_cache: Arc<MetadataCache>,
+    /// Metadata behind the table listing, details and dependency endpoints.
+    pub schema_provider: Arc<dyn SchemaProvider>,
 }
 
 pub fn create_router(state: AppState) -> Router {
+    health::mark_started();
     Router::new()
-        .route("/api/health", get(health_check))
+        .route("/api/health", get(health::health_check))
+        .route("/api/driver/check", get(driver::check_driver_handler))
         .route("/api/connection/test", post(connection::test_connection))
+        .route("/api/server/info", get(server::server_info))
         .route("/api/schemas", get(schema::list_schemas))
         .route("/api/tables", get(schema::list_tables))
+        .route("/api/tables/details/batch", post(schema::get_table_details_batch))
         .route("/api/tables/:table/details", get(schema::get_table_details_handler))
+        .route("/api/tables/:table/dependencies", get(schema::get_table_dependencies))
         .route("/api/export/ddl", post(export::export_ddl))
         .route("/api/export/data", post(export::export_data))
+        .route("/api/export/review", post(export::export_review))
+        .route("/api/export/full", post(export::export_full))
+        .route("/api/export/estimate", post(estimate::estimate_export))
+        .route("/api/export/compare", post(export::compare_exports))
+        .route("/api/export/plan", post(export::plan_exports))
+        .route("/api/export/jobs/:id/timeline", get(export::get_job_timeline))
+        .route("/api/lint", post(lint::lint_schema))
+        .route("/api/verify", post(verify::verify_export))
+        .route("/api/import/binary", post(import::import_binary))
+        .route("/api/import/csv", post(import::import_csv))
+        .route("/api/import/csv/upload", post(import::import_csv_upload))
+        .route("/api/projects", get(project::list_projects).post(project::save_project))
+        .route("/api/projects/:name", delete(project::delete_project))
+        .route("/api/projects/:name/run", post(project::run_project))
         .route("/api/config/connection", get(config::get_connection).post(config::save_connection))
-        .layer(CorsLayer::permissive())
+        .route("/api/config/driver", post(config::save_driver))
+        .route("/api/config/log-level", post(config::save_log_level))
+        .route("/api/config/effective", get(config::get_effective_config))
+        .route("/api/config/export", get(config::export_config))
+        .route("/api/config/import", post(config::import_config))
+        .route("/api/config/profiles", get(profile::list_profiles).post(profile::save_profile))
+        .route("/api/config/profiles/:name", get(profile::get_profile).delete(profile::delete_profile))
+        .route("/api/config/profiles/:name/run", post(profile::run_profile))
+        .route("/api/workspaces", get(workspace::list_workspaces).post(workspace::save_workspace))
+        .route("/api/workspaces/:name", delete(workspace::delete_workspace))
+        .route("/api/workspaces/:name/activate", post(workspace::activate_workspace))
+        .route("/api/logs/tail", get(logs::tail_logs))
+        .route("/api/audit", get(audit::list_audit))
+        .route("/api/cache/invalidate", post(schema::invalidate_cache))
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
+        .layer(cors_layer())
         .with_state(state)
 }
 
-async fn health_check() -> &'static str {
-    "OK"
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::Body,
+        http::{header, HeaderMap, Method, Request},
+    };
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    use super::{create_router, AppState};
+    use crate::config_store::ConfigStore;
+    use crate::db::driver::driver_override;
+    use crate::db::provider::InMemorySchemaProvider;
+
+    async fn preflight(origin: &str) -> HeaderMap {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+        let router = create_router(AppState {
+            config_store: Arc::new(store),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(InMemorySchemaProvider::new()),
+        });
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/config/connection")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap();
+        router.oneshot(request).await.unwrap().headers().clone()
+    }
+
+    #[tokio::test]
+    async fn cors_allows_the_app_frontend() {
+        let headers = preflight("tauri://localhost").await;
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "tauri://localhost");
+    }
+
+    #[tokio::test]
+    async fn cors_rejects_other_origins() {
+        let headers = preflight("https://attacker.example").await;
+        assert!(headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
 }
@@ -0,0 +1,96 @@
+use axum::{
+    extract::{ConnectInfo, Json, Path as AxumPath, State},
+    http::StatusCode,
+};
+use std::net::SocketAddr;
+
+use crate::{
+    api::{
+        config::default_connection, error::error_response, project::run_saved_project, AppState,
+    },
+    export::project::{profile_project, validate_profile},
+    models::{ApiResponse, ErrorCode, ExportProfile, ExportResponse},
+};
+
+pub async fn list_profiles(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<ExportProfile>>>, StatusCode> {
+    match state.config_store.list_profiles() {
+        Ok(profiles) => Ok(Json(ApiResponse::success(profiles))),
+        Err(e) => Ok(Json(error_response("Failed to read profiles", &e))),
+    }
+}
+
+pub async fn get_profile(
+    State(state): State<AppState>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<ApiResponse<ExportProfile>>, StatusCode> {
+    match state.config_store.get_profile(&name) {
+        Ok(Some(profile)) => Ok(Json(ApiResponse::success(profile))),
+        Ok(None) => Ok(Json(profile_not_found(&name))),
+        Err(e) => Ok(Json(error_response("Failed to read profile", &e))),
+    }
+}
+
+pub async fn save_profile(
+    State(state): State<AppState>,
+    Json(profile): Json<ExportProfile>,
+) -> Result<Json<ApiResponse<ExportProfile>>, StatusCode> {
+    if let Err(e) = validate_profile(&profile) {
+        return Ok(Json(ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            format!("Invalid profile: {:#}", e),
+            None,
+        )));
+    }
+
+    match state.config_store.upsert_profile(&profile) {
+        Ok(()) => Ok(Json(ApiResponse::success(profile))),
+        Err(e) => Ok(Json(error_response("Failed to save profile", &e))),
+    }
+}
+
+pub async fn delete_profile(
+    State(state): State<AppState>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<ApiResponse<bool>>, StatusCode> {
+    match state.config_store.delete_profile(&name) {
+        Ok(deleted) => Ok(Json(ApiResponse::success(deleted))),
+        Err(e) => Ok(Json(error_response("Failed to delete profile", &e))),
+    }
+}
+
+/// Re-runs a saved profile over the saved connection (or the `DATABASE_*` environment) as a
+/// one-schema project job: artifacts go to `exports/<job_id>/01_<schema>/`.
+pub async fn run_profile(
+    State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<ApiResponse<ExportResponse>>, StatusCode> {
+    let profile = match state.config_store.get_profile(&name) {
+        Ok(Some(profile)) => profile,
+        Ok(None) => return Ok(Json(profile_not_found(&name))),
+        Err(e) => return Ok(Json(error_response("Failed to read profile", &e))),
+    };
+
+    let config = match default_connection(&state) {
+        Ok((_, provided)) => provided.config,
+        Err(e) => return Ok(Json(error_response("Failed to read connection config", &e))),
+    };
+
+    let project = profile_project(&profile, &config);
+    Ok(Json(run_saved_project(
+        &state,
+        client.as_ref(),
+        &project,
+        "profile",
+    )))
+}
+
+fn profile_not_found<T>(name: &str) -> ApiResponse<T> {
+    ApiResponse::failure(
+        ErrorCode::InvalidRequest,
+        format!("Export profile '{}' not found", name),
+        None,
+    )
+}
@@ -0,0 +1,281 @@
+use axum::{
+    extract::{ConnectInfo, Json, Path as AxumPath, State},
+    http::StatusCode,
+};
+use std::{fs, net::SocketAddr};
+
+use crate::{
+    api::{
+        audit,
+        error::error_response,
+        export::{
+            check_output_dir, data_options, ddl_options, finish_job, format_error_chain,
+            keepalive_interval, output_dir_allowed, resolve_target_schema, retry_policy,
+            selected_tables,
+        },
+        AppState,
+    },
+    db::{connection::ConnectionPool, retry::RetryingConnection},
+    export::data::export_schema_data,
+    export::ddl::export_schema_ddl,
+    export::job::JobContext,
+    export::project::{member_dir_name, member_request, validate_project, write_project_order},
+    models::{ApiResponse, ErrorCode, ExportProject, ExportResponse, ExportStats, JobEventKind},
+};
+
+pub async fn list_projects(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<ExportProject>>>, StatusCode> {
+    match state.config_store.list_projects() {
+        Ok(projects) => Ok(Json(ApiResponse::success(projects))),
+        Err(e) => Ok(Json(error_response("Failed to read projects", &e))),
+    }
+}
+
+/// Saves a project. Member `${scheme:reference}` credentials are resolved when it runs, so
+/// only clients on this machine may save them.
+pub async fn save_project(
+    State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
+    Json(project): Json<ExportProject>,
+) -> Result<Json<ApiResponse<ExportProject>>, StatusCode> {
+    let has_references = project
+        .members
+        .iter()
+        .any(|member| member.config.has_secret_references());
+    if has_references && client.is_some_and(|ConnectInfo(addr)| !addr.ip().is_loopback()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if let Err(e) = validate_project(&project) {
+        return Ok(Json(ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            format!("Invalid project: {:#}", e),
+            None,
+        )));
+    }
+
+    match state.config_store.upsert_project(&project) {
+        Ok(()) => Ok(Json(ApiResponse::success(project))),
+        Err(e) => Ok(Json(error_response("Failed to save project", &e))),
+    }
+}
+
+pub async fn delete_project(
+    State(state): State<AppState>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<ApiResponse<bool>>, StatusCode> {
+    match state.config_store.delete_project(&name) {
+        Ok(deleted) => Ok(Json(ApiResponse::success(deleted))),
+        Err(e) => Ok(Json(error_response("Failed to delete project", &e))),
+    }
+}
+
+/// Exports every schema of a saved project as one job. Member artifacts go to
+/// `exports/<job_id>/<NN>_<schema>/`, with `project_order.txt` listing the files in execution
+/// order. The run stops at the first failing member.
+pub async fn run_project(
+    State(state): State<AppState>,
+    client: Option<ConnectInfo<SocketAddr>>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<ApiResponse<ExportResponse>>, StatusCode> {
+    let project = match state.config_store.get_project(&name) {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            return Ok(Json(ApiResponse::failure(
+                ErrorCode::InvalidRequest,
+                format!("Export project '{}' not found", name),
+                None,
+            )))
+        }
+        Err(e) => return Ok(Json(error_response("Failed to read project", &e))),
+    };
+    Ok(Json(run_saved_project(
+        &state,
+        client.as_ref(),
+        &project,
+        "project",
+    )))
+}
+
+/// Runs `project` as one job of `kind` and audits every member's export.
+pub(crate) fn run_saved_project(
+    state: &AppState,
+    client: Option<&ConnectInfo<SocketAddr>>,
+    project: &ExportProject,
+    kind: &str,
+) -> ApiResponse<ExportResponse> {
+    let output_dir = project
+        .options
+        .get("output_dir")
+        .and_then(|root| root.as_str());
+    if !output_dir_allowed(output_dir, client) {
+        return ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            "output_dir can only be chosen from the local app".to_string(),
+            None,
+        );
+    }
+    if let Err(e) = check_output_dir(output_dir) {
+        return ApiResponse::failure(ErrorCode::InvalidRequest, format!("{:#}", e), None);
+    }
+    let mut job = JobContext::start(state.config_store.clone(), kind).with_output_root(output_dir);
+    job.record(
+        JobEventKind::Started,
+        None,
+        Some(format!(
+            "{} schema(s) in project {}",
+            project.members.len(),
+            project.name
+        )),
+    );
+    let outcome = run_project_export(project, &mut job);
+    let response = finish_job(&mut job, outcome);
+
+    for (index, member) in project.members.iter().enumerate() {
+        if let Ok(req) = member_request(project, member) {
+            let target_schema = resolve_target_schema(
+                &req.config.schema,
+                req.export_schema
+                    .as_deref()
+                    .or(req.config.export_schema.as_deref()),
+            );
+            let entry = audit::export_entry(&req, kind, Some(&target_schema), client);
+            let tables = job.selections.get(index).cloned().unwrap_or_default();
+            audit::record_outcome(state, entry, tables, &response);
+        }
+    }
+    response
+}
+
+fn run_project_export(
+    project: &ExportProject,
+    job: &mut JobContext,
+) -> ApiResponse<ExportResponse> {
+    if let Err(e) = validate_project(project) {
+        return ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            format!("Invalid project: {:#}", e),
+            None,
+        );
+    }
+
+    let output_dir = job.output_dir();
+    let mut ddl_files = Vec::new();
+    let mut data_files = Vec::new();
+
+    for (index, member) in project.members.iter().enumerate() {
+        let req = match member_request(project, member) {
+            Ok(req) => req,
+            Err(e) => return error_response("Invalid project member", &e),
+        };
+        let data = match data_options(&req, None) {
+            Ok(options) => options,
+            Err(e) => {
+                return ApiResponse::failure(
+                    ErrorCode::InvalidRequest,
+                    format!("Invalid project member: {:#}", e),
+                    None,
+                )
+            }
+        };
+        let source_schema = req.config.schema.clone();
+        let target_schema = resolve_target_schema(
+            &source_schema,
+            req.export_schema
+                .as_deref()
+                .or(req.config.export_schema.as_deref()),
+        );
+        let pool = match ConnectionPool::new(req.config.clone()) {
+            Ok(pool) => pool,
+            Err(e) => return error_response("Failed to create connection", &e),
+        };
+
+        let connection = match pool.get_connection() {
+            Ok(conn) => conn,
+            Err(e) => return error_response("Failed to get connection", &e),
+        };
+        let tables = match selected_tables(&connection, &req) {
+            Ok(tables) => tables,
+            Err(e) => return error_response("Failed to select tables", &e),
+        };
+        job.record(
+            JobEventKind::Started,
+            None,
+            Some(format!(
+                "Schema {} of {}: {} -> {} ({} table(s))",
+                index + 1,
+                project.members.len(),
+                source_schema,
+                target_schema,
+                tables.len()
+            )),
+        );
+        job.selections.push(tables.clone());
+        job.capabilities = match pool.capabilities(&connection) {
+            Ok(capabilities) => capabilities,
+            Err(e) => return error_response("Failed to detect server capabilities", &e),
+        };
+        let mut session = RetryingConnection::new(&pool, connection, retry_policy(&req))
+            .with_keepalive(keepalive_interval(&req));
+
+        let dir_name = member_dir_name(index, &source_schema);
+        let member_dir = output_dir.join(&dir_name);
+        if let Err(e) = export_schema_ddl(
+            &mut session,
+            job,
+            &source_schema,
+            &target_schema,
+            &tables,
+            &member_dir.join("ddl.sql"),
+            &ddl_options(&req),
+        ) {
+            tracing::error!("Project DDL export failed: {}", format_error_chain(&e));
+            return error_response(
+                &format!("Failed to export DDL of schema {}", source_schema),
+                &e,
+            );
+        }
+        ddl_files.push(format!("{}/ddl.sql", dir_name));
+
+        if project.include_data {
+            if let Err(e) = export_schema_data(
+                &mut session,
+                job,
+                &source_schema,
+                &target_schema,
+                &tables,
+                &member_dir.join("data.sql"),
+                &data,
+            ) {
+                tracing::error!("Project data export failed: {}", format_error_chain(&e));
+                return error_response(
+                    &format!("Failed to export data of schema {}", source_schema),
+                    &e,
+                );
+            }
+            data_files.push(format!("{}/data.sql", dir_name));
+        }
+    }
+
+    if let Err(e) = fs::create_dir_all(&output_dir)
+        .map_err(anyhow::Error::from)
+        .and_then(|_| write_project_order(&output_dir, project, &ddl_files, &data_files))
+    {
+        return error_response("Failed to write project order", &e);
+    }
+
+    ApiResponse::success(ExportResponse {
+        success: true,
+        message: format!(
+            "Project {} exported: {} schema(s)",
+            project.name,
+            project.members.len()
+        ),
+        file_path: Some(output_dir.to_string_lossy().to_string()),
+        job_id: Some(job.job_id.clone()),
+        output_dir: Some(output_dir.to_string_lossy().to_string()),
+        memory: Vec::new(),
+        warnings: Vec::new(),
+        stats: ExportStats::default(),
+    })
+}
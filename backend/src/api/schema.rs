@@ -1,111 +1,274 @@
 use axum::{
-    extract::{Json, Path, Query},
+    extract::{Json, Path, Query, State},
     http::StatusCode,
 };
 use serde::Deserialize;
 
 use crate::{
+    api::{error::error_response, AppState},
     db::{
-        connection::ConnectionPool,
-        schema::{get_table_details, get_tables},
+        backend::DatabaseKind, capabilities::forget_capabilities, metadata_cache::connection_key,
+    },
+    export::dependency::table_dependencies,
+    models::{
+        ApiResponse, CacheInvalidateRequest, CacheInvalidateResponse, ConnectionConfig, ErrorCode,
+        Table, TableDependencies, TableDetails, TableDetailsBatchRequest,
     },
-    models::{ApiResponse, ConnectionConfig, Table, TableDetails},
 };
 
 #[derive(Debug, Deserialize)]
 pub struct SchemaQuery {
+    #[serde(default)]
+    pub db_type: DatabaseKind,
+    #[serde(default)]
     pub host: String,
+    #[serde(default)]
     pub port: u16,
     pub username: String,
     pub password: String,
     pub schema: String,
+    /// Pre-configured ODBC data source; `host` and `port` may be omitted when set.
+    #[serde(default)]
+    pub dsn: Option<String>,
+    #[serde(default)]
+    pub ssl: bool,
+    #[serde(default)]
+    pub ssl_cert_path: Option<String>,
+    #[serde(default)]
+    pub ssl_cert_password: Option<String>,
+    #[serde(default)]
+    pub ssl_cipher: Option<String>,
+    #[serde(default)]
+    pub login_timeout_secs: Option<u32>,
+    #[serde(default)]
+    pub query_timeout_secs: Option<u32>,
+}
+
+impl SchemaQuery {
+    fn connection_config(&self) -> ConnectionConfig {
+        ConnectionConfig {
+            db_type: self.db_type,
+            host: self.host.clone(),
+            port: self.port,
+            username: self.username.clone(),
+            password: self.password.clone(),
+            schema: self.schema.clone(),
+            export_schema: None,
+            dsn: self.dsn.clone(),
+            ssl: self.ssl,
+            ssl_cert_path: self.ssl_cert_path.clone(),
+            ssl_cert_password: self.ssl_cert_password.clone(),
+            ssl_cipher: self.ssl_cipher.clone(),
+            login_timeout_secs: self.login_timeout_secs,
+            query_timeout_secs: self.query_timeout_secs,
+        }
+    }
 }
 
 pub async fn list_schemas() -> Json<ApiResponse<Vec<String>>> {
-    Json(ApiResponse::error(
+    Json(ApiResponse::failure(
+        ErrorCode::NotImplemented,
         "List schemas not implemented yet".to_string(),
+        None,
     ))
 }
 
 pub async fn list_tables(
+    State(state): State<AppState>,
     Query(query): Query<SchemaQuery>,
 ) -> Result<Json<ApiResponse<Vec<Table>>>, StatusCode> {
-    let config = ConnectionConfig {
-        host: query.host,
-        port: query.port,
-        username: query.username,
-        password: query.password,
-        schema: query.schema.clone(),
-        export_schema: None,
-    };
-
-    let pool = match ConnectionPool::new(config) {
-        Ok(pool) => pool,
-        Err(e) => {
-            return Ok(Json(ApiResponse::error(format!(
-                "Failed to create connection: {}",
-                e
-            ))))
-        }
-    };
-
-    let connection = match pool.get_connection() {
-        Ok(conn) => conn,
-        Err(e) => {
-            return Ok(Json(ApiResponse::error(format!(
-                "Failed to get connection: {}",
-                e
-            ))))
-        }
-    };
-
-    match get_tables(&connection, &query.schema) {
+    let config = query.connection_config();
+    match state.schema_provider.get_tables(&config, &query.schema) {
         Ok(tables) => Ok(Json(ApiResponse::success(tables))),
-        Err(e) => Ok(Json(ApiResponse::error(format!(
-            "Failed to get tables: {}",
-            e
-        )))),
+        Err(e) => Ok(Json(error_response("Failed to get tables", &e))),
     }
 }
 
 pub async fn get_table_details_handler(
+    State(state): State<AppState>,
     Path(table): Path<String>,
     Query(query): Query<SchemaQuery>,
 ) -> Result<Json<ApiResponse<TableDetails>>, StatusCode> {
-    let config = ConnectionConfig {
-        host: query.host,
-        port: query.port,
-        username: query.username,
-        password: query.password,
-        schema: query.schema.clone(),
-        export_schema: None,
+    let config = query.connection_config();
+    match state
+        .schema_provider
+        .get_table_details(&config, &query.schema, &table)
+    {
+        Ok(details) => Ok(Json(ApiResponse::success(details))),
+        Err(e) => Ok(Json(error_response("Failed to get table details", &e))),
+    }
+}
+
+/// Details of every requested table in one response, read over a single connection instead of
+/// one request (and connection) per table.
+pub async fn get_table_details_batch(
+    State(state): State<AppState>,
+    Json(req): Json<TableDetailsBatchRequest>,
+) -> Result<Json<ApiResponse<Vec<TableDetails>>>, StatusCode> {
+    match state
+        .schema_provider
+        .get_tables_details(&req.config, &req.config.schema, &req.tables)
+    {
+        Ok(details) => Ok(Json(ApiResponse::success(details))),
+        Err(e) => Ok(Json(error_response("Failed to get table details", &e))),
+    }
+}
+
+/// Tables, triggers and sequences `table` depends on or is needed by, for auto-including
+/// dependencies in a partial export.
+pub async fn get_table_dependencies(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Query(query): Query<SchemaQuery>,
+) -> Result<Json<ApiResponse<TableDependencies>>, StatusCode> {
+    let config = query.connection_config();
+    let provider = &state.schema_provider;
+    let details = match provider.get_table_details(&config, &query.schema, &table) {
+        Ok(details) => details,
+        Err(e) => return Ok(Json(error_response("Failed to get table details", &e))),
     };
 
-    let pool = match ConnectionPool::new(config) {
-        Ok(pool) => pool,
-        Err(e) => {
-            return Ok(Json(ApiResponse::error(format!(
-                "Failed to create connection: {}",
-                e
-            ))))
-        }
+    match provider.fetch_referencing_tables(&config, &query.schema, &table) {
+        Ok(referenced_by) => Ok(Json(ApiResponse::success(table_dependencies(
+            &query.schema,
+            &details,
+            referenced_by,
+        )))),
+        Err(e) => Ok(Json(error_response("Failed to get referencing tables", &e))),
+    }
+}
+
+/// Drops cached table metadata, e.g. after the source schema changed, so the next preview or
+/// export reads the catalog again.
+pub async fn invalidate_cache(
+    State(state): State<AppState>,
+    Json(req): Json<CacheInvalidateRequest>,
+) -> Result<Json<ApiResponse<CacheInvalidateResponse>>, StatusCode> {
+    let connection = req.config.as_ref().map(connection_key);
+    let schema = req
+        .schema
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    // Whole-server invalidation also re-probes the catalog capabilities, e.g. after an upgrade.
+    if schema.is_none() && req.tables.is_empty() {
+        forget_capabilities(connection.as_deref());
+    }
+    let invalidated = state
+        .metadata_cache
+        .invalidate(connection.as_deref(), schema, &req.tables);
+    Ok(Json(ApiResponse::success(CacheInvalidateResponse {
+        invalidated,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+        Router,
     };
+    use serde_json::{json, Value};
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    use crate::api::{create_router, AppState};
+    use crate::config_store::ConfigStore;
+    use crate::db::{driver::driver_override, provider::InMemorySchemaProvider};
+    use crate::models::{Column, ForeignKey, TableDetails};
 
-    let connection = match pool.get_connection() {
-        Ok(conn) => conn,
-        Err(e) => {
-            return Ok(Json(ApiResponse::error(format!(
-                "Failed to get connection: {}",
-                e
-            ))))
+    fn table(name: &str, foreign_keys: Vec<ForeignKey>) -> TableDetails {
+        let id = Column {
+            nullable: false,
+            ..Column::named("ID", "INT")
+        };
+        TableDetails {
+            foreign_keys,
+            ..TableDetails::named(name, vec![id], &["ID"])
         }
-    };
+    }
 
-    match get_table_details(&connection, &query.schema, &table) {
-        Ok(details) => Ok(Json(ApiResponse::success(details))),
-        Err(e) => Ok(Json(ApiResponse::error(format!(
-            "Failed to get table details: {}",
-            e
-        )))),
+    fn router(dir: &TempDir) -> Router {
+        let provider = InMemorySchemaProvider::new()
+            .with_table("APP", table("CUSTOMERS", Vec::new()))
+            .with_table(
+                "APP",
+                table(
+                    "ORDERS",
+                    vec![ForeignKey {
+                        name: "FK_ORDERS_CUSTOMER".to_string(),
+                        columns: vec!["ID".to_string()],
+                        referenced_table: "APP.CUSTOMERS".to_string(),
+                        referenced_columns: vec!["ID".to_string()],
+                        delete_rule: None,
+                        update_rule: None,
+                    }],
+                ),
+            );
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+        create_router(AppState {
+            config_store: Arc::new(store),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(provider),
+        })
+    }
+
+    async fn send(router: Router, request: Request<Body>) -> Value {
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn get(uri: &str) -> Request<Body> {
+        let query = "username=u&password=p&schema=app";
+        let separator = if uri.contains('?') { '&' } else { '?' };
+        Request::get(format!("{}{}{}", uri, separator, query))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn table_endpoints_serve_provider_metadata() {
+        let dir = TempDir::new().unwrap();
+
+        let tables = send(router(&dir), get("/api/tables")).await;
+        assert_eq!(tables["success"], json!(true));
+        assert_eq!(tables["data"][0]["name"], json!("CUSTOMERS"));
+        assert_eq!(tables["data"][1]["name"], json!("ORDERS"));
+
+        let details = send(router(&dir), get("/api/tables/orders/details")).await;
+        assert_eq!(details["data"]["primary_keys"], json!(["ID"]));
+
+        let missing = send(router(&dir), get("/api/tables/NOPE/details")).await;
+        assert_eq!(missing["success"], json!(false));
+        assert_eq!(missing["code"], json!("table_not_found"));
+
+        let dependencies = send(router(&dir), get("/api/tables/CUSTOMERS/dependencies")).await;
+        assert_eq!(dependencies["data"]["referenced_by"][0]["table"], json!("ORDERS"));
+
+        let batch = Request::post("/api/tables/details/batch")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "config": {
+                        "host": "", "port": 0, "username": "u", "password": "p", "schema": "APP"
+                    },
+                    "tables": ["ORDERS", "CUSTOMERS"]
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let batch = send(router(&dir), batch).await;
+        assert_eq!(batch["data"][0]["name"], json!("ORDERS"));
+        assert_eq!(batch["data"][1]["name"], json!("CUSTOMERS"));
+
+        // A table named like the batch route's first segment is still a table.
+        let named_details = send(router(&dir), get("/api/tables/details/details")).await;
+        assert_eq!(named_details["code"], json!("table_not_found"));
     }
 }
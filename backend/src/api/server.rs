@@ -0,0 +1,74 @@
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::{
+    api::{config::default_connection, error::error_response, AppState},
+    db::capabilities::ServerInfo,
+    models::ApiResponse,
+};
+
+/// Version, character set, case sensitivity, page size and catalog capabilities of the server
+/// behind the saved (or `DATABASE_*`) connection.
+pub async fn server_info(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<ServerInfo>>, StatusCode> {
+    let config = match default_connection(&state) {
+        Ok((_, provided)) => provided.config,
+        Err(e) => return Ok(Json(error_response("Failed to read connection config", &e))),
+    };
+    let provider = state.schema_provider.clone();
+    let info = tokio::task::spawn_blocking(move || provider.server_info(&config))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    match info {
+        Ok(info) => Ok(Json(ApiResponse::success(info))),
+        Err(e) => Ok(Json(error_response("Failed to read server info", &e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::config_store::ConfigStore;
+    use crate::db::{
+        capabilities::ServerCapabilities, driver::driver_override, provider::InMemorySchemaProvider,
+    };
+    use crate::models::ConnectionConfig;
+
+    #[tokio::test]
+    async fn reports_the_server_behind_the_saved_connection() {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+        let config: ConnectionConfig = serde_json::from_value(serde_json::json!({
+            "host": "db-host",
+            "port": 5236,
+            "username": "SYSDBA",
+            "password": "secret",
+            "schema": "APP"
+        }))
+        .unwrap();
+        store.upsert_default(&config).unwrap();
+
+        let info = ServerInfo {
+            version: Some("DM Database Server 64 V8".to_string()),
+            banner: vec!["DM Database Server 64 V8".to_string()],
+            charset: Some("UTF-8".to_string()),
+            case_sensitive: Some(true),
+            page_size: Some(8192),
+            capabilities: ServerCapabilities::default(),
+        };
+        let state = AppState {
+            config_store: Arc::new(store),
+            driver: driver_override(),
+            metadata_cache: Arc::default(),
+            schema_provider: Arc::new(InMemorySchemaProvider::new().with_server_info(info.clone())),
+        };
+
+        let response = server_info(State(state)).await.unwrap().0;
+        assert!(response.success);
+        assert_eq!(response.data.unwrap(), info);
+    }
+}
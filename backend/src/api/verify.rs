@@ -0,0 +1,42 @@
+use axum::{extract::Json, http::StatusCode};
+use std::path::Path;
+
+use crate::{
+    api::error::error_response,
+    db::connection::ConnectionPool,
+    export::manifest::{read_manifest, verify_manifest},
+    models::{ApiResponse, VerifyReport, VerifyRequest},
+};
+
+/// Compares a target database's row counts with a data export manifest.
+pub async fn verify_export(
+    Json(req): Json<VerifyRequest>,
+) -> Result<Json<ApiResponse<VerifyReport>>, StatusCode> {
+    let manifest = match read_manifest(Path::new(&req.manifest_path)) {
+        Ok(manifest) => manifest,
+        Err(e) => return Ok(Json(error_response("Failed to load manifest", &e))),
+    };
+    let schema = req
+        .schema
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(&manifest.target_schema)
+        .to_uppercase();
+
+    let pool = match ConnectionPool::new(req.config) {
+        Ok(pool) => pool,
+        Err(e) => return Ok(Json(error_response("Failed to create connection", &e))),
+    };
+
+    let connection = match pool.get_connection() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(Json(error_response("Failed to get connection", &e))),
+    };
+
+    Ok(Json(ApiResponse::success(verify_manifest(
+        &connection,
+        &manifest,
+        &schema,
+    ))))
+}
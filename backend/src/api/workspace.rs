@@ -0,0 +1,92 @@
+use axum::{
+    extract::{Json, Path as AxumPath, State},
+    http::StatusCode,
+};
+
+use crate::{
+    api::{error::error_response, AppState},
+    config_store::DEFAULT_WORKSPACE,
+    models::{ApiResponse, ErrorCode, Workspace, WorkspaceRequest},
+};
+
+pub async fn list_workspaces(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<Workspace>>>, StatusCode> {
+    match state.config_store.list_workspaces() {
+        Ok(workspaces) => Ok(Json(ApiResponse::success(workspaces))),
+        Err(e) => Ok(Json(error_response("Failed to read workspaces", &e))),
+    }
+}
+
+/// Creates a workspace, or updates the description of an existing one. The active workspace
+/// does not change.
+pub async fn save_workspace(
+    State(state): State<AppState>,
+    Json(req): Json<WorkspaceRequest>,
+) -> Result<Json<ApiResponse<Workspace>>, StatusCode> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Ok(Json(ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            "Workspace name must not be empty".to_string(),
+            None,
+        )));
+    }
+    let description = req
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|description| !description.is_empty());
+
+    if let Err(e) = state.config_store.upsert_workspace(name, description) {
+        return Ok(Json(error_response("Failed to save workspace", &e)));
+    }
+    match state.config_store.list_workspaces() {
+        Ok(workspaces) => match workspaces.into_iter().find(|w| w.name == name) {
+            Some(workspace) => Ok(Json(ApiResponse::success(workspace))),
+            None => Ok(Json(workspace_not_found(name))),
+        },
+        Err(e) => Ok(Json(error_response("Failed to read workspaces", &e))),
+    }
+}
+
+/// Makes a workspace active: the saved connection, export profiles, audit log and recent jobs
+/// read and written afterwards are that workspace's.
+pub async fn activate_workspace(
+    State(state): State<AppState>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<ApiResponse<bool>>, StatusCode> {
+    match state.config_store.set_active_workspace(&name) {
+        Ok(true) => {
+            tracing::info!("Switched to workspace {}", name);
+            Ok(Json(ApiResponse::success(true)))
+        }
+        Ok(false) => Ok(Json(workspace_not_found(&name))),
+        Err(e) => Ok(Json(error_response("Failed to switch workspace", &e))),
+    }
+}
+
+pub async fn delete_workspace(
+    State(state): State<AppState>,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<ApiResponse<bool>>, StatusCode> {
+    if name == DEFAULT_WORKSPACE {
+        return Ok(Json(ApiResponse::failure(
+            ErrorCode::InvalidRequest,
+            "The default workspace cannot be deleted".to_string(),
+            None,
+        )));
+    }
+    match state.config_store.delete_workspace(&name) {
+        Ok(deleted) => Ok(Json(ApiResponse::success(deleted))),
+        Err(e) => Ok(Json(error_response("Failed to delete workspace", &e))),
+    }
+}
+
+fn workspace_not_found<T>(name: &str) -> ApiResponse<T> {
+    ApiResponse::failure(
+        ErrorCode::InvalidRequest,
+        format!("Workspace '{}' not found", name),
+        None,
+    )
+}
@@ -0,0 +1,181 @@
+//! Portable copy of the saved settings for moving a setup to another machine or keeping a
+//! backup. The bundle holds credentials, so it is only ever written encrypted: AES-256-GCM
+//! under a key derived from the user's passphrase with PBKDF2-SHA256.
+
+use std::collections::BTreeMap;
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use anyhow::{anyhow, ensure, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use pbkdf2::pbkdf2_hmac;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::models::{ConnectionConfig, EncryptedConfigBundle, ExportProfile, ExportProject};
+
+pub const BUNDLE_FORMAT: &str = "amarone-config";
+const BUNDLE_VERSION: u32 = 1;
+const KDF: &str = "pbkdf2-sha256";
+const KDF_ITERATIONS: u32 = 600_000;
+/// Upper bound accepted from an imported bundle, so a crafted file cannot stall the server.
+const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+pub const MIN_PASSPHRASE_CHARS: usize = 8;
+
+/// Saved settings in a bundle.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub workspaces: Vec<BundleWorkspace>,
+    pub projects: Vec<ExportProject>,
+    /// Settings except the machine-specific ones (driver path, active workspace).
+    pub settings: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleWorkspace {
+    pub name: String,
+    pub description: Option<String>,
+    pub connection: Option<ConnectionConfig>,
+    pub profiles: Vec<ExportProfile>,
+}
+
+impl ConfigBundle {
+    /// Whether a connection in the bundle has a `${scheme:reference}` credential.
+    pub fn has_secret_references(&self) -> bool {
+        self.workspaces
+            .iter()
+            .filter_map(|workspace| workspace.connection.as_ref())
+            .chain(
+                self.projects
+                    .iter()
+                    .flat_map(|project| project.members.iter().map(|member| &member.config)),
+            )
+            .any(ConnectionConfig::has_secret_references)
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+fn decode(field: &str, value: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(value)
+        .with_context(|| format!("Invalid {} in config bundle", field))
+}
+
+pub fn encrypt_bundle(bundle: &ConfigBundle, passphrase: &str) -> Result<EncryptedConfigBundle> {
+    ensure!(
+        passphrase.chars().count() >= MIN_PASSPHRASE_CHARS,
+        "Passphrase of a config bundle must have at least {} characters",
+        MIN_PASSPHRASE_CHARS
+    );
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, KDF_ITERATIONS);
+    let cipher = Aes256Gcm::new(&key.into());
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let plaintext = serde_json::to_vec(bundle)?;
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| anyhow!("Failed to encrypt settings"))?;
+
+    Ok(EncryptedConfigBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        kdf: KDF.to_string(),
+        iterations: KDF_ITERATIONS,
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+pub fn decrypt_bundle(file: &EncryptedConfigBundle, passphrase: &str) -> Result<ConfigBundle> {
+    ensure!(
+        file.format == BUNDLE_FORMAT,
+        "Not a config bundle (format '{}')",
+        file.format
+    );
+    ensure!(
+        file.version == BUNDLE_VERSION,
+        "Unsupported config bundle version {}",
+        file.version
+    );
+    ensure!(
+        file.kdf == KDF && (1..=MAX_KDF_ITERATIONS).contains(&file.iterations),
+        "Unsupported key derivation in config bundle: {} with {} iterations",
+        file.kdf,
+        file.iterations
+    );
+    let salt = decode("salt", &file.salt)?;
+    let nonce = decode("nonce", &file.nonce)?;
+    ensure!(nonce.len() == NONCE_LEN, "Invalid nonce in config bundle");
+    let ciphertext = decode("ciphertext", &file.ciphertext)?;
+
+    let key = derive_key(passphrase, &salt, file.iterations);
+    let cipher = Aes256Gcm::new(&key.into());
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| anyhow!("Wrong passphrase or damaged config bundle"))?;
+    serde_json::from_slice(&plaintext).context("Unreadable settings in config bundle")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle() -> ConfigBundle {
+        let mut settings = BTreeMap::new();
+        settings.insert("log_level".to_string(), "debug".to_string());
+        ConfigBundle {
+            workspaces: vec![BundleWorkspace {
+                name: "acme".to_string(),
+                description: None,
+                connection: None,
+                profiles: Vec::new(),
+            }],
+            projects: Vec::new(),
+            settings,
+        }
+    }
+
+    #[test]
+    fn bundles_decrypt_only_with_their_passphrase() {
+        let file = encrypt_bundle(&bundle(), "correct horse").unwrap();
+        assert_eq!(file.format, BUNDLE_FORMAT);
+        assert!(!file.ciphertext.contains("acme"));
+
+        let restored = decrypt_bundle(&file, "correct horse").unwrap();
+        assert_eq!(restored.workspaces[0].name, "acme");
+        assert_eq!(restored.settings["log_level"], "debug");
+
+        let err = decrypt_bundle(&file, "wrong horse").unwrap_err();
+        assert!(err.to_string().contains("Wrong passphrase"));
+
+        let mut tampered = file.clone();
+        tampered.ciphertext = STANDARD.encode(b"not the settings at all");
+        assert!(decrypt_bundle(&tampered, "correct horse").is_err());
+    }
+
+    #[test]
+    fn short_passphrases_and_foreign_files_are_rejected() {
+        assert!(encrypt_bundle(&bundle(), "short").is_err());
+
+        let mut file = encrypt_bundle(&bundle(), "correct horse").unwrap();
+        file.iterations = u32::MAX;
+        assert!(decrypt_bundle(&file, "correct horse").is_err());
+        file.format = "something-else".to_string();
+        let err = decrypt_bundle(&file, "correct horse").unwrap_err();
+        assert!(err.to_string().contains("Not a config bundle"));
+    }
+}
@@ -1,10 +1,38 @@
-use std::{fs, path::PathBuf};
+pub mod bundle;
+pub mod provider;
 
-use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use anyhow::{anyhow, ensure, Context, Result};
 use chrono::Utc;
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, types::Type, Connection, OptionalExtension};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::db::{backend::DatabaseKind, driver::DRIVER_PATH_SETTING};
+use crate::models::{
+    AuditEntry, ConfigImportResponse, ConfigSource, ConnectionConfig, ExportJob, ExportProfile,
+    ExportProject, JobEvent, JobEventKind, JobStatus, JobTimeline, Workspace,
+};
+use bundle::{BundleWorkspace, ConfigBundle};
+
+/// Workspace holding the settings saved before workspaces existed; it cannot be deleted.
+pub const DEFAULT_WORKSPACE: &str = "default";
+/// `settings` key naming the workspace saved settings are read from and written to.
+pub const ACTIVE_WORKSPACE_SETTING: &str = "active_workspace";
 
-use crate::models::{ConfigSource, ConnectionConfig};
+const DEFAULT_CONNECTION: &str = "default-dm8";
+
+/// How long a write waits for another process holding the database lock before failing.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Settings that only make sense on the machine that saved them; config bundles leave them out.
+const LOCAL_SETTINGS: &[&str] = &[DRIVER_PATH_SETTING, ACTIVE_WORKSPACE_SETTING];
 
 #[derive(Debug, Clone)]
 pub struct StoredConnection {
@@ -13,9 +41,12 @@ pub struct StoredConnection {
     pub updated_at: Option<String>,
 }
 
+/// Saved settings in a SQLite database. Clones share one connection, so the API handlers
+/// and running exports take turns instead of failing with `database is locked`.
 #[derive(Debug, Clone)]
 pub struct ConfigStore {
     db_path: PathBuf,
+    conn: Arc<Mutex<Connection>>,
 }
 
 impl ConfigStore {
@@ -25,9 +56,14 @@ impl ConfigStore {
                 .with_context(|| format!("Failed to create config directory {:?}", parent))?;
         }
 
-        let store = Self { db_path };
-        store.init_db()?;
-        Ok(store)
+        let mut conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open SQLite at {:?}", db_path))?;
+        configure(&conn)?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            db_path,
+            conn: Arc::new(Mutex::new(conn)),
+        })
     }
 
     pub fn ensure_default_path() -> Result<Self> {
@@ -36,130 +72,999 @@ impl ConfigStore {
         Self::new_with_path(db_path)
     }
 
+    /// The shared connection; a panic in another caller does not make it unusable.
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     pub fn get_default(&self) -> Result<Option<StoredConnection>> {
-        let conn = Connection::open(&self.db_path)
-            .with_context(|| format!("Failed to open SQLite at {:?}", self.db_path))?;
+        let conn = self.connection();
+
+        let name = connection_name(&active_workspace_in(&conn)?);
+        read_connection(&conn, &name)
+    }
+
+    pub fn upsert_default(&self, config: &ConnectionConfig) -> Result<StoredConnection> {
+        let conn = self.connection();
+
+        let updated_at = Utc::now().to_rfc3339();
+        let name = connection_name(&active_workspace_in(&conn)?);
+
+        write_connection(&conn, &name, config, &updated_at)?;
+
+        Ok(StoredConnection {
+            config: config.clone(),
+            source: ConfigSource::Sqlite,
+            updated_at: Some(updated_at),
+        })
+    }
+
+    pub fn record_audit(&self, entry: &AuditEntry) -> Result<i64> {
+        let conn = self.connection();
+
+        let tables = serde_json::to_string(&entry.tables)?;
+        conn.execute(
+            "INSERT INTO audit_log (occurred_at, client_ip, username, host, port, kind, \
+             source_schema, target_schema, tables, success, file_path, message, workspace) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                &entry.occurred_at,
+                &entry.client_ip,
+                &entry.username,
+                &entry.host,
+                entry.port as i64,
+                &entry.kind,
+                &entry.source_schema,
+                &entry.target_schema,
+                &tables,
+                entry.success,
+                &entry.file_path,
+                &entry.message,
+                active_workspace_in(&conn)?
+            ],
+        )?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Returns the most recent audit entries of the active workspace, newest first.
+    pub fn list_audit(&self, limit: usize) -> Result<Vec<AuditEntry>> {
+        let conn = self.connection();
 
         let mut stmt = conn.prepare(
-            "SELECT db_type, host, port, username, password, schema, export_schema, updated_at \
-             FROM connections WHERE name = ?1 LIMIT 1",
+            "SELECT id, occurred_at, client_ip, username, host, port, kind, source_schema, \
+             target_schema, tables, success, file_path, message \
+             FROM audit_log WHERE workspace = ?2 ORDER BY id DESC LIMIT ?1",
         )?;
 
-        let row = stmt
-            .query_row(params!["default-dm8"], |row| {
-                let port: i64 = row.get(2)?;
-                let port = u16::try_from(port).unwrap_or_default();
-                Ok(StoredConnection {
-                    config: ConnectionConfig {
-                        host: row.get(1)?,
-                        port,
-                        username: row.get(3)?,
-                        password: row.get(4)?,
-                        schema: row.get(5)?,
-                        export_schema: row.get(6)?,
-                    },
-                    source: ConfigSource::Sqlite,
-                    updated_at: row.get(7)?,
-                })
+        let workspace = active_workspace_in(&conn)?;
+        let rows = stmt.query_map(params![limit as i64, &workspace], |row| {
+            let port: i64 = row.get(5)?;
+            let tables: String = row.get(9)?;
+            Ok(AuditEntry {
+                id: Some(row.get(0)?),
+                occurred_at: row.get(1)?,
+                client_ip: row.get(2)?,
+                username: row.get(3)?,
+                host: row.get(4)?,
+                port: u16::try_from(port).unwrap_or_default(),
+                kind: row.get(6)?,
+                source_schema: row.get(7)?,
+                target_schema: row.get(8)?,
+                tables: serde_json::from_str(&tables).unwrap_or_default(),
+                success: row.get(10)?,
+                file_path: row.get(11)?,
+                message: row.get(12)?,
             })
-            .optional()?;
+        })?;
 
-        Ok(row)
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+        Ok(entries)
     }
 
-    pub fn upsert_default(&self, config: &ConnectionConfig) -> Result<StoredConnection> {
-        let conn = Connection::open(&self.db_path)
-            .with_context(|| format!("Failed to open SQLite at {:?}", self.db_path))?;
+    pub fn create_job(&self, job: &ExportJob) -> Result<()> {
+        let conn = self.connection();
 
-        let updated_at = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO export_jobs (id, kind, status, started_at, finished_at, file_path, message, \
+             workspace) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                &job.id,
+                &job.kind,
+                enum_text(&job.status)?,
+                &job.started_at,
+                &job.finished_at,
+                &job.file_path,
+                &job.message,
+                active_workspace_in(&conn)?
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn finish_job(
+        &self,
+        job_id: &str,
+        status: JobStatus,
+        file_path: Option<&str>,
+        message: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.connection();
 
         conn.execute(
-            "INSERT INTO connections (name, db_type, host, port, username, password, schema, export_schema, updated_at) \
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9) \
-             ON CONFLICT(name) DO UPDATE SET \
-             db_type=excluded.db_type, host=excluded.host, port=excluded.port, \
-             username=excluded.username, password=excluded.password, schema=excluded.schema, \
-             export_schema=excluded.export_schema, updated_at=excluded.updated_at",
+            "UPDATE export_jobs SET status = ?2, finished_at = ?3, file_path = ?4, message = ?5 \
+             WHERE id = ?1",
             params![
-                "default-dm8",
-                "dm8",
-                &config.host,
-                config.port as i64,
-                &config.username,
-                &config.password,
-                &config.schema,
-                &config.export_schema,
-                &updated_at
+                job_id,
+                enum_text(&status)?,
+                Utc::now().to_rfc3339(),
+                file_path,
+                message
             ],
         )?;
 
-        Ok(StoredConnection {
-            config: config.clone(),
-            source: ConfigSource::Sqlite,
-            updated_at: Some(updated_at),
+        Ok(())
+    }
+
+    pub fn append_job_event(&self, job_id: &str, event: &JobEvent) -> Result<()> {
+        let conn = self.connection();
+
+        conn.execute(
+            "INSERT INTO export_job_events (job_id, occurred_at, kind, code, table_name, message) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                job_id,
+                &event.occurred_at,
+                enum_text(&event.kind)?,
+                event.code.as_ref().map(enum_text).transpose()?,
+                &event.table,
+                &event.message
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn get_job_timeline(&self, job_id: &str) -> Result<Option<JobTimeline>> {
+        let conn = self.connection();
+
+        let job = conn
+            .query_row(
+                "SELECT id, kind, status, started_at, finished_at, file_path, message \
+                 FROM export_jobs WHERE id = ?1",
+                params![job_id],
+                job_from_row,
+            )
+            .optional()?;
+
+        let Some(job) = job else {
+            return Ok(None);
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT occurred_at, kind, code, table_name, message FROM export_job_events \
+             WHERE job_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![job_id], |row| {
+            let kind: String = row.get(1)?;
+            let code: Option<String> = row.get(2)?;
+            Ok(JobEvent {
+                occurred_at: row.get(0)?,
+                kind: enum_from_text(kind).unwrap_or(JobEventKind::Warning),
+                code: code.and_then(enum_from_text),
+                table: row.get(3)?,
+                message: row.get(4)?,
+            })
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            events.push(row?);
+        }
+
+        Ok(Some(JobTimeline { job, events }))
+    }
+
+    /// The latest `limit` successful jobs of the active workspace that produced a file, newest
+    /// first.
+    pub fn recent_jobs(&self, limit: usize) -> Result<Vec<ExportJob>> {
+        let conn = self.connection();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, status, started_at, finished_at, file_path, message \
+             FROM export_jobs WHERE status = ?1 AND file_path IS NOT NULL AND workspace = ?3 \
+             ORDER BY finished_at DESC, started_at DESC LIMIT ?2",
+        )?;
+        let workspace = active_workspace_in(&conn)?;
+        let rows = stmt.query_map(
+            params![enum_text(&JobStatus::Succeeded)?, limit as i64, &workspace],
+            job_from_row,
+        )?;
+
+        let mut jobs = Vec::new();
+        for row in rows {
+            jobs.push(row?);
+        }
+        Ok(jobs)
+    }
+
+    /// Directories of the files export jobs produced, in every workspace.
+    pub fn job_output_dirs(&self) -> Result<Vec<PathBuf>> {
+        let conn = self.connection();
+
+        let mut stmt =
+            conn.prepare("SELECT DISTINCT file_path FROM export_jobs WHERE file_path IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut dirs = Vec::new();
+        for row in rows {
+            if let Some(dir) = Path::new(&row?).parent() {
+                dirs.push(dir.to_path_buf());
+            }
+        }
+        Ok(dirs)
+    }
+
+    /// Saves a project definition, replacing any project of the same name.
+    pub fn upsert_project(&self, project: &ExportProject) -> Result<()> {
+        let conn = self.connection();
+
+        write_project(&conn, project)
+    }
+
+    pub fn get_project(&self, name: &str) -> Result<Option<ExportProject>> {
+        let conn = self.connection();
+
+        let definition: Option<String> = conn
+            .query_row(
+                "SELECT definition FROM export_projects WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let project: Option<ExportProject> = definition
+            .map(|definition| {
+                serde_json::from_str(&definition)
+                    .with_context(|| format!("Stored project '{}' is corrupt", name))
+            })
+            .transpose()?;
+        if let Some(project) = &project {
+            trust_member_secrets(project);
+        }
+        Ok(project)
+    }
+
+    /// Every saved project, by name. Unreadable definitions are skipped.
+    pub fn list_projects(&self) -> Result<Vec<ExportProject>> {
+        let conn = self.connection();
+
+        read_projects(&conn)
+    }
+
+    /// Removes a project; returns whether it existed.
+    pub fn delete_project(&self, name: &str) -> Result<bool> {
+        let conn = self.connection();
+
+        let deleted = conn.execute("DELETE FROM export_projects WHERE name = ?1", params![name])?;
+        Ok(deleted > 0)
+    }
+
+    /// Saves an export profile in the active workspace, replacing any profile of the same name.
+    pub fn upsert_profile(&self, profile: &ExportProfile) -> Result<()> {
+        let conn = self.connection();
+
+        write_profile(&conn, &active_workspace_in(&conn)?, profile)
+    }
+
+    pub fn get_profile(&self, name: &str) -> Result<Option<ExportProfile>> {
+        let conn = self.connection();
+
+        let definition: Option<String> = conn
+            .query_row(
+                "SELECT definition FROM export_profiles WHERE workspace = ?1 AND name = ?2",
+                params![active_workspace_in(&conn)?, name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        definition
+            .map(|definition| {
+                serde_json::from_str(&definition)
+                    .with_context(|| format!("Stored profile '{}' is corrupt", name))
+            })
+            .transpose()
+    }
+
+    /// Every profile of the active workspace, by name. Unreadable definitions are skipped.
+    pub fn list_profiles(&self) -> Result<Vec<ExportProfile>> {
+        let conn = self.connection();
+
+        read_profiles(&conn, &active_workspace_in(&conn)?)
+    }
+
+    /// Removes a profile; returns whether it existed.
+    pub fn delete_profile(&self, name: &str) -> Result<bool> {
+        let conn = self.connection();
+
+        let deleted = conn.execute(
+            "DELETE FROM export_profiles WHERE workspace = ?1 AND name = ?2",
+            params![active_workspace_in(&conn)?, name],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Name of the workspace saved settings currently belong to.
+    pub fn active_workspace(&self) -> Result<String> {
+        let conn = self.connection();
+
+        active_workspace_in(&conn)
+    }
+
+    /// Every workspace, the default one first.
+    pub fn list_workspaces(&self) -> Result<Vec<Workspace>> {
+        let conn = self.connection();
+
+        read_workspaces(&conn)
+    }
+
+    /// Creates a workspace, or updates the description of an existing one.
+    pub fn upsert_workspace(&self, name: &str, description: Option<&str>) -> Result<()> {
+        let conn = self.connection();
+
+        write_workspace(&conn, name, description)
+    }
+
+    /// Makes `name` the active workspace; returns whether it exists.
+    pub fn set_active_workspace(&self, name: &str) -> Result<bool> {
+        let conn = self.connection();
+
+        let exists = conn
+            .query_row(
+                "SELECT 1 FROM workspaces WHERE name = ?1",
+                params![name],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if exists {
+            write_setting(&conn, ACTIVE_WORKSPACE_SETTING, name)?;
+        }
+        Ok(exists)
+    }
+
+    /// Removes a workspace with its connection and export profiles; its entries in the export
+    /// history are kept. Removing the active workspace activates the default one. Returns
+    /// whether the workspace existed.
+    pub fn delete_workspace(&self, name: &str) -> Result<bool> {
+        ensure!(
+            name != DEFAULT_WORKSPACE,
+            "The default workspace cannot be deleted"
+        );
+        let mut conn = self.connection();
+
+        let tx = conn.transaction()?;
+        let deleted = tx.execute("DELETE FROM workspaces WHERE name = ?1", params![name])?;
+        tx.execute(
+            "DELETE FROM connections WHERE name = ?1",
+            params![connection_name(name)],
+        )?;
+        tx.execute(
+            "DELETE FROM export_profiles WHERE workspace = ?1",
+            params![name],
+        )?;
+        tx.execute(
+            "DELETE FROM settings WHERE key = ?1 AND value = ?2",
+            params![ACTIVE_WORKSPACE_SETTING, name],
+        )?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    /// A value saved with [`ConfigStore::set_setting`].
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let conn = self.connection();
+
+        Ok(conn
+            .query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        let conn = self.connection();
+
+        write_setting(&conn, key, value)
+    }
+
+    /// Every workspace with its connection and export profiles, the export projects and the
+    /// portable settings, ready for [`bundle::encrypt_bundle`].
+    pub fn export_bundle(&self) -> Result<ConfigBundle> {
+        let conn = self.connection();
+
+        let mut workspaces = Vec::new();
+        for workspace in read_workspaces(&conn)? {
+            let connection = read_connection(&conn, &connection_name(&workspace.name))?;
+            workspaces.push(BundleWorkspace {
+                connection: connection.map(|stored| stored.config),
+                profiles: read_profiles(&conn, &workspace.name)?,
+                name: workspace.name,
+                description: workspace.description,
+            });
+        }
+
+        let mut stmt = conn.prepare("SELECT key, value FROM settings ORDER BY key")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut settings = BTreeMap::new();
+        for row in rows {
+            let (key, value) = row?;
+            if !LOCAL_SETTINGS.contains(&key.as_str()) {
+                settings.insert(key, value);
+            }
+        }
+
+        Ok(ConfigBundle {
+            workspaces,
+            projects: read_projects(&conn)?,
+            settings,
         })
     }
 
-    fn init_db(&self) -> Result<()> {
+    /// Restores a bundle in one transaction. Items replace those saved under the same name;
+    /// nothing else is removed and the active workspace does not change.
+    pub fn import_bundle(&self, bundle: &ConfigBundle) -> Result<ConfigImportResponse> {
+        let mut conn = self.connection();
+
+        let tx = conn.transaction()?;
+        let updated_at = Utc::now().to_rfc3339();
+        let mut summary = ConfigImportResponse::default();
+        for workspace in &bundle.workspaces {
+            ensure!(
+                !workspace.name.trim().is_empty(),
+                "Workspace name in config bundle must not be empty"
+            );
+            write_workspace(&tx, &workspace.name, workspace.description.as_deref())?;
+            summary.workspaces += 1;
+            if let Some(config) = &workspace.connection {
+                write_connection(&tx, &connection_name(&workspace.name), config, &updated_at)?;
+                summary.connections += 1;
+            }
+            for profile in &workspace.profiles {
+                write_profile(&tx, &workspace.name, profile)?;
+                summary.profiles += 1;
+            }
+        }
+        for project in &bundle.projects {
+            write_project(&tx, project)?;
+            summary.projects += 1;
+        }
+        for (key, value) in &bundle.settings {
+            if !LOCAL_SETTINGS.contains(&key.as_str()) {
+                write_setting(&tx, key, value)?;
+                summary.settings += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(summary)
+    }
+
+    /// Location of the SQLite database.
+    pub fn path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Takes the database's write lock and releases it again, failing when the file or its
+    /// directory cannot be written. Uses a connection of its own, since the shared one keeps
+    /// the access it had when it was opened.
+    pub fn check_writable(&self) -> Result<()> {
         let conn = Connection::open(&self.db_path)
             .with_context(|| format!("Failed to open SQLite at {:?}", self.db_path))?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.execute_batch("BEGIN IMMEDIATE; ROLLBACK;")
+            .with_context(|| format!("Config database {:?} is not writable", self.db_path))
+    }
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS connections (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL UNIQUE,
-                db_type TEXT NOT NULL,
-                host TEXT NOT NULL,
-                port INTEGER NOT NULL,
-                username TEXT NOT NULL,
-                password TEXT NOT NULL,
-                schema TEXT NOT NULL,
-                export_schema TEXT,
-                updated_at TEXT NOT NULL
-            )",
-            [],
-        )?;
+/// Switches the database to write-ahead logging, so readers never wait for the writer, and
+/// makes writers wait [`BUSY_TIMEOUT`] for another process (such as a second backend on the
+/// same home directory) instead of failing at once.
+fn configure(conn: &Connection) -> Result<()> {
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    let mode: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+    if !mode.eq_ignore_ascii_case("wal") {
+        // Some network file systems cannot share the WAL index.
+        tracing::warn!(
+            "Config database stays in {} journal mode; WAL is not available",
+            mode
+        );
+    }
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    Ok(())
+}
 
-        ensure_export_schema_column(&conn)?;
+type MigrationStep = fn(&Connection) -> Result<()>;
 
-        Ok(())
+/// Schema changes in the order they shipped. `PRAGMA user_version` counts the steps already
+/// applied to a database, so each step runs once. Append new steps at the end and never edit
+/// or reorder shipped ones. The first [`UNVERSIONED_STEPS`] predate the version counter and
+/// must tolerate databases that already have their changes.
+const MIGRATIONS: &[(&str, MigrationStep)] = &[
+    ("connections, audit log and settings", create_base_tables),
+    ("connection options", add_connection_columns),
+    ("export jobs and timeline events", create_job_tables),
+    ("export projects", create_project_table),
+    ("workspaces and export profiles", create_workspace_tables),
+];
+
+/// Steps that ran as `CREATE TABLE IF NOT EXISTS` on every start before versioning.
+const UNVERSIONED_STEPS: usize = 5;
+
+/// Applies the migrations `conn` has not seen yet, each in its own transaction. A database
+/// written by a newer build is left alone.
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let applied = usize::try_from(version).unwrap_or_default();
+    if applied > MIGRATIONS.len() {
+        tracing::warn!(
+            "Config database schema version {} is newer than this build ({}); skipping migrations",
+            applied,
+            MIGRATIONS.len()
+        );
+        return Ok(());
+    }
+
+    for (index, (description, step)) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let tx = conn.transaction()?;
+        step(&tx).with_context(|| {
+            format!(
+                "Config database migration {} ({}) failed",
+                index + 1,
+                description
+            )
+        })?;
+        tx.execute_batch(&format!("PRAGMA user_version = {}", index + 1))?;
+        tx.commit()?;
+        if index >= UNVERSIONED_STEPS {
+            tracing::info!(
+                "Config database migrated to version {}: {}",
+                index + 1,
+                description
+            );
+        }
+    }
+    Ok(())
+}
+
+fn create_base_tables(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS connections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            db_type TEXT NOT NULL,
+            host TEXT NOT NULL,
+            port INTEGER NOT NULL,
+            username TEXT NOT NULL,
+            password TEXT NOT NULL,
+            schema TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            occurred_at TEXT NOT NULL,
+            client_ip TEXT,
+            username TEXT NOT NULL,
+            host TEXT NOT NULL,
+            port INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            source_schema TEXT NOT NULL,
+            target_schema TEXT,
+            tables TEXT NOT NULL,
+            success INTEGER NOT NULL,
+            file_path TEXT,
+            message TEXT
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn add_connection_columns(conn: &Connection) -> Result<()> {
+    for (column, definition) in [
+        ("export_schema", "TEXT"),
+        ("dsn", "TEXT"),
+        ("ssl", "INTEGER NOT NULL DEFAULT 0"),
+        ("ssl_cert_path", "TEXT"),
+        ("ssl_cert_password", "TEXT"),
+        ("ssl_cipher", "TEXT"),
+        ("login_timeout_secs", "INTEGER"),
+        ("query_timeout_secs", "INTEGER"),
+    ] {
+        ensure_column(conn, "connections", column, definition)?;
+    }
+    Ok(())
+}
+
+fn create_job_tables(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS export_jobs (
+            id TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            status TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            finished_at TEXT,
+            file_path TEXT,
+            message TEXT
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS export_job_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id TEXT NOT NULL,
+            occurred_at TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            table_name TEXT,
+            message TEXT
+        )",
+        [],
+    )?;
+    ensure_column(conn, "export_job_events", "code", "TEXT")
+}
+
+fn create_project_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS export_projects (
+            name TEXT PRIMARY KEY,
+            definition TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Rows of the history tables written before workspaces existed belong to the default
+/// workspace.
+fn create_workspace_tables(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS workspaces (
+            name TEXT PRIMARY KEY,
+            description TEXT,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO workspaces (name, created_at) VALUES (?1, ?2)",
+        params![DEFAULT_WORKSPACE, Utc::now().to_rfc3339()],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS export_profiles (
+            workspace TEXT NOT NULL,
+            name TEXT NOT NULL,
+            definition TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (workspace, name)
+        )",
+        [],
+    )?;
+
+    let workspace_column = format!("TEXT NOT NULL DEFAULT '{}'", DEFAULT_WORKSPACE);
+    ensure_column(conn, "audit_log", "workspace", &workspace_column)?;
+    ensure_column(conn, "export_jobs", "workspace", &workspace_column)
+}
+
+/// Stores a unit-like serde enum by its snake_case name.
+fn enum_text<T: Serialize>(value: &T) -> Result<String> {
+    match serde_json::to_value(value)? {
+        serde_json::Value::String(text) => Ok(text),
+        other => Err(anyhow!("Expected string enum value, got {}", other)),
     }
 }
 
-fn ensure_export_schema_column(conn: &Connection) -> Result<()> {
-    let mut stmt = conn.prepare("PRAGMA table_info(connections)")?;
+fn enum_from_text<T: DeserializeOwned>(text: String) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(text)).ok()
+}
+
+/// Adds `column` to `table` unless a database created before it was introduced already has
+/// it.
+fn ensure_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
     let mut rows = stmt.query([])?;
     let mut has_column = false;
 
     while let Some(row) = rows.next()? {
         let name: String = row.get(1)?;
-        if name == "export_schema" {
+        if name == column {
             has_column = true;
             break;
         }
     }
 
     if !has_column {
-        conn.execute("ALTER TABLE connections ADD COLUMN export_schema TEXT", [])?;
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The active workspace, [`DEFAULT_WORKSPACE`] until another one is activated.
+fn active_workspace_in(conn: &Connection) -> Result<String> {
+    let active: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            params![ACTIVE_WORKSPACE_SETTING],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(active.unwrap_or_else(|| DEFAULT_WORKSPACE.to_string()))
+}
+
+/// Row name of a workspace's default connection; the default workspace keeps the name used
+/// before workspaces existed.
+fn connection_name(workspace: &str) -> String {
+    if workspace == DEFAULT_WORKSPACE {
+        DEFAULT_CONNECTION.to_string()
+    } else {
+        format!("{}:{}", workspace, DEFAULT_CONNECTION)
+    }
+}
+
+/// The connection saved under the row name `name`.
+fn read_connection(conn: &Connection, name: &str) -> Result<Option<StoredConnection>> {
+    let mut stmt = conn.prepare(
+        "SELECT db_type, host, port, username, password, schema, export_schema, updated_at, dsn, \
+         ssl, ssl_cert_path, ssl_cert_password, ssl_cipher, login_timeout_secs, query_timeout_secs \
+         FROM connections WHERE name = ?1 LIMIT 1",
+    )?;
+
+    let row = stmt
+        .query_row(params![name], |row| {
+            let db_type: String = row.get(0)?;
+            let db_type = DatabaseKind::parse(&db_type)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, e.into()))?;
+            let port: i64 = row.get(2)?;
+            let port = u16::try_from(port).unwrap_or_default();
+            Ok(StoredConnection {
+                config: ConnectionConfig {
+                    db_type,
+                    host: row.get(1)?,
+                    port,
+                    username: row.get(3)?,
+                    password: row.get(4)?,
+                    schema: row.get(5)?,
+                    export_schema: row.get(6)?,
+                    dsn: row.get(8)?,
+                    ssl: row.get(9)?,
+                    ssl_cert_path: row.get(10)?,
+                    ssl_cert_password: row.get(11)?,
+                    ssl_cipher: row.get(12)?,
+                    login_timeout_secs: row.get(13)?,
+                    query_timeout_secs: row.get(14)?,
+                },
+                source: ConfigSource::Sqlite,
+                updated_at: row.get(7)?,
+            })
+        })
+        .optional()?;
+
+    if let Some(stored) = &row {
+        stored.config.trust_secrets();
+    }
+    Ok(row)
+}
+
+/// Saves `config` under the row name `name`, replacing what was saved there.
+fn write_connection(
+    conn: &Connection,
+    name: &str,
+    config: &ConnectionConfig,
+    updated_at: &str,
+) -> Result<()> {
+    config.trust_secrets();
+    conn.execute(
+        "INSERT INTO connections (name, db_type, host, port, username, password, schema, export_schema, updated_at, dsn, \
+         ssl, ssl_cert_path, ssl_cert_password, ssl_cipher, login_timeout_secs, query_timeout_secs) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16) \
+         ON CONFLICT(name) DO UPDATE SET \
+         db_type=excluded.db_type, host=excluded.host, port=excluded.port, \
+         username=excluded.username, password=excluded.password, schema=excluded.schema, \
+         export_schema=excluded.export_schema, updated_at=excluded.updated_at, dsn=excluded.dsn, \
+         ssl=excluded.ssl, ssl_cert_path=excluded.ssl_cert_path, \
+         ssl_cert_password=excluded.ssl_cert_password, ssl_cipher=excluded.ssl_cipher, \
+         login_timeout_secs=excluded.login_timeout_secs, query_timeout_secs=excluded.query_timeout_secs",
+        params![
+            name,
+            config.db_type.as_str(),
+            &config.host,
+            config.port as i64,
+            &config.username,
+            &config.password,
+            &config.schema,
+            &config.export_schema,
+            updated_at,
+            &config.dsn,
+            config.ssl,
+            &config.ssl_cert_path,
+            &config.ssl_cert_password,
+            &config.ssl_cipher,
+            config.login_timeout_secs,
+            config.query_timeout_secs
+        ],
+    )?;
+    Ok(())
+}
+
+fn write_project(conn: &Connection, project: &ExportProject) -> Result<()> {
+    trust_member_secrets(project);
+    let definition = serde_json::to_string(project)?;
+    conn.execute(
+        "INSERT INTO export_projects (name, definition, updated_at) VALUES (?1, ?2, ?3) \
+         ON CONFLICT(name) DO UPDATE SET \
+         definition=excluded.definition, updated_at=excluded.updated_at",
+        params![&project.name, &definition, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+fn write_profile(conn: &Connection, workspace: &str, profile: &ExportProfile) -> Result<()> {
+    let definition = serde_json::to_string(profile)?;
+    conn.execute(
+        "INSERT INTO export_profiles (workspace, name, definition, updated_at) \
+         VALUES (?1, ?2, ?3, ?4) ON CONFLICT(workspace, name) DO UPDATE SET \
+         definition=excluded.definition, updated_at=excluded.updated_at",
+        params![
+            workspace,
+            &profile.name,
+            &definition,
+            Utc::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Profiles of `workspace`, by name. Unreadable definitions are skipped.
+fn read_profiles(conn: &Connection, workspace: &str) -> Result<Vec<ExportProfile>> {
+    let mut stmt =
+        conn.prepare("SELECT definition FROM export_profiles WHERE workspace = ?1 ORDER BY name")?;
+    let rows = stmt.query_map(params![workspace], |row| row.get::<_, String>(0))?;
+
+    let mut profiles = Vec::new();
+    for row in rows {
+        match serde_json::from_str(&row?) {
+            Ok(profile) => profiles.push(profile),
+            Err(e) => tracing::warn!("Skipping unreadable export profile: {}", e),
+        }
+    }
+    Ok(profiles)
+}
+
+/// Marks the `${scheme:reference}` credentials of a stored project's members as resolvable.
+fn trust_member_secrets(project: &ExportProject) {
+    for member in &project.members {
+        member.config.trust_secrets();
+    }
+}
+
+/// Every saved project, by name. Unreadable definitions are skipped.
+fn read_projects(conn: &Connection) -> Result<Vec<ExportProject>> {
+    let mut stmt = conn.prepare("SELECT definition FROM export_projects ORDER BY name")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut projects = Vec::new();
+    for row in rows {
+        match serde_json::from_str(&row?) {
+            Ok(project) => {
+                trust_member_secrets(&project);
+                projects.push(project)
+            }
+            Err(e) => tracing::warn!("Skipping unreadable export project: {}", e),
+        }
+    }
+    Ok(projects)
+}
+
+/// Every workspace, the default one first.
+fn read_workspaces(conn: &Connection) -> Result<Vec<Workspace>> {
+    let active = active_workspace_in(conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT name, description, created_at FROM workspaces \
+         ORDER BY name = ?1 DESC, name",
+    )?;
+    let rows = stmt.query_map(params![DEFAULT_WORKSPACE], |row| {
+        let name: String = row.get(0)?;
+        Ok(Workspace {
+            active: name == active,
+            name,
+            description: row.get(1)?,
+            created_at: row.get(2)?,
+        })
+    })?;
+
+    let mut workspaces = Vec::new();
+    for row in rows {
+        workspaces.push(row?);
     }
+    Ok(workspaces)
+}
+
+fn write_workspace(conn: &Connection, name: &str, description: Option<&str>) -> Result<()> {
+    conn.execute(
+        "INSERT INTO workspaces (name, description, created_at) VALUES (?1, ?2, ?3) \
+         ON CONFLICT(name) DO UPDATE SET description=excluded.description",
+        params![name, description, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
 
+fn write_setting(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3) \
+         ON CONFLICT(key) DO UPDATE SET value=excluded.value, updated_at=excluded.updated_at",
+        params![key, value, Utc::now().to_rfc3339()],
+    )?;
     Ok(())
 }
 
+fn job_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ExportJob> {
+    let status: String = row.get(2)?;
+    Ok(ExportJob {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        status: enum_from_text(status).unwrap_or(JobStatus::Failed),
+        started_at: row.get(3)?,
+        finished_at: row.get(4)?,
+        file_path: row.get(5)?,
+        message: row.get(6)?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::IssueCode;
     use std::{thread, time::Duration};
     use tempfile::TempDir;
 
     fn sample_config() -> ConnectionConfig {
         ConnectionConfig {
+            db_type: DatabaseKind::Dm8,
             host: "localhost".into(),
             port: 5236,
             username: "SYSDBA".into(),
             password: "SYSDBA".into(),
             schema: "SYSDBA".into(),
             export_schema: Some("APP".into()),
+            dsn: None,
+            ssl: false,
+            ssl_cert_path: None,
+            ssl_cert_password: None,
+            ssl_cipher: None,
+            login_timeout_secs: None,
+            query_timeout_secs: None,
         }
     }
 
@@ -191,7 +1096,43 @@ mod tests {
         assert_eq!(fetched.source, ConfigSource::Sqlite);
         assert_eq!(fetched.config.schema, "SYSDBA");
         assert_eq!(fetched.config.export_schema.as_deref(), Some("APP"));
+        assert_eq!(fetched.config.db_type, DatabaseKind::Dm8);
         assert!(fetched.updated_at.is_some());
+
+        let dsn_config = ConnectionConfig {
+            host: String::new(),
+            port: 0,
+            dsn: Some("DM8_PROD".into()),
+            ssl: true,
+            ssl_cert_path: Some("/etc/dm8/client_ssl".into()),
+            ssl_cert_password: Some("${env:DM8_SSL_PWD}".into()),
+            ssl_cipher: Some("AES256_CBC".into()),
+            login_timeout_secs: Some(5),
+            query_timeout_secs: Some(0),
+            ..sample_config()
+        };
+        store.upsert_default(&dsn_config).unwrap();
+        let fetched = store.get_default().unwrap().unwrap();
+        assert_eq!(fetched.config, dsn_config);
+    }
+
+    #[test]
+    fn unsupported_db_type_is_an_error_not_a_dm8_connection() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("config.db");
+        let store = ConfigStore::new_with_path(db_path.clone()).unwrap();
+        store.upsert_default(&sample_config()).unwrap();
+
+        let json = serde_json::json!({ "username": "u", "password": "p", "schema": "APP" });
+        let config: ConnectionConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.db_type, DatabaseKind::Dm8);
+
+        Connection::open(&db_path)
+            .unwrap()
+            .execute("UPDATE connections SET db_type = 'oracle'", [])
+            .unwrap();
+        let err = store.get_default().unwrap_err();
+        assert!(format!("{:#}", err).contains("oracle"));
     }
 
     #[test]
@@ -214,4 +1155,459 @@ mod tests {
         let fetched = store.get_default().unwrap().unwrap();
         assert_eq!(fetched.config.host, "127.0.0.1");
     }
+
+    #[test]
+    fn audit_entries_are_listed_newest_first() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("config.db");
+        let store = ConfigStore::new_with_path(db_path).unwrap();
+
+        let mut entry = AuditEntry {
+            id: None,
+            occurred_at: "2026-01-30T10:00:00Z".into(),
+            client_ip: Some("10.0.0.8".into()),
+            username: "SYSDBA".into(),
+            host: "localhost".into(),
+            port: 5236,
+            kind: "ddl".into(),
+            source_schema: "APP".into(),
+            target_schema: Some("APP_BAK".into()),
+            tables: vec!["USERS".into(), "ORDERS".into()],
+            success: true,
+            file_path: Some("exports/a.sql".into()),
+            message: None,
+        };
+        store.record_audit(&entry).unwrap();
+        entry.kind = "data".into();
+        entry.success = false;
+        entry.message = Some("boom".into());
+        store.record_audit(&entry).unwrap();
+
+        let entries = store.list_audit(10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, "data");
+        assert!(!entries[0].success);
+        assert_eq!(entries[1].tables, vec!["USERS".to_string(), "ORDERS".to_string()]);
+        assert_eq!(entries[1].client_ip.as_deref(), Some("10.0.0.8"));
+
+        assert_eq!(store.list_audit(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn job_timeline_round_trips_events_in_order() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("config.db");
+        let store = ConfigStore::new_with_path(db_path).unwrap();
+
+        store
+            .create_job(&ExportJob {
+                id: "job-1".into(),
+                kind: "data".into(),
+                status: JobStatus::Running,
+                started_at: "2026-01-30T01:00:00Z".into(),
+                finished_at: None,
+                file_path: None,
+                message: None,
+            })
+            .unwrap();
+        for (kind, code, table) in [
+            (JobEventKind::Started, None, None),
+            (JobEventKind::TableStarted, None, Some("USERS")),
+            (
+                JobEventKind::Warning,
+                Some(IssueCode::RowCountUnavailable),
+                Some("USERS"),
+            ),
+        ] {
+            let event = JobEvent {
+                occurred_at: Utc::now().to_rfc3339(),
+                kind,
+                code,
+                table: table.map(str::to_string),
+                message: None,
+            };
+            store.append_job_event("job-1", &event).unwrap();
+        }
+        store
+            .finish_job("job-1", JobStatus::Failed, None, Some("disk full"))
+            .unwrap();
+
+        let timeline = store.get_job_timeline("job-1").unwrap().unwrap();
+        assert_eq!(timeline.job.status, JobStatus::Failed);
+        assert_eq!(timeline.job.message.as_deref(), Some("disk full"));
+        assert!(timeline.job.finished_at.is_some());
+        let kinds: Vec<JobEventKind> = timeline.events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![JobEventKind::Started, JobEventKind::TableStarted, JobEventKind::Warning]
+        );
+        assert_eq!(timeline.events[1].table.as_deref(), Some("USERS"));
+        assert_eq!(timeline.events[2].code, Some(IssueCode::RowCountUnavailable));
+
+        assert!(store.get_job_timeline("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn recent_jobs_lists_successful_exports_newest_first() {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+
+        for (id, status, file_path) in [
+            ("job-old", JobStatus::Succeeded, Some("exports/old.sql")),
+            ("job-failed", JobStatus::Failed, None),
+            ("job-running", JobStatus::Running, None),
+            ("job-new", JobStatus::Succeeded, Some("exports/new.sql")),
+        ] {
+            store
+                .create_job(&ExportJob {
+                    id: id.into(),
+                    kind: "ddl".into(),
+                    status: JobStatus::Running,
+                    started_at: Utc::now().to_rfc3339(),
+                    finished_at: None,
+                    file_path: None,
+                    message: None,
+                })
+                .unwrap();
+            if status != JobStatus::Running {
+                store.finish_job(id, status, file_path, None).unwrap();
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        let recent = store.recent_jobs(5).unwrap();
+        let ids: Vec<&str> = recent.iter().map(|job| job.id.as_str()).collect();
+        assert_eq!(ids, vec!["job-new", "job-old"]);
+        assert_eq!(store.recent_jobs(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn workspaces_keep_connections_profiles_and_history_apart() {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+        let profile = |name: &str| ExportProfile {
+            name: name.to_string(),
+            schema: "APP".to_string(),
+            export_schema: None,
+            tables: vec!["ORDERS".to_string()],
+            include_data: false,
+            options: serde_json::Map::new(),
+        };
+
+        store.upsert_default(&sample_config()).unwrap();
+        store.upsert_profile(&profile("shared")).unwrap();
+        store
+            .create_job(&ExportJob {
+                id: "job-default".into(),
+                kind: "ddl".into(),
+                status: JobStatus::Running,
+                started_at: Utc::now().to_rfc3339(),
+                finished_at: None,
+                file_path: None,
+                message: None,
+            })
+            .unwrap();
+        store
+            .finish_job("job-default", JobStatus::Succeeded, Some("a.sql"), None)
+            .unwrap();
+
+        assert!(!store.set_active_workspace("acme").unwrap());
+        store.upsert_workspace("acme", Some("ACME Corp")).unwrap();
+        assert!(store.set_active_workspace("acme").unwrap());
+        assert_eq!(store.active_workspace().unwrap(), "acme");
+        assert!(store.get_default().unwrap().is_none());
+        assert!(store.list_profiles().unwrap().is_empty());
+        assert!(store.recent_jobs(5).unwrap().is_empty());
+
+        let mut acme = sample_config();
+        acme.host = "acme-db".into();
+        store.upsert_default(&acme).unwrap();
+        store.upsert_profile(&profile("shared")).unwrap();
+        store.upsert_profile(&profile("acme-only")).unwrap();
+        assert_eq!(store.list_profiles().unwrap().len(), 2);
+
+        let workspaces = store.list_workspaces().unwrap();
+        let names: Vec<&str> = workspaces.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec![DEFAULT_WORKSPACE, "acme"]);
+        assert!(workspaces[1].active);
+        assert_eq!(workspaces[1].description.as_deref(), Some("ACME Corp"));
+
+        assert!(store.delete_workspace(DEFAULT_WORKSPACE).is_err());
+        assert!(store.delete_workspace("acme").unwrap());
+        assert_eq!(store.active_workspace().unwrap(), DEFAULT_WORKSPACE);
+        let restored = store.get_default().unwrap().unwrap();
+        assert_eq!(restored.config.host, "localhost");
+        assert_eq!(store.list_profiles().unwrap().len(), 1);
+        assert_eq!(store.recent_jobs(5).unwrap().len(), 1);
+
+        store.upsert_workspace("acme", None).unwrap();
+        store.set_active_workspace("acme").unwrap();
+        assert!(store.get_default().unwrap().is_none());
+        assert!(store.list_profiles().unwrap().is_empty());
+    }
+
+    #[test]
+    fn project_round_trip_and_delete() {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+
+        let mut project = ExportProject {
+            name: "erp".to_string(),
+            members: vec![crate::models::ProjectMember {
+                config: sample_config(),
+                tables: vec!["ORDERS".to_string()],
+                export_schema: None,
+            }],
+            include_data: false,
+            options: serde_json::Map::new(),
+        };
+        store.upsert_project(&project).unwrap();
+        project.include_data = true;
+        store.upsert_project(&project).unwrap();
+
+        let projects = store.list_projects().unwrap();
+        assert_eq!(projects.len(), 1);
+        assert!(projects[0].include_data);
+        assert_eq!(
+            store.get_project("erp").unwrap().unwrap().members[0].tables,
+            vec!["ORDERS".to_string()]
+        );
+
+        assert!(store.delete_project("erp").unwrap());
+        assert!(!store.delete_project("erp").unwrap());
+        assert!(store.get_project("erp").unwrap().is_none());
+    }
+
+    #[test]
+    fn profile_round_trip_and_delete() {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+
+        let mut profile = ExportProfile {
+            name: "nightly".to_string(),
+            schema: "SALES".to_string(),
+            export_schema: Some("SALES_BAK".to_string()),
+            tables: vec!["ORDERS".to_string()],
+            include_data: false,
+            options: serde_json::Map::new(),
+        };
+        store.upsert_profile(&profile).unwrap();
+        profile.include_data = true;
+        profile
+            .options
+            .insert("data_format".to_string(), serde_json::json!("jsonl"));
+        store.upsert_profile(&profile).unwrap();
+
+        let profiles = store.list_profiles().unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert!(profiles[0].include_data);
+        let stored = store.get_profile("nightly").unwrap().unwrap();
+        assert_eq!(stored.export_schema.as_deref(), Some("SALES_BAK"));
+        assert_eq!(stored.options["data_format"], "jsonl");
+        assert!(store.list_projects().unwrap().is_empty());
+
+        assert!(store.delete_profile("nightly").unwrap());
+        assert!(!store.delete_profile("nightly").unwrap());
+        assert!(store.get_profile("nightly").unwrap().is_none());
+    }
+
+    fn user_version(path: &Path) -> i64 {
+        Connection::open(path)
+            .unwrap()
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn migrations_run_once_and_record_the_schema_version() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("config.db");
+        let store = ConfigStore::new_with_path(db_path.clone()).unwrap();
+        assert_eq!(user_version(&db_path), MIGRATIONS.len() as i64);
+
+        store.upsert_default(&sample_config()).unwrap();
+        let reopened = ConfigStore::new_with_path(db_path.clone()).unwrap();
+        assert!(reopened.get_default().unwrap().is_some());
+        assert_eq!(user_version(&db_path), MIGRATIONS.len() as i64);
+
+        let newer = MIGRATIONS.len() as i64 + 1;
+        Connection::open(&db_path)
+            .unwrap()
+            .execute_batch(&format!("PRAGMA user_version = {}", newer))
+            .unwrap();
+        assert!(ConfigStore::new_with_path(db_path.clone()).is_ok());
+        assert_eq!(user_version(&db_path), newer);
+    }
+
+    #[test]
+    fn unversioned_database_is_upgraded_in_place() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("config.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE connections (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                db_type TEXT NOT NULL,
+                host TEXT NOT NULL,
+                port INTEGER NOT NULL,
+                username TEXT NOT NULL,
+                password TEXT NOT NULL,
+                schema TEXT NOT NULL,
+                export_schema TEXT,
+                updated_at TEXT NOT NULL
+            );
+            INSERT INTO connections (name, db_type, host, port, username, password, schema, \
+                export_schema, updated_at)
+            VALUES ('default-dm8', 'dm8', 'legacy-host', 5236, 'SYSDBA', 'pw', 'APP', 'APP_BAK', \
+                '2025-01-01T00:00:00Z');
+            CREATE TABLE export_jobs (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                status TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                finished_at TEXT,
+                file_path TEXT,
+                message TEXT
+            );
+            INSERT INTO export_jobs VALUES ('job-legacy', 'ddl', 'succeeded', \
+                '2025-01-01T00:00:00Z', '2025-01-01T00:01:00Z', 'a.sql', NULL);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let store = ConfigStore::new_with_path(db_path.clone()).unwrap();
+        assert_eq!(user_version(&db_path), MIGRATIONS.len() as i64);
+
+        let stored = store.get_default().unwrap().unwrap();
+        assert_eq!(stored.config.host, "legacy-host");
+        assert_eq!(stored.config.export_schema.as_deref(), Some("APP_BAK"));
+        assert!(!stored.config.ssl);
+        assert_eq!(store.recent_jobs(5).unwrap()[0].id, "job-legacy");
+    }
+
+    #[test]
+    fn bundles_move_workspaces_projects_and_portable_settings() {
+        let dir = TempDir::new().unwrap();
+        let source = ConfigStore::new_with_path(dir.path().join("source.db")).unwrap();
+        source.upsert_default(&sample_config()).unwrap();
+        source.upsert_workspace("acme", Some("ACME Corp")).unwrap();
+        source.set_active_workspace("acme").unwrap();
+        let mut acme = sample_config();
+        acme.host = "acme-db".into();
+        source.upsert_default(&acme).unwrap();
+        source
+            .upsert_profile(&ExportProfile {
+                name: "nightly".to_string(),
+                schema: "SALES".to_string(),
+                export_schema: None,
+                tables: vec!["ORDERS".to_string()],
+                include_data: true,
+                options: serde_json::Map::new(),
+            })
+            .unwrap();
+        source.set_setting("log_level", "debug").unwrap();
+        source
+            .set_setting(DRIVER_PATH_SETTING, "/opt/dm8/libdodbc.so")
+            .unwrap();
+
+        let bundle = source.export_bundle().unwrap();
+        assert_eq!(bundle.workspaces.len(), 2);
+        assert!(!bundle.settings.contains_key(DRIVER_PATH_SETTING));
+        assert!(!bundle.settings.contains_key(ACTIVE_WORKSPACE_SETTING));
+
+        let target = ConfigStore::new_with_path(dir.path().join("target.db")).unwrap();
+        let summary = target.import_bundle(&bundle).unwrap();
+        assert_eq!(summary.workspaces, 2);
+        assert_eq!(summary.connections, 2);
+        assert_eq!(summary.profiles, 1);
+        assert_eq!(summary.settings, 1);
+
+        assert_eq!(target.active_workspace().unwrap(), DEFAULT_WORKSPACE);
+        assert_eq!(
+            target.get_default().unwrap().unwrap().config,
+            sample_config()
+        );
+        assert_eq!(
+            target.get_setting("log_level").unwrap().as_deref(),
+            Some("debug")
+        );
+        assert!(target.get_setting(DRIVER_PATH_SETTING).unwrap().is_none());
+        target.set_active_workspace("acme").unwrap();
+        assert_eq!(target.get_default().unwrap().unwrap().config, acme);
+        assert_eq!(target.list_profiles().unwrap()[0].name, "nightly");
+    }
+
+    #[test]
+    fn concurrent_writers_do_not_hit_locked_database() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.db");
+        let store = ConfigStore::new_with_path(path.clone()).unwrap();
+        // A second store stands in for another process with its own connection.
+        let other = ConfigStore::new_with_path(path.clone()).unwrap();
+
+        let entry = AuditEntry {
+            id: None,
+            occurred_at: "2026-01-30T10:00:00Z".into(),
+            client_ip: None,
+            username: "SYSDBA".into(),
+            host: "localhost".into(),
+            port: 5236,
+            kind: "ddl".into(),
+            source_schema: "APP".into(),
+            target_schema: None,
+            tables: vec!["USERS".into()],
+            success: true,
+            file_path: None,
+            message: None,
+        };
+        let handles: Vec<_> = (0..8)
+            .map(|worker| {
+                let store = if worker % 2 == 0 {
+                    store.clone()
+                } else {
+                    other.clone()
+                };
+                let entry = entry.clone();
+                thread::spawn(move || {
+                    for i in 0..25 {
+                        store.record_audit(&entry).unwrap();
+                        store
+                            .set_setting(&format!("worker_{}", worker), &i.to_string())
+                            .unwrap();
+                        if i % 5 == 0 {
+                            store.upsert_default(&sample_config()).unwrap();
+                            store.list_audit(10).unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(store.list_audit(500).unwrap().len(), 200);
+        let last = other.get_setting("worker_7").unwrap();
+        assert_eq!(last.as_deref(), Some("24"));
+        let mode: String = Connection::open(&path)
+            .unwrap()
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+    }
+
+    #[test]
+    fn settings_round_trip_and_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+
+        assert!(store.get_setting("driver_path").unwrap().is_none());
+        store.set_setting("driver_path", "/opt/dm/a.so").unwrap();
+        store.set_setting("driver_path", "/opt/dm/b.so").unwrap();
+        assert_eq!(
+            store.get_setting("driver_path").unwrap().as_deref(),
+            Some("/opt/dm/b.so")
+        );
+    }
 }
@@ -0,0 +1,435 @@
+//! Sources of the connection settings. Each [`ConfigProvider`] supplies a whole connection or
+//! none, and [`resolve_connection`] takes the first one in precedence order: a connection in
+//! the request body, then the saved connection of the active workspace, then the `DATABASE_*`
+//! environment variables. Fields the chosen source leaves unset keep their built-in defaults;
+//! sources are never mixed field by field, so a stray `DATABASE_DSN` cannot redirect a saved
+//! host/port connection.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::config_store::ConfigStore;
+use crate::db::backend::DatabaseKind;
+use crate::models::{ConfigSource, ConnectionConfig, EffectiveConfigResponse, EffectiveField};
+use crate::secrets::is_secret_reference;
+
+/// `ConnectionConfig` fields with the environment variable setting each of them.
+pub const ENV_VARS: &[(&str, &str)] = &[
+    ("db_type", "DATABASE_TYPE"),
+    ("host", "DATABASE_HOST"),
+    ("port", "DATABASE_PORT"),
+    ("username", "DATABASE_USERNAME"),
+    ("password", "DATABASE_PASSWORD"),
+    ("schema", "DATABASE_SCHEMA"),
+    ("export_schema", "DATABASE_EXPORT_SCHEMA"),
+    ("dsn", "DATABASE_DSN"),
+    ("ssl", "DATABASE_SSL"),
+    ("ssl_cert_path", "DATABASE_SSL_CERT_PATH"),
+    ("ssl_cert_password", "DATABASE_SSL_CERT_PASSWORD"),
+    ("ssl_cipher", "DATABASE_SSL_CIPHER"),
+    ("login_timeout_secs", "DATABASE_LOGIN_TIMEOUT_SECS"),
+    ("query_timeout_secs", "DATABASE_QUERY_TIMEOUT_SECS"),
+];
+
+/// A source of the connection settings.
+pub trait ConfigProvider {
+    fn source(&self) -> ConfigSource;
+
+    /// The connection this source supplies, `None` when it has none.
+    fn connection(&self) -> Result<Option<ProvidedConnection>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct ProvidedConnection {
+    pub config: ConnectionConfig,
+    /// Fields the source set itself; the others keep their defaults.
+    pub fields: Vec<&'static str>,
+    pub updated_at: Option<String>,
+}
+
+impl ProvidedConnection {
+    /// A complete config, setting every field that has a value.
+    fn complete(config: ConnectionConfig, updated_at: Option<String>) -> Self {
+        let value = serde_json::to_value(&config).unwrap_or_default();
+        let fields = ENV_VARS
+            .iter()
+            .map(|(field, _)| *field)
+            .filter(|field| value.get(field).is_some_and(|v| !v.is_null()))
+            .collect();
+        Self {
+            config,
+            fields,
+            updated_at,
+        }
+    }
+}
+
+/// A connection sent along with a request.
+pub struct RequestConfig(pub ConnectionConfig);
+
+impl ConfigProvider for RequestConfig {
+    fn source(&self) -> ConfigSource {
+        ConfigSource::Request
+    }
+
+    fn connection(&self) -> Result<Option<ProvidedConnection>> {
+        Ok(Some(ProvidedConnection::complete(self.0.clone(), None)))
+    }
+}
+
+/// The connection saved in the active workspace.
+pub struct SavedConfig<'a>(pub &'a ConfigStore);
+
+impl ConfigProvider for SavedConfig<'_> {
+    fn source(&self) -> ConfigSource {
+        ConfigSource::Sqlite
+    }
+
+    fn connection(&self) -> Result<Option<ProvidedConnection>> {
+        let stored = self
+            .0
+            .get_default()
+            .context("Failed to read saved connection")?;
+        Ok(stored.map(|stored| ProvidedConnection::complete(stored.config, stored.updated_at)))
+    }
+}
+
+/// The `DATABASE_*` environment variables of [`ENV_VARS`]; blank values count as unset.
+pub struct EnvConfig {
+    vars: HashMap<String, String>,
+}
+
+impl EnvConfig {
+    pub fn from_env() -> Self {
+        Self::from_vars(
+            ENV_VARS
+                .iter()
+                .filter_map(|(_, var)| std::env::var(var).ok().map(|value| (*var, value))),
+        )
+    }
+
+    pub fn from_vars<K: Into<String>, V: Into<String>>(
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        Self {
+            vars: vars
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .filter(|(_, value)| !value.trim().is_empty())
+                .collect(),
+        }
+    }
+
+    fn get(&self, var: &str) -> Option<String> {
+        self.vars.get(var).cloned()
+    }
+
+    fn require(&self, var: &str) -> Result<String> {
+        self.get(var).ok_or_else(|| anyhow!("{} not set", var))
+    }
+
+    fn parse<T: std::str::FromStr>(&self, var: &str, kind: &str) -> Result<Option<T>> {
+        self.get(var)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|_| anyhow!("{} is not a valid {}", var, kind))
+            })
+            .transpose()
+    }
+
+    fn read(&self) -> Result<ProvidedConnection> {
+        // With a DSN the data source definition supplies the server, so host and port are
+        // optional.
+        let dsn = self.get("DATABASE_DSN");
+        let (host, port) = if dsn.is_some() {
+            (
+                self.get("DATABASE_HOST").unwrap_or_default(),
+                self.parse("DATABASE_PORT", "u16")?.unwrap_or_default(),
+            )
+        } else {
+            (
+                self.require("DATABASE_HOST")?,
+                self.parse("DATABASE_PORT", "u16")?
+                    .ok_or_else(|| anyhow!("DATABASE_PORT not set"))?,
+            )
+        };
+        let db_type = match self.get("DATABASE_TYPE") {
+            Some(kind) => DatabaseKind::parse(&kind).context("Invalid DATABASE_TYPE")?,
+            None => DatabaseKind::default(),
+        };
+        let ssl = match self.get("DATABASE_SSL") {
+            Some(value) => {
+                parse_flag(&value).ok_or_else(|| anyhow!("DATABASE_SSL is not a valid boolean"))?
+            }
+            None => false,
+        };
+
+        let config = ConnectionConfig {
+            db_type,
+            host,
+            port,
+            username: self.require("DATABASE_USERNAME")?,
+            password: self.require("DATABASE_PASSWORD")?,
+            schema: self.require("DATABASE_SCHEMA")?,
+            export_schema: self.get("DATABASE_EXPORT_SCHEMA"),
+            dsn,
+            ssl,
+            ssl_cert_path: self.get("DATABASE_SSL_CERT_PATH"),
+            ssl_cert_password: self.get("DATABASE_SSL_CERT_PASSWORD"),
+            ssl_cipher: self.get("DATABASE_SSL_CIPHER"),
+            login_timeout_secs: self.parse("DATABASE_LOGIN_TIMEOUT_SECS", "u32")?,
+            query_timeout_secs: self.parse("DATABASE_QUERY_TIMEOUT_SECS", "u32")?,
+        };
+        config.trust_secrets();
+        let fields = ENV_VARS
+            .iter()
+            .filter(|(_, var)| self.vars.contains_key(*var))
+            .map(|(field, _)| *field)
+            .collect();
+        Ok(ProvidedConnection {
+            config,
+            fields,
+            updated_at: None,
+        })
+    }
+}
+
+impl ConfigProvider for EnvConfig {
+    fn source(&self) -> ConfigSource {
+        ConfigSource::Env
+    }
+
+    fn connection(&self) -> Result<Option<ProvidedConnection>> {
+        if self.vars.is_empty() {
+            return Ok(None);
+        }
+        self.read()
+            .map(Some)
+            .context("Invalid DATABASE_* environment variables")
+    }
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Connection chosen from `providers`, highest precedence first, with the source it came from.
+/// An unreadable source is an error rather than a reason to fall back to the next one.
+pub fn resolve_connection(
+    providers: &[&dyn ConfigProvider],
+) -> Result<(ConfigSource, ProvidedConnection)> {
+    for provider in providers {
+        if let Some(provided) = provider.connection()? {
+            return Ok((provider.source(), provided));
+        }
+    }
+    bail!(
+        "No connection configured: save one or set DATABASE_HOST, DATABASE_PORT, \
+         DATABASE_USERNAME, DATABASE_PASSWORD and DATABASE_SCHEMA"
+    )
+}
+
+/// Which source each field of the resolved connection comes from, and which lower sources set
+/// it too but are ignored. Secrets are masked unless they are `${scheme:reference}`s.
+pub fn effective_config(providers: &[&dyn ConfigProvider]) -> Result<EffectiveConfigResponse> {
+    let (source, resolved) = resolve_connection(providers)?;
+    let mut warnings = Vec::new();
+    let mut lower = Vec::new();
+    for provider in providers
+        .iter()
+        .skip_while(|provider| provider.source() != source)
+        .skip(1)
+    {
+        match provider.connection() {
+            Ok(Some(provided)) => lower.push((provider.source(), provided.fields)),
+            Ok(None) => {}
+            Err(e) => warnings.push(format!("{}: {:#}", provider.source().as_str(), e)),
+        }
+    }
+
+    let fields = ENV_VARS
+        .iter()
+        .map(|(field, _)| EffectiveField {
+            name: field.to_string(),
+            source: if resolved.fields.contains(field) {
+                source.clone()
+            } else {
+                ConfigSource::Default
+            },
+            shadowed: lower
+                .iter()
+                .filter(|(_, fields)| fields.contains(field))
+                .map(|(source, _)| source.clone())
+                .collect(),
+        })
+        .collect();
+
+    Ok(EffectiveConfigResponse {
+        source,
+        config: masked(resolved.config),
+        updated_at: resolved.updated_at,
+        precedence: providers.iter().map(|provider| provider.source()).collect(),
+        fields,
+        warnings,
+    })
+}
+
+/// `config` with its passwords replaced by `******`, except secret references.
+fn masked(mut config: ConnectionConfig) -> ConnectionConfig {
+    let mask = |value: &mut String| {
+        if !value.is_empty() && !is_secret_reference(value) {
+            *value = "******".to_string();
+        }
+    };
+    mask(&mut config.password);
+    if let Some(password) = config.ssl_cert_password.as_mut() {
+        mask(password);
+    }
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn env(vars: &[(&str, &str)]) -> EnvConfig {
+        EnvConfig::from_vars(vars.iter().copied())
+    }
+
+    fn saved_config() -> ConnectionConfig {
+        ConnectionConfig {
+            db_type: DatabaseKind::Dm8,
+            host: "saved-host".into(),
+            port: 5236,
+            username: "SAVED".into(),
+            password: "saved-secret".into(),
+            schema: "APP".into(),
+            export_schema: None,
+            dsn: None,
+            ssl: false,
+            ssl_cert_path: None,
+            ssl_cert_password: None,
+            ssl_cipher: None,
+            login_timeout_secs: None,
+            query_timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn env_supplies_every_field() {
+        let provided = env(&[
+            ("DATABASE_HOST", "db"),
+            ("DATABASE_PORT", "5237"),
+            ("DATABASE_USERNAME", "SYSDBA"),
+            ("DATABASE_PASSWORD", "${file:/run/secrets/dm8}"),
+            ("DATABASE_SCHEMA", "APP"),
+            ("DATABASE_EXPORT_SCHEMA", "APP_BAK"),
+            ("DATABASE_SSL", "yes"),
+            ("DATABASE_SSL_CERT_PATH", "/certs"),
+            ("DATABASE_SSL_CIPHER", "AES256_CBC"),
+            ("DATABASE_LOGIN_TIMEOUT_SECS", "5"),
+            ("DATABASE_QUERY_TIMEOUT_SECS", "0"),
+            ("DATABASE_SSL_CERT_PASSWORD", " "),
+        ])
+        .connection()
+        .unwrap()
+        .unwrap();
+        let config = provided.config;
+        assert_eq!(config.port, 5237);
+        assert_eq!(config.export_schema.as_deref(), Some("APP_BAK"));
+        assert!(config.ssl);
+        assert_eq!(config.login_timeout_secs, Some(5));
+        assert_eq!(config.query_timeout_secs, Some(0));
+        assert_eq!(config.ssl_cert_password, None);
+        assert!(!provided.fields.contains(&"db_type"));
+        assert!(!provided.fields.contains(&"ssl_cert_password"));
+
+        assert!(env(&[]).connection().unwrap().is_none());
+        let err = env(&[("DATABASE_HOST", "db")]).connection().unwrap_err();
+        assert_eq!(err.root_cause().to_string(), "DATABASE_PORT not set");
+        let err = env(&[("DATABASE_DSN", "DM8_PROD"), ("DATABASE_SSL", "maybe")])
+            .connection()
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("DATABASE_SSL"));
+    }
+
+    #[test]
+    fn first_source_with_a_connection_wins_as_a_whole() {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+        let env = env(&[
+            ("DATABASE_DSN", "DM8_PROD"),
+            ("DATABASE_USERNAME", "ENV"),
+            ("DATABASE_PASSWORD", "env-secret"),
+            ("DATABASE_SCHEMA", "ENV_APP"),
+        ]);
+
+        let (source, provided) = resolve_connection(&[&SavedConfig(&store), &env]).unwrap();
+        assert_eq!(source, ConfigSource::Env);
+        assert_eq!(provided.config.dsn.as_deref(), Some("DM8_PROD"));
+
+        store.upsert_default(&saved_config()).unwrap();
+        let (source, provided) = resolve_connection(&[&SavedConfig(&store), &env]).unwrap();
+        assert_eq!(source, ConfigSource::Sqlite);
+        assert_eq!(provided.config, saved_config());
+
+        let mut sent = saved_config();
+        sent.username = "SENT".into();
+        let request = RequestConfig(sent);
+        let (source, provided) =
+            resolve_connection(&[&request, &SavedConfig(&store), &env]).unwrap();
+        assert_eq!(source, ConfigSource::Request);
+        assert_eq!(provided.config.username, "SENT");
+
+        assert!(resolve_connection(&[&EnvConfig::from_vars::<String, String>([])]).is_err());
+    }
+
+    #[test]
+    fn effective_config_names_sources_and_masks_secrets() {
+        let dir = TempDir::new().unwrap();
+        let store = ConfigStore::new_with_path(dir.path().join("config.db")).unwrap();
+        store.upsert_default(&saved_config()).unwrap();
+        let env = env(&[
+            ("DATABASE_HOST", "env-host"),
+            ("DATABASE_PORT", "not-a-port"),
+        ]);
+
+        let effective = effective_config(&[&SavedConfig(&store), &env]).unwrap();
+        assert_eq!(effective.source, ConfigSource::Sqlite);
+        assert_eq!(effective.config.password, "******");
+        assert!(effective.updated_at.is_some());
+        let field = |name: &str| effective.fields.iter().find(|f| f.name == name).unwrap();
+        assert_eq!(field("password").source, ConfigSource::Sqlite);
+        assert_eq!(field("login_timeout_secs").source, ConfigSource::Default);
+        assert_eq!(effective.warnings.len(), 1);
+        assert!(effective.warnings[0].contains("DATABASE_PORT"));
+
+        let env = EnvConfig::from_vars([
+            ("DATABASE_HOST", "env-host"),
+            ("DATABASE_PORT", "5236"),
+            ("DATABASE_USERNAME", "ENV"),
+            ("DATABASE_PASSWORD", "${env:DM8_PASSWORD}"),
+            ("DATABASE_SCHEMA", "ENV_APP"),
+        ]);
+        let effective = effective_config(&[&SavedConfig(&store), &env]).unwrap();
+        assert_eq!(field_shadowed(&effective, "host"), vec![ConfigSource::Env]);
+        assert!(field_shadowed(&effective, "dsn").is_empty());
+
+        let empty = ConfigStore::new_with_path(dir.path().join("empty.db")).unwrap();
+        let effective = effective_config(&[&SavedConfig(&empty), &env]).unwrap();
+        assert_eq!(effective.config.password, "${env:DM8_PASSWORD}");
+    }
+
+    fn field_shadowed(effective: &EffectiveConfigResponse, name: &str) -> Vec<ConfigSource> {
+        let field = effective.fields.iter().find(|f| f.name == name).unwrap();
+        field.shadowed.clone()
+    }
+}
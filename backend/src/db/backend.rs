@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use odbc_api::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{
+    connection::ConnectionPool,
+    metadata_cache::MetadataCache,
+    provider::{OdbcSchemaProvider, SchemaProvider},
+    read_only::{execute_read, SESSION_READ_ONLY_SQL},
+};
+use crate::export::data::{fetch_rows, fetch_wide_rows, FetchProgress};
+use crate::models::{ConnectionConfig, TableMemoryStats};
+
+/// Kind of source database. Only DM8 is implemented so far; the kind is what the config store
+/// keeps in its `db_type` column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseKind {
+    #[default]
+    Dm8,
+}
+
+impl DatabaseKind {
+    /// Value of the config store's `db_type` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DatabaseKind::Dm8 => "dm8",
+        }
+    }
+
+    /// Name used in log and error messages.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            DatabaseKind::Dm8 => "DM8",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "dm8" | "dm" | "dameng" => Ok(DatabaseKind::Dm8),
+            other => bail!("Unsupported source database '{}'", other),
+        }
+    }
+
+    /// The implementation for this kind of database.
+    pub fn backend(self) -> &'static dyn DatabaseBackend {
+        match self {
+            DatabaseKind::Dm8 => &Dm8Backend,
+        }
+    }
+}
+
+/// Row set size and bookkeeping of one [`DatabaseBackend::stream_rows`] call.
+pub struct RowFetch<'a> {
+    pub batch_size: usize,
+    pub column_count: usize,
+    /// Column whose last value is tracked in `progress`, in keyset mode.
+    pub key_index: Option<usize>,
+    /// UTF-16 code units per column when the rows are fetched as wide text; `None` for a
+    /// column the driver's display size sizes.
+    pub wide_lengths: Option<&'a [Option<usize>]>,
+    pub stats: &'a mut TableMemoryStats,
+    pub progress: &'a mut FetchProgress,
+}
+
+/// What the tool needs from a source database: how to connect to it, how to read its catalog
+/// and how to stream rows out of it. Every source is reached over ODBC, so a backend supplies
+/// the connection string and session setup while [`ConnectionPool`] does the connecting.
+pub trait DatabaseBackend: Send + Sync {
+    fn kind(&self) -> DatabaseKind;
+
+    /// ODBC connection string for `config`.
+    fn connection_string(&self, config: &ConnectionConfig) -> String;
+
+    /// Statement switching the session's default schema.
+    fn set_schema_sql(&self, schema: &str) -> String;
+
+    /// Statement asking the server to refuse writes in this session, when it has one.
+    fn read_only_session_sql(&self) -> Option<&'static str>;
+
+    /// A read-only pool for `config`.
+    fn connect(&self, config: ConnectionConfig) -> Result<ConnectionPool>;
+
+    /// Catalog reader of this database, with table details going through `metadata_cache`.
+    fn schema_provider(&self, metadata_cache: Arc<MetadataCache>) -> Arc<dyn SchemaProvider>;
+
+    /// Runs the read-only `query` and hands its rows to `emit` as text, one row set at a time.
+    fn stream_rows(
+        &self,
+        connection: &Connection<'_>,
+        query: &str,
+        fetch: RowFetch<'_>,
+        emit: &mut dyn FnMut(Vec<Vec<Option<String>>>) -> Result<()>,
+    ) -> Result<()>;
+}
+
+/// DM8 through its ODBC driver.
+pub struct Dm8Backend;
+
+impl DatabaseBackend for Dm8Backend {
+    fn kind(&self) -> DatabaseKind {
+        DatabaseKind::Dm8
+    }
+
+    fn connection_string(&self, config: &ConnectionConfig) -> String {
+        config.connection_string()
+    }
+
+    fn set_schema_sql(&self, schema: &str) -> String {
+        format!("SET SCHEMA {}", schema)
+    }
+
+    fn read_only_session_sql(&self) -> Option<&'static str> {
+        Some(SESSION_READ_ONLY_SQL)
+    }
+
+    fn connect(&self, config: ConnectionConfig) -> Result<ConnectionPool> {
+        ConnectionPool::with_backend(config, &Dm8Backend)
+    }
+
+    fn schema_provider(&self, metadata_cache: Arc<MetadataCache>) -> Arc<dyn SchemaProvider> {
+        Arc::new(OdbcSchemaProvider::new(metadata_cache))
+    }
+
+    fn stream_rows(
+        &self,
+        connection: &Connection<'_>,
+        query: &str,
+        fetch: RowFetch<'_>,
+        emit: &mut dyn FnMut(Vec<Vec<Option<String>>>) -> Result<()>,
+    ) -> Result<()> {
+        let Some(cursor) = execute_read(connection, query)? else {
+            return Ok(());
+        };
+        if let Some(max_lens) = fetch.wide_lengths {
+            return fetch_wide_rows(
+                cursor,
+                fetch.batch_size,
+                max_lens,
+                fetch.key_index,
+                fetch.stats,
+                fetch.progress,
+                emit,
+            );
+        }
+        fetch_rows(
+            cursor,
+            fetch.batch_size,
+            fetch.column_count,
+            fetch.key_index,
+            fetch.stats,
+            fetch.progress,
+            emit,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DatabaseKind;
+
+    #[test]
+    fn database_kind_round_trips_config_store_value() {
+        let kind = DatabaseKind::parse(" DM8 ").unwrap();
+        assert_eq!(kind, DatabaseKind::Dm8);
+        assert_eq!(DatabaseKind::parse(kind.as_str()).unwrap(), kind);
+        assert_eq!(kind.backend().kind(), kind);
+        assert!(DatabaseKind::parse("oracle").is_err());
+    }
+}
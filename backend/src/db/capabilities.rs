@@ -0,0 +1,338 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use anyhow::Result;
+use odbc_api::{buffers::TextRowSet, Connection, Cursor};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{read_only::execute_read, schema::fetch_case_sensitivity};
+
+/// Optional catalog columns and views that differ between DM8 releases.
+///
+/// Detected once per pool by probing each column with a zero-row query; metadata queries
+/// select `NULL` in place of anything the server does not expose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerCapabilities {
+    /// `ALL_TRIGGERS.TRIGGER_TYPE`
+    pub trigger_type: bool,
+    /// `ALL_TRIGGERS.DESCRIPTION`
+    pub trigger_description: bool,
+    /// `ALL_TRIGGERS.WHEN_CLAUSE`
+    pub trigger_when_clause: bool,
+    /// `ALL_CONSTRAINTS.UPDATE_RULE`
+    pub constraint_update_rule: bool,
+    /// `ALL_IND_COLUMNS.DESCEND`
+    pub index_descend: bool,
+    /// `ALL_IND_EXPRESSIONS` view
+    pub index_expressions: bool,
+    /// `ALL_TAB_COLUMNS.COLLATION`
+    pub column_collation: bool,
+    /// `ALL_TABLES.DEFAULT_COLLATION`
+    pub table_default_collation: bool,
+    /// `ALL_TAB_IDENTITY_COLS` view
+    pub identity_generation: bool,
+    /// `ALL_TAB_COLS.VIRTUAL_COLUMN`
+    pub virtual_columns: bool,
+}
+
+impl Default for ServerCapabilities {
+    /// Assumes a current DM8 release with every optional column available.
+    fn default() -> Self {
+        Self {
+            trigger_type: true,
+            trigger_description: true,
+            trigger_when_clause: true,
+            constraint_update_rule: true,
+            index_descend: true,
+            index_expressions: true,
+            column_collation: true,
+            table_default_collation: true,
+            identity_generation: true,
+            virtual_columns: true,
+        }
+    }
+}
+
+impl ServerCapabilities {
+    /// Probes every optional column. Fails when a probe fails for any reason other than the
+    /// column or view not existing, so a dropped link is not mistaken for an older server.
+    pub fn detect(connection: &Connection<'_>) -> Result<Self> {
+        let capabilities = Self {
+            trigger_type: probe(connection, "ALL_TRIGGERS", "TRIGGER_TYPE")?,
+            trigger_description: probe(connection, "ALL_TRIGGERS", "DESCRIPTION")?,
+            trigger_when_clause: probe(connection, "ALL_TRIGGERS", "WHEN_CLAUSE")?,
+            constraint_update_rule: probe(connection, "ALL_CONSTRAINTS", "UPDATE_RULE")?,
+            index_descend: probe(connection, "ALL_IND_COLUMNS", "DESCEND")?,
+            index_expressions: probe(connection, "ALL_IND_EXPRESSIONS", "COLUMN_EXPRESSION")?,
+            column_collation: probe(connection, "ALL_TAB_COLUMNS", "COLLATION")?,
+            table_default_collation: probe(connection, "ALL_TABLES", "DEFAULT_COLLATION")?,
+            identity_generation: probe(connection, "ALL_TAB_IDENTITY_COLS", "GENERATION_TYPE")?,
+            virtual_columns: probe(connection, "ALL_TAB_COLS", "VIRTUAL_COLUMN")?,
+        };
+        tracing::debug!("Detected DM8 catalog capabilities: {:?}", capabilities);
+        Ok(capabilities)
+    }
+
+    /// Returns `column` when available, otherwise `NULL AS column` so result positions stay stable.
+    /// Table-qualified columns (`ac.UPDATE_RULE`) are aliased by their bare name.
+    pub fn column(available: bool, column: &str) -> String {
+        if available {
+            column.to_string()
+        } else {
+            let alias = column.rsplit('.').next().unwrap_or(column);
+            format!("NULL AS {}", alias)
+        }
+    }
+}
+
+/// Capabilities detected so far, keyed by server and login (`connection_key`). Pools are
+/// created per request, so this spares each of them the probes; a key only ever holds what
+/// its own server reported, so an older server never degrades queries against a newer one.
+static DETECTED: OnceLock<Mutex<HashMap<String, ServerCapabilities>>> = OnceLock::new();
+
+fn detected() -> &'static Mutex<HashMap<String, ServerCapabilities>> {
+    DETECTED.get_or_init(Mutex::default)
+}
+
+/// Capabilities recorded for `server`, running `detect` on the first request for it. A failed
+/// detection is not recorded, so the next request probes again.
+pub fn cached_capabilities(
+    server: &str,
+    detect: impl FnOnce() -> Result<ServerCapabilities>,
+) -> Result<ServerCapabilities> {
+    if let Some(capabilities) = detected()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(server)
+    {
+        return Ok(*capabilities);
+    }
+    // Probe without the lock, so a slow server does not hold up the others.
+    let capabilities = detect()?;
+    Ok(*detected()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(server.to_string())
+        .or_insert(capabilities))
+}
+
+/// Drops the recorded capabilities of `server`, or of every server, so the next pool probes
+/// again (e.g. after a server upgrade).
+pub fn forget_capabilities(server: Option<&str>) {
+    let mut detected = detected().lock().unwrap_or_else(|e| e.into_inner());
+    match server {
+        Some(server) => {
+            detected.remove(server);
+        }
+        None => detected.clear(),
+    }
+}
+
+/// What the server is, as served by `GET /api/server/info`: probed once per pool next to the
+/// [`ServerCapabilities`]. Each probe is a query of its own and reports `None` when the
+/// server cannot answer it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerInfo {
+    /// First line of `V$VERSION`, e.g. `DM Database Server 64 V8`.
+    pub version: Option<String>,
+    /// Every line of `V$VERSION`, including the build.
+    pub banner: Vec<String>,
+    /// Database character set: `GB18030`, `UTF-8` or `EUC-KR`.
+    pub charset: Option<String>,
+    /// Whether identifiers and strings compare case-sensitively (`CASE_SENSITIVE`).
+    pub case_sensitive: Option<bool>,
+    /// Data page size in bytes.
+    pub page_size: Option<u32>,
+    pub capabilities: ServerCapabilities,
+}
+
+impl ServerInfo {
+    pub fn detect(connection: &Connection<'_>, capabilities: ServerCapabilities) -> Self {
+        let banner = optional(
+            "version",
+            fetch_column(connection, "SELECT BANNER FROM V$VERSION"),
+        )
+        .unwrap_or_default();
+        let charset = optional(
+            "character set",
+            fetch_column(connection, "SELECT SF_GET_UNICODE_FLAG() FROM DUAL"),
+        )
+        .and_then(|values| values.first().and_then(|flag| charset_name(flag)));
+        let page_size = optional(
+            "page size",
+            fetch_column(connection, "SELECT PAGE() FROM DUAL"),
+        )
+        .and_then(|values| values.first().and_then(|size| size.trim().parse().ok()));
+        let info = Self {
+            version: banner.first().cloned(),
+            banner,
+            charset,
+            case_sensitive: optional("case sensitivity", fetch_case_sensitivity(connection))
+                .flatten(),
+            page_size,
+            capabilities,
+        };
+        tracing::debug!("Detected DM8 server: {:?}", info);
+        info
+    }
+}
+
+/// Name of the character set for a `SF_GET_UNICODE_FLAG()` value.
+fn charset_name(flag: &str) -> Option<String> {
+    let name = match flag.trim() {
+        "0" => "GB18030",
+        "1" => "UTF-8",
+        "2" => "EUC-KR",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+fn optional<T>(what: &str, result: Result<T>) -> Option<T> {
+    result
+        .map_err(|err| tracing::debug!("Could not read the DM8 {}: {:#}", what, err))
+        .ok()
+}
+
+/// Non-null values of the first column of `sql`, trimmed.
+fn fetch_column(connection: &Connection<'_>, sql: &str) -> Result<Vec<String>> {
+    let Some(mut cursor) = execute_read(connection, sql)? else {
+        return Ok(Vec::new());
+    };
+    let mut buffers = TextRowSet::for_cursor(50, &mut cursor, Some(1024))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    let mut values = Vec::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            if let Some(value) = batch.at_as_str(0, row_index)? {
+                values.push(value.trim().to_string());
+            }
+        }
+    }
+    Ok(values)
+}
+
+/// SQLSTATEs and DM8 error codes for a column or view the server does not know: the only
+/// probe failures that mean an optional catalog column is missing.
+const MISSING_OBJECT_MARKERS: &[&str] = &[
+    "State: 42S02",
+    "State: 42S22",
+    "Native error: -2106",
+    "Native error: -2111",
+    "无效的表或视图名",
+    "无效的列名",
+];
+
+/// Whether a failed probe means the column or view does not exist.
+fn is_missing_object(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        let message = cause.to_string();
+        MISSING_OBJECT_MARKERS
+            .iter()
+            .any(|marker| message.contains(marker))
+    })
+}
+
+fn probe(connection: &Connection<'_>, view: &str, column: &str) -> Result<bool> {
+    let sql = format!("SELECT {} FROM {} WHERE 1 = 0", column, view);
+    match execute_read(connection, &sql) {
+        Ok(_) => Ok(true),
+        Err(err) if is_missing_object(&err) => {
+            tracing::debug!("DM8 catalog lacks {}.{}: {}", view, column, err);
+            Ok(false)
+        }
+        Err(err) => Err(err.context(format!("Failed to probe {}.{}", view, column))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cached_capabilities, charset_name, forget_capabilities, is_missing_object,
+        ServerCapabilities,
+    };
+
+    #[test]
+    fn capabilities_are_recorded_per_server() {
+        let old = ServerCapabilities {
+            trigger_type: false,
+            constraint_update_rule: false,
+            ..ServerCapabilities::default()
+        };
+        let old_server = "SYSDBA@old-host:5236";
+        let new_server = "SYSDBA@new-host:5236";
+        forget_capabilities(Some(old_server));
+        forget_capabilities(Some(new_server));
+
+        assert_eq!(cached_capabilities(old_server, || Ok(old)).unwrap(), old);
+        assert_eq!(
+            cached_capabilities(new_server, || Ok(ServerCapabilities::default())).unwrap(),
+            ServerCapabilities::default()
+        );
+        // Later pools reuse the first probe of their own server.
+        assert_eq!(
+            cached_capabilities(old_server, || unreachable!("already detected")).unwrap(),
+            old
+        );
+
+        forget_capabilities(Some(old_server));
+        assert_eq!(
+            cached_capabilities(old_server, || Ok(ServerCapabilities::default())).unwrap(),
+            ServerCapabilities::default()
+        );
+    }
+
+    #[test]
+    fn failed_detection_is_not_recorded() {
+        let server = "SYSDBA@flaky-host:5236";
+        forget_capabilities(Some(server));
+
+        assert!(cached_capabilities(server, || anyhow::bail!("link dropped")).is_err());
+        assert_eq!(
+            cached_capabilities(server, || Ok(ServerCapabilities::default())).unwrap(),
+            ServerCapabilities::default()
+        );
+        forget_capabilities(Some(server));
+    }
+
+    #[test]
+    fn only_unknown_columns_and_views_count_as_missing() {
+        let column = anyhow::anyhow!(
+            "State: 42S22, Native error: -2111, Message: [DM][ODBC DM driver]无效的列名[WHEN_CLAUSE]"
+        );
+        let view = anyhow::anyhow!("State: 42S02, Native error: -2106, Message: 无效的表或视图名");
+        assert!(is_missing_object(&column));
+        assert!(is_missing_object(&view.context("Failed to execute query")));
+
+        let link = anyhow::anyhow!(
+            "State: 08S01, Native error: -70019, Message: Communication link failure"
+        );
+        let privilege = anyhow::anyhow!("State: 42000, Native error: -5504, Message: 没有权限");
+        assert!(!is_missing_object(&link));
+        assert!(!is_missing_object(&privilege));
+    }
+
+    #[test]
+    fn unicode_flag_names_the_character_set() {
+        assert_eq!(charset_name("0").as_deref(), Some("GB18030"));
+        assert_eq!(charset_name(" 1 ").as_deref(), Some("UTF-8"));
+        assert_eq!(charset_name("2").as_deref(), Some("EUC-KR"));
+        assert_eq!(charset_name("9"), None);
+    }
+
+    #[test]
+    fn column_selects_null_placeholder_when_missing() {
+        assert_eq!(ServerCapabilities::column(true, "DESCEND"), "DESCEND");
+        assert_eq!(
+            ServerCapabilities::column(false, "DESCEND"),
+            "NULL AS DESCEND"
+        );
+        assert_eq!(
+            ServerCapabilities::column(false, "ac.UPDATE_RULE"),
+            "NULL AS UPDATE_RULE"
+        );
+    }
+}
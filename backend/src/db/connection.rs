@@ -1,80 +1,213 @@
-use anyhow::{ensure, Context, Result};
-use odbc_api::{Connection, ConnectionOptions, Environment};
+use anyhow::{anyhow, ensure, Context, Result};
+use odbc_api::handles::{AsStatementRef, Statement};
+use odbc_api::{sys, Connection, ConnectionOptions, Environment, Preallocated};
 use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
 
+use crate::db::backend::DatabaseBackend;
+use crate::db::capabilities::{cached_capabilities, ServerCapabilities, ServerInfo};
+use crate::db::metadata_cache::connection_key;
+use crate::db::read_only::ensure_read_only;
+use crate::db::driver::{driver_override, resolve_driver};
 use crate::models::ConnectionConfig;
+use crate::secrets::{is_secret_reference, trust_references, SecretResolver};
+
+/// Login timeout when the config does not set one.
+pub const DEFAULT_LOGIN_TIMEOUT_SECS: u32 = 15;
+/// Timeout of connection setup and health check statements when the config does not set one.
+pub const DEFAULT_QUERY_TIMEOUT_SECS: u32 = 30;
 
 impl ConnectionConfig {
-    /// Returns the ODBC driver value; prefers an explicit path from `DM8_DRIVER_PATH`.
+    /// Returns the ODBC driver value in braces, as the connection string expects it.
     fn driver_value() -> String {
-        if let Ok(path) = std::env::var("DM8_DRIVER_PATH") {
-            if !path.trim().is_empty() {
-                return format!("{{{}}}", path.trim());
+        format!("{{{}}}", resolve_driver(driver_override()).path)
+    }
+
+    /// The configured DSN name, ignoring blank values.
+    pub fn dsn_name(&self) -> Option<&str> {
+        non_blank(&self.dsn)
+    }
+
+    /// Builds the ODBC connection string expected by the DM8 driver, or a `DSN=` string that
+    /// leaves driver and server to the data source definition. SSL settings are appended to
+    /// either form.
+    pub fn connection_string(&self) -> String {
+        let mut connection_string = match self.dsn_name() {
+            Some(dsn) => format!("DSN={};UID={};PWD={}", dsn, self.username, self.password),
+            None => format!(
+                "DRIVER={};SERVER={};PORT={};UID={};PWD={}",
+                Self::driver_value(),
+                self.host,
+                self.port,
+                self.username,
+                self.password
+            ),
+        };
+        if self.ssl {
+            if let Some(path) = non_blank(&self.ssl_cert_path) {
+                connection_string.push_str(&format!(";SSL_PATH={}", braced(path)));
+            }
+            if let Some(password) = non_blank(&self.ssl_cert_password) {
+                connection_string.push_str(&format!(";SSL_PWD={}", braced(password)));
+            }
+            if let Some(cipher) = non_blank(&self.ssl_cipher) {
+                connection_string.push_str(&format!(";CIPHER_NAME={}", cipher));
             }
         }
+        connection_string
+    }
 
-        // Try bundled relative path (for HTTP dev runs)
-        let candidates = [
-            "drivers/dm8/libdodbc.so",
-            "../drivers/dm8/libdodbc.so",
-        ];
-        for candidate in candidates {
-            let path = std::path::Path::new(candidate);
-            if path.exists() {
-                return format!("{{{}}}", path.display());
-            }
+    /// Effective login timeout; `None` leaves it to the driver.
+    pub fn login_timeout(&self) -> Option<u32> {
+        Some(self.login_timeout_secs.unwrap_or(DEFAULT_LOGIN_TIMEOUT_SECS)).filter(|&secs| secs > 0)
+    }
+
+    /// Effective timeout of short statements; `None` means unlimited.
+    pub fn query_timeout(&self) -> Option<u32> {
+        Some(self.query_timeout_secs.unwrap_or(DEFAULT_QUERY_TIMEOUT_SECS)).filter(|&secs| secs > 0)
+    }
+
+    /// Replaces `${scheme:reference}` credentials with their secret values.
+    /// Saved profiles keep the reference; the secret only lives in the resolved copy. Only
+    /// references that a saved connection or the environment set are resolved (see
+    /// [`ConnectionConfig::trust_secrets`]).
+    pub fn resolve_secrets(mut self) -> Result<Self> {
+        let resolver = SecretResolver::default();
+        self.username = resolver.resolve_trusted(&self.username)?;
+        self.password = resolver.resolve_trusted(&self.password)?;
+        if let Some(password) = &self.ssl_cert_password {
+            self.ssl_cert_password = Some(resolver.resolve_trusted(password)?);
         }
+        Ok(self)
+    }
 
-        "{DM8 ODBC DRIVER}".to_string()
+    /// Allows the secret references of this config, which comes from the config store or the
+    /// environment, to be resolved when a request sends them back.
+    pub fn trust_secrets(&self) {
+        trust_references(
+            [self.username.as_str(), self.password.as_str()]
+                .into_iter()
+                .chain(self.ssl_cert_password.as_deref()),
+        );
     }
 
-    /// Builds the ODBC connection string expected by the DM8 driver.
-    pub fn connection_string(&self) -> String {
-        let driver = Self::driver_value();
-        format!(
-            "DRIVER={};SERVER={};PORT={};UID={};PWD={}",
-            driver, self.host, self.port, self.username, self.password
-        )
+    /// Whether a credential is a `${scheme:reference}`.
+    pub fn has_secret_references(&self) -> bool {
+        [self.username.as_str(), self.password.as_str()]
+            .into_iter()
+            .chain(self.ssl_cert_password.as_deref())
+            .any(is_secret_reference)
     }
 
     /// Basic validation to surface misconfiguration early.
     pub fn validate(&self) -> Result<()> {
-        ensure!(!self.host.trim().is_empty(), "DM8 host is required");
-        ensure!(self.port > 0, "DM8 port must be greater than zero");
+        if let Some(dsn) = self.dsn_name() {
+            ensure!(
+                !dsn.contains(['{', '}', ';', '=']),
+                "DM8 DSN must not contain '{{', '}}', ';' or '='"
+            );
+        } else {
+            ensure!(!self.host.trim().is_empty(), "DM8 host is required");
+            ensure!(self.port > 0, "DM8 port must be greater than zero");
+        }
         ensure!(
             !self.username.trim().is_empty(),
             "DM8 username is required"
         );
         ensure!(!self.password.is_empty(), "DM8 password is required");
+        if self.ssl {
+            self.validate_ssl()?;
+        }
         Ok(())
     }
+
+    fn validate_ssl(&self) -> Result<()> {
+        let path = non_blank(&self.ssl_cert_path)
+            .ok_or_else(|| anyhow!("SSL certificate path is required when SSL is enabled"))?;
+        ensure!(
+            Path::new(path).exists(),
+            "SSL certificate path {} does not exist",
+            path
+        );
+        if let Some(cipher) = non_blank(&self.ssl_cipher) {
+            ensure!(
+                cipher
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+                "SSL cipher '{}' may only contain letters, digits, '_' and '-'",
+                cipher
+            );
+        }
+        Ok(())
+    }
+}
+
+fn non_blank(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Wraps a connection string value in braces so `;` and `=` inside it are taken literally.
+fn braced(value: &str) -> String {
+    format!("{{{}}}", value.replace('}', "}}"))
 }
 
 pub struct ConnectionPool {
+    backend: &'static dyn DatabaseBackend,
     environment: Environment,
     connection_string: String,
     schema: Option<String>,
     display_dsn: String,
+    login_timeout: Option<u32>,
+    query_timeout: Option<u32>,
+    /// Mark every session read-only and refuse anything but queries; on unless a caller
+    /// explicitly opts out with [`ConnectionPool::with_read_only`].
+    read_only: bool,
+    /// Server and login, keying the capabilities shared with other pools.
+    server: String,
+    capabilities: OnceLock<ServerCapabilities>,
+    server_info: OnceLock<ServerInfo>,
 }
 
 impl fmt::Debug for ConnectionPool {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ConnectionPool")
+            .field("backend", &self.backend.kind())
             .field("dsn", &self.display_dsn)
             .field("schema", &self.schema)
+            .field("read_only", &self.read_only)
             .finish()
     }
 }
 
 impl ConnectionPool {
-    /// Create a new pool backed by the DM8 ODBC driver.
+    /// Create a new pool for the config's `db_type`.
     pub fn new(config: ConnectionConfig) -> Result<Self> {
+        let backend = config.db_type.backend();
+        Self::with_backend(config, backend)
+    }
+
+    /// Create a new pool whose connection string and session setup come from `backend`.
+    pub fn with_backend(
+        config: ConnectionConfig,
+        backend: &'static dyn DatabaseBackend,
+    ) -> Result<Self> {
+        let config = config
+            .resolve_secrets()
+            .context("Failed to resolve DM8 credentials")?;
         config
             .validate()
             .context("Invalid DM8 connection configuration")?;
 
         let environment = Environment::new().context("Failed to initialize ODBC environment")?;
-        let connection_string = config.connection_string();
+        let connection_string = backend.connection_string(&config);
+        let login_timeout = config.login_timeout();
+        let query_timeout = config.query_timeout();
+        let display_dsn = match config.dsn_name() {
+            Some(dsn) => format!("DSN {} as {}", dsn, config.username),
+            None => format!("{}:{} as {}", config.host, config.port, config.username),
+        };
+        let server = connection_key(&config);
         let schema = if config.schema.trim().is_empty() {
             None
         } else {
@@ -82,48 +215,259 @@ impl ConnectionPool {
         };
 
         Ok(Self {
+            backend,
             environment,
-            display_dsn: format!("{}:{} as {}", config.host, config.port, config.username),
+            display_dsn,
             connection_string,
+            login_timeout,
+            query_timeout,
+            read_only: true,
             schema,
+            server,
+            capabilities: OnceLock::new(),
+            server_info: OnceLock::new(),
         })
     }
 
+    /// Whether sessions are marked read-only and statements other than queries are refused.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// The database implementation this pool connects to.
+    pub fn backend(&self) -> &'static dyn DatabaseBackend {
+        self.backend
+    }
+
     /// Attempts to open a connection and run a lightweight query.
     pub fn test_connection(&self) -> Result<()> {
         let connection = self
             .get_connection()
             .context("Unable to open test connection to DM8")?;
 
-        connection
-            .execute("SELECT 1", ())
+        self.execute_with_timeout(&connection, "SELECT 1")
             .context("Connected to DM8 but failed to execute health query")?;
 
         Ok(())
     }
 
-    /// Returns a new ODBC connection configured for DM8.
+    /// Runs a short statement (health check, heartbeat) and discards any result, failing when
+    /// it takes longer than the configured query timeout. Read-only pools only accept queries.
+    pub fn execute_with_timeout(&self, connection: &Connection<'_>, sql: &str) -> Result<()> {
+        if self.read_only {
+            ensure_read_only(sql)?;
+        }
+        self.execute_session_statement(connection, sql)
+    }
+
+    /// Session setup issued by the pool itself; bypasses the read-only guard.
+    fn execute_session_statement(&self, connection: &Connection<'_>, sql: &str) -> Result<()> {
+        let mut statement = connection.preallocate()?;
+        if let Some(secs) = self.query_timeout {
+            set_query_timeout(&mut statement, secs)?;
+        }
+        statement.execute(sql, ())?;
+        Ok(())
+    }
+
+    /// Returns a new ODBC connection with the backend's session setup applied.
     pub fn get_connection(&self) -> Result<Connection<'_>> {
+        let database = self.backend.kind().display_name();
         let mut connection = self
             .environment
             .connect_with_connection_string(
                 &self.connection_string,
-                ConnectionOptions::default(),
+                ConnectionOptions {
+                    login_timeout_sec: self.login_timeout,
+                    ..ConnectionOptions::default()
+                },
             )
-            .with_context(|| format!("Failed to connect to DM8 at {}", self.display_dsn))?;
+            .with_context(|| match self.login_timeout {
+                Some(secs) => format!(
+                    "Failed to connect to {} at {} (login timeout {} s)",
+                    database, self.display_dsn, secs
+                ),
+                None => format!("Failed to connect to {} at {}", database, self.display_dsn),
+            })?;
 
+        self.apply_read_only(&connection);
         self.apply_schema(&mut connection)?;
 
         Ok(connection)
     }
 
+    /// Catalog capabilities of the server, probed the first time any pool for the same server
+    /// and login asks and cached for the pool.
+    pub fn capabilities(&self, connection: &Connection<'_>) -> Result<ServerCapabilities> {
+        if let Some(capabilities) = self.capabilities.get() {
+            return Ok(*capabilities);
+        }
+        let capabilities =
+            cached_capabilities(&self.server, || ServerCapabilities::detect(connection))?;
+        Ok(*self.capabilities.get_or_init(|| capabilities))
+    }
+
+    /// Version, character set and other facts about the server, probed on first use and
+    /// cached for the pool.
+    pub fn server_info(&self, connection: &Connection<'_>) -> Result<ServerInfo> {
+        if let Some(info) = self.server_info.get() {
+            return Ok(info.clone());
+        }
+        let capabilities = self.capabilities(connection)?;
+        Ok(self
+            .server_info
+            .get_or_init(|| ServerInfo::detect(connection, capabilities))
+            .clone())
+    }
+
+    /// Asks the server to refuse writes for this session. Older servers without the procedure
+    /// still have every statement checked by the read-only guard, so this is not fatal.
+    fn apply_read_only(&self, connection: &Connection<'_>) {
+        let Some(sql) = self.backend.read_only_session_sql().filter(|_| self.read_only) else {
+            return;
+        };
+        if let Err(e) = self.execute_session_statement(connection, sql) {
+            tracing::warn!(
+                "Could not mark the {} session on {} read-only, relying on the statement guard: {:#}",
+                self.backend.kind().display_name(),
+                self.display_dsn,
+                e
+            );
+        }
+    }
+
     fn apply_schema(&self, connection: &mut Connection<'_>) -> Result<()> {
         if let Some(schema) = &self.schema {
-            let statement = format!("SET SCHEMA {}", schema);
-            connection
-                .execute(&statement, ())
-                .with_context(|| format!("Connected to DM8 but failed to set schema to '{}'", schema))?;
+            let statement = self.backend.set_schema_sql(schema);
+            self.execute_session_statement(connection, &statement).with_context(|| {
+                format!(
+                    "Connected to {} but failed to set schema to '{}'",
+                    self.backend.kind().display_name(),
+                    schema
+                )
+            })?;
         }
         Ok(())
     }
 }
+
+/// Sets `SQL_ATTR_QUERY_TIMEOUT` on `statement`; odbc-api 8 offers no setter for it. Drivers
+/// that cannot honour the value substitute their own and report it as a warning, which is
+/// accepted.
+fn set_query_timeout(statement: &mut Preallocated<'_>, secs: u32) -> Result<()> {
+    let handle = statement.as_stmt_ref().as_sys();
+    // SAFETY: `handle` is the allocated statement borrowed from `statement`; integer
+    // attributes are passed by value in the pointer argument.
+    let result = unsafe {
+        sys::SQLSetStmtAttr(
+            handle,
+            sys::StatementAttribute::QueryTimeout,
+            secs as usize as sys::Pointer,
+            0,
+        )
+    };
+    ensure!(
+        result != sys::SqlReturn::ERROR && result != sys::SqlReturn::INVALID_HANDLE,
+        "Failed to set the query timeout to {} s",
+        secs
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::backend::DatabaseKind;
+    use crate::models::ConnectionConfig;
+
+    fn dsn_config(dsn: &str) -> ConnectionConfig {
+        ConnectionConfig {
+            db_type: DatabaseKind::Dm8,
+            host: String::new(),
+            port: 0,
+            username: "SYSDBA".into(),
+            password: "secret".into(),
+            schema: "APP".into(),
+            export_schema: None,
+            dsn: Some(dsn.into()),
+            ssl: false,
+            ssl_cert_path: None,
+            ssl_cert_password: None,
+            ssl_cipher: None,
+            login_timeout_secs: None,
+            query_timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn dsn_replaces_driver_and_server() {
+        let config = dsn_config(" DM8_PROD ");
+        config.validate().unwrap();
+        assert_eq!(
+            config.connection_string(),
+            "DSN=DM8_PROD;UID=SYSDBA;PWD=secret"
+        );
+    }
+
+    #[test]
+    fn blank_dsn_falls_back_to_host_and_port() {
+        let config = dsn_config("  ");
+        assert_eq!(config.dsn_name(), None);
+        assert!(config.validate().is_err(), "host and port are required without a DSN");
+        assert!(dsn_config("DM8;DRIVER=x").validate().is_err());
+    }
+
+    #[test]
+    fn ssl_options_are_appended_when_enabled() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        let mut config = dsn_config("DM8_PROD");
+        config.ssl_cert_path = Some(path.clone());
+        config.ssl_cert_password = Some("p;w}d".into());
+        config.ssl_cipher = Some("AES256_CBC".into());
+        assert_eq!(
+            config.connection_string(),
+            "DSN=DM8_PROD;UID=SYSDBA;PWD=secret",
+            "SSL settings are ignored while SSL is off"
+        );
+
+        config.ssl = true;
+        config.validate().unwrap();
+        assert_eq!(
+            config.connection_string(),
+            format!(
+                "DSN=DM8_PROD;UID=SYSDBA;PWD=secret;SSL_PATH={{{}}};SSL_PWD={{p;w}}}}d}};\
+                 CIPHER_NAME=AES256_CBC",
+                path
+            )
+        );
+    }
+
+    #[test]
+    fn ssl_validation_explains_missing_settings() {
+        let mut config = dsn_config("DM8_PROD");
+        config.ssl = true;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("SSL certificate path is required"), "{}", err);
+
+        config.ssl_cert_path = Some("/nonexistent/dm8/ssl".into());
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("does not exist"), "{}", err);
+    }
+
+    #[test]
+    fn timeouts_default_and_zero_disables() {
+        let mut config = dsn_config("DM8_PROD");
+        assert_eq!(config.login_timeout(), Some(super::DEFAULT_LOGIN_TIMEOUT_SECS));
+        assert_eq!(config.query_timeout(), Some(super::DEFAULT_QUERY_TIMEOUT_SECS));
+
+        config.login_timeout_secs = Some(5);
+        config.query_timeout_secs = Some(0);
+        assert_eq!(config.login_timeout(), Some(5));
+        assert_eq!(config.query_timeout(), None);
+    }
+}
@@ -0,0 +1,290 @@
+//! Step-by-step connection check behind `POST /api/connection/test` with `diagnostics`: driver
+//! load, TCP reachability, login, `SELECT 1`, schema lookup and table count. Each step reports
+//! its own outcome and timing, so a failure points at the layer that broke; steps that need a
+//! session are skipped once login fails.
+
+use std::{
+    net::{TcpStream, ToSocketAddrs},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::db::{
+    connection::ConnectionPool,
+    driver::{check_driver, driver_override},
+    schema::{fetch_schema_names, fetch_table_names},
+};
+use crate::models::{ConnectionConfig, DiagnosticStatus, DiagnosticStep, DiagnosticStepKind};
+
+/// TCP wait when the config has no login timeout.
+const DEFAULT_TCP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Runs every diagnostic step for `config` and returns them in order.
+pub fn run_diagnostics(config: &ConnectionConfig) -> Vec<DiagnosticStep> {
+    let mut steps = Vec::new();
+
+    steps.push(timed(DiagnosticStepKind::Driver, || {
+        let check = check_driver(driver_override());
+        let library = check.library.unwrap_or(check.driver.path);
+        match check.error {
+            None => Ok(format!("Loaded {}", library)),
+            Some(error) => Err(anyhow!(error)),
+        }
+    }));
+
+    steps.push(match config.dsn_name() {
+        Some(dsn) => skipped(
+            DiagnosticStepKind::Tcp,
+            format!("Server address comes from ODBC data source '{}'", dsn),
+        ),
+        None => timed(DiagnosticStepKind::Tcp, || check_tcp(config)),
+    });
+
+    // Log in without switching schema, so a missing schema fails its own step.
+    let session_config = ConnectionConfig {
+        schema: String::new(),
+        ..config.clone()
+    };
+    let started = Instant::now();
+    let pool = match ConnectionPool::new(session_config) {
+        Ok(pool) => pool,
+        Err(e) => return login_failed(steps, started, &e),
+    };
+    let connection = match pool.get_connection() {
+        Ok(connection) => connection,
+        Err(e) => return login_failed(steps, started, &e),
+    };
+    steps.push(passed(
+        DiagnosticStepKind::Login,
+        started,
+        format!("Logged in as {}", config.username),
+    ));
+
+    steps.push(timed(DiagnosticStepKind::Query, || {
+        pool.execute_with_timeout(&connection, "SELECT 1")
+            .map(|()| "SELECT 1 succeeded".to_string())
+    }));
+
+    let schema = config.schema.trim();
+    if schema.is_empty() {
+        for step in [DiagnosticStepKind::Schema, DiagnosticStepKind::Tables] {
+            steps.push(skipped(step, "No schema given".to_string()));
+        }
+        return steps;
+    }
+    let started = Instant::now();
+    let found = fetch_schema_names(&connection).and_then(|names| find_schema(&names, schema));
+    let schema = match found {
+        Ok(name) => {
+            steps.push(passed(
+                DiagnosticStepKind::Schema,
+                started,
+                format!("Schema {} exists", name),
+            ));
+            name
+        }
+        Err(e) => {
+            steps.push(failed(DiagnosticStepKind::Schema, started, &e));
+            steps.push(skipped(
+                DiagnosticStepKind::Tables,
+                "Schema not found".to_string(),
+            ));
+            return steps;
+        }
+    };
+
+    let started = Instant::now();
+    steps.push(match fetch_table_names(&connection, &schema) {
+        Ok(tables) => DiagnosticStep {
+            count: Some(tables.len()),
+            ..passed(
+                DiagnosticStepKind::Tables,
+                started,
+                format!("{} table(s) visible in {}", tables.len(), schema),
+            )
+        },
+        Err(e) => failed(DiagnosticStepKind::Tables, started, &e),
+    });
+    steps
+}
+
+fn login_failed(
+    mut steps: Vec<DiagnosticStep>,
+    started: Instant,
+    error: &anyhow::Error,
+) -> Vec<DiagnosticStep> {
+    steps.push(failed(DiagnosticStepKind::Login, started, error));
+    for step in [
+        DiagnosticStepKind::Query,
+        DiagnosticStepKind::Schema,
+        DiagnosticStepKind::Tables,
+    ] {
+        steps.push(skipped(step, "Login failed".to_string()));
+    }
+    steps
+}
+
+/// Whether every step that ran passed.
+pub fn diagnostics_passed(steps: &[DiagnosticStep]) -> bool {
+    steps
+        .iter()
+        .all(|step| step.status != DiagnosticStatus::Failed)
+}
+
+fn check_tcp(config: &ConnectionConfig) -> Result<String> {
+    let timeout = config
+        .login_timeout()
+        .map_or(DEFAULT_TCP_TIMEOUT, |secs| Duration::from_secs(secs.into()));
+    let target = format!("{}:{}", config.host.trim(), config.port);
+    let addresses: Vec<_> = target
+        .to_socket_addrs()
+        .with_context(|| format!("Cannot resolve {}", target))?
+        .collect();
+
+    let mut last_error = anyhow!("{} resolves to no address", target);
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(_) => return Ok(format!("Reached {} ({})", target, address)),
+            Err(e) => last_error = anyhow!("Cannot reach {} ({}): {}", target, address, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// The catalog spelling of `schema`: an exact match, else the only case-insensitive one.
+fn find_schema(names: &[String], schema: &str) -> Result<String> {
+    if let Some(name) = names.iter().find(|name| *name == schema) {
+        return Ok(name.clone());
+    }
+    let matches: Vec<_> = names
+        .iter()
+        .filter(|name| name.eq_ignore_ascii_case(schema))
+        .collect();
+    match matches.as_slice() {
+        [name] => Ok((*name).clone()),
+        _ => Err(anyhow!(
+            "Schema '{}' does not exist or is not visible to this user",
+            schema
+        )),
+    }
+}
+
+fn timed(step: DiagnosticStepKind, run: impl FnOnce() -> Result<String>) -> DiagnosticStep {
+    let started = Instant::now();
+    match run() {
+        Ok(message) => passed(step, started, message),
+        Err(e) => failed(step, started, &e),
+    }
+}
+
+fn passed(step: DiagnosticStepKind, started: Instant, message: String) -> DiagnosticStep {
+    DiagnosticStep {
+        step,
+        status: DiagnosticStatus::Passed,
+        duration_ms: elapsed_ms(started),
+        message,
+        count: None,
+    }
+}
+
+fn failed(step: DiagnosticStepKind, started: Instant, error: &anyhow::Error) -> DiagnosticStep {
+    DiagnosticStep {
+        step,
+        status: DiagnosticStatus::Failed,
+        duration_ms: elapsed_ms(started),
+        message: format!("{:#}", error),
+        count: None,
+    }
+}
+
+fn skipped(step: DiagnosticStepKind, message: String) -> DiagnosticStep {
+    DiagnosticStep {
+        step,
+        status: DiagnosticStatus::Skipped,
+        duration_ms: 0,
+        message,
+        count: None,
+    }
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::db::backend::DatabaseKind;
+
+    fn config(host: &str, port: u16) -> ConnectionConfig {
+        ConnectionConfig {
+            db_type: DatabaseKind::Dm8,
+            host: host.into(),
+            port,
+            username: "SYSDBA".into(),
+            password: "secret".into(),
+            schema: "APP".into(),
+            export_schema: None,
+            dsn: None,
+            ssl: false,
+            ssl_cert_path: None,
+            ssl_cert_password: None,
+            ssl_cipher: None,
+            login_timeout_secs: Some(2),
+            query_timeout_secs: None,
+        }
+    }
+
+    fn kinds(steps: &[DiagnosticStep]) -> Vec<(DiagnosticStepKind, DiagnosticStatus)> {
+        steps.iter().map(|step| (step.step, step.status)).collect()
+    }
+
+    #[test]
+    fn reachable_server_that_refuses_login_skips_session_steps() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let steps = run_diagnostics(&config("127.0.0.1", port));
+        assert_eq!(
+            kinds(&steps[1..]),
+            vec![
+                (DiagnosticStepKind::Tcp, DiagnosticStatus::Passed),
+                (DiagnosticStepKind::Login, DiagnosticStatus::Failed),
+                (DiagnosticStepKind::Query, DiagnosticStatus::Skipped),
+                (DiagnosticStepKind::Schema, DiagnosticStatus::Skipped),
+                (DiagnosticStepKind::Tables, DiagnosticStatus::Skipped),
+            ]
+        );
+        assert_eq!(steps[0].step, DiagnosticStepKind::Driver);
+        assert!(!diagnostics_passed(&steps));
+    }
+
+    #[test]
+    fn closed_port_fails_tcp_and_dsn_skips_it() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let tcp = check_tcp(&config("127.0.0.1", port)).unwrap_err();
+        assert!(tcp.to_string().contains("Cannot reach 127.0.0.1:"));
+
+        let mut dsn = config("", 0);
+        dsn.dsn = Some("DM8_PROD".into());
+        let steps = run_diagnostics(&dsn);
+        assert_eq!(steps[1].status, DiagnosticStatus::Skipped);
+        assert!(steps[1].message.contains("DM8_PROD"));
+    }
+
+    #[test]
+    fn schema_lookup_prefers_exact_then_unique_case_insensitive_match() {
+        let names = vec!["APP".to_string(), "Mixed".to_string(), "MIXED".to_string()];
+        assert_eq!(find_schema(&names, "APP").unwrap(), "APP");
+        assert_eq!(find_schema(&names, "app").unwrap(), "APP");
+        assert_eq!(find_schema(&names, "Mixed").unwrap(), "Mixed");
+        assert!(find_schema(&names, "mixed").is_err());
+        assert!(find_schema(&names, "OTHER").is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use std::path::Path;
+use std::sync::RwLock;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use odbc_api::Environment;
+
+use crate::config_store::ConfigStore;
+use crate::models::{DriverCheckResponse, DriverInfo, DriverSource};
+
+/// Config store setting holding a driver path chosen through `POST /api/config/driver`.
+pub const DRIVER_PATH_SETTING: &str = "driver_path";
+
+/// Name of the driver registered with the ODBC driver manager, used when no library is found.
+pub const SYSTEM_DRIVER_NAME: &str = "DM8 ODBC DRIVER";
+
+/// File name of the DM8 ODBC driver library on this platform.
+const DRIVER_FILENAME: &str = if cfg!(target_os = "windows") {
+    "dmodbc.dll"
+} else if cfg!(target_os = "macos") {
+    "libdodbc.dylib"
+} else {
+    "libdodbc.so"
+};
+
+/// Function every ODBC driver exports; a library without it is not a driver.
+const DRIVER_ENTRY_POINT: &str = "SQLDriverConnect";
+
+/// Driver manager attributes that may carry a driver's version, most specific first.
+const VERSION_ATTRIBUTES: [&str; 3] = ["Version", "DriverVer", "DriverODBCVer"];
+
+/// Directories searched for the bundled driver (for HTTP dev runs).
+const BUNDLED_DIRS: [&str; 2] = ["drivers/dm8", "../drivers/dm8"];
+
+/// Driver library chosen at run time through `POST /api/config/driver`, or restored from the
+/// config store at startup. New connections read it instead of the process environment, which
+/// is never changed while the server runs.
+#[derive(Debug, Default)]
+pub struct DriverOverride(RwLock<Option<String>>);
+
+impl DriverOverride {
+    pub fn get(&self) -> Option<String> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn set(&self, path: String) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Some(path);
+    }
+}
+
+static DRIVER_OVERRIDE: DriverOverride = DriverOverride(RwLock::new(None));
+
+/// The override of this process, held by the API state and read by connection setup.
+pub fn driver_override() -> &'static DriverOverride {
+    &DRIVER_OVERRIDE
+}
+
+/// Resolves the ODBC driver new connections use: the chosen `driver`, then an explicit path
+/// from `DM8_DRIVER_PATH`, then the bundled library, then the registered driver name.
+pub fn resolve_driver(driver: &DriverOverride) -> DriverInfo {
+    if let Some(path) = driver.get() {
+        return DriverInfo {
+            exists: Some(Path::new(&path).exists()),
+            path,
+            source: DriverSource::Selected,
+        };
+    }
+    if let Ok(path) = std::env::var("DM8_DRIVER_PATH") {
+        if !path.trim().is_empty() {
+            return DriverInfo {
+                path: path.trim().to_string(),
+                source: DriverSource::Env,
+                exists: Some(Path::new(path.trim()).exists()),
+            };
+        }
+    }
+
+    for dir in BUNDLED_DIRS {
+        let path = Path::new(dir).join(DRIVER_FILENAME);
+        if path.exists() {
+            return DriverInfo {
+                path: path.display().to_string(),
+                source: DriverSource::Bundled,
+                exists: Some(true),
+            };
+        }
+    }
+
+    DriverInfo {
+        path: SYSTEM_DRIVER_NAME.to_string(),
+        source: DriverSource::System,
+        exists: None,
+    }
+}
+
+/// Loads the resolved driver library without connecting to a server. A registered driver is
+/// loaded from the library the driver manager lists for it; the version comes from the
+/// driver manager's entry, when it has one.
+pub fn check_driver(driver: &DriverOverride) -> DriverCheckResponse {
+    let driver = resolve_driver(driver);
+    let registered = registered_driver(&driver);
+    let library = match driver.source {
+        DriverSource::System => registered
+            .as_ref()
+            .and_then(|entry| entry.attributes.get("Driver").cloned()),
+        DriverSource::Selected | DriverSource::Env | DriverSource::Bundled => {
+            Some(driver.path.clone())
+        }
+    };
+    let version = registered.as_ref().and_then(|entry| {
+        VERSION_ATTRIBUTES
+            .iter()
+            .find_map(|key| entry.attributes.get(*key).cloned())
+    });
+
+    let loaded = match &library {
+        Some(library) => load_library(library),
+        None => Err(anyhow!(
+            "Driver '{}' is not registered with the ODBC driver manager",
+            driver.path
+        )),
+    };
+
+    DriverCheckResponse {
+        driver,
+        library,
+        loaded: loaded.is_ok(),
+        version,
+        error: loaded.err().map(|e| format!("{:#}", e)),
+    }
+}
+
+/// Every DM8 ODBC driver library a driver path may be switched to, like the desktop shell's
+/// driver choice: the library named by `DM8_DRIVER_PATH`, the bundled library and each library
+/// registered with the ODBC driver manager, when its file has the DM8 driver's name. A library
+/// reachable several ways is listed once.
+pub fn discover_candidates() -> Vec<String> {
+    let env = std::env::var("DM8_DRIVER_PATH")
+        .ok()
+        .map(|path| path.trim().to_string());
+    let bundled = BUNDLED_DIRS
+        .iter()
+        .map(|dir| Path::new(dir).join(DRIVER_FILENAME).display().to_string());
+    let registered: Vec<String> = Environment::new()
+        .ok()
+        .and_then(|environment| environment.drivers().ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| entry.attributes.get("Driver").cloned())
+        .collect();
+
+    let mut candidates: Vec<String> = Vec::new();
+    for path in env.into_iter().chain(bundled).chain(registered) {
+        let file = Path::new(&path);
+        let is_driver = file.is_file()
+            && file
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy() == DRIVER_FILENAME);
+        if is_driver && !candidates.iter().any(|known| same_file(known, &path)) {
+            candidates.push(path);
+        }
+    }
+    candidates
+}
+
+/// Checks that `path` is one of `candidates` and a loadable ODBC driver library. Only
+/// discovered drivers are accepted, so a request cannot make the server load any library it
+/// names.
+pub fn validate_driver_path(path: &str, candidates: &[String]) -> Result<()> {
+    let file = Path::new(path);
+    ensure!(file.is_file(), "Driver file {} does not exist", path);
+    ensure!(
+        candidates
+            .iter()
+            .any(|candidate| same_file(candidate, path)),
+        "{} is not a discovered DM8 ODBC driver",
+        path
+    );
+    load_library(path)
+}
+
+/// Applies the driver path saved in `store` to `driver`, when there is one and it is still a
+/// discovered driver. A saved path was chosen explicitly, so it overrides the one the desktop
+/// shell discovered.
+pub fn restore_driver_path(store: &ConfigStore, driver: &DriverOverride) {
+    match store.get_setting(DRIVER_PATH_SETTING) {
+        Ok(Some(path))
+            if discover_candidates()
+                .iter()
+                .any(|candidate| same_file(candidate, &path)) =>
+        {
+            tracing::info!("Using saved ODBC driver {}", path);
+            driver.set(path);
+        }
+        Ok(Some(path)) => {
+            tracing::warn!(
+                "Saved ODBC driver {} is no longer installed; ignoring it",
+                path
+            )
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to read saved ODBC driver path: {:#}", e),
+    }
+}
+
+/// The driver manager's entry for `driver`: by name for a registered driver, otherwise the
+/// entry whose library is the resolved file.
+fn registered_driver(driver: &DriverInfo) -> Option<odbc_api::DriverInfo> {
+    let environment = Environment::new().ok()?;
+    let drivers = environment.drivers().ok()?;
+    drivers.into_iter().find(|entry| match driver.source {
+        DriverSource::System => entry.description.eq_ignore_ascii_case(&driver.path),
+        DriverSource::Selected | DriverSource::Env | DriverSource::Bundled => entry
+            .attributes
+            .get("Driver")
+            .is_some_and(|library| same_file(library, &driver.path)),
+    })
+}
+
+fn same_file(a: &str, b: &str) -> bool {
+    match (Path::new(a).canonicalize(), Path::new(b).canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn load_library(path: &str) -> Result<()> {
+    // SAFETY: loading runs the library's initializers, which the driver manager runs anyway
+    // when it connects through this driver.
+    let library = unsafe { libloading::Library::new(path) }
+        .with_context(|| format!("Failed to load ODBC driver {}", path))?;
+    // SAFETY: the symbol is only looked up, never called.
+    let entry_point =
+        unsafe { library.get::<unsafe extern "C" fn()>(DRIVER_ENTRY_POINT.as_bytes()) };
+    entry_point.with_context(|| {
+        format!(
+            "{} does not export {}; it is not an ODBC driver",
+            path, DRIVER_ENTRY_POINT
+        )
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_library, resolve_driver, validate_driver_path, DriverOverride};
+    use crate::models::DriverSource;
+
+    #[test]
+    fn load_library_reports_missing_file() {
+        let err = load_library("/nonexistent/libdodbc.so").unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to load ODBC driver"));
+    }
+
+    #[test]
+    fn validate_driver_path_requires_a_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let err = validate_driver_path(dir.path().to_str().unwrap(), &[]).unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+    }
+
+    #[test]
+    fn validate_driver_path_accepts_only_discovered_drivers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let planted = dir.path().join("libdodbc.so");
+        std::fs::write(&planted, b"not a driver").unwrap();
+        let planted = planted.display().to_string();
+
+        let err = validate_driver_path(&planted, &[]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("is not a discovered DM8 ODBC driver"));
+        // A discovered file is still loaded before it is accepted.
+        let err = validate_driver_path(&planted, std::slice::from_ref(&planted)).unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to load ODBC driver"));
+    }
+
+    #[test]
+    fn chosen_driver_overrides_the_environment() {
+        let driver = DriverOverride::default();
+        driver.set("/opt/dm8/libdodbc.so".to_string());
+        let resolved = resolve_driver(&driver);
+        assert_eq!(resolved.path, "/opt/dm8/libdodbc.so");
+        assert_eq!(resolved.source, DriverSource::Selected);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn load_library_rejects_library_without_driver_entry_point() {
+        let err = load_library("libc.so.6").unwrap_err();
+        assert!(err.to_string().contains("it is not an ODBC driver"));
+    }
+}
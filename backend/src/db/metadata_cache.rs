@@ -0,0 +1,257 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use odbc_api::Connection;
+
+use crate::db::{
+    capabilities::ServerCapabilities,
+    schema::{get_table_details, get_tables_details},
+};
+use crate::models::{ConnectionConfig, TableDetails};
+
+/// How long cached table metadata is served when `METADATA_CACHE_TTL_SECS` is not set.
+pub const DEFAULT_METADATA_TTL: Duration = Duration::from_secs(300);
+
+/// Identifies one table's metadata: the server and login it was read with, plus the table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MetadataKey {
+    pub connection: String,
+    pub schema: String,
+    pub table: String,
+}
+
+/// Server and login a configuration connects with; different logins may see different
+/// catalogs, so they never share entries.
+pub fn connection_key(config: &ConnectionConfig) -> String {
+    let server = match config.dsn.as_deref().filter(|dsn| !dsn.trim().is_empty()) {
+        Some(dsn) => format!("dsn={}", dsn.trim()),
+        None => format!("{}:{}", config.host.trim(), config.port),
+    };
+    format!("{}@{}", config.username.trim().to_uppercase(), server)
+}
+
+/// Table metadata shared by all requests, so repeated previews and exports of the same tables
+/// do not re-run the catalog queries each time. Entries expire after the TTL and can be
+/// dropped early with `POST /api/cache/invalidate`.
+pub struct MetadataCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<MetadataKey, (Instant, TableDetails)>>,
+}
+
+impl Default for MetadataCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_METADATA_TTL)
+    }
+}
+
+impl MetadataCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A cache whose TTL comes from `METADATA_CACHE_TTL_SECS`; `0` disables caching.
+    pub fn from_env() -> Self {
+        let ttl = std::env::var("METADATA_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|secs| secs.trim().parse().ok())
+            .map_or(DEFAULT_METADATA_TTL, Duration::from_secs);
+        Self::new(ttl)
+    }
+
+    pub fn get(&self, key: &MetadataKey) -> Option<TableDetails> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key) {
+            Some((stored, details)) if stored.elapsed() < self.ttl => Some(details.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: MetadataKey, details: TableDetails) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(key, (Instant::now(), details));
+    }
+
+    /// Drops the entries matching every given filter (all entries when none is given) and
+    /// returns how many were dropped. Schema and table names compare case-insensitively.
+    pub fn invalidate(
+        &self,
+        connection: Option<&str>,
+        schema: Option<&str>,
+        tables: &[String],
+    ) -> usize {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let before = entries.len();
+        entries.retain(|key, _| {
+            let matches = connection.map_or(true, |c| key.connection == c)
+                && schema.map_or(true, |s| key.schema.eq_ignore_ascii_case(s))
+                && (tables.is_empty() || tables.iter().any(|t| key.table.eq_ignore_ascii_case(t)));
+            !matches
+        });
+        before - entries.len()
+    }
+
+    /// The cache as seen by one connection configuration.
+    pub fn for_connection(self: &Arc<Self>, config: &ConnectionConfig) -> ConnectionMetadata {
+        ConnectionMetadata {
+            cache: Arc::clone(self),
+            connection: connection_key(config),
+        }
+    }
+}
+
+/// A [`MetadataCache`] bound to the connection its entries are read with.
+#[derive(Clone)]
+pub struct ConnectionMetadata {
+    cache: Arc<MetadataCache>,
+    connection: String,
+}
+
+impl ConnectionMetadata {
+    /// Cached details of `schema.table`, reading and storing them on a miss.
+    pub fn table_details(
+        &self,
+        connection: &Connection<'_>,
+        capabilities: &ServerCapabilities,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableDetails> {
+        let key = MetadataKey {
+            connection: self.connection.clone(),
+            schema: schema.to_string(),
+            table: table.to_string(),
+        };
+        if let Some(details) = self.cache.get(&key) {
+            return Ok(details);
+        }
+        let details = get_table_details(connection, capabilities, schema, table)?;
+        self.cache.insert(key, details.clone());
+        Ok(details)
+    }
+
+    /// Cached details of several tables in the order given; all misses are read together with
+    /// one set of catalog queries.
+    pub fn tables_details(
+        &self,
+        connection: &Connection<'_>,
+        capabilities: &ServerCapabilities,
+        schema: &str,
+        tables: &[String],
+    ) -> Result<Vec<TableDetails>> {
+        let keys: Vec<MetadataKey> = tables
+            .iter()
+            .map(|table| MetadataKey {
+                connection: self.connection.clone(),
+                schema: schema.to_string(),
+                table: table.clone(),
+            })
+            .collect();
+        let mut cached: Vec<Option<TableDetails>> =
+            keys.iter().map(|key| self.cache.get(key)).collect();
+
+        let missing: Vec<String> = keys
+            .iter()
+            .zip(&cached)
+            .filter(|(_, details)| details.is_none())
+            .map(|(key, _)| key.table.clone())
+            .collect();
+        if !missing.is_empty() {
+            let loaded: HashMap<String, TableDetails> =
+                get_tables_details(connection, capabilities, schema, &missing)?
+                    .into_iter()
+                    .map(|details| (details.name.clone(), details))
+                    .collect();
+            for (key, slot) in keys.iter().zip(cached.iter_mut()) {
+                if slot.is_none() {
+                    if let Some(details) = loaded.get(&key.table) {
+                        self.cache.insert(key.clone(), details.clone());
+                        *slot = Some(details.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(cached.into_iter().flatten().collect())
+    }
+}
+
+/// Details of `schema.table`, through `metadata` when the caller has a cache.
+pub fn cached_table_details(
+    metadata: Option<&ConnectionMetadata>,
+    connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
+    schema: &str,
+    table: &str,
+) -> Result<TableDetails> {
+    match metadata {
+        Some(metadata) => metadata.table_details(connection, capabilities, schema, table),
+        None => get_table_details(connection, capabilities, schema, table),
+    }
+}
+
+/// Details of several tables, through `metadata` when the caller has a cache.
+pub fn cached_tables_details(
+    metadata: Option<&ConnectionMetadata>,
+    connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
+    schema: &str,
+    tables: &[String],
+) -> Result<Vec<TableDetails>> {
+    match metadata {
+        Some(metadata) => metadata.tables_details(connection, capabilities, schema, tables),
+        None => get_tables_details(connection, capabilities, schema, tables),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MetadataCache, MetadataKey};
+    use crate::models::TableDetails;
+    use std::time::Duration;
+
+    fn key(connection: &str, schema: &str, table: &str) -> MetadataKey {
+        MetadataKey {
+            connection: connection.to_string(),
+            schema: schema.to_string(),
+            table: table.to_string(),
+        }
+    }
+
+    fn details(name: &str) -> TableDetails {
+        TableDetails::named(name, Vec::new(), &[])
+    }
+
+    #[test]
+    fn entries_expire_and_can_be_invalidated() {
+        let cache = MetadataCache::new(Duration::from_secs(60));
+        cache.insert(key("A@h:1", "APP", "ORDERS"), details("ORDERS"));
+        cache.insert(key("A@h:1", "APP", "ITEMS"), details("ITEMS"));
+        cache.insert(key("B@h:1", "APP", "ORDERS"), details("ORDERS"));
+        assert!(cache.get(&key("A@h:1", "APP", "ORDERS")).is_some());
+
+        assert_eq!(
+            cache.invalidate(Some("A@h:1"), Some("app"), &["orders".to_string()]),
+            1
+        );
+        assert!(cache.get(&key("A@h:1", "APP", "ORDERS")).is_none());
+        assert!(cache.get(&key("B@h:1", "APP", "ORDERS")).is_some());
+        assert_eq!(cache.invalidate(None, None, &[]), 2);
+
+        let expired = MetadataCache::new(Duration::ZERO);
+        expired.insert(key("A@h:1", "APP", "ORDERS"), details("ORDERS"));
+        assert!(expired.get(&key("A@h:1", "APP", "ORDERS")).is_none());
+    }
+}
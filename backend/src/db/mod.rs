@@ -1,3 +1,11 @@
 pub mod connection;
 pub mod schema;
 pub mod dm8_adapter;
+pub mod capabilities;
+pub mod retry;
+pub mod read_only;
+pub mod metadata_cache;
+pub mod provider;
+pub mod backend;
+pub mod driver;
+pub mod diagnostics;
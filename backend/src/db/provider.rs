@@ -0,0 +1,260 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{anyhow, Context, Result};
+use odbc_api::Connection;
+
+use crate::db::{
+    capabilities::{ServerCapabilities, ServerInfo},
+    connection::ConnectionPool,
+    metadata_cache::MetadataCache,
+    schema::{catalog_name, fetch_referencing_tables, fetch_sequences, get_tables},
+};
+use crate::models::{ConnectionConfig, Sequence, Table, TableDetails, TableReference};
+
+/// Source of the schema metadata served by the HTTP API. [`OdbcSchemaProvider`] reads a DM8
+/// catalog; [`InMemorySchemaProvider`] serves fixed metadata, so the API can be exercised
+/// without a database and other metadata sources can be plugged in through `AppState`.
+pub trait SchemaProvider: Send + Sync {
+    /// Tables of `schema` ordered by name.
+    fn get_tables(&self, config: &ConnectionConfig, schema: &str) -> Result<Vec<Table>>;
+
+    fn get_table_details(
+        &self,
+        config: &ConnectionConfig,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableDetails>;
+
+    /// Details of several tables in the order given.
+    fn get_tables_details(
+        &self,
+        config: &ConnectionConfig,
+        schema: &str,
+        tables: &[String],
+    ) -> Result<Vec<TableDetails>> {
+        tables
+            .iter()
+            .map(|table| self.get_table_details(config, schema, table))
+            .collect()
+    }
+
+    /// Foreign keys in any schema that reference `schema.table`.
+    fn fetch_referencing_tables(
+        &self,
+        config: &ConnectionConfig,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableReference>>;
+
+    fn fetch_sequences(&self, config: &ConnectionConfig, schema: &str) -> Result<Vec<Sequence>>;
+
+    /// Version, character set and capabilities of the server behind `config`.
+    fn server_info(&self, config: &ConnectionConfig) -> Result<ServerInfo>;
+}
+
+/// Reads metadata from DM8 over ODBC, one connection per call, with table details going
+/// through the shared [`MetadataCache`].
+pub struct OdbcSchemaProvider {
+    metadata_cache: Arc<MetadataCache>,
+}
+
+impl OdbcSchemaProvider {
+    pub fn new(metadata_cache: Arc<MetadataCache>) -> Self {
+        Self { metadata_cache }
+    }
+
+    fn with_connection<T>(
+        config: &ConnectionConfig,
+        f: impl FnOnce(&Connection<'_>, &ServerCapabilities) -> Result<T>,
+    ) -> Result<T> {
+        let pool = ConnectionPool::new(config.clone()).context("Failed to create connection")?;
+        let connection = pool.get_connection().context("Failed to get connection")?;
+        let capabilities = pool.capabilities(&connection)?;
+        f(&connection, &capabilities)
+    }
+}
+
+impl SchemaProvider for OdbcSchemaProvider {
+    fn get_tables(&self, config: &ConnectionConfig, schema: &str) -> Result<Vec<Table>> {
+        Self::with_connection(config, |connection, _| get_tables(connection, schema))
+    }
+
+    fn get_table_details(
+        &self,
+        config: &ConnectionConfig,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableDetails> {
+        let metadata = self.metadata_cache.for_connection(config);
+        let (schema, table) = (catalog_name(schema, false), catalog_name(table, false));
+        Self::with_connection(config, |connection, capabilities| {
+            metadata.table_details(connection, capabilities, &schema, &table)
+        })
+    }
+
+    fn get_tables_details(
+        &self,
+        config: &ConnectionConfig,
+        schema: &str,
+        tables: &[String],
+    ) -> Result<Vec<TableDetails>> {
+        let metadata = self.metadata_cache.for_connection(config);
+        let schema = catalog_name(schema, false);
+        let tables: Vec<String> = tables.iter().map(|t| catalog_name(t, false)).collect();
+        Self::with_connection(config, |connection, capabilities| {
+            metadata.tables_details(connection, capabilities, &schema, &tables)
+        })
+    }
+
+    fn fetch_referencing_tables(
+        &self,
+        config: &ConnectionConfig,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableReference>> {
+        Self::with_connection(config, |connection, _| {
+            fetch_referencing_tables(connection, schema, table)
+        })
+    }
+
+    fn fetch_sequences(&self, config: &ConnectionConfig, schema: &str) -> Result<Vec<Sequence>> {
+        Self::with_connection(config, |connection, _| fetch_sequences(connection, schema))
+    }
+
+    fn server_info(&self, config: &ConnectionConfig) -> Result<ServerInfo> {
+        let pool = ConnectionPool::new(config.clone()).context("Failed to create connection")?;
+        let connection = pool.get_connection().context("Failed to get connection")?;
+        pool.server_info(&connection)
+    }
+}
+
+/// Fixed metadata held in memory; the connection configuration is ignored. Schema and table
+/// names compare case-insensitively, like unquoted DM8 identifiers.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySchemaProvider {
+    schemas: HashMap<String, InMemorySchema>,
+    server_info: Option<ServerInfo>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct InMemorySchema {
+    tables: Vec<TableDetails>,
+    sequences: Vec<Sequence>,
+}
+
+impl InMemorySchemaProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `table` to `schema`, replacing a table of the same name.
+    pub fn with_table(mut self, schema: &str, table: TableDetails) -> Self {
+        let tables = &mut self
+            .schemas
+            .entry(schema.to_uppercase())
+            .or_default()
+            .tables;
+        tables.retain(|existing| !existing.name.eq_ignore_ascii_case(&table.name));
+        tables.push(table);
+        self
+    }
+
+    pub fn with_sequence(mut self, schema: &str, sequence: Sequence) -> Self {
+        self.schemas
+            .entry(schema.to_uppercase())
+            .or_default()
+            .sequences
+            .push(sequence);
+        self
+    }
+
+    pub fn with_server_info(mut self, info: ServerInfo) -> Self {
+        self.server_info = Some(info);
+        self
+    }
+
+    fn schema(&self, schema: &str) -> Option<&InMemorySchema> {
+        self.schemas.get(&schema.to_uppercase())
+    }
+}
+
+impl SchemaProvider for InMemorySchemaProvider {
+    fn get_tables(&self, _config: &ConnectionConfig, schema: &str) -> Result<Vec<Table>> {
+        let mut tables: Vec<Table> = self
+            .schema(schema)
+            .map(|schema| schema.tables.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|table| Table {
+                name: table.name.clone(),
+                comment: table.comment.clone(),
+                row_count: None,
+            })
+            .collect();
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tables)
+    }
+
+    fn get_table_details(
+        &self,
+        _config: &ConnectionConfig,
+        schema: &str,
+        table: &str,
+    ) -> Result<TableDetails> {
+        self.schema(schema)
+            .and_then(|found| {
+                found
+                    .tables
+                    .iter()
+                    .find(|details| details.name.eq_ignore_ascii_case(table))
+            })
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Table '{}' does not exist in schema '{}'",
+                    table.to_uppercase(),
+                    schema.to_uppercase()
+                )
+            })
+    }
+
+    fn fetch_referencing_tables(
+        &self,
+        _config: &ConnectionConfig,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<TableReference>> {
+        let target = format!("{}.{}", schema, table).to_uppercase();
+        let mut references = Vec::new();
+        for (owner, found) in &self.schemas {
+            for details in &found.tables {
+                for fk in &details.foreign_keys {
+                    if fk.referenced_table.eq_ignore_ascii_case(&target) {
+                        references.push(TableReference {
+                            schema: owner.clone(),
+                            table: details.name.clone(),
+                            constraint: fk.name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        references.sort_by(|a, b| {
+            (&a.schema, &a.table, &a.constraint).cmp(&(&b.schema, &b.table, &b.constraint))
+        });
+        Ok(references)
+    }
+
+    fn fetch_sequences(&self, _config: &ConnectionConfig, schema: &str) -> Result<Vec<Sequence>> {
+        Ok(self
+            .schema(schema)
+            .map(|schema| schema.sequences.clone())
+            .unwrap_or_default())
+    }
+
+    fn server_info(&self, _config: &ConnectionConfig) -> Result<ServerInfo> {
+        self.server_info
+            .clone()
+            .ok_or_else(|| anyhow!("No server information in the in-memory provider"))
+    }
+}
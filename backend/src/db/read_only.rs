@@ -0,0 +1,121 @@
+use anyhow::{ensure, Result};
+use odbc_api::{Connection, Cursor};
+
+/// Marks the session read-only on the server; DM8 then rejects DML and DDL itself.
+pub const SESSION_READ_ONLY_SQL: &str = "SP_SET_SESSION_READONLY(1)";
+
+/// Runs a query against the source database after checking it cannot modify anything.
+///
+/// Every catalog and data read goes through here, so a bug that builds a write statement fails
+/// before it reaches the server.
+pub fn execute_read<'c>(
+    connection: &'c Connection<'_>,
+    sql: &str,
+) -> Result<Option<impl Cursor + 'c>> {
+    ensure_read_only(sql)?;
+    Ok(connection.execute(sql, ())?)
+}
+
+/// Accepts a single `SELECT` (or `WITH ... SELECT`) statement without `FOR UPDATE`.
+pub fn ensure_read_only(sql: &str) -> Result<()> {
+    let code = code_outside_literals(sql).to_uppercase();
+    let code = code.trim().trim_end_matches(';');
+    ensure!(
+        !code.contains(';'),
+        "Read-only guard rejected a statement batch: {}",
+        preview(sql)
+    );
+
+    let words: Vec<&str> = code
+        .split(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '$' | '#')))
+        .filter(|word| !word.is_empty())
+        .collect();
+    ensure!(
+        matches!(words.first(), Some(&"SELECT") | Some(&"WITH")),
+        "Read-only guard rejected a statement that is not a query: {}",
+        preview(sql)
+    );
+    ensure!(
+        !words.windows(2).any(|pair| pair == ["FOR", "UPDATE"]),
+        "Read-only guard rejected a locking query (FOR UPDATE): {}",
+        preview(sql)
+    );
+    Ok(())
+}
+
+/// The statement with string literals, quoted identifiers and comments blanked out, so their
+/// contents cannot be mistaken for keywords.
+fn code_outside_literals(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                // A doubled quote inside the literal ends it and immediately reopens it.
+                for next in chars.by_ref() {
+                    if next == c {
+                        break;
+                    }
+                }
+                out.push(' ');
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+                out.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn preview(sql: &str) -> String {
+    let flat = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    match flat.char_indices().nth(120) {
+        Some((index, _)) => format!("{}...", &flat[..index]),
+        None => flat,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ensure_read_only;
+
+    #[test]
+    fn accepts_single_queries() {
+        ensure_read_only("SELECT COUNT(*) FROM \"APP\".\"ORDERS\"").unwrap();
+        ensure_read_only("  with t as (select 1 from dual) select * from t;").unwrap();
+        ensure_read_only("(SELECT 1 FROM DUAL)").unwrap();
+        ensure_read_only("SELECT 'DELETE FROM x; FOR UPDATE' FROM DUAL").unwrap();
+        ensure_read_only("-- DROP TABLE x\nSELECT /* ; */ 1 FROM DUAL").unwrap();
+    }
+
+    #[test]
+    fn rejects_writes_batches_and_locks() {
+        for sql in [
+            "DELETE FROM APP.ORDERS",
+            "update t set a = 1",
+            "/* SELECT */ TRUNCATE TABLE t",
+            "SELECT 1 FROM DUAL; DROP TABLE t",
+            "SELECT * FROM t FOR UPDATE",
+            "SP_SET_SESSION_READONLY(0)",
+        ] {
+            assert!(ensure_read_only(sql).is_err(), "{}", sql);
+        }
+    }
+}
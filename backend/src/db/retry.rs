@@ -0,0 +1,240 @@
+use std::{
+    fmt, thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use odbc_api::Connection;
+
+use crate::db::{backend::DatabaseBackend, connection::ConnectionPool};
+
+/// How often and how patiently an operation is retried after a transient ODBC error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including the first one; `1` disables retries.
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for every further retry.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1000),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before retry number `retry` (1-based).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// SQLSTATE classes and driver messages that indicate a dropped link or timeout rather than a
+/// problem with the statement itself.
+const TRANSIENT_MARKERS: &[&str] = &[
+    "State: 08",
+    "State: HYT00",
+    "State: HYT01",
+    "State: 40001",
+    "State: 40003",
+    "Communication link failure",
+    "Connection reset",
+    "connection reset",
+    "Broken pipe",
+    "broken pipe",
+    "timed out",
+    "网络通信异常",
+    "通信异常",
+];
+
+/// Whether retrying `err` on a fresh connection may succeed.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    if err.downcast_ref::<PartialOutput>().is_some() {
+        return false;
+    }
+    err.chain().any(|cause| {
+        let message = cause.to_string();
+        TRANSIENT_MARKERS
+            .iter()
+            .any(|marker| message.contains(marker))
+    })
+}
+
+/// Context for a failure after part of a result was already written; repeating the operation
+/// would duplicate that output, so it is never retried.
+#[derive(Debug)]
+pub struct PartialOutput;
+
+impl fmt::Display for PartialOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed after rows were already written; not retrying")
+    }
+}
+
+/// Query sent on a connection that sat idle for longer than the keepalive interval.
+const HEARTBEAT_QUERY: &str = "SELECT 1";
+
+/// Whether a connection idle for `idle` must answer a heartbeat before it is used again.
+pub fn heartbeat_due(idle: Duration, keepalive: Option<Duration>) -> bool {
+    keepalive.is_some_and(|interval| idle >= interval)
+}
+
+/// A pooled connection that is re-opened when an operation fails with a transient error.
+///
+/// With a keepalive interval, a connection that sat idle for longer (e.g. while a large DDL
+/// script is rendered) gets a heartbeat before its next use; when the server has closed the
+/// idle session in the meantime, a new connection is opened and the export continues on it.
+pub struct RetryingConnection<'p> {
+    pool: &'p ConnectionPool,
+    connection: Connection<'p>,
+    policy: RetryPolicy,
+    keepalive: Option<Duration>,
+    last_used: Instant,
+}
+
+impl<'p> RetryingConnection<'p> {
+    pub fn new(pool: &'p ConnectionPool, connection: Connection<'p>, policy: RetryPolicy) -> Self {
+        Self {
+            pool,
+            connection,
+            policy,
+            keepalive: None,
+            last_used: Instant::now(),
+        }
+    }
+
+    /// Sends a heartbeat before using a connection that was idle for `interval` or longer.
+    pub fn with_keepalive(mut self, interval: Option<Duration>) -> Self {
+        self.keepalive = interval;
+        self
+    }
+
+    /// The database implementation behind the connection.
+    pub fn backend(&self) -> &'static dyn DatabaseBackend {
+        self.pool.backend()
+    }
+
+    pub fn connection(&self) -> &Connection<'p> {
+        &self.connection
+    }
+
+    /// Runs `op`, retrying it with exponential backoff on a re-opened connection while it fails
+    /// with a transient error. `on_retry` is told about every failed attempt that is retried.
+    pub fn run<T>(
+        &mut self,
+        what: &str,
+        on_retry: &mut dyn FnMut(String),
+        mut op: impl FnMut(&Connection<'p>) -> Result<T>,
+    ) -> Result<T> {
+        self.keep_alive(on_retry)?;
+        let mut attempt = 1;
+        let mut reconnect = false;
+        loop {
+            let result = match if reconnect { self.reopen() } else { Ok(()) } {
+                Ok(()) => op(&self.connection),
+                Err(e) => Err(e),
+            };
+            self.last_used = Instant::now();
+            match result {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.policy.max_attempts && is_transient(&e) => {
+                    let delay = self.policy.backoff(attempt);
+                    on_retry(format!(
+                        "{} failed (attempt {} of {}), retrying in {} ms on a new connection: {:#}",
+                        what,
+                        attempt,
+                        self.policy.max_attempts,
+                        delay.as_millis(),
+                        e
+                    ));
+                    thread::sleep(delay);
+                    attempt += 1;
+                    reconnect = true;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Pings an idle connection and replaces it when the server no longer answers.
+    fn keep_alive(&mut self, on_retry: &mut dyn FnMut(String)) -> Result<()> {
+        let idle = self.last_used.elapsed();
+        if !heartbeat_due(idle, self.keepalive) {
+            return Ok(());
+        }
+        if let Err(e) = self
+            .pool
+            .execute_with_timeout(&self.connection, HEARTBEAT_QUERY)
+        {
+            on_retry(format!(
+                "Connection idle for {} s did not answer the heartbeat, reconnecting: {:#}",
+                idle.as_secs(),
+                e
+            ));
+            self.reopen()?;
+        }
+        self.last_used = Instant::now();
+        Ok(())
+    }
+
+    fn reopen(&mut self) -> Result<()> {
+        self.connection = self.pool.get_connection()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{heartbeat_due, is_transient, PartialOutput, RetryPolicy};
+    use std::time::Duration;
+
+    #[test]
+    fn classifies_link_failures_and_timeouts_as_transient() {
+        let link = anyhow::anyhow!(
+            "ODBC emitted an error calling 'SQLFetch':\nState: 08S01, Native error: -70019, \
+             Message: Communication link failure"
+        )
+        .context("Failed to export data for table 'ORDERS'");
+        assert!(is_transient(&link));
+        assert!(is_transient(&anyhow::anyhow!(
+            "State: HYT00, query timed out"
+        )));
+
+        let syntax = anyhow::anyhow!("State: 42000, Native error: -2007, Message: syntax error");
+        assert!(!is_transient(&syntax));
+
+        let partial = link.context(PartialOutput);
+        assert!(!is_transient(&partial));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_limit() {
+        let policy = RetryPolicy {
+            max_attempts: 6,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(3),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(3), Duration::from_millis(2000));
+        assert_eq!(policy.backoff(4), Duration::from_secs(3));
+        assert_eq!(policy.backoff(40), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn heartbeat_only_after_the_keepalive_interval() {
+        let interval = Some(Duration::from_secs(60));
+        assert!(!heartbeat_due(Duration::from_secs(59), interval));
+        assert!(heartbeat_due(Duration::from_secs(60), interval));
+        assert!(!heartbeat_due(Duration::from_secs(3600), None));
+    }
+}
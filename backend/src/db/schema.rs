@@ -1,12 +1,14 @@
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU8, Ordering};
 
-use anyhow::{anyhow, ensure, Context, Result};
+use anyhow::{anyhow, Context, Result};
 use odbc_api::{Connection, Cursor, buffers::TextRowSet};
 
+use crate::db::capabilities::ServerCapabilities;
+use crate::db::read_only::execute_read;
+use crate::export::geometry::is_geometry_type;
 use crate::models::{
-    CheckConstraint, Column, ForeignKey, Index, Sequence, Table, TableDetails, TriggerDefinition,
-    UniqueConstraint,
+    CheckConstraint, Column, ForeignKey, IdentityGeneration, Index, IndexColumn, ObjectGrant, Sequence, Table,
+    TableDetails, TableReference, TriggerDefinition, UniqueConstraint, ViewDefinition,
 };
 
 pub fn get_tables(connection: &Connection<'_>, schema: &str) -> Result<Vec<Table>> {
@@ -21,8 +23,7 @@ pub fn get_tables(connection: &Connection<'_>, schema: &str) -> Result<Vec<Table
         owner.replace("'", "''")
     );
 
-    let mut cursor = connection
-        .execute(&sql, ())
+    let mut cursor = execute_read(connection, &sql)
         .context("Failed to query DM8 tables")?
         .ok_or_else(|| anyhow!("DM8 returned no cursor for tables query"))?;
 
@@ -59,82 +60,166 @@ pub fn get_tables(connection: &Connection<'_>, schema: &str) -> Result<Vec<Table
     Ok(tables)
 }
 
+/// Tables per catalog query when details of many tables are read at once; longer lists are
+/// split so the statements stay a reasonable size.
+const TABLES_PER_QUERY: usize = 500;
+
+/// Filter on a table name column: `column = 'T'` for one table, `column IN ('A', 'B')` for more.
+fn table_filter(column: &str, tables: &[String]) -> String {
+    let quoted: Vec<String> = tables
+        .iter()
+        .map(|table| format!("'{}'", table.replace("'", "''")))
+        .collect();
+    match quoted.as_slice() {
+        [single] => format!("{} = {}", column, single),
+        _ => format!("{} IN ({})", column, quoted.join(", ")),
+    }
+}
+
+/// Catalog spelling of a schema or table name from a request. DM8 folds unquoted identifiers
+/// to upper case; with `preserve_case` names created quoted in mixed or lower case are looked up
+/// exactly as given.
+pub fn catalog_name(name: &str, preserve_case: bool) -> String {
+    if preserve_case {
+        name.to_string()
+    } else {
+        name.to_uppercase()
+    }
+}
+
 pub fn get_table_details(
     connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
     schema: &str,
     table: &str,
 ) -> Result<TableDetails> {
-    let owner = schema.to_uppercase();
-    let table_name = table.to_uppercase();
+    get_tables_details(connection, capabilities, schema, &[table.to_string()])?
+        .pop()
+        .ok_or_else(|| anyhow!("No details returned for table {}", table))
+}
 
-    let comment = fetch_table_comment(connection, &owner, &table_name)?;
+/// Details of several tables, in the order of `tables`. Columns, keys, indexes, constraints and
+/// triggers are each read with one set-based query for the whole table set (per
+/// `TABLES_PER_QUERY` tables) and assembled in memory, instead of about eight queries per table.
+/// Names are matched exactly; callers fold them with [`catalog_name`].
+pub fn get_tables_details(
+    connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
+    owner: &str,
+    names: &[String],
+) -> Result<Vec<TableDetails>> {
+    let mut details = Vec::with_capacity(names.len());
+    for chunk in names.chunks(TABLES_PER_QUERY) {
+        let loaded = fetch_details_chunk(connection, capabilities, owner, chunk)?;
+        for name in chunk {
+            let table = loaded.get(name).cloned().ok_or_else(|| {
+                anyhow!("Table '{}' does not exist in schema '{}'", name, owner)
+            })?;
+            details.push(table);
+        }
+    }
+    Ok(details)
+}
 
-    let columns = fetch_columns(connection, &owner, &table_name)
-        .with_context(|| format!("Failed to fetch columns for table {}", table_name))?;
-    ensure!(
-        !columns.is_empty(),
-        "Table '{}' does not exist in schema '{}'",
-        table_name,
-        owner
-    );
+fn fetch_details_chunk(
+    connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
+    owner: &str,
+    tables: &[String],
+) -> Result<HashMap<String, TableDetails>> {
+    let mut columns = fetch_columns(connection, capabilities, owner, tables)
+        .context("Failed to fetch column metadata")?;
+    let mut storage = fetch_table_storage(connection, capabilities, owner, tables)?;
+    let mut primary_keys = fetch_primary_keys(connection, owner, tables)?;
+    let mut indexes = fetch_indexes(connection, capabilities, owner, tables)?;
+    let mut unique_constraints = fetch_unique_constraints(connection, owner, tables)?;
+    let mut foreign_keys = fetch_foreign_keys(connection, capabilities, owner, tables)?;
+    let mut check_constraints = fetch_check_constraints(connection, owner, tables)?;
+    let mut triggers = fetch_triggers(connection, capabilities, owner, tables)?;
+
+    let mut details = HashMap::new();
+    for name in tables {
+        let Some(table_columns) = columns.remove(name).filter(|c| !c.is_empty()) else {
+            continue;
+        };
+        let (comment, tablespace, default_collation) =
+            storage.remove(name).unwrap_or_default();
+        details.insert(
+            name.clone(),
+            TableDetails {
+                name: name.clone(),
+                comment,
+                columns: table_columns,
+                primary_keys: primary_keys.remove(name).unwrap_or_default(),
+                indexes: indexes.remove(name).unwrap_or_default(),
+                unique_constraints: unique_constraints.remove(name).unwrap_or_default(),
+                foreign_keys: foreign_keys.remove(name).unwrap_or_default(),
+                check_constraints: check_constraints.remove(name).unwrap_or_default(),
+                triggers: triggers.remove(name).unwrap_or_default(),
+                tablespace,
+                default_collation,
+            },
+        );
+    }
+    Ok(details)
+}
+
+/// Comment, tablespace and default collation of a table.
+type TableStorage = (Option<String>, Option<String>, Option<String>);
 
-    let primary_keys = fetch_primary_keys(connection, &owner, &table_name)?;
-    let indexes = fetch_indexes(connection, &owner, &table_name)?;
-    let unique_constraints = fetch_unique_constraints(connection, &owner, &table_name)?;
-    let foreign_keys = fetch_foreign_keys(connection, &owner, &table_name)?;
-    let check_constraints = fetch_check_constraints(connection, &owner, &table_name)?;
-    let triggers = fetch_triggers(connection, &owner, &table_name)?;
-
-    Ok(TableDetails {
-        name: table_name,
-        comment,
-        columns,
-        primary_keys,
-        indexes,
-        unique_constraints,
-        foreign_keys,
-        check_constraints,
-        triggers,
-    })
-}
-
-fn fetch_table_comment(
+/// [`TableStorage`] of every table from `ALL_TABLES`.
+fn fetch_table_storage(
     connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
     schema: &str,
-    table: &str,
-) -> Result<Option<String>> {
+    tables: &[String],
+) -> Result<HashMap<String, TableStorage>> {
     let sql = format!(
-        "SELECT COMMENTS FROM ALL_TAB_COMMENTS WHERE OWNER = '{}' AND TABLE_NAME = '{}'",
+        "SELECT t.TABLE_NAME, c.COMMENTS, t.TABLESPACE_NAME, {} \
+         FROM ALL_TABLES t \
+         LEFT JOIN ALL_TAB_COMMENTS c ON c.OWNER = t.OWNER AND c.TABLE_NAME = t.TABLE_NAME \
+         WHERE t.OWNER = '{}' AND {}",
+        ServerCapabilities::column(capabilities.table_default_collation, "t.DEFAULT_COLLATION"),
         schema.replace("'", "''"),
-        table.replace("'", "''")
+        table_filter("t.TABLE_NAME", tables)
     );
 
-    let mut cursor = match connection
-        .execute(&sql, ())
-        .context("Failed to query table comment")?
+    let mut cursor = match execute_read(connection, &sql)
+        .context("Failed to query table storage attributes")?
     {
         Some(cursor) => cursor,
-        None => return Ok(None),
+        None => return Ok(HashMap::new()),
     };
 
-    let mut buffers = TextRowSet::for_cursor(1, &mut cursor, Some(8192))?;
+    let mut buffers = TextRowSet::for_cursor(200, &mut cursor, Some(8192))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    if let Some(batch) = row_set_cursor.fetch()? {
-        if batch.num_rows() > 0 {
-            let comment = batch.at_as_str(0, 0)?.map(|s| s.to_string());
-            return Ok(comment);
+    let mut storage = HashMap::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            let Some(table) = batch.at_as_str(0, row_index)? else {
+                continue;
+            };
+            let comment = batch.at_as_str(1, row_index)?.map(|s| s.to_string());
+            let tablespace = batch
+                .at_as_str(2, row_index)?
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string());
+            let default_collation = explicit_collation(batch.at_as_str(3, row_index)?);
+            storage.insert(table.to_string(), (comment, tablespace, default_collation));
         }
     }
 
-    Ok(None)
+    Ok(storage)
 }
 
 fn fetch_columns(
     connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
     schema: &str,
-    table: &str,
-) -> Result<Vec<Column>> {
+    tables: &[String],
+) -> Result<HashMap<String, Vec<Column>>> {
     // DM8 stores identity column info in SYS.SYSCOLUMNS.INFO2 field
     // When INFO2 & 0x01 = 0x01, the column is an identity column
     // Use IDENT_SEED() and IDENT_INCR() functions to get seed and increment values
@@ -154,28 +239,29 @@ fn fetch_columns(
                 c.DATA_PRECISION, c.DATA_SCALE, c.CHAR_USED, \
                 c.NULLABLE, c.DATA_DEFAULT, \
                 CASE WHEN sc.INFO2 & 1 = 1 THEN 'YES' ELSE 'NO' END AS IDENTITY_COLUMN, \
-                cc.COMMENTS \
+                cc.COMMENTS, {}, c.TABLE_NAME \
          FROM ALL_TAB_COLUMNS c \
          LEFT JOIN ALL_COL_COMMENTS cc ON cc.OWNER = c.OWNER AND cc.TABLE_NAME = c.TABLE_NAME AND cc.COLUMN_NAME = c.COLUMN_NAME \
          LEFT JOIN SYS.SYSOBJECTS sch ON sch.NAME = c.OWNER AND sch.TYPE$ = 'SCH' \
          LEFT JOIN SYS.SYSOBJECTS so ON so.NAME = c.TABLE_NAME AND so.SCHID = sch.ID AND so.TYPE$ = 'SCHOBJ' \
          LEFT JOIN SYS.SYSCOLUMNS sc ON sc.ID = so.ID AND sc.NAME = c.COLUMN_NAME \
-         WHERE c.OWNER = '{}' AND c.TABLE_NAME = '{}' \
-         ORDER BY c.COLUMN_ID",
+         WHERE c.OWNER = '{}' AND {} \
+         ORDER BY c.TABLE_NAME, c.COLUMN_ID",
+        ServerCapabilities::column(capabilities.column_collation, "c.COLLATION"),
         schema.replace("'", "''"),
-        table.replace("'", "''")
+        table_filter("c.TABLE_NAME", tables)
     );
 
-    let mut cursor = match connection.execute(&sql, ()).context("Failed to query DM8 columns")? {
+    let mut cursor = match execute_read(connection, &sql).context("Failed to query DM8 columns")? {
         Some(cursor) => cursor,
-        None => return Ok(vec![]),
+        None => return Ok(HashMap::new()),
     };
 
     let mut buffers = TextRowSet::for_cursor(100, &mut cursor, Some(8192))
         .context("Failed to prepare column buffer")?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    let mut columns = Vec::new();
+    let mut columns: HashMap<String, Vec<Column>> = HashMap::new();
 
     while let Some(batch) = row_set_cursor.fetch().context("Failed to fetch column metadata")? {
         for row_index in 0..batch.num_rows() {
@@ -194,10 +280,14 @@ fn fetch_columns(
             let default_value = batch.at_as_str(7, row_index)?.map(|s| s.to_string());
             let identity_flag = batch.at_as_str(8, row_index)?;
             let comment = batch.at_as_str(9, row_index)?.map(|s| s.to_string());
+            let collation = explicit_collation(batch.at_as_str(10, row_index)?);
+            let table = batch.at_as_str(11, row_index)?
+                .ok_or_else(|| anyhow!("Encountered column without a table"))?
+                .to_string();
             let nullable = matches!(nullable_flag, Some(flag) if flag.eq_ignore_ascii_case("Y"));
             let identity = matches!(identity_flag, Some(flag) if flag.eq_ignore_ascii_case("YES") || flag.eq_ignore_ascii_case("Y"));
 
-            columns.push(Column {
+            columns.entry(table).or_default().push(Column {
                 name,
                 data_type,
                 length,
@@ -210,19 +300,98 @@ fn fetch_columns(
                 identity,
                 identity_start: None,
                 identity_increment: None,
+                identity_generation: None,
+                identity_current: None,
+                virtual_expression: None,
+                geometry_srid: None,
+                collation,
             });
         }
     }
 
     // Fetch identity seed and increment for tables with identity columns
     // Note: DM8 allows only ONE identity column per table, so we only update the first one found
-    let has_identity = columns.iter().any(|c| c.identity);
-    if has_identity {
-        if let Ok(Some((seed, incr))) = fetch_identity_info(connection, schema, table) {
-            // Only update the first identity column (DM8 constraint: one per table)
-            if let Some(col) = columns.iter_mut().find(|c| c.identity) {
-                col.identity_start = Some(seed);
-                col.identity_increment = Some(incr);
+    let identity_tables: Vec<String> = tables
+        .iter()
+        .filter(|table| {
+            columns
+                .get(*table)
+                .is_some_and(|cols| cols.iter().any(|c| c.identity))
+        })
+        .cloned()
+        .collect();
+    if !identity_tables.is_empty() {
+        if let Ok(identities) = fetch_identity_info(connection, schema, &identity_tables) {
+            for (table, (seed, incr)) in identities {
+                // Only update the first identity column (DM8 constraint: one per table)
+                if let Some(col) = columns
+                    .get_mut(&table)
+                    .and_then(|cols| cols.iter_mut().find(|c| c.identity))
+                {
+                    col.identity_start = Some(seed);
+                    col.identity_increment = Some(incr);
+                }
+            }
+        }
+
+        // Both are optional details: older servers lack the view or the function, and the
+        // column is still exported as a native IDENTITY without them.
+        if capabilities.identity_generation {
+            match fetch_identity_generation(connection, schema, &identity_tables) {
+                Ok(modes) => {
+                    for ((table, column), mode) in modes {
+                        if let Some(col) = columns
+                            .get_mut(&table)
+                            .and_then(|cols| cols.iter_mut().find(|c| c.name == column))
+                        {
+                            col.identity_generation = Some(mode);
+                        }
+                    }
+                }
+                Err(e) => tracing::debug!("Identity generation modes unavailable: {:#}", e),
+            }
+        }
+        match fetch_identity_current(connection, schema, &identity_tables) {
+            Ok(currents) => {
+                for (table, current) in currents {
+                    if let Some(col) = columns
+                        .get_mut(&table)
+                        .and_then(|cols| cols.iter_mut().find(|c| c.identity))
+                    {
+                        col.identity_current = Some(current);
+                    }
+                }
+            }
+            Err(e) => tracing::debug!("Identity high-water values unavailable: {:#}", e),
+        }
+    }
+
+    // The catalog reports a virtual column's expression as its DATA_DEFAULT.
+    if capabilities.virtual_columns {
+        match fetch_virtual_columns(connection, schema, tables) {
+            Ok(virtual_columns) => {
+                for (table, column) in virtual_columns {
+                    if let Some(col) = columns
+                        .get_mut(&table)
+                        .and_then(|cols| cols.iter_mut().find(|c| c.name == column))
+                    {
+                        col.virtual_expression = col.default_value.take();
+                    }
+                }
+            }
+            Err(e) => tracing::debug!("Virtual columns unavailable: {:#}", e),
+        }
+    }
+
+    // DM8 geometry types carry no SRID, so it is taken from a stored value.
+    for (table, table_columns) in columns.iter_mut() {
+        for col in table_columns
+            .iter_mut()
+            .filter(|c| is_geometry_type(&c.data_type))
+        {
+            match fetch_geometry_srid(connection, schema, table, &col.name) {
+                Ok(srid) => col.geometry_srid = srid,
+                Err(e) => tracing::debug!("SRID of {}.{} unavailable: {:#}", table, col.name, e),
             }
         }
     }
@@ -230,100 +399,327 @@ fn fetch_columns(
     Ok(columns)
 }
 
-fn fetch_identity_info(
+/// SRID of the first non-NULL value of a geometry column; `None` when it holds none.
+fn fetch_geometry_srid(
     connection: &Connection<'_>,
     schema: &str,
     table: &str,
-) -> Result<Option<(i64, i64)>> {
-    // Use IDENT_SEED and IDENT_INCR functions to get identity column properties
-    // DM8 accepts table name in format: 'SCHEMA.TABLE' or '"SCHEMA"."TABLE"'
+    column: &str,
+) -> Result<Option<i32>> {
+    let column = column.replace('"', "\"\"");
     let sql = format!(
-        "SELECT IDENT_SEED('{}.{}'), IDENT_INCR('{}.{}') FROM DUAL",
-        schema.replace("'", "''"),
-        table.replace("'", "''"),
-        schema.replace("'", "''"),
-        table.replace("'", "''")
+        "SELECT DMGEO.ST_SRID(\"{0}\") FROM \"{1}\".\"{2}\" WHERE \"{0}\" IS NOT NULL LIMIT 1",
+        column,
+        schema.replace('"', "\"\""),
+        table.replace('"', "\"\"")
     );
 
-    let mut cursor = match connection.execute(&sql, ()).context("Failed to query identity info")? {
-        Some(cursor) => cursor,
-        None => return Ok(None),
-    };
+    let mut cursor = execute_read(connection, &sql)
+        .with_context(|| format!("Failed to read SRID of {}.{}", table, column))?
+        .ok_or_else(|| anyhow!("DM8 returned no cursor for SRID query"))?;
 
-    let mut buffers = TextRowSet::for_cursor(1, &mut cursor, Some(64))?;
+    let mut buffers = TextRowSet::for_cursor(1, &mut cursor, Some(32))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
     if let Some(batch) = row_set_cursor.fetch()? {
         if batch.num_rows() > 0 {
-            let seed = batch.at_as_str(0, 0)?.and_then(|s| s.parse::<i64>().ok());
-            let incr = batch.at_as_str(1, 0)?.and_then(|s| s.parse::<i64>().ok());
-            if let (Some(seed), Some(incr)) = (seed, incr) {
-                return Ok(Some((seed, incr)));
-            }
+            return Ok(batch
+                .at_as_str(0, 0)?
+                .and_then(|srid| srid.trim().parse::<i32>().ok()));
         }
     }
 
     Ok(None)
 }
 
-const TRIGGER_LEVEL_FULL: u8 = 0;
-const TRIGGER_LEVEL_NO_TYPE: u8 = 1;
-const TRIGGER_LEVEL_NO_WHEN: u8 = 2;
+/// `(table, column)` of every virtual column of the tables.
+fn fetch_virtual_columns(
+    connection: &Connection<'_>,
+    schema: &str,
+    tables: &[String],
+) -> Result<Vec<(String, String)>> {
+    let sql = format!(
+        "SELECT TABLE_NAME, COLUMN_NAME \
+         FROM ALL_TAB_COLS \
+         WHERE OWNER = '{}' AND VIRTUAL_COLUMN = 'YES' AND {}",
+        schema.replace("'", "''"),
+        table_filter("TABLE_NAME", tables)
+    );
+    let mut cursor =
+        match execute_read(connection, &sql).context("Failed to query virtual columns")? {
+            Some(cursor) => cursor,
+            None => return Ok(Vec::new()),
+        };
+
+    let mut buffers = TextRowSet::for_cursor(100, &mut cursor, Some(256))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    let mut virtual_columns = Vec::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            let table = batch.at_as_str(0, row_index)?;
+            let column = batch.at_as_str(1, row_index)?;
+            if let (Some(table), Some(column)) = (table, column) {
+                virtual_columns.push((table.to_string(), column.to_string()));
+            }
+        }
+    }
+
+    Ok(virtual_columns)
+}
+
+fn fetch_identity_generation(
+    connection: &Connection<'_>,
+    schema: &str,
+    tables: &[String],
+) -> Result<HashMap<(String, String), IdentityGeneration>> {
+    let sql = format!(
+        "SELECT TABLE_NAME, COLUMN_NAME, GENERATION_TYPE \
+         FROM ALL_TAB_IDENTITY_COLS \
+         WHERE OWNER = '{}' AND {}",
+        schema.replace("'", "''"),
+        table_filter("TABLE_NAME", tables)
+    );
+    let mut cursor = match execute_read(connection, &sql)
+        .context("Failed to query identity generation modes")?
+    {
+        Some(cursor) => cursor,
+        None => return Ok(HashMap::new()),
+    };
+
+    let mut buffers = TextRowSet::for_cursor(100, &mut cursor, Some(256))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    let mut modes = HashMap::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            let table = batch.at_as_str(0, row_index)?;
+            let column = batch.at_as_str(1, row_index)?;
+            let mode = batch
+                .at_as_str(2, row_index)?
+                .and_then(IdentityGeneration::from_catalog);
+            if let (Some(table), Some(column), Some(mode)) = (table, column, mode) {
+                modes.insert((table.to_string(), column.to_string()), mode);
+            }
+        }
+    }
 
-fn is_trigger_metadata_missing(err: &anyhow::Error) -> bool {
-    err.chain().any(|cause| {
-        let message = cause.to_string().to_uppercase();
-        message.contains("TRIGGER_TYPE")
-            || message.contains("WHEN_CLAUSE")
-            || message.contains("TRIGGER_BODY")
-            || message.contains("DESCRIPTION")
-            || message.contains("42S22")
-    })
+    Ok(modes)
 }
 
-fn trigger_missing_column(err: &anyhow::Error) -> Option<&'static str> {
-    for cause in err.chain() {
-        let message = cause.to_string().to_uppercase();
-        if message.contains("TRIGGER_TYPE") {
-            return Some("TRIGGER_TYPE");
+/// Last value handed out by each table's identity (`IDENT_CURRENT`); tables whose identity
+/// never generated a value are missing from the result.
+fn fetch_identity_current(
+    connection: &Connection<'_>,
+    schema: &str,
+    tables: &[String],
+) -> Result<HashMap<String, i64>> {
+    let sql = tables
+        .iter()
+        .map(|table| {
+            format!(
+                "SELECT '{}', IDENT_CURRENT('{}.{}') FROM DUAL",
+                table.replace("'", "''"),
+                schema.replace("'", "''"),
+                table.replace("'", "''")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+
+    let mut cursor = match execute_read(connection, &sql)
+        .context("Failed to query identity high-water values")?
+    {
+        Some(cursor) => cursor,
+        None => return Ok(HashMap::new()),
+    };
+
+    let mut buffers = TextRowSet::for_cursor(100, &mut cursor, Some(256))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    let mut currents = HashMap::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            let table = batch.at_as_str(0, row_index)?;
+            let current = batch
+                .at_as_str(1, row_index)?
+                .and_then(|s| s.trim().parse::<i64>().ok());
+            if let (Some(table), Some(current)) = (table, current) {
+                currents.insert(table.to_string(), current);
+            }
         }
-        if message.contains("WHEN_CLAUSE") {
-            return Some("WHEN_CLAUSE");
+    }
+
+    Ok(currents)
+}
+
+fn fetch_identity_info(
+    connection: &Connection<'_>,
+    schema: &str,
+    tables: &[String],
+) -> Result<HashMap<String, (i64, i64)>> {
+    // Use IDENT_SEED and IDENT_INCR functions to get identity column properties
+    // DM8 accepts table name in format: 'SCHEMA.TABLE' or '"SCHEMA"."TABLE"'
+    let sql = tables
+        .iter()
+        .map(|table| {
+            let qualified = format!(
+                "{}.{}",
+                schema.replace("'", "''"),
+                table.replace("'", "''")
+            );
+            format!(
+                "SELECT '{}', IDENT_SEED('{}'), IDENT_INCR('{}') FROM DUAL",
+                table.replace("'", "''"),
+                qualified,
+                qualified
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+
+    let mut cursor = match execute_read(connection, &sql).context("Failed to query identity info")? {
+        Some(cursor) => cursor,
+        None => return Ok(HashMap::new()),
+    };
+
+    let mut buffers = TextRowSet::for_cursor(100, &mut cursor, Some(256))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    let mut identities = HashMap::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            let table = batch.at_as_str(0, row_index)?;
+            let seed = batch.at_as_str(1, row_index)?.and_then(|s| s.parse::<i64>().ok());
+            let incr = batch.at_as_str(2, row_index)?.and_then(|s| s.parse::<i64>().ok());
+            if let (Some(table), Some(seed), Some(incr)) = (table, seed, incr) {
+                identities.insert(table.to_string(), (seed, incr));
+            }
         }
-        if message.contains("DESCRIPTION") {
-            return Some("DESCRIPTION");
+    }
+
+    Ok(identities)
+}
+
+fn trigger_query(capabilities: &ServerCapabilities, schema: &str, tables: &[String]) -> String {
+    format!(
+        "SELECT TRIGGER_NAME, {}, TRIGGERING_EVENT, TABLE_NAME, {}, TRIGGER_BODY, {} \
+         FROM ALL_TRIGGERS \
+         WHERE TABLE_OWNER = '{}' AND {} \
+         ORDER BY TABLE_NAME, TRIGGER_NAME",
+        ServerCapabilities::column(capabilities.trigger_type, "TRIGGER_TYPE"),
+        ServerCapabilities::column(capabilities.trigger_when_clause, "WHEN_CLAUSE"),
+        ServerCapabilities::column(capabilities.trigger_description, "DESCRIPTION"),
+        schema.replace("'", "''"),
+        table_filter("TABLE_NAME", tables)
+    )
+}
+
+/// Normalizes a catalog collation value; empty and `USING_NLS_COMP` (inherit) become `None`.
+fn explicit_collation(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && !v.eq_ignore_ascii_case("USING_NLS_COMP"))
+        .map(str::to_uppercase)
+}
+
+/// Whether the instance compares identifiers and strings case-sensitively (`CASE_SENSITIVE`
+/// init parameter). This is fixed when the database is created, so it can only be reported.
+pub fn fetch_case_sensitivity(connection: &Connection<'_>) -> Result<Option<bool>> {
+    let sql = "SELECT SF_GET_CASE_SENSITIVE_FLAG() FROM DUAL";
+    let mut cursor = match execute_read(connection, sql).context("Failed to query case sensitivity")? {
+        Some(cursor) => cursor,
+        None => return Ok(None),
+    };
+
+    let mut buffers = TextRowSet::for_cursor(1, &mut cursor, Some(16))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    if let Some(batch) = row_set_cursor.fetch()? {
+        if batch.num_rows() > 0 {
+            return Ok(batch.at_as_str(0, 0)?.map(|flag| flag.trim() == "1"));
         }
-        if message.contains("TRIGGER_BODY") {
-            return Some("TRIGGER_BODY");
+    }
+    Ok(None)
+}
+
+/// Names of the tables of `schema` (catalog spelling), without the row counts of [`get_tables`].
+pub fn fetch_table_names(connection: &Connection<'_>, schema: &str) -> Result<Vec<String>> {
+    let sql = format!(
+        "SELECT TABLE_NAME FROM ALL_TABLES WHERE OWNER = '{}' ORDER BY TABLE_NAME",
+        schema.replace('\'', "''")
+    );
+    let mut cursor = execute_read(connection, &sql)
+        .context("Failed to query table names")?
+        .ok_or_else(|| anyhow!("DM8 returned no cursor for table name query"))?;
+
+    let mut buffers = TextRowSet::for_cursor(500, &mut cursor, Some(256))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    let mut names = Vec::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            if let Some(name) = batch.at_as_str(0, row_index)? {
+                names.push(name.to_string());
+            }
         }
     }
-    None
+    Ok(names)
 }
 
-fn trigger_fallback_level(current_level: u8, err: &anyhow::Error) -> Option<u8> {
-    let missing = trigger_missing_column(err);
+/// Names of every schema (user) visible to the session.
+pub fn fetch_schema_names(connection: &Connection<'_>) -> Result<Vec<String>> {
+    let sql = "SELECT USERNAME FROM ALL_USERS ORDER BY USERNAME";
+    let mut cursor = execute_read(connection, sql)
+        .context("Failed to query schema names")?
+        .ok_or_else(|| anyhow!("DM8 returned no cursor for schema name query"))?;
 
-    match (current_level, missing) {
-        (TRIGGER_LEVEL_FULL, Some("TRIGGER_TYPE")) => Some(TRIGGER_LEVEL_NO_TYPE),
-        (TRIGGER_LEVEL_FULL, Some("DESCRIPTION")) => Some(TRIGGER_LEVEL_NO_TYPE),
-        (TRIGGER_LEVEL_FULL, Some("WHEN_CLAUSE")) => Some(TRIGGER_LEVEL_NO_WHEN),
-        (TRIGGER_LEVEL_NO_TYPE, Some("WHEN_CLAUSE")) => Some(TRIGGER_LEVEL_NO_WHEN),
-        (TRIGGER_LEVEL_NO_TYPE, Some("TRIGGER_TYPE")) => Some(TRIGGER_LEVEL_NO_TYPE),
-        (TRIGGER_LEVEL_NO_TYPE, Some("DESCRIPTION")) => Some(TRIGGER_LEVEL_NO_TYPE),
-        (TRIGGER_LEVEL_NO_WHEN, _) => None,
-        (_, Some("TRIGGER_BODY")) => None,
-        _ => {
-            if is_trigger_metadata_missing(err) {
-                match current_level {
-                    TRIGGER_LEVEL_FULL => Some(TRIGGER_LEVEL_NO_TYPE),
-                    TRIGGER_LEVEL_NO_TYPE => Some(TRIGGER_LEVEL_NO_WHEN),
-                    _ => None,
-                }
-            } else {
-                None
+    let mut buffers = TextRowSet::for_cursor(500, &mut cursor, Some(256))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    let mut names = Vec::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            if let Some(name) = batch.at_as_str(0, row_index)? {
+                names.push(name.to_string());
             }
         }
     }
+    Ok(names)
+}
+
+/// Largest value of a LOB column in bytes, `None` for an empty table.
+pub fn fetch_max_lob_length(
+    connection: &Connection<'_>,
+    schema: &str,
+    table: &str,
+    column: &str,
+) -> Result<Option<i64>> {
+    let sql = format!(
+        "SELECT MAX(DBMS_LOB.GETLENGTH(\"{}\")) FROM \"{}\".\"{}\"",
+        column.replace('"', "\"\""),
+        schema.replace('"', "\"\""),
+        table.replace('"', "\"\"")
+    );
+
+    let mut cursor = match execute_read(connection, &sql)
+        .with_context(|| format!("Failed to measure LOB column {}.{}", table, column))?
+    {
+        Some(cursor) => cursor,
+        None => return Ok(None),
+    };
+
+    let mut buffers = TextRowSet::for_cursor(1, &mut cursor, Some(32))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    if let Some(batch) = row_set_cursor.fetch()? {
+        if batch.num_rows() > 0 {
+            return Ok(batch
+                .at_as_str(0, 0)?
+                .and_then(|value| value.trim().parse::<i64>().ok()));
+        }
+    }
+    Ok(None)
 }
 
 pub fn fetch_row_count(connection: &Connection<'_>, schema: &str, table: &str) -> Result<i64> {
@@ -332,9 +728,28 @@ pub fn fetch_row_count(connection: &Connection<'_>, schema: &str, table: &str) -
         schema.replace('"', "\"\""),
         table.replace('"', "\"\"")
     );
+    read_row_count(connection, &sql, table)
+}
+
+/// Rows of `schema.table` in a `SAMPLE(percent)` of it, for estimating the row count without
+/// counting every row.
+pub fn fetch_sampled_row_count(
+    connection: &Connection<'_>,
+    schema: &str,
+    table: &str,
+    percent: f64,
+) -> Result<i64> {
+    let sql = format!(
+        "SELECT COUNT(*) AS CNT FROM \"{}\".\"{}\" SAMPLE({})",
+        schema.replace('"', "\"\""),
+        table.replace('"', "\"\""),
+        percent
+    );
+    read_row_count(connection, &sql, table)
+}
 
-    let mut cursor = connection
-        .execute(&sql, ())
+fn read_row_count(connection: &Connection<'_>, sql: &str, table: &str) -> Result<i64> {
+    let mut cursor = execute_read(connection, sql)
         .with_context(|| format!("Failed to count rows for table {}", table))?
         .ok_or_else(|| anyhow!("DM8 returned no cursor for row count query"))?;
 
@@ -354,72 +769,181 @@ pub fn fetch_row_count(connection: &Connection<'_>, schema: &str, table: &str) -
     Err(anyhow!("Failed to read row count for {}", table))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{is_trigger_metadata_missing, trigger_fallback_level};
+/// Fails unless the login may SELECT from `schema.table`; the query itself returns no rows.
+pub fn check_readable(connection: &Connection<'_>, schema: &str, table: &str) -> Result<()> {
+    let sql = format!(
+        "SELECT 1 FROM \"{}\".\"{}\" WHERE 1 = 0",
+        schema.replace('"', "\"\""),
+        table.replace('"', "\"\"")
+    );
+    execute_read(connection, &sql)
+        .with_context(|| format!("Cannot read table {}.{}", schema, table))?;
+    Ok(())
+}
 
-    #[test]
-    fn trigger_metadata_missing_detects_missing_trigger_type_column() {
-        let err = anyhow::anyhow!(
-            "State: 42S22, Native error: -2111, Message: 第1 行附近出现错误: 无效的列名[TRIGGER_TYPE]"
+/// Optimizer statistics of a table from `ALL_TABLES`; unset until the table is analyzed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableStatistics {
+    pub num_rows: Option<u64>,
+    /// Average row length in bytes.
+    pub avg_row_len: Option<u64>,
+}
+
+/// [`TableStatistics`] of `tables`; tables the catalog does not list are left out.
+pub fn fetch_table_statistics(
+    connection: &Connection<'_>,
+    schema: &str,
+    tables: &[String],
+) -> Result<HashMap<String, TableStatistics>> {
+    let mut statistics = HashMap::new();
+    for chunk in tables.chunks(TABLES_PER_QUERY) {
+        let sql = format!(
+            "SELECT TABLE_NAME, NUM_ROWS, AVG_ROW_LEN FROM ALL_TABLES \
+             WHERE OWNER = '{}' AND {}",
+            schema.replace("'", "''"),
+            table_filter("TABLE_NAME", chunk)
         );
-        assert!(is_trigger_metadata_missing(&err));
-    }
+        let mut cursor =
+            match execute_read(connection, &sql).context("Failed to query table statistics")? {
+                Some(cursor) => cursor,
+                None => continue,
+            };
 
-    #[test]
-    fn trigger_metadata_missing_ignores_other_errors() {
-        let err = anyhow::anyhow!("some other error");
-        assert!(!is_trigger_metadata_missing(&err));
+        let mut buffers = TextRowSet::for_cursor(200, &mut cursor, Some(256))?;
+        let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+        while let Some(batch) = row_set_cursor.fetch()? {
+            for row_index in 0..batch.num_rows() {
+                let Some(table) = batch.at_as_str(0, row_index)? else {
+                    continue;
+                };
+                let number = |column: usize| -> Result<Option<u64>> {
+                    Ok(batch
+                        .at_as_str(column, row_index)?
+                        .and_then(|value| value.trim().parse::<u64>().ok()))
+                };
+                statistics.insert(
+                    table.to_string(),
+                    TableStatistics {
+                        num_rows: number(1)?,
+                        avg_row_len: number(2)?,
+                    },
+                );
+            }
+        }
     }
+    Ok(statistics)
+}
 
-    #[test]
-    fn trigger_fallback_level_handles_missing_trigger_type() {
-        let err = anyhow::anyhow!(
-            "State: 42S22, Native error: -2111, Message: 第1 行附近出现错误: 无效的列名[TRIGGER_TYPE]"
-        );
-        assert_eq!(trigger_fallback_level(0, &err), Some(1));
+/// Average length in bytes of the first `limit` rows of a table with every value read as
+/// text (long values capped at 8192 bytes); `None` for an empty table.
+pub fn sample_row_length(
+    connection: &Connection<'_>,
+    schema: &str,
+    table: &str,
+    limit: usize,
+) -> Result<Option<u64>> {
+    let sql = format!(
+        "SELECT TOP {} * FROM \"{}\".\"{}\"",
+        limit,
+        schema.replace('"', "\"\""),
+        table.replace('"', "\"\"")
+    );
+    let mut cursor = match execute_read(connection, &sql)
+        .with_context(|| format!("Failed to sample rows of table {}", table))?
+    {
+        Some(cursor) => cursor,
+        None => return Ok(None),
+    };
+
+    let mut buffers = TextRowSet::for_cursor(100, &mut cursor, Some(8192))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+    let (mut rows, mut bytes) = (0_u64, 0_u64);
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            rows += 1;
+            for column in 0..batch.num_cols() {
+                bytes += batch
+                    .at(column, row_index)
+                    .map_or(0, |value| value.len() as u64);
+            }
+        }
     }
+    Ok((rows > 0).then(|| bytes / rows))
+}
 
-    #[test]
-    fn trigger_fallback_level_handles_missing_when_clause() {
-        let err = anyhow::anyhow!(
-            "State: 42S22, Native error: -2111, Message: 第1 行附近出现错误: 无效的列名[WHEN_CLAUSE]"
-        );
-        assert_eq!(trigger_fallback_level(1, &err), Some(2));
+/// Largest value of an integer column, `None` when the table is empty.
+pub fn fetch_column_max(
+    connection: &Connection<'_>,
+    schema: &str,
+    table: &str,
+    column: &str,
+) -> Result<Option<i64>> {
+    let sql = format!(
+        "SELECT MAX(\"{}\") AS MAX_VALUE FROM \"{}\".\"{}\"",
+        column.replace('"', "\"\""),
+        schema.replace('"', "\"\""),
+        table.replace('"', "\"\"")
+    );
+
+    let mut cursor = execute_read(connection, &sql)
+        .with_context(|| format!("Failed to read MAX({}) of table {}", column, table))?
+        .ok_or_else(|| anyhow!("DM8 returned no cursor for MAX query"))?;
+
+    let mut buffers = TextRowSet::for_cursor(1, &mut cursor, Some(64))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    if let Some(batch) = row_set_cursor.fetch()? {
+        if batch.num_rows() > 0 {
+            let Some(val) = batch.at_as_str(0, 0)? else {
+                return Ok(None);
+            };
+            // NUMBER columns may come back with a zero fraction, e.g. `42.0`.
+            let integral = val.trim().split('.').next().unwrap_or_default();
+            return integral.parse::<i64>().map(Some).with_context(|| {
+                format!(
+                    "MAX({}) of table {} is not an integer: {}",
+                    column, table, val
+                )
+            });
+        }
     }
+
+    Ok(None)
 }
 
 fn fetch_primary_keys(
     connection: &Connection<'_>,
     schema: &str,
-    table: &str,
-) -> Result<Vec<String>> {
+    tables: &[String],
+) -> Result<HashMap<String, Vec<String>>> {
     let sql = format!(
-        "SELECT acc.COLUMN_NAME \
+        "SELECT ac.TABLE_NAME, acc.COLUMN_NAME \
          FROM ALL_CONSTRAINTS ac \
          JOIN ALL_CONS_COLUMNS acc ON ac.OWNER = acc.OWNER AND ac.CONSTRAINT_NAME = acc.CONSTRAINT_NAME \
-         WHERE ac.CONSTRAINT_TYPE = 'P' AND ac.OWNER = '{}' AND ac.TABLE_NAME = '{}' \
-         ORDER BY acc.POSITION",
+         WHERE ac.CONSTRAINT_TYPE = 'P' AND ac.OWNER = '{}' AND {} \
+         ORDER BY ac.TABLE_NAME, acc.POSITION",
         schema.replace("'", "''"),
-        table.replace("'", "''")
+        table_filter("ac.TABLE_NAME", tables)
     );
 
-    let mut cursor = connection
-        .execute(&sql, ())
+    let mut cursor = execute_read(connection, &sql)
         .context("Failed to query primary keys")?
         .ok_or_else(|| anyhow!("DM8 returned no cursor for primary key query"))?;
 
     let mut buffers = TextRowSet::for_cursor(100, &mut cursor, Some(8192))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    let mut keys = Vec::new();
+    let mut keys: HashMap<String, Vec<String>> = HashMap::new();
 
     while let Some(batch) = row_set_cursor.fetch()? {
         for row_index in 0..batch.num_rows() {
-            let name = batch.at_as_str(0, row_index)?
+            let table = batch.at_as_str(0, row_index)?
+                .ok_or_else(|| anyhow!("Primary key table name missing"))?
+                .to_string();
+            let name = batch.at_as_str(1, row_index)?
                 .ok_or_else(|| anyhow!("Primary key column name missing"))?
                 .to_string();
-            keys.push(name);
+            keys.entry(table).or_default().push(name);
         }
     }
 
@@ -429,46 +953,46 @@ fn fetch_primary_keys(
 fn fetch_unique_constraints(
     connection: &Connection<'_>,
     schema: &str,
-    table: &str,
-) -> Result<Vec<UniqueConstraint>> {
+    tables: &[String],
+) -> Result<HashMap<String, Vec<UniqueConstraint>>> {
     let sql = format!(
-        "SELECT ac.CONSTRAINT_NAME, acc.COLUMN_NAME \
+        "SELECT ac.TABLE_NAME, ac.CONSTRAINT_NAME, acc.COLUMN_NAME \
          FROM ALL_CONSTRAINTS ac \
          JOIN ALL_CONS_COLUMNS acc ON ac.OWNER = acc.OWNER AND ac.CONSTRAINT_NAME = acc.CONSTRAINT_NAME \
-         WHERE ac.CONSTRAINT_TYPE = 'U' AND ac.OWNER = '{}' AND ac.TABLE_NAME = '{}' \
-         ORDER BY ac.CONSTRAINT_NAME, acc.POSITION",
+         WHERE ac.CONSTRAINT_TYPE = 'U' AND ac.OWNER = '{}' AND {} \
+         ORDER BY ac.TABLE_NAME, ac.CONSTRAINT_NAME, acc.POSITION",
         schema.replace("'", "''"),
-        table.replace("'", "''")
+        table_filter("ac.TABLE_NAME", tables)
     );
 
-    let mut cursor = connection
-        .execute(&sql, ())
+    let mut cursor = execute_read(connection, &sql)
         .context("Failed to query unique constraints")?
         .ok_or_else(|| anyhow!("DM8 returned no cursor for unique constraint query"))?;
 
     let mut buffers = TextRowSet::for_cursor(200, &mut cursor, Some(8192))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    let mut constraints: Vec<UniqueConstraint> = Vec::new();
-    let mut current_name: Option<String> = None;
+    let mut constraints: HashMap<String, Vec<UniqueConstraint>> = HashMap::new();
 
     while let Some(batch) = row_set_cursor.fetch()? {
         for row_index in 0..batch.num_rows() {
-            let name = batch.at_as_str(0, row_index)?
+            let table = batch.at_as_str(0, row_index)?
+                .ok_or_else(|| anyhow!("Unique constraint table missing"))?
+                .to_string();
+            let name = batch.at_as_str(1, row_index)?
                 .ok_or_else(|| anyhow!("Unique constraint name missing"))?
                 .to_string();
-            let column = batch.at_as_str(1, row_index)?
+            let column = batch.at_as_str(2, row_index)?
                 .ok_or_else(|| anyhow!("Unique constraint column missing"))?
                 .to_string();
 
-            if current_name.as_ref() != Some(&name) {
-                constraints.push(UniqueConstraint {
-                    name: name.clone(),
+            let table_constraints = constraints.entry(table).or_default();
+            match table_constraints.last_mut() {
+                Some(last) if last.name == name => last.columns.push(column),
+                _ => table_constraints.push(UniqueConstraint {
+                    name,
                     columns: vec![column],
-                });
-                current_name = Some(name);
-            } else if let Some(last) = constraints.last_mut() {
-                last.columns.push(column);
+                }),
             }
         }
     }
@@ -479,197 +1003,162 @@ fn fetch_unique_constraints(
 fn fetch_check_constraints(
     connection: &Connection<'_>,
     schema: &str,
-    table: &str,
-) -> Result<Vec<CheckConstraint>> {
+    tables: &[String],
+) -> Result<HashMap<String, Vec<CheckConstraint>>> {
     let sql = format!(
-        "SELECT ac.CONSTRAINT_NAME, ac.SEARCH_CONDITION \
+        "SELECT ac.TABLE_NAME, ac.CONSTRAINT_NAME, ac.SEARCH_CONDITION \
          FROM ALL_CONSTRAINTS ac \
-         WHERE ac.CONSTRAINT_TYPE = 'C' AND ac.OWNER = '{}' AND ac.TABLE_NAME = '{}' \
-         ORDER BY ac.CONSTRAINT_NAME",
+         WHERE ac.CONSTRAINT_TYPE = 'C' AND ac.OWNER = '{}' AND {} \
+         ORDER BY ac.TABLE_NAME, ac.CONSTRAINT_NAME",
         schema.replace("'", "''"),
-        table.replace("'", "''")
+        table_filter("ac.TABLE_NAME", tables)
     );
 
-    let mut cursor = connection
-        .execute(&sql, ())
+    let mut cursor = execute_read(connection, &sql)
         .context("Failed to query check constraints")?
         .ok_or_else(|| anyhow!("DM8 returned no cursor for check constraint query"))?;
 
     let mut buffers = TextRowSet::for_cursor(200, &mut cursor, Some(8192))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    let mut constraints = Vec::new();
+    let mut constraints: HashMap<String, Vec<CheckConstraint>> = HashMap::new();
 
     while let Some(batch) = row_set_cursor.fetch()? {
         for row_index in 0..batch.num_rows() {
-            let name = batch.at_as_str(0, row_index)?
+            let table = batch.at_as_str(0, row_index)?
+                .ok_or_else(|| anyhow!("Check constraint table missing"))?
+                .to_string();
+            let name = batch.at_as_str(1, row_index)?
                 .ok_or_else(|| anyhow!("Check constraint name missing"))?
                 .to_string();
-            let condition = batch.at_as_str(1, row_index)?
+            let condition = batch.at_as_str(2, row_index)?
                 .ok_or_else(|| anyhow!("Check constraint condition missing"))?
                 .to_string();
-            constraints.push(CheckConstraint { name, condition });
+            constraints
+                .entry(table)
+                .or_default()
+                .push(CheckConstraint { name, condition });
         }
     }
 
     Ok(constraints)
 }
 
+/// Foreign keys with their columns and the referenced key's columns, matched by position, in
+/// one query over `ALL_CONSTRAINTS` and `ALL_CONS_COLUMNS`.
 fn fetch_foreign_keys(
     connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
     schema: &str,
-    table: &str,
-) -> Result<Vec<ForeignKey>> {
-    // Try with UPDATE_RULE first, fallback without it if not supported
-    // DM8 may not have UPDATE_RULE column in ALL_CONSTRAINTS
-    let sql_with_update = format!(
-        "SELECT ac.CONSTRAINT_NAME, ac.R_CONSTRAINT_NAME, ac.DELETE_RULE, ac.UPDATE_RULE \
-         FROM ALL_CONSTRAINTS ac \
-         WHERE ac.CONSTRAINT_TYPE = 'R' AND ac.OWNER = '{}' AND ac.TABLE_NAME = '{}' \
-         ORDER BY ac.CONSTRAINT_NAME",
-        schema.replace("'", "''"),
-        table.replace("'", "''")
-    );
-
-    let sql_without_update = format!(
-        "SELECT ac.CONSTRAINT_NAME, ac.R_CONSTRAINT_NAME, ac.DELETE_RULE, NULL AS UPDATE_RULE \
+    tables: &[String],
+) -> Result<HashMap<String, Vec<ForeignKey>>> {
+    let sql = format!(
+        "SELECT ac.TABLE_NAME, ac.CONSTRAINT_NAME, ac.DELETE_RULE, {}, \
+                acc.COLUMN_NAME, rc.OWNER, rc.TABLE_NAME, rcc.COLUMN_NAME \
          FROM ALL_CONSTRAINTS ac \
-         WHERE ac.CONSTRAINT_TYPE = 'R' AND ac.OWNER = '{}' AND ac.TABLE_NAME = '{}' \
-         ORDER BY ac.CONSTRAINT_NAME",
+         JOIN ALL_CONS_COLUMNS acc ON acc.OWNER = ac.OWNER AND acc.CONSTRAINT_NAME = ac.CONSTRAINT_NAME \
+         JOIN ALL_CONSTRAINTS rc ON rc.OWNER = ac.R_OWNER AND rc.CONSTRAINT_NAME = ac.R_CONSTRAINT_NAME \
+         JOIN ALL_CONS_COLUMNS rcc ON rcc.OWNER = rc.OWNER AND rcc.CONSTRAINT_NAME = rc.CONSTRAINT_NAME \
+                                  AND rcc.POSITION = acc.POSITION \
+         WHERE ac.CONSTRAINT_TYPE = 'R' AND ac.OWNER = '{}' AND {} \
+         ORDER BY ac.TABLE_NAME, ac.CONSTRAINT_NAME, acc.POSITION",
+        ServerCapabilities::column(capabilities.constraint_update_rule, "ac.UPDATE_RULE"),
         schema.replace("'", "''"),
-        table.replace("'", "''")
+        table_filter("ac.TABLE_NAME", tables)
     );
 
-    // Try with UPDATE_RULE first
-    let (cursor_result, has_update_rule) = match connection.execute(&sql_with_update, ()) {
-        Ok(cursor) => (Ok(cursor), true),
-        Err(e) => {
-            let err_msg = e.to_string().to_uppercase();
-            if err_msg.contains("UPDATE_RULE") || err_msg.contains("-2207") {
-                // UPDATE_RULE not supported, fallback
-                (connection.execute(&sql_without_update, ()), false)
-            } else {
-                (Err(e), true)
-            }
-        }
-    };
-
-    let mut cursor = cursor_result
+    let mut cursor = execute_read(connection, &sql)
         .context("Failed to query foreign key constraints")?
         .ok_or_else(|| anyhow!("DM8 returned no cursor for foreign key constraint query"))?;
 
-    if !has_update_rule {
-        tracing::debug!("DM8 ALL_CONSTRAINTS does not have UPDATE_RULE column, using fallback query");
-    }
-
     let mut buffers = TextRowSet::for_cursor(200, &mut cursor, Some(8192))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    let mut fks = Vec::new();
+    let mut fks: HashMap<String, Vec<ForeignKey>> = HashMap::new();
 
     while let Some(batch) = row_set_cursor.fetch()? {
         for row_index in 0..batch.num_rows() {
-            let name = batch.at_as_str(0, row_index)?
-                .ok_or_else(|| anyhow!("Foreign key name missing"))?
+            let table = batch.at_as_str(0, row_index)?
+                .ok_or_else(|| anyhow!("Foreign key table missing"))?
                 .to_string();
-            let ref_constraint = batch.at_as_str(1, row_index)?
-                .ok_or_else(|| anyhow!("Referenced constraint name missing"))?
+            let name = batch.at_as_str(1, row_index)?
+                .ok_or_else(|| anyhow!("Foreign key name missing"))?
                 .to_string();
             let delete_rule = batch.at_as_str(2, row_index)?.map(|s| s.to_string());
             let update_rule = batch.at_as_str(3, row_index)?.map(|s| s.to_string());
+            let column = batch.at_as_str(4, row_index)?
+                .ok_or_else(|| anyhow!("Constraint column missing"))?
+                .to_string();
+            let ref_owner = batch.at_as_str(5, row_index)?
+                .ok_or_else(|| anyhow!("Referenced owner missing"))?;
+            let ref_table = batch.at_as_str(6, row_index)?
+                .ok_or_else(|| anyhow!("Referenced table missing"))?;
+            let ref_column = batch.at_as_str(7, row_index)?
+                .ok_or_else(|| anyhow!("Constraint column missing"))?
+                .to_string();
 
-            // Columns in FK
-            let fk_cols = fetch_constraint_columns(connection, schema, &name)?;
-
-            // Referenced table & columns
-            let (ref_table, ref_cols) =
-                fetch_referenced_columns(connection, &ref_constraint)?;
-
-            fks.push(ForeignKey {
-                name,
-                columns: fk_cols,
-                referenced_table: ref_table,
-                referenced_columns: ref_cols,
-                delete_rule,
-                update_rule,
-            });
+            let table_fks = fks.entry(table).or_default();
+            match table_fks.last_mut() {
+                Some(last) if last.name == name => {
+                    last.columns.push(column);
+                    last.referenced_columns.push(ref_column);
+                }
+                _ => table_fks.push(ForeignKey {
+                    name,
+                    columns: vec![column],
+                    referenced_table: format!("{}.{}", ref_owner, ref_table),
+                    referenced_columns: vec![ref_column],
+                    delete_rule,
+                    update_rule,
+                }),
+            }
         }
     }
 
     Ok(fks)
 }
 
-fn fetch_constraint_columns(
+/// Foreign keys in any schema that reference a primary or unique key of `schema.table`.
+pub fn fetch_referencing_tables(
     connection: &Connection<'_>,
     schema: &str,
-    constraint_name: &str,
-) -> Result<Vec<String>> {
+    table: &str,
+) -> Result<Vec<TableReference>> {
     let sql = format!(
-        "SELECT acc.COLUMN_NAME \
-         FROM ALL_CONS_COLUMNS acc \
-         WHERE acc.OWNER = '{}' AND acc.CONSTRAINT_NAME = '{}' \
-         ORDER BY acc.POSITION",
+        "SELECT c.OWNER, c.TABLE_NAME, c.CONSTRAINT_NAME \
+         FROM ALL_CONSTRAINTS c \
+         JOIN ALL_CONSTRAINTS p ON p.OWNER = c.R_OWNER AND p.CONSTRAINT_NAME = c.R_CONSTRAINT_NAME \
+         WHERE c.CONSTRAINT_TYPE = 'R' AND p.OWNER = '{}' AND p.TABLE_NAME = '{}' \
+         ORDER BY c.OWNER, c.TABLE_NAME, c.CONSTRAINT_NAME",
         schema.replace("'", "''"),
-        constraint_name.replace("'", "''")
+        table.replace("'", "''")
     );
 
-    let mut cursor = connection
-        .execute(&sql, ())
-        .context("Failed to query constraint columns")?
-        .ok_or_else(|| anyhow!("DM8 returned no cursor for constraint columns query"))?;
+    let mut cursor = execute_read(connection, &sql)
+        .context("Failed to query referencing foreign keys")?
+        .ok_or_else(|| anyhow!("DM8 returned no cursor for referencing foreign key query"))?;
 
-    let mut buffers = TextRowSet::for_cursor(200, &mut cursor, Some(8192))?;
+    let mut buffers = TextRowSet::for_cursor(200, &mut cursor, Some(256))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    let mut cols = Vec::new();
+    let mut references = Vec::new();
     while let Some(batch) = row_set_cursor.fetch()? {
         for row_index in 0..batch.num_rows() {
-            let name = batch.at_as_str(0, row_index)?
-                .ok_or_else(|| anyhow!("Constraint column missing"))?
-                .to_string();
-            cols.push(name);
+            let text = |column| -> Result<String> {
+                Ok(batch
+                    .at_as_str(column, row_index)?
+                    .unwrap_or_default()
+                    .to_string())
+            };
+            references.push(TableReference {
+                schema: text(0)?,
+                table: text(1)?,
+                constraint: text(2)?,
+            });
         }
     }
-    Ok(cols)
-}
-
-fn fetch_referenced_columns(
-    connection: &Connection<'_>,
-    referenced_constraint: &str,
-) -> Result<(String, Vec<String>)> {
-    let sql = format!(
-        "SELECT ac.OWNER, ac.TABLE_NAME \
-         FROM ALL_CONSTRAINTS ac \
-         WHERE ac.CONSTRAINT_NAME = '{}'",
-        referenced_constraint.replace("'", "''")
-    );
-
-    let mut cursor = connection
-        .execute(&sql, ())
-        .context("Failed to query referenced constraint")?
-        .ok_or_else(|| anyhow!("DM8 returned no cursor for referenced constraint query"))?;
-
-    let mut buffers = TextRowSet::for_cursor(10, &mut cursor, Some(128))?;
-    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    let (owner, table) = if let Some(batch) = row_set_cursor.fetch()? {
-        if batch.num_rows() > 0 {
-            let owner = batch.at_as_str(0, 0)?
-                .ok_or_else(|| anyhow!("Referenced owner missing"))?
-                .to_string();
-            let table = batch.at_as_str(1, 0)?
-                .ok_or_else(|| anyhow!("Referenced table missing"))?
-                .to_string();
-            (owner, table)
-        } else {
-            return Err(anyhow!("Referenced constraint {} not found", referenced_constraint));
-        }
-    } else {
-        return Err(anyhow!("Referenced constraint {} not found", referenced_constraint));
-    };
-
-    let columns = fetch_constraint_columns(connection, &owner, referenced_constraint)?;
-    Ok((format!("{}.{}", owner, table), columns))
+    Ok(references)
 }
 
 pub fn fetch_sequences(connection: &Connection<'_>, schema: &str) -> Result<Vec<Sequence>> {
@@ -679,8 +1168,7 @@ pub fn fetch_sequences(connection: &Connection<'_>, schema: &str) -> Result<Vec<
         schema.replace("'", "''")
     );
 
-    let mut cursor = connection
-        .execute(&sql, ())
+    let mut cursor = execute_read(connection, &sql)
         .context("Failed to query sequences")?
         .ok_or_else(|| anyhow!("DM8 returned no cursor for sequences query"))?;
 
@@ -716,94 +1204,105 @@ pub fn fetch_sequences(connection: &Connection<'_>, schema: &str) -> Result<Vec<
     Ok(seqs)
 }
 
-fn fetch_triggers(
-    connection: &Connection<'_>,
-    schema: &str,
-    table: &str,
-) -> Result<Vec<TriggerDefinition>> {
-    static TRIGGER_METADATA_LEVEL: AtomicU8 = AtomicU8::new(TRIGGER_LEVEL_FULL);
-
-    let sql_full = format!(
-        "SELECT TRIGGER_NAME, TRIGGER_TYPE, TRIGGERING_EVENT, TABLE_NAME, WHEN_CLAUSE, TRIGGER_BODY, DESCRIPTION \
-         FROM ALL_TRIGGERS \
-         WHERE TABLE_OWNER = '{}' AND TABLE_NAME = '{}' \
-         ORDER BY TRIGGER_NAME",
-        schema.replace("'", "''"),
-        table.replace("'", "''")
+/// View definitions in `schema`, from `ALL_VIEWS`.
+pub fn fetch_views(connection: &Connection<'_>, schema: &str) -> Result<Vec<ViewDefinition>> {
+    let sql = format!(
+        "SELECT VIEW_NAME, TEXT FROM ALL_VIEWS WHERE OWNER = '{}' ORDER BY VIEW_NAME",
+        schema.replace("'", "''")
     );
 
-    let sql_no_type = format!(
-        "SELECT TRIGGER_NAME, NULL AS TRIGGER_TYPE, TRIGGERING_EVENT, TABLE_NAME, WHEN_CLAUSE, TRIGGER_BODY, NULL AS DESCRIPTION \
-         FROM ALL_TRIGGERS \
-         WHERE TABLE_OWNER = '{}' AND TABLE_NAME = '{}' \
-         ORDER BY TRIGGER_NAME",
-        schema.replace("'", "''"),
-        table.replace("'", "''")
-    );
+    let mut cursor = match execute_read(connection, &sql)
+        .context("Failed to query views")?
+    {
+        Some(cursor) => cursor,
+        None => return Ok(Vec::new()),
+    };
 
-    let sql_no_when = format!(
-        "SELECT TRIGGER_NAME, NULL AS TRIGGER_TYPE, TRIGGERING_EVENT, TABLE_NAME, NULL AS WHEN_CLAUSE, TRIGGER_BODY, NULL AS DESCRIPTION \
-         FROM ALL_TRIGGERS \
-         WHERE TABLE_OWNER = '{}' AND TABLE_NAME = '{}' \
-         ORDER BY TRIGGER_NAME",
-        schema.replace("'", "''"),
-        table.replace("'", "''")
+    let mut buffers = TextRowSet::for_cursor(20, &mut cursor, Some(65536))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
+
+    let mut views = Vec::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            let (Some(name), Some(definition)) = (
+                batch.at_as_str(0, row_index)?,
+                batch.at_as_str(1, row_index)?,
+            ) else {
+                continue;
+            };
+            views.push(ViewDefinition {
+                name: name.to_string(),
+                definition: definition.trim().to_string(),
+            });
+        }
+    }
+    Ok(views)
+}
+
+/// Object privileges granted on tables in `schema`, from `ALL_TAB_PRIVS`.
+pub fn fetch_object_grants(connection: &Connection<'_>, schema: &str) -> Result<Vec<ObjectGrant>> {
+    let sql = format!(
+        "SELECT TABLE_NAME, GRANTEE, PRIVILEGE, GRANTABLE \
+         FROM ALL_TAB_PRIVS WHERE TABLE_SCHEMA = '{}' \
+         ORDER BY TABLE_NAME, GRANTEE, PRIVILEGE",
+        schema.replace("'", "''")
     );
 
-    let trigger_level_label = |level| match level {
-        TRIGGER_LEVEL_FULL => "full",
-        TRIGGER_LEVEL_NO_TYPE => "no-trigger-type",
-        TRIGGER_LEVEL_NO_WHEN => "no-when-clause",
-        _ => "unknown",
+    let mut cursor = match execute_read(connection, &sql)
+        .context("Failed to query object grants")?
+    {
+        Some(cursor) => cursor,
+        None => return Ok(Vec::new()),
     };
 
-    let mut level = TRIGGER_METADATA_LEVEL.load(Ordering::Relaxed);
-    let mut attempts = 0u8;
-    let mut cursor = loop {
-        let (sql, context_label) = match level {
-            TRIGGER_LEVEL_FULL => (&sql_full, "Failed to query triggers (full)"),
-            TRIGGER_LEVEL_NO_TYPE => (&sql_no_type, "Failed to query triggers (no trigger type)"),
-            TRIGGER_LEVEL_NO_WHEN => (&sql_no_when, "Failed to query triggers (no when clause)"),
-            _ => (&sql_no_when, "Failed to query triggers (fallback)"),
-        };
+    let mut buffers = TextRowSet::for_cursor(200, &mut cursor, Some(512))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-        match connection.execute(sql, ()) {
-            Ok(Some(cursor)) => break cursor,
-            Ok(None) => return Ok(vec![]),
-            Err(err) => {
-                let err = anyhow!(err).context(context_label);
-                if let Some(next_level) = trigger_fallback_level(level, &err) {
-                    if next_level == level {
-                        return Err(err);
-                    }
-                    attempts = attempts.saturating_add(1);
-                    if attempts > 3 {
-                        return Err(err);
-                    }
-                    if TRIGGER_METADATA_LEVEL
-                        .compare_exchange(level, next_level, Ordering::Relaxed, Ordering::Relaxed)
-                        .is_ok()
-                    {
-                        level = next_level;
-                    } else {
-                        level = TRIGGER_METADATA_LEVEL.load(Ordering::Relaxed);
-                    }
-                    tracing::warn!(
-                        "Trigger metadata not available, fallback to {}: {}",
-                        trigger_level_label(level),
-                        err
-                    );
-                    continue;
-                }
-                return Err(err);
-            }
+    let mut grants = Vec::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
+        for row_index in 0..batch.num_rows() {
+            let (Some(table_name), Some(grantee), Some(privilege)) = (
+                batch.at_as_str(0, row_index)?,
+                batch.at_as_str(1, row_index)?,
+                batch.at_as_str(2, row_index)?,
+            ) else {
+                continue;
+            };
+            let grantable = matches!(
+                batch.at_as_str(3, row_index)?,
+                Some(flag) if flag.eq_ignore_ascii_case("YES") || flag.eq_ignore_ascii_case("Y")
+            );
+
+            grants.push(ObjectGrant {
+                table_name: table_name.to_string(),
+                grantee: grantee.to_string(),
+                privilege: privilege.trim().to_uppercase(),
+                grantable,
+            });
         }
+    }
+    Ok(grants)
+}
+
+fn fetch_triggers(
+    connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
+    schema: &str,
+    tables: &[String],
+) -> Result<HashMap<String, Vec<TriggerDefinition>>> {
+    let sql = trigger_query(capabilities, schema, tables);
+
+    let mut cursor = match execute_read(connection, &sql)
+        .context("Failed to query triggers")?
+    {
+        Some(cursor) => cursor,
+        None => return Ok(HashMap::new()),
     };
 
     let mut buffers = TextRowSet::for_cursor(200, &mut cursor, Some(8192))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    let mut triggers = Vec::new();
+    let mut triggers: HashMap<String, Vec<TriggerDefinition>> = HashMap::new();
     while let Some(batch) = row_set_cursor.fetch()? {
         for row_index in 0..batch.num_rows() {
             let name = batch.at_as_str(0, row_index)?
@@ -811,7 +1310,9 @@ fn fetch_triggers(
                 .to_string();
             let trigger_type = batch.at_as_str(1, row_index)?.unwrap_or("BEFORE");
             let triggering_event = batch.at_as_str(2, row_index)?.unwrap_or("INSERT");
-            let table_name = batch.at_as_str(3, row_index)?.unwrap_or(table).to_string();
+            let table_name = batch.at_as_str(3, row_index)?
+                .ok_or_else(|| anyhow!("Trigger table missing"))?
+                .to_string();
             let when_clause = batch.at_as_str(4, row_index)?.unwrap_or("").to_string();
             let body = batch.at_as_str(5, row_index)?.unwrap_or("").to_string();
             let description = batch.at_as_str(6, row_index)?.unwrap_or("").to_string();
@@ -848,7 +1349,7 @@ fn fetch_triggers(
             }
             trigger_body.push_str(body.trim());
 
-            triggers.push(TriggerDefinition {
+            triggers.entry(table_name.clone()).or_default().push(TriggerDefinition {
                 name,
                 table_name,
                 timing,
@@ -863,47 +1364,59 @@ fn fetch_triggers(
 }
 fn fetch_indexes(
     connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
     schema: &str,
-    table: &str,
-) -> Result<Vec<Index>> {
+    tables: &[String],
+) -> Result<HashMap<String, Vec<Index>>> {
     let sql = format!(
-        "SELECT ai.INDEX_NAME, ai.UNIQUENESS \
+        "SELECT ai.TABLE_NAME, ai.INDEX_NAME, ai.UNIQUENESS, ai.TABLESPACE_NAME \
          FROM ALL_INDEXES ai \
-         WHERE ai.TABLE_OWNER = '{}' AND ai.TABLE_NAME = '{}' \
-         ORDER BY ai.INDEX_NAME",
+         WHERE ai.TABLE_OWNER = '{}' AND {} \
+         ORDER BY ai.TABLE_NAME, ai.INDEX_NAME",
         schema.replace("'", "''"),
-        table.replace("'", "''")
+        table_filter("ai.TABLE_NAME", tables)
     );
 
-    let mut cursor = connection
-        .execute(&sql, ())
+    let mut cursor = execute_read(connection, &sql)
         .context("Failed to query indexes")?
         .ok_or_else(|| anyhow!("DM8 returned no cursor for index query"))?;
 
     let mut buffers = TextRowSet::for_cursor(100, &mut cursor, Some(8192))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
+    // (table, index) in catalog order, and the indexes keyed the same way.
     let mut order = Vec::new();
     let mut indexes = HashMap::new();
 
     while let Some(batch) = row_set_cursor.fetch()? {
         for row_index in 0..batch.num_rows() {
-            let name = batch.at_as_str(0, row_index)?
+            let table = batch.at_as_str(0, row_index)?
+                .ok_or_else(|| anyhow!("Index table missing"))?
+                .to_string();
+            let name = batch.at_as_str(1, row_index)?
                 .ok_or_else(|| anyhow!("Index name missing"))?
                 .to_string();
-            let uniqueness = batch.at_as_str(1, row_index)?;
+            let uniqueness = batch.at_as_str(2, row_index)?;
             let unique = matches!(
                 uniqueness,
                 Some(flag) if flag.eq_ignore_ascii_case("UNIQUE") || flag.eq_ignore_ascii_case("Y")
             );
+            let tablespace = batch
+                .at_as_str(3, row_index)?
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string());
 
-            order.push(name.clone());
+            let key = (table, name.clone());
+            order.push(key.clone());
             indexes.insert(
-                name.clone(),
+                key,
                 Index {
                     name,
                     columns: Vec::new(),
+                    column_specs: Vec::new(),
                     unique,
+                    tablespace,
                 },
             );
         }
@@ -911,48 +1424,175 @@ fn fetch_indexes(
 
     // Fetch index columns
     let sql = format!(
-        "SELECT ic.INDEX_NAME, ic.COLUMN_NAME \
+        "SELECT ic.TABLE_NAME, ic.INDEX_NAME, ic.COLUMN_NAME, {} \
          FROM ALL_IND_COLUMNS ic \
-         WHERE ic.INDEX_OWNER = '{}' AND ic.TABLE_NAME = '{}' \
-         ORDER BY ic.INDEX_NAME, ic.COLUMN_POSITION",
+         WHERE ic.INDEX_OWNER = '{}' AND {} \
+         ORDER BY ic.TABLE_NAME, ic.INDEX_NAME, ic.COLUMN_POSITION",
+        ServerCapabilities::column(capabilities.index_descend, "ic.DESCEND"),
         schema.replace("'", "''"),
-        table.replace("'", "''")
+        table_filter("ic.TABLE_NAME", tables)
     );
 
-    let mut column_cursor = match connection
-        .execute(&sql, ())
+    if let Some(mut column_cursor) = execute_read(connection, &sql)
         .context("Failed to query index columns")?
+    {
+        let mut col_buffers = TextRowSet::for_cursor(100, &mut column_cursor, Some(8192))?;
+        let mut col_row_set_cursor = column_cursor.bind_buffer(&mut col_buffers)?;
+
+        while let Some(batch) = col_row_set_cursor.fetch()? {
+            for row_index in 0..batch.num_rows() {
+                let (Some(table), Some(index_name)) =
+                    (batch.at_as_str(0, row_index)?, batch.at_as_str(1, row_index)?)
+                else {
+                    continue;
+                };
+                let column_name = match batch.at_as_str(2, row_index)? {
+                    Some(val) => val.to_string(),
+                    None => continue,
+                };
+
+                let descending = matches!(
+                    batch.at_as_str(3, row_index)?,
+                    Some(flag) if flag.trim().eq_ignore_ascii_case("DESC")
+                );
+
+                let key = (table.to_string(), index_name.to_string());
+                if let Some(index) = indexes.get_mut(&key) {
+                    index.columns.push(column_name);
+                    index.column_specs.push(IndexColumn {
+                        descending,
+                        expression: None,
+                    });
+                }
+            }
+        }
+    }
+
+    if capabilities.index_expressions {
+        for (table, index_name, position, expression) in
+            fetch_index_expressions(connection, schema, tables)?
+        {
+            let spec = indexes
+                .get_mut(&(table, index_name))
+                .and_then(|index| index.column_specs.get_mut(position.saturating_sub(1)));
+            if let Some(spec) = spec {
+                spec.expression = Some(expression);
+            }
+        }
+    }
+
+    let mut result: HashMap<String, Vec<Index>> = HashMap::new();
+    for key in order {
+        if let Some(mut index) = indexes.remove(&key) {
+            if index
+                .column_specs
+                .iter()
+                .all(|spec| !spec.descending && spec.expression.is_none())
+            {
+                index.column_specs.clear();
+            }
+            result.entry(key.0).or_default().push(index);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Returns `(table_name, index_name, column_position, expression)` for function-based index
+/// columns.
+fn fetch_index_expressions(
+    connection: &Connection<'_>,
+    schema: &str,
+    tables: &[String],
+) -> Result<Vec<(String, String, usize, String)>> {
+    let sql = format!(
+        "SELECT ie.TABLE_NAME, ie.INDEX_NAME, ie.COLUMN_POSITION, ie.COLUMN_EXPRESSION \
+         FROM ALL_IND_EXPRESSIONS ie \
+         WHERE ie.INDEX_OWNER = '{}' AND {} \
+         ORDER BY ie.TABLE_NAME, ie.INDEX_NAME, ie.COLUMN_POSITION",
+        schema.replace("'", "''"),
+        table_filter("ie.TABLE_NAME", tables)
+    );
+
+    let mut cursor = match execute_read(connection, &sql)
+        .context("Failed to query index expressions")?
     {
         Some(cursor) => cursor,
-        None => return Ok(order.into_iter().filter_map(|name| indexes.remove(&name)).collect()),
+        None => return Ok(Vec::new()),
     };
 
-    let mut col_buffers = TextRowSet::for_cursor(100, &mut column_cursor, Some(8192))?;
-    let mut col_row_set_cursor = column_cursor.bind_buffer(&mut col_buffers)?;
+    let mut buffers = TextRowSet::for_cursor(100, &mut cursor, Some(8192))?;
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-    while let Some(batch) = col_row_set_cursor.fetch()? {
+    let mut expressions = Vec::new();
+    while let Some(batch) = row_set_cursor.fetch()? {
         for row_index in 0..batch.num_rows() {
-            let index_name = match batch.at_as_str(0, row_index)? {
-                Some(val) => val,
+            let table = match batch.at_as_str(0, row_index)? {
+                Some(val) => val.to_string(),
                 None => continue,
             };
-            let column_name = match batch.at_as_str(1, row_index)? {
+            let index_name = match batch.at_as_str(1, row_index)? {
                 Some(val) => val.to_string(),
                 None => continue,
             };
-
-            if let Some(index) = indexes.get_mut(index_name) {
-                index.columns.push(column_name);
-            }
+            let position = match batch.at_as_str(2, row_index)?.and_then(|s| s.trim().parse::<usize>().ok()) {
+                Some(val) => val,
+                None => continue,
+            };
+            let expression = match batch.at_as_str(3, row_index)? {
+                Some(val) if !val.trim().is_empty() => val.trim().to_string(),
+                _ => continue,
+            };
+            expressions.push((table, index_name, position, expression));
         }
     }
 
-    let mut result = Vec::new();
-    for name in order {
-        if let Some(index) = indexes.remove(&name) {
-            result.push(index);
-        }
+    Ok(expressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{catalog_name, table_filter, trigger_query};
+    use crate::db::capabilities::ServerCapabilities;
+
+    #[test]
+    fn catalog_names_fold_unless_case_is_preserved() {
+        assert_eq!(catalog_name("Sales.Orders", false), "SALES.ORDERS");
+        assert_eq!(catalog_name("order_Items", true), "order_Items");
+        assert_eq!(catalog_name("APP", true), "APP");
     }
 
-    Ok(result)
+    #[test]
+    fn trigger_query_selects_all_columns_on_current_servers() {
+        let sql = trigger_query(&ServerCapabilities::default(), "APP", &["T1".to_string()]);
+        assert!(sql.contains("TRIGGER_TYPE, TRIGGERING_EVENT"));
+        assert!(sql.contains("WHEN_CLAUSE, TRIGGER_BODY, DESCRIPTION"));
+    }
+
+    #[test]
+    fn trigger_query_replaces_missing_columns_with_null() {
+        let capabilities = ServerCapabilities {
+            trigger_type: false,
+            trigger_when_clause: false,
+            ..ServerCapabilities::default()
+        };
+        let sql = trigger_query(&capabilities, "APP", &["O'NEIL".to_string()]);
+        assert!(sql.contains("NULL AS TRIGGER_TYPE"));
+        assert!(sql.contains("NULL AS WHEN_CLAUSE"));
+        assert!(sql.contains("TRIGGER_BODY, DESCRIPTION"));
+        assert!(sql.contains("TABLE_NAME = 'O''NEIL'"));
+    }
+
+    #[test]
+    fn table_filter_lists_several_tables() {
+        let tables = vec!["ORDERS".to_string(), "O'NEIL".to_string()];
+        assert_eq!(
+            table_filter("c.TABLE_NAME", &tables),
+            "c.TABLE_NAME IN ('ORDERS', 'O''NEIL')"
+        );
+        assert_eq!(
+            table_filter("TABLE_NAME", &tables[..1]),
+            "TABLE_NAME = 'ORDERS'"
+        );
+    }
 }
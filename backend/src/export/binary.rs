@@ -0,0 +1,622 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use chrono::Utc;
+use odbc_api::{buffers::TextColumn, handles::AsStatementRef, ColumnarBulkInserter, Connection};
+
+use crate::db::retry::RetryingConnection;
+use crate::db::schema::catalog_name;
+use crate::export::data::{
+    load_table_metadata, quote_identifier, stream_table_rows, DataExportOptions,
+};
+use crate::export::dependency::load_order;
+use crate::export::job::JobContext;
+use crate::export::manifest::{write_manifest, ChecksumWriter, MANIFEST_FILE};
+use crate::export::renames::renamed_table;
+use crate::export::subset::consistent_subset;
+use crate::models::{
+    DataFormat, ExportManifest, ImportReport, IssueCode, JobEventKind, ManifestColumn,
+    ManifestTable, TableImport,
+};
+
+/// First bytes of every table file, followed by the format version.
+const MAGIC: &[u8; 6] = b"DM8BIN";
+const FORMAT_VERSION: u8 = 1;
+/// Precedes every row.
+const ROW_TAG: u8 = 1;
+/// Follows the last row; a file without it was cut short.
+const END_TAG: u8 = 0;
+/// Length prefix of a NULL value.
+const NULL_LENGTH: u32 = u32::MAX;
+/// Magic, version byte and column count.
+const HEADER_LEN: u64 = MAGIC.len() as u64 + 1 + 4;
+
+/// Rows bound per array insert when the import request does not say otherwise.
+pub const DEFAULT_IMPORT_BATCH_SIZE: usize = 1000;
+
+/// Writes one table of a binary dump.
+///
+/// Layout: `DM8BIN`, a version byte and the column count (u32 LE); then per row a row tag and,
+/// for every column, its length (u32 LE, `u32::MAX` for NULL) followed by the value's text
+/// bytes; an end tag closes the file.
+pub struct BinaryTableWriter<W: Write> {
+    inner: W,
+    max_bytes: Vec<usize>,
+    rows: usize,
+}
+
+impl<W: Write> BinaryTableWriter<W> {
+    pub fn new(mut inner: W, column_count: usize) -> Result<Self> {
+        inner.write_all(MAGIC)?;
+        inner.write_all(&[FORMAT_VERSION])?;
+        inner.write_all(&length_prefix(column_count)?.to_le_bytes())?;
+        Ok(Self {
+            inner,
+            max_bytes: vec![0; column_count],
+            rows: 0,
+        })
+    }
+
+    pub fn write_row(&mut self, row: &[Option<String>]) -> Result<()> {
+        ensure!(
+            row.len() == self.max_bytes.len(),
+            "Row has {} values, the table has {} columns",
+            row.len(),
+            self.max_bytes.len()
+        );
+        self.inner.write_all(&[ROW_TAG])?;
+        for (value, max_bytes) in row.iter().zip(&mut self.max_bytes) {
+            match value {
+                None => self.inner.write_all(&NULL_LENGTH.to_le_bytes())?,
+                Some(value) => {
+                    self.inner
+                        .write_all(&length_prefix(value.len())?.to_le_bytes())?;
+                    self.inner.write_all(value.as_bytes())?;
+                    *max_bytes = (*max_bytes).max(value.len());
+                }
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Writes the end tag; returns the row count and the longest value of every column.
+    pub fn finish(mut self) -> Result<(usize, Vec<usize>)> {
+        self.inner.write_all(&[END_TAG])?;
+        self.inner.flush()?;
+        Ok((self.rows, self.max_bytes))
+    }
+}
+
+fn length_prefix(len: usize) -> Result<u32> {
+    u32::try_from(len)
+        .ok()
+        .filter(|&len| len != NULL_LENGTH)
+        .ok_or_else(|| anyhow!("Value of {} bytes is too long for a binary dump", len))
+}
+
+/// Reads a table file written by [`BinaryTableWriter`].
+pub struct BinaryTableReader<R: Read> {
+    inner: R,
+    column_count: usize,
+    /// Longest value accepted per column, checked before a value's buffer is allocated.
+    max_bytes: Option<Vec<usize>>,
+    /// Bytes of the file not read yet; a length prefix may not claim more.
+    remaining: u64,
+    finished: bool,
+}
+
+impl<R: Read> BinaryTableReader<R> {
+    pub fn open(mut inner: R) -> Result<Self> {
+        let mut magic = [0u8; 6];
+        inner
+            .read_exact(&mut magic)
+            .context("File is too short to be a binary dump")?;
+        ensure!(&magic == MAGIC, "File is not a DM8 binary dump");
+        let version = read_u8(&mut inner)?;
+        ensure!(
+            version == FORMAT_VERSION,
+            "Unsupported binary dump version {} (expected {})",
+            version,
+            FORMAT_VERSION
+        );
+        let column_count = read_u32(&mut inner)? as usize;
+        Ok(Self {
+            inner,
+            column_count,
+            max_bytes: None,
+            remaining: u64::MAX,
+            finished: false,
+        })
+    }
+
+    /// Rejects values longer than the manifest's `max_bytes` of their column or than what is
+    /// left of a file of `file_len` bytes, so a corrupt length prefix cannot allocate more.
+    pub fn with_limits(mut self, max_bytes: Vec<usize>, file_len: u64) -> Result<Self> {
+        ensure!(
+            max_bytes.len() == self.column_count,
+            "Binary dump has {} columns, {} limits given",
+            self.column_count,
+            max_bytes.len()
+        );
+        self.max_bytes = Some(max_bytes);
+        self.remaining = file_len.saturating_sub(HEADER_LEN);
+        Ok(self)
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.column_count
+    }
+
+    /// The next row's values as raw text bytes; `None` after the end tag.
+    pub fn next_row(&mut self) -> Result<Option<Vec<Option<Vec<u8>>>>> {
+        if self.finished {
+            return Ok(None);
+        }
+        let tag = read_u8(&mut self.inner).context("Binary dump ends without an end marker")?;
+        self.remaining = self.remaining.saturating_sub(1);
+        match tag {
+            END_TAG => {
+                self.finished = true;
+                Ok(None)
+            }
+            ROW_TAG => {
+                let mut row = Vec::with_capacity(self.column_count);
+                for index in 0..self.column_count {
+                    let len = read_u32(&mut self.inner).context("Binary dump row is truncated")?;
+                    self.remaining = self.remaining.saturating_sub(4);
+                    if len == NULL_LENGTH {
+                        row.push(None);
+                        continue;
+                    }
+                    if let Some(max_bytes) = &self.max_bytes {
+                        ensure!(
+                            len as usize <= max_bytes[index],
+                            "Value of {} bytes in column {} exceeds the manifest's {} bytes",
+                            len,
+                            index + 1,
+                            max_bytes[index]
+                        );
+                    }
+                    ensure!(
+                        u64::from(len) <= self.remaining,
+                        "Binary dump row is truncated: a value of {} bytes with {} bytes left",
+                        len,
+                        self.remaining
+                    );
+                    let mut value = vec![0u8; len as usize];
+                    self.inner
+                        .read_exact(&mut value)
+                        .context("Binary dump row is truncated")?;
+                    self.remaining -= u64::from(len);
+                    row.push(Some(value));
+                }
+                Ok(Some(row))
+            }
+            other => bail!("Corrupt binary dump: unexpected row tag {}", other),
+        }
+    }
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Table file name inside a dump directory: load position plus a file-system safe name.
+pub fn table_file_name(position: usize, table: &str, extension: &str) -> String {
+    let safe: String = table
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '$' | '#') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{:04}_{}.{}", position, safe, extension)
+}
+
+/// Dumps `tables` into `output_dir`, one binary file per table in foreign key load order, and
+/// writes the manifest describing files and columns next to them. Returns the exported row
+/// count and the manifest path.
+pub fn export_schema_binary(
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
+    source_schema: &str,
+    target_schema: &str,
+    tables: &[String],
+    output_dir: &Path,
+    options: &DataExportOptions,
+) -> Result<(usize, PathBuf)> {
+    let source_schema_name = catalog_name(source_schema, options.preserve_case);
+    let target_schema_name = catalog_name(target_schema, options.preserve_case);
+    let (table_details_list, _) =
+        load_table_metadata(session, job, &source_schema_name, tables, options)?;
+    let row_filters = consistent_subset(job, &source_schema_name, &table_details_list, options)?;
+
+    let load = load_order(&source_schema_name, &table_details_list);
+    if !load.cyclic.is_empty() {
+        job.warn(
+            IssueCode::ForeignKeyCycle,
+            None,
+            format!(
+                "Foreign key cycle between {}; these tables keep their requested order",
+                load.cyclic.join(", ")
+            ),
+        );
+    }
+
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create dump directory {}", output_dir.display()))?;
+
+    let mut exported_total = 0;
+    let mut manifest_tables = Vec::with_capacity(table_details_list.len());
+    for (position, &index) in load.order.iter().enumerate() {
+        let details = &table_details_list[index];
+        let source_table = details.name.clone();
+        let target_table = renamed_table(&options.table_renames, &source_table);
+        let file_name = table_file_name(position + 1, &target_table, "bin");
+
+        job.record(JobEventKind::TableStarted, Some(&source_table), None);
+        let path = output_dir.join(&file_name);
+        let mut file = BufWriter::new(
+            File::create(&path)
+                .with_context(|| format!("Failed to create dump file {}", path.display()))?,
+        );
+        let mut checksum_writer = ChecksumWriter::new(&mut file);
+        let mut writer = BinaryTableWriter::new(&mut checksum_writer, details.columns.len())?;
+        let (_, stats) = stream_table_rows(
+            session,
+            job,
+            &source_schema_name,
+            details,
+            row_filters.get(&details.name).map(String::as_str),
+            options,
+            &mut |rows| rows.iter().try_for_each(|row| writer.write_row(row)),
+        )
+        .with_context(|| format!("Failed to dump table '{}'", source_table))?;
+        let (rows, max_bytes) = writer.finish()?;
+        let checksum = checksum_writer.finish();
+        file.flush()
+            .with_context(|| format!("Failed to flush dump file {}", path.display()))?;
+
+        manifest_tables.push(ManifestTable {
+            table: target_table,
+            source_table: source_table.clone(),
+            rows,
+            checksum,
+            file: Some(file_name),
+            columns: details
+                .columns
+                .iter()
+                .zip(max_bytes)
+                .map(|(column, max_bytes)| ManifestColumn {
+                    name: column.name.clone(),
+                    data_type: column.data_type.clone(),
+                    identity: column.identity,
+                    max_bytes,
+                })
+                .collect(),
+        });
+        job.record(
+            JobEventKind::TableFinished,
+            Some(&source_table),
+            Some(format!("{} rows", rows)),
+        );
+        job.memory.push(stats);
+        exported_total += rows;
+    }
+
+    let manifest = ExportManifest {
+        job_id: job.job_id.clone(),
+        generated_at: Utc::now().to_rfc3339(),
+        source_schema: source_schema_name,
+        target_schema: target_schema_name,
+        format: DataFormat::Binary,
+        data_file: String::new(),
+        row_limit: None,
+        sample: options.sample.clone(),
+        tables: manifest_tables,
+    };
+    let manifest_path = output_dir.join(MANIFEST_FILE);
+    write_manifest(&manifest_path, &manifest)?;
+    Ok((exported_total, manifest_path))
+}
+
+/// Loads every table of a binary dump into `schema` on the target, `batch_size` rows per ODBC
+/// array insert, in manifest (load) order.
+pub fn import_binary_dump(
+    connection: &Connection<'_>,
+    manifest: &ExportManifest,
+    dump_dir: &Path,
+    schema: &str,
+    batch_size: usize,
+) -> Result<ImportReport> {
+    ensure!(
+        manifest.format == DataFormat::Binary,
+        "Manifest does not describe a binary dump"
+    );
+    ensure!(batch_size > 0, "batch_size must be greater than zero");
+
+    let mut tables = Vec::with_capacity(manifest.tables.len());
+    for table in &manifest.tables {
+        let rows = import_table(connection, table, dump_dir, schema, batch_size)
+            .with_context(|| format!("Failed to load table '{}'", table.table))?;
+        ensure!(
+            rows == table.rows,
+            "Loaded {} rows into '{}', the manifest lists {}",
+            rows,
+            table.table,
+            table.rows
+        );
+        tables.push(TableImport {
+            table: table.table.clone(),
+            rows,
+        });
+    }
+
+    Ok(ImportReport {
+        total_rows: tables.iter().map(|t| t.rows).sum(),
+        tables,
+    })
+}
+
+fn import_table(
+    connection: &Connection<'_>,
+    table: &ManifestTable,
+    dump_dir: &Path,
+    schema: &str,
+    batch_size: usize,
+) -> Result<usize> {
+    let file = table
+        .file
+        .as_deref()
+        .ok_or_else(|| anyhow!("Manifest entry names no binary file"))?;
+    let path = dump_file_path(dump_dir, file)?;
+    let file = File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let file_len = file.metadata()?.len();
+    let reader = BinaryTableReader::open(BufReader::new(file))?;
+    ensure!(
+        reader.column_count() == table.columns.len(),
+        "{} has {} columns, the manifest lists {}",
+        path.display(),
+        reader.column_count(),
+        table.columns.len()
+    );
+    let max_lengths: Vec<usize> = table
+        .columns
+        .iter()
+        .map(|column| column.max_bytes.max(1))
+        .collect();
+    let mut reader = reader.with_limits(max_lengths.clone(), file_len)?;
+
+    let qualified = quote_identifier(&format!("{}.{}", schema, table.table));
+    let columns: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column| quote_identifier(&column.name))
+        .collect();
+    let insert = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        qualified,
+        columns.join(", "),
+        vec!["?"; columns.len()].join(", ")
+    );
+    let identity = table.columns.iter().any(|column| column.identity);
+    if !identity {
+        return load_rows(
+            connection,
+            &insert,
+            &mut reader,
+            table,
+            &max_lengths,
+            batch_size,
+        );
+    }
+
+    connection.execute(&format!("SET IDENTITY_INSERT {} ON", qualified), ())?;
+    let loaded = load_rows(
+        connection,
+        &insert,
+        &mut reader,
+        table,
+        &max_lengths,
+        batch_size,
+    );
+    // Turned off after a failed load too: the session goes on to the next table.
+    let off = connection.execute(&format!("SET IDENTITY_INSERT {} OFF", qualified), ());
+    let loaded = loaded?;
+    off?;
+    Ok(loaded)
+}
+
+/// Resolves a manifest's file name inside `dump_dir`; it may not be absolute or leave the
+/// directory.
+fn dump_file_path(dump_dir: &Path, file: &str) -> Result<PathBuf> {
+    let name = Path::new(file);
+    ensure!(
+        !file.is_empty() && name.components().all(|c| matches!(c, Component::Normal(_))),
+        "Manifest file name '{}' must be a relative path inside the dump directory",
+        file
+    );
+    Ok(dump_dir.join(name))
+}
+
+/// Inserts the rows of `reader` with `insert`, `batch_size` per array insert.
+fn load_rows<R: Read>(
+    connection: &Connection<'_>,
+    insert: &str,
+    reader: &mut BinaryTableReader<R>,
+    table: &ManifestTable,
+    max_lengths: &[usize],
+    batch_size: usize,
+) -> Result<usize> {
+    let mut inserter = connection
+        .prepare(insert)?
+        .into_text_inserter(batch_size, max_lengths.iter().copied())?;
+
+    let mut loaded = 0;
+    let mut rows = Vec::with_capacity(batch_size);
+    loop {
+        let row = reader.next_row()?;
+        let done = row.is_none();
+        rows.extend(row);
+        if rows.len() == batch_size || (done && !rows.is_empty()) {
+            insert_batch(&mut inserter, table, max_lengths, &rows)?;
+            loaded += rows.len();
+            rows.clear();
+        }
+        if done {
+            break;
+        }
+    }
+    Ok(loaded)
+}
+
+/// Binds `rows` to the inserter's column buffers and executes one array insert.
+fn insert_batch<S>(
+    inserter: &mut ColumnarBulkInserter<S, TextColumn<u8>>,
+    table: &ManifestTable,
+    max_lengths: &[usize],
+    rows: &[Vec<Option<Vec<u8>>>],
+) -> Result<()>
+where
+    S: AsStatementRef,
+{
+    inserter.set_num_rows(rows.len());
+    for (index, &max_length) in max_lengths.iter().enumerate() {
+        let mut column = inserter.column_mut(index);
+        for (row_index, row) in rows.iter().enumerate() {
+            let value = row[index].as_deref();
+            if let Some(value) = value {
+                ensure!(
+                    value.len() <= max_length,
+                    "Value of {} bytes in column {} exceeds the manifest's {} bytes",
+                    value.len(),
+                    table.columns[index].name,
+                    max_length
+                );
+            }
+            column.set_cell(row_index, value);
+        }
+    }
+    inserter.execute()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{dump_file_path, table_file_name, BinaryTableReader, BinaryTableWriter};
+
+    #[test]
+    fn table_files_round_trip_values_and_nulls() {
+        let mut out = Vec::new();
+        let mut writer = BinaryTableWriter::new(&mut out, 3).unwrap();
+        writer
+            .write_row(&[Some("1".to_string()), None, Some("名称".to_string())])
+            .unwrap();
+        writer
+            .write_row(&[Some("2".to_string()), Some(String::new()), None])
+            .unwrap();
+        assert_eq!(writer.finish().unwrap(), (2, vec![1, 0, 6]));
+
+        let mut reader = BinaryTableReader::open(out.as_slice()).unwrap();
+        assert_eq!(reader.column_count(), 3);
+        assert_eq!(
+            reader.next_row().unwrap(),
+            Some(vec![
+                Some(b"1".to_vec()),
+                None,
+                Some("名称".as_bytes().to_vec())
+            ])
+        );
+        assert_eq!(
+            reader.next_row().unwrap(),
+            Some(vec![Some(b"2".to_vec()), Some(Vec::new()), None])
+        );
+        assert_eq!(reader.next_row().unwrap(), None);
+        assert_eq!(reader.next_row().unwrap(), None);
+    }
+
+    #[test]
+    fn truncated_and_foreign_files_are_rejected() {
+        let mut out = Vec::new();
+        let mut writer = BinaryTableWriter::new(&mut out, 1).unwrap();
+        writer.write_row(&[Some("abc".to_string())]).unwrap();
+        writer.finish().unwrap();
+
+        let truncated = &out[..out.len() - 3];
+        let mut reader = BinaryTableReader::open(truncated).unwrap();
+        assert!(reader.next_row().is_err());
+
+        let without_end = &out[..out.len() - 1];
+        let mut reader = BinaryTableReader::open(without_end).unwrap();
+        assert!(reader.next_row().unwrap().is_some());
+        assert!(reader.next_row().is_err());
+
+        assert!(BinaryTableReader::open(&b"INSERT INTO"[..]).is_err());
+    }
+
+    #[test]
+    fn value_lengths_are_checked_before_reading() {
+        let mut out = Vec::new();
+        let mut writer = BinaryTableWriter::new(&mut out, 1).unwrap();
+        writer.write_row(&[Some("abcd".to_string())]).unwrap();
+        writer.finish().unwrap();
+        let len = out.len() as u64;
+
+        let reader = BinaryTableReader::open(out.as_slice()).unwrap();
+        let mut reader = reader.with_limits(vec![3], len).unwrap();
+        let err = reader.next_row().unwrap_err();
+        assert!(err.to_string().contains("exceeds the manifest's 3 bytes"));
+
+        // A length prefix claiming 4 GB in a file of a few bytes.
+        let mut forged = out[..12].to_vec();
+        forged.extend_from_slice(&(u32::MAX - 1).to_le_bytes());
+        let reader = BinaryTableReader::open(forged.as_slice()).unwrap();
+        let mut reader = reader
+            .with_limits(vec![usize::MAX], forged.len() as u64)
+            .unwrap();
+        let err = reader.next_row().unwrap_err();
+        assert!(err.to_string().contains("is truncated"));
+    }
+
+    #[test]
+    fn manifest_file_names_stay_in_the_dump_directory() {
+        let dir = Path::new("/dumps/job");
+        assert_eq!(
+            dump_file_path(dir, "0001_ORDERS.bin").unwrap(),
+            dir.join("0001_ORDERS.bin")
+        );
+        for file in [
+            "",
+            "/etc/passwd",
+            "../0001_ORDERS.bin",
+            "a/../../b.bin",
+            "./x.bin",
+        ] {
+            assert!(dump_file_path(dir, file).is_err(), "{}", file);
+        }
+    }
+
+    #[test]
+    fn file_names_keep_load_order_and_are_path_safe() {
+        assert_eq!(table_file_name(3, "ORDERS", "bin"), "0003_ORDERS.bin");
+        assert_eq!(table_file_name(12, "A/B C", "bin"), "0012_A_B_C.bin");
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+
+use crate::export::encoding::decode_line;
+use crate::export::job::JobContext;
+use crate::models::{ExportEncoding, IssueCode};
+
+/// Encoding problems found in the part of an export file that belongs to one table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharsetFinding {
+    /// Table whose section contains the problem; `None` for the file header.
+    pub table: Option<String>,
+    /// Lines that are not valid in the file's encoding.
+    pub invalid_lines: usize,
+    /// Lines containing U+FFFD, which the ODBC text conversion emits for unmappable bytes.
+    pub replacement_lines: usize,
+    /// 1-based number of the first affected line.
+    pub first_line: usize,
+}
+
+/// Re-reads a written export file and reports, per table section, lines that are not valid in
+/// the file's encoding or contain the Unicode replacement character.
+///
+/// Sections start at the `-- Data for table:` (data) and `-- 表:` (DDL) marker comments.
+pub fn scan_export_file(path: &Path, encoding: ExportEncoding) -> Result<Vec<CharsetFinding>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open {} for charset check", path.display()))?;
+    scan_lines(BufReader::new(file), encoding)
+        .with_context(|| format!("Failed to read {} for charset check", path.display()))
+}
+
+fn scan_lines(mut reader: impl BufRead, encoding: ExportEncoding) -> Result<Vec<CharsetFinding>> {
+    let mut findings: Vec<CharsetFinding> = Vec::new();
+    let mut table: Option<String> = None;
+    let mut line = Vec::new();
+    let mut line_number = 0;
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        let (invalid, replacement) = match decode_line(&line, encoding) {
+            Some(text) => {
+                if let Some(name) = section_table(&text) {
+                    table = Some(name);
+                    continue;
+                }
+                (false, text.contains('\u{FFFD}'))
+            }
+            None => (true, false),
+        };
+        if !invalid && !replacement {
+            continue;
+        }
+
+        let index = match findings.iter().position(|f| f.table == table) {
+            Some(index) => index,
+            None => {
+                findings.push(CharsetFinding {
+                    table: table.clone(),
+                    invalid_lines: 0,
+                    replacement_lines: 0,
+                    first_line: line_number,
+                });
+                findings.len() - 1
+            }
+        };
+        if invalid {
+            findings[index].invalid_lines += 1;
+        } else {
+            findings[index].replacement_lines += 1;
+        }
+    }
+
+    Ok(findings)
+}
+
+fn section_table(line: &str) -> Option<String> {
+    let line = line.trim_end();
+    if let Some(rest) = line.strip_prefix("-- Data for table: ") {
+        let name = rest.split(" (").next().unwrap_or(rest);
+        return Some(name.trim().to_string());
+    }
+    line.strip_prefix("-- 表: ")
+        .map(|rest| rest.trim().replace('"', ""))
+}
+
+/// Runs the charset check on a finished export and records one job warning per affected table.
+/// Returns the number of affected sections; a failed check is itself only a warning.
+pub fn check_export_file(job: &mut JobContext, path: &Path, encoding: ExportEncoding) -> usize {
+    let findings = match scan_export_file(path, encoding) {
+        Ok(findings) => findings,
+        Err(e) => {
+            job.warn(
+                IssueCode::CharsetCheckSkipped,
+                None,
+                format!("Charset check skipped: {:#}", e),
+            );
+            return 0;
+        }
+    };
+
+    for finding in &findings {
+        let mut problems = Vec::new();
+        if finding.invalid_lines > 0 {
+            problems.push(format!(
+                "{} line(s) with invalid {}",
+                finding.invalid_lines,
+                encoding.char_code()
+            ));
+        }
+        if finding.replacement_lines > 0 {
+            problems.push(format!(
+                "{} line(s) with U+FFFD replacement characters",
+                finding.replacement_lines
+            ));
+        }
+        let subject = finding.table.as_deref().unwrap_or("file header");
+        job.warn(
+            IssueCode::CharsetCorruption,
+            finding.table.as_deref(),
+            format!(
+                "Possible charset corruption in {}: {} (first at line {})",
+                subject,
+                problems.join(", "),
+                finding.first_line
+            ),
+        );
+    }
+    findings.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scan_lines, CharsetFinding};
+    use crate::models::ExportEncoding;
+
+    #[test]
+    fn scan_attributes_findings_to_table_sections() {
+        let mut content = Vec::new();
+        content.extend_from_slice(b"-- DM8 Data Export\n");
+        content.extend_from_slice(b"-- Data for table: APP.USERS (2 rows)\n");
+        content.extend_from_slice("INSERT INTO t VALUES ('ok');\n".as_bytes());
+        content.extend_from_slice("INSERT INTO t VALUES ('\u{FFFD}\u{FFFD}');\n".as_bytes());
+        content.extend_from_slice(b"-- Data for table: APP.ORDERS (rows unknown)\n");
+        content.extend_from_slice(b"INSERT INTO t VALUES ('\xC4\xE3');\n");
+        content.extend_from_slice("-- 表: \"APP\".\"ITEMS\"\n".as_bytes());
+        content.extend_from_slice(
+            "CREATE TABLE \"APP\".\"ITEMS\" (\"名称\" VARCHAR(10));\n".as_bytes(),
+        );
+
+        let findings = scan_lines(content.as_slice(), ExportEncoding::Utf8).unwrap();
+        assert_eq!(
+            findings,
+            vec![
+                CharsetFinding {
+                    table: Some("APP.USERS".to_string()),
+                    invalid_lines: 0,
+                    replacement_lines: 1,
+                    first_line: 4,
+                },
+                CharsetFinding {
+                    table: Some("APP.ORDERS".to_string()),
+                    invalid_lines: 1,
+                    replacement_lines: 0,
+                    first_line: 6,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn scan_reads_ddl_section_markers() {
+        let content = "-- 表: \"APP\".\"ITEMS\"\nCOMMENT ON TABLE x IS '\u{FFFD}';\n";
+        let findings = scan_lines(content.as_bytes(), ExportEncoding::Utf8).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].table.as_deref(), Some("APP.ITEMS"));
+    }
+}
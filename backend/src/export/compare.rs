@@ -0,0 +1,351 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{ensure, Context, Result};
+
+use crate::models::{ChangedStatement, CompareResponse, StatementEntry};
+
+/// Compare two DDL artifacts statement by statement.
+///
+/// Statements are matched by the object they define (table, index, constraint, comment,
+/// sequence, trigger), so a column change inside `CREATE TABLE` shows up as "changed"
+/// rather than as one removed and one added statement. Both files must lie below one of
+/// `export_dirs`.
+pub fn compare_ddl_files(
+    left: &Path,
+    right: &Path,
+    export_dirs: &[PathBuf],
+) -> Result<CompareResponse> {
+    let left_script = read_artifact(left, export_dirs)?;
+    let right_script = read_artifact(right, export_dirs)?;
+    Ok(compare_ddl_scripts(&left_script, &right_script))
+}
+
+fn read_artifact(path: &Path, export_dirs: &[PathBuf]) -> Result<String> {
+    ensure!(
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("sql")),
+        "Artifact must be a .sql file: {}",
+        path.display()
+    );
+    // Resolved first, so neither `..` nor a symlink leads out of the export directories.
+    let resolved = path
+        .canonicalize()
+        .with_context(|| format!("Failed to read export artifact {}", path.display()))?;
+    ensure!(
+        export_dirs
+            .iter()
+            .filter_map(|dir| dir.canonicalize().ok())
+            .any(|dir| resolved.starts_with(dir)),
+        "Artifact {} is not in an export output directory",
+        path.display()
+    );
+    fs::read_to_string(&resolved)
+        .with_context(|| format!("Failed to read export artifact {}", path.display()))
+}
+
+pub fn compare_ddl_scripts(left: &str, right: &str) -> CompareResponse {
+    let left_statements = keyed_statements(left);
+    let right_statements = keyed_statements(right);
+
+    let left_map: HashMap<&str, &str> = left_statements
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    let right_map: HashMap<&str, &str> = right_statements
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    let mut unchanged_count = 0;
+
+    for (key, statement) in &right_statements {
+        match left_map.get(key.as_str()) {
+            None => added.push(StatementEntry {
+                key: key.clone(),
+                statement: statement.clone(),
+            }),
+            Some(before) if *before != statement.as_str() => changed.push(ChangedStatement {
+                key: key.clone(),
+                before: before.to_string(),
+                after: statement.clone(),
+            }),
+            Some(_) => unchanged_count += 1,
+        }
+    }
+
+    let removed = left_statements
+        .iter()
+        .filter(|(key, _)| !right_map.contains_key(key.as_str()))
+        .map(|(key, statement)| StatementEntry {
+            key: key.clone(),
+            statement: statement.clone(),
+        })
+        .collect();
+
+    CompareResponse {
+        added,
+        removed,
+        changed,
+        unchanged_count,
+    }
+}
+
+/// Split a script into statements and attach an identity key to each.
+/// Duplicate keys keep the last definition, matching the effect of running the script.
+fn keyed_statements(script: &str) -> Vec<(String, String)> {
+    let mut result: Vec<(String, String)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for statement in split_statements(script) {
+        let normalized = normalize_whitespace(&statement);
+        let key = statement_key(&normalized);
+        if let Some(&pos) = positions.get(&key) {
+            result[pos].1 = normalized;
+        } else {
+            positions.insert(key.clone(), result.len());
+            result.push((key, normalized));
+        }
+    }
+
+    result
+}
+
+/// Split a SQL script into statements, ignoring `--` comment lines.
+///
+/// PL/SQL blocks (triggers, procedures) end either at a standalone `/` line or at the
+/// `END;` that closes the outermost `BEGIN`; other statements end at `;` outside quotes.
+pub fn split_statements(script: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut in_block = false;
+    let mut block_depth = 0i32;
+    let mut block_opened = false;
+    // Literals may span lines, e.g. a multi-line comment text.
+    let mut in_quote = false;
+
+    for line in script.lines() {
+        let trimmed = line.trim();
+
+        // Inside a literal every line is part of the value.
+        if !in_quote {
+            if current.is_empty() && trimmed.is_empty() {
+                continue;
+            }
+            if !in_block && trimmed.starts_with("--") {
+                continue;
+            }
+            if trimmed == "/" {
+                push_statement(&mut statements, &mut current);
+                in_block = false;
+                continue;
+            }
+        }
+
+        if current.is_empty() {
+            in_block = is_block_start(trimmed);
+            block_depth = 0;
+            block_opened = false;
+        }
+
+        if in_block {
+            if !current.is_empty() {
+                current.push('\n');
+            }
+            current.push_str(line.trim_end());
+
+            let upper = trimmed.to_uppercase();
+            let begins = upper
+                .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .filter(|word| *word == "BEGIN")
+                .count() as i32;
+            if begins > 0 {
+                block_depth += begins;
+                block_opened = true;
+            }
+            if is_block_end(&upper) {
+                block_depth -= 1;
+                if block_opened && block_depth <= 0 {
+                    push_statement(&mut statements, &mut current);
+                    in_block = false;
+                }
+            }
+            continue;
+        }
+
+        for ch in line.chars() {
+            if ch == '\'' {
+                in_quote = !in_quote;
+            }
+            if ch == ';' && !in_quote {
+                current.push(ch);
+                push_statement(&mut statements, &mut current);
+                continue;
+            }
+            if current.is_empty() && ch.is_whitespace() {
+                continue;
+            }
+            current.push(ch);
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+    }
+
+    push_statement(&mut statements, &mut current);
+    statements
+}
+
+fn push_statement(statements: &mut Vec<String>, current: &mut String) {
+    let statement = current.trim();
+    if !statement.is_empty() {
+        statements.push(statement.to_string());
+    }
+    current.clear();
+}
+
+fn is_block_start(line: &str) -> bool {
+    let upper = normalize_whitespace(line).to_uppercase();
+    ["TRIGGER", "PROCEDURE", "FUNCTION", "PACKAGE"]
+        .iter()
+        .any(|kind| {
+            upper.starts_with(&format!("CREATE {}", kind))
+                || upper.starts_with(&format!("CREATE OR REPLACE {}", kind))
+        })
+}
+
+fn is_block_end(upper_line: &str) -> bool {
+    if !upper_line.starts_with("END") {
+        return false;
+    }
+    let rest = upper_line[3..].trim_start();
+    if rest.starts_with("IF") || rest.starts_with("LOOP") || rest.starts_with("CASE") {
+        return false;
+    }
+    upper_line.ends_with(';')
+}
+
+fn normalize_whitespace(statement: &str) -> String {
+    statement.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Derive an object identity key for a normalized statement.
+/// Unrecognized statements are keyed by their own text.
+fn statement_key(statement: &str) -> String {
+    let tokens: Vec<&str> = statement.split(' ').collect();
+    let upper: Vec<String> = tokens.iter().map(|t| t.to_uppercase()).collect();
+    let word = |i: usize| upper.get(i).map(String::as_str).unwrap_or("");
+    let ident = |i: usize| {
+        tokens
+            .get(i)
+            .map(|t| t.trim_end_matches([';', '(']).replace('"', ""))
+            .unwrap_or_default()
+    };
+
+    match (word(0), word(1), word(2)) {
+        ("CREATE", "TABLE", _) => format!("TABLE {}", ident(2)),
+        ("CREATE", "INDEX", _) => format!("INDEX {}", ident(2)),
+        ("CREATE", "UNIQUE", "INDEX") => format!("INDEX {}", ident(3)),
+        ("CREATE", "SEQUENCE", _) => format!("SEQUENCE {}", ident(2)),
+        ("CREATE", "TRIGGER", _) => format!("TRIGGER {}", ident(2)),
+        ("CREATE", "OR", "REPLACE") => format!("{} {}", word(3), ident(4)),
+        ("DROP", "TABLE", "IF") => format!("DROP TABLE {}", ident(4)),
+        ("DROP", kind, _) => format!("DROP {} {}", kind, ident(2)),
+        ("COMMENT", "ON", kind) => format!("COMMENT {} {}", kind, ident(3)),
+        ("ALTER", "TABLE", _) if word(3) == "ADD" && word(4) == "CONSTRAINT" => {
+            format!("CONSTRAINT {}.{}", ident(2), ident(5))
+        }
+        _ => statement.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_ddl_files, compare_ddl_scripts, split_statements};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn split_statements_keeps_trigger_body_together() {
+        let script = "-- header\nCREATE TABLE \"A\" (\n    \"ID\" INT NOT NULL\n);\n\nCREATE OR REPLACE TRIGGER \"S\".\"TRG\"\nBEFORE INSERT ON \"S\".\"A\"\nBEGIN\nIF 1 = 1 THEN\nNULL;\nEND IF;\nEND;\n/\nCREATE INDEX \"I1\" ON \"A\" (\"ID\");";
+        let statements = split_statements(script);
+        assert_eq!(statements.len(), 3, "{:?}", statements);
+        assert!(statements[1].starts_with("CREATE OR REPLACE TRIGGER"));
+        assert!(statements[1].ends_with("END;"));
+        assert!(statements[2].starts_with("CREATE INDEX"));
+    }
+
+    #[test]
+    fn split_statements_ignores_semicolons_in_literals() {
+        let script = "COMMENT ON TABLE \"A\" IS 'a;b';\nCOMMENT ON COLUMN \"A\".\"ID\" IS 'x';";
+        let statements = split_statements(script);
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0], "COMMENT ON TABLE \"A\" IS 'a;b';");
+    }
+
+    #[test]
+    fn split_statements_keeps_quote_state_across_lines() {
+        let script = "COMMENT ON TABLE \"A\" IS 'first;\n-- not a comment\n\nlast';\nCREATE INDEX \"I1\" ON \"A\" (\"ID\");";
+        let statements = split_statements(script);
+        assert_eq!(statements.len(), 2, "{:?}", statements);
+        assert_eq!(
+            statements[0],
+            "COMMENT ON TABLE \"A\" IS 'first;\n-- not a comment\n\nlast';"
+        );
+        assert!(statements[1].starts_with("CREATE INDEX"));
+    }
+
+    #[test]
+    fn only_files_in_export_directories_are_compared() {
+        let dir = TempDir::new().unwrap();
+        let exports = dir.path().join("exports");
+        let job = exports.join("job_1");
+        fs::create_dir_all(&job).unwrap();
+        let inside = job.join("a.sql");
+        fs::write(&inside, "CREATE TABLE \"A\" (\"ID\" INT);").unwrap();
+        let outside = dir.path().join("secret.sql");
+        fs::write(&outside, "CREATE TABLE \"B\" (\"ID\" INT);").unwrap();
+        let export_dirs = [exports.clone()];
+
+        let diff = compare_ddl_files(&inside, &inside, &export_dirs).unwrap();
+        assert_eq!(diff.unchanged_count, 1);
+
+        let err = compare_ddl_files(&inside, &outside, &export_dirs).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("not in an export output directory"));
+        let escaped = job.join("..").join("..").join("secret.sql");
+        assert!(compare_ddl_files(&inside, &escaped, &export_dirs).is_err());
+    }
+
+    #[test]
+    fn compare_reports_added_removed_and_changed_statements() {
+        let left = "CREATE TABLE \"S\".\"A\" (\n    \"ID\" INT NOT NULL\n);\nCREATE INDEX \"I_OLD\" ON \"S\".\"A\" (\"ID\");\nCOMMENT ON TABLE \"S\".\"A\" IS 'same';";
+        let right = "CREATE TABLE \"S\".\"A\" (\n    \"ID\" BIGINT NOT NULL\n);\nCREATE INDEX \"I_NEW\" ON \"S\".\"A\" (\"ID\");\nCOMMENT ON TABLE \"S\".\"A\" IS 'same';";
+
+        let diff = compare_ddl_scripts(left, right);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].key, "TABLE S.A");
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].key, "INDEX I_NEW");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].key, "INDEX I_OLD");
+        assert_eq!(diff.unchanged_count, 1);
+    }
+
+    #[test]
+    fn compare_ignores_whitespace_only_differences() {
+        let left = "ALTER TABLE \"S\".\"A\" ADD CONSTRAINT \"PK_A\" PRIMARY KEY (\"ID\");";
+        let right = "ALTER TABLE \"S\".\"A\"   ADD CONSTRAINT \"PK_A\"\nPRIMARY KEY (\"ID\");";
+        let diff = compare_ddl_scripts(left, right);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.is_empty());
+        assert_eq!(diff.unchanged_count, 1);
+    }
+}
@@ -0,0 +1,292 @@
+use std::{fs::File, io::Read, path::Path};
+
+use anyhow::{bail, ensure, Context, Result};
+use csv::{ReaderBuilder, StringRecord};
+use odbc_api::{buffers::TextColumn, handles::AsStatementRef, ColumnarBulkInserter, Connection};
+
+use crate::export::data::quote_identifier;
+use crate::models::{CsvColumnMapping, ImportReport, TableImport};
+
+/// Rows bound per array insert when the request does not say otherwise.
+pub const DEFAULT_CSV_BATCH_SIZE: usize = 1000;
+
+/// How a CSV file is split into fields and which fields are NULL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvFormat {
+    pub delimiter: u8,
+    pub has_header: bool,
+    /// Field text loaded as NULL; an empty field when `None`.
+    pub null_value: Option<String>,
+}
+
+impl Default for CsvFormat {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_header: true,
+            null_value: None,
+        }
+    }
+}
+
+impl CsvFormat {
+    fn is_null(&self, field: &str) -> bool {
+        match &self.null_value {
+            Some(marker) => field == marker,
+            None => field.is_empty(),
+        }
+    }
+}
+
+/// CSV field index and target column of every loaded column.
+///
+/// A mapping source names a header field (case-insensitive) or a 1-based field position.
+/// Without a mapping every header field is loaded into the column of the same name. Target
+/// names are upper-cased like every other identifier this tool writes.
+fn resolve_mapping(
+    headers: Option<&StringRecord>,
+    mapping: &[CsvColumnMapping],
+) -> Result<Vec<(usize, String)>> {
+    if mapping.is_empty() {
+        let Some(headers) = headers else {
+            bail!("A column mapping is required for CSV files without a header row");
+        };
+        return Ok(headers
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (index, name.trim().to_uppercase()))
+            .collect());
+    }
+
+    mapping
+        .iter()
+        .map(|entry| {
+            let source = entry.source.trim();
+            let by_name = headers.and_then(|headers| {
+                headers
+                    .iter()
+                    .position(|name| name.trim().eq_ignore_ascii_case(source))
+            });
+            let index = match by_name {
+                Some(index) => index,
+                None => match source.parse::<usize>() {
+                    Ok(position) if position > 0 => position - 1,
+                    _ => bail!("CSV column '{}' not found", source),
+                },
+            };
+            Ok((index, entry.target.trim().to_uppercase()))
+        })
+        .collect()
+}
+
+/// Loads the CSV file at `path` into `schema.table`, `batch_size` rows per ODBC array insert.
+pub fn import_csv_file(
+    connection: &Connection<'_>,
+    path: &Path,
+    schema: &str,
+    table: &str,
+    mapping: &[CsvColumnMapping],
+    format: &CsvFormat,
+    batch_size: usize,
+) -> Result<ImportReport> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    import_csv(connection, file, schema, table, mapping, format, batch_size)
+}
+
+/// Loads CSV data from `input` into `schema.table`. Column buffers start at the longest value
+/// of the first batch and the statement is re-prepared with larger buffers when a later batch
+/// holds a longer value.
+pub fn import_csv<R: Read>(
+    connection: &Connection<'_>,
+    input: R,
+    schema: &str,
+    table: &str,
+    mapping: &[CsvColumnMapping],
+    format: &CsvFormat,
+    batch_size: usize,
+) -> Result<ImportReport> {
+    ensure!(batch_size > 0, "batch_size must be greater than zero");
+    ensure!(!table.trim().is_empty(), "Target table is required");
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(format.delimiter)
+        .has_headers(format.has_header)
+        .from_reader(input);
+    let headers = if format.has_header {
+        Some(
+            reader
+                .headers()
+                .context("Failed to read the CSV header")?
+                .clone(),
+        )
+    } else {
+        None
+    };
+    let columns = resolve_mapping(headers.as_ref(), mapping)?;
+    ensure!(!columns.is_empty(), "No columns to import");
+
+    let target = quote_identifier(&format!("{}.{}", schema, table));
+    let insert = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        target,
+        columns
+            .iter()
+            .map(|(_, name)| quote_identifier(name))
+            .collect::<Vec<_>>()
+            .join(", "),
+        vec!["?"; columns.len()].join(", ")
+    );
+
+    let mut max_lengths = vec![1; columns.len()];
+    let mut inserter = None;
+    let mut loaded = 0;
+    let mut rows: Vec<Vec<Option<String>>> = Vec::with_capacity(batch_size);
+    let mut records = reader.records();
+    loop {
+        let record = records.next().transpose().context("Failed to parse CSV")?;
+        let done = record.is_none();
+        if let Some(record) = record {
+            let line = record.position().map_or(0, |position| position.line());
+            let row = columns
+                .iter()
+                .map(|&(index, _)| match record.get(index) {
+                    Some(field) if format.is_null(field) => Ok(None),
+                    Some(field) => Ok(Some(field.to_string())),
+                    None => bail!("CSV line {} has no field {}", line, index + 1),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            rows.push(row);
+        }
+        if rows.len() == batch_size || (done && !rows.is_empty()) {
+            let needed = longest_values(&rows);
+            if inserter.is_none() || needed.iter().zip(&max_lengths).any(|(n, m)| n > m) {
+                for (max, need) in max_lengths.iter_mut().zip(needed) {
+                    *max = (*max).max(need);
+                }
+                inserter = Some(
+                    connection
+                        .prepare(&insert)?
+                        .into_text_inserter(batch_size, max_lengths.iter().copied())?,
+                );
+            }
+            if let Some(inserter) = inserter.as_mut() {
+                insert_batch(inserter, &rows).with_context(|| {
+                    format!(
+                        "Failed to insert rows {}..{}",
+                        loaded + 1,
+                        loaded + rows.len()
+                    )
+                })?;
+            }
+            loaded += rows.len();
+            rows.clear();
+        }
+        if done {
+            break;
+        }
+    }
+
+    Ok(ImportReport {
+        tables: vec![TableImport {
+            table: table.to_string(),
+            rows: loaded,
+        }],
+        total_rows: loaded,
+    })
+}
+
+/// Longest value in bytes of every column of `rows`, at least 1.
+fn longest_values(rows: &[Vec<Option<String>>]) -> Vec<usize> {
+    let width = rows.first().map_or(0, Vec::len);
+    (0..width)
+        .map(|index| {
+            rows.iter()
+                .filter_map(|row| row[index].as_ref().map(String::len))
+                .max()
+                .unwrap_or(0)
+                .max(1)
+        })
+        .collect()
+}
+
+fn insert_batch<S>(
+    inserter: &mut ColumnarBulkInserter<S, TextColumn<u8>>,
+    rows: &[Vec<Option<String>>],
+) -> Result<()>
+where
+    S: AsStatementRef,
+{
+    inserter.set_num_rows(rows.len());
+    let width = rows.first().map_or(0, Vec::len);
+    for index in 0..width {
+        let mut column = inserter.column_mut(index);
+        for (row_index, row) in rows.iter().enumerate() {
+            column.set_cell(row_index, row[index].as_deref().map(str::as_bytes));
+        }
+    }
+    inserter.execute()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{longest_values, resolve_mapping, CsvFormat};
+    use crate::models::CsvColumnMapping;
+    use csv::StringRecord;
+
+    fn mapping(pairs: &[(&str, &str)]) -> Vec<CsvColumnMapping> {
+        pairs
+            .iter()
+            .map(|(source, target)| CsvColumnMapping {
+                source: source.to_string(),
+                target: target.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn maps_header_names_and_positions() {
+        let headers = StringRecord::from(vec!["id", " Name ", "created"]);
+        assert_eq!(
+            resolve_mapping(Some(&headers), &[]).unwrap(),
+            vec![
+                (0, "ID".to_string()),
+                (1, "NAME".to_string()),
+                (2, "CREATED".to_string())
+            ]
+        );
+        assert_eq!(
+            resolve_mapping(
+                Some(&headers),
+                &mapping(&[("NAME", "customer_name"), ("1", "ID")])
+            )
+            .unwrap(),
+            vec![(1, "CUSTOMER_NAME".to_string()), (0, "ID".to_string())]
+        );
+        assert!(resolve_mapping(Some(&headers), &mapping(&[("missing", "X")])).is_err());
+        assert!(resolve_mapping(None, &[]).is_err());
+        assert_eq!(
+            resolve_mapping(None, &mapping(&[("2", "NAME")])).unwrap(),
+            vec![(1, "NAME".to_string())]
+        );
+    }
+
+    #[test]
+    fn null_marker_and_buffer_sizes() {
+        let format = CsvFormat::default();
+        assert!(format.is_null(""));
+        assert!(!format.is_null("NULL"));
+        let format = CsvFormat {
+            null_value: Some("NULL".to_string()),
+            ..CsvFormat::default()
+        };
+        assert!(format.is_null("NULL"));
+        assert!(!format.is_null(""));
+
+        let rows = vec![
+            vec![Some("1".to_string()), None],
+            vec![Some("1234".to_string()), None],
+        ];
+        assert_eq!(longest_values(&rows), vec![4, 1]);
+    }
+}
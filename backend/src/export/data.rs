@@ -1,105 +1,1145 @@
 use std::{
+    collections::HashMap,
     fs::{self, File},
-    io::{BufWriter, Write},
+    io::Write,
+    num::NonZeroUsize,
     path::Path,
+    time::Instant,
 };
 
-use anyhow::{Context, Result};
-use chrono::Local;
-use odbc_api::{buffers::TextRowSet, Connection, Cursor};
+use anyhow::{ensure, Context, Result};
+use chrono::{FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
+use odbc_api::{
+    buffers::{ColumnarBuffer, Indicator, TextColumn, TextRowSet},
+    Cursor,
+};
 
-use crate::db::schema::{fetch_row_count, fetch_sequences, get_table_details};
-use crate::models::TableDetails;
+use crate::db::backend::RowFetch;
+use crate::db::retry::{PartialOutput, RetryingConnection};
+use crate::db::metadata_cache::{cached_table_details, cached_tables_details};
+use crate::db::schema::{
+    catalog_name, check_readable, fetch_column_max, fetch_row_count, fetch_sequences,
+};
+use crate::export::charset::check_export_file;
+use crate::export::dependency::{add_referenced_tables, load_order};
+use crate::export::dialect::Dialect;
+use crate::export::encoding::{script_writer, unmappable_count, warn_unmappable};
+use crate::export::geometry::{self, geometry_literal, is_geometry_type};
+use crate::export::job::JobContext;
+use crate::export::manifest::{manifest_path, write_manifest, ChecksumWriter};
+use crate::export::renames::renamed_table;
+use crate::export::sequences::{
+    apply_sequence_renames, filter_referenced_sequences, sequence_fed_columns,
+};
+use crate::export::memory::{
+    adaptive_batch_size, estimated_row_bytes, estimated_wide_row_bytes, in_flight_bytes,
+    wide_text_lengths,
+};
+use crate::export::pipeline::{run_pipeline, PIPELINE_DEPTH};
+use crate::export::quoting::quote_name;
+use crate::export::sample::{is_reproducible, sample_label, sample_query};
+use crate::export::stats::{duration_ms, CountingWriter};
+use crate::export::subset::consistent_subset;
+use crate::models::{
+    DataFormat, EmptyStringPolicy, ExportEncoding, ExportManifest, IssueCode, JobEventKind, LineEnding, ManifestTable,
+    QuoteStyle, RowErrorPolicy, SampleSpec, Sequence, TableDetails, TableMemoryStats,
+};
 
+/// Writes the INSERT statements for one table. Reads that fail with a transient error are
+/// retried on a re-opened connection (each retry is recorded on `job`): keyset pages resume
+/// after the last written key, a plain cursor only when no rows were written yet. When a keyset
+/// read still fails, the error names that key so a later run can continue from it with
+/// `keyset_resume_after`. `row_filter` restricts the rows to a consistent sample's subset.
+#[allow(clippy::too_many_arguments)]
 pub fn export_table_data(
-    connection: &Connection<'_>,
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
     source_schema: &str,
     target_schema: &str,
     table: &str,
     table_details: &TableDetails,
-    writer: &mut impl Write,
-    batch_size: usize,
-) -> Result<usize> {
-    let source_schema_upper = source_schema.to_uppercase();
-    let target_schema_upper = target_schema.to_uppercase();
-    let table_upper = table.to_uppercase();
-    let source_qualified_table = format!("{}.{}", source_schema_upper, table_upper);
-    let target_qualified_table = format!("{}.{}", target_schema_upper, table_upper);
+    row_filter: Option<&str>,
+    writer: &mut (impl Write + Send),
+    options: &DataExportOptions,
+) -> Result<(usize, TableMemoryStats)> {
+    let source_schema_name = catalog_name(source_schema, options.preserve_case);
+    let target_schema_name = catalog_name(target_schema, options.preserve_case);
+    let table_name = catalog_name(table, options.preserve_case);
+    let source_qualified_table = format!("{}.{}", source_schema_name, table_name);
+    let target_qualified_table = format!(
+        "{}.{}",
+        target_schema_name,
+        renamed_table(&options.table_renames, &table_name)
+    );
     let source_ident = quote_identifier(&source_qualified_table);
-    let target_ident = quote_identifier(&target_qualified_table);
+    let target_ident = quote_name(&target_qualified_table, options.quote_style);
 
     let column_idents: Vec<String> = table_details
         .columns
         .iter()
         .map(|col| quote_identifier(&col.name))
         .collect();
+    let insert_columns: Vec<String> = table_details
+        .columns
+        .iter()
+        .map(|col| quote_name(&col.name, options.quote_style))
+        .collect();
 
     // Use explicit column list to ensure SELECT and INSERT column order match
-    let select_columns = column_idents.join(", ");
-    let query = format!("SELECT {} FROM {}", select_columns, source_ident);
+    let select_columns = select_list(table_details);
+    let keyset = match (options.row_limit, options.keyset_page_size) {
+        (None, Some(page_size)) if page_size > 0 && options.sample.is_none() => {
+            keyset_key(table_details).map(|key| (key, page_size))
+        }
+        _ => None,
+    };
+    let resume_after = keyset_resume_key(
+        &options.keyset_resume_after,
+        &table_name,
+        options.preserve_case,
+    )?;
+    ensure!(
+        resume_after.is_none() || keyset.is_some(),
+        "{} cannot resume after a key: it is not read with keyset pagination (it needs \
+         keyset_page_size and a single integer primary key)",
+        source_qualified_table
+    );
+
+    let wide_lengths = wide_fetch_lengths(table_details);
+    let row_buffer_bytes = fetch_row_bytes(table_details, wide_lengths.as_deref());
+    let mut batch_size = match options.max_batch_bytes {
+        Some(budget) => adaptive_batch_size(options.batch_size, row_buffer_bytes, budget),
+        None => options.batch_size,
+    };
+    if let Some((_, page_size)) = keyset {
+        batch_size = batch_size.min(page_size);
+    }
+    if batch_size < options.batch_size {
+        tracing::info!(
+            "Batch size for {} reduced from {} to {} (about {} bytes per row)",
+            source_qualified_table,
+            options.batch_size,
+            batch_size,
+            row_buffer_bytes
+        );
+    }
+    let mut stats = TableMemoryStats {
+        table: table_name.clone(),
+        requested_batch_size: options.batch_size,
+        batch_size,
+        row_buffer_bytes,
+        fetch_buffer_bytes: batch_size * row_buffer_bytes,
+        ..TableMemoryStats::default()
+    };
+
+    let data_types: Vec<&str> = table_details
+        .columns
+        .iter()
+        .map(|col| col.data_type.as_str())
+        .collect();
+    let column_count = data_types.len();
+    let text_columns: Vec<bool> = data_types.iter().map(|dt| is_text_type(dt)).collect();
+    let mut empty_strings = 0;
+    let mut screen = RowScreen::new(options.on_row_error, &source_qualified_table, table_details);
+
+    let mut commit = CommitInterval::new(options.commit_every_rows);
+
+    // Fetch row sets here, format INSERTs and write them on worker threads; bounded channels
+    // between the stages keep a slow destination from buffering unbounded batches.
+    let format_batch = |rows: Vec<Vec<Option<String>>>| {
+        empty_strings += rows
+            .iter()
+            .flat_map(|row| row.iter().zip(&text_columns))
+            .filter(|(value, &text)| text && value.as_deref() == Some(""))
+            .count();
+        let values: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                let literals: Vec<String> = row
+                    .iter()
+                    .zip(&data_types)
+                    .map(|(value, data_type)| match value {
+                        None => "NULL".to_string(),
+                        Some(v) => format_literal(data_type, v, options.literals),
+                    })
+                    .collect();
+                format!("({})", literals.join(", "))
+            })
+            .collect();
+        render_batch(
+            &target_ident,
+            &insert_columns,
+            &values,
+            options.max_rows_per_insert,
+            options.max_statement_bytes,
+            &mut commit,
+        )
+    };
 
-    let mut cursor = match connection.execute(&query, ())? {
-        Some(cursor) => cursor,
-        None => {
-            tracing::info!("No data to export for table {}", source_qualified_table);
-            return Ok(0);
+    let what = format!("Reading {}", source_qualified_table);
+    let backend = session.backend();
+    let mut progress = FetchProgress {
+        last_key: resume_after,
+        ..FetchProgress::default()
+    };
+    let (row_count, pipeline) = run_pipeline(PIPELINE_DEPTH, writer, format_batch, |emit| {
+        // Rows are screened here, before formatting, so `abort` can stop the read.
+        let emit = &mut |rows: Vec<Vec<Option<String>>>| -> Result<()> {
+            let rows = screen.screen(rows)?;
+            if rows.is_empty() {
+                return Ok(());
+            }
+            emit(rows)
+        };
+        let mut on_retry =
+            |message: String| job.record(JobEventKind::Retry, Some(table), Some(message));
+
+        let Some((key_index, page_size)) = keyset else {
+            let query = table_query(
+                &select_columns,
+                &source_ident,
+                table_details,
+                row_filter,
+                options,
+            );
+            session.run(&what, &mut on_retry, |connection| {
+                let fetch = RowFetch {
+                    batch_size,
+                    column_count,
+                    key_index: None,
+                    wide_lengths: wide_lengths.as_deref(),
+                    stats: &mut stats,
+                    progress: &mut progress,
+                };
+                backend
+                    .stream_rows(connection, &query, fetch, emit)
+                    .map_err(|e| if progress.rows > 0 { e.context(PartialOutput) } else { e })
+            })?;
+            return Ok(progress.rows);
+        };
+
+        // One short query per page instead of a cursor held open for the whole table. A retried
+        // page starts after the last key already written.
+        let key_ident = &column_idents[key_index];
+        loop {
+            let page = session.run(&what, &mut on_retry, |connection| {
+                let before = progress.rows;
+                let last_key = progress
+                    .last_key
+                    .as_deref()
+                    .map(|value| {
+                        value.trim().parse::<i128>().with_context(|| {
+                            format!(
+                                "Keyset column {} of {} returned a non-integer value",
+                                key_ident, source_qualified_table
+                            )
+                        })
+                    })
+                    .transpose()?;
+                let query =
+                    keyset_query(&select_columns, &source_ident, key_ident, last_key, page_size);
+                let fetch = RowFetch {
+                    batch_size,
+                    column_count,
+                    key_index: Some(key_index),
+                    wide_lengths: wide_lengths.as_deref(),
+                    stats: &mut stats,
+                    progress: &mut progress,
+                };
+                backend.stream_rows(connection, &query, fetch, emit)?;
+                Ok(progress.rows - before)
+            });
+            let page_rows = match (page, progress.last_key.as_deref()) {
+                (Ok(rows), _) => rows,
+                (Err(e), None) => return Err(e),
+                (Err(e), Some(last_key)) => {
+                    return Err(e.context(format!(
+                        "Keyset export of {} stopped after {} = {}; export it again with \
+                         keyset_resume_after {{\"{}\": \"{}\"}} to continue from there",
+                        source_qualified_table, key_ident, last_key, table_name, last_key
+                    )))
+                }
+            };
+            if page_rows < page_size {
+                break;
+            }
+            tracing::debug!(
+                "Keyset page of {} ended at {} = {} ({} rows so far)",
+                source_qualified_table,
+                key_ident,
+                progress.last_key.as_deref().unwrap_or_default(),
+                progress.rows
+            );
         }
+
+        Ok(progress.rows)
+    })?;
+
+    stats.backpressure_stalls = pipeline.stalls;
+    let row_count = row_count - screen.skipped;
+    screen.report(job, &table_name);
+    if row_filter.is_none() {
+        warn_unreproducible_sample(job, &table_name, table_details, options);
+    }
+    warn_truncated_values(job, &table_name, progress.truncated_values);
+    warn_empty_strings(job, &table_name, empty_strings, options.literals);
+    if commit.is_some_and(|interval| interval.pending > 0) {
+        writeln!(writer, "COMMIT;")?;
+    }
+
+    tracing::info!(
+        "Exported {} rows from {} (batch {} rows, peak batch {} bytes, at most ~{} bytes in flight, {} stalls)",
+        row_count,
+        source_qualified_table,
+        stats.batch_size,
+        stats.peak_batch_bytes,
+        in_flight_bytes(stats.fetch_buffer_bytes.max(stats.peak_batch_bytes)),
+        stats.backpressure_stalls
+    );
+    Ok((row_count, stats))
+}
+
+/// Buffer lengths for fetching the character columns of `details` as UTF-16, so values
+/// outside the client charset survive; `None` when the table has no character column.
+pub(crate) fn wide_fetch_lengths(details: &TableDetails) -> Option<Vec<Option<usize>>> {
+    details
+        .columns
+        .iter()
+        .any(|column| is_text_type(&column.data_type))
+        .then(|| wide_text_lengths(details))
+}
+
+/// Fetch buffer bytes per row of `details`, with `wide_lengths` from [`wide_fetch_lengths`].
+fn fetch_row_bytes(details: &TableDetails, wide_lengths: Option<&[Option<usize>]>) -> usize {
+    match wide_lengths {
+        Some(lengths) => estimated_wide_row_bytes(details, lengths),
+        None => estimated_row_bytes(details),
+    }
+}
+
+/// Streams every row of `details` (a table of `schema`, in catalog spelling) to `emit` in fetch
+/// batches sized like the INSERT export's. A failed read is retried on a new connection only
+/// while nothing was emitted yet. Returns the row count and the table's memory use.
+/// `row_filter` restricts the rows to a consistent sample's subset.
+pub fn stream_table_rows(
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
+    schema: &str,
+    details: &TableDetails,
+    row_filter: Option<&str>,
+    options: &DataExportOptions,
+    emit: &mut dyn FnMut(Vec<Vec<Option<String>>>) -> Result<()>,
+) -> Result<(usize, TableMemoryStats)> {
+    let table = details.name.clone();
+    let source_ident = quote_identifier(&format!("{}.{}", schema, table));
+    let query = table_query(
+        &select_list(details),
+        &source_ident,
+        details,
+        row_filter,
+        options,
+    );
+    if row_filter.is_none() {
+        warn_unreproducible_sample(job, &table, details, options);
+    }
+
+    let wide_lengths = wide_fetch_lengths(details);
+    let row_buffer_bytes = fetch_row_bytes(details, wide_lengths.as_deref());
+    let batch_size = match options.max_batch_bytes {
+        Some(budget) => adaptive_batch_size(options.batch_size, row_buffer_bytes, budget),
+        None => options.batch_size,
+    };
+    let mut stats = TableMemoryStats {
+        table: table.clone(),
+        requested_batch_size: options.batch_size,
+        batch_size,
+        row_buffer_bytes,
+        fetch_buffer_bytes: batch_size * row_buffer_bytes,
+        ..TableMemoryStats::default()
     };
 
-    let mut batch = Vec::new();
-    let mut row_count = 0;
-    let mut buffers = TextRowSet::for_cursor(batch_size, &mut cursor, Some(8192))?;
+    let mut progress = FetchProgress::default();
+    let backend = session.backend();
+    session.run(
+        &format!("Reading {}", source_ident),
+        &mut |message| job.record(JobEventKind::Retry, Some(&table), Some(message)),
+        |connection| {
+            let fetch = RowFetch {
+                batch_size,
+                column_count: details.columns.len(),
+                key_index: None,
+                wide_lengths: wide_lengths.as_deref(),
+                stats: &mut stats,
+                progress: &mut progress,
+            };
+            backend
+                .stream_rows(connection, &query, fetch, emit)
+                .map_err(|e| if progress.rows > 0 { e.context(PartialOutput) } else { e })
+        },
+    )?;
+    warn_truncated_values(job, &table, progress.truncated_values);
+    Ok((progress.rows, stats))
+}
+
+/// Applies `on_row_error` to the rows of one table: finds values that cannot be written as a
+/// literal of their column type and fails, drops the row or nulls the value, logging each
+/// row it skipped or changed by its primary key (or position, without one).
+struct RowScreen<'a> {
+    policy: RowErrorPolicy,
+    table: &'a str,
+    details: &'a TableDetails,
+    keys: Vec<usize>,
+    /// Rows seen so far, for naming rows of tables without a primary key.
+    seen: usize,
+    skipped: usize,
+    nulled: usize,
+    log: Vec<String>,
+}
+
+impl<'a> RowScreen<'a> {
+    fn new(policy: RowErrorPolicy, table: &'a str, details: &'a TableDetails) -> Self {
+        let keys = details
+            .primary_keys
+            .iter()
+            .filter_map(|key| {
+                details
+                    .columns
+                    .iter()
+                    .position(|column| column.name.eq_ignore_ascii_case(key))
+            })
+            .collect();
+        Self {
+            policy,
+            table,
+            details,
+            keys,
+            seen: 0,
+            skipped: 0,
+            nulled: 0,
+            log: Vec::new(),
+        }
+    }
+
+    fn screen(&mut self, rows: Vec<Vec<Option<String>>>) -> Result<Vec<Vec<Option<String>>>> {
+        let mut kept = Vec::with_capacity(rows.len());
+        for mut row in rows {
+            self.seen += 1;
+            let bad: Vec<usize> = row
+                .iter()
+                .zip(&self.details.columns)
+                .enumerate()
+                .filter(|(_, (value, column))| {
+                    value
+                        .as_deref()
+                        .is_some_and(|raw| !value_fits_type(&column.data_type, raw))
+                })
+                .map(|(index, _)| index)
+                .collect();
+            if bad.is_empty() {
+                kept.push(row);
+                continue;
+            }
+            let problems: Vec<String> = bad
+                .iter()
+                .map(|&index| {
+                    let column = &self.details.columns[index];
+                    let raw = row[index].as_deref().unwrap_or_default();
+                    format!(
+                        "{} '{}' is not a valid {}",
+                        column.name,
+                        shorten(raw, 64),
+                        column.data_type
+                    )
+                })
+                .collect();
+            let row_label = self.row_label(&row);
+            match self.policy {
+                RowErrorPolicy::Abort => anyhow::bail!(
+                    "{} of {}: {} (set on_row_error to skip or null to continue past such rows)",
+                    row_label,
+                    self.table,
+                    problems.join("; ")
+                ),
+                RowErrorPolicy::Skip => {
+                    self.skipped += 1;
+                    self.log.push(format!(
+                        "{} {}: {}; row skipped",
+                        self.table,
+                        row_label,
+                        problems.join("; ")
+                    ));
+                }
+                RowErrorPolicy::Null => {
+                    for &index in &bad {
+                        row[index] = None;
+                    }
+                    self.nulled += 1;
+                    self.log.push(format!(
+                        "{} {}: {}; written as NULL",
+                        self.table,
+                        row_label,
+                        problems.join("; ")
+                    ));
+                    kept.push(row);
+                }
+            }
+        }
+        Ok(kept)
+    }
+
+    /// `(ID=42)` from the primary key values, else `(row 17)`.
+    fn row_label(&self, row: &[Option<String>]) -> String {
+        let key_values: Option<Vec<String>> = self
+            .keys
+            .iter()
+            .map(|&index| {
+                row[index]
+                    .as_deref()
+                    .map(|value| format!("{}={}", self.details.columns[index].name, value))
+            })
+            .collect();
+        match key_values.filter(|values| !values.is_empty()) {
+            Some(values) => format!("({})", values.join(", ")),
+            None => format!("(row {})", self.seen),
+        }
+    }
+
+    /// Hands the log lines to `job` with a `W-ROW-ERROR` warning for the table.
+    fn report(&mut self, job: &mut JobContext, table: &str) {
+        if self.skipped + self.nulled == 0 {
+            return;
+        }
+        let message = match self.policy {
+            RowErrorPolicy::Null => format!(
+                "{} row(s) of {} had values unfit for their column type written as NULL",
+                self.nulled, table
+            ),
+            _ => format!(
+                "{} row(s) of {} with values unfit for their column type were skipped",
+                self.skipped, table
+            ),
+        };
+        job.warn(IssueCode::RowErrors, Some(table), message);
+        job.row_errors.append(&mut self.log);
+    }
+}
+
+/// Whether `raw` can be written as a literal of `data_type`. Only types written unquoted or
+/// converted by the target are checked; any text fits a character column.
+fn value_fits_type(data_type: &str, raw: &str) -> bool {
+    let upper = data_type.to_uppercase();
+    let raw = raw.trim();
+    if is_numeric_type(&upper) {
+        return is_number(raw);
+    }
+    if is_binary_type(&upper) {
+        let hex = raw.trim_start_matches("0x").trim_start_matches("0X");
+        return hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    if is_date_type(&upper) || is_timestamp_type(&upper) || is_time_type(&upper) {
+        return raw.starts_with(|c: char| c.is_ascii_digit())
+            && raw
+                .chars()
+                .all(|c| c.is_ascii_digit() || " -:./+TZ".contains(c));
+    }
+    true
+}
+
+/// `[+-]digits[.digits][e[+-]digits]`, with digits on at least one side of the point.
+fn is_number(raw: &str) -> bool {
+    fn digits(text: &str) -> bool {
+        !text.is_empty() && text.chars().all(|c| c.is_ascii_digit())
+    }
+    let unsigned = raw.strip_prefix(['+', '-']).unwrap_or(raw);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let mantissa_ok = match mantissa.split_once('.') {
+        Some((whole, fraction)) => {
+            (digits(whole) || whole.is_empty())
+                && (digits(fraction) || fraction.is_empty())
+                && !(whole.is_empty() && fraction.is_empty())
+        }
+        None => digits(mantissa),
+    };
+    mantissa_ok
+        && exponent.map_or(true, |exponent| {
+            digits(exponent.strip_prefix(['+', '-']).unwrap_or(exponent))
+        })
+}
+
+/// `value` cut to `max_chars` characters for a log line.
+fn shorten(value: &str, max_chars: usize) -> String {
+    match value.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &value[..end]),
+        None => value.to_string(),
+    }
+}
+
+/// Rows of a table handed to the pipeline so far.
+#[derive(Debug, Default)]
+pub struct FetchProgress {
+    pub rows: usize,
+    /// Key column value of the last row handed on, in keyset mode.
+    pub last_key: Option<String>,
+    /// Values longer than [`MAX_TEXT_VALUE_BYTES`] that were handed on cut to that length.
+    pub truncated_values: usize,
+}
+
+fn warn_truncated_values(job: &mut JobContext, table: &str, count: usize) {
+    if count > 0 {
+        job.warn(
+            IssueCode::ValueTruncated,
+            Some(table),
+            format!(
+                "{} value(s) of {} are longer than {} bytes and were truncated",
+                count, table, MAX_TEXT_VALUE_BYTES
+            ),
+        );
+    }
+}
+
+/// A seeded sample of a table without a primary key falls back to a random pick.
+fn warn_unreproducible_sample(
+    job: &mut JobContext,
+    table: &str,
+    details: &TableDetails,
+    options: &DataExportOptions,
+) {
+    if let Some(spec) = &options.sample {
+        if !is_reproducible(spec, details) {
+            job.warn(
+                IssueCode::SampleNotReproducible,
+                Some(table),
+                format!(
+                    "{} has no primary key; its sample is random and not reproducible by seed",
+                    table
+                ),
+            );
+        }
+    }
+}
+
+/// Counts the table's empty text values into the run stats. Written as `''` they may load as
+/// NULL on a DM8 target in Oracle-compatible mode; written as NULL they lost their emptiness.
+fn warn_empty_strings(job: &mut JobContext, table: &str, count: usize, style: LiteralStyle) {
+    if count == 0 {
+        return;
+    }
+    job.stats.empty_strings += count;
+    let written = match style.empty_string_as {
+        EmptyStringPolicy::Empty => "written as '' (the target may store them as NULL)",
+        EmptyStringPolicy::Null => "written as NULL",
+    };
+    job.warn(
+        IssueCode::EmptyStrings,
+        Some(table),
+        format!(
+            "{} empty text value(s) of {} were {}",
+            count, table, written
+        ),
+    );
+}
+
+/// Fetches every row of `cursor` in row sets of `batch_size` and hands them to `emit`, recording
+/// batch and row payload peaks in `stats`. `progress` is updated after every batch, so it is
+/// accurate even when the fetch fails halfway; with `key_index` set it tracks that column's
+/// value in the last row handed on.
+pub fn fetch_rows<C: Cursor>(
+    mut cursor: C,
+    batch_size: usize,
+    column_count: usize,
+    key_index: Option<usize>,
+    stats: &mut TableMemoryStats,
+    progress: &mut FetchProgress,
+    emit: &mut dyn FnMut(Vec<Vec<Option<String>>>) -> Result<()>,
+) -> Result<()> {
+    let mut buffers = TextRowSet::for_cursor(batch_size, &mut cursor, Some(MAX_TEXT_VALUE_BYTES))?;
     let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
     while let Some(batch_result) = row_set_cursor.fetch()? {
+        let mut rows = Vec::with_capacity(batch_result.num_rows());
+        let mut truncated = 0;
         for row_index in 0..batch_result.num_rows() {
-            let mut values = Vec::new();
-
-            for (col_index, column) in table_details.columns.iter().enumerate() {
-                let value = batch_result.at_as_str(col_index, row_index)?;
-
-                let formatted_value = match value {
-                    None => "NULL".to_string(),
-                    Some(v) => format_literal(&column.data_type, v),
+            let mut row = Vec::with_capacity(column_count);
+            for col_index in 0..column_count {
+                // The indicator holds the full length; the buffer only the first bytes.
+                truncated += match batch_result.indicator_at(col_index, row_index) {
+                    Indicator::Length(len) => usize::from(len > MAX_TEXT_VALUE_BYTES),
+                    Indicator::NoTotal => 1,
+                    Indicator::Null => 0,
                 };
-
-                values.push(formatted_value);
+                let value = batch_result
+                    .at_as_str(col_index, row_index)?
+                    .map(str::to_string);
+                row.push(value);
             }
+            rows.push(row);
+        }
+        hand_on_batch(rows, truncated, key_index, stats, progress, emit)?;
+    }
 
-            batch.push(format!("({})", values.join(", ")));
-            row_count += 1;
+    Ok(())
+}
+
+/// Like [`fetch_rows`], but binds every column as UTF-16 text of at most `max_lens[i]` code
+/// units, so characters the client charset cannot represent arrive intact; columns without a
+/// length get the driver's display size. A value filling its buffer completely counts as
+/// truncated.
+pub fn fetch_wide_rows<C: Cursor>(
+    mut cursor: C,
+    batch_size: usize,
+    max_lens: &[Option<usize>],
+    key_index: Option<usize>,
+    stats: &mut TableMemoryStats,
+    progress: &mut FetchProgress,
+    emit: &mut dyn FnMut(Vec<Vec<Option<String>>>) -> Result<()>,
+) -> Result<()> {
+    let max_lens = wide_buffer_lengths(max_lens, |column_number| {
+        Ok(cursor.col_display_size(column_number)?)
+    })?;
+    let columns = max_lens
+        .iter()
+        .zip(1u16..)
+        .map(|(&max_len, column_number)| {
+            (column_number, TextColumn::<u16>::new(batch_size, max_len))
+        })
+        .collect();
+    let mut buffers = ColumnarBuffer::new(columns);
+    let mut row_set_cursor = cursor.bind_buffer(&mut buffers)?;
 
-            if batch.len() >= batch_size {
-                write_batch(writer, &target_ident, &column_idents, &batch)?;
-                batch.clear();
+    while let Some(batch_result) = row_set_cursor.fetch()? {
+        let mut rows = Vec::with_capacity(batch_result.num_rows());
+        let mut truncated = 0;
+        for row_index in 0..batch_result.num_rows() {
+            let mut row = Vec::with_capacity(max_lens.len());
+            for (col_index, &max_len) in max_lens.iter().enumerate() {
+                let value = batch_result.column(col_index).get(row_index);
+                truncated += usize::from(value.is_some_and(|units| units.len() >= max_len));
+                row.push(value.map(String::from_utf16_lossy));
             }
+            rows.push(row);
         }
+        hand_on_batch(rows, truncated, key_index, stats, progress, emit)?;
     }
 
-    if !batch.is_empty() {
-        write_batch(writer, &target_ident, &column_idents, &batch)?;
+    Ok(())
+}
+
+/// Code units of every wide fetch buffer: the given length, or the `display_size` the driver
+/// reports for the (1-based) column, capped at `MAX_TEXT_VALUE_BYTES` like the narrow fetch.
+fn wide_buffer_lengths(
+    max_lens: &[Option<usize>],
+    mut display_size: impl FnMut(u16) -> Result<Option<NonZeroUsize>>,
+) -> Result<Vec<usize>> {
+    max_lens
+        .iter()
+        .zip(1u16..)
+        .map(|(&max_len, column_number)| match max_len {
+            Some(units) => Ok(units),
+            None => Ok(display_size(column_number)?
+                .map_or(MAX_TEXT_VALUE_BYTES, NonZeroUsize::get)
+                .min(MAX_TEXT_VALUE_BYTES)),
+        })
+        .collect()
+}
+
+/// Records the payload peaks of a fetched batch and hands it to `emit`.
+fn hand_on_batch(
+    rows: Vec<Vec<Option<String>>>,
+    truncated: usize,
+    key_index: Option<usize>,
+    stats: &mut TableMemoryStats,
+    progress: &mut FetchProgress,
+    emit: &mut dyn FnMut(Vec<Vec<Option<String>>>) -> Result<()>,
+) -> Result<()> {
+    let mut batch_bytes = 0;
+    for row in &rows {
+        let row_bytes: usize = row.iter().flatten().map(String::len).sum();
+        stats.peak_row_bytes = stats.peak_row_bytes.max(row_bytes);
+        batch_bytes += row_bytes;
+    }
+    stats.peak_batch_bytes = stats.peak_batch_bytes.max(batch_bytes);
+    let last_key = match (key_index, rows.last()) {
+        (Some(index), Some(last)) => last[index].clone(),
+        _ => None,
+    };
+    if !rows.is_empty() {
+        let count = rows.len();
+        emit(rows)?;
+        progress.rows += count;
+        progress.truncated_values += truncated;
+        if last_key.is_some() {
+            progress.last_key = last_key;
+        }
     }
+    Ok(())
+}
 
-    tracing::info!(
-        "Exported {} rows from {}",
-        row_count,
-        source_qualified_table
+/// Index of the column keyset pagination can page by: the table's only primary key column,
+/// when it has an integer type.
+fn keyset_key(details: &TableDetails) -> Option<usize> {
+    let [key] = details.primary_keys.as_slice() else {
+        return None;
+    };
+    let index = details
+        .columns
+        .iter()
+        .position(|column| column.name.eq_ignore_ascii_case(key))?;
+    let column = &details.columns[index];
+    let data_type = column.data_type.to_uppercase();
+    let integer = match data_type.as_str() {
+        "INT" | "INTEGER" | "BIGINT" | "SMALLINT" | "TINYINT" | "BYTE" => true,
+        // An unknown scale may hide fractions, which `WHERE key > last` would skip over.
+        "NUMBER" | "NUMERIC" | "DECIMAL" | "DEC" => column.scale == Some(0),
+        _ => false,
+    };
+    integer.then_some(index)
+}
+
+/// Key from `keyset_resume_after` to continue the keyset export of `table` after.
+fn keyset_resume_key(
+    resume_after: &HashMap<String, String>,
+    table: &str,
+    preserve_case: bool,
+) -> Result<Option<String>> {
+    let Some((_, key)) = resume_after
+        .iter()
+        .find(|(name, _)| catalog_name(name, preserve_case) == table)
+    else {
+        return Ok(None);
+    };
+    let key = key.trim();
+    ensure!(
+        key.parse::<i128>().is_ok(),
+        "keyset_resume_after key '{}' of {} is not an integer",
+        key,
+        table
     );
-    Ok(row_count)
+    Ok(Some(key.to_string()))
+}
+
+/// Next page of a keyset scan: rows after `last_key` in key order.
+fn keyset_query(
+    columns: &str,
+    table: &str,
+    key: &str,
+    last_key: Option<i128>,
+    page_size: usize,
+) -> String {
+    let filter = last_key
+        .map(|last| format!(" WHERE {} > {}", key, last))
+        .unwrap_or_default();
+    format!(
+        "SELECT {} FROM {}{} ORDER BY {} LIMIT {}",
+        columns, table, filter, key, page_size
+    )
+}
+
+/// Longest value (in bytes) the data export reads per column; longer values are truncated.
+pub const MAX_TEXT_VALUE_BYTES: usize = 8192;
+
+/// Rows per table exported into a review bundle when the request does not say otherwise.
+pub const DEFAULT_PREVIEW_ROWS: usize = 100;
+
+/// Output options for `export_schema_data`.
+#[derive(Debug, Clone)]
+pub struct DataExportOptions {
+    /// Rows fetched per round trip.
+    pub batch_size: usize,
+    /// Rows per generated INSERT statement; a fetched batch is split when it has more.
+    pub max_rows_per_insert: Option<usize>,
+    /// Length cap of one generated INSERT statement in bytes, for DM8's statement size limit.
+    pub max_statement_bytes: Option<usize>,
+    /// Write `COMMIT;` after about this many rows and at the end of every table, so loading
+    /// the script does not run as one huge transaction.
+    pub commit_every_rows: Option<usize>,
+    pub include_row_counts: bool,
+    /// Export only the first N rows of each table (review bundles).
+    pub row_limit: Option<usize>,
+    /// Reset only sequences referenced by the selected tables' defaults and triggers.
+    pub only_referenced_sequences: bool,
+    /// Source sequence name -> target name for the sequence reset section.
+    pub sequence_renames: HashMap<String, String>,
+    /// Source table name -> target name for TRUNCATE, INSERT and IDENTITY_INSERT targets.
+    pub table_renames: HashMap<String, String>,
+    /// Encoding of the written script.
+    pub encoding: ExportEncoding,
+    pub line_ending: LineEnding,
+    /// Memory budget of one fetched batch; when set, `batch_size` is reduced for tables whose
+    /// rows are too wide to fit that many in the budget (adaptive batch sizing).
+    pub max_batch_bytes: Option<usize>,
+    /// Page tables with a single integer primary key in key order, this many rows per query,
+    /// instead of reading them through one long-running cursor. Ignored for `row_limit` and
+    /// sampled exports.
+    pub keyset_page_size: Option<usize>,
+    /// Source table name -> key after which its keyset export continues, to finish a run
+    /// that was cut short.
+    pub keyset_resume_after: HashMap<String, String>,
+    /// Also export tables the selection references through foreign keys, so inserts into
+    /// child tables do not fail on missing parent rows.
+    pub include_dependencies: bool,
+    /// After each table with an identity column, restart the identity past the source's
+    /// high-water value, since TRUNCATE reset it to the seed.
+    pub restart_identities: bool,
+    /// After the inserts, move each sequence to the MAX of the columns it feeds instead of
+    /// resetting it to its START value before them.
+    pub reseed_sequences: bool,
+    /// How text values are written into the INSERT literals.
+    pub literals: LiteralStyle,
+    /// Take schema and table names as given instead of upper-casing them, for tables created
+    /// with quoted mixed- or lower-case names.
+    pub preserve_case: bool,
+    /// Which identifiers the INSERT, TRUNCATE and sequence statements quote.
+    pub quote_style: QuoteStyle,
+    /// Write a commented placeholder for tables the login cannot read instead of failing.
+    pub skip_unreadable: bool,
+    /// Export only a sample of each table's rows. Ignored for `row_limit` exports.
+    pub sample: Option<SampleSpec>,
+    /// What happens to rows with a value that cannot be written as a literal of its column.
+    pub on_row_error: RowErrorPolicy,
+    /// Wrap the INSERTs of tables with triggers in `DISABLE`/`ENABLE ALL TRIGGERS`.
+    pub disable_triggers_during_load: bool,
+    /// Templates written before and after each table's rows, with `{schema}` and `{table}`
+    /// placeholders.
+    pub pre_table_sql: Option<String>,
+    pub post_table_sql: Option<String>,
+}
+
+/// `ALTER TABLE ... RESTART WITH` continuing the identity of `table` after the last value the
+/// source handed out; `None` without an identity column or a known high-water value.
+pub fn identity_restart_statement(
+    qualified_table: &str,
+    table: &TableDetails,
+    style: QuoteStyle,
+) -> Option<String> {
+    let column = table.columns.iter().find(|col| col.identity)?;
+    let current = column.identity_current?;
+    let next = current.checked_add(column.identity_increment.unwrap_or(1))?;
+    Some(format!(
+        "ALTER TABLE {} ALTER COLUMN {} RESTART WITH {};",
+        qualified_table,
+        quote_name(&column.name, style),
+        next
+    ))
+}
+
+/// Indexes of the `tables` the login cannot SELECT from, with the reason; each is recorded as
+/// a `W-TABLE-UNREADABLE` warning. Link failures are retried first, so what remains is a
+/// missing privilege or a similar lasting error.
+fn unreadable_tables(
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
+    schema: &str,
+    tables: &[String],
+) -> HashMap<usize, String> {
+    let mut unreadable = HashMap::new();
+    for (index, table) in tables.iter().enumerate() {
+        let checked = session.run(
+            &format!("Checking read access to {}", table),
+            &mut |message| job.record(JobEventKind::Retry, Some(table), Some(message)),
+            |connection| check_readable(connection, schema, table),
+        );
+        if let Err(e) = checked {
+            let reason = format!("{:#}", e);
+            job.warn(
+                IssueCode::TableUnreadable,
+                Some(table),
+                format!("Skipping data of {}: {}", table, reason),
+            );
+            unreadable.insert(index, reason);
+        }
+    }
+    unreadable
+}
+
+/// Section standing in for a table whose data could not be read: the statements the script
+/// would have run are commented out, so the table can be re-exported once readable.
+fn write_unreadable_placeholder(
+    writer: &mut impl Write,
+    qualified_table: &str,
+    style: QuoteStyle,
+    reason: &str,
+) -> Result<()> {
+    writeln!(
+        writer,
+        "-- Data for table: {} (skipped: not readable)",
+        qualified_table
+    )?;
+    for line in reason.lines() {
+        writeln!(writer, "-- {}", line)?;
+    }
+    writeln!(
+        writer,
+        "-- TRUNCATE TABLE {};",
+        quote_name(qualified_table, style)
+    )?;
+    writeln!(
+        writer,
+        "-- INSERT INTO {} ... (no rows exported)",
+        quote_name(qualified_table, style)
+    )?;
+    Ok(())
+}
+
+/// Query reading the rows of `details` through one cursor: the first rows of a preview, the
+/// subset of a consistent sample (`row_filter`), a sample, or every row.
+fn table_query(
+    columns: &str,
+    table: &str,
+    details: &TableDetails,
+    row_filter: Option<&str>,
+    options: &DataExportOptions,
+) -> String {
+    match (options.row_limit, row_filter, &options.sample) {
+        (Some(limit), _, _) => preview_query(columns, table, details, limit),
+        (None, Some(filter), _) => format!("SELECT {} FROM {} WHERE {}", columns, table, filter),
+        (None, None, Some(spec)) => sample_query(columns, table, details, spec),
+        (None, None, None) => format!("SELECT {} FROM {}", columns, table),
+    }
+}
+
+/// `SELECT TOP n`, ordered by the primary key when there is one so the sample is stable.
+pub fn preview_query(columns: &str, table: &str, details: &TableDetails, limit: usize) -> String {
+    let mut query = format!("SELECT TOP {} {} FROM {}", limit, columns, table);
+    if !details.primary_keys.is_empty() {
+        let keys: Vec<String> = details
+            .primary_keys
+            .iter()
+            .map(|key| quote_identifier(key))
+            .collect();
+        query.push_str(&format!(" ORDER BY {}", keys.join(", ")));
+    }
+    query
+}
+
+/// Reads the metadata of `tables` of `schema` (catalog spelling) for a data export and, with
+/// `include_dependencies`, of the tables they reference. Returns the details in load-request
+/// order and the names of the tables that were added as dependencies.
+pub fn load_table_metadata(
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
+    schema: &str,
+    tables: &[String],
+    options: &DataExportOptions,
+) -> Result<(Vec<TableDetails>, Vec<String>)> {
+    let tables: Vec<String> = tables
+        .iter()
+        .map(|table| catalog_name(table, options.preserve_case))
+        .collect();
+    let capabilities = job.capabilities;
+    let metadata = job.metadata.clone();
+    let mut details = session
+        .run(
+            &format!("Reading metadata of {} tables", tables.len()),
+            &mut |message| job.record(JobEventKind::Retry, None, Some(message)),
+            |connection| {
+                cached_tables_details(
+                    metadata.as_ref(),
+                    connection,
+                    &capabilities,
+                    schema,
+                    &tables,
+                )
+            },
+        )
+        .with_context(|| format!("Failed to get table details in {}", schema))?;
+    let mut load = |table_name: &str| {
+        session
+            .run(
+                &format!("Reading metadata of {}", table_name),
+                &mut |message| job.record(JobEventKind::Retry, Some(table_name), Some(message)),
+                |connection| {
+                    cached_table_details(
+                        metadata.as_ref(),
+                        connection,
+                        &capabilities,
+                        schema,
+                        table_name,
+                    )
+                },
+            )
+            .with_context(|| format!("Failed to get table details for {}.{}", schema, table_name))
+    };
+    let auto_included = if options.include_dependencies {
+        add_referenced_tables(schema, &mut details, &mut load)?
+    } else {
+        Vec::new()
+    };
+    // The target computes virtual columns itself and rejects values inserted into them.
+    for table in &mut details {
+        table
+            .columns
+            .retain(|column| column.virtual_expression.is_none());
+    }
+    Ok((details, auto_included))
 }
 
 pub fn export_schema_data(
-    connection: &Connection<'_>,
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
     source_schema: &str,
     target_schema: &str,
     tables: &[String],
     output_path: &Path,
-    batch_size: usize,
-    include_row_counts: bool,
+    options: &DataExportOptions,
 ) -> Result<usize> {
-    let source_schema_upper = source_schema.to_uppercase();
-    let target_schema_upper = target_schema.to_uppercase();
-    let sequences = fetch_sequences(connection, &source_schema_upper).unwrap_or_default();
+    let started = Instant::now();
+    let include_row_counts = options.include_row_counts;
+    let source_schema_name = catalog_name(source_schema, options.preserve_case);
+    let target_schema_name = catalog_name(target_schema, options.preserve_case);
+
+    // Metadata for every table up front: the sequence filter needs all defaults and triggers
+    // before the reset section is written.
+    let (mut table_details_list, auto_included) =
+        load_table_metadata(session, job, &source_schema_name, tables, options)?;
+    let tables: Vec<String> = table_details_list.iter().map(|t| t.name.clone()).collect();
+    let row_filters = consistent_subset(job, &source_schema_name, &table_details_list, options)?;
+
+    let fetched = session.run(
+        "Reading sequences",
+        &mut |message| job.record(JobEventKind::Retry, None, Some(message)),
+        |connection| fetch_sequences(connection, &source_schema_name),
+    );
+    let mut sequences = match fetched {
+        Ok(sequences) if options.only_referenced_sequences => {
+            filter_referenced_sequences(sequences, &table_details_list)
+        }
+        Ok(sequences) => sequences,
+        Err(e) => {
+            job.warn(
+                IssueCode::SequencesUnavailable,
+                None,
+                format!("Failed to fetch sequences, skipping reset: {:#}", e),
+            );
+            Vec::new()
+        }
+    };
+    apply_sequence_renames(
+        &mut sequences,
+        &mut table_details_list,
+        &options.sequence_renames,
+    );
+    let high_water = if options.reseed_sequences {
+        sequence_high_water(
+            session,
+            job,
+            &source_schema_name,
+            &sequences,
+            &table_details_list,
+        )
+    } else {
+        HashMap::new()
+    };
+    let (reseeded, reset): (Vec<_>, Vec<_>) = sequences
+        .iter()
+        .partition(|seq| high_water.contains_key(&seq.name.to_uppercase()));
+    let unreadable = if options.skip_unreadable {
+        unreadable_tables(session, job, &source_schema_name, &tables)
+    } else {
+        HashMap::new()
+    };
 
     if let Some(parent) = output_path.parent() {
         fs::create_dir_all(parent).with_context(|| {
@@ -113,23 +1153,39 @@ pub fn export_schema_data(
     let file = File::create(output_path).with_context(|| {
         format!("Failed to create data export file at {}", output_path.display())
     })?;
-    let mut writer = BufWriter::new(file);
+    let mut writer = script_writer(file, options.encoding, options.line_ending);
 
     // Pre-compute row counts for header (optional)
     let mut total_rows: i64 = 0;
     let mut table_row_counts = Vec::new();
     if include_row_counts {
-        for table in tables {
-            match fetch_row_count(connection, &source_schema_upper, table) {
+        for (index, table) in tables.iter().enumerate() {
+            if unreadable.contains_key(&index) {
+                table_row_counts.push((table.clone(), None));
+                continue;
+            }
+            let counted = session.run(
+                &format!("Counting rows of {}", table),
+                &mut |message| job.record(JobEventKind::Retry, Some(table), Some(message)),
+                |connection| fetch_row_count(connection, &source_schema_name, table),
+            );
+            match counted {
                 Ok(cnt) => {
                     total_rows += cnt;
                     table_row_counts.push((table.clone(), Some(cnt)));
                 }
-                Err(_) => table_row_counts.push((table.clone(), None)),
+                Err(e) => {
+                    job.warn(
+                        IssueCode::RowCountUnavailable,
+                        Some(table),
+                        format!("Row count unavailable for {}: {:#}", table, e),
+                    );
+                    table_row_counts.push((table.clone(), None));
+                }
             }
         }
     } else {
-        for table in tables {
+        for table in &tables {
             table_row_counts.push((table.clone(), None));
         }
     }
@@ -137,97 +1193,453 @@ pub fn export_schema_data(
     let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
     writeln!(writer, "-- DM8 Data Export")?;
     writeln!(writer, "-- Tables: {}", tables.len())?;
+    if !unreadable.is_empty() {
+        let mut skipped: Vec<&str> = unreadable.keys().map(|&i| tables[i].as_str()).collect();
+        skipped.sort_unstable();
+        writeln!(
+            writer,
+            "-- Skipped (not readable by this login): {}",
+            skipped.join(", ")
+        )?;
+    }
+    if !auto_included.is_empty() {
+        writeln!(
+            writer,
+            "-- Auto-included parent tables: {}",
+            auto_included.join(", ")
+        )?;
+    }
     if include_row_counts {
         writeln!(writer, "-- Rows (estimated): {}", total_rows)?;
     } else {
         writeln!(writer, "-- Rows (estimated): skipped (per request)")?;
     }
     writeln!(writer, "-- Generated at: {}", timestamp)?;
+    writeln!(
+        writer,
+        "-- Encoding: {0} (run SET CHAR_CODE {0} in DIsql before executing)",
+        options.encoding.char_code()
+    )?;
+    if let Some(zone) = options.literals.timezone {
+        writeln!(
+            writer,
+            "-- Timestamps with time zone converted to: {}",
+            timezone_label(zone)
+        )?;
+    }
+    if let Some(limit) = options.row_limit {
+        writeln!(writer, "-- Preview: only the first {} rows of each table are included", limit)?;
+    } else if let Some(spec) = &options.sample {
+        writeln!(writer, "-- Sample: {}", sample_label(spec))?;
+    }
     writeln!(writer, "-- Warning: This script truncates tables before inserting data.")?;
-    if !sequences.is_empty() {
+    if options.disable_triggers_during_load {
+        writeln!(
+            writer,
+            "-- Triggers are disabled while each table's rows are inserted"
+        )?;
+    }
+    if let Some(every) = options.commit_every_rows.filter(|&every| every > 0) {
+        writeln!(writer, "-- Commits every {} rows and after each table", every)?;
+    }
+    if !reset.is_empty() {
         writeln!(writer, "-- Sequences will be reset to START values before inserts")?;
     }
+    if !reseeded.is_empty() {
+        writeln!(writer, "-- Sequences feeding exported columns will be reseeded to their MAX after inserts")?;
+    }
     writeln!(writer)?;
 
-    if !sequences.is_empty() {
+    if !reset.is_empty() {
         writeln!(writer, "-- Reset sequences (DM8 uses CURRENT VALUE, not RESTART WITH)")?;
-        for seq in &sequences {
+        for seq in &reset {
             let start = seq.start_with.unwrap_or(1);
             writeln!(
                 writer,
                 "ALTER SEQUENCE {} CURRENT VALUE {};",
-                quote_identifier(&format!("{}.{}", target_schema_upper, seq.name)),
+                quote_name(
+                    &format!("{}.{}", target_schema_name, seq.name),
+                    options.quote_style
+                ),
                 start
             )?;
         }
         writeln!(writer)?;
     }
 
+    // TRUNCATE children before parents, then INSERT parents before children, so the script
+    // runs against a target whose foreign keys are enabled.
+    let load = load_order(&source_schema_name, &table_details_list);
+    if !load.cyclic.is_empty() {
+        job.warn(
+            IssueCode::ForeignKeyCycle,
+            None,
+            format!(
+                "Foreign key cycle between {}; these tables keep their requested order",
+                load.cyclic.join(", ")
+            ),
+        );
+    }
+
+    // TRUNCATE TABLE resets IDENTITY columns to their original seed value in DM8
+    writeln!(writer, "-- Truncate tables (referencing tables first)")?;
+    for &index in load.order.iter().rev() {
+        if unreadable.contains_key(&index) {
+            continue;
+        }
+        let qualified = quote_name(
+            &format!(
+                "{}.{}",
+                target_schema_name,
+                renamed_table(&options.table_renames, &tables[index])
+            ),
+            options.quote_style,
+        );
+        writeln!(writer, "TRUNCATE TABLE {};", qualified)?;
+    }
+    writeln!(writer)?;
+
     let mut exported_total: usize = 0;
+    let mut manifest_tables = Vec::with_capacity(tables.len());
 
-    for (i, (table_name, expected_rows)) in table_row_counts.iter().enumerate() {
+    for (i, &index) in load.order.iter().enumerate() {
+        let (table_name, expected_rows) = &table_row_counts[index];
+        let table_details = &table_details_list[index];
         if i > 0 {
             writeln!(writer)?;
         }
+        if let Some(reason) = unreadable.get(&index) {
+            let target_table = renamed_table(&options.table_renames, table_name);
+            write_unreadable_placeholder(
+                &mut writer,
+                &format!("{}.{}", target_schema_name, target_table),
+                options.quote_style,
+                reason,
+            )?;
+            continue;
+        }
 
-        let table_upper = table_name.to_uppercase();
-        let source_qualified = format!("{}.{}", source_schema_upper, table_upper);
-        let table_details = get_table_details(connection, &source_schema_upper, &table_upper)
-            .with_context(|| format!("Failed to get table details for {}", source_qualified))?;
-        let has_identity = table_details.columns.iter().any(|col| col.identity);
+        let table_started = Instant::now();
+        job.record(JobEventKind::TableStarted, Some(table_name), None);
+        let target_table = renamed_table(&options.table_renames, table_name);
 
+        let mut section = CountingWriter::new(&mut writer);
         writeln!(
-            writer,
+            section,
             "-- Data for table: {}.{}{}",
-            target_schema_upper,
-            table_upper,
+            target_schema_name,
+            target_table,
             expected_rows
                 .map(|c| format!(" ({} rows)", c))
                 .unwrap_or_else(|| " (rows unknown)".to_string())
         )?;
-        let qualified = quote_identifier(&format!("{}.{}", target_schema_upper, table_upper));
-        // TRUNCATE TABLE resets IDENTITY columns to their original seed value in DM8
-        writeln!(writer, "TRUNCATE TABLE {};", qualified)?;
+        let qualified = quote_name(
+            &format!("{}.{}", target_schema_name, target_table),
+            options.quote_style,
+        );
 
-        if has_identity {
-            write_identity_insert(&mut writer, &qualified, true)?;
+        let hook = |template: &Option<String>| {
+            table_hook(
+                template.as_deref(),
+                &target_schema_name,
+                &target_table,
+                options.quote_style,
+            )
+        };
+        if let Some(sql) = hook(&options.pre_table_sql) {
+            writeln!(section, "{}", sql)?;
         }
+        write_load_prologue(
+            &mut section,
+            &qualified,
+            table_details,
+            options.disable_triggers_during_load,
+        )?;
 
-        let count = export_table_data(
-            connection,
-            &source_schema_upper,
-            &target_schema_upper,
+        let mut checksum_writer = ChecksumWriter::new(&mut section);
+        let (count, memory) = export_table_data(
+            session,
+            job,
+            &source_schema_name,
+            &target_schema_name,
             table_name,
-            &table_details,
-            &mut writer,
-            batch_size,
+            table_details,
+            row_filters.get(table_name).map(String::as_str),
+            &mut checksum_writer,
+            options,
         )
         .with_context(|| format!("Failed to export data for table '{}'", table_name))?;
+        manifest_tables.push(ManifestTable {
+            table: target_table.clone(),
+            source_table: table_name.clone(),
+            rows: count,
+            checksum: checksum_writer.finish(),
+            file: None,
+            columns: Vec::new(),
+        });
 
-        if has_identity {
-            write_identity_insert(&mut writer, &qualified, false)?;
+        write_load_epilogue(
+            &mut section,
+            &qualified,
+            table_details,
+            options.disable_triggers_during_load,
+            options.restart_identities.then_some(options.quote_style),
+        )?;
+        if let Some(sql) = hook(&options.post_table_sql) {
+            writeln!(section, "{}", sql)?;
         }
+        let bytes = section.bytes();
 
+        job.record(
+            JobEventKind::TableFinished,
+            Some(table_name),
+            Some(format!(
+                "{} rows (batch {} of {} requested, peak batch {} bytes, {} backpressure stalls)",
+                count,
+                memory.batch_size,
+                memory.requested_batch_size,
+                memory.peak_batch_bytes,
+                memory.backpressure_stalls
+            )),
+        );
+        job.memory.push(memory);
+        job.record_table_stats(&target_table, "data", count, bytes, table_started.elapsed());
         exported_total += count;
     }
 
+    if !reseeded.is_empty() {
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "-- Reseed sequences to the highest value of the columns they feed"
+        )?;
+        for seq in &reseeded {
+            writeln!(
+                writer,
+                "ALTER SEQUENCE {} CURRENT VALUE {};",
+                quote_name(
+                    &format!("{}.{}", target_schema_name, seq.name),
+                    options.quote_style
+                ),
+                high_water[&seq.name.to_uppercase()]
+            )?;
+        }
+    }
+
     writer.flush().context("Failed to flush data export to disk")?;
+    let unmappable = unmappable_count(&writer);
+    drop(writer);
+    warn_unmappable(job, output_path, options.encoding, unmappable);
+    check_export_file(job, output_path, options.encoding);
+
+    let manifest = ExportManifest {
+        job_id: job.job_id.clone(),
+        generated_at: Utc::now().to_rfc3339(),
+        source_schema: source_schema_name.clone(),
+        target_schema: target_schema_name.clone(),
+        format: DataFormat::Sql,
+        data_file: output_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        row_limit: options.row_limit,
+        sample: options.sample.clone(),
+        tables: manifest_tables,
+    };
+    write_manifest(&manifest_path(output_path), &manifest)?;
+    write_row_errors(job, output_path)?;
+    job.stats.duration_ms += duration_ms(started.elapsed());
     Ok(exported_total)
 }
 
-fn write_batch(
-    writer: &mut impl Write,
+/// `template` with `{schema}` and `{table}` replaced by the quoted target names; `None` for a
+/// missing or blank template.
+fn table_hook(
+    template: Option<&str>,
+    schema: &str,
+    table: &str,
+    style: QuoteStyle,
+) -> Option<String> {
+    let template = template.map(str::trim).filter(|sql| !sql.is_empty())?;
+    Some(
+        template
+            .replace("{schema}", &quote_name(schema, style))
+            .replace("{table}", &quote_name(table, style)),
+    )
+}
+
+/// Writes the rows `on_row_error` skipped or changed to `<export>.errors.log`, one per line;
+/// no file when there were none.
+fn write_row_errors(job: &mut JobContext, output_path: &Path) -> Result<()> {
+    if job.row_errors.is_empty() {
+        return Ok(());
+    }
+    let log_path = output_path.with_extension("errors.log");
+    let mut log = job.row_errors.drain(..).collect::<Vec<_>>().join("\n");
+    log.push('\n');
+    fs::write(&log_path, log)
+        .with_context(|| format!("Failed to write row error log {}", log_path.display()))
+}
+
+/// Source MAX of the columns each ascending sequence feeds, keyed by upper-cased sequence name.
+/// Sequences feeding no column, feeding only empty tables or whose MAX could not be read are
+/// left out and keep their START reset.
+fn sequence_high_water(
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
+    schema: &str,
+    sequences: &[Sequence],
+    tables: &[TableDetails],
+) -> HashMap<String, i64> {
+    let fed = sequence_fed_columns(tables);
+    let mut high_water = HashMap::new();
+    'sequences: for seq in sequences.iter().filter(|seq| seq.increment_by > 0) {
+        let key = seq.name.to_uppercase();
+        let Some(columns) = fed.get(&key) else {
+            continue;
+        };
+        let mut max: Option<i64> = None;
+        for (table, column) in columns {
+            let read = session.run(
+                &format!("Reading MAX({}) of {}", column, table),
+                &mut |message| job.record(JobEventKind::Retry, Some(table), Some(message)),
+                |connection| fetch_column_max(connection, schema, table, column),
+            );
+            match read {
+                Ok(value) => max = max.max(value),
+                Err(e) => {
+                    job.warn(
+                        IssueCode::SequenceReseedSkipped,
+                        Some(table),
+                        format!(
+                            "Failed to read MAX({}) of {} for sequence {}, resetting it to START instead: {:#}",
+                            column, table, seq.name, e
+                        ),
+                    );
+                    continue 'sequences;
+                }
+            }
+        }
+        if let Some(max) = max {
+            high_water.insert(key, max);
+        }
+    }
+    high_water
+}
+
+/// Rows written since the last `COMMIT;` of a data script that commits every `every` rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CommitInterval {
+    every: usize,
+    pending: usize,
+}
+
+impl CommitInterval {
+    fn new(every: Option<usize>) -> Option<Self> {
+        every
+            .filter(|&every| every > 0)
+            .map(|every| Self { every, pending: 0 })
+    }
+
+    /// Counts a statement of `rows` rows; true when a `COMMIT;` is due after it.
+    fn record(&mut self, rows: usize) -> bool {
+        self.pending += rows;
+        if self.pending < self.every {
+            return false;
+        }
+        self.pending = 0;
+        true
+    }
+}
+
+/// Renders a fetched batch of row literals as multi-row INSERTs. A new statement starts when
+/// the current one already has `max_rows` rows or the next row would take it past `max_bytes`;
+/// a single row longer than `max_bytes` still gets a statement of its own. With a commit
+/// interval, `COMMIT;` follows the statement that reaches it.
+fn render_batch(
     table: &str,
     columns: &[String],
     batch: &[String],
+    max_rows: Option<usize>,
+    max_bytes: Option<usize>,
+    commit: &mut Option<CommitInterval>,
+) -> String {
+    let head = format!("INSERT INTO {} ({}) VALUES\n", table, columns.join(", "));
+    let max_rows = max_rows.filter(|&rows| rows > 0).unwrap_or(usize::MAX);
+    let mut out = String::new();
+    let mut start = 0;
+    while start < batch.len() {
+        // Statement length so far, including the closing ";\n".
+        let mut bytes = head.len() + batch[start].len() + 2;
+        let mut end = start + 1;
+        while end < batch.len() && end - start < max_rows {
+            let next = bytes + 2 + batch[end].len();
+            if max_bytes.is_some_and(|limit| next > limit) {
+                break;
+            }
+            bytes = next;
+            end += 1;
+        }
+        out.push_str(&head);
+        out.push_str(&batch[start..end].join(",\n"));
+        out.push_str(";\n");
+        if commit.as_mut().is_some_and(|interval| interval.record(end - start)) {
+            out.push_str("COMMIT;\n");
+        }
+        start = end;
+    }
+    out
+}
+
+/// Whether the rows of `table` are loaded between `DISABLE` and `ENABLE ALL TRIGGERS`; tables
+/// without triggers are left alone.
+fn switches_triggers(disable_triggers: bool, table: &TableDetails) -> bool {
+    disable_triggers && !table.triggers.is_empty()
+}
+
+/// Statements ahead of a table's rows: with `disable_triggers`, its triggers are disabled
+/// before identity inserts are switched on.
+fn write_load_prologue(
+    writer: &mut impl Write,
+    qualified_table: &str,
+    table: &TableDetails,
+    disable_triggers: bool,
 ) -> Result<()> {
-    writeln!(
-        writer,
-        "INSERT INTO {} ({}) VALUES\n{};",
-        table,
-        columns.join(", "),
-        batch.join(",\n")
-    )?;
+    if switches_triggers(disable_triggers, table) {
+        write_trigger_switch(writer, qualified_table, false)?;
+    }
+    if table.columns.iter().any(|col| col.identity) {
+        write_identity_insert(writer, qualified_table, true)?;
+    }
+    Ok(())
+}
+
+/// Statements after a table's rows, closing what [`write_load_prologue`] opened in reverse
+/// order. With `restart_style`, the identity is restarted past the source's high-water value.
+fn write_load_epilogue(
+    writer: &mut impl Write,
+    qualified_table: &str,
+    table: &TableDetails,
+    disable_triggers: bool,
+    restart_style: Option<QuoteStyle>,
+) -> Result<()> {
+    if table.columns.iter().any(|col| col.identity) {
+        write_identity_insert(writer, qualified_table, false)?;
+        if let Some(style) = restart_style {
+            match identity_restart_statement(qualified_table, table, style) {
+                Some(statement) => writeln!(writer, "{}", statement)?,
+                None => writeln!(
+                    writer,
+                    "-- Identity of {} has no high-water value in the source; not restarted",
+                    qualified_table
+                )?,
+            }
+        }
+    }
+    if switches_triggers(disable_triggers, table) {
+        write_trigger_switch(writer, qualified_table, true)?;
+    }
     Ok(())
 }
 
@@ -237,7 +1649,13 @@ fn write_identity_insert(writer: &mut impl Write, table: &str, enabled: bool) ->
     Ok(())
 }
 
-fn is_numeric_type(data_type: &str) -> bool {
+fn write_trigger_switch(writer: &mut impl Write, table: &str, enabled: bool) -> Result<()> {
+    let mode = if enabled { "ENABLE" } else { "DISABLE" };
+    writeln!(writer, "ALTER TABLE {} {} ALL TRIGGERS;", table, mode)?;
+    Ok(())
+}
+
+pub fn is_numeric_type(data_type: &str) -> bool {
     let upper = data_type.to_uppercase();
     matches!(
         upper.as_str(),
@@ -245,11 +1663,25 @@ fn is_numeric_type(data_type: &str) -> bool {
     )
 }
 
+fn is_boolean_type(dt: &str) -> bool {
+    matches!(dt, "BIT" | "BOOL" | "BOOLEAN")
+}
+
+/// ODBC returns BIT as `1`/`0`; textual spellings come from BOOLEAN columns and CSV-like
+/// sources. Anything else is left to the quoted-text fallback.
+fn parse_boolean(raw: &str) -> Option<bool> {
+    match raw.trim().to_ascii_uppercase().as_str() {
+        "1" | "TRUE" | "T" | "Y" | "YES" => Some(true),
+        "0" | "FALSE" | "F" | "N" | "NO" => Some(false),
+        _ => None,
+    }
+}
+
 fn escape_single_quotes(value: &str) -> String {
     value.replace('\'', "''")
 }
 
-fn quote_identifier(identifier: &str) -> String {
+pub fn quote_identifier(identifier: &str) -> String {
     identifier
         .split('.')
         .map(|part| format!("\"{}\"", part.replace('"', "\"\"")))
@@ -257,21 +1689,68 @@ fn quote_identifier(identifier: &str) -> String {
         .join(".")
 }
 
-fn is_date_type(dt: &str) -> bool {
+pub fn is_date_type(dt: &str) -> bool {
     matches!(dt.to_uppercase().as_str(), "DATE")
 }
 
-fn is_timestamp_type(dt: &str) -> bool {
+pub fn is_timestamp_type(dt: &str) -> bool {
     matches!(
         dt.to_uppercase().as_str(),
         "TIMESTAMP" | "TIMESTAMP WITH TIME ZONE" | "TIMESTAMP WITH LOCAL TIME ZONE"
     )
 }
 
+/// Upper-case type name with its `(precision)` parts removed: `TIME(3) WITH TIME ZONE` ->
+/// `TIME WITH TIME ZONE`.
+pub fn type_without_precision(dt: &str) -> String {
+    dt.to_uppercase()
+        .split(['(', ')'])
+        .step_by(2)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `TIME` and `TIME WITH TIME ZONE`, with or without a precision.
+fn is_time_type(dt: &str) -> bool {
+    matches!(
+        type_without_precision(dt).as_str(),
+        "TIME" | "TIME WITH TIME ZONE"
+    )
+}
+
+/// `INTERVAL '<value>' <qualifier>`, via `TO_DSINTERVAL`/`TO_YMINTERVAL` for the two interval
+/// types those functions read. A value the driver already returned as an interval literal is
+/// written unchanged.
+fn interval_literal(upper: &str, raw: &str) -> String {
+    let value = raw.trim();
+    if value.to_uppercase().starts_with("INTERVAL") {
+        return value.to_string();
+    }
+    let qualifier = upper["INTERVAL".len()..].trim();
+    match type_without_precision(qualifier).as_str() {
+        "DAY TO SECOND" => format!("TO_DSINTERVAL('{}')", escape_single_quotes(value)),
+        "YEAR TO MONTH" => format!("TO_YMINTERVAL('{}')", escape_single_quotes(value)),
+        _ => format!("INTERVAL '{}' {}", escape_single_quotes(value), qualifier),
+    }
+}
+
 fn is_binary_type(dt: &str) -> bool {
     matches!(dt.to_uppercase().as_str(), "RAW" | "BINARY" | "VARBINARY" | "BLOB")
 }
 
+fn is_text_type(dt: &str) -> bool {
+    let upper = dt.to_uppercase();
+    upper.contains("CHAR") || matches!(upper.as_str(), "TEXT" | "CLOB" | "NCLOB" | "LONG")
+}
+
+/// NCHAR, NVARCHAR(2) and NCLOB, whose literals take the `N` prefix.
+fn is_national_type(dt: &str) -> bool {
+    let upper = dt.to_uppercase();
+    upper.starts_with("NCHAR") || upper.starts_with("NVARCHAR") || upper == "NCLOB"
+}
+
 /// Normalize ISO 8601 timestamp to DM8-compatible format.
 /// Handles: T→space, comma→dot, Z→+00:00, +HH→+HH:00, +HHMM→+HH:MM
 fn normalize_iso8601_timestamp(raw: &str) -> String {
@@ -310,15 +1789,127 @@ fn normalize_iso8601_timestamp(raw: &str) -> String {
     normalized
 }
 
-fn format_literal(data_type: &str, raw: &str) -> String {
+/// Choices in how `format_literal` writes values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LiteralStyle {
+    /// Write CR, LF and TAB inside text as `CHR(n)` pieces instead of raw characters, for
+    /// script runners that split statements at line breaks.
+    pub escape_line_breaks: bool,
+    /// Whether empty text values become `''` or `NULL`.
+    pub empty_string_as: EmptyStringPolicy,
+    /// Target database; decides how BIT/BOOLEAN values are spelled.
+    pub dialect: Dialect,
+    /// Zone values carrying an offset are converted to before they are written.
+    pub timezone: Option<FixedOffset>,
+}
+
+/// Parses the `timezone` option: `UTC` (also `Z`, `GMT`) or a fixed offset such as `+08:00`,
+/// `+0800`, `-05` or `UTC+8`. Named regions are rejected since their offset changes with DST.
+pub fn parse_timezone(value: &str) -> Result<FixedOffset> {
+    let upper = value.trim().to_ascii_uppercase();
+    let offset = upper
+        .strip_prefix("UTC")
+        .or_else(|| upper.strip_prefix("GMT"))
+        .unwrap_or(&upper);
+    if offset.is_empty() || offset == "Z" {
+        return Ok(FixedOffset::east_opt(0).expect("zero offset is valid"));
+    }
+    parse_offset(offset).with_context(|| {
+        format!(
+            "Invalid timezone '{}': expected UTC or an offset such as +08:00",
+            value.trim()
+        )
+    })
+}
+
+/// `+HH`, `+HHMM`, `+HH:MM` and `+H`, either sign.
+fn parse_offset(offset: &str) -> Option<FixedOffset> {
+    let sign = match offset.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits = &offset[1..];
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    let all_digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if hours.len() > 2 || !all_digits(hours) || !all_digits(minutes) {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// `UTC` for a zero offset, else `+HH:MM`; as written into script headers.
+pub fn timezone_label(zone: FixedOffset) -> String {
+    if zone.local_minus_utc() == 0 {
+        "UTC".to_string()
+    } else {
+        zone.to_string()
+    }
+}
+
+/// Re-expresses a normalized `YYYY-MM-DD HH:MM:SS[.f] +HH:MM` value in `zone`. `None` when the
+/// value does not parse, so it is written as fetched.
+fn convert_timezone(normalized: &str, zone: FixedOffset) -> Option<String> {
+    let pos = normalized.rfind(['+', '-'])?;
+    let offset = parse_offset(&normalized[pos..])?;
+    let local =
+        NaiveDateTime::parse_from_str(normalized[..pos].trim(), "%Y-%m-%d %H:%M:%S%.f").ok()?;
+    let at = zone.from_utc_datetime(&(local - offset));
+    Some(at.format("%Y-%m-%d %H:%M:%S%.f%:z").to_string())
+}
+
+/// Quoted column names of `details` for a SELECT, geometry columns read as text.
+fn select_list(details: &TableDetails) -> String {
+    details
+        .columns
+        .iter()
+        .map(|column| {
+            let ident = quote_identifier(&column.name);
+            if is_geometry_type(&column.data_type) {
+                geometry::select_expression(&ident)
+            } else {
+                ident
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_literal(data_type: &str, raw: &str, style: LiteralStyle) -> String {
     let upper = data_type.to_uppercase();
+    if is_geometry_type(&upper) {
+        return geometry_literal(raw);
+    }
     if is_numeric_type(&upper) {
         return raw.to_string();
     }
+    if is_boolean_type(&upper) {
+        if let Some(value) = parse_boolean(raw) {
+            return style.dialect.boolean_literal(value).to_string();
+        }
+    }
     if is_binary_type(&upper) {
         let trimmed = raw.trim_start_matches("0x").trim_start_matches("0X");
         return format!("HEXTORAW('{}')", trimmed);
     }
+    if raw.is_empty() && is_text_type(&upper) && style.empty_string_as == EmptyStringPolicy::Null {
+        return "NULL".to_string();
+    }
+    if upper.starts_with("INTERVAL") {
+        return interval_literal(&upper, raw);
+    }
+    if is_time_type(&upper) {
+        return format!("TIME '{}'", escape_single_quotes(raw.trim()));
+    }
     if is_date_type(&upper) {
         // Choose format based on actual value content
         let format_str = if raw.contains(':') {
@@ -334,10 +1925,15 @@ fn format_literal(data_type: &str, raw: &str) -> String {
     }
     if is_timestamp_type(&upper) {
         // Normalize ISO 8601 format to DM8-compatible format
-        let normalized = normalize_iso8601_timestamp(raw.trim());
+        let mut normalized = normalize_iso8601_timestamp(raw.trim());
 
         // Detect timezone offset (+HH:MM or -HH:MM after time part)
         let has_tz = has_timezone_offset(&normalized);
+        if let Some(zone) = style.timezone.filter(|_| has_tz) {
+            if let Some(converted) = convert_timezone(&normalized, zone) {
+                normalized = converted;
+            }
+        }
 
         // Extract main part (without timezone) for format string analysis
         let main_part = if has_tz {
@@ -390,7 +1986,33 @@ fn format_literal(data_type: &str, raw: &str) -> String {
             format_str
         );
     }
-    format!("'{}'", escape_single_quotes(raw))
+    text_literal(is_national_type(&upper), raw, style)
+}
+
+/// `'value'`, or `N'value'` for national character columns. ASCII control characters other
+/// than tab, line feed and carriage return (those too with `escape_line_breaks`) are written
+/// as `CHR(n)` pieces joined with `||`, since script runners and client charsets do not carry
+/// them through a literal intact.
+fn text_literal(national: bool, value: &str, style: LiteralStyle) -> String {
+    let prefix = if national { "N" } else { "" };
+    let mut pieces = Vec::new();
+    let mut run = String::new();
+    for c in value.chars() {
+        let line_break = matches!(c, '\t' | '\n' | '\r');
+        if c.is_ascii_control() && (style.escape_line_breaks || !line_break) {
+            if !run.is_empty() {
+                pieces.push(format!("{}'{}'", prefix, escape_single_quotes(&run)));
+                run.clear();
+            }
+            pieces.push(format!("CHR({})", u32::from(c)));
+        } else {
+            run.push(c);
+        }
+    }
+    if !run.is_empty() || pieces.is_empty() {
+        pieces.push(format!("{}'{}'", prefix, escape_single_quotes(&run)));
+    }
+    pieces.join("||")
 }
 
 /// Check if the string has a timezone offset (+HH:MM or -HH:MM).
@@ -416,3 +2038,452 @@ fn has_timezone_offset(s: &str) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_literal, identity_restart_statement, keyset_key, keyset_query, keyset_resume_key,
+        parse_timezone, render_batch, select_list, table_hook, timezone_label, value_fits_type,
+        wide_buffer_lengths, write_load_epilogue, write_load_prologue,
+        write_unreadable_placeholder, CommitInterval, LiteralStyle, RowScreen,
+        MAX_TEXT_VALUE_BYTES,
+    };
+    use std::{collections::HashMap, io::Write, num::NonZeroUsize};
+
+    use crate::export::dialect::Dialect;
+    use crate::export::memory::wide_text_lengths;
+    use crate::models::{
+        Column, EmptyStringPolicy, QuoteStyle, RowErrorPolicy, TableDetails, TriggerDefinition,
+    };
+
+    fn column(name: &str, data_type: &str, scale: Option<i32>) -> Column {
+        Column {
+            scale,
+            nullable: false,
+            ..Column::named(name, data_type)
+        }
+    }
+
+    fn table(columns: Vec<Column>, primary_keys: &[&str]) -> TableDetails {
+        TableDetails::named("ORDERS", columns, primary_keys)
+    }
+
+    #[test]
+    fn keyset_requires_single_integer_primary_key() {
+        let columns = vec![
+            column("CODE", "VARCHAR", None),
+            column("ID", "NUMBER", Some(0)),
+            column("AMOUNT", "DECIMAL", Some(2)),
+        ];
+        assert_eq!(keyset_key(&table(columns.clone(), &["ID"])), Some(1));
+        assert_eq!(keyset_key(&table(columns.clone(), &["CODE"])), None);
+        assert_eq!(keyset_key(&table(columns.clone(), &["AMOUNT"])), None);
+        assert_eq!(keyset_key(&table(columns.clone(), &["ID", "CODE"])), None);
+        assert_eq!(keyset_key(&table(columns, &[])), None);
+
+        let unknown_scale = vec![column("ID", "NUMBER", None)];
+        assert_eq!(keyset_key(&table(unknown_scale, &["ID"])), None);
+    }
+
+    #[test]
+    fn keyset_resume_key_matches_the_catalog_table_name() {
+        let resume_after = HashMap::from([
+            ("orders".to_string(), " 1200 ".to_string()),
+            ("ITEMS".to_string(), "12.5".to_string()),
+        ]);
+        assert_eq!(
+            keyset_resume_key(&resume_after, "ORDERS", false)
+                .unwrap()
+                .as_deref(),
+            Some("1200")
+        );
+        assert_eq!(
+            keyset_resume_key(&resume_after, "ORDERS", true).unwrap(),
+            None
+        );
+        assert!(keyset_resume_key(&resume_after, "ITEMS", false).is_err());
+    }
+
+    #[test]
+    fn table_hooks_fill_in_quoted_target_names() {
+        let template = Some("ALTER TABLE {schema}.{table} DISABLE ALL TRIGGERS;");
+        assert_eq!(
+            table_hook(template, "APP", "ORDERS", QuoteStyle::Always).as_deref(),
+            Some("ALTER TABLE \"APP\".\"ORDERS\" DISABLE ALL TRIGGERS;")
+        );
+        assert_eq!(
+            table_hook(template, "APP", "ORDERS", QuoteStyle::Never).as_deref(),
+            Some("ALTER TABLE APP.ORDERS DISABLE ALL TRIGGERS;")
+        );
+        assert_eq!(
+            table_hook(Some("  "), "APP", "ORDERS", QuoteStyle::Always),
+            None
+        );
+        assert_eq!(table_hook(None, "APP", "ORDERS", QuoteStyle::Always), None);
+    }
+
+    #[test]
+    fn values_are_checked_against_their_column_type() {
+        for number in ["42", "-0.5", ".5", "7.", "+1.5E-10"] {
+            assert!(value_fits_type("NUMBER", number), "{}", number);
+        }
+        for junk in ["", "abc", "1.2.3", "1e", "."] {
+            assert!(!value_fits_type("DECIMAL", junk), "{}", junk);
+        }
+        assert!(value_fits_type("BLOB", "0x0AFF"));
+        assert!(!value_fits_type("RAW", "0xZZ"));
+        assert!(value_fits_type(
+            "TIMESTAMP WITH TIME ZONE",
+            "2026-01-30 08:15:00.5 +08:00"
+        ));
+        assert!(!value_fits_type("DATE", "N/A"));
+        assert!(value_fits_type("VARCHAR", "N/A"));
+    }
+
+    #[test]
+    fn row_errors_abort_skip_or_null_per_policy() {
+        let details = table(
+            vec![
+                column("ID", "INT", None),
+                column("AMOUNT", "DECIMAL", Some(2)),
+            ],
+            &["ID"],
+        );
+        let rows = || {
+            vec![
+                vec![Some("1".to_string()), Some("9.50".to_string())],
+                vec![Some("2".to_string()), Some("n/a".to_string())],
+            ]
+        };
+
+        let mut abort = RowScreen::new(RowErrorPolicy::Abort, "APP.ORDERS", &details);
+        let err = abort.screen(rows()).unwrap_err().to_string();
+        assert!(err.contains("(ID=2) of APP.ORDERS: AMOUNT 'n/a' is not a valid DECIMAL"));
+
+        let mut skip = RowScreen::new(RowErrorPolicy::Skip, "APP.ORDERS", &details);
+        assert_eq!(skip.screen(rows()).unwrap(), rows()[..1].to_vec());
+        assert_eq!(skip.skipped, 1);
+        assert!(skip.log[0].ends_with("row skipped"));
+
+        let mut null = RowScreen::new(RowErrorPolicy::Null, "APP.ORDERS", &details);
+        let kept = null.screen(rows()).unwrap();
+        assert_eq!(kept[1], vec![Some("2".to_string()), None]);
+        assert_eq!((null.skipped, null.nulled), (0, 1));
+
+        let keyless = table(details.columns.clone(), &[]);
+        let mut skip = RowScreen::new(RowErrorPolicy::Skip, "APP.ORDERS", &keyless);
+        skip.screen(rows()).unwrap();
+        assert!(skip.log[0].starts_with("APP.ORDERS (row 2): "));
+    }
+
+    #[test]
+    fn unreadable_table_placeholder_is_commented_out() {
+        let mut script = Vec::new();
+        write_unreadable_placeholder(
+            &mut script,
+            "APP.SALARIES",
+            QuoteStyle::Always,
+            "Cannot read table APP.SALARIES\n[-5504] no privilege",
+        )
+        .unwrap();
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.lines().all(|line| line.starts_with("-- ")));
+        assert!(script.contains("-- [-5504] no privilege\n"));
+        assert!(script.contains("-- TRUNCATE TABLE \"APP\".\"SALARIES\";"));
+    }
+
+    #[test]
+    fn identity_restart_continues_after_source_high_water() {
+        let mut id = column("ID", "INT", None);
+        id.identity = true;
+        id.identity_increment = Some(5);
+        let mut details = table(vec![id, column("CODE", "VARCHAR", None)], &["ID"]);
+        assert_eq!(
+            identity_restart_statement("\"APP\".\"ORDERS\"", &details, QuoteStyle::Always),
+            None
+        );
+
+        details.columns[0].identity_current = Some(120);
+        assert_eq!(
+            identity_restart_statement("\"APP\".\"ORDERS\"", &details, QuoteStyle::Always)
+                .as_deref(),
+            Some("ALTER TABLE \"APP\".\"ORDERS\" ALTER COLUMN \"ID\" RESTART WITH 125;")
+        );
+        assert_eq!(
+            identity_restart_statement("APP.ORDERS", &details, QuoteStyle::WhenNeeded).as_deref(),
+            Some("ALTER TABLE APP.ORDERS ALTER COLUMN ID RESTART WITH 125;")
+        );
+
+        let plain = table(vec![column("ID", "INT", None)], &["ID"]);
+        assert_eq!(
+            identity_restart_statement("\"APP\".\"ORDERS\"", &plain, QuoteStyle::Always),
+            None
+        );
+    }
+
+    /// A table's data section with a single INSERT between its prologue and epilogue.
+    fn load_section(details: &TableDetails, disable_triggers: bool) -> String {
+        let qualified = "\"APP\".\"ORDERS\"";
+        let mut out = Vec::new();
+        write_load_prologue(&mut out, qualified, details, disable_triggers).unwrap();
+        writeln!(out, "INSERT INTO {} VALUES (1);", qualified).unwrap();
+        write_load_epilogue(&mut out, qualified, details, disable_triggers, None).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn triggers_are_disabled_around_the_rows_of_tables_that_have_them() {
+        let mut id = column("ID", "INT", None);
+        id.identity = true;
+        let mut details = table(vec![id], &["ID"]);
+        details.triggers = vec![TriggerDefinition {
+            name: "TRG_ORDERS_AUDIT".to_string(),
+            table_name: "ORDERS".to_string(),
+            timing: "AFTER".to_string(),
+            events: vec!["INSERT".to_string()],
+            each_row: true,
+            body: "BEGIN NULL; END;".to_string(),
+        }];
+        assert_eq!(
+            load_section(&details, true),
+            "ALTER TABLE \"APP\".\"ORDERS\" DISABLE ALL TRIGGERS;\n\
+             SET IDENTITY_INSERT \"APP\".\"ORDERS\" ON;\n\
+             INSERT INTO \"APP\".\"ORDERS\" VALUES (1);\n\
+             SET IDENTITY_INSERT \"APP\".\"ORDERS\" OFF;\n\
+             ALTER TABLE \"APP\".\"ORDERS\" ENABLE ALL TRIGGERS;\n"
+        );
+        assert!(!load_section(&details, false).contains("ALL TRIGGERS"));
+
+        // A table without triggers is loaded as is, even with the option on.
+        let plain = table(vec![column("ID", "INT", None)], &["ID"]);
+        assert_eq!(
+            load_section(&plain, true),
+            "INSERT INTO \"APP\".\"ORDERS\" VALUES (1);\n"
+        );
+    }
+
+    #[test]
+    fn text_literals_keep_national_prefix_and_escape_control_characters() {
+        let raw = LiteralStyle::default();
+        assert_eq!(format_literal("VARCHAR", "it's", raw), "'it''s'");
+        assert_eq!(format_literal("NVARCHAR", "日本語 ✓", raw), "N'日本語 ✓'");
+        assert_eq!(format_literal("nchar", "x", raw), "N'x'");
+        assert_eq!(format_literal("NVARCHAR", "", raw), "N''");
+        assert_eq!(
+            format_literal("VARCHAR", "a\u{0}b\u{1b}", raw),
+            "'a'||CHR(0)||'b'||CHR(27)"
+        );
+        assert_eq!(format_literal("NCLOB", "\u{7}é", raw), "CHR(7)||N'é'");
+        assert_eq!(
+            format_literal("CLOB", "line\nnext\t", raw),
+            "'line\nnext\t'"
+        );
+    }
+
+    #[test]
+    fn line_breaks_become_chr_pieces_when_escaped() {
+        let escaped = LiteralStyle {
+            escape_line_breaks: true,
+            ..LiteralStyle::default()
+        };
+        assert_eq!(
+            format_literal("VARCHAR", "a\r\nb\tc", escaped),
+            "'a'||CHR(13)||CHR(10)||'b'||CHR(9)||'c'"
+        );
+        assert_eq!(format_literal("NVARCHAR", "\n", escaped), "CHR(10)");
+        assert_eq!(format_literal("VARCHAR", "plain", escaped), "'plain'");
+    }
+
+    #[test]
+    fn empty_text_values_follow_policy() {
+        let as_null = LiteralStyle {
+            empty_string_as: EmptyStringPolicy::Null,
+            ..LiteralStyle::default()
+        };
+        assert_eq!(format_literal("VARCHAR", "", LiteralStyle::default()), "''");
+        assert_eq!(format_literal("VARCHAR(20)", "", as_null), "NULL");
+        assert_eq!(format_literal("NCHAR", "", as_null), "NULL");
+        assert_eq!(format_literal("VARCHAR", " ", as_null), "' '");
+        assert_ne!(format_literal("BLOB", "", as_null), "NULL");
+    }
+
+    #[test]
+    fn boolean_values_are_unquoted_per_dialect() {
+        let dm8 = LiteralStyle::default();
+        let pg = LiteralStyle {
+            dialect: Dialect::PostgreSql,
+            ..LiteralStyle::default()
+        };
+        assert_eq!(format_literal("BIT", "1", dm8), "1");
+        assert_eq!(format_literal("bit", "0", dm8), "0");
+        assert_eq!(format_literal("BOOLEAN", "true", dm8), "1");
+        assert_eq!(format_literal("BIT", "1", pg), "TRUE");
+        assert_eq!(format_literal("BOOLEAN", "F", pg), "FALSE");
+        assert_eq!(format_literal("BIT", "maybe", pg), "'maybe'");
+    }
+
+    #[test]
+    fn timezone_option_accepts_utc_and_offsets() {
+        assert_eq!(timezone_label(parse_timezone("utc").unwrap()), "UTC");
+        assert_eq!(timezone_label(parse_timezone("Z").unwrap()), "UTC");
+        assert_eq!(timezone_label(parse_timezone("+08:00").unwrap()), "+08:00");
+        assert_eq!(timezone_label(parse_timezone("+0530").unwrap()), "+05:30");
+        assert_eq!(timezone_label(parse_timezone("UTC-5").unwrap()), "-05:00");
+        assert!(parse_timezone("Asia/Shanghai").is_err());
+        assert!(parse_timezone("+08:75").is_err());
+    }
+
+    #[test]
+    fn timestamps_with_offset_are_converted_to_export_zone() {
+        let utc = LiteralStyle {
+            timezone: Some(parse_timezone("UTC").unwrap()),
+            ..LiteralStyle::default()
+        };
+        assert_eq!(
+            format_literal(
+                "TIMESTAMP WITH TIME ZONE",
+                "2024-03-01 02:30:00.250 +08:00",
+                utc
+            ),
+            "TO_TIMESTAMP_TZ('2024-02-29 18:30:00.250+00:00','YYYY-MM-DD HH24:MI:SS.FF TZH:TZM')"
+        );
+        assert_eq!(
+            format_literal("TIMESTAMP WITH TIME ZONE", "2024-03-01T10:00:00-0500", utc),
+            "TO_TIMESTAMP_TZ('2024-03-01 15:00:00+00:00','YYYY-MM-DD HH24:MI:SS TZH:TZM')"
+        );
+        // Values without an offset have no zone to convert from.
+        assert_eq!(
+            format_literal("TIMESTAMP", "2024-03-01 02:30:00", utc),
+            "TO_TIMESTAMP('2024-03-01 02:30:00','YYYY-MM-DD HH24:MI:SS')"
+        );
+    }
+
+    #[test]
+    fn time_and_interval_values_get_typed_literals() {
+        let raw = LiteralStyle::default();
+        assert_eq!(format_literal("TIME", "12:34:56", raw), "TIME '12:34:56'");
+        assert_eq!(
+            format_literal("TIME(3) WITH TIME ZONE", "12:34:56.125 +08:00", raw),
+            "TIME '12:34:56.125 +08:00'"
+        );
+        assert_eq!(
+            format_literal("INTERVAL DAY(2) TO SECOND(6)", "+01 02:03:04.500000", raw),
+            "TO_DSINTERVAL('+01 02:03:04.500000')"
+        );
+        assert_eq!(
+            format_literal("INTERVAL YEAR TO MONTH", "1-6", raw),
+            "TO_YMINTERVAL('1-6')"
+        );
+        assert_eq!(
+            format_literal("INTERVAL DAY TO HOUR", "3 04", raw),
+            "INTERVAL '3 04' DAY TO HOUR"
+        );
+        let fetched = "INTERVAL '1-6' YEAR(2) TO MONTH";
+        assert_eq!(
+            format_literal("INTERVAL YEAR TO MONTH", fetched, raw),
+            fetched
+        );
+    }
+
+    #[test]
+    fn geometry_columns_are_selected_as_text_and_rebuilt() {
+        let shapes = table(
+            vec![column("ID", "INT", None), column("SHAPE", "ST_POINT", None)],
+            &["ID"],
+        );
+        assert_eq!(
+            select_list(&shapes),
+            "\"ID\", CASE WHEN \"SHAPE\" IS NULL THEN NULL ELSE 'SRID=' || \
+             DMGEO.ST_SRID(\"SHAPE\") || ';' || DMGEO.ST_ASTEXT(\"SHAPE\") END"
+        );
+        let raw = LiteralStyle::default();
+        let point = format_literal("SYSGEO.ST_POINT", "SRID=4326;POINT(1 2)", raw);
+        assert_eq!(point, "DMGEO.ST_GEOMFROMTEXT('POINT(1 2)', 4326)");
+    }
+
+    #[test]
+    fn keyset_query_pages_after_last_key() {
+        assert_eq!(
+            keyset_query("\"ID\", \"NAME\"", "\"APP\".\"T\"", "\"ID\"", None, 500),
+            "SELECT \"ID\", \"NAME\" FROM \"APP\".\"T\" ORDER BY \"ID\" LIMIT 500"
+        );
+        assert_eq!(
+            keyset_query("\"ID\"", "\"APP\".\"T\"", "\"ID\"", Some(-42), 10),
+            "SELECT \"ID\" FROM \"APP\".\"T\" WHERE \"ID\" > -42 ORDER BY \"ID\" LIMIT 10"
+        );
+    }
+
+    #[test]
+    fn wide_fetch_sizes_intervals_from_the_driver() {
+        let mut name = column("NAME", "VARCHAR", None);
+        name.length = Some(10);
+        let mut span = column("SPAN", "INTERVAL DAY(2) TO SECOND(6)", None);
+        span.precision = Some(2);
+        let lengths = wide_text_lengths(&table(vec![name, span], &[]));
+        assert_eq!(lengths, vec![Some(20), None]);
+
+        // `+01 02:03:04.500000` does not fit the catalog precision, but the display size.
+        let mut asked = Vec::new();
+        let buffers = wide_buffer_lengths(&lengths, |column| {
+            asked.push(column);
+            Ok(NonZeroUsize::new(29))
+        })
+        .unwrap();
+        assert_eq!(buffers, vec![20, 29]);
+        assert_eq!(asked, vec![2]);
+
+        let unbounded = wide_buffer_lengths(&[None], |_| Ok(None)).unwrap();
+        assert_eq!(unbounded, vec![MAX_TEXT_VALUE_BYTES]);
+    }
+
+    #[test]
+    fn batches_split_by_row_count_and_statement_size() {
+        let columns = vec!["\"ID\"".to_string()];
+        let rows: Vec<String> = (1..=5).map(|id| format!("({})", id)).collect();
+
+        assert_eq!(
+            render_batch("T", &columns, &rows, None, None, &mut None),
+            "INSERT INTO T (\"ID\") VALUES\n(1),\n(2),\n(3),\n(4),\n(5);\n"
+        );
+        assert_eq!(
+            render_batch("T", &columns, &rows, Some(2), None, &mut None),
+            "INSERT INTO T (\"ID\") VALUES\n(1),\n(2);\n\
+             INSERT INTO T (\"ID\") VALUES\n(3),\n(4);\n\
+             INSERT INTO T (\"ID\") VALUES\n(5);\n"
+        );
+
+        // The header is 28 bytes, each row 3 plus a 2-byte separator or terminator.
+        let statements = render_batch("T", &columns, &rows, None, Some(38), &mut None);
+        assert_eq!(
+            statements,
+            "INSERT INTO T (\"ID\") VALUES\n(1),\n(2);\n\
+             INSERT INTO T (\"ID\") VALUES\n(3),\n(4);\n\
+             INSERT INTO T (\"ID\") VALUES\n(5);\n"
+        );
+        assert!(statements.split_inclusive(";\n").all(|s| s.len() <= 38));
+
+        // A row that alone exceeds the cap is still written.
+        let oversized = render_batch("T", &columns, &rows[..2], None, Some(10), &mut None);
+        assert_eq!(oversized.matches("INSERT").count(), 2);
+    }
+
+    #[test]
+    fn commits_after_the_statement_that_reaches_the_interval() {
+        let columns = vec!["\"ID\"".to_string()];
+        let rows: Vec<String> = (1..=5).map(|id| format!("({})", id)).collect();
+        let mut commit = CommitInterval::new(Some(3));
+
+        let first = render_batch("T", &columns, &rows, Some(2), None, &mut commit);
+        assert_eq!(
+            first,
+            "INSERT INTO T (\"ID\") VALUES\n(1),\n(2);\n\
+             INSERT INTO T (\"ID\") VALUES\n(3),\n(4);\nCOMMIT;\n\
+             INSERT INTO T (\"ID\") VALUES\n(5);\n"
+        );
+        // The count carries over into the next fetched batch of the same table.
+        let second = render_batch("T", &columns, &rows[..2], None, None, &mut commit);
+        assert!(second.ends_with("(2);\nCOMMIT;\n"));
+        assert_eq!(commit.map(|c| c.pending), Some(0));
+        assert_eq!(CommitInterval::new(Some(0)), None);
+    }
+}
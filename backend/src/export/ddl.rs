@@ -1,18 +1,39 @@
 use std::{
-    collections::HashSet,
+    collections::{BTreeSet, HashMap, HashSet},
     fmt::Write as FmtWrite,
     fs::{self, File},
-    io::{BufWriter, Write},
+    io::Write,
     path::Path,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result};
 use chrono::Local;
-use odbc_api::Connection;
 
 use crate::{
-    db::schema::{fetch_sequences, get_table_details},
-    models::{Column, Index, Sequence, TableDetails, TriggerDefinition},
+    db::metadata_cache::{cached_table_details, cached_tables_details},
+    db::retry::RetryingConnection,
+    db::schema::{
+        catalog_name, fetch_case_sensitivity, fetch_object_grants, fetch_sequences, fetch_views,
+    },
+    export::{
+        charset::check_export_file,
+        dependency::add_referenced_tables,
+        dialect::{translate_defaults, Dialect},
+        encoding::{script_writer, unmappable_count, warn_unmappable},
+        geometry::{is_geometry_type, qualified_type, srid_check},
+        job::JobContext,
+        quoting::{apply_quote_style, literal_len},
+        renames::{apply_table_renames, renamed_table},
+        run_order::{Phase, RunOrder},
+        sequences::{apply_sequence_renames, filter_referenced_sequences},
+        stats::{duration_ms, CountingWriter},
+    },
+    models::{
+        Column, CommentsPlacement, DdlObjectType, ExportEncoding, IdentityGeneration, Index,
+        IndexColumn, IssueCode, JobEventKind, LineEnding, ObjectGrant, QuoteStyle, Sequence,
+        TableDetails, TriggerDefinition, ViewDefinition,
+    },
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,45 +43,178 @@ pub enum TriggerTerminator {
     DataGripScript,
 }
 
+/// Which objects of the selection a DDL script holds. The combined export writes the objects
+/// the rows need before its data and the ones that would slow down or reject the load after it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DdlStage {
+    #[default]
+    Complete,
+    /// Schema, tables, comments, primary key, unique and check constraints, indexes and
+    /// sequences.
+    BeforeData,
+    /// Foreign keys, triggers, views and grants.
+    AfterData,
+}
+
+impl DdlStage {
+    pub fn before_data(self) -> bool {
+        self != DdlStage::AfterData
+    }
+
+    pub fn after_data(self) -> bool {
+        self != DdlStage::BeforeData
+    }
+}
+
+/// Output options for `export_schema_ddl`.
+#[derive(Debug, Clone)]
+pub struct DdlExportOptions {
+    pub drop_existing: bool,
+    pub trigger_terminator: TriggerTerminator,
+    pub include_storage: bool,
+    pub tablespace_map: HashMap<String, String>,
+    pub include_grants: bool,
+    pub objects: BTreeSet<DdlObjectType>,
+    /// Drop sequences that no selected table references.
+    pub only_referenced_sequences: bool,
+    /// Source sequence name -> target name, applied to sequences, defaults and trigger bodies.
+    pub sequence_renames: HashMap<String, String>,
+    /// Source table name -> target name, applied to tables, foreign key references, derived
+    /// index/constraint/trigger names and grants.
+    pub table_renames: HashMap<String, String>,
+    /// Encoding of the written script files.
+    pub encoding: ExportEncoding,
+    pub line_ending: LineEnding,
+    /// Add tables the selection references through foreign keys, and the sequences the tables'
+    /// defaults and triggers use, when they were not selected.
+    pub include_dependencies: bool,
+    /// Drop check constraints that only repeat a column's NOT NULL and quote column names in
+    /// the remaining conditions.
+    pub skip_not_null_checks: bool,
+    /// Target database of the script; column defaults go through its translation table.
+    pub dialect: Dialect,
+    /// Take schema and table names as given instead of upper-casing them, for tables created
+    /// with quoted mixed- or lower-case names.
+    pub preserve_case: bool,
+    /// Start the script with the `CREATE SCHEMA` of the target schema.
+    pub include_schema_ddl: bool,
+    /// User the created schema is authorized to; `SYSDBA` when unset.
+    pub schema_owner: Option<String>,
+    /// Which identifiers the written statements keep quoted.
+    pub quote_style: QuoteStyle,
+    /// Where the selected comments go; `Omit` drops them like an unselected object kind.
+    pub comments_placement: CommentsPlacement,
+    pub stage: DdlStage,
+    /// In a staged export, write the indexes in the after-data script instead of with their
+    /// tables.
+    pub indexes_after_data: bool,
+}
+
+impl DdlExportOptions {
+    pub fn includes(&self, kind: DdlObjectType) -> bool {
+        self.objects.contains(&kind)
+    }
+
+    /// Whether this script writes the selected indexes: with their tables before the data, or
+    /// on their own after it with `indexes_after_data`.
+    fn writes_indexes(&self, after_data: bool) -> bool {
+        let deferred = self.indexes_after_data && self.stage != DdlStage::Complete;
+        let stage_writes = if after_data {
+            self.stage == DdlStage::AfterData
+        } else {
+            self.stage.before_data()
+        };
+        self.includes(DdlObjectType::Indexes) && deferred == after_data && stage_writes
+    }
+}
+
+/// Drops tablespace assignments when storage clauses are disabled, otherwise applies the
+/// source -> target remapping (case-insensitive on the source name).
+fn apply_storage_options(table: &mut TableDetails, options: &DdlExportOptions) {
+    let map_tablespace = |tablespace: Option<String>| {
+        if !options.include_storage {
+            return None;
+        }
+        tablespace.map(|ts| {
+            options
+                .tablespace_map
+                .iter()
+                .find(|(source, _)| source.eq_ignore_ascii_case(&ts))
+                .map(|(_, target)| target.clone())
+                .unwrap_or(ts)
+        })
+    };
+
+    table.tablespace = map_tablespace(table.tablespace.take());
+    for index in &mut table.indexes {
+        index.tablespace = map_tablespace(index.tablespace.take());
+    }
+}
+
+fn tablespace_clause(tablespace: Option<&str>) -> String {
+    match tablespace.map(str::trim).filter(|ts| !ts.is_empty()) {
+        Some(ts) => format!(" TABLESPACE {}", quote_identifier(ts)),
+        None => String::new(),
+    }
+}
+
 pub fn generate_create_table(table: &TableDetails) -> String {
     let table_ident = quote_identifier(&table.name);
 
     let column_lines = table
         .columns
         .iter()
-        .map(|col| format!("    {}", format_column_definition(col)))
+        .map(|col| {
+            format!(
+                "    {}",
+                format_column_definition(col, table.default_collation.as_deref())
+            )
+        })
         .collect::<Vec<_>>()
         .join(",\n");
 
     let mut ddl = String::new();
     let _ = writeln!(
         ddl,
-        "CREATE TABLE {} (\n{}\n);",
-        table_ident, column_lines
+        "CREATE TABLE {} (\n{}\n){}{};",
+        table_ident,
+        column_lines,
+        table
+            .default_collation
+            .as_deref()
+            .map(|collation| format!(" DEFAULT COLLATION {}", collation))
+            .unwrap_or_default(),
+        tablespace_clause(table.tablespace.as_deref())
     );
 
+    ddl.trim_end().to_string()
+}
+
+/// `COMMENT ON TABLE` / `COMMENT ON COLUMN` statements for non-empty comments.
+pub fn generate_comments(table: &TableDetails) -> Vec<String> {
+    let table_ident = quote_identifier(&table.name);
+    let mut statements = Vec::new();
+
     if let Some(comment) = table.comment.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
-        let _ = writeln!(
-            ddl,
+        statements.push(format!(
             "COMMENT ON TABLE {} IS '{}';",
             table_ident,
             escape_single_quotes(comment)
-        );
+        ));
     }
 
     for column in &table.columns {
         if let Some(comment) = column.comment.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
-            let _ = writeln!(
-                ddl,
+            statements.push(format!(
                 "COMMENT ON COLUMN {}.{} IS '{}';",
                 table_ident,
                 quote_identifier(&column.name),
                 escape_single_quotes(comment)
-            );
+            ));
         }
     }
 
-    ddl.trim_end().to_string()
+    statements
 }
 
 pub fn generate_primary_key(table: &TableDetails) -> Option<String> {
@@ -90,7 +244,19 @@ pub fn generate_primary_key(table: &TableDetails) -> Option<String> {
     ))
 }
 
-pub fn generate_indexes(table: &TableDetails) -> Vec<String> {
+/// Why an index is left out of the generated DDL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexSkip {
+    /// The catalog returned no columns for it.
+    NoColumns,
+    /// Same column set as the primary key or a unique constraint, which creates its own index.
+    CoveredByConstraint,
+    /// Same ordered column list as an earlier index.
+    Duplicate,
+}
+
+/// The skip decision for every index of `table`, in declaration order.
+fn index_skips(table: &TableDetails) -> Vec<Option<IndexSkip>> {
     let mut reserved_sets: HashSet<String> = HashSet::new();
     let mut seen_index_keys: HashSet<String> = HashSet::new();
 
@@ -106,29 +272,55 @@ pub fn generate_indexes(table: &TableDetails) -> Vec<String> {
     table
         .indexes
         .iter()
-        .filter_map(|index| {
+        .map(|index| {
             if index.columns.is_empty() {
-                return None;
+                return Some(IndexSkip::NoColumns);
             }
 
-            let ordered_key = normalize_columns_ordered(&index.columns);
-            let sorted_key = normalize_columns_sorted(&index.columns);
+            let key_parts = index_key_parts(index);
+            let ordered_key = normalize_columns_ordered(&key_parts);
+            let sorted_key = normalize_columns_sorted(&key_parts);
 
             // Skip indexes that cover the same column set as PK/unique constraints.
             if reserved_sets.contains(&sorted_key) {
-                return None;
+                return Some(IndexSkip::CoveredByConstraint);
             }
 
             // Skip duplicate indexes that use the same ordered column list.
-            if seen_index_keys.contains(&ordered_key) {
-                return None;
+            if !seen_index_keys.insert(ordered_key) {
+                return Some(IndexSkip::Duplicate);
             }
-            seen_index_keys.insert(ordered_key);
+            None
+        })
+        .collect()
+}
+
+/// Indexes of `table` whose definition is lost from the export: those without columns and
+/// duplicates. Indexes covered by a constraint are not listed, the constraint recreates them.
+pub fn skipped_indexes(table: &TableDetails) -> Vec<(&Index, IndexSkip)> {
+    table
+        .indexes
+        .iter()
+        .zip(index_skips(table))
+        .filter_map(|(index, skip)| match skip {
+            Some(IndexSkip::CoveredByConstraint) | None => None,
+            Some(skip) => Some((index, skip)),
+        })
+        .collect()
+}
 
+pub fn generate_indexes(table: &TableDetails) -> Vec<String> {
+    table
+        .indexes
+        .iter()
+        .zip(index_skips(table))
+        .filter(|(_, skip)| skip.is_none())
+        .map(|(index, _)| {
             let columns = index
                 .columns
                 .iter()
-                .map(|s| quote_identifier(s))
+                .enumerate()
+                .map(|(pos, column)| render_index_column(column, index.column_specs.get(pos)))
                 .collect::<Vec<_>>()
                 .join(", ");
 
@@ -140,17 +332,46 @@ pub fn generate_indexes(table: &TableDetails) -> Vec<String> {
                 "CREATE INDEX"
             };
 
-            Some(format!(
-                "{} {} ON {} ({});",
+            format!(
+                "{} {} ON {} ({}){};",
                 prefix,
                 quote_identifier(&index_name),
                 quote_identifier(&table.name),
-                columns
-            ))
+                columns,
+                tablespace_clause(index.tablespace.as_deref())
+            )
         })
         .collect()
 }
 
+/// Identity of each index column for duplicate detection. Plain ascending columns use the bare
+/// name so they still match PK/unique column sets; DESC and expression columns never do.
+fn index_key_parts(index: &Index) -> Vec<String> {
+    index
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(pos, column)| match index.column_specs.get(pos) {
+            Some(spec) if spec.descending || spec.expression.is_some() => {
+                render_index_column(column, Some(spec))
+            }
+            _ => column.clone(),
+        })
+        .collect()
+}
+
+fn render_index_column(column: &str, spec: Option<&IndexColumn>) -> String {
+    let base = match spec.and_then(|s| s.expression.as_deref()) {
+        Some(expression) => expression.to_string(),
+        None => quote_identifier(column),
+    };
+    if spec.is_some_and(|s| s.descending) {
+        format!("{} DESC", base)
+    } else {
+        base
+    }
+}
+
 fn normalize_columns_ordered(columns: &[String]) -> String {
     columns
         .iter()
@@ -217,6 +438,101 @@ pub fn generate_unique_constraints(table: &TableDetails) -> Vec<String> {
         .collect()
 }
 
+/// With `skip_not_null_checks`, drops the check constraints DM8 generates for NOT NULL columns
+/// (`"COL" IS NOT NULL`, already expressed by the column definition) and rewrites the other
+/// conditions with quoted column names, as the rest of the script writes them.
+fn sanitize_check_constraints(table: &mut TableDetails, options: &DdlExportOptions) {
+    if !options.skip_not_null_checks {
+        return;
+    }
+    let columns = &table.columns;
+    table
+        .check_constraints
+        .retain(|check| !repeats_not_null(&check.condition, columns));
+    for check in &mut table.check_constraints {
+        check.condition = quote_condition_columns(&check.condition, columns);
+    }
+}
+
+/// True for `COL IS NOT NULL` (optionally quoted and parenthesized) on a NOT NULL column.
+fn repeats_not_null(condition: &str, columns: &[Column]) -> bool {
+    let mut condition = condition.trim();
+    while let Some(inner) = condition
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        condition = inner.trim();
+    }
+    let words: Vec<&str> = condition.split_whitespace().collect();
+    let [column, is, not, null] = words.as_slice() else {
+        return false;
+    };
+    if !is.eq_ignore_ascii_case("IS")
+        || !not.eq_ignore_ascii_case("NOT")
+        || !null.eq_ignore_ascii_case("NULL")
+    {
+        return false;
+    }
+    // Quoted names are case-sensitive; DM8 folds unquoted ones to upper case.
+    let name = match column
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        Some(quoted) => quoted.to_string(),
+        None => column.to_uppercase(),
+    };
+    columns.iter().any(|col| col.name == name && !col.nullable)
+}
+
+/// Quotes the bare words of `condition` that name a column of the table. String literals,
+/// quoted identifiers, qualified names and function calls are left alone.
+fn quote_condition_columns(condition: &str, columns: &[Column]) -> String {
+    let chars: Vec<char> = condition.chars().collect();
+    let mut out = String::with_capacity(condition.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' || c == '"' {
+            // Copy the literal or quoted identifier; a doubled quote continues it.
+            let start = i;
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c {
+                    if chars.get(i + 1) == Some(&c) {
+                        i += 2;
+                        continue;
+                    }
+                    i += 1;
+                    break;
+                }
+                i += 1;
+            }
+            out.extend(&chars[start..i]);
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || "_$#".contains(chars[i])) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let qualified = (start > 0 && chars[start - 1] == '.') || chars.get(i) == Some(&'.');
+            let call = chars[i..]
+                .iter()
+                .find(|ch| !ch.is_whitespace())
+                .is_some_and(|&ch| ch == '(');
+            let upper = word.to_uppercase();
+            if !qualified && !call && columns.iter().any(|col| col.name == upper) {
+                out.push_str(&quote_identifier(&upper));
+            } else {
+                out.push_str(&word);
+            }
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+    out
+}
+
 pub fn generate_check_constraints(table: &TableDetails) -> Vec<String> {
     table
         .check_constraints
@@ -232,6 +548,35 @@ pub fn generate_check_constraints(table: &TableDetails) -> Vec<String> {
         .collect()
 }
 
+/// Points foreign keys that reference a table of the source schema (or an unqualified table) at
+/// the target schema, so renaming the whole schema keeps the constraints valid. References to
+/// other schemas are left alone; the returned list holds, per foreign key, a warning for those
+/// because the target database must already provide the referenced table.
+pub fn remap_foreign_key_schemas(
+    table: &mut TableDetails,
+    source_schema: &str,
+    target_schema: &str,
+) -> Vec<Option<String>> {
+    table
+        .foreign_keys
+        .iter_mut()
+        .map(|fk| match fk.referenced_table.split_once('.') {
+            Some((owner, _)) if !owner.eq_ignore_ascii_case(source_schema) => Some(format!(
+                "Foreign key {} on {} references {} outside schema {}; it must exist on the target",
+                fk.name, table.name, fk.referenced_table, source_schema
+            )),
+            Some((_, referenced)) => {
+                fk.referenced_table = format!("{}.{}", target_schema, referenced);
+                None
+            }
+            None => {
+                fk.referenced_table = format!("{}.{}", target_schema, fk.referenced_table);
+                None
+            }
+        })
+        .collect()
+}
+
 pub fn generate_foreign_keys(table: &TableDetails) -> Vec<String> {
     table
         .foreign_keys
@@ -279,6 +624,17 @@ pub fn generate_foreign_keys(table: &TableDetails) -> Vec<String> {
         .collect()
 }
 
+/// Owner of the created schema when the request names none.
+pub const DEFAULT_SCHEMA_OWNER: &str = "SYSDBA";
+
+pub fn generate_create_schema(schema: &str, owner: &str) -> String {
+    format!(
+        "CREATE SCHEMA {} AUTHORIZATION {};",
+        quote_identifier(schema),
+        quote_identifier(owner)
+    )
+}
+
 pub fn generate_sequences(schema: &str, sequences: &[Sequence]) -> Vec<String> {
     sequences
         .iter()
@@ -320,6 +676,235 @@ pub fn generate_sequences(schema: &str, sequences: &[Sequence]) -> Vec<String> {
         .collect()
 }
 
+/// `CREATE OR REPLACE VIEW` statements of `views` under `schema`; names qualified with
+/// `source_schema` in the queries are qualified with `schema` instead.
+pub fn generate_views(source_schema: &str, schema: &str, views: &[ViewDefinition]) -> Vec<String> {
+    views
+        .iter()
+        .map(|view| {
+            let (column_list, query) = split_view_definition(&view.definition);
+            let mut stmt = format!(
+                "CREATE OR REPLACE VIEW {}.{}",
+                quote_identifier(schema),
+                quote_identifier(&view.name)
+            );
+            if let Some(columns) = column_list {
+                stmt.push(' ');
+                stmt.push_str(&columns);
+            }
+            stmt.push_str(" AS\n");
+            let query = retarget_schema(query, source_schema, schema);
+            stmt.push_str(query.trim_end().trim_end_matches(';').trim_end());
+            stmt.push(';');
+            stmt
+        })
+        .collect()
+}
+
+/// The views reading one of `tables` (catalog names of `schema`), directly or through another
+/// such view, in an order that creates every view after the views it reads. Views over other
+/// tables only belong to the export that selects those.
+pub fn views_on_tables(
+    schema: &str,
+    views: Vec<ViewDefinition>,
+    tables: &[String],
+) -> Vec<ViewDefinition> {
+    let mut readable: HashSet<String> = tables.iter().cloned().collect();
+    let mut pending = views;
+    let mut ordered = Vec::new();
+    loop {
+        let (ready, rest): (Vec<_>, Vec<_>) = pending.into_iter().partition(|view| {
+            let (_, query) = split_view_definition(&view.definition);
+            referenced_names(query, schema)
+                .iter()
+                .any(|name| readable.contains(name))
+        });
+        if ready.is_empty() {
+            return ordered;
+        }
+        readable.extend(ready.iter().map(|view| view.name.clone()));
+        ordered.extend(ready);
+        pending = rest;
+    }
+}
+
+/// A name in SQL text: its byte range and the name itself, upper-cased unless quoted.
+struct SqlName {
+    start: usize,
+    end: usize,
+    name: String,
+}
+
+/// Names of `sql` outside string literals and comments, in order.
+fn sql_names(sql: &str) -> Vec<SqlName> {
+    let mut names = Vec::new();
+    let mut pos = 0;
+    while let Some(c) = sql[pos..].chars().next() {
+        let rest = &sql[pos..];
+        let len = if c == '\'' {
+            literal_len(rest)
+        } else if rest.starts_with("--") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            rest.find("*/").map_or(rest.len(), |end| end + 2)
+        } else if c == '"' {
+            let len = literal_len(rest);
+            if len > 1 && rest[..len].ends_with('"') {
+                names.push(SqlName {
+                    start: pos,
+                    end: pos + len,
+                    name: rest[1..len - 1].replace("\"\"", "\""),
+                });
+            }
+            len
+        } else if c.is_alphanumeric() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '$' | '#')))
+                .unwrap_or(rest.len());
+            if !c.is_ascii_digit() {
+                names.push(SqlName {
+                    start: pos,
+                    end: pos + len,
+                    name: rest[..len].to_uppercase(),
+                });
+            }
+            len
+        } else {
+            c.len_utf8()
+        };
+        pos += len;
+    }
+    names
+}
+
+/// The name qualifying `names[index]` in `sql` (`owner.name`), if any.
+fn qualifier<'a>(sql: &str, names: &'a [SqlName], index: usize) -> Option<&'a SqlName> {
+    let previous = names.get(index.checked_sub(1)?)?;
+    (sql[previous.end..names[index].start].trim() == ".").then_some(previous)
+}
+
+/// Names `sql` may read as tables or views of `schema`: unqualified names and names qualified
+/// with `schema`.
+fn referenced_names(sql: &str, schema: &str) -> Vec<String> {
+    let names = sql_names(sql);
+    (0..names.len())
+        .filter(|&index| qualifier(sql, &names, index).map_or(true, |owner| owner.name == schema))
+        .map(|index| names[index].name.clone())
+        .collect()
+}
+
+/// `sql` with every `source.` qualifier replaced by the quoted `target`.
+fn retarget_schema(sql: &str, source: &str, target: &str) -> String {
+    let names = sql_names(sql);
+    let mut out = String::with_capacity(sql.len());
+    let mut copied = 0;
+    for (index, name) in names.iter().enumerate() {
+        let qualifies_next = sql[name.end..].trim_start().starts_with('.');
+        if name.name == source && qualifies_next && qualifier(sql, &names, index).is_none() {
+            out.push_str(&sql[copied..name.start]);
+            out.push_str(&quote_identifier(target));
+            copied = name.end;
+        }
+    }
+    out.push_str(&sql[copied..]);
+    out
+}
+
+/// Splits a view definition into its optional column list and query. DM8 may store either the
+/// bare query or the full `CREATE VIEW ... AS` statement; the latter is reduced to its query so
+/// the view can be recreated under the target schema.
+fn split_view_definition(definition: &str) -> (Option<String>, &str) {
+    let trimmed = definition.trim();
+    if !trimmed.to_uppercase().starts_with("CREATE") {
+        return (None, trimmed);
+    }
+
+    let bytes = trimmed.as_bytes();
+    let mut depth = 0i32;
+    let mut in_quote: Option<u8> = None;
+    let mut column_start = None;
+    for (i, &b) in bytes.iter().enumerate() {
+        if let Some(q) = in_quote {
+            if b == q {
+                in_quote = None;
+            }
+            continue;
+        }
+        match b {
+            b'\'' | b'"' => in_quote = Some(b),
+            b'(' => {
+                if depth == 0 && column_start.is_none() {
+                    column_start = Some(i);
+                }
+                depth += 1;
+            }
+            b')' => depth -= 1,
+            b'A' | b'a'
+                if depth == 0
+                    && i > 0
+                    && i + 2 < bytes.len()
+                    && bytes[i - 1].is_ascii_whitespace()
+                    && bytes[i + 1].eq_ignore_ascii_case(&b'S')
+                    && bytes[i + 2].is_ascii_whitespace() =>
+            {
+                let columns = column_start
+                    .map(|start| trimmed[start..i].trim().to_string())
+                    .filter(|c| c.starts_with('('));
+                return (columns, trimmed[i + 2..].trim_start());
+            }
+            _ => {}
+        }
+    }
+    (None, trimmed)
+}
+
+/// Privileges of one grantee on one table with the same grant option: one `GRANT` statement.
+type GrantGroup<'a> = (&'a str, &'a str, bool, Vec<&'a str>);
+
+fn group_grants(grants: &[ObjectGrant]) -> Vec<GrantGroup<'_>> {
+    let mut grouped: Vec<GrantGroup<'_>> = Vec::new();
+    for grant in grants {
+        let key = (
+            grant.table_name.as_str(),
+            grant.grantee.as_str(),
+            grant.grantable,
+        );
+        match grouped
+            .iter_mut()
+            .find(|(table, grantee, grantable, _)| (*table, *grantee, *grantable) == key)
+        {
+            Some((_, _, _, privileges)) => {
+                if !privileges.contains(&grant.privilege.as_str()) {
+                    privileges.push(&grant.privilege);
+                }
+            }
+            None => grouped.push((key.0, key.1, key.2, vec![grant.privilege.as_str()])),
+        }
+    }
+    grouped
+}
+
+/// Groups privileges per (table, grantee, grant option) into one `GRANT` statement each.
+pub fn generate_grants(schema: &str, grants: &[ObjectGrant]) -> Vec<String> {
+    group_grants(grants)
+        .into_iter()
+        .map(|(table, grantee, grantable, privileges)| {
+            let mut stmt = format!(
+                "GRANT {} ON {}.{} TO {}",
+                privileges.join(", "),
+                quote_identifier(schema),
+                quote_identifier(table),
+                quote_identifier(grantee)
+            );
+            if grantable {
+                stmt.push_str(" WITH GRANT OPTION");
+            }
+            stmt.push(';');
+            stmt
+        })
+        .collect()
+}
+
 pub fn generate_triggers(
     schema: &str,
     triggers: &[TriggerDefinition],
@@ -396,6 +981,38 @@ pub fn generate_triggers(
         .collect()
 }
 
+/// Every statement of one table as a DDL export writes it: `CREATE TABLE`, comments, primary
+/// key, unique and check constraints, indexes, foreign keys and triggers, one blank line between
+/// object kinds. `table.name` is expected to be schema-qualified (`SCHEMA.TABLE`); triggers are
+/// created in that schema and are left out for an unqualified name.
+pub fn generate_full_table_ddl(table: &TableDetails) -> String {
+    let mut blocks = vec![
+        vec![generate_create_table(table)],
+        generate_comments(table),
+        generate_primary_key(table).into_iter().collect(),
+        generate_unique_constraints(table),
+        generate_check_constraints(table),
+        generate_indexes(table),
+        generate_foreign_keys(table),
+    ];
+    if let Some((schema, _)) = table.name.split_once('.') {
+        blocks.push(generate_triggers(
+            schema,
+            &table.triggers,
+            TriggerTerminator::DataGrip,
+        ));
+    }
+
+    let mut ddl = blocks
+        .into_iter()
+        .filter(|block| !block.is_empty())
+        .map(|block| block.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    ddl.push('\n');
+    ddl
+}
+
 fn apply_trigger_terminator(stmt: &mut String, terminator: TriggerTerminator) {
     if !stmt.trim_end().ends_with(';') {
         stmt.push(';');
@@ -412,30 +1029,387 @@ fn apply_trigger_terminator(stmt: &mut String, terminator: TriggerTerminator) {
     }
 }
 
+/// Object counts of a DDL script, written into its header as the `@inventory` block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DdlInventory {
+    pub tables: usize,
+    pub columns: usize,
+    pub indexes: usize,
+    /// Primary keys, unique, check and foreign key constraints.
+    pub constraints: usize,
+    pub triggers: usize,
+    pub sequences: usize,
+    pub views: usize,
+    pub grants: usize,
+}
+
+impl DdlInventory {
+    /// Counts what the script will contain: only selected object kinds of its stage, without
+    /// the indexes and triggers that are skipped.
+    pub fn collect(
+        tables: &[TableDetails],
+        sequences: &[Sequence],
+        views: &[ViewDefinition],
+        grants: &[ObjectGrant],
+        options: &DdlExportOptions,
+    ) -> Self {
+        let before_data = options.stage.before_data();
+        let after_data = options.stage.after_data();
+        let mut inventory = Self {
+            sequences: sequences.len(),
+            views: views.len(),
+            grants: group_grants(grants).len(),
+            ..Self::default()
+        };
+        for table in tables {
+            if before_data && options.includes(DdlObjectType::Tables) {
+                inventory.tables += 1;
+                inventory.columns += table.columns.len();
+            }
+            if options.writes_indexes(false) || options.writes_indexes(true) {
+                inventory.indexes += generate_indexes(table).len();
+            }
+            if options.includes(DdlObjectType::Constraints) {
+                if before_data {
+                    inventory.constraints += usize::from(!table.primary_keys.is_empty())
+                        + table.unique_constraints.len()
+                        + table.check_constraints.len();
+                }
+                if after_data {
+                    inventory.constraints += table.foreign_keys.len();
+                }
+            }
+            if after_data && options.includes(DdlObjectType::Triggers) {
+                inventory.triggers += table
+                    .triggers
+                    .iter()
+                    .filter(|trigger| !trigger.body.trim().is_empty())
+                    .count();
+            }
+        }
+        inventory
+    }
+
+    /// The header block: `-- @inventory begin`, one `-- <kind>=<count>` line per kind, then
+    /// `-- @inventory end`.
+    pub fn header_lines(&self) -> Vec<String> {
+        let counts = [
+            ("tables", self.tables),
+            ("columns", self.columns),
+            ("indexes", self.indexes),
+            ("constraints", self.constraints),
+            ("triggers", self.triggers),
+            ("sequences", self.sequences),
+            ("views", self.views),
+            ("grants", self.grants),
+        ];
+        let mut lines = vec!["-- @inventory begin".to_string()];
+        lines.extend(counts.iter().map(|(kind, count)| format!("-- {}={}", kind, count)));
+        lines.push("-- @inventory end".to_string());
+        lines
+    }
+}
+
+/// `-- @begin <KIND> <name>` or `-- @end <KIND> <name>` around the statements of one object, so
+/// validation scripts can cut the script into objects without parsing SQL.
+fn object_marker(edge: &str, kind: &str, name: &str) -> String {
+    format!("-- @{} {} {}", edge, kind, name)
+}
+
+/// Writes `statement` (already annotated with its step) between its object markers, after a
+/// blank line separating it from the previous statement.
+fn write_object(writer: &mut impl Write, kind: &str, name: &str, statement: &str) -> Result<()> {
+    writeln!(writer)?;
+    writeln!(writer, "{}", object_marker("begin", kind, name))?;
+    writeln!(writer, "{}", statement)?;
+    writeln!(writer, "{}", object_marker("end", kind, name))?;
+    Ok(())
+}
+
+/// Statements of a table's section, one block per selected object kind in script order, and
+/// the comments kept out of it for the separate comment section.
+fn table_blocks(
+    table: &TableDetails,
+    options: &DdlExportOptions,
+) -> (Vec<(Phase, Vec<String>)>, Vec<String>) {
+    let before_data = options.stage.before_data();
+    let mut blocks: Vec<(Phase, Vec<String>)> = Vec::new();
+    let mut separate_comments = Vec::new();
+    if before_data && options.includes(DdlObjectType::Tables) {
+        let mut block = Vec::new();
+        if options.drop_existing {
+            block.push(format!(
+                "DROP TABLE IF EXISTS {};",
+                quote_identifier(&table.name)
+            ));
+        }
+        block.push(generate_create_table(table));
+        blocks.push((Phase::Table, block));
+    }
+    if before_data && options.includes(DdlObjectType::Comments) {
+        match options.comments_placement {
+            CommentsPlacement::Inline => blocks.push((Phase::Comment, generate_comments(table))),
+            CommentsPlacement::SeparateSection => separate_comments = generate_comments(table),
+            CommentsPlacement::Omit => {}
+        }
+    }
+    if before_data && options.includes(DdlObjectType::Constraints) {
+        blocks.push((
+            Phase::Constraint,
+            generate_primary_key(table).into_iter().collect(),
+        ));
+        blocks.push((Phase::Constraint, generate_unique_constraints(table)));
+        blocks.push((Phase::Constraint, generate_check_constraints(table)));
+    }
+    if options.writes_indexes(false) {
+        blocks.push((Phase::Index, generate_indexes(table)));
+    }
+    blocks.retain(|(_, block)| !block.is_empty());
+    (blocks, separate_comments)
+}
+
+/// Marker names and statements of the indexes written after the data load.
+fn deferred_indexes(table: &TableDetails, target_schema: &str) -> Vec<(String, String)> {
+    let kept = table
+        .indexes
+        .iter()
+        .zip(index_skips(table))
+        .filter(|(_, skip)| skip.is_none());
+    generate_indexes(table)
+        .into_iter()
+        .zip(kept)
+        .map(|(stmt, (index, _))| {
+            let name = quote_identifier(&format!(
+                "{}.{}",
+                target_schema,
+                normalize_index_name(&table.name, index)
+            ));
+            (name, stmt)
+        })
+        .collect()
+}
+
+fn warn_skipped_indexes(job: &mut JobContext, table: &TableDetails) {
+    for (index, skip) in skipped_indexes(table) {
+        let reason = match skip {
+            IndexSkip::NoColumns => "the catalog returned no columns for it",
+            _ => "it repeats the column list of another index",
+        };
+        job.warn(
+            IssueCode::IndexSkipped,
+            Some(&table.name),
+            format!(
+                "Index {} of {} was not exported: {}",
+                index.name, table.name, reason
+            ),
+        );
+    }
+}
 
 pub fn export_schema_ddl(
-    connection: &Connection<'_>,
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
     source_schema: &str,
     target_schema: &str,
     tables: &[String],
     output_path: &Path,
-    drop_existing: bool,
-    trigger_terminator: TriggerTerminator,
+    options: &DdlExportOptions,
 ) -> Result<()> {
-    let source_schema = source_schema.to_uppercase();
-    let target_schema = target_schema.to_uppercase();
+    let started = Instant::now();
+    let drop_existing = options.drop_existing;
+    let trigger_terminator = options.trigger_terminator;
+    // Statements are generated fully quoted and requoted just before they are written.
+    let requote = |statement: &str| apply_quote_style(statement, options.quote_style);
+    let source_schema = catalog_name(source_schema, options.preserve_case);
+    let target_schema = catalog_name(target_schema, options.preserve_case);
+    let tables: Vec<String> = tables
+        .iter()
+        .map(|table| catalog_name(table, options.preserve_case))
+        .collect();
 
     // Cache table details to avoid repeated queries.
-    let mut table_cache = Vec::new();
-    for table_name in tables {
-        let details =
-            get_table_details(connection, &source_schema, table_name).with_context(|| {
-                format!("Failed to fetch table metadata for '{}'", table_name)
-            })?;
-        table_cache.push(details);
+    let capabilities = job.capabilities;
+    let metadata = job.metadata.clone();
+    for table_name in &tables {
+        job.record(JobEventKind::TableStarted, Some(table_name), None);
+    }
+    let mut table_cache = session
+        .run(
+            &format!("Reading metadata of {} tables", tables.len()),
+            &mut |message| job.record(JobEventKind::Retry, None, Some(message)),
+            |connection| {
+                cached_tables_details(
+                    metadata.as_ref(),
+                    connection,
+                    &capabilities,
+                    &source_schema,
+                    &tables,
+                )
+            },
+        )
+        .context("Failed to fetch table metadata")?;
+    for details in &mut table_cache {
+        apply_storage_options(details, options);
+        sanitize_check_constraints(details, options);
+        translate_defaults(details, options.dialect);
+        job.record(
+            JobEventKind::TableFinished,
+            Some(&details.name),
+            Some("metadata loaded".to_string()),
+        );
+    }
+
+    // Objects the selection depends on but did not include, listed in the header.
+    let mut auto_included = Vec::new();
+    if options.include_dependencies {
+        let added = add_referenced_tables(&source_schema, &mut table_cache, |table_name| {
+            job.record(JobEventKind::TableStarted, Some(table_name), None);
+            let mut details = session
+                .run(
+                    &format!("Reading metadata of {}", table_name),
+                    &mut |message| job.record(JobEventKind::Retry, Some(table_name), Some(message)),
+                    |connection| {
+                        cached_table_details(
+                            metadata.as_ref(),
+                            connection,
+                            &capabilities,
+                            &source_schema,
+                            table_name,
+                        )
+                    },
+                )
+                .with_context(|| {
+                    format!("Failed to fetch metadata of referenced table '{}'", table_name)
+                })?;
+            apply_storage_options(&mut details, options);
+            sanitize_check_constraints(&mut details, options);
+            translate_defaults(&mut details, options.dialect);
+            job.record(
+                JobEventKind::TableFinished,
+                Some(table_name),
+                Some("metadata loaded (referenced by a selected table)".to_string()),
+            );
+            Ok(details)
+        })?;
+        auto_included.extend(added.into_iter().map(|name| format!("TABLE {}", name)));
+    }
+    let tables: Vec<String> = table_cache.iter().map(|t| t.name.clone()).collect();
+
+    // Sequences that were not selected are still needed by the defaults and triggers using them.
+    let sequences_as_dependencies = options.include_dependencies
+        && !options.includes(DdlObjectType::Sequences)
+        && (options.includes(DdlObjectType::Tables) || options.includes(DdlObjectType::Triggers));
+    let mut sequences = if options.includes(DdlObjectType::Sequences) || sequences_as_dependencies {
+        match session.run(
+            "Reading sequences",
+            &mut |message| job.record(JobEventKind::Retry, None, Some(message)),
+            |connection| fetch_sequences(connection, &source_schema),
+        ) {
+            Ok(sequences) if sequences_as_dependencies => {
+                let sequences = filter_referenced_sequences(sequences, &table_cache);
+                auto_included.extend(sequences.iter().map(|seq| format!("SEQUENCE {}", seq.name)));
+                sequences
+            }
+            Ok(sequences) if options.only_referenced_sequences => {
+                filter_referenced_sequences(sequences, &table_cache)
+            }
+            Ok(sequences) => sequences,
+            Err(e) => {
+                job.warn(
+                    IssueCode::SequencesUnavailable,
+                    None,
+                    format!("Failed to fetch sequences, skipping them: {:#}", e),
+                );
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    apply_sequence_renames(&mut sequences, &mut table_cache, &options.sequence_renames);
+    apply_table_renames(&mut table_cache, &source_schema, &options.table_renames);
+    // Sequences are created before the data; a later stage only read them for the renames.
+    if !options.stage.before_data() {
+        sequences.clear();
+    }
+
+    // Case sensitivity is an instance init parameter; it cannot be expressed in DDL, so it is
+    // surfaced in the header and as a warning when the target must be created to match.
+    let case_sensitive = match session.run(
+        "Reading case sensitivity",
+        &mut |message| job.record(JobEventKind::Retry, None, Some(message)),
+        fetch_case_sensitivity,
+    ) {
+        Ok(flag) => flag,
+        Err(e) => {
+            job.warn(
+                IssueCode::CaseSensitivityUnknown,
+                None,
+                format!("Failed to detect case sensitivity: {:#}", e),
+            );
+            None
+        }
+    };
+    if case_sensitive == Some(false) {
+        job.warn(
+            IssueCode::CaseInsensitiveSource,
+            None,
+            "Source instance is case-insensitive (CASE_SENSITIVE=0); initialize the target \
+             the same way or string comparisons will change behavior",
+        );
     }
 
-    let sequences = fetch_sequences(connection, &source_schema).unwrap_or_default();
+    let views = if options.includes(DdlObjectType::Views) && options.stage.after_data() {
+        match session.run(
+            "Reading views",
+            &mut |message| job.record(JobEventKind::Retry, None, Some(message)),
+            |connection| fetch_views(connection, &source_schema),
+        ) {
+            Ok(views) => views_on_tables(&source_schema, views, &tables),
+            Err(e) => {
+                job.warn(
+                    IssueCode::ViewsUnavailable,
+                    None,
+                    format!("Failed to fetch views, skipping them: {:#}", e),
+                );
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    let grants = if options.include_grants && options.stage.after_data() {
+        match session.run(
+            "Reading object grants",
+            &mut |message| job.record(JobEventKind::Retry, None, Some(message)),
+            |connection| fetch_object_grants(connection, &source_schema),
+        ) {
+            Ok(grants) => grants
+                .into_iter()
+                .filter(|grant| {
+                    tables
+                        .iter()
+                        .any(|table| table.eq_ignore_ascii_case(&grant.table_name))
+                })
+                .map(|mut grant| {
+                    grant.table_name = renamed_table(&options.table_renames, &grant.table_name);
+                    grant
+                })
+                .collect(),
+            Err(e) => {
+                job.warn(
+                    IssueCode::GrantsUnavailable,
+                    None,
+                    format!("Failed to fetch object grants, skipping them: {:#}", e),
+                );
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
 
     if let Some(parent) = output_path.parent() {
         fs::create_dir_all(parent).with_context(|| {
@@ -449,7 +1423,7 @@ pub fn export_schema_ddl(
     let file = File::create(output_path).with_context(|| {
         format!("Failed to create DDL export file at {}", output_path.display())
     })?;
-    let mut writer = BufWriter::new(file);
+    let mut writer = script_writer(file, options.encoding, options.line_ending);
 
     // File header
     let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
@@ -464,6 +1438,42 @@ pub fn export_schema_ddl(
     writeln!(writer, "-- 目标 Schema: {}", target_schema)?;
     writeln!(writer, "-- 表数量: {}", tables.len())?;
     writeln!(writer, "-- 涉及的表: {}", table_names.join(", "))?;
+    if !auto_included.is_empty() {
+        writeln!(writer, "-- 自动包含的依赖: {}", auto_included.join(", "))?;
+    }
+    let object_names: Vec<&str> = options.objects.iter().map(|kind| kind.as_str()).collect();
+    writeln!(writer, "-- 导出对象: {}", object_names.join(", "))?;
+    match (options.stage, options.indexes_after_data) {
+        (DdlStage::Complete, _) => {}
+        (DdlStage::BeforeData, false) => {
+            writeln!(writer, "-- 导出阶段: 数据之前 (表、约束、索引、序列)")?
+        }
+        (DdlStage::BeforeData, true) => writeln!(
+            writer,
+            "-- 导出阶段: 数据之前 (表、约束、序列；索引在数据之后创建)"
+        )?,
+        (DdlStage::AfterData, false) => {
+            writeln!(writer, "-- 导出阶段: 数据之后 (外键、触发器、视图、权限)")?
+        }
+        (DdlStage::AfterData, true) => writeln!(
+            writer,
+            "-- 导出阶段: 数据之后 (索引、外键、触发器、视图、权限)"
+        )?,
+    }
+    match case_sensitive {
+        Some(true) => writeln!(writer, "-- 源库大小写敏感: 是")?,
+        Some(false) => writeln!(
+            writer,
+            "-- 源库大小写敏感: 否 (目标库须以 CASE_SENSITIVE=0 初始化)"
+        )?,
+        None => writeln!(writer, "-- 源库大小写敏感: 未知")?,
+    }
+    writeln!(
+        writer,
+        "-- 文件编码: {0} (使用 DIsql 执行前请先运行 SET CHAR_CODE {0})",
+        options.encoding.char_code()
+    )?;
+    writeln!(writer, "-- 执行顺序: 语句前的 \"步骤 NNNN\" 注释与同名 .run_order.txt 索引一致")?;
     writeln!(writer, "--")?;
     if trigger_terminator == TriggerTerminator::DataGripScript {
         writeln!(writer, "-- 执行方式: DataGrip 脚本模式")?;
@@ -475,95 +1485,199 @@ pub fn export_schema_ddl(
         writeln!(writer, "-- 执行方式: DataGrip 逐语句运行")?;
         writeln!(writer, "-- 注意: 请在 DataGrip 中逐条执行语句")?;
     }
-    if drop_existing {
+    if drop_existing && options.includes(DdlObjectType::Tables) {
         writeln!(writer, "-- 警告: 此脚本会先删除已存在的表再重新创建")?;
     } else {
         writeln!(writer, "-- 说明: 此脚本不会删除已存在的表")?;
     }
     writeln!(writer, "-- 重要: 触发器通常依赖 SEQUENCE (序列) 生成主键")?;
     writeln!(writer, "-- 重要: 必须先执行 SEQUENCE 再执行触发器")?;
+    writeln!(writer, "-- 对象清单: 以下 @inventory 块为各类对象数量，每个对象前后有 @begin/@end 标记")?;
+    let inventory = DdlInventory::collect(&table_cache, &sequences, &views, &grants, options);
+    for line in inventory.header_lines() {
+        writeln!(writer, "{}", line)?;
+    }
     writeln!(writer, "-- ============================================")?;
     writeln!(writer)?;
 
-    for (i, table_details) in table_cache.iter().enumerate() {
-        let mut render_table = table_details.clone();
-        render_table.name = format!("{}.{}", target_schema, table_details.name);
-
-        if i > 0 {
-            writeln!(writer)?;
-        }
-
+    // Every statement is prefixed with its step number; run_order.txt indexes them.
+    let mut run_order = RunOrder::default();
+    let main_file = output_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    if options.include_schema_ddl && options.stage.before_data() {
+        let owner = options
+            .schema_owner
+            .as_deref()
+            .unwrap_or(DEFAULT_SCHEMA_OWNER);
+        let statement = requote(&generate_create_schema(&target_schema, owner));
+        writeln!(writer, "-- 创建 Schema (在全新实例上首先执行)")?;
         writeln!(
             writer,
-            "-- 表: {}",
-            quote_identifier(&render_table.name)
+            "-- 所有者用户不存在时先创建: CREATE USER {} IDENTIFIED BY \"<password>\";",
+            requote(&quote_identifier(owner))
         )?;
-        if drop_existing {
+        // DM8 creates the same-named schema together with a user.
+        if owner.eq_ignore_ascii_case(&target_schema) {
             writeln!(
                 writer,
-                "DROP TABLE IF EXISTS {};",
-                quote_identifier(&render_table.name)
+                "-- 创建用户时已自动创建同名 Schema，无需执行: {}",
+                statement
+            )?;
+        } else {
+            write_object(
+                &mut writer,
+                "SCHEMA",
+                &quote_identifier(&target_schema),
+                &run_order.annotate(Phase::Schema, &main_file, &statement),
             )?;
         }
-        writeln!(writer, "{}", generate_create_table(&render_table))?;
+        writeln!(writer)?;
+    }
 
-        if let Some(pk_stmt) = generate_primary_key(&render_table) {
-            writeln!(writer)?;
-            writeln!(writer, "{}", pk_stmt)?;
-        }
+    // Script bytes and generation time per table; foreign keys and triggers written in later
+    // sections count towards their table.
+    let mut table_stats = vec![(0_u64, Duration::ZERO); table_cache.len()];
+    let mut separate_comments = Vec::new();
+    let mut first_table = true;
+    for (position, table_details) in table_cache.iter().enumerate() {
+        let table_started = Instant::now();
+        let mut render_table = table_details.clone();
+        render_table.name = format!("{}.{}", target_schema, table_details.name);
 
-        let unique_stmts = generate_unique_constraints(&render_table);
-        if !unique_stmts.is_empty() {
-            writeln!(writer)?;
-            for stmt in unique_stmts {
-                writeln!(writer, "{}", stmt)?;
-            }
+        if options.writes_indexes(false) {
+            warn_skipped_indexes(job, table_details);
+        }
+        let (blocks, comments) = table_blocks(&render_table, options);
+        if !comments.is_empty() {
+            separate_comments.push((position, render_table.name.clone(), comments));
+        }
+        if blocks.is_empty() {
+            continue;
         }
 
-        let check_stmts = generate_check_constraints(&render_table);
-        if !check_stmts.is_empty() {
+        if !first_table {
             writeln!(writer)?;
-            for stmt in check_stmts {
-                writeln!(writer, "{}", stmt)?;
+        }
+        first_table = false;
+
+        let table_ident = quote_identifier(&render_table.name);
+        let mut section = CountingWriter::new(&mut writer);
+        writeln!(section, "{}", object_marker("begin", "TABLE", &table_ident))?;
+        writeln!(section, "-- 表: {}", table_ident)?;
+        // One blank line before every statement and none inside it, so DataGrip's script
+        // runner splits the section exactly at statement boundaries.
+        for (phase, block) in &blocks {
+            for stmt in block {
+                writeln!(section)?;
+                writeln!(
+                    section,
+                    "{}",
+                    run_order.annotate(*phase, &main_file, &requote(stmt))
+                )?;
             }
         }
-
-        let index_statements = generate_indexes(&render_table);
-        if !index_statements.is_empty() {
-            writeln!(writer)?;
-            for stmt in index_statements {
-                writeln!(writer, "{}", stmt)?;
+        writeln!(section, "{}", object_marker("end", "TABLE", &table_ident))?;
+        table_stats[position] = (section.bytes(), table_started.elapsed());
+    }
+
+    // Indexes deferred past the data load, ahead of the foreign keys.
+    if options.writes_indexes(true) {
+        let mut first_index = true;
+        for (position, table_details) in table_cache.iter().enumerate() {
+            warn_skipped_indexes(job, table_details);
+            let mut render_table = table_details.clone();
+            render_table.name = format!("{}.{}", target_schema, table_details.name);
+            for (name, stmt) in deferred_indexes(&render_table, &target_schema) {
+                if first_index {
+                    writeln!(writer)?;
+                    writeln!(writer, "-- 索引 (数据装载后创建)")?;
+                    first_index = false;
+                }
+                let mut statement = CountingWriter::new(&mut writer);
+                write_object(
+                    &mut statement,
+                    "INDEX",
+                    &name,
+                    &run_order.annotate(Phase::Index, &main_file, &requote(&stmt)),
+                )?;
+                table_stats[position].0 += statement.bytes();
             }
         }
     }
 
     // Emit foreign keys after all tables to reduce dependency issues.
     let mut fk_statements = Vec::new();
-    for table_details in &table_cache {
-        let mut render_table = table_details.clone();
-        render_table.name = format!("{}.{}", target_schema, table_details.name);
-        fk_statements.extend(generate_foreign_keys(&render_table));
+    if options.includes(DdlObjectType::Constraints) && options.stage.after_data() {
+        for (position, table_details) in table_cache.iter().enumerate() {
+            let mut render_table = table_details.clone();
+            let warnings =
+                remap_foreign_key_schemas(&mut render_table, &source_schema, &target_schema);
+            render_table.name = format!("{}.{}", target_schema, table_details.name);
+            let statements = generate_foreign_keys(&render_table);
+            for ((warning, stmt), fk) in warnings
+                .into_iter()
+                .zip(statements)
+                .zip(&render_table.foreign_keys)
+            {
+                let name = quote_identifier(&format!("{}.{}", render_table.name, fk.name));
+                if let Some(message) = &warning {
+                    job.warn(
+                        IssueCode::ExternalForeignKey,
+                        Some(&table_details.name),
+                        message.clone(),
+                    );
+                }
+                fk_statements.push((position, name, warning, stmt));
+            }
+        }
     }
 
     if !fk_statements.is_empty() {
         writeln!(writer)?;
         writeln!(writer, "-- 外键")?;
-        for stmt in fk_statements {
-            writeln!(writer, "{}", stmt)?;
+        for (position, name, warning, stmt) in fk_statements {
+            let mut statement = CountingWriter::new(&mut writer);
+            let mut text = run_order.annotate(Phase::ForeignKey, &main_file, &requote(&stmt));
+            if let Some(message) = warning {
+                text = format!("-- WARNING: {}\n{}", message, text);
+            }
+            write_object(&mut statement, "FOREIGN_KEY", &name, &text)?;
+            table_stats[position].0 += statement.bytes();
         }
     }
 
     // Emit sequences and triggers together as a related section.
     let seq_stmts = generate_sequences(&target_schema, &sequences);
     let mut trig_stmts = Vec::new();
-    for table_details in &table_cache {
-        let mut render_table = table_details.clone();
-        render_table.name = format!("{}.{}", target_schema, table_details.name);
-        trig_stmts.extend(generate_triggers(
-            &target_schema,
-            &render_table.triggers,
-            trigger_terminator,
-        ));
+    if options.includes(DdlObjectType::Triggers) && options.stage.after_data() {
+        for (position, table_details) in table_cache.iter().enumerate() {
+            // A trigger whose body the catalog did not return would be written as an empty,
+            // uncompilable statement.
+            let (triggers, incomplete): (Vec<_>, Vec<_>) = table_details
+                .triggers
+                .iter()
+                .cloned()
+                .partition(|trigger| !trigger.body.trim().is_empty());
+            for trigger in incomplete {
+                job.warn(
+                    IssueCode::TriggerIncomplete,
+                    Some(&table_details.name),
+                    format!(
+                        "Trigger {} on {} has no body in the catalog and was not exported",
+                        trigger.name, table_details.name
+                    ),
+                );
+            }
+            let statements = generate_triggers(&target_schema, &triggers, trigger_terminator);
+            for (stmt, trigger) in statements.into_iter().zip(&triggers) {
+                let name = quote_identifier(&format!("{}.{}", target_schema, trigger.name));
+                trig_stmts.push((position, name, stmt));
+            }
+        }
     }
 
     // 只有当存在 SEQUENCE 或触发器时才输出这个 section
@@ -580,8 +1694,13 @@ pub fn export_schema_ddl(
     if !seq_stmts.is_empty() {
         writeln!(writer)?;
         writeln!(writer, "-- SEQUENCE (第一步: 请先执行)")?;
-        for stmt in seq_stmts {
-            writeln!(writer, "{}", stmt)?;
+        for (stmt, seq) in seq_stmts.into_iter().zip(&sequences) {
+            write_object(
+                &mut writer,
+                "SEQUENCE",
+                &quote_identifier(&format!("{}.{}", target_schema, seq.name)),
+                &run_order.annotate(Phase::Sequence, &main_file, &requote(&stmt)),
+            )?;
         }
     }
 
@@ -601,7 +1720,13 @@ pub fn export_schema_ddl(
                 trigger_path.display()
             )
         })?;
-        let mut trigger_writer = BufWriter::new(trigger_file);
+        let mut trigger_writer =
+            script_writer(trigger_file, options.encoding, options.line_ending);
+        let trigger_file_name = trigger_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
 
         writeln!(trigger_writer, "-- ============================================")?;
         writeln!(trigger_writer, "-- DM8 触发器 DDL 导出脚本")?;
@@ -609,6 +1734,11 @@ pub fn export_schema_ddl(
         writeln!(trigger_writer, "-- 生成时间: {}", timestamp)?;
         writeln!(trigger_writer, "-- 目标 Schema: {}", target_schema)?;
         writeln!(trigger_writer, "-- 触发器数量: {}", trig_stmts.len())?;
+        writeln!(
+            trigger_writer,
+            "-- 文件编码: {0} (使用 DIsql 执行前请先运行 SET CHAR_CODE {0})",
+            options.encoding.char_code()
+        )?;
         writeln!(trigger_writer, "-- 涉及的表: {}", trigger_tables.join(", "))?;
         writeln!(trigger_writer, "--")?;
         writeln!(trigger_writer, "-- 执行方式:")?;
@@ -619,14 +1749,23 @@ pub fn export_schema_ddl(
         writeln!(trigger_writer, "-- 重要: 必须先执行主DDL文件中的 SEQUENCE，再执行本文件")?;
         writeln!(trigger_writer, "-- 注意: 每个触发器以 / 结尾作为语句分隔符")?;
         writeln!(trigger_writer, "-- ============================================")?;
-        writeln!(trigger_writer)?;
-        for stmt in &trig_stmts {
-            writeln!(trigger_writer, "{}", stmt)?;
-            writeln!(trigger_writer)?;
+        for (position, name, stmt) in &trig_stmts {
+            let mut statement = CountingWriter::new(&mut trigger_writer);
+            write_object(
+                &mut statement,
+                "TRIGGER",
+                name,
+                &run_order.annotate(Phase::Trigger, &trigger_file_name, &requote(stmt)),
+            )?;
+            table_stats[*position].0 += statement.bytes();
         }
         trigger_writer
             .flush()
             .context("Failed to flush trigger export to disk")?;
+        let unmappable = unmappable_count(&trigger_writer);
+        drop(trigger_writer);
+        warn_unmappable(job, &trigger_path, options.encoding, unmappable);
+        check_export_file(job, &trigger_path, options.encoding);
 
         // 在主文件中添加提示
         writeln!(writer)?;
@@ -634,7 +1773,7 @@ pub fn export_schema_ddl(
         writeln!(
             writer,
             "-- 注意: 触发器已导出到单独的文件: {}",
-            trigger_path.file_name().unwrap_or_default().to_string_lossy()
+            trigger_file_name
         )?;
         writeln!(
             writer,
@@ -642,29 +1781,129 @@ pub fn export_schema_ddl(
         )?;
     } else if !trig_stmts.is_empty() {
         writeln!(writer)?;
-        writeln!(writer, "-- 触发器 (第二步: 请在 SEQUENCE 之后执行)")?;
-        for stmt in trig_stmts {
-            writeln!(writer, "{}", stmt)?;
+        writeln!(writer, "-- 触发器 (第二步: 请在 SEQUENCE 之后执行)")?;
+        for (position, name, stmt) in trig_stmts {
+            let mut statement = CountingWriter::new(&mut writer);
+            write_object(
+                &mut statement,
+                "TRIGGER",
+                &name,
+                &run_order.annotate(Phase::Trigger, &main_file, &requote(&stmt)),
+            )?;
+            table_stats[position].0 += statement.bytes();
+        }
+    }
+
+    // 视图可能引用多张表，放在所有表之后
+    let view_stmts = generate_views(&source_schema, &target_schema, &views);
+    if !view_stmts.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "-- 视图")?;
+        for (stmt, view) in view_stmts.into_iter().zip(&views) {
+            write_object(
+                &mut writer,
+                "VIEW",
+                &quote_identifier(&format!("{}.{}", target_schema, view.name)),
+                &run_order.annotate(Phase::View, &main_file, &requote(&stmt)),
+            )?;
+        }
+    }
+
+    // 权限放在最后，确保所有对象都已创建
+    let grant_stmts = generate_grants(&target_schema, &grants);
+    if !grant_stmts.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "-- 权限")?;
+        for (stmt, (table, grantee, _, _)) in grant_stmts.into_iter().zip(group_grants(&grants)) {
+            let name = format!(
+                "{} TO {}",
+                quote_identifier(&format!("{}.{}", target_schema, table)),
+                quote_identifier(grantee)
+            );
+            write_object(
+                &mut writer,
+                "GRANT",
+                &name,
+                &run_order.annotate(Phase::Grant, &main_file, &requote(&stmt)),
+            )?;
+        }
+    }
+
+    // Comments grouped at the end, one object per table, when kept out of the table sections.
+    if !separate_comments.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "-- 注释")?;
+        for (position, table_name, comments) in separate_comments {
+            let name = quote_identifier(&table_name);
+            let mut section = CountingWriter::new(&mut writer);
+            writeln!(section)?;
+            writeln!(section, "{}", object_marker("begin", "COMMENT", &name))?;
+            for stmt in &comments {
+                writeln!(section)?;
+                writeln!(
+                    section,
+                    "{}",
+                    run_order.annotate(Phase::Comment, &main_file, &requote(stmt))
+                )?;
+            }
+            writeln!(section, "{}", object_marker("end", "COMMENT", &name))?;
+            table_stats[position].0 += section.bytes();
         }
     }
 
     writer.flush().context("Failed to flush DDL export to disk")?;
+    let unmappable = unmappable_count(&writer);
+    drop(writer);
+    warn_unmappable(job, output_path, options.encoding, unmappable);
+    check_export_file(job, output_path, options.encoding);
+    run_order.write_index(output_path)?;
+
+    for (table_details, (bytes, duration)) in table_cache.iter().zip(table_stats) {
+        if bytes > 0 {
+            job.record_table_stats(&table_details.name, "ddl", 0, bytes, duration);
+        }
+    }
+    job.stats.duration_ms += duration_ms(started.elapsed());
     Ok(())
 }
 
-fn format_column_definition(column: &Column) -> String {
+fn format_column_definition(column: &Column, table_collation: Option<&str>) -> String {
     let mut parts = Vec::new();
     parts.push(quote_identifier(&column.name));
     parts.push(format_data_type(column));
 
-    if column.identity {
-        // IDENTITY column - DM8 syntax: IDENTITY(seed, increment)
+    // Only collations that differ from the table default need a column-level clause.
+    if let Some(collation) = column.collation.as_deref() {
+        if is_string_type(&column.data_type.to_uppercase())
+            && table_collation.map_or(true, |table| !table.eq_ignore_ascii_case(collation))
+        {
+            parts.push(format!("COLLATE {}", collation));
+        }
+    }
+
+    if let Some(expression) = column.virtual_expression.as_deref() {
+        // Virtual columns take neither IDENTITY nor DEFAULT.
+        parts.push(format!("AS ({})", expression.trim()));
+    } else if column.identity {
+        // IDENTITY column - DM8 syntax: IDENTITY(seed, increment), default IDENTITY(1, 1)
         // Note: IDENTITY columns cannot have DEFAULT clause
-        if let (Some(start), Some(inc)) = (column.identity_start, column.identity_increment) {
-            parts.push(format!("IDENTITY({}, {})", start, inc));
-        } else {
-            // Default: IDENTITY(1, 1)
-            parts.push("IDENTITY(1, 1)".to_string());
+        let (start, inc) = match (column.identity_start, column.identity_increment) {
+            (Some(start), Some(inc)) => (start, inc),
+            _ => (1, 1),
+        };
+        // A generation mode reported by the catalog is kept, since it decides whether inserts
+        // may supply their own values.
+        let mode = match column.identity_generation {
+            Some(IdentityGeneration::Always) => Some("ALWAYS"),
+            Some(IdentityGeneration::ByDefault) => Some("BY DEFAULT"),
+            None => None,
+        };
+        match mode {
+            Some(mode) => parts.push(format!(
+                "GENERATED {} AS IDENTITY (START WITH {} INCREMENT BY {})",
+                mode, start, inc
+            )),
+            None => parts.push(format!("IDENTITY({}, {})", start, inc)),
         }
     } else if let Some(default) = column
         .default_value
@@ -679,9 +1918,40 @@ fn format_column_definition(column: &Column) -> String {
     let nullability = if column.nullable { "NULL" } else { "NOT NULL" };
     parts.push(nullability.to_string());
 
+    if let (Some(srid), true) = (column.geometry_srid, is_geometry_type(&column.data_type)) {
+        parts.push(srid_check(&quote_identifier(&column.name), srid));
+    }
+
     parts.join(" ")
 }
 
+/// `INTERVAL DAY TO SECOND` and friends with their precisions: the leading field takes
+/// `precision`, a SECOND field (trailing or sole) the fractional `scale`.
+fn format_interval_type(data_type: &str, precision: Option<i32>, scale: Option<i32>) -> String {
+    let fields = data_type["INTERVAL ".len()..].trim();
+    let (leading, trailing) = match fields.split_once(" TO ") {
+        Some((leading, trailing)) => (leading.trim(), Some(trailing.trim())),
+        None => (fields, None),
+    };
+    let precision = precision.filter(|p| *p > 0);
+    let fsp = scale.filter(|s| *s >= 0 && *s <= 9);
+
+    let mut out = format!("INTERVAL {}", leading);
+    match (precision, leading == "SECOND" && trailing.is_none(), fsp) {
+        (Some(p), true, Some(s)) => out.push_str(&format!("({}, {})", p, s)),
+        (Some(p), _, _) => out.push_str(&format!("({})", p)),
+        _ => {}
+    }
+    if let Some(trailing) = trailing {
+        out.push_str(" TO ");
+        out.push_str(trailing);
+        if let Some(s) = fsp.filter(|_| trailing == "SECOND") {
+            out.push_str(&format!("({})", s));
+        }
+    }
+    out
+}
+
 fn format_data_type(column: &Column) -> String {
     let mut data_type = column.data_type.trim().to_uppercase();
 
@@ -740,6 +2010,18 @@ fn format_data_type(column: &Column) -> String {
                 }
             }
         }
+        // Time of day with fractional seconds precision; DM8 defaults to 0
+        "TIME" | "TIME WITH TIME ZONE" => {
+            if let Some(fsp) = column.scale.filter(|s| *s > 0 && *s <= 6) {
+                data_type = data_type.replacen("TIME", &format!("TIME({})", fsp), 1);
+            }
+        }
+        _ if is_geometry_type(&data_type) => {
+            data_type = qualified_type(&data_type);
+        }
+        _ if data_type.starts_with("INTERVAL ") => {
+            data_type = format_interval_type(&data_type, column.precision, column.scale);
+        }
         // These types don't need length/precision in DDL
         "DATE" | "BLOB" | "CLOB" | "NCLOB" | "TEXT" | "LONG" | "LONGVARBINARY"
         | "INTEGER" | "INT" | "BIGINT" | "SMALLINT" | "TINYINT" | "BIT" | "BOOLEAN" => {
@@ -1262,7 +2544,7 @@ fn extract_when_clause(body: &str) -> (String, String) {
 
 #[cfg(test)]
 mod format_default_tests {
-    use super::format_default;
+    use super::{format_column_definition, format_data_type, format_default};
     use crate::models::Column;
 
     fn column_with_type(data_type: &str) -> Column {
@@ -1279,9 +2561,50 @@ mod format_default_tests {
             identity: false,
             identity_start: None,
             identity_increment: None,
+            identity_generation: None,
+            identity_current: None,
+            virtual_expression: None,
+            geometry_srid: None,
+            collation: None,
         }
     }
 
+    #[test]
+    fn format_data_type_keeps_time_and_interval_precisions() {
+        let mut time = column_with_type("TIME");
+        assert_eq!(format_data_type(&time), "TIME");
+        time.scale = Some(3);
+        assert_eq!(format_data_type(&time), "TIME(3)");
+        time.data_type = "TIME WITH TIME ZONE".to_string();
+        assert_eq!(format_data_type(&time), "TIME(3) WITH TIME ZONE");
+
+        let mut interval = column_with_type("INTERVAL DAY TO SECOND");
+        assert_eq!(format_data_type(&interval), "INTERVAL DAY TO SECOND");
+        interval.precision = Some(3);
+        interval.scale = Some(2);
+        assert_eq!(format_data_type(&interval), "INTERVAL DAY(3) TO SECOND(2)");
+        interval.data_type = "interval year to month".to_string();
+        assert_eq!(format_data_type(&interval), "INTERVAL YEAR(3) TO MONTH");
+        interval.data_type = "INTERVAL SECOND".to_string();
+        assert_eq!(format_data_type(&interval), "INTERVAL SECOND(3, 2)");
+        interval.data_type = "INTERVAL DAY(4) TO SECOND(1)".to_string();
+        assert_eq!(format_data_type(&interval), "INTERVAL DAY(4) TO SECOND(1)");
+    }
+
+    #[test]
+    fn geometry_columns_keep_type_and_srid() {
+        let mut shape = column_with_type("ST_POLYGON");
+        assert_eq!(
+            format_column_definition(&shape, None),
+            "\"col\" SYSGEO.ST_POLYGON NULL"
+        );
+        shape.geometry_srid = Some(4326);
+        assert_eq!(
+            format_column_definition(&shape, None),
+            "\"col\" SYSGEO.ST_POLYGON NULL CHECK (DMGEO.ST_SRID(\"col\") = 4326)"
+        );
+    }
+
     #[test]
     fn format_default_keeps_user_keyword_for_string_types() {
         let column = column_with_type("VARCHAR");
@@ -1525,8 +2848,21 @@ fn normalize_trigger_references(input: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{generate_foreign_keys, generate_indexes, generate_triggers, TriggerTerminator};
-    use crate::models::{CheckConstraint, ForeignKey, Index, TableDetails, TriggerDefinition, UniqueConstraint};
+    use super::{
+        apply_storage_options, deferred_indexes, generate_comments, generate_create_schema, generate_create_table,
+        generate_foreign_keys, remap_foreign_key_schemas, sanitize_check_constraints,
+        generate_grants, generate_indexes, generate_triggers, generate_views, retarget_schema,
+        skipped_indexes, table_blocks, views_on_tables,
+        DdlExportOptions, DdlInventory, DdlStage, IndexSkip, TriggerTerminator,
+    };
+    use std::collections::HashMap;
+    use crate::export::dialect::Dialect;
+    use crate::export::run_order::Phase;
+    use crate::models::{
+        CheckConstraint, Column, CommentsPlacement, DdlObjectType, ExportEncoding, ForeignKey,
+        IdentityGeneration, Index, IndexColumn, LineEnding, ObjectGrant, QuoteStyle, TableDetails,
+        TriggerDefinition, UniqueConstraint, ViewDefinition,
+    };
 
     fn base_table_details(name: &str, indexes: Vec<Index>) -> TableDetails {
         TableDetails {
@@ -1539,6 +2875,8 @@ mod tests {
             foreign_keys: Vec::<ForeignKey>::new(),
             check_constraints: Vec::<CheckConstraint>::new(),
             triggers: Vec::<TriggerDefinition>::new(),
+            tablespace: None,
+            default_collation: None,
         }
     }
 
@@ -1553,7 +2891,9 @@ mod tests {
                     "TRIGGER_NAME".to_string(),
                     "TRIGGER_GROUP".to_string(),
                 ],
+                column_specs: Vec::new(),
                 unique: false,
+                tablespace: None,
             }],
         );
 
@@ -1575,7 +2915,9 @@ mod tests {
                     "TRIGGER_NAME".to_string(),
                     "TRIGGER_GROUP".to_string(),
                 ],
+                column_specs: Vec::new(),
                 unique: false,
+                tablespace: None,
             }],
         );
         table.primary_keys = vec![
@@ -1596,12 +2938,16 @@ mod tests {
                 Index {
                     name: "IDX_ONE".to_string(),
                     columns: vec!["A".to_string(), "B".to_string()],
+                    column_specs: Vec::new(),
                     unique: false,
+                    tablespace: None,
                 },
                 Index {
                     name: "IDX_TWO".to_string(),
                     columns: vec!["A".to_string(), "B".to_string()],
+                    column_specs: Vec::new(),
                     unique: false,
+                    tablespace: None,
                 },
             ],
         );
@@ -1610,6 +2956,40 @@ mod tests {
         assert_eq!(statements.len(), 1, "Should skip duplicate index columns");
     }
 
+    #[test]
+    fn skipped_indexes_reports_lost_indexes_but_not_constraint_covered_ones() {
+        let index = |name: &str, columns: &[&str]| Index {
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            column_specs: Vec::new(),
+            unique: false,
+            tablespace: None,
+        };
+        let mut table = base_table_details(
+            "PLATFORM_V3.ORDERS",
+            vec![
+                index("INDEX33561156", &["ID"]),
+                index("IDX_CUSTOMER", &["CUSTOMER_ID"]),
+                index("IDX_CUSTOMER_DUP", &["CUSTOMER_ID"]),
+                index("IDX_EMPTY", &[]),
+            ],
+        );
+        table.primary_keys = vec!["ID".to_string()];
+
+        let skipped: Vec<(&str, IndexSkip)> = skipped_indexes(&table)
+            .into_iter()
+            .map(|(index, skip)| (index.name.as_str(), skip))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("IDX_CUSTOMER_DUP", IndexSkip::Duplicate),
+                ("IDX_EMPTY", IndexSkip::NoColumns),
+            ]
+        );
+        assert_eq!(generate_indexes(&table).len(), 1);
+    }
+
     #[test]
     fn generate_indexes_skips_index_matching_unique_constraint_columns() {
         let mut table = base_table_details(
@@ -1617,7 +2997,9 @@ mod tests {
             vec![Index {
                 name: "IDX_UNIQ".to_string(),
                 columns: vec!["CODE".to_string(), "TYPE".to_string()],
+                column_specs: Vec::new(),
                 unique: false,
+                tablespace: None,
             }],
         );
         table.unique_constraints = vec![UniqueConstraint {
@@ -1629,6 +3011,471 @@ mod tests {
         assert_eq!(statements.len(), 0, "Should skip index that matches unique constraint columns");
     }
 
+    #[test]
+    fn generate_indexes_renders_descending_and_expression_columns() {
+        let table = base_table_details(
+            "APP.ORDERS",
+            vec![Index {
+                name: "IDX_ORDERS_MIX".to_string(),
+                columns: vec!["CREATED_AT".to_string(), "SYS_NC00005$".to_string()],
+                column_specs: vec![
+                    IndexColumn {
+                        descending: true,
+                        expression: None,
+                    },
+                    IndexColumn {
+                        descending: false,
+                        expression: Some("UPPER(\"CODE\")".to_string()),
+                    },
+                ],
+                unique: false,
+                tablespace: None,
+            }],
+        );
+
+        let statements = generate_indexes(&table);
+        assert_eq!(
+            statements,
+            vec!["CREATE INDEX \"IDX_ORDERS_MIX\" ON \"APP\".\"ORDERS\" (\"CREATED_AT\" DESC, UPPER(\"CODE\"));".to_string()]
+        );
+    }
+
+    #[test]
+    fn generate_indexes_keeps_descending_index_on_pk_columns() {
+        let mut table = base_table_details(
+            "APP.EVENTS",
+            vec![Index {
+                name: "IDX_EVENTS_ID_DESC".to_string(),
+                columns: vec!["ID".to_string()],
+                column_specs: vec![IndexColumn {
+                    descending: true,
+                    expression: None,
+                }],
+                unique: false,
+                tablespace: None,
+            }],
+        );
+        table.primary_keys = vec!["ID".to_string()];
+
+        let statements = generate_indexes(&table);
+        assert_eq!(statements.len(), 1);
+        assert!(statements[0].contains("(\"ID\" DESC)"));
+    }
+
+    fn storage_options(include_storage: bool) -> DdlExportOptions {
+        DdlExportOptions {
+            drop_existing: false,
+            trigger_terminator: TriggerTerminator::DataGrip,
+            include_storage,
+            tablespace_map: HashMap::from([("MAIN".to_string(), "APP_DATA".to_string())]),
+            include_grants: false,
+            objects: DdlObjectType::ALL.into_iter().collect(),
+            only_referenced_sequences: true,
+            sequence_renames: HashMap::new(),
+            table_renames: HashMap::new(),
+            encoding: ExportEncoding::Utf8,
+            line_ending: LineEnding::Lf,
+            include_dependencies: false,
+            skip_not_null_checks: false,
+            dialect: Dialect::Dm8,
+            preserve_case: false,
+            quote_style: QuoteStyle::Always,
+            comments_placement: CommentsPlacement::Inline,
+            include_schema_ddl: false,
+            schema_owner: None,
+            stage: DdlStage::Complete,
+            indexes_after_data: false,
+        }
+    }
+
+    fn table_with_storage() -> TableDetails {
+        let mut table = base_table_details(
+            "APP.ORDERS",
+            vec![Index {
+                name: "IDX_ORDERS_CODE".to_string(),
+                columns: vec!["CODE".to_string()],
+                column_specs: Vec::new(),
+                unique: false,
+                tablespace: Some("IDX_TS".to_string()),
+            }],
+        );
+        table.tablespace = Some("main".to_string());
+        table
+    }
+
+    #[test]
+    fn storage_options_remap_tablespaces_into_ddl() {
+        let mut table = table_with_storage();
+        apply_storage_options(&mut table, &storage_options(true));
+
+        assert!(generate_create_table(&table).contains("\n) TABLESPACE \"APP_DATA\";"));
+        let indexes = generate_indexes(&table);
+        assert!(indexes[0].ends_with("(\"CODE\") TABLESPACE \"IDX_TS\";"));
+    }
+
+    #[test]
+    fn storage_options_disabled_omit_tablespace_clauses() {
+        let mut table = table_with_storage();
+        apply_storage_options(&mut table, &storage_options(false));
+
+        assert!(!generate_create_table(&table).contains("TABLESPACE"));
+        assert!(!generate_indexes(&table)[0].contains("TABLESPACE"));
+    }
+
+    #[test]
+    fn generate_create_table_keeps_identity_generation_mode() {
+        let identity = |name: &str, mode: Option<IdentityGeneration>| Column {
+            name: name.to_string(),
+            data_type: "BIGINT".to_string(),
+            length: None,
+            precision: None,
+            scale: None,
+            char_semantics: None,
+            nullable: false,
+            comment: None,
+            default_value: None,
+            identity: true,
+            identity_start: Some(100),
+            identity_increment: Some(10),
+            identity_generation: mode,
+            identity_current: Some(250),
+            virtual_expression: None,
+            geometry_srid: None,
+            collation: None,
+        };
+        let create = |mode| {
+            let mut table = base_table_details("S.ORDERS", Vec::new());
+            table.columns = vec![identity("ID", mode)];
+            generate_create_table(&table)
+        };
+
+        assert!(create(None).contains("\"ID\" BIGINT IDENTITY(100, 10) NOT NULL"));
+        assert!(create(Some(IdentityGeneration::Always)).contains(
+            "\"ID\" BIGINT GENERATED ALWAYS AS IDENTITY (START WITH 100 INCREMENT BY 10) NOT NULL"
+        ));
+        assert!(create(Some(IdentityGeneration::ByDefault))
+            .contains("GENERATED BY DEFAULT AS IDENTITY (START WITH 100 INCREMENT BY 10)"));
+    }
+
+    #[test]
+    fn generate_create_table_writes_virtual_column_expression() {
+        let mut table = base_table_details("S.ITEMS", Vec::new());
+        table.columns = vec![Column {
+            name: "TOTAL".to_string(),
+            data_type: "NUMBER".to_string(),
+            length: None,
+            precision: Some(12),
+            scale: Some(2),
+            char_semantics: None,
+            nullable: true,
+            comment: None,
+            default_value: None,
+            identity: false,
+            identity_start: None,
+            identity_increment: None,
+            identity_generation: None,
+            identity_current: None,
+            virtual_expression: Some(" \"PRICE\" * \"QTY\" ".to_string()),
+            geometry_srid: None,
+            collation: None,
+        }];
+
+        let ddl = generate_create_table(&table);
+        assert!(ddl.contains("\"TOTAL\" NUMBER(12,2) AS (\"PRICE\" * \"QTY\") NULL"));
+        assert!(!ddl.contains("DEFAULT"));
+    }
+
+    #[test]
+    fn generate_create_table_emits_collation_clauses() {
+        let mut table = base_table_details("S.USERS", Vec::new());
+        table.default_collation = Some("BINARY_CI".to_string());
+        let varchar = |name: &str, collation: &str| Column {
+            name: name.to_string(),
+            data_type: "VARCHAR".to_string(),
+            length: Some(20),
+            precision: None,
+            scale: None,
+            char_semantics: None,
+            nullable: true,
+            comment: None,
+            default_value: None,
+            identity: false,
+            identity_start: None,
+            identity_increment: None,
+            identity_generation: None,
+            identity_current: None,
+            virtual_expression: None,
+            geometry_srid: None,
+            collation: Some(collation.to_string()),
+        };
+        table.columns = vec![varchar("NAME", "BINARY_CI"), varchar("CODE", "BINARY_CS")];
+
+        let ddl = generate_create_table(&table);
+        assert!(ddl.contains("\"NAME\" VARCHAR(20) NULL"));
+        assert!(ddl.contains("\"CODE\" VARCHAR(20) COLLATE BINARY_CS NULL"));
+        assert!(ddl.ends_with(") DEFAULT COLLATION BINARY_CI;"));
+    }
+
+    #[test]
+    fn sanitize_check_constraints_drops_not_null_duplicates_and_quotes_columns() {
+        let column = |name: &str, nullable: bool| Column {
+            name: name.to_string(),
+            data_type: "INT".to_string(),
+            length: None,
+            precision: None,
+            scale: None,
+            char_semantics: None,
+            nullable,
+            comment: None,
+            default_value: None,
+            identity: false,
+            identity_start: None,
+            identity_increment: None,
+            identity_generation: None,
+            identity_current: None,
+            virtual_expression: None,
+            geometry_srid: None,
+            collation: None,
+        };
+        let check = |name: &str, condition: &str| CheckConstraint {
+            name: name.to_string(),
+            condition: condition.to_string(),
+        };
+        let mut table = base_table_details("S.ORDERS", Vec::new());
+        table.columns = vec![
+            column("ID", false),
+            column("QTY", true),
+            column("STATUS", true),
+        ];
+        table.check_constraints = vec![
+            check("CK_SYS_1", "\"ID\" IS NOT NULL"),
+            check("CK_SYS_2", "(id is not null)"),
+            check("CK_QTY_SET", "\"QTY\" IS NOT NULL"),
+            check(
+                "CK_QTY",
+                "qty > 0 AND STATUS IN ('qty', 'ID') AND abs(Qty) < 10",
+            ),
+        ];
+
+        let mut options = storage_options(false);
+        let mut untouched = table.clone();
+        sanitize_check_constraints(&mut untouched, &options);
+        assert_eq!(untouched.check_constraints.len(), 4);
+
+        options.skip_not_null_checks = true;
+        sanitize_check_constraints(&mut table, &options);
+        let conditions: Vec<&str> = table
+            .check_constraints
+            .iter()
+            .map(|check| check.condition.as_str())
+            .collect();
+        assert_eq!(
+            conditions,
+            vec![
+                "\"QTY\" IS NOT NULL",
+                "\"QTY\" > 0 AND \"STATUS\" IN ('qty', 'ID') AND abs(\"QTY\") < 10",
+            ]
+        );
+    }
+
+    #[test]
+    fn generate_comments_are_separate_from_create_table() {
+        let mut table = base_table_details("S.USERS", Vec::new());
+        table.comment = Some("用户".to_string());
+
+        assert!(!generate_create_table(&table).contains("COMMENT ON"));
+        assert_eq!(
+            generate_comments(&table),
+            vec!["COMMENT ON TABLE \"S\".\"USERS\" IS '用户';"]
+        );
+    }
+
+    #[test]
+    fn separate_comments_leave_the_table_section() {
+        let mut table = base_table_details("S.USERS", Vec::new());
+        table.comment = Some("用户".to_string());
+        table.columns = vec![Column {
+            comment: Some("主键".to_string()),
+            ..Column::named("ID", "INT")
+        }];
+        let comments = vec![
+            "COMMENT ON TABLE \"S\".\"USERS\" IS '用户';".to_string(),
+            "COMMENT ON COLUMN \"S\".\"USERS\".\"ID\" IS '主键';".to_string(),
+        ];
+        let section_comments = |blocks: &[(Phase, Vec<String>)]| {
+            blocks
+                .iter()
+                .filter(|(phase, _)| *phase == Phase::Comment)
+                .flat_map(|(_, block)| block.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let inline = storage_options(false);
+        let (blocks, separate) = table_blocks(&table, &inline);
+        assert_eq!(section_comments(&blocks), comments);
+        assert!(separate.is_empty());
+
+        let options = DdlExportOptions {
+            comments_placement: CommentsPlacement::SeparateSection,
+            ..storage_options(false)
+        };
+        let (blocks, separate) = table_blocks(&table, &options);
+        assert_eq!(separate, comments);
+        assert!(section_comments(&blocks).is_empty());
+        assert!(blocks
+            .iter()
+            .flat_map(|(_, block)| block)
+            .all(|stmt| !stmt.contains("COMMENT ON")));
+        assert!(blocks[0].1[0].starts_with("CREATE TABLE \"S\".\"USERS\""));
+
+        let omitted = DdlExportOptions {
+            comments_placement: CommentsPlacement::Omit,
+            ..storage_options(false)
+        };
+        let (blocks, separate) = table_blocks(&table, &omitted);
+        assert!(separate.is_empty());
+        assert!(section_comments(&blocks).is_empty());
+    }
+
+    #[test]
+    fn deferred_indexes_move_past_the_data() {
+        let mut table = table_with_storage();
+        apply_storage_options(&mut table, &storage_options(false));
+        let staged = |stage: DdlStage, indexes_after_data: bool| DdlExportOptions {
+            stage,
+            indexes_after_data,
+            ..storage_options(false)
+        };
+        let has_index_block = |options: &DdlExportOptions| {
+            table_blocks(&table, options)
+                .0
+                .iter()
+                .any(|(phase, _)| *phase == Phase::Index)
+        };
+
+        // Deferred: the before-data script only creates the table, the after-data script
+        // (written after the data section) creates the index.
+        let before = staged(DdlStage::BeforeData, true);
+        let (blocks, _) = table_blocks(&table, &before);
+        assert!(!has_index_block(&before));
+        assert!(blocks
+            .iter()
+            .flat_map(|(_, block)| block)
+            .all(|stmt| !stmt.contains("CREATE INDEX")));
+        assert!(!before.writes_indexes(true));
+        let after = staged(DdlStage::AfterData, true);
+        assert!(after.writes_indexes(true));
+        assert!(table_blocks(&table, &after).0.is_empty());
+        let deferred = deferred_indexes(&table, "APP");
+        assert_eq!(deferred.len(), 1);
+        assert_eq!(deferred[0].0, "\"APP\".\"IDX_ORDERS_CODE\"");
+        assert!(deferred[0].1.starts_with("CREATE INDEX"));
+
+        // Not deferred, or in a single script: the index stays with its table.
+        assert!(has_index_block(&staged(DdlStage::BeforeData, false)));
+        assert!(!staged(DdlStage::AfterData, false).writes_indexes(true));
+        assert!(has_index_block(&staged(DdlStage::Complete, true)));
+        assert!(!staged(DdlStage::Complete, true).writes_indexes(true));
+    }
+
+    #[test]
+    fn generate_views_retargets_full_create_statement() {
+        let views = vec![
+            ViewDefinition {
+                name: "V_USERS".to_string(),
+                definition: "SELECT ID FROM SRC.USERS;".to_string(),
+            },
+            ViewDefinition {
+                name: "V_NAMES".to_string(),
+                definition: "create view SRC.V_NAMES (ID, NAME_ALIAS) as select ID, NAME from SRC.USERS"
+                    .to_string(),
+            },
+        ];
+
+        let statements = generate_views("SRC", "TGT", &views);
+        assert_eq!(
+            statements[0],
+            "CREATE OR REPLACE VIEW \"TGT\".\"V_USERS\" AS\nSELECT ID FROM \"TGT\".USERS;"
+        );
+        assert_eq!(
+            statements[1],
+            "CREATE OR REPLACE VIEW \"TGT\".\"V_NAMES\" (ID, NAME_ALIAS) AS\nselect ID, NAME from \"TGT\".USERS;"
+        );
+    }
+
+    #[test]
+    fn view_queries_are_retargeted_outside_literals_and_other_schemas() {
+        let query = "SELECT u.ID, 'SRC.X' AS NOTE, o.SRC -- SRC.Y\n\
+                     FROM src.USERS u JOIN \"SRC\".\"ORDERS\" o ON o.ID = u.ID \
+                     JOIN OTHER.SRC.T t ON 1 = 1";
+        assert_eq!(
+            retarget_schema(query, "SRC", "TGT"),
+            "SELECT u.ID, 'SRC.X' AS NOTE, o.SRC -- SRC.Y\n\
+             FROM \"TGT\".USERS u JOIN \"TGT\".\"ORDERS\" o ON o.ID = u.ID \
+             JOIN OTHER.SRC.T t ON 1 = 1"
+        );
+    }
+
+    #[test]
+    fn only_views_reading_the_selected_tables_are_exported() {
+        let view = |name: &str, definition: &str| ViewDefinition {
+            name: name.to_string(),
+            definition: definition.to_string(),
+        };
+        let views = vec![
+            view("V_ACTIVE", "SELECT * FROM V_USERS WHERE ACTIVE = 1"),
+            view("V_AUDIT", "SELECT * FROM SRC.AUDIT_LOG"),
+            view("V_FOREIGN", "SELECT * FROM OTHER.USERS"),
+            view("V_USERS", "SELECT ID, ACTIVE FROM SRC.USERS"),
+        ];
+
+        let kept = views_on_tables("SRC", views, &["USERS".to_string()]);
+        let names: Vec<&str> = kept.iter().map(|view| view.name.as_str()).collect();
+        assert_eq!(names, vec!["V_USERS", "V_ACTIVE"]);
+    }
+
+    #[test]
+    fn ddl_options_report_selected_object_types() {
+        let mut options = storage_options(false);
+        options.objects = [DdlObjectType::Indexes].into_iter().collect();
+        assert!(options.includes(DdlObjectType::Indexes));
+        assert!(!options.includes(DdlObjectType::Tables));
+    }
+
+    #[test]
+    fn generate_grants_groups_privileges_per_grantee() {
+        let grant = |grantee: &str, privilege: &str, grantable: bool| ObjectGrant {
+            table_name: "USERS".to_string(),
+            grantee: grantee.to_string(),
+            privilege: privilege.to_string(),
+            grantable,
+        };
+        let grants = vec![
+            grant("APP_RO", "SELECT", false),
+            grant("APP_RW", "INSERT", false),
+            grant("APP_RW", "SELECT", false),
+            grant("APP_RW", "UPDATE", true),
+        ];
+
+        let statements = generate_grants("TARGET", &grants);
+        assert_eq!(
+            statements,
+            vec![
+                "GRANT SELECT ON \"TARGET\".\"USERS\" TO \"APP_RO\";",
+                "GRANT INSERT, SELECT ON \"TARGET\".\"USERS\" TO \"APP_RW\";",
+                "GRANT UPDATE ON \"TARGET\".\"USERS\" TO \"APP_RW\" WITH GRANT OPTION;",
+            ]
+        );
+    }
+
+    #[test]
+    fn generate_create_schema_authorizes_owner() {
+        assert_eq!(
+            generate_create_schema("TARGET", "SYSDBA"),
+            "CREATE SCHEMA \"TARGET\" AUTHORIZATION \"SYSDBA\";"
+        );
+    }
+
     #[test]
     fn generate_foreign_keys_omits_no_action_rule() {
         let mut table = base_table_details("PLATFORM_V3.QRTZ_TRIGGERS", Vec::new());
@@ -1648,6 +3495,34 @@ mod tests {
         assert!(!stmt.contains("ON UPDATE NO ACTION"));
     }
 
+    #[test]
+    fn foreign_keys_follow_the_schema_rename() {
+        let fk = |name: &str, referenced: &str| ForeignKey {
+            name: name.to_string(),
+            columns: vec!["ID".to_string()],
+            referenced_table: referenced.to_string(),
+            referenced_columns: vec!["ID".to_string()],
+            delete_rule: None,
+            update_rule: None,
+        };
+        let mut table = base_table_details("ORDERS", Vec::new());
+        table.foreign_keys = vec![
+            fk("FK_CUSTOMER", "app.CUSTOMERS"),
+            fk("FK_REGION", "REGIONS"),
+            fk("FK_USER", "AUTH.USERS"),
+        ];
+
+        let warnings = remap_foreign_key_schemas(&mut table, "APP", "APP_NEW");
+        table.name = "APP_NEW.ORDERS".to_string();
+        let statements = generate_foreign_keys(&table);
+
+        assert!(statements[0].contains("REFERENCES \"APP_NEW\".\"CUSTOMERS\""));
+        assert!(statements[1].contains("REFERENCES \"APP_NEW\".\"REGIONS\""));
+        assert!(statements[2].contains("REFERENCES \"AUTH\".\"USERS\""));
+        assert_eq!(warnings[..2], [None, None]);
+        assert!(warnings[2].as_deref().unwrap().contains("AUTH.USERS outside schema APP"));
+    }
+
     #[test]
     fn generate_triggers_uses_full_body_when_body_contains_create() {
         let body = "CREATE OR REPLACE TRIGGER TRG_BPM_CATEGORY_ID\nBEFORE INSERT ON BPM_CATEGORY\nBEGIN\nNULL;\nEND;";
@@ -1865,4 +3740,159 @@ mod tests {
         assert!(stmt.contains("\n/"), "Expected script mode to include '/' terminator");
         assert!(stmt.trim_end().ends_with('/'));
     }
+
+    #[test]
+    fn inventory_counts_selected_objects_without_skipped_ones() {
+        let index = |name: &str, columns: &[&str]| Index {
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            column_specs: Vec::new(),
+            unique: false,
+            tablespace: None,
+        };
+        let trigger = |name: &str, body: &str| TriggerDefinition {
+            name: name.to_string(),
+            table_name: "ORDERS".to_string(),
+            timing: "BEFORE".to_string(),
+            events: vec!["INSERT".to_string()],
+            each_row: true,
+            body: body.to_string(),
+        };
+        let mut table = table_with_storage();
+        table.indexes.push(index("IDX_ORDERS_CODE_DUP", &["CODE"]));
+        table.primary_keys = vec!["ID".to_string()];
+        table.check_constraints = vec![CheckConstraint {
+            name: "CK_ORDERS_CODE".to_string(),
+            condition: "CODE <> ''".to_string(),
+        }];
+        table.triggers = vec![
+            trigger("TRG_ORDERS_ID", "BEGIN\n:NEW.ID := 1;\nEND"),
+            trigger("TRG_ORDERS_BLANK", "  "),
+        ];
+        let grant = |privilege: &str| ObjectGrant {
+            table_name: "ORDERS".to_string(),
+            grantee: "REPORTING".to_string(),
+            privilege: privilege.to_string(),
+            grantable: false,
+        };
+        let grants = vec![grant("SELECT"), grant("UPDATE")];
+
+        let mut options = storage_options(false);
+        let inventory =
+            DdlInventory::collect(std::slice::from_ref(&table), &[], &[], &grants, &options);
+        assert_eq!(
+            inventory,
+            DdlInventory {
+                tables: 1,
+                columns: table.columns.len(),
+                indexes: 1,
+                constraints: 2,
+                triggers: 1,
+                sequences: 0,
+                views: 0,
+                grants: 1,
+            }
+        );
+        let lines = inventory.header_lines();
+        assert_eq!(
+            lines.first().map(String::as_str),
+            Some("-- @inventory begin")
+        );
+        assert!(lines.contains(&"-- indexes=1".to_string()));
+        assert_eq!(lines.last().map(String::as_str), Some("-- @inventory end"));
+
+        options.objects = [DdlObjectType::Tables].into_iter().collect();
+        let tables_only =
+            DdlInventory::collect(std::slice::from_ref(&table), &[], &[], &grants, &options);
+        assert_eq!((tables_only.indexes, tables_only.triggers), (0, 0));
+
+        options.objects = DdlObjectType::ALL.into_iter().collect();
+        table.foreign_keys = vec![ForeignKey {
+            name: "FK_ORDERS_CUSTOMER".to_string(),
+            columns: vec!["CUSTOMER_ID".to_string()],
+            referenced_table: "CUSTOMERS".to_string(),
+            referenced_columns: vec!["ID".to_string()],
+            delete_rule: None,
+            update_rule: None,
+        }];
+        options.stage = DdlStage::BeforeData;
+        let before = DdlInventory::collect(std::slice::from_ref(&table), &[], &[], &[], &options);
+        assert_eq!((before.tables, before.constraints), (1, 2));
+        assert_eq!(before.triggers, 0);
+        options.stage = DdlStage::AfterData;
+        let after = DdlInventory::collect(std::slice::from_ref(&table), &[], &[], &[], &options);
+        assert_eq!((after.tables, after.indexes), (0, 0));
+        assert_eq!((after.constraints, after.triggers), (1, 1));
+
+        options.indexes_after_data = true;
+        let after = DdlInventory::collect(std::slice::from_ref(&table), &[], &[], &[], &options);
+        assert_eq!(after.indexes, 1);
+        options.stage = DdlStage::BeforeData;
+        let before = DdlInventory::collect(std::slice::from_ref(&table), &[], &[], &[], &options);
+        assert_eq!(before.indexes, 0);
+        options.stage = DdlStage::Complete;
+        let complete = DdlInventory::collect(std::slice::from_ref(&table), &[], &[], &[], &options);
+        assert_eq!(complete.indexes, 1);
+    }
+}
+
+#[cfg(test)]
+mod golden_tests {
+    use super::generate_full_table_ddl;
+    use crate::models::TableDetails;
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    /// Each `<case>.json` holds serialized `TableDetails`; `<case>.sql` next to it is the DDL it
+    /// must render to. Run with `UPDATE_GOLDEN=1` to rewrite the `.sql` files after an intended
+    /// change and review the diff.
+    fn fixtures() -> Vec<PathBuf> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/export/testdata/ddl");
+        let mut fixtures: Vec<PathBuf> = fs::read_dir(&dir)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", dir.display(), e))
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        fixtures.sort();
+        fixtures
+    }
+
+    #[test]
+    fn full_table_ddl_matches_golden_files() {
+        let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+        let fixtures = fixtures();
+        assert!(!fixtures.is_empty(), "No DDL fixtures found");
+
+        let mut mismatches = Vec::new();
+        for fixture in fixtures {
+            let json = fs::read_to_string(&fixture).unwrap();
+            let table: TableDetails = serde_json::from_str(&json)
+                .unwrap_or_else(|e| panic!("Invalid fixture {}: {}", fixture.display(), e));
+            let actual = generate_full_table_ddl(&table);
+
+            let golden = fixture.with_extension("sql");
+            if update {
+                fs::write(&golden, &actual).unwrap();
+                continue;
+            }
+            let expected = fs::read_to_string(&golden)
+                .unwrap_or_default()
+                .replace("\r\n", "\n");
+            if actual != expected {
+                mismatches.push(format!(
+                    "{}\n--- expected\n{}--- actual\n{}",
+                    golden.display(),
+                    expected,
+                    actual
+                ));
+            }
+        }
+        assert!(
+            mismatches.is_empty(),
+            "DDL differs from golden files (UPDATE_GOLDEN=1 rewrites them):\n{}",
+            mismatches.join("\n")
+        );
+    }
 }
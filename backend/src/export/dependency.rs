@@ -0,0 +1,279 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anyhow::Result;
+
+use crate::export::sequences::referenced_sequence_names;
+use crate::models::{TableDependencies, TableDetails, TableReference};
+
+/// Load order for a set of tables derived from their foreign keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadOrder {
+    /// Indexes into the input slice, referenced (parent) tables before referencing ones.
+    pub order: Vec<usize>,
+    /// Tables on a foreign key cycle; they are appended in input order.
+    pub cyclic: Vec<String>,
+}
+
+/// Orders tables so every table comes after the tables its foreign keys reference.
+///
+/// Only references to other tables in `tables` (within `schema`) count; self-references and
+/// references to tables outside the export do not constrain the order. Among tables that are
+/// ready at the same time the input order is kept, so unrelated tables stay where the user put
+/// them.
+pub fn load_order(schema: &str, tables: &[TableDetails]) -> LoadOrder {
+    let positions: HashMap<String, usize> = tables
+        .iter()
+        .enumerate()
+        .map(|(i, table)| (table.name.to_uppercase(), i))
+        .collect();
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); tables.len()];
+    let mut pending_parents = vec![0usize; tables.len()];
+    for (child, table) in tables.iter().enumerate() {
+        let mut parents = BTreeSet::new();
+        for fk in &table.foreign_keys {
+            let Some(bare) = same_schema_table(schema, &fk.referenced_table) else {
+                continue;
+            };
+            if let Some(&parent) = positions.get(&bare.to_uppercase()) {
+                if parent != child {
+                    parents.insert(parent);
+                }
+            }
+        }
+        pending_parents[child] = parents.len();
+        for parent in parents {
+            children[parent].push(child);
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = (0..tables.len())
+        .filter(|&i| pending_parents[i] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(tables.len());
+    while let Some(next) = ready.pop_first() {
+        order.push(next);
+        for &child in &children[next] {
+            pending_parents[child] -= 1;
+            if pending_parents[child] == 0 {
+                ready.insert(child);
+            }
+        }
+    }
+
+    let mut cyclic = Vec::new();
+    for (i, table) in tables.iter().enumerate() {
+        if pending_parents[i] > 0 {
+            order.push(i);
+            cyclic.push(table.name.clone());
+        }
+    }
+
+    LoadOrder { order, cyclic }
+}
+
+/// Name of a foreign key's referenced table, as the catalog stores it, when it lives in
+/// `schema`; unqualified references belong to the referencing table's schema.
+pub fn same_schema_table(schema: &str, referenced_table: &str) -> Option<String> {
+    match referenced_table.split_once('.') {
+        Some((owner, name)) if owner.eq_ignore_ascii_case(schema) => Some(name.to_string()),
+        Some(_) => None,
+        None => Some(referenced_table.to_string()),
+    }
+}
+
+/// Completes a table selection with the tables of `schema` that it references through foreign
+/// keys, transitively, so the exported script does not fail on a missing parent.
+///
+/// Missing parents are loaded with `load` and appended to `tables`; the names of the added tables
+/// are returned in the order they were found.
+pub fn add_referenced_tables(
+    schema: &str,
+    tables: &mut Vec<TableDetails>,
+    mut load: impl FnMut(&str) -> Result<TableDetails>,
+) -> Result<Vec<String>> {
+    let mut known: HashSet<String> = tables.iter().map(|t| t.name.to_uppercase()).collect();
+    let mut added = Vec::new();
+    let mut next = 0;
+    while next < tables.len() {
+        let missing: Vec<String> = tables[next]
+            .foreign_keys
+            .iter()
+            .filter_map(|fk| same_schema_table(schema, &fk.referenced_table))
+            .filter(|name| known.insert(name.to_uppercase()))
+            .collect();
+        for name in missing {
+            tables.push(load(&name)?);
+            added.push(name);
+        }
+        next += 1;
+    }
+    Ok(added)
+}
+
+/// Dependency report for `details` (a table of `schema`) given the foreign keys that reference
+/// it. Self-references are left out of both directions.
+pub fn table_dependencies(
+    schema: &str,
+    details: &TableDetails,
+    referenced_by: Vec<TableReference>,
+) -> TableDependencies {
+    let is_self = |owner: &str, table: &str| {
+        owner.eq_ignore_ascii_case(schema) && table.eq_ignore_ascii_case(&details.name)
+    };
+
+    let references = details
+        .foreign_keys
+        .iter()
+        .map(|fk| {
+            let (owner, table) = fk
+                .referenced_table
+                .split_once('.')
+                .unwrap_or((schema, fk.referenced_table.as_str()));
+            TableReference {
+                schema: owner.to_string(),
+                table: table.to_string(),
+                constraint: fk.name.clone(),
+            }
+        })
+        .filter(|reference| !is_self(&reference.schema, &reference.table))
+        .collect();
+
+    let mut sequences: Vec<String> = referenced_sequence_names(std::slice::from_ref(details))
+        .into_iter()
+        .collect();
+    sequences.sort();
+
+    TableDependencies {
+        table: details.name.clone(),
+        references,
+        referenced_by: referenced_by
+            .into_iter()
+            .filter(|reference| !is_self(&reference.schema, &reference.table))
+            .collect(),
+        triggers: details.triggers.iter().map(|t| t.name.clone()).collect(),
+        sequences,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_referenced_tables, load_order, table_dependencies};
+    use crate::models::{ForeignKey, TableDetails, TableReference};
+
+    fn table(name: &str, references: &[&str]) -> TableDetails {
+        TableDetails {
+            foreign_keys: references
+                .iter()
+                .map(|referenced| ForeignKey {
+                    name: format!("FK_{}_{}", name, referenced.replace('.', "_")),
+                    columns: vec!["REF_ID".to_string()],
+                    referenced_table: referenced.to_string(),
+                    referenced_columns: vec!["ID".to_string()],
+                    delete_rule: None,
+                    update_rule: None,
+                })
+                .collect(),
+            ..TableDetails::named(name, Vec::new(), &[])
+        }
+    }
+
+    #[test]
+    fn parents_load_before_children() {
+        let tables = vec![
+            table("ORDER_ITEMS", &["APP.ORDERS", "APP.PRODUCTS"]),
+            table("ORDERS", &["APP.CUSTOMERS"]),
+            table("PRODUCTS", &[]),
+            table("CUSTOMERS", &[]),
+        ];
+
+        let order = load_order("APP", &tables);
+        let names: Vec<&str> = order
+            .order
+            .iter()
+            .map(|&i| tables[i].name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["PRODUCTS", "CUSTOMERS", "ORDERS", "ORDER_ITEMS"]
+        );
+        assert!(order.cyclic.is_empty());
+    }
+
+    #[test]
+    fn ignores_self_and_external_references_and_reports_cycles() {
+        let tables = vec![
+            table("EMPLOYEES", &["APP.EMPLOYEES", "HR.DEPARTMENTS"]),
+            table("A", &["APP.B"]),
+            table("B", &["APP.A"]),
+        ];
+
+        let order = load_order("app", &tables);
+        assert_eq!(order.order, vec![0, 1, 2]);
+        assert_eq!(order.cyclic, vec!["A".to_string(), "B".to_string()]);
+    }
+
+    #[test]
+    fn dependency_report_lists_both_directions_without_self_references() {
+        let mut orders = table("ORDERS", &["APP.CUSTOMERS", "AUTH.USERS", "APP.ORDERS"]);
+        orders.columns.push(crate::models::Column {
+            nullable: false,
+            default_value: Some("APP.SEQ_ORDERS.NEXTVAL".to_string()),
+            ..crate::models::Column::named("ID", "INT")
+        });
+        let reference = |schema: &str, table: &str| TableReference {
+            schema: schema.to_string(),
+            table: table.to_string(),
+            constraint: format!("FK_{}", table),
+        };
+
+        let report = table_dependencies(
+            "APP",
+            &orders,
+            vec![reference("APP", "ORDER_ITEMS"), reference("APP", "ORDERS")],
+        );
+
+        let referenced: Vec<_> = report
+            .references
+            .iter()
+            .map(|r| format!("{}.{}", r.schema, r.table))
+            .collect();
+        assert_eq!(referenced, ["APP.CUSTOMERS", "AUTH.USERS"]);
+        assert_eq!(report.referenced_by, [reference("APP", "ORDER_ITEMS")]);
+        assert_eq!(report.sequences, ["SEQ_ORDERS"]);
+    }
+
+    #[test]
+    fn missing_parents_are_added_transitively() {
+        let mut tables = vec![
+            table("ORDER_ITEMS", &["APP.ORDERS", "APP.PRODUCTS", "AUTH.USERS"]),
+            table("PRODUCTS", &[]),
+        ];
+        let mut loaded = Vec::new();
+        let added = add_referenced_tables("app", &mut tables, |name| {
+            loaded.push(name.to_string());
+            Ok(match name {
+                "ORDERS" => table("ORDERS", &["CUSTOMERS", "APP.ORDERS"]),
+                _ => table(name, &["APP.ORDER_ITEMS"]),
+            })
+        })
+        .unwrap();
+
+        assert_eq!(added, ["ORDERS", "CUSTOMERS"]);
+        assert_eq!(loaded, added);
+        let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["ORDER_ITEMS", "PRODUCTS", "ORDERS", "CUSTOMERS"]);
+    }
+
+    #[test]
+    fn mixed_case_parents_keep_their_catalog_spelling() {
+        let mut tables = vec![table("order_Items", &["Sales.Orders", "Sales.order_Items"])];
+        let added =
+            add_referenced_tables("Sales", &mut tables, |name| Ok(table(name, &[]))).unwrap();
+        assert_eq!(added, ["Orders"]);
+
+        let order = load_order("Sales", &tables);
+        assert_eq!(order.order, [1, 0]);
+        assert!(order.cyclic.is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+use crate::models::TableDetails;
+
+/// Database a DDL script is written for, named by `export_compat`. Column defaults are written
+/// through the dialect's translation table so server functions such as `SYSDATE` load there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    #[default]
+    Dm8,
+    Oracle,
+    PostgreSql,
+    MySql,
+}
+
+impl Dialect {
+    /// `dm8`, `oracle`, `postgresql` (or `postgres`/`pg`) and `mysql`, in any case; other
+    /// compat modes name trigger terminators and yield `None`.
+    pub fn from_compat(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "dm8" | "dm" => Some(Self::Dm8),
+            "oracle" => Some(Self::Oracle),
+            "postgresql" | "postgres" | "pg" => Some(Self::PostgreSql),
+            "mysql" => Some(Self::MySql),
+            _ => None,
+        }
+    }
+
+    /// DM8 default expression -> equivalent in this dialect. Entries are matched as whole
+    /// words, case-insensitively; DM8 itself needs no translation.
+    fn default_translations(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Dm8 => &[],
+            Self::Oracle => &[
+                ("GETDATE()", "SYSDATE"),
+                ("NOW()", "SYSDATE"),
+                ("CURDATE()", "TRUNC(SYSDATE)"),
+                ("CURRENT USER", "USER"),
+                ("CURRENT SCHEMA", "SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')"),
+                ("CURRENT_SCHEMA", "SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')"),
+                ("TRUE", "1"),
+                ("FALSE", "0"),
+            ],
+            Self::PostgreSql => &[
+                ("SYSTIMESTAMP", "CURRENT_TIMESTAMP"),
+                ("SYSDATE", "CURRENT_TIMESTAMP"),
+                ("GETDATE()", "CURRENT_TIMESTAMP"),
+                ("CURDATE()", "CURRENT_DATE"),
+                ("SYS_GUID()", "gen_random_uuid()"),
+                ("CURRENT USER", "CURRENT_USER"),
+                ("SESSION USER", "SESSION_USER"),
+                ("CURRENT SCHEMA", "CURRENT_SCHEMA"),
+                ("USER", "CURRENT_USER"),
+                ("DBTIMEZONE", "current_setting('TimeZone')"),
+                ("SESSIONTIMEZONE", "current_setting('TimeZone')"),
+            ],
+            Self::MySql => &[
+                ("SYSTIMESTAMP", "CURRENT_TIMESTAMP"),
+                ("SYSDATE", "CURRENT_TIMESTAMP"),
+                ("GETDATE()", "CURRENT_TIMESTAMP"),
+                ("SYS_GUID()", "(UUID())"),
+                ("CURRENT USER", "CURRENT_USER"),
+                ("SESSION USER", "(SESSION_USER())"),
+                ("USER", "CURRENT_USER"),
+                ("CURRENT SCHEMA", "(DATABASE())"),
+                ("CURRENT_SCHEMA", "(DATABASE())"),
+            ],
+        }
+    }
+
+    /// Spelling of a BIT/BOOLEAN value. DM8 and Oracle store them as `BIT`/`NUMBER(1)`, MySQL
+    /// reads `TRUE`/`FALSE` as `1`/`0` anyway, and PostgreSQL rejects integers for `boolean`.
+    pub fn boolean_literal(self, value: bool) -> &'static str {
+        match (self, value) {
+            (Self::PostgreSql, true) => "TRUE",
+            (Self::PostgreSql, false) => "FALSE",
+            (_, true) => "1",
+            (_, false) => "0",
+        }
+    }
+
+    /// Rewrites the function and keyword calls of a default expression for this dialect.
+    /// String literals and quoted identifiers are copied unchanged.
+    pub fn translate_default(self, expr: &str) -> String {
+        let translations = self.default_translations();
+        if translations.is_empty() {
+            return expr.to_string();
+        }
+
+        let upper = expr.to_ascii_uppercase();
+        let mut out = String::with_capacity(expr.len());
+        let mut quote: Option<char> = None;
+        let mut skip_to = 0;
+        let mut prev: Option<char> = None;
+        for (i, c) in expr.char_indices() {
+            if i < skip_to {
+                prev = Some(c);
+                continue;
+            }
+            if let Some(open) = quote {
+                if c == open {
+                    quote = None;
+                }
+            } else if c == '\'' || c == '"' {
+                quote = Some(c);
+            } else if !prev.is_some_and(|p| is_word_char(p) || p == '.') {
+                let matched = translations.iter().find(|(from, _)| {
+                    let end = i + from.len();
+                    upper[i..].starts_with(from)
+                        && !(from.ends_with(is_word_char) && expr[end..].starts_with(is_word_char))
+                });
+                if let Some((from, to)) = matched {
+                    out.push_str(to);
+                    skip_to = i + from.len();
+                    prev = Some(c);
+                    continue;
+                }
+            }
+            out.push(c);
+            prev = Some(c);
+        }
+        out
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$' || c == '#'
+}
+
+/// Translates every column default of `table` for `dialect`.
+pub fn translate_defaults(table: &mut TableDetails, dialect: Dialect) {
+    for column in &mut table.columns {
+        if let Some(default) = column.default_value.as_mut() {
+            *default = dialect.translate_default(default);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dialect;
+
+    #[test]
+    fn compat_names_select_dialects() {
+        assert_eq!(
+            Dialect::from_compat(" PostgreSQL "),
+            Some(Dialect::PostgreSql)
+        );
+        assert_eq!(Dialect::from_compat("pg"), Some(Dialect::PostgreSql));
+        assert_eq!(Dialect::from_compat("MySQL"), Some(Dialect::MySql));
+        assert_eq!(Dialect::from_compat("oracle"), Some(Dialect::Oracle));
+        assert_eq!(Dialect::from_compat("dm8"), Some(Dialect::Dm8));
+        assert_eq!(Dialect::from_compat("datagrip"), None);
+    }
+
+    #[test]
+    fn dm8_keeps_defaults_unchanged() {
+        for expr in ["SYSDATE", "USER", "SYS_GUID()", "TRUE", "'SYSDATE'"] {
+            assert_eq!(Dialect::Dm8.translate_default(expr), expr);
+        }
+    }
+
+    #[test]
+    fn oracle_translates_non_oracle_functions() {
+        let oracle = Dialect::Oracle;
+        assert_eq!(oracle.translate_default("GETDATE()"), "SYSDATE");
+        assert_eq!(oracle.translate_default("now()"), "SYSDATE");
+        assert_eq!(oracle.translate_default("CURDATE()"), "TRUNC(SYSDATE)");
+        assert_eq!(oracle.translate_default("CURRENT USER"), "USER");
+        assert_eq!(
+            oracle.translate_default("CURRENT_SCHEMA"),
+            "SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')"
+        );
+        assert_eq!(oracle.translate_default("TRUE"), "1");
+        assert_eq!(oracle.translate_default("false"), "0");
+        assert_eq!(oracle.translate_default("SYSDATE + 1"), "SYSDATE + 1");
+        assert_eq!(oracle.translate_default("TRUE_FLAG"), "TRUE_FLAG");
+    }
+
+    #[test]
+    fn postgresql_translates_dm8_functions() {
+        let pg = Dialect::PostgreSql;
+        assert_eq!(pg.translate_default("SYSDATE"), "CURRENT_TIMESTAMP");
+        assert_eq!(pg.translate_default("sysdate - 1"), "CURRENT_TIMESTAMP - 1");
+        assert_eq!(pg.translate_default("SYSTIMESTAMP"), "CURRENT_TIMESTAMP");
+        assert_eq!(pg.translate_default("GETDATE()"), "CURRENT_TIMESTAMP");
+        assert_eq!(pg.translate_default("CURDATE()"), "CURRENT_DATE");
+        assert_eq!(pg.translate_default("SYS_GUID()"), "gen_random_uuid()");
+        assert_eq!(pg.translate_default("USER"), "CURRENT_USER");
+        assert_eq!(pg.translate_default("CURRENT USER"), "CURRENT_USER");
+        assert_eq!(pg.translate_default("SESSION USER"), "SESSION_USER");
+        assert_eq!(pg.translate_default("SESSION_USER"), "SESSION_USER");
+        assert_eq!(pg.translate_default("CURRENT SCHEMA"), "CURRENT_SCHEMA");
+        assert_eq!(
+            pg.translate_default("DBTIMEZONE"),
+            "current_setting('TimeZone')"
+        );
+        assert_eq!(
+            pg.translate_default("CURRENT_TIMESTAMP"),
+            "CURRENT_TIMESTAMP"
+        );
+        assert_eq!(pg.translate_default("NOW()"), "NOW()");
+    }
+
+    #[test]
+    fn mysql_translates_dm8_functions() {
+        let mysql = Dialect::MySql;
+        assert_eq!(mysql.translate_default("SYSDATE"), "CURRENT_TIMESTAMP");
+        assert_eq!(mysql.translate_default("SYSTIMESTAMP"), "CURRENT_TIMESTAMP");
+        assert_eq!(mysql.translate_default("getdate()"), "CURRENT_TIMESTAMP");
+        assert_eq!(mysql.translate_default("SYS_GUID()"), "(UUID())");
+        assert_eq!(mysql.translate_default("USER"), "CURRENT_USER");
+        assert_eq!(mysql.translate_default("SESSION USER"), "(SESSION_USER())");
+        assert_eq!(mysql.translate_default("CURRENT_SCHEMA"), "(DATABASE())");
+        assert_eq!(mysql.translate_default("CURRENT_DATE"), "CURRENT_DATE");
+    }
+
+    #[test]
+    fn boolean_literals_follow_dialect() {
+        assert_eq!(Dialect::Dm8.boolean_literal(true), "1");
+        assert_eq!(Dialect::Dm8.boolean_literal(false), "0");
+        assert_eq!(Dialect::Oracle.boolean_literal(true), "1");
+        assert_eq!(Dialect::MySql.boolean_literal(false), "0");
+        assert_eq!(Dialect::PostgreSql.boolean_literal(true), "TRUE");
+        assert_eq!(Dialect::PostgreSql.boolean_literal(false), "FALSE");
+    }
+
+    #[test]
+    fn translation_skips_literals_identifiers_and_qualified_names() {
+        let pg = Dialect::PostgreSql;
+        assert_eq!(pg.translate_default("'SYSDATE'"), "'SYSDATE'");
+        assert_eq!(pg.translate_default("'it''s USER'"), "'it''s USER'");
+        assert_eq!(pg.translate_default("\"USER\""), "\"USER\"");
+        assert_eq!(pg.translate_default("APP.USER"), "APP.USER");
+        assert_eq!(pg.translate_default("USERNAME"), "USERNAME");
+        assert_eq!(
+            pg.translate_default("NVL(USER, 'x') || SYSDATE"),
+            "NVL(CURRENT_USER, 'x') || CURRENT_TIMESTAMP"
+        );
+    }
+}
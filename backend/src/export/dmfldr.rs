@@ -0,0 +1,323 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{ensure, Context, Result};
+use chrono::Utc;
+
+use crate::db::retry::RetryingConnection;
+use crate::db::schema::catalog_name;
+use crate::export::binary::table_file_name;
+use crate::export::data::{
+    load_table_metadata, quote_identifier, stream_table_rows, DataExportOptions,
+};
+use crate::export::dependency::load_order;
+use crate::export::job::JobContext;
+use crate::export::manifest::{write_manifest, ChecksumWriter, MANIFEST_FILE};
+use crate::export::renames::renamed_table;
+use crate::export::subset::consistent_subset;
+use crate::models::{Column, DataFormat, ExportManifest, IssueCode, JobEventKind, ManifestTable};
+
+/// Field separator when the request does not set `dmfldr_delimiter`.
+pub const DEFAULT_FIELD_DELIMITER: &str = "|";
+/// Text written for NULL when the request does not set `dmfldr_null_marker`.
+pub const DEFAULT_NULL_MARKER: &str = "\\N";
+/// Encloses values that contain the delimiter, a quote, a line break or the null marker.
+const ENCLOSURE: char = '"';
+
+/// How the data files of a dmfldr export separate fields and mark NULL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelimitedFormat {
+    pub delimiter: String,
+    pub null_marker: String,
+}
+
+impl Default for DelimitedFormat {
+    fn default() -> Self {
+        Self {
+            delimiter: DEFAULT_FIELD_DELIMITER.to_string(),
+            null_marker: DEFAULT_NULL_MARKER.to_string(),
+        }
+    }
+}
+
+impl DelimitedFormat {
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.delimiter.is_empty(),
+            "dmfldr_delimiter must not be empty"
+        );
+        ensure!(
+            !self.delimiter.contains([ENCLOSURE, '\n', '\r', '\'']),
+            "dmfldr_delimiter must not contain quotes or line breaks"
+        );
+        ensure!(
+            !self.null_marker.is_empty(),
+            "dmfldr_null_marker must not be empty"
+        );
+        ensure!(
+            !self.null_marker.contains(&self.delimiter)
+                && !self.null_marker.contains([ENCLOSURE, '\n', '\r', '\'']),
+            "dmfldr_null_marker must not contain the delimiter, quotes or line breaks"
+        );
+        Ok(())
+    }
+
+    /// One field of a data file. Values that could be mistaken for a separator, a record end or
+    /// NULL are enclosed in double quotes, with embedded quotes doubled.
+    fn field(&self, value: Option<&str>) -> String {
+        let Some(value) = value else {
+            return self.null_marker.clone();
+        };
+        if value.contains(&self.delimiter)
+            || value.contains([ENCLOSURE, '\n', '\r'])
+            || value == self.null_marker
+        {
+            let doubled = value.replace(ENCLOSURE, "\"\"");
+            format!("{}{}{}", ENCLOSURE, doubled, ENCLOSURE)
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn record(&self, row: &[Option<String>]) -> String {
+        row.iter()
+            .map(|value| self.field(value.as_deref()))
+            .collect::<Vec<_>>()
+            .join(&self.delimiter)
+    }
+}
+
+/// Whether a character column counts its length in characters rather than bytes
+/// (`CHAR_USED = 'C'`).
+fn uses_char_semantics(column: &Column) -> bool {
+    column
+        .char_semantics
+        .as_deref()
+        .map(str::to_uppercase)
+        .is_some_and(|cs| cs == "C" || cs.contains("CHAR"))
+}
+
+/// Control file loading `data_file` into `table`: fields in column order, the configured
+/// separator and null marker, and UTF-8 input so lengths of character-semantics columns are
+/// checked in characters after conversion to the server character set.
+fn control_file(
+    table: &str,
+    columns: &[Column],
+    data_file: &str,
+    format: &DelimitedFormat,
+) -> String {
+    let stem = data_file
+        .rsplit_once('.')
+        .map_or(data_file, |(stem, _)| stem);
+    let mut ctl = String::new();
+    ctl.push_str(&format!("-- dmfldr control file for {}\n", table));
+    let char_columns: Vec<&str> = columns
+        .iter()
+        .filter(|column| uses_char_semantics(column))
+        .map(|column| column.name.as_str())
+        .collect();
+    if !char_columns.is_empty() {
+        ctl.push_str(&format!(
+            "-- Character-semantics columns (length in characters): {}\n",
+            char_columns.join(", ")
+        ));
+    }
+    ctl.push_str("OPTIONS\n(\n");
+    ctl.push_str("  CHARACTER_CODE = 'UTF-8'\n");
+    ctl.push_str("  NULL_MODE = TRUE\n");
+    ctl.push_str(&format!("  NULL_STR = '{}'\n", format.null_marker));
+    ctl.push_str(")\n");
+    ctl.push_str("LOAD DATA\n");
+    ctl.push_str(&format!("INFILE '{}'\n", data_file));
+    ctl.push_str(&format!("BADFILE '{}.bad'\n", stem));
+    ctl.push_str("APPEND\n");
+    ctl.push_str(&format!("INTO TABLE {}\n", table));
+    ctl.push_str(&format!(
+        "FIELDS '{}' OPTIONALLY ENCLOSED BY '{}'\n",
+        format.delimiter, ENCLOSURE
+    ));
+    ctl.push_str("(\n");
+    let names: Vec<String> = columns
+        .iter()
+        .map(|column| format!("  {}", quote_identifier(&column.name)))
+        .collect();
+    ctl.push_str(&names.join(",\n"));
+    ctl.push_str("\n)\n");
+    ctl
+}
+
+/// Writes one delimiter-separated `NNNN_<table>.txt` data file and a matching `.ctl` control
+/// file per table into `output_dir`, in foreign key load order, plus a `manifest.json` with
+/// row counts and data file checksums. Each table is loaded with
+/// `dmfldr USERID=... CONTROL='NNNN_<table>.ctl'` run from that directory.
+#[allow(clippy::too_many_arguments)]
+pub fn export_schema_dmfldr(
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
+    source_schema: &str,
+    target_schema: &str,
+    tables: &[String],
+    output_dir: &Path,
+    options: &DataExportOptions,
+    format: &DelimitedFormat,
+) -> Result<(usize, PathBuf)> {
+    format.validate()?;
+    let source_schema_name = catalog_name(source_schema, options.preserve_case);
+    let target_schema_name = catalog_name(target_schema, options.preserve_case);
+    let (table_details_list, _) =
+        load_table_metadata(session, job, &source_schema_name, tables, options)?;
+    let row_filters = consistent_subset(job, &source_schema_name, &table_details_list, options)?;
+
+    let load = load_order(&source_schema_name, &table_details_list);
+    if !load.cyclic.is_empty() {
+        job.warn(
+            IssueCode::ForeignKeyCycle,
+            None,
+            format!(
+                "Foreign key cycle between {}; these tables keep their requested order",
+                load.cyclic.join(", ")
+            ),
+        );
+    }
+
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create export directory {}", output_dir.display()))?;
+
+    let mut exported_total = 0;
+    let mut manifest_tables = Vec::with_capacity(table_details_list.len());
+    for (position, &index) in load.order.iter().enumerate() {
+        let details = &table_details_list[index];
+        let source_table = details.name.clone();
+        let target_table = renamed_table(&options.table_renames, &source_table);
+        let data_file = table_file_name(position + 1, &target_table, "txt");
+        let ctl_file = table_file_name(position + 1, &target_table, "ctl");
+        let target_ident = quote_identifier(&format!("{}.{}", target_schema_name, target_table));
+
+        job.record(JobEventKind::TableStarted, Some(&source_table), None);
+        let ctl_path = output_dir.join(&ctl_file);
+        fs::write(
+            &ctl_path,
+            control_file(&target_ident, &details.columns, &data_file, format),
+        )
+        .with_context(|| format!("Failed to write control file {}", ctl_path.display()))?;
+
+        let path = output_dir.join(&data_file);
+        let mut file = BufWriter::new(
+            File::create(&path)
+                .with_context(|| format!("Failed to create data file {}", path.display()))?,
+        );
+        let mut checksum_writer = ChecksumWriter::new(&mut file);
+        let (rows, stats) = stream_table_rows(
+            session,
+            job,
+            &source_schema_name,
+            details,
+            row_filters.get(&details.name).map(String::as_str),
+            options,
+            &mut |rows| {
+                for row in &rows {
+                    writeln!(checksum_writer, "{}", format.record(row))?;
+                }
+                Ok(())
+            },
+        )
+        .with_context(|| format!("Failed to export table '{}' for dmfldr", source_table))?;
+        let checksum = checksum_writer.finish();
+        file.flush()
+            .with_context(|| format!("Failed to flush data file {}", path.display()))?;
+
+        manifest_tables.push(ManifestTable {
+            table: target_table,
+            source_table: source_table.clone(),
+            rows,
+            checksum,
+            file: Some(data_file),
+            columns: Vec::new(),
+        });
+        job.record(
+            JobEventKind::TableFinished,
+            Some(&source_table),
+            Some(format!("{} rows", rows)),
+        );
+        job.memory.push(stats);
+        exported_total += rows;
+    }
+
+    let manifest = ExportManifest {
+        job_id: job.job_id.clone(),
+        generated_at: Utc::now().to_rfc3339(),
+        source_schema: source_schema_name,
+        target_schema: target_schema_name,
+        format: DataFormat::Dmfldr,
+        data_file: String::new(),
+        row_limit: None,
+        sample: options.sample.clone(),
+        tables: manifest_tables,
+    };
+    let manifest_path = output_dir.join(MANIFEST_FILE);
+    write_manifest(&manifest_path, &manifest)?;
+    Ok((exported_total, manifest_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{control_file, DelimitedFormat};
+    use crate::models::Column;
+
+    fn column(name: &str, char_semantics: Option<&str>) -> Column {
+        Column {
+            length: Some(20),
+            char_semantics: char_semantics.map(str::to_string),
+            ..Column::named(name, "VARCHAR")
+        }
+    }
+
+    #[test]
+    fn records_mark_nulls_and_enclose_ambiguous_values() {
+        let format = DelimitedFormat::default();
+        let row = vec![
+            Some("1".to_string()),
+            None,
+            Some("a|b".to_string()),
+            Some("say \"hi\"\nbye".to_string()),
+            Some("\\N".to_string()),
+            Some(String::new()),
+        ];
+        assert_eq!(
+            format.record(&row),
+            "1|\\N|\"a|b\"|\"say \"\"hi\"\"\nbye\"|\"\\N\"|"
+        );
+    }
+
+    #[test]
+    fn rejects_unusable_delimiters() {
+        let format = |delimiter: &str, null_marker: &str| DelimitedFormat {
+            delimiter: delimiter.to_string(),
+            null_marker: null_marker.to_string(),
+        };
+        assert!(format("\"", "\\N").validate().is_err());
+        assert!(format(",", "N,A").validate().is_err());
+        format(",", "NULL").validate().unwrap();
+    }
+
+    #[test]
+    fn control_file_lists_columns_in_order() {
+        let columns = vec![column("ID", None), column("NAME", Some("C"))];
+        let ctl = control_file(
+            "\"APP\".\"ORDERS\"",
+            &columns,
+            "0001_ORDERS.txt",
+            &DelimitedFormat::default(),
+        );
+        assert!(ctl.contains("INFILE '0001_ORDERS.txt'\nBADFILE '0001_ORDERS.bad'\n"));
+        assert!(ctl.contains("NULL_STR = '\\N'"));
+        assert!(
+            ctl.contains("INTO TABLE \"APP\".\"ORDERS\"\nFIELDS '|' OPTIONALLY ENCLOSED BY '\"'\n")
+        );
+        assert!(ctl.ends_with("(\n  \"ID\",\n  \"NAME\"\n)\n"));
+        assert!(ctl.contains("length in characters): NAME\n"));
+    }
+}
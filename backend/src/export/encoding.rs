@@ -0,0 +1,274 @@
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use encoding_rs::{EncoderResult, GBK};
+
+use crate::export::job::JobContext;
+use crate::models::{ExportEncoding, IssueCode, LineEnding};
+
+/// Buffered writer for a generated SQL file: line endings are applied to the UTF-8 text, which
+/// is then transcoded into the file encoding.
+pub type ScriptWriter = BufWriter<LineEndingWriter<EncodedWriter<File>>>;
+
+pub fn script_writer(
+    file: File,
+    encoding: ExportEncoding,
+    line_ending: LineEnding,
+) -> ScriptWriter {
+    BufWriter::new(LineEndingWriter::new(
+        EncodedWriter::new(file, encoding),
+        line_ending,
+    ))
+}
+
+/// Characters a finished script writer replaced because its encoding could not represent them.
+pub fn unmappable_count(writer: &ScriptWriter) -> usize {
+    writer.get_ref().get_ref().unmappable()
+}
+
+/// Transcodes the UTF-8 text written to it into the export encoding.
+///
+/// Writes may split a multi-byte character (e.g. at `BufWriter` boundaries); the incomplete tail
+/// is held back until the rest arrives. Characters GBK cannot represent are written as `?` and
+/// counted so the export can warn about them.
+pub struct EncodedWriter<W: Write> {
+    inner: W,
+    encoding: ExportEncoding,
+    pending: Vec<u8>,
+    unmappable: usize,
+}
+
+impl<W: Write> EncodedWriter<W> {
+    pub fn new(inner: W, encoding: ExportEncoding) -> Self {
+        Self {
+            inner,
+            encoding,
+            pending: Vec::new(),
+            unmappable: 0,
+        }
+    }
+
+    /// Characters replaced by `?` because the target encoding has no mapping for them.
+    pub fn unmappable(&self) -> usize {
+        self.unmappable
+    }
+
+    fn encode_pending(&mut self) -> io::Result<()> {
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+        let text = std::str::from_utf8(&self.pending[..valid])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let bytes = encode_gbk(text, &mut self.unmappable);
+        self.inner.write_all(&bytes)?;
+        self.pending.drain(..valid);
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for EncodedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.encoding {
+            ExportEncoding::Utf8 => self.inner.write(buf),
+            ExportEncoding::Gbk => {
+                self.pending.extend_from_slice(buf);
+                self.encode_pending()?;
+                Ok(buf.len())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.pending.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "export output ended in the middle of a UTF-8 character",
+            ));
+        }
+        self.inner.flush()
+    }
+}
+
+fn encode_gbk(text: &str, unmappable: &mut usize) -> Vec<u8> {
+    let mut encoder = GBK.new_encoder();
+    let mut out = Vec::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let needed = encoder
+            .max_buffer_length_from_utf8_without_replacement(rest.len())
+            .unwrap_or(rest.len() * 2);
+        out.reserve(needed);
+        let (result, read) =
+            encoder.encode_from_utf8_to_vec_without_replacement(rest, &mut out, true);
+        rest = &rest[read..];
+        match result {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(_) => {
+                out.push(b'?');
+                *unmappable += 1;
+            }
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlState {
+    Code,
+    Literal,
+    LineComment,
+    BlockComment,
+}
+
+/// Rewrites `\n` line endings as `\r\n` when CRLF output is requested.
+///
+/// Newlines inside single-quoted string literals are data and are left alone; a tiny SQL lexer
+/// (literals, `--` and `/* */` comments) tracks where they are across write calls. Existing
+/// `\r\n` pairs are not doubled.
+pub struct LineEndingWriter<W: Write> {
+    inner: W,
+    line_ending: LineEnding,
+    state: SqlState,
+    previous: u8,
+}
+
+impl<W: Write> LineEndingWriter<W> {
+    pub fn new(inner: W, line_ending: LineEnding) -> Self {
+        Self {
+            inner,
+            line_ending,
+            state: SqlState::Code,
+            previous: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<W: Write> Write for LineEndingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.line_ending == LineEnding::Lf {
+            return self.inner.write(buf);
+        }
+
+        let mut out = Vec::with_capacity(buf.len() + buf.len() / 16);
+        for &byte in buf {
+            let next = match (self.state, byte) {
+                (SqlState::Code, b'\'') => SqlState::Literal,
+                (SqlState::Code, b'-') if self.previous == b'-' => SqlState::LineComment,
+                (SqlState::Code, b'*') if self.previous == b'/' => SqlState::BlockComment,
+                (SqlState::Literal, b'\'') => SqlState::Code,
+                (SqlState::LineComment, b'\n') => SqlState::Code,
+                (SqlState::BlockComment, b'/') if self.previous == b'*' => SqlState::Code,
+                (state, _) => state,
+            };
+            if byte == b'\n' && next != SqlState::Literal && self.previous != b'\r' {
+                out.push(b'\r');
+            }
+            out.push(byte);
+            // The byte that opened or closed a comment must not pair up with the next one.
+            self.previous = if next == self.state { byte } else { 0 };
+            self.state = next;
+        }
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Decodes one line of an export file written in `encoding`; `None` when the bytes are invalid.
+pub fn decode_line(line: &[u8], encoding: ExportEncoding) -> Option<Cow<'_, str>> {
+    match encoding {
+        ExportEncoding::Utf8 => std::str::from_utf8(line).ok().map(Cow::Borrowed),
+        ExportEncoding::Gbk => GBK.decode_without_bom_handling_and_without_replacement(line),
+    }
+}
+
+/// Records a job warning when characters had to be replaced while transcoding `path`.
+pub fn warn_unmappable(job: &mut JobContext, path: &Path, encoding: ExportEncoding, count: usize) {
+    if count == 0 {
+        return;
+    }
+    job.warn(
+        IssueCode::EncodingUnmappable,
+        None,
+        format!(
+            "{} character(s) in {} cannot be represented in {} and were written as '?'",
+            count,
+            path.display(),
+            encoding.char_code()
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_line, EncodedWriter, LineEndingWriter};
+    use crate::models::{ExportEncoding, LineEnding};
+    use std::io::Write;
+
+    #[test]
+    fn gbk_writer_transcodes_split_characters() {
+        let mut writer = EncodedWriter::new(Vec::new(), ExportEncoding::Gbk);
+        let text = "INSERT INTO t VALUES ('你好');\n".as_bytes();
+        // Split inside the three-byte UTF-8 sequence of the first character.
+        let split = text.iter().position(|&b| b >= 0x80).unwrap() + 1;
+        writer.write_all(&text[..split]).unwrap();
+        writer.write_all(&text[split..]).unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(writer.unmappable(), 0);
+        let bytes = writer.inner.clone();
+        assert!(bytes.windows(4).any(|w| w == [0xC4, 0xE3, 0xBA, 0xC3]));
+        assert_eq!(
+            decode_line(&bytes, ExportEncoding::Gbk).as_deref(),
+            Some("INSERT INTO t VALUES ('你好');\n")
+        );
+    }
+
+    #[test]
+    fn gbk_writer_replaces_unmappable_characters() {
+        let mut writer = EncodedWriter::new(Vec::new(), ExportEncoding::Gbk);
+        writer.write_all("a😀b".as_bytes()).unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(writer.inner, b"a?b");
+        assert_eq!(writer.unmappable(), 1);
+    }
+
+    #[test]
+    fn crlf_writer_keeps_newlines_inside_literals() {
+        let mut writer = LineEndingWriter::new(Vec::new(), LineEnding::Crlf);
+        writer
+            .write_all(b"-- it's a header\nINSERT INTO t VALUES ('a\nb', 'it''s');\r\n")
+            .unwrap();
+        writer
+            .write_all(b"/* don't */\nBEGIN\n  NULL;\nEND;\n")
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.inner).unwrap(),
+            "-- it's a header\r\nINSERT INTO t VALUES ('a\nb', 'it''s');\r\n\
+             /* don't */\r\nBEGIN\r\n  NULL;\r\nEND;\r\n"
+        );
+    }
+
+    #[test]
+    fn utf8_writer_passes_bytes_through() {
+        let mut writer = EncodedWriter::new(Vec::new(), ExportEncoding::Utf8);
+        writer.write_all("名称".as_bytes()).unwrap();
+        assert_eq!(writer.inner, "名称".as_bytes());
+    }
+}
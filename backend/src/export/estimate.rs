@@ -0,0 +1,140 @@
+//! Size estimate behind `POST /api/export/estimate`. Analyzed tables are estimated from their
+//! optimizer statistics (`NUM_ROWS`, `AVG_ROW_LEN`); the others from the row count of a
+//! `SAMPLE` scaled up to the table (an exact `COUNT(*)` with `exact_counts`) and the average
+//! length of a sample of rows. Durations assume a fixed throughput and are only meant to tell a
+//! coffee break from an overnight run.
+
+use anyhow::Result;
+use odbc_api::Connection;
+
+use crate::db::schema::{
+    fetch_row_count, fetch_sampled_row_count, fetch_table_statistics, sample_row_length,
+    TableStatistics,
+};
+use crate::models::{EstimateSource, ExportEstimate, TableEstimate};
+
+/// Rows read to measure the row length of a table without statistics.
+pub const SAMPLE_ROWS: usize = 1000;
+/// Share of the rows counted to estimate the row count of a table without statistics.
+const SAMPLE_PERCENT: f64 = 1.0;
+/// Rows a data export reads and formats per second, before the cost of their bytes.
+const ROWS_PER_SEC: u64 = 20_000;
+/// Bytes a data export reads and writes per second.
+const BYTES_PER_SEC: u64 = 8 * 1024 * 1024;
+
+/// Estimates every table of `tables` (catalog names) in `schema`; `exact_counts` counts every
+/// row of the tables without statistics. A table that cannot be counted or sampled is listed
+/// with its error instead of failing the estimate.
+pub fn estimate_tables(
+    connection: &Connection<'_>,
+    schema: &str,
+    tables: &[String],
+    exact_counts: bool,
+) -> Result<ExportEstimate> {
+    let statistics = fetch_table_statistics(connection, schema, tables)?;
+    let estimates = tables
+        .iter()
+        .map(|table| {
+            if let Some((rows, row_len)) = statistics.get(table).copied().and_then(analyzed) {
+                return table_estimate(table, rows, row_len, EstimateSource::Statistics);
+            }
+            let (rows, source) = if exact_counts {
+                let rows = fetch_row_count(connection, schema, table);
+                (rows.map(|rows| rows.max(0) as u64), EstimateSource::Counted)
+            } else {
+                let rows = fetch_sampled_row_count(connection, schema, table, SAMPLE_PERCENT);
+                (rows.map(scale_sample), EstimateSource::Sampled)
+            };
+            let sampled = rows.and_then(|rows| {
+                let row_len = sample_row_length(connection, schema, table, SAMPLE_ROWS)?;
+                Ok((rows, row_len.unwrap_or(0)))
+            });
+            match sampled {
+                Ok((rows, row_len)) => table_estimate(table, rows, row_len, source),
+                Err(e) => TableEstimate {
+                    table: table.clone(),
+                    rows: 0,
+                    bytes: 0,
+                    duration_ms: 0,
+                    source: None,
+                    error: Some(format!("{:#}", e)),
+                },
+            }
+        })
+        .collect();
+    Ok(build_estimate(estimates))
+}
+
+/// Row count and row length of an analyzed, non-empty table. Zero rows usually means the
+/// statistics are older than the data, so such tables are sampled instead.
+fn analyzed(statistics: TableStatistics) -> Option<(u64, u64)> {
+    match (statistics.num_rows, statistics.avg_row_len) {
+        (Some(rows), Some(row_len)) if rows > 0 && row_len > 0 => Some((rows, row_len)),
+        _ => None,
+    }
+}
+
+/// Rows of a table whose `SAMPLE_PERCENT` sample held `sampled` rows.
+fn scale_sample(sampled: i64) -> u64 {
+    (sampled.max(0) as f64 * 100.0 / SAMPLE_PERCENT).round() as u64
+}
+
+fn table_estimate(table: &str, rows: u64, row_len: u64, source: EstimateSource) -> TableEstimate {
+    let bytes = rows.saturating_mul(row_len);
+    TableEstimate {
+        table: table.to_string(),
+        rows,
+        bytes,
+        duration_ms: rows.saturating_mul(1000) / ROWS_PER_SEC
+            + bytes.saturating_mul(1000) / BYTES_PER_SEC,
+        source: Some(source),
+        error: None,
+    }
+}
+
+fn build_estimate(tables: Vec<TableEstimate>) -> ExportEstimate {
+    ExportEstimate {
+        total_rows: tables.iter().map(|table| table.rows).sum(),
+        total_bytes: tables.iter().map(|table| table.bytes).sum(),
+        duration_ms: tables.iter().map(|table| table.duration_ms).sum(),
+        tables,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_are_used_only_for_analyzed_non_empty_tables() {
+        let statistics = |num_rows, avg_row_len| TableStatistics {
+            num_rows,
+            avg_row_len,
+        };
+        assert_eq!(analyzed(statistics(Some(10), Some(80))), Some((10, 80)));
+        assert_eq!(analyzed(statistics(Some(0), Some(80))), None);
+        assert_eq!(analyzed(statistics(Some(10), None)), None);
+        assert_eq!(analyzed(statistics(None, None)), None);
+    }
+
+    #[test]
+    fn sampled_counts_scale_up_to_the_table() {
+        assert_eq!(scale_sample(0), 0);
+        assert_eq!(scale_sample(-1), 0);
+        assert_eq!(scale_sample(1234), 123_400);
+    }
+
+    #[test]
+    fn durations_follow_rows_and_bytes_and_totals_add_up() {
+        let orders = table_estimate("ORDERS", 40_000, 256, EstimateSource::Statistics);
+        assert_eq!(orders.bytes, 10_240_000);
+        assert_eq!(orders.duration_ms, 2000 + 10_240_000 * 1000 / BYTES_PER_SEC);
+        let codes = table_estimate("CODES", 0, 0, EstimateSource::Sampled);
+        assert_eq!((codes.bytes, codes.duration_ms), (0, 0));
+
+        let estimate = build_estimate(vec![orders.clone(), codes]);
+        assert_eq!(estimate.total_rows, 40_000);
+        assert_eq!(estimate.total_bytes, orders.bytes);
+        assert_eq!(estimate.duration_ms, orders.duration_ms);
+    }
+}
@@ -0,0 +1,284 @@
+//! Single-script export behind `POST /api/export/full`: the DDL the rows need, the data, then the
+//! objects that would slow down or reject the load (foreign keys, triggers, views, grants). Each
+//! part is generated into its own directory under `parts/` with the final file name, so the files
+//! it references next to itself (trigger script, manifest, row error log) keep their names when
+//! they are moved up beside the combined script.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::export::encoding::{decode_line, EncodedWriter, LineEndingWriter};
+use crate::export::run_order::{shift_step_comment, RunOrder};
+use crate::models::{ExportEncoding, LineEnding};
+
+/// Directory under the job directory holding the parts while they are generated.
+pub const PARTS_DIR: &str = "parts";
+
+/// One generated script of a combined export.
+pub struct ScriptPart {
+    pub title: &'static str,
+    pub path: PathBuf,
+}
+
+impl ScriptPart {
+    /// The part's script: `parts/<dir>/<file name of output_path>`.
+    pub fn new(title: &'static str, dir: &str, output_path: &Path) -> Self {
+        let parts_dir = output_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(PARTS_DIR);
+        Self {
+            title,
+            path: parts_dir
+                .join(dir)
+                .join(output_path.file_name().unwrap_or_default()),
+        }
+    }
+}
+
+/// Writes the parts, in order and each under a banner, into `output_path`, moves the files they
+/// wrote beside themselves next to it and removes `parts/`. The parts are already in `encoding`
+/// and copied as they are, except that the step numbers of every DDL part go on from those of
+/// the parts before it; their run order indexes are merged into one beside the script.
+pub fn combine_parts(
+    parts: &[ScriptPart],
+    output_path: &Path,
+    encoding: ExportEncoding,
+    line_ending: LineEnding,
+) -> Result<()> {
+    let mut file = File::create(output_path).with_context(|| {
+        format!(
+            "Failed to create combined export file at {}",
+            output_path.display()
+        )
+    })?;
+    let titles: Vec<&str> = parts.iter().map(|part| part.title).collect();
+    let header = [
+        "-- ============================================".to_string(),
+        "-- DM8 完整导出脚本 (DDL + 数据)".to_string(),
+        "-- ============================================".to_string(),
+        format!("-- 执行顺序: {}", titles.join(" -> ")),
+        "-- 说明: 各部分保留自己的文件头，步骤号跨部分连续编号，与同名 .run_order.txt 索引一致"
+            .to_string(),
+        "-- ============================================".to_string(),
+    ];
+    write_lines(&mut file, encoding, line_ending, &header)?;
+
+    let mut run_order = RunOrder::default();
+    for (number, part) in parts.iter().enumerate() {
+        write_lines(
+            &mut file,
+            encoding,
+            line_ending,
+            &[
+                String::new(),
+                "-- ############################################".to_string(),
+                format!("-- 第 {} 部分: {}", number + 1, part.title),
+                "-- ############################################".to_string(),
+            ],
+        )?;
+        let mut script = File::open(&part.path)
+            .with_context(|| format!("Failed to open export part {}", part.path.display()))?;
+        let index = part.path.with_extension("run_order.txt");
+        let copied = if index.exists() {
+            let offset = run_order.len();
+            run_order.append(RunOrder::read_index(&index)?);
+            copy_renumbered(BufReader::new(script), &mut file, encoding, offset)
+        } else {
+            io::copy(&mut script, &mut file).map(drop)
+        };
+        copied.with_context(|| {
+            format!(
+                "Failed to copy export part {} into {}",
+                part.path.display(),
+                output_path.display()
+            )
+        })?;
+    }
+    file.flush()
+        .context("Failed to flush combined export to disk")?;
+    drop(file);
+    if !run_order.is_empty() {
+        run_order.write_index(output_path)?;
+    }
+
+    let output_dir = output_path.parent().unwrap_or_else(|| Path::new("."));
+    for part in parts {
+        adopt_sidecars(part, output_dir)?;
+    }
+    let parts_dir = output_dir.join(PARTS_DIR);
+    fs::remove_dir_all(&parts_dir)
+        .with_context(|| format!("Failed to remove {}", parts_dir.display()))
+}
+
+/// Copies a DDL part, moving the numbers of its step comments up by `offset`.
+fn copy_renumbered(
+    mut script: impl BufRead,
+    file: &mut File,
+    encoding: ExportEncoding,
+    offset: usize,
+) -> io::Result<()> {
+    let mut line = Vec::new();
+    while script.read_until(b'\n', &mut line)? > 0 {
+        let shifted =
+            decode_line(&line, encoding).and_then(|text| shift_step_comment(&text, offset));
+        match shifted {
+            Some(shifted) => {
+                EncodedWriter::new(&mut *file, encoding).write_all(shifted.as_bytes())?
+            }
+            None => file.write_all(&line)?,
+        }
+        line.clear();
+    }
+    Ok(())
+}
+
+/// Comment lines in the script encoding and line ending.
+fn write_lines(
+    file: &mut File,
+    encoding: ExportEncoding,
+    line_ending: LineEnding,
+    lines: &[String],
+) -> Result<()> {
+    let mut writer = LineEndingWriter::new(EncodedWriter::new(file, encoding), line_ending);
+    for line in lines {
+        writeln!(writer, "{}", line)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Moves the files a part wrote next to its script into `output_dir`, except the script and its
+/// run order index.
+fn adopt_sidecars(part: &ScriptPart, output_dir: &Path) -> Result<()> {
+    let Some(part_dir) = part.path.parent() else {
+        return Ok(());
+    };
+    let index = part.path.with_extension("run_order.txt");
+    for entry in
+        fs::read_dir(part_dir).with_context(|| format!("Failed to list {}", part_dir.display()))?
+    {
+        let path = entry?.path();
+        if path == part.path || path == index || !path.is_file() {
+            continue;
+        }
+        let target = output_dir.join(path.file_name().unwrap_or_default());
+        fs::rename(&path, &target).with_context(|| {
+            format!("Failed to move {} to {}", path.display(), target.display())
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::run_order::Phase;
+    use tempfile::TempDir;
+
+    /// Writes a DDL part whose statements are annotated like `export_schema_ddl` does.
+    fn write_ddl_part(part: &ScriptPart, statements: &[(Phase, &str)]) {
+        let mut order = RunOrder::default();
+        let file = part.path.file_name().unwrap().to_string_lossy().to_string();
+        let body: String = statements
+            .iter()
+            .map(|(phase, statement)| format!("{}\n", order.annotate(*phase, &file, statement)))
+            .collect();
+        let mut writer = EncodedWriter::new(File::create(&part.path).unwrap(), ExportEncoding::Gbk);
+        writer.write_all(body.as_bytes()).unwrap();
+        order.write_index(&part.path).unwrap();
+    }
+
+    #[test]
+    fn parts_are_combined_in_order_and_their_sidecars_kept() {
+        let dir = TempDir::new().unwrap();
+        let output_path = dir.path().join("APP_to_APP_full_1.sql");
+        let parts = [
+            ScriptPart::new("建表", "schema", &output_path),
+            ScriptPart::new("数据", "data", &output_path),
+        ];
+        for part in &parts {
+            fs::create_dir_all(part.path.parent().unwrap()).unwrap();
+        }
+        write_ddl_part(&parts[0], &[(Phase::Table, "CREATE TABLE T;")]);
+        fs::write(&parts[1].path, "INSERT INTO T;\n").unwrap();
+        fs::write(parts[1].path.with_file_name("manifest.json"), "{}").unwrap();
+
+        combine_parts(&parts, &output_path, ExportEncoding::Gbk, LineEnding::Lf).unwrap();
+
+        let bytes = fs::read(&output_path).unwrap();
+        let (text, _, malformed) = encoding_rs::GBK.decode(&bytes);
+        assert!(!malformed);
+        let create = text.find("CREATE TABLE T;").unwrap();
+        let insert = text.find("INSERT INTO T;").unwrap();
+        let data_banner = text.find("-- 第 2 部分: 数据").unwrap();
+        assert!(text.contains("-- 执行顺序: 建表 -> 数据"));
+        assert!(create < data_banner && data_banner < insert);
+
+        assert!(dir.path().join("manifest.json").exists());
+        assert!(!dir.path().join(PARTS_DIR).exists());
+    }
+
+    #[test]
+    fn step_numbers_run_on_across_parts() {
+        let dir = TempDir::new().unwrap();
+        let output_path = dir.path().join("APP_to_APP_full_1.sql");
+        let parts = [
+            ScriptPart::new("建表", "schema", &output_path),
+            ScriptPart::new("数据", "data", &output_path),
+            ScriptPart::new("延后对象", "deferred", &output_path),
+        ];
+        for part in &parts {
+            fs::create_dir_all(part.path.parent().unwrap()).unwrap();
+        }
+        write_ddl_part(
+            &parts[0],
+            &[
+                (Phase::Table, "CREATE TABLE A;"),
+                (Phase::Table, "CREATE TABLE B;"),
+            ],
+        );
+        fs::write(&parts[1].path, "INSERT INTO A;\n").unwrap();
+        write_ddl_part(
+            &parts[2],
+            &[
+                (Phase::Index, "CREATE INDEX I ON A;"),
+                (Phase::ForeignKey, "ALTER TABLE B;"),
+            ],
+        );
+
+        combine_parts(&parts, &output_path, ExportEncoding::Gbk, LineEnding::Crlf).unwrap();
+
+        let bytes = fs::read(&output_path).unwrap();
+        let (text, _, malformed) = encoding_rs::GBK.decode(&bytes);
+        assert!(!malformed);
+        let steps: Vec<&str> = text
+            .lines()
+            .filter(|line| line.starts_with("-- 步骤 "))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                "-- 步骤 0001 [table]",
+                "-- 步骤 0002 [table]",
+                "-- 步骤 0003 [index]",
+                "-- 步骤 0004 [foreign_key]",
+            ]
+        );
+        assert!(text.contains("-- 步骤 0003 [index]\nCREATE INDEX I ON A;"));
+
+        let index = fs::read_to_string(output_path.with_extension("run_order.txt")).unwrap();
+        let numbers: Vec<&str> = index
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split('\t').next())
+            .collect();
+        assert_eq!(numbers, ["0001", "0002", "0003", "0004"]);
+        assert!(index.contains("0004\tforeign_key\tAPP_to_APP_full_1.sql\tALTER TABLE B;"));
+    }
+}
@@ -0,0 +1,99 @@
+//! DM8 spatial (DMGEO) columns. Their values are object types the ODBC text fetch cannot
+//! render, so they are read as `SRID=<n>;<WKT>` through `DMGEO.ST_SRID`/`DMGEO.ST_ASTEXT` and
+//! written back with `DMGEO.ST_GEOMFROMTEXT`.
+
+/// Types of the `SYSGEO` schema, as the catalog reports them with or without the owner.
+const GEOMETRY_TYPES: &[&str] = &[
+    "ST_GEOMETRY",
+    "ST_POINT",
+    "ST_LINESTRING",
+    "ST_POLYGON",
+    "ST_MULTIPOINT",
+    "ST_MULTILINESTRING",
+    "ST_MULTIPOLYGON",
+    "ST_GEOMCOLLECTION",
+    "ST_GEOGRAPHY",
+];
+
+/// Owner of the spatial types; DDL names them qualified since the target schema differs.
+const GEOMETRY_OWNER: &str = "SYSGEO";
+
+fn type_name(data_type: &str) -> String {
+    let upper = data_type.trim().to_uppercase();
+    let name = upper
+        .strip_prefix(GEOMETRY_OWNER)
+        .and_then(|t| t.strip_prefix('.'))
+        .unwrap_or(&upper);
+    name.trim_matches('"').to_string()
+}
+
+pub fn is_geometry_type(data_type: &str) -> bool {
+    GEOMETRY_TYPES.contains(&type_name(data_type).as_str())
+}
+
+/// `SYSGEO.ST_POINT` for `ST_POINT`.
+pub fn qualified_type(data_type: &str) -> String {
+    format!("{}.{}", GEOMETRY_OWNER, type_name(data_type))
+}
+
+/// Select-list expression reading a geometry column as `SRID=<n>;<WKT>`, NULL for NULL.
+pub fn select_expression(column_ident: &str) -> String {
+    format!(
+        "CASE WHEN {0} IS NULL THEN NULL ELSE 'SRID=' || DMGEO.ST_SRID({0}) || ';' || DMGEO.ST_ASTEXT({0}) END",
+        column_ident
+    )
+}
+
+/// `DMGEO.ST_GEOMFROMTEXT('<WKT>', <srid>)` for a value read by [`select_expression`]. Plain
+/// WKT without the SRID prefix gets SRID 0.
+pub fn geometry_literal(raw: &str) -> String {
+    let value = raw.trim();
+    let (srid, wkt) = value
+        .strip_prefix("SRID=")
+        .and_then(|rest| rest.split_once(';'))
+        .and_then(|(srid, wkt)| srid.trim().parse::<i32>().ok().map(|srid| (srid, wkt)))
+        .unwrap_or((0, value));
+    format!(
+        "DMGEO.ST_GEOMFROMTEXT('{}', {})",
+        wkt.trim().replace('\'', "''"),
+        srid
+    )
+}
+
+/// Column check keeping the SRID found in the source, since DM8 geometry types carry none.
+pub fn srid_check(column_ident: &str, srid: i32) -> String {
+    format!("CHECK (DMGEO.ST_SRID({}) = {})", column_ident, srid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_types_are_recognized_with_or_without_owner() {
+        assert!(is_geometry_type("ST_POINT"));
+        assert!(is_geometry_type("sysgeo.st_polygon"));
+        assert!(is_geometry_type("SYSGEO.\"ST_GEOMETRY\""));
+        assert!(!is_geometry_type("VARCHAR"));
+        assert!(!is_geometry_type("POINT"));
+        assert_eq!(qualified_type("st_multipolygon"), "SYSGEO.ST_MULTIPOLYGON");
+        assert_eq!(qualified_type("SYSGEO.ST_POINT"), "SYSGEO.ST_POINT");
+    }
+
+    #[test]
+    fn geometry_values_become_geomfromtext_calls() {
+        assert_eq!(
+            geometry_literal("SRID=4326;POINT(116.39 39.9)"),
+            "DMGEO.ST_GEOMFROMTEXT('POINT(116.39 39.9)', 4326)"
+        );
+        assert_eq!(
+            geometry_literal("LINESTRING(0 0, 1 1)"),
+            "DMGEO.ST_GEOMFROMTEXT('LINESTRING(0 0, 1 1)', 0)"
+        );
+        assert_eq!(
+            select_expression("\"SHAPE\""),
+            "CASE WHEN \"SHAPE\" IS NULL THEN NULL ELSE 'SRID=' || DMGEO.ST_SRID(\"SHAPE\") \
+             || ';' || DMGEO.ST_ASTEXT(\"SHAPE\") END"
+        );
+    }
+}
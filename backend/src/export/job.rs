@@ -0,0 +1,288 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use chrono::{Local, Utc};
+
+use crate::{
+    config_store::ConfigStore,
+    db::{capabilities::ServerCapabilities, metadata_cache::ConnectionMetadata},
+    export::stats::duration_ms,
+    models::{
+        ExportJob, ExportStats, ExportWarning, IssueCode, JobEvent, JobEventKind, JobStatus,
+        TableExportStats, TableMemoryStats,
+    },
+};
+
+/// Root under which every job gets its own artifact directory.
+pub const EXPORTS_ROOT: &str = "exports";
+
+/// Jobs registered with [`JobContext::start`] that have not been dropped yet.
+static ACTIVE_JOBS: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of export jobs currently running in this process.
+pub fn active_jobs() -> usize {
+    ACTIVE_JOBS.load(Ordering::Relaxed)
+}
+
+/// State of a single export run: the detected server capabilities and the job's timeline.
+///
+/// Every recorded event is persisted immediately when a store is attached, so the timeline
+/// of a job that dies halfway is still available afterwards.
+pub struct JobContext {
+    pub job_id: String,
+    pub capabilities: ServerCapabilities,
+    /// Memory use of every table the job's data exports have read.
+    pub memory: Vec<TableMemoryStats>,
+    /// Rows, bytes and time of every table the job's DDL and data exports wrote.
+    pub stats: ExportStats,
+    /// One line per row a data export skipped or wrote with NULLs under `on_row_error`.
+    pub row_errors: Vec<String>,
+    /// Shared table metadata cache for the job's source connection, when the caller has one.
+    pub metadata: Option<ConnectionMetadata>,
+    /// Tables each of the job's exports covers as `selected_tables` resolved them, in run order.
+    pub selections: Vec<Vec<String>>,
+    store: Option<Arc<ConfigStore>>,
+    events: Vec<JobEvent>,
+    warnings: Vec<ExportWarning>,
+    output_root: PathBuf,
+}
+
+impl Drop for JobContext {
+    fn drop(&mut self) {
+        if self.store.is_some() {
+            ACTIVE_JOBS.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl JobContext {
+    /// Registers a new running job in the store.
+    pub fn start(store: Arc<ConfigStore>, kind: &str) -> Self {
+        let job_id = new_job_id(kind);
+        let job = ExportJob {
+            id: job_id.clone(),
+            kind: kind.to_string(),
+            status: JobStatus::Running,
+            started_at: Utc::now().to_rfc3339(),
+            finished_at: None,
+            file_path: None,
+            message: None,
+        };
+        if let Err(e) = store.create_job(&job) {
+            tracing::warn!("Failed to persist export job {}: {:#}", job_id, e);
+        }
+        ACTIVE_JOBS.fetch_add(1, Ordering::Relaxed);
+
+        Self {
+            job_id,
+            capabilities: ServerCapabilities::default(),
+            memory: Vec::new(),
+            stats: ExportStats::default(),
+            row_errors: Vec::new(),
+            metadata: None,
+            selections: Vec::new(),
+            store: Some(store),
+            events: Vec::new(),
+            warnings: Vec::new(),
+            output_root: PathBuf::from(EXPORTS_ROOT),
+        }
+    }
+
+    /// A job that only keeps its timeline in memory.
+    pub fn detached(kind: &str) -> Self {
+        Self {
+            job_id: new_job_id(kind),
+            capabilities: ServerCapabilities::default(),
+            memory: Vec::new(),
+            stats: ExportStats::default(),
+            row_errors: Vec::new(),
+            metadata: None,
+            selections: Vec::new(),
+            store: None,
+            events: Vec::new(),
+            warnings: Vec::new(),
+            output_root: PathBuf::from(EXPORTS_ROOT),
+        }
+    }
+
+    /// Puts the job's directory under `root` instead of [`EXPORTS_ROOT`]; blank values keep
+    /// the default.
+    pub fn with_output_root(mut self, root: Option<&str>) -> Self {
+        if let Some(root) = root.map(str::trim).filter(|root| !root.is_empty()) {
+            self.output_root = PathBuf::from(root);
+        }
+        self
+    }
+
+    pub fn record(&mut self, kind: JobEventKind, table: Option<&str>, message: Option<String>) {
+        self.push_event(kind, None, table, message);
+    }
+
+    fn push_event(
+        &mut self,
+        kind: JobEventKind,
+        code: Option<IssueCode>,
+        table: Option<&str>,
+        message: Option<String>,
+    ) {
+        let event = JobEvent {
+            occurred_at: Utc::now().to_rfc3339(),
+            kind,
+            code,
+            table: table.map(str::to_string),
+            message,
+        };
+        if let Some(store) = &self.store {
+            if let Err(e) = store.append_job_event(&self.job_id, &event) {
+                tracing::warn!("Failed to persist event for job {}: {:#}", self.job_id, e);
+            }
+        }
+        self.events.push(event);
+    }
+
+    pub fn warn(&mut self, code: IssueCode, table: Option<&str>, message: impl Into<String>) {
+        let message = message.into();
+        tracing::warn!("{}", message);
+        self.warnings.push(ExportWarning {
+            code,
+            table: table.map(str::to_string),
+            message: message.clone(),
+        });
+        self.push_event(JobEventKind::Warning, Some(code), table, Some(message));
+    }
+
+    /// Adds one table's entry to the job's export statistics.
+    pub fn record_table_stats(
+        &mut self,
+        table: &str,
+        kind: &str,
+        rows: usize,
+        bytes: u64,
+        duration: Duration,
+    ) {
+        self.stats.tables.push(TableExportStats {
+            table: table.to_string(),
+            kind: kind.to_string(),
+            rows,
+            bytes,
+            duration_ms: duration_ms(duration),
+        });
+    }
+
+    /// Records the terminal event and stores the job's final status. `code` classifies a
+    /// failure.
+    pub fn finish(
+        &mut self,
+        success: bool,
+        file_path: Option<&str>,
+        message: Option<&str>,
+        code: Option<IssueCode>,
+    ) {
+        let (kind, status) = if success {
+            (JobEventKind::Finished, JobStatus::Succeeded)
+        } else {
+            (JobEventKind::Failed, JobStatus::Failed)
+        };
+        self.push_event(kind, code, None, message.map(str::to_string));
+        if let Some(store) = &self.store {
+            if let Err(e) = store.finish_job(&self.job_id, status, file_path, message) {
+                tracing::warn!("Failed to finish job {}: {:#}", self.job_id, e);
+            }
+        }
+    }
+
+    /// `exports/<job_id>/` (or `<output root>/<job_id>/`): all files of this job, so
+    /// concurrent jobs never share a directory.
+    pub fn output_dir(&self) -> PathBuf {
+        self.output_root.join(&self.job_id)
+    }
+
+    pub fn events(&self) -> &[JobEvent] {
+        &self.events
+    }
+
+    /// Warnings recorded so far, for the export response's post-export report.
+    pub fn warnings(&self) -> &[ExportWarning] {
+        &self.warnings
+    }
+}
+
+fn new_job_id(kind: &str) -> String {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    format!(
+        "{}_{}_{}",
+        kind,
+        Local::now().format("%Y%m%d_%H%M%S_%3f"),
+        sequence
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JobContext;
+    use crate::models::{IssueCode, JobEventKind};
+
+    #[test]
+    fn job_ids_are_unique_within_a_process() {
+        let first = JobContext::detached("ddl");
+        let second = JobContext::detached("ddl");
+        assert!(first.job_id.starts_with("ddl_"));
+        assert_ne!(first.job_id, second.job_id);
+        assert_ne!(first.output_dir(), second.output_dir());
+        assert!(first.output_dir().starts_with("exports"));
+
+        let chosen = JobContext::detached("ddl").with_output_root(Some("/data/out"));
+        assert!(chosen.output_dir().starts_with("/data/out"));
+        assert!(chosen.output_dir().ends_with(&chosen.job_id));
+        let blank = JobContext::detached("ddl").with_output_root(Some("  "));
+        assert!(blank.output_dir().starts_with("exports"));
+    }
+
+    #[test]
+    fn finish_appends_terminal_event() {
+        let mut job = JobContext::detached("data");
+        job.record(JobEventKind::Started, None, None);
+        job.warn(
+            IssueCode::RowCountUnavailable,
+            Some("USERS"),
+            "row count unavailable",
+        );
+        job.finish(false, None, Some("boom"), Some(IssueCode::IoError));
+
+        let kinds: Vec<JobEventKind> = job.events().iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                JobEventKind::Started,
+                JobEventKind::Warning,
+                JobEventKind::Failed
+            ]
+        );
+        assert_eq!(job.events()[1].table.as_deref(), Some("USERS"));
+        assert_eq!(job.events()[1].code, Some(IssueCode::RowCountUnavailable));
+        assert_eq!(job.events()[2].code, Some(IssueCode::IoError));
+    }
+
+    #[test]
+    fn warnings_are_collected_for_the_response() {
+        let mut job = JobContext::detached("ddl");
+        job.record(JobEventKind::TableStarted, Some("USERS"), None);
+        job.warn(IssueCode::IndexSkipped, Some("USERS"), "index skipped");
+        job.warn(IssueCode::ViewsUnavailable, None, "views unavailable");
+
+        let codes: Vec<IssueCode> = job.warnings().iter().map(|w| w.code).collect();
+        assert_eq!(
+            codes,
+            vec![IssueCode::IndexSkipped, IssueCode::ViewsUnavailable]
+        );
+        assert_eq!(job.warnings()[0].table.as_deref(), Some("USERS"));
+        assert_eq!(job.warnings()[1].message, "views unavailable");
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+use crate::db::retry::RetryingConnection;
+use crate::db::schema::catalog_name;
+use crate::export::binary::table_file_name;
+use crate::export::data::{
+    is_date_type, is_numeric_type, is_timestamp_type, load_table_metadata, stream_table_rows,
+    DataExportOptions,
+};
+use crate::export::dependency::load_order;
+use crate::export::job::JobContext;
+use crate::export::manifest::{write_manifest, ChecksumWriter, MANIFEST_FILE};
+use crate::export::renames::renamed_table;
+use crate::export::subset::consistent_subset;
+use crate::models::{DataFormat, ExportManifest, IssueCode, JobEventKind, ManifestTable};
+
+/// Significant digits a double holds exactly; longer decimals stay strings so consumers that
+/// parse JSON numbers as doubles do not round them.
+const MAX_EXACT_DIGITS: usize = 15;
+
+/// JSON text of one fetched value: numbers that survive a round trip through a JSON parser as
+/// numbers, dates and timestamps in ISO-8601, everything else as a string.
+fn json_value(data_type: &str, raw: &str) -> String {
+    if is_numeric_type(data_type) {
+        let value = raw.trim();
+        if is_json_number(value)
+            && (value.parse::<i64>().is_ok() || significant_digits(value) <= MAX_EXACT_DIGITS)
+        {
+            return value.to_string();
+        }
+    } else if is_date_type(data_type) || is_timestamp_type(data_type) {
+        return json_string(&iso8601(raw.trim()));
+    }
+    json_string(raw)
+}
+
+fn json_string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// Whether `value` is a number in JSON's grammar (DM8 may print e.g. `.5` or `1.`, which is not).
+fn is_json_number(value: &str) -> bool {
+    matches!(
+        serde_json::from_str::<serde_json::Value>(value),
+        Ok(serde_json::Value::Number(_))
+    )
+}
+
+fn significant_digits(value: &str) -> usize {
+    let mantissa = value.split(['e', 'E']).next().unwrap_or(value);
+    mantissa
+        .trim_start_matches('-')
+        .trim_start_matches(['0', '.'])
+        .chars()
+        .filter(char::is_ascii_digit)
+        .count()
+}
+
+/// `2026-01-30 08:15:00.5 +08:00` becomes `2026-01-30T08:15:00.5+08:00`; dates pass through.
+fn iso8601(value: &str) -> String {
+    let Some((date, rest)) = value.split_once(' ') else {
+        return value.to_string();
+    };
+    let time: String = rest.split_whitespace().collect();
+    format!("{}T{}", date, time)
+}
+
+/// One JSON object per row, keys in column order; NULL values are written as explicit `null`.
+fn json_line(keys: &[String], data_types: &[&str], row: &[Option<String>]) -> String {
+    let mut line = String::from("{");
+    for (index, ((key, data_type), value)) in keys.iter().zip(data_types).zip(row).enumerate() {
+        if index > 0 {
+            line.push(',');
+        }
+        line.push_str(key);
+        line.push(':');
+        match value {
+            Some(raw) => line.push_str(&json_value(data_type, raw)),
+            None => line.push_str("null"),
+        }
+    }
+    line.push('}');
+    line
+}
+
+/// Writes one `NNNN_<table>.jsonl` file per table into `output_dir`, in foreign key load order,
+/// plus a `manifest.json` with row counts and checksums. Each line is one row as a JSON object
+/// keyed by column name.
+pub fn export_schema_jsonl(
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
+    source_schema: &str,
+    target_schema: &str,
+    tables: &[String],
+    output_dir: &Path,
+    options: &DataExportOptions,
+) -> Result<(usize, PathBuf)> {
+    let source_schema_name = catalog_name(source_schema, options.preserve_case);
+    let target_schema_name = catalog_name(target_schema, options.preserve_case);
+    let (table_details_list, _) =
+        load_table_metadata(session, job, &source_schema_name, tables, options)?;
+    let row_filters = consistent_subset(job, &source_schema_name, &table_details_list, options)?;
+
+    let load = load_order(&source_schema_name, &table_details_list);
+    if !load.cyclic.is_empty() {
+        job.warn(
+            IssueCode::ForeignKeyCycle,
+            None,
+            format!(
+                "Foreign key cycle between {}; these tables keep their requested order",
+                load.cyclic.join(", ")
+            ),
+        );
+    }
+
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create export directory {}", output_dir.display()))?;
+
+    let mut exported_total = 0;
+    let mut manifest_tables = Vec::with_capacity(table_details_list.len());
+    for (position, &index) in load.order.iter().enumerate() {
+        let details = &table_details_list[index];
+        let source_table = details.name.clone();
+        let target_table = renamed_table(&options.table_renames, &source_table);
+        let file_name = table_file_name(position + 1, &target_table, "jsonl");
+        let keys: Vec<String> = details
+            .columns
+            .iter()
+            .map(|column| json_string(&column.name))
+            .collect();
+        let data_types: Vec<&str> = details
+            .columns
+            .iter()
+            .map(|column| column.data_type.as_str())
+            .collect();
+
+        job.record(JobEventKind::TableStarted, Some(&source_table), None);
+        let path = output_dir.join(&file_name);
+        let mut file = BufWriter::new(
+            File::create(&path)
+                .with_context(|| format!("Failed to create export file {}", path.display()))?,
+        );
+        let mut checksum_writer = ChecksumWriter::new(&mut file);
+        let (rows, stats) = stream_table_rows(
+            session,
+            job,
+            &source_schema_name,
+            details,
+            row_filters.get(&details.name).map(String::as_str),
+            options,
+            &mut |rows| {
+                for row in &rows {
+                    writeln!(checksum_writer, "{}", json_line(&keys, &data_types, row))?;
+                }
+                Ok(())
+            },
+        )
+        .with_context(|| format!("Failed to export table '{}' to JSON Lines", source_table))?;
+        let checksum = checksum_writer.finish();
+        file.flush()
+            .with_context(|| format!("Failed to flush export file {}", path.display()))?;
+
+        manifest_tables.push(ManifestTable {
+            table: target_table,
+            source_table: source_table.clone(),
+            rows,
+            checksum,
+            file: Some(file_name),
+            columns: Vec::new(),
+        });
+        job.record(
+            JobEventKind::TableFinished,
+            Some(&source_table),
+            Some(format!("{} rows", rows)),
+        );
+        job.memory.push(stats);
+        exported_total += rows;
+    }
+
+    let manifest = ExportManifest {
+        job_id: job.job_id.clone(),
+        generated_at: Utc::now().to_rfc3339(),
+        source_schema: source_schema_name,
+        target_schema: target_schema_name,
+        format: DataFormat::Jsonl,
+        data_file: String::new(),
+        row_limit: None,
+        sample: options.sample.clone(),
+        tables: manifest_tables,
+    };
+    let manifest_path = output_dir.join(MANIFEST_FILE);
+    write_manifest(&manifest_path, &manifest)?;
+    Ok((exported_total, manifest_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_line, json_value};
+
+    #[test]
+    fn values_keep_their_json_types() {
+        assert_eq!(json_value("INTEGER", "42"), "42");
+        assert_eq!(
+            json_value("BIGINT", "9223372036854775807"),
+            "9223372036854775807"
+        );
+        assert_eq!(json_value("NUMBER", "-12.50"), "-12.50");
+        // Too precise for a double, or not valid JSON number syntax.
+        assert_eq!(
+            json_value("DECIMAL", "12345678901234567.89"),
+            "\"12345678901234567.89\""
+        );
+        assert_eq!(json_value("NUMBER", ".5"), "\".5\"");
+        assert_eq!(json_value("DATE", "2026-01-30"), "\"2026-01-30\"");
+        assert_eq!(
+            json_value("TIMESTAMP", "2026-01-30 08:15:00.500000"),
+            "\"2026-01-30T08:15:00.500000\""
+        );
+        assert_eq!(
+            json_value("TIMESTAMP WITH TIME ZONE", "2026-01-30 08:15:00 +08:00"),
+            "\"2026-01-30T08:15:00+08:00\""
+        );
+        assert_eq!(
+            json_value("VARCHAR", "say \"hi\"\n"),
+            "\"say \\\"hi\\\"\\n\""
+        );
+    }
+
+    #[test]
+    fn rows_become_objects_in_column_order() {
+        let keys = vec!["\"ID\"".to_string(), "\"NOTE\"".to_string()];
+        let line = json_line(&keys, &["INT", "VARCHAR"], &[Some("7".to_string()), None]);
+        assert_eq!(line, "{\"ID\":7,\"NOTE\":null}");
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use odbc_api::Connection;
+
+use crate::db::{
+    capabilities::ServerCapabilities,
+    schema::{fetch_max_lob_length, fetch_schema_names, get_table_details},
+};
+use crate::export::data::MAX_TEXT_VALUE_BYTES;
+use crate::models::{LintFinding, LintReport, LintRule, LintSeverity, TableDetails, TableLint};
+
+/// Words DM8 reserves; tables or columns named like this need quoting in every hand-written
+/// statement and break tools that do not quote identifiers.
+const RESERVED_WORDS: &[&str] = &[
+    "ADD",
+    "ALL",
+    "ALTER",
+    "AND",
+    "ANY",
+    "AS",
+    "ASC",
+    "AUDIT",
+    "BETWEEN",
+    "BY",
+    "CASE",
+    "CHECK",
+    "CLUSTER",
+    "COLUMN",
+    "COMMENT",
+    "CONNECT",
+    "CONSTRAINT",
+    "CONTEXT",
+    "CREATE",
+    "CROSS",
+    "CURRENT",
+    "DECLARE",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DOMAIN",
+    "DROP",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "EXISTS",
+    "FOR",
+    "FOREIGN",
+    "FROM",
+    "FULL",
+    "GRANT",
+    "GROUP",
+    "HAVING",
+    "IDENTITY",
+    "IN",
+    "INDEX",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "LEFT",
+    "LEVEL",
+    "LIKE",
+    "LIMIT",
+    "MINUS",
+    "NOT",
+    "NULL",
+    "OF",
+    "OFFSET",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "PRIMARY",
+    "PRIOR",
+    "PUBLIC",
+    "REFERENCES",
+    "REVOKE",
+    "RIGHT",
+    "ROW",
+    "ROWID",
+    "ROWNUM",
+    "SELECT",
+    "SET",
+    "SIZE",
+    "START",
+    "SYSDATE",
+    "TABLE",
+    "THEN",
+    "TO",
+    "TOP",
+    "TRIGGER",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "USER",
+    "USING",
+    "VALUES",
+    "VERIFY",
+    "VIEW",
+    "WHEN",
+    "WHERE",
+    "WITH",
+];
+
+/// Column types the DDL and data exporters know how to render.
+const SUPPORTED_TYPES: &[&str] = &[
+    "CHAR",
+    "CHARACTER",
+    "VARCHAR",
+    "VARCHAR2",
+    "NCHAR",
+    "NVARCHAR",
+    "NVARCHAR2",
+    "TEXT",
+    "LONG",
+    "LONGVARCHAR",
+    "LONG VARCHAR",
+    "CLOB",
+    "NCLOB",
+    "NUMBER",
+    "NUMERIC",
+    "DECIMAL",
+    "DEC",
+    "INT",
+    "INTEGER",
+    "BIGINT",
+    "SMALLINT",
+    "TINYINT",
+    "BYTE",
+    "BIT",
+    "BOOLEAN",
+    "FLOAT",
+    "DOUBLE",
+    "DOUBLE PRECISION",
+    "REAL",
+    "DATE",
+    "TIME",
+    "DATETIME",
+    "TIMESTAMP",
+    "TIMESTAMP WITH TIME ZONE",
+    "TIMESTAMP WITH LOCAL TIME ZONE",
+    "TIME WITH TIME ZONE",
+    "BINARY",
+    "VARBINARY",
+    "RAW",
+    "BLOB",
+    "IMAGE",
+    "LONGVARBINARY",
+];
+
+const LOB_TYPES: &[&str] = &[
+    "BLOB",
+    "CLOB",
+    "NCLOB",
+    "TEXT",
+    "IMAGE",
+    "LONG",
+    "LONGVARCHAR",
+    "LONGVARBINARY",
+];
+
+/// Facts about the source database the per-table rules need.
+#[derive(Debug, Default)]
+pub struct LintContext {
+    /// Upper-cased schema being exported.
+    pub schema: String,
+    /// Upper-cased names of every schema on the instance.
+    pub schemas: HashSet<String>,
+    /// Longest value per upper-cased LOB column name.
+    pub lob_lengths: HashMap<String, i64>,
+}
+
+/// Loads the selected tables and checks them for constructs that cause migration trouble.
+pub fn lint_tables(
+    connection: &Connection<'_>,
+    capabilities: &ServerCapabilities,
+    schema: &str,
+    tables: &[String],
+) -> Result<LintReport> {
+    let schema = schema.to_uppercase();
+    let schemas = match fetch_schema_names(connection) {
+        Ok(names) => names.into_iter().map(|name| name.to_uppercase()).collect(),
+        Err(e) => {
+            tracing::warn!("Cross-schema trigger check skipped: {:#}", e);
+            HashSet::new()
+        }
+    };
+
+    let mut results = Vec::with_capacity(tables.len());
+    for table in tables {
+        let details = get_table_details(connection, capabilities, &schema, &table.to_uppercase())
+            .with_context(|| format!("Failed to fetch table metadata for '{}'", table))?;
+
+        let mut lob_lengths = HashMap::new();
+        for column in details.columns.iter().filter(|c| is_lob_type(&c.data_type)) {
+            match fetch_max_lob_length(connection, &schema, &details.name, &column.name) {
+                Ok(Some(length)) => {
+                    lob_lengths.insert(column.name.to_uppercase(), length);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("LOB size check skipped: {:#}", e),
+            }
+        }
+
+        let context = LintContext {
+            schema: schema.clone(),
+            schemas: schemas.clone(),
+            lob_lengths,
+        };
+        results.push(lint_table(&details, &context));
+    }
+
+    Ok(build_report(results))
+}
+
+pub fn lint_table(table: &TableDetails, context: &LintContext) -> TableLint {
+    let mut findings = Vec::new();
+
+    if table.primary_keys.is_empty() {
+        findings.push(LintFinding {
+            rule: LintRule::MissingPrimaryKey,
+            severity: LintSeverity::Warning,
+            column: None,
+            message: "Table has no primary key; previews are unordered and rows cannot be \
+                      matched after migration"
+                .to_string(),
+        });
+    }
+    if is_reserved_word(&table.name) {
+        findings.push(LintFinding {
+            rule: LintRule::ReservedWord,
+            severity: LintSeverity::Warning,
+            column: None,
+            message: format!("Table name {} is a reserved word", table.name),
+        });
+    }
+
+    for column in &table.columns {
+        if is_reserved_word(&column.name) {
+            findings.push(LintFinding {
+                rule: LintRule::ReservedWord,
+                severity: LintSeverity::Warning,
+                column: Some(column.name.clone()),
+                message: format!("Column name {} is a reserved word", column.name),
+            });
+        }
+        if !is_supported_type(&column.data_type) {
+            findings.push(LintFinding {
+                rule: LintRule::UnsupportedType,
+                severity: LintSeverity::Error,
+                column: Some(column.name.clone()),
+                message: format!(
+                    "Type {} is not handled by the exporter and needs a manual migration",
+                    column.data_type
+                ),
+            });
+        }
+        if let Some(&length) = context.lob_lengths.get(&column.name.to_uppercase()) {
+            if length > MAX_TEXT_VALUE_BYTES as i64 {
+                findings.push(LintFinding {
+                    rule: LintRule::OversizedLob,
+                    severity: LintSeverity::Error,
+                    column: Some(column.name.clone()),
+                    message: format!(
+                        "Largest value is {} long; the data export truncates values over {} bytes",
+                        length, MAX_TEXT_VALUE_BYTES
+                    ),
+                });
+            }
+        }
+    }
+
+    for trigger in &table.triggers {
+        let foreign = foreign_schema_refs(&trigger.body, &context.schema, &context.schemas);
+        if !foreign.is_empty() {
+            findings.push(LintFinding {
+                rule: LintRule::CrossSchemaTrigger,
+                severity: LintSeverity::Warning,
+                column: None,
+                message: format!(
+                    "Trigger {} references schema(s) {}, which must exist on the target",
+                    trigger.name,
+                    foreign.join(", ")
+                ),
+            });
+        }
+    }
+
+    TableLint {
+        table: table.name.clone(),
+        score: score(&findings),
+        findings,
+    }
+}
+
+pub fn build_report(tables: Vec<TableLint>) -> LintReport {
+    let score = if tables.is_empty() {
+        100
+    } else {
+        let total: usize = tables.iter().map(|t| t.score as usize).sum();
+        (total / tables.len()) as u8
+    };
+    let ready = tables
+        .iter()
+        .flat_map(|t| &t.findings)
+        .all(|f| f.severity != LintSeverity::Error);
+    LintReport {
+        score,
+        ready,
+        tables,
+    }
+}
+
+fn score(findings: &[LintFinding]) -> u8 {
+    let penalty: u32 = findings
+        .iter()
+        .map(|f| match f.severity {
+            LintSeverity::Error => 25,
+            LintSeverity::Warning => 10,
+            LintSeverity::Info => 2,
+        })
+        .sum();
+    100u32.saturating_sub(penalty) as u8
+}
+
+fn base_type(data_type: &str) -> String {
+    let upper = data_type.trim().to_uppercase();
+    match upper.find('(') {
+        Some(pos) => upper[..pos].trim_end().to_string(),
+        None => upper,
+    }
+}
+
+fn is_supported_type(data_type: &str) -> bool {
+    SUPPORTED_TYPES.contains(&base_type(data_type).as_str())
+}
+
+fn is_lob_type(data_type: &str) -> bool {
+    LOB_TYPES.contains(&base_type(data_type).as_str())
+}
+
+fn is_reserved_word(name: &str) -> bool {
+    RESERVED_WORDS.contains(&name.to_uppercase().as_str())
+}
+
+/// Schemas other than `own` used as `SCHEMA.OBJECT` qualifiers in a trigger body. String
+/// literals and `:NEW`/`:OLD` references are skipped.
+fn foreign_schema_refs(body: &str, own: &str, schemas: &HashSet<String>) -> Vec<String> {
+    let chars: Vec<char> = body.chars().collect();
+    let mut found: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != '\'' {
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+
+        let bind_variable = i > 0 && chars[i - 1] == ':';
+        let (name, end) = if c == '"' {
+            let start = i + 1;
+            let mut end = start;
+            while end < chars.len() && chars[end] != '"' {
+                end += 1;
+            }
+            (
+                chars[start..end.min(chars.len())]
+                    .iter()
+                    .collect::<String>(),
+                end + 1,
+            )
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = i;
+            while end < chars.len()
+                && (chars[end].is_alphanumeric() || matches!(chars[end], '_' | '$' | '#'))
+            {
+                end += 1;
+            }
+            (chars[i..end].iter().collect::<String>().to_uppercase(), end)
+        } else {
+            i += 1;
+            continue;
+        };
+
+        let qualifier = chars.get(end) == Some(&'.');
+        let after_dot = i > 0 && chars[i - 1] == '.';
+        if qualifier
+            && !bind_variable
+            && !after_dot
+            && name != own
+            && schemas.contains(&name)
+            && !found.contains(&name)
+        {
+            found.push(name);
+        }
+        i = end;
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_report, foreign_schema_refs, lint_table, LintContext};
+    use crate::models::{Column, LintRule, LintSeverity, TableDetails, TriggerDefinition};
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn clean_table_scores_full_marks() {
+        let details =
+            TableDetails::named("ORDERS", vec![Column::named("ID", "NUMBER(10)")], &["ID"]);
+        let result = lint_table(&details, &LintContext::default());
+        assert!(result.findings.is_empty());
+        assert_eq!(result.score, 100);
+    }
+
+    #[test]
+    fn flags_missing_pk_reserved_words_types_and_large_lobs() {
+        let details = TableDetails::named(
+            "USER",
+            vec![
+                Column::named("LEVEL", "INT"),
+                Column::named("SHAPE", "SYSGEO.ST_GEOMETRY"),
+                Column::named("PAYLOAD", "CLOB"),
+            ],
+            &[],
+        );
+        let context = LintContext {
+            schema: "APP".to_string(),
+            schemas: HashSet::new(),
+            lob_lengths: HashMap::from([("PAYLOAD".to_string(), 1_000_000)]),
+        };
+
+        let result = lint_table(&details, &context);
+        let rules: Vec<LintRule> = result.findings.iter().map(|f| f.rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                LintRule::MissingPrimaryKey,
+                LintRule::ReservedWord,
+                LintRule::ReservedWord,
+                LintRule::UnsupportedType,
+                LintRule::OversizedLob,
+            ]
+        );
+        assert_eq!(result.score, 100 - 10 - 10 - 10 - 25 - 25);
+
+        let report = build_report(vec![result]);
+        assert!(!report.ready);
+        assert_eq!(report.score, 20);
+    }
+
+    #[test]
+    fn detects_triggers_using_other_schemas() {
+        let schemas: HashSet<String> = ["APP", "AUDIT", "HR"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let body = "BEGIN INSERT INTO \"AUDIT\".LOG VALUES (:NEW.ID, 'HR.X'); \
+                    SELECT APP.SEQ.NEXTVAL INTO :NEW.ID FROM DUAL; END;";
+        assert_eq!(
+            foreign_schema_refs(body, "APP", &schemas),
+            vec!["AUDIT".to_string()]
+        );
+
+        let mut details = TableDetails::named("T", vec![Column::named("ID", "INT")], &["ID"]);
+        details.triggers.push(TriggerDefinition {
+            name: "TRG_T".to_string(),
+            table_name: "T".to_string(),
+            timing: "BEFORE".to_string(),
+            events: vec!["INSERT".to_string()],
+            each_row: true,
+            body: body.to_string(),
+        });
+        let context = LintContext {
+            schema: "APP".to_string(),
+            schemas,
+            lob_lengths: HashMap::new(),
+        };
+        let result = lint_table(&details, &context);
+        assert_eq!(result.findings.len(), 1);
+        assert_eq!(result.findings[0].rule, LintRule::CrossSchemaTrigger);
+        assert_eq!(result.findings[0].severity, LintSeverity::Warning);
+    }
+}
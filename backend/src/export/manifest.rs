@@ -0,0 +1,188 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use odbc_api::Connection;
+use sha2::{Digest, Sha256};
+
+use crate::db::schema::fetch_row_count;
+use crate::models::{ExportManifest, TableVerification, VerifyReport};
+
+/// File name of the manifest written next to a data export.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Forwards writes and computes a SHA-256 over everything written through it.
+pub struct ChecksumWriter<'a, W: Write> {
+    inner: &'a mut W,
+    hasher: Sha256,
+}
+
+impl<'a, W: Write> ChecksumWriter<'a, W> {
+    pub fn new(inner: &'a mut W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Lower-case hex digest of the bytes written so far.
+    pub fn finish(self) -> String {
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// `manifest.json` in the directory of the exported SQL file.
+pub fn manifest_path(output_path: &Path) -> PathBuf {
+    output_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(MANIFEST_FILE)
+}
+
+pub fn write_manifest(path: &Path, manifest: &ExportManifest) -> Result<()> {
+    let json = serde_json::to_string_pretty(manifest).context("Failed to serialize manifest")?;
+    fs::write(path, json).with_context(|| format!("Failed to write manifest {}", path.display()))
+}
+
+pub fn read_manifest(path: &Path) -> Result<ExportManifest> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read manifest {}", path.display()))?;
+    serde_json::from_str(&json)
+        .with_context(|| format!("Failed to parse manifest {}", path.display()))
+}
+
+/// Counts the rows of every manifest table in `schema` on the target and compares them with the
+/// exported counts. A table that cannot be counted is reported as a mismatch.
+pub fn verify_manifest(
+    connection: &Connection<'_>,
+    manifest: &ExportManifest,
+    schema: &str,
+) -> VerifyReport {
+    compare_counts(manifest, |table| fetch_row_count(connection, schema, table))
+}
+
+fn compare_counts(
+    manifest: &ExportManifest,
+    mut count_rows: impl FnMut(&str) -> Result<i64>,
+) -> VerifyReport {
+    let tables: Vec<TableVerification> = manifest
+        .tables
+        .iter()
+        .map(|entry| {
+            let (actual, error) = match count_rows(&entry.table) {
+                Ok(count) => (Some(count), None),
+                Err(e) => (None, Some(format!("{:#}", e))),
+            };
+            TableVerification {
+                table: entry.table.clone(),
+                expected: entry.rows,
+                actual,
+                matches: actual == Some(entry.rows as i64),
+                error,
+            }
+        })
+        .collect();
+
+    VerifyReport {
+        // Partial (preview or sampled) exports never prove a complete migration.
+        complete: manifest.row_limit.is_none()
+            && manifest.sample.is_none()
+            && tables.iter().all(|t| t.matches),
+        tables,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_counts, ChecksumWriter};
+    use crate::models::{DataFormat, ExportManifest, ManifestTable, SampleSpec};
+    use std::io::Write;
+
+    fn manifest(row_limit: Option<usize>) -> ExportManifest {
+        ExportManifest {
+            job_id: "data_1".to_string(),
+            generated_at: "2026-01-30T01:00:00Z".to_string(),
+            source_schema: "APP".to_string(),
+            target_schema: "APP_NEW".to_string(),
+            format: DataFormat::Sql,
+            data_file: "data.sql".to_string(),
+            row_limit,
+            sample: None,
+            tables: vec![
+                ManifestTable {
+                    table: "USERS".to_string(),
+                    source_table: "USERS".to_string(),
+                    rows: 3,
+                    checksum: String::new(),
+                    file: None,
+                    columns: Vec::new(),
+                },
+                ManifestTable {
+                    table: "ORDERS".to_string(),
+                    source_table: "ORDERS".to_string(),
+                    rows: 5,
+                    checksum: String::new(),
+                    file: None,
+                    columns: Vec::new(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn checksum_covers_written_bytes() {
+        let mut out = Vec::new();
+        let mut writer = ChecksumWriter::new(&mut out);
+        writer.write_all(b"abc").unwrap();
+        assert_eq!(
+            writer.finish(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(out, b"abc");
+    }
+
+    #[test]
+    fn verification_reports_mismatches_and_errors() {
+        let report = compare_counts(&manifest(None), |table| match table {
+            "USERS" => Ok(3),
+            _ => Err(anyhow::anyhow!("table missing")),
+        });
+        assert!(!report.complete);
+        assert!(report.tables[0].matches);
+        assert_eq!(report.tables[1].actual, None);
+        assert_eq!(report.tables[1].error.as_deref(), Some("table missing"));
+
+        let report = compare_counts(&manifest(None), |table| {
+            Ok(if table == "USERS" { 3 } else { 5 })
+        });
+        assert!(report.complete);
+
+        let report = compare_counts(&manifest(Some(10)), |table| {
+            Ok(if table == "USERS" { 3 } else { 5 })
+        });
+        assert!(!report.complete);
+
+        let mut sampled = manifest(None);
+        sampled.sample = Some(SampleSpec {
+            percent: Some(10.0),
+            ..SampleSpec::default()
+        });
+        let report = compare_counts(&sampled, |table| Ok(if table == "USERS" { 3 } else { 5 }));
+        assert!(!report.complete);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::export::data::{type_without_precision, MAX_TEXT_VALUE_BYTES};
+use crate::export::geometry::is_geometry_type;
+use crate::export::pipeline::PIPELINE_DEPTH;
+use crate::models::{Column, TableDetails};
+
+/// Memory one fetched batch may take when adaptive batch sizing is on and the request sets no
+/// budget of its own.
+pub const DEFAULT_MAX_BATCH_BYTES: usize = 32 * 1024 * 1024;
+
+/// Bytes the text fetch buffer reserves per row of `details`: every column's widest text form
+/// (capped at `MAX_TEXT_VALUE_BYTES`, as the fetch is) plus its length indicator.
+pub fn estimated_row_bytes(details: &TableDetails) -> usize {
+    details
+        .columns
+        .iter()
+        .map(|column| column_text_bytes(column).min(MAX_TEXT_VALUE_BYTES) + INDICATOR_BYTES)
+        .sum::<usize>()
+        .max(1)
+}
+
+const INDICATOR_BYTES: usize = 8;
+
+/// UTF-16 code units the wide fetch buffer reserves per column of `details`: two per character
+/// of a character column (surrogate pairs), capped at `MAX_TEXT_VALUE_BYTES` like the narrow
+/// fetch. Other columns are `None`: the fetch sizes them from the driver's display size, as the
+/// narrow fetch does, since catalog precisions understate their text (`INTERVAL`, zoned `TIME`).
+pub fn wide_text_lengths(details: &TableDetails) -> Vec<Option<usize>> {
+    details
+        .columns
+        .iter()
+        .map(|column| {
+            let length = column.length.and_then(|l| usize::try_from(l).ok())?;
+            column
+                .data_type
+                .to_uppercase()
+                .contains("CHAR")
+                .then(|| (length * 2).clamp(1, MAX_TEXT_VALUE_BYTES))
+        })
+        .collect()
+}
+
+/// Bytes the wide fetch buffer reserves per row of `details`: two per code unit plus the length
+/// indicator, with the text width standing in for the display size of columns without a length.
+pub fn estimated_wide_row_bytes(details: &TableDetails, lengths: &[Option<usize>]) -> usize {
+    details
+        .columns
+        .iter()
+        .zip(lengths)
+        .map(|(column, units)| {
+            let units =
+                units.unwrap_or_else(|| column_text_bytes(column).clamp(1, MAX_TEXT_VALUE_BYTES));
+            units * 2 + INDICATOR_BYTES
+        })
+        .sum::<usize>()
+        .max(1)
+}
+
+/// Widest text of an `INTERVAL` value as the driver returns it, which may repeat the qualifier:
+/// `INTERVAL '+123456789 12:34:56.123456789' DAY(9) TO SECOND(9)`.
+const INTERVAL_TEXT_BYTES: usize = 80;
+
+fn column_text_bytes(column: &Column) -> usize {
+    let data_type = type_without_precision(&column.data_type);
+    let length = column.length.and_then(|l| usize::try_from(l).ok());
+    match data_type.as_str() {
+        "CLOB" | "TEXT" | "LONGVARCHAR" | "NCLOB" | "BLOB" | "IMAGE" | "LONGVARBINARY" => {
+            MAX_TEXT_VALUE_BYTES
+        }
+        // Geometry values are fetched as WKT of unbounded length.
+        t if is_geometry_type(t) => MAX_TEXT_VALUE_BYTES,
+        // Binary values are fetched as hex text, two characters per byte.
+        "BINARY" | "VARBINARY" | "RAW" => length.map_or(MAX_TEXT_VALUE_BYTES, |l| l * 2),
+        // UTF-8 text of a character column takes up to four bytes per character.
+        t if t.contains("CHAR") => length.map_or(MAX_TEXT_VALUE_BYTES, |l| l * 4),
+        t if t.starts_with("INTERVAL") => INTERVAL_TEXT_BYTES,
+        t if t.starts_with("TIMESTAMP") || t.starts_with("DATETIME") => 40,
+        // `12:34:56.123456789 +08:00`
+        "TIME WITH TIME ZONE" => 32,
+        "DATE" | "TIME" => 20,
+        _ => column
+            .precision
+            .and_then(|p| usize::try_from(p).ok())
+            .map_or(40, |p| p + 2),
+    }
+}
+
+/// Rows per fetch so that one batch stays within `max_batch_bytes`, never more than the
+/// requested batch size and never less than one row.
+pub fn adaptive_batch_size(requested: usize, row_bytes: usize, max_batch_bytes: usize) -> usize {
+    (max_batch_bytes / row_bytes.max(1)).clamp(1, requested.max(1))
+}
+
+/// Upper bound of the batches a table export holds at once: the fetch buffer plus the batches
+/// queued in front of the format and write stages.
+pub fn in_flight_bytes(batch_bytes: usize) -> usize {
+    batch_bytes * (1 + 2 * PIPELINE_DEPTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        adaptive_batch_size, estimated_row_bytes, estimated_wide_row_bytes, wide_text_lengths,
+    };
+    use crate::models::{Column, TableDetails};
+
+    fn column(name: &str, data_type: &str, length: Option<i32>) -> Column {
+        Column {
+            length,
+            ..Column::named(name, data_type)
+        }
+    }
+
+    fn table(columns: Vec<Column>) -> TableDetails {
+        TableDetails::named("DOCS", columns, &[])
+    }
+
+    #[test]
+    fn wide_lob_tables_get_smaller_batches() {
+        let narrow = table(vec![
+            column("ID", "INT", None),
+            column("CODE", "VARCHAR", Some(10)),
+        ]);
+        let wide = table(vec![
+            column("ID", "INT", None),
+            column("BODY", "CLOB", Some(i32::MAX)),
+            column("ATTACHMENT", "BLOB", Some(i32::MAX)),
+        ]);
+
+        assert_eq!(estimated_row_bytes(&narrow), 40 + 8 + 40 + 8);
+        assert_eq!(estimated_row_bytes(&wide), 48 + 2 * (8192 + 8));
+
+        let budget = 1024 * 1024;
+        assert_eq!(
+            adaptive_batch_size(1000, estimated_row_bytes(&narrow), budget),
+            1000
+        );
+        assert_eq!(
+            adaptive_batch_size(1000, estimated_row_bytes(&wide), budget),
+            63
+        );
+        assert_eq!(adaptive_batch_size(1000, usize::MAX, budget), 1);
+    }
+
+    #[test]
+    fn wide_fetch_reserves_two_units_per_character() {
+        let details = table(vec![
+            column("ID", "INT", None),
+            column("NAME", "NVARCHAR", Some(10)),
+            column("BODY", "CLOB", Some(i32::MAX)),
+        ]);
+
+        let lengths = wide_text_lengths(&details);
+        assert_eq!(lengths, vec![None, Some(20), None]);
+        assert_eq!(
+            estimated_wide_row_bytes(&details, &lengths),
+            (40 + 20 + 8192) * 2 + 3 * 8
+        );
+    }
+
+    #[test]
+    fn interval_and_zoned_time_columns_reserve_their_full_text() {
+        let mut time = column("AT", "TIME(6) WITH TIME ZONE", None);
+        time.precision = Some(6);
+        let mut interval = column("SPAN", "INTERVAL DAY(9) TO SECOND(9)", None);
+        interval.precision = Some(9);
+        let details = table(vec![time, interval, column("T", "TIME(3)", None)]);
+
+        assert_eq!(estimated_row_bytes(&details), 32 + 8 + 80 + 8 + 20 + 8);
+    }
+}
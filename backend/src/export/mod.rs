@@ -1,2 +1,30 @@
 pub mod ddl;
 pub mod data;
+pub mod compare;
+pub mod job;
+pub mod pipeline;
+pub mod charset;
+pub mod run_order;
+pub mod sequences;
+pub mod dependency;
+pub mod renames;
+pub mod encoding;
+pub mod lint;
+pub mod manifest;
+pub mod binary;
+pub mod xlsx;
+pub mod jsonl;
+pub mod dmfldr;
+pub mod csv_import;
+pub mod memory;
+pub mod plan;
+pub mod project;
+pub mod stats;
+pub mod dialect;
+pub mod geometry;
+pub mod quoting;
+pub mod selection;
+pub mod full;
+pub mod estimate;
+pub mod sample;
+pub mod subset;
@@ -0,0 +1,189 @@
+use std::{
+    io::Write,
+    sync::mpsc::{sync_channel, SyncSender, TrySendError},
+    thread,
+};
+
+use anyhow::{anyhow, Context, Result};
+
+/// Batches allowed in flight between two pipeline stages. When the destination is slower than
+/// the database, the upstream `send` blocks instead of queueing, so memory stays bounded by
+/// about `PIPELINE_DEPTH` batches per stage.
+pub const PIPELINE_DEPTH: usize = 4;
+
+/// Backpressure observed by one pipeline run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineStats {
+    /// Batches the producer had to wait for because the formatter was `depth` batches behind.
+    pub stalls: usize,
+}
+
+/// Runs a fetch -> format -> write pipeline connected by bounded channels.
+///
+/// `produce` runs on the calling thread (ODBC cursors are not `Send`) and hands each fetched
+/// batch to `emit`, which blocks while the formatter is `depth` batches behind. Formatting and
+/// writing run on scoped worker threads; `format` sees the batches one at a time, in order. A
+/// write failure stops the producer and is reported in preference to the resulting "pipeline
+/// closed" error.
+pub fn run_pipeline<T, W, F, P>(
+    depth: usize,
+    writer: &mut W,
+    format: F,
+    produce: P,
+) -> Result<(usize, PipelineStats)>
+where
+    T: Send,
+    W: Write + Send,
+    F: FnMut(T) -> String + Send,
+    P: FnOnce(&mut dyn FnMut(T) -> Result<()>) -> Result<usize>,
+{
+    let (batch_tx, batch_rx) = sync_channel::<T>(depth);
+    let (chunk_tx, chunk_rx) = sync_channel::<String>(depth);
+
+    thread::scope(|scope| {
+        let formatter = scope.spawn(move || {
+            let mut format = format;
+            for batch in batch_rx {
+                if chunk_tx.send(format(batch)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let sink = scope.spawn(move || -> Result<()> {
+            for chunk in chunk_rx {
+                writer
+                    .write_all(chunk.as_bytes())
+                    .context("Failed to write export chunk")?;
+            }
+            Ok(())
+        });
+
+        let mut stats = PipelineStats::default();
+        let produced = {
+            let mut emit = |batch: T| send_batch(&batch_tx, batch, &mut stats);
+            produce(&mut emit)
+        };
+        drop(batch_tx);
+
+        formatter
+            .join()
+            .map_err(|_| anyhow!("Export format stage panicked"))?;
+        sink.join()
+            .map_err(|_| anyhow!("Export write stage panicked"))??;
+        Ok((produced?, stats))
+    })
+}
+
+fn send_batch<T>(sender: &SyncSender<T>, batch: T, stats: &mut PipelineStats) -> Result<()> {
+    let batch = match sender.try_send(batch) {
+        Ok(()) => return Ok(()),
+        Err(TrySendError::Full(batch)) => {
+            stats.stalls += 1;
+            batch
+        }
+        Err(TrySendError::Disconnected(_)) => {
+            return Err(anyhow!(
+                "Export pipeline closed before all batches were written"
+            ))
+        }
+    };
+    sender
+        .send(batch)
+        .map_err(|_| anyhow!("Export pipeline closed before all batches were written"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_pipeline;
+    use std::io::{self, Write};
+
+    #[test]
+    fn pipeline_preserves_batch_order() {
+        let mut out = Vec::new();
+        let (count, _) = run_pipeline(
+            1,
+            &mut out,
+            |batch: Vec<u32>| format!("{:?};", batch),
+            |emit| {
+                for i in 0..10u32 {
+                    emit(vec![i, i + 1])?;
+                }
+                Ok(10)
+            },
+        )
+        .unwrap();
+
+        assert_eq!(count, 10);
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("[0, 1];[1, 2];"));
+        assert!(text.ends_with("[9, 10];"));
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pipeline_stops_producer_and_reports_write_error() {
+        let mut emitted = 0;
+        let err = run_pipeline(
+            1,
+            &mut FailingWriter,
+            |batch: u32| batch.to_string(),
+            |emit| {
+                for i in 0..1000u32 {
+                    emit(i)?;
+                    emitted += 1;
+                }
+                Ok(1000)
+            },
+        )
+        .unwrap_err();
+
+        assert!(format!("{:#}", err).contains("disk full"));
+        assert!(emitted < 1000, "producer should stop once the writer fails");
+    }
+
+    struct SlowWriter(Vec<u8>);
+
+    impl Write for SlowWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pipeline_counts_stalls_behind_a_slow_writer() {
+        let mut writer = SlowWriter(Vec::new());
+        let (count, stats) = run_pipeline(
+            1,
+            &mut writer,
+            |batch: u32| batch.to_string(),
+            |emit| {
+                for i in 0..20u32 {
+                    emit(i)?;
+                }
+                Ok(20)
+            },
+        )
+        .unwrap();
+
+        assert_eq!(count, 20);
+        assert!(stats.stalls > 0);
+        assert_eq!(writer.0.len(), "012345678910111213141516171819".len());
+    }
+}
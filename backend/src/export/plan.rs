@@ -0,0 +1,635 @@
+use std::{fmt::Write as FmtWrite, fs, path::Path};
+
+use anyhow::{Context, Result};
+
+use crate::models::{ApplyPlan, CompareResponse, PlanRisk, PlanStep};
+
+/// Position of a planned statement in the apply script. Dependents are dropped before what they
+/// depend on, and created after it; table and sequence drops run last so every dependent object
+/// is already gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PlanPhase {
+    DropTrigger,
+    DropForeignKey,
+    DropConstraint,
+    DropIndex,
+    CreateSequence,
+    CreateTable,
+    AlterTable,
+    AddConstraint,
+    AddForeignKey,
+    CreateIndex,
+    Comment,
+    CreateTrigger,
+    Other,
+    DropTable,
+    DropSequence,
+}
+
+struct Change {
+    phase: PlanPhase,
+    risk: PlanRisk,
+    description: String,
+    statement: String,
+    rollback: Option<String>,
+}
+
+/// Turns a statement diff of two DDL artifacts (left = deployed, right = wanted) into an ordered
+/// apply plan. Changed tables become column-level `ALTER TABLE` statements, other changed
+/// objects are dropped and recreated. Every step carries its inverse where one exists, and steps
+/// that can lose data are marked destructive.
+pub fn build_apply_plan(diff: &CompareResponse) -> ApplyPlan {
+    let mut changes = Vec::new();
+    let mut notes = Vec::new();
+
+    for entry in &diff.removed {
+        plan_removed(&entry.key, &entry.statement, &mut changes, &mut notes);
+    }
+    for entry in &diff.changed {
+        plan_changed(
+            &entry.key,
+            &entry.before,
+            &entry.after,
+            &mut changes,
+            &mut notes,
+        );
+    }
+    for entry in &diff.added {
+        plan_added(&entry.key, &entry.statement, &mut changes);
+    }
+
+    // Stable: within a phase removals come first, then changes, then additions.
+    changes.sort_by_key(|change| change.phase);
+
+    let steps = changes
+        .into_iter()
+        .enumerate()
+        .map(|(i, change)| PlanStep {
+            number: i + 1,
+            risk: change.risk,
+            description: change.description,
+            statement: change.statement,
+            rollback: change.rollback,
+        })
+        .collect();
+
+    ApplyPlan { steps, notes }
+}
+
+fn plan_added(key: &str, statement: &str, changes: &mut Vec<Change>) {
+    let (kind, name) = key.split_once(' ').unwrap_or((key, ""));
+    let (phase, rollback) = match kind {
+        "TABLE" => (
+            PlanPhase::CreateTable,
+            Some(format!("DROP TABLE {};", quote_identifier(name))),
+        ),
+        "INDEX" => (
+            PlanPhase::CreateIndex,
+            Some(format!("DROP INDEX {};", quote_identifier(name))),
+        ),
+        "SEQUENCE" => (
+            PlanPhase::CreateSequence,
+            Some(format!("DROP SEQUENCE {};", quote_identifier(name))),
+        ),
+        "CONSTRAINT" => (
+            constraint_phase(statement, false),
+            Some(drop_constraint(name)),
+        ),
+        "COMMENT" => (PlanPhase::Comment, Some(clear_comment(name))),
+        "TRIGGER" => (
+            PlanPhase::CreateTrigger,
+            Some(format!("DROP TRIGGER {};", quote_identifier(name))),
+        ),
+        "VIEW" | "PROCEDURE" | "FUNCTION" | "PACKAGE" => (
+            PlanPhase::Other,
+            Some(format!("DROP {} {};", kind, quote_identifier(name))),
+        ),
+        // Drops in a script only clear the way for its own CREATE statements.
+        "DROP" => return,
+        _ => (PlanPhase::Other, None),
+    };
+
+    let risk = if rollback.is_some() {
+        PlanRisk::Safe
+    } else {
+        PlanRisk::Review
+    };
+    changes.push(Change {
+        phase,
+        risk,
+        description: format!("create {}", describe(key)),
+        statement: statement.to_string(),
+        rollback,
+    });
+}
+
+fn plan_removed(key: &str, statement: &str, changes: &mut Vec<Change>, notes: &mut Vec<String>) {
+    let (kind, name) = key.split_once(' ').unwrap_or((key, ""));
+    let (phase, risk, drop) = match kind {
+        "TABLE" => (
+            PlanPhase::DropTable,
+            PlanRisk::Destructive,
+            format!("DROP TABLE {};", quote_identifier(name)),
+        ),
+        "INDEX" => (
+            PlanPhase::DropIndex,
+            PlanRisk::Review,
+            format!("DROP INDEX {};", quote_identifier(name)),
+        ),
+        "SEQUENCE" => (
+            PlanPhase::DropSequence,
+            PlanRisk::Destructive,
+            format!("DROP SEQUENCE {};", quote_identifier(name)),
+        ),
+        "CONSTRAINT" => (
+            constraint_phase(statement, true),
+            PlanRisk::Review,
+            drop_constraint(name),
+        ),
+        "COMMENT" => (PlanPhase::Comment, PlanRisk::Safe, clear_comment(name)),
+        "TRIGGER" => (
+            PlanPhase::DropTrigger,
+            PlanRisk::Review,
+            format!("DROP TRIGGER {};", quote_identifier(name)),
+        ),
+        "VIEW" | "PROCEDURE" | "FUNCTION" | "PACKAGE" => (
+            PlanPhase::Other,
+            PlanRisk::Review,
+            format!("DROP {} {};", kind, quote_identifier(name)),
+        ),
+        "DROP" => return,
+        _ => {
+            notes.push(format!(
+                "Statement only in the deployed script has no inverse and is not planned: {}",
+                statement
+            ));
+            return;
+        }
+    };
+
+    let description = match risk {
+        PlanRisk::Destructive => format!(
+            "drop {} (rollback restores structure, not data)",
+            describe(key)
+        ),
+        _ => format!("drop {}", describe(key)),
+    };
+    changes.push(Change {
+        phase,
+        risk,
+        description,
+        statement: drop,
+        rollback: Some(statement.to_string()),
+    });
+}
+
+fn plan_changed(
+    key: &str,
+    before: &str,
+    after: &str,
+    changes: &mut Vec<Change>,
+    notes: &mut Vec<String>,
+) {
+    let (kind, name) = key.split_once(' ').unwrap_or((key, ""));
+    match kind {
+        "TABLE" => plan_table_change(name, before, after, changes, notes),
+        "COMMENT" => changes.push(Change {
+            phase: PlanPhase::Comment,
+            risk: PlanRisk::Safe,
+            description: format!("update {}", describe(key)),
+            statement: after.to_string(),
+            rollback: Some(before.to_string()),
+        }),
+        "TRIGGER" | "VIEW" | "PROCEDURE" | "FUNCTION" | "PACKAGE"
+            if starts_with_create_or_replace(after) && starts_with_create_or_replace(before) =>
+        {
+            changes.push(Change {
+                phase: if kind == "TRIGGER" {
+                    PlanPhase::CreateTrigger
+                } else {
+                    PlanPhase::Other
+                },
+                risk: PlanRisk::Review,
+                description: format!("replace {}", describe(key)),
+                statement: after.to_string(),
+                rollback: Some(before.to_string()),
+            })
+        }
+        // Sequences are recreated before the tables that may use them, not in the final drops.
+        "SEQUENCE" => {
+            let sequence = quote_identifier(name);
+            changes.push(Change {
+                phase: PlanPhase::CreateSequence,
+                risk: PlanRisk::Destructive,
+                description: format!(
+                    "drop {} for recreation (current value is reset)",
+                    describe(key)
+                ),
+                statement: format!("DROP SEQUENCE {};", sequence),
+                rollback: Some(before.to_string()),
+            });
+            changes.push(Change {
+                phase: PlanPhase::CreateSequence,
+                risk: PlanRisk::Safe,
+                description: format!("recreate {}", describe(key)),
+                statement: after.to_string(),
+                rollback: Some(format!("DROP SEQUENCE {};", sequence)),
+            });
+        }
+        "DROP" => {}
+        _ => {
+            // Objects without an ALTER form are dropped with the old definition and recreated.
+            plan_removed(key, before, changes, notes);
+            plan_added(key, after, changes);
+        }
+    }
+}
+
+/// Column-level `ALTER TABLE` statements turning the `before` table definition into `after`.
+fn plan_table_change(
+    table: &str,
+    before: &str,
+    after: &str,
+    changes: &mut Vec<Change>,
+    notes: &mut Vec<String>,
+) {
+    let (Some((old_columns, old_options)), Some((new_columns, new_options))) =
+        (parse_create_table(before), parse_create_table(after))
+    else {
+        notes.push(format!(
+            "Table {} changed but its definition could not be parsed; review it manually",
+            table
+        ));
+        return;
+    };
+
+    let table_ident = quote_identifier(table);
+    let find = |columns: &[(String, String)], name: &str| {
+        columns
+            .iter()
+            .find(|(column, _)| column.eq_ignore_ascii_case(name))
+            .map(|(_, definition)| definition.clone())
+    };
+
+    for (column, old_definition) in &old_columns {
+        if find(&new_columns, column).is_none() {
+            changes.push(Change {
+                phase: PlanPhase::AlterTable,
+                risk: PlanRisk::Destructive,
+                description: format!(
+                    "drop column {} from {} (rollback restores structure, not data)",
+                    column, table
+                ),
+                statement: format!("ALTER TABLE {} DROP COLUMN {};", table_ident, column),
+                rollback: Some(format!(
+                    "ALTER TABLE {} ADD COLUMN {} {};",
+                    table_ident, column, old_definition
+                )),
+            });
+        }
+    }
+
+    for (column, new_definition) in &new_columns {
+        match find(&old_columns, column) {
+            None => {
+                let upper = new_definition.to_uppercase();
+                let needs_value = upper.ends_with("NOT NULL")
+                    && !upper.contains(" DEFAULT ")
+                    && !upper.contains("IDENTITY");
+                changes.push(Change {
+                    phase: PlanPhase::AlterTable,
+                    risk: if needs_value {
+                        PlanRisk::Review
+                    } else {
+                        PlanRisk::Safe
+                    },
+                    description: if needs_value {
+                        format!(
+                            "add NOT NULL column {} without default to {} (fails on non-empty tables)",
+                            column, table
+                        )
+                    } else {
+                        format!("add column {} to {}", column, table)
+                    },
+                    statement: format!(
+                        "ALTER TABLE {} ADD COLUMN {} {};",
+                        table_ident, column, new_definition
+                    ),
+                    rollback: Some(format!("ALTER TABLE {} DROP COLUMN {};", table_ident, column)),
+                });
+            }
+            Some(old_definition) if old_definition != *new_definition => {
+                let type_changed =
+                    !column_type(&old_definition).eq_ignore_ascii_case(column_type(new_definition));
+                changes.push(Change {
+                    phase: PlanPhase::AlterTable,
+                    risk: if type_changed {
+                        PlanRisk::Destructive
+                    } else {
+                        PlanRisk::Review
+                    },
+                    description: if type_changed {
+                        format!(
+                            "change type of {}.{} from {} to {} (may truncate or reject existing data)",
+                            table,
+                            column,
+                            column_type(&old_definition),
+                            column_type(new_definition)
+                        )
+                    } else {
+                        format!("modify column {}.{}", table, column)
+                    },
+                    statement: format!(
+                        "ALTER TABLE {} MODIFY {} {};",
+                        table_ident, column, new_definition
+                    ),
+                    rollback: Some(format!(
+                        "ALTER TABLE {} MODIFY {} {};",
+                        table_ident, column, old_definition
+                    )),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    if old_options != new_options {
+        notes.push(format!(
+            "Table {} storage options changed from '{}' to '{}'; apply them manually",
+            table, old_options, new_options
+        ));
+    }
+}
+
+/// Splits a normalized `CREATE TABLE` statement into `(column, definition)` pairs and the table
+/// options after the column list (collation, tablespace).
+fn parse_create_table(statement: &str) -> Option<(Vec<(String, String)>, String)> {
+    let open = statement.find('(')?;
+    let mut depth = 0;
+    let mut in_quote = false;
+    let mut close = None;
+    for (i, ch) in statement.char_indices().skip_while(|(i, _)| *i < open) {
+        match ch {
+            '\'' => in_quote = !in_quote,
+            '(' if !in_quote => depth += 1,
+            ')' if !in_quote => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+
+    let columns = split_top_level(&statement[open + 1..close])
+        .into_iter()
+        .filter_map(|item| {
+            let item = item.trim();
+            let (name, definition) = split_column_name(item)?;
+            Some((name.to_string(), definition.trim().to_string()))
+        })
+        .collect();
+    let options = statement[close + 1..]
+        .trim()
+        .trim_end_matches(';')
+        .trim()
+        .to_string();
+    Some((columns, options))
+}
+
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut in_quote = false;
+    let mut start = 0;
+    for (i, ch) in list.char_indices() {
+        match ch {
+            '\'' => in_quote = !in_quote,
+            '(' if !in_quote => depth += 1,
+            ')' if !in_quote => depth -= 1,
+            ',' if !in_quote && depth == 0 => {
+                items.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&list[start..]);
+    items
+}
+
+/// Quoted (or bare) column name and the rest of the column definition.
+fn split_column_name(item: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = item.strip_prefix('"') {
+        let end = rest.find('"')? + 2;
+        Some((&item[..end], &item[end..]))
+    } else {
+        item.split_once(' ')
+    }
+}
+
+/// Data type at the start of a column definition, e.g. `NUMBER(10, 2)`.
+fn column_type(definition: &str) -> &str {
+    let mut depth = 0;
+    for (i, ch) in definition.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ' ' if depth == 0 => return &definition[..i],
+            _ => {}
+        }
+    }
+    definition
+}
+
+fn constraint_phase(statement: &str, drop: bool) -> PlanPhase {
+    let foreign_key = statement.to_uppercase().contains("FOREIGN KEY");
+    match (foreign_key, drop) {
+        (true, true) => PlanPhase::DropForeignKey,
+        (true, false) => PlanPhase::AddForeignKey,
+        (false, true) => PlanPhase::DropConstraint,
+        (false, false) => PlanPhase::AddConstraint,
+    }
+}
+
+/// `ALTER TABLE ... DROP CONSTRAINT` for a `CONSTRAINT <table>.<name>` key.
+fn drop_constraint(name: &str) -> String {
+    let (table, constraint) = name.rsplit_once('.').unwrap_or(("", name));
+    format!(
+        "ALTER TABLE {} DROP CONSTRAINT {};",
+        quote_identifier(table),
+        quote_identifier(constraint)
+    )
+}
+
+/// Empties the comment of a `COMMENT <kind> <object>` key.
+fn clear_comment(name: &str) -> String {
+    let (kind, object) = name.split_once(' ').unwrap_or(("TABLE", name));
+    format!("COMMENT ON {} {} IS '';", kind, quote_identifier(object))
+}
+
+fn starts_with_create_or_replace(statement: &str) -> bool {
+    statement.to_uppercase().starts_with("CREATE OR REPLACE")
+}
+
+fn describe(key: &str) -> String {
+    let (kind, name) = key.split_once(' ').unwrap_or((key, ""));
+    if name.is_empty() {
+        return key.to_string();
+    }
+    format!("{} {}", kind.to_lowercase(), name)
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    identifier
+        .split('.')
+        .map(|part| format!("\"{}\"", part.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The apply script: steps in order, each annotated with its number and risk.
+pub fn render_apply_script(plan: &ApplyPlan) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "-- DM8 增量变更脚本（按步骤号顺序执行）");
+    let destructive = plan
+        .steps
+        .iter()
+        .filter(|step| step.risk == PlanRisk::Destructive)
+        .count();
+    let _ = writeln!(
+        out,
+        "-- 步骤数: {}，破坏性步骤: {}",
+        plan.steps.len(),
+        destructive
+    );
+    for note in &plan.notes {
+        let _ = writeln!(out, "-- 注意: {}", note);
+    }
+    for step in &plan.steps {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "-- 步骤 {:04} [{}] {}",
+            step.number,
+            step.risk.as_str(),
+            step.description
+        );
+        let _ = writeln!(out, "{}", step.statement);
+    }
+    out
+}
+
+/// The rollback script: inverses of the apply steps in reverse order. Steps without an inverse
+/// are listed as comments so the gap is visible.
+pub fn render_rollback_script(plan: &ApplyPlan) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "-- DM8 增量变更回滚脚本（撤销变更脚本中已执行的步骤，倒序执行）"
+    );
+    for step in plan.steps.iter().rev() {
+        let _ = writeln!(out);
+        match &step.rollback {
+            Some(rollback) => {
+                let _ = writeln!(out, "-- 回滚步骤 {:04}: {}", step.number, step.description);
+                let _ = writeln!(out, "{}", rollback);
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "-- 步骤 {:04} 无法自动回滚，请手工处理: {}",
+                    step.number, step.description
+                );
+            }
+        }
+    }
+    out
+}
+
+/// Writes `<artifact>.apply.sql` and `<artifact>.rollback.sql` next to the target DDL artifact
+/// and returns their paths.
+pub fn write_plan_files(artifact: &Path, plan: &ApplyPlan) -> Result<(String, String)> {
+    let apply_path = artifact.with_extension("apply.sql");
+    let rollback_path = artifact.with_extension("rollback.sql");
+    fs::write(&apply_path, render_apply_script(plan))
+        .with_context(|| format!("Failed to write apply script {}", apply_path.display()))?;
+    fs::write(&rollback_path, render_rollback_script(plan)).with_context(|| {
+        format!(
+            "Failed to write rollback script {}",
+            rollback_path.display()
+        )
+    })?;
+    Ok((
+        apply_path.to_string_lossy().to_string(),
+        rollback_path.to_string_lossy().to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_apply_plan, render_rollback_script};
+    use crate::export::compare::compare_ddl_scripts;
+    use crate::models::PlanRisk;
+
+    #[test]
+    fn table_change_becomes_column_alters_with_inverses() {
+        let left = "CREATE TABLE \"S\".\"A\" (\n    \"ID\" INT NOT NULL,\n    \"OLD\" VARCHAR(10) NULL,\n    \"AMOUNT\" NUMBER(10, 2) NULL\n);";
+        let right = "CREATE TABLE \"S\".\"A\" (\n    \"ID\" INT NOT NULL,\n    \"AMOUNT\" NUMBER(12, 2) NULL,\n    \"NOTE\" VARCHAR(50) DEFAULT 'x' NOT NULL\n);";
+
+        let plan = build_apply_plan(&compare_ddl_scripts(left, right));
+        let statements: Vec<&str> = plan.steps.iter().map(|s| s.statement.as_str()).collect();
+        assert_eq!(
+            statements,
+            vec![
+                "ALTER TABLE \"S\".\"A\" DROP COLUMN \"OLD\";",
+                "ALTER TABLE \"S\".\"A\" MODIFY \"AMOUNT\" NUMBER(12, 2) NULL;",
+                "ALTER TABLE \"S\".\"A\" ADD COLUMN \"NOTE\" VARCHAR(50) DEFAULT 'x' NOT NULL;",
+            ]
+        );
+        assert_eq!(plan.steps[0].risk, PlanRisk::Destructive);
+        assert_eq!(
+            plan.steps[0].rollback.as_deref(),
+            Some("ALTER TABLE \"S\".\"A\" ADD COLUMN \"OLD\" VARCHAR(10) NULL;")
+        );
+        assert_eq!(plan.steps[1].risk, PlanRisk::Destructive);
+        assert_eq!(plan.steps[2].risk, PlanRisk::Safe);
+        assert!(plan.notes.is_empty());
+
+        let rollback = render_rollback_script(&plan);
+        let drop_new = rollback.find("DROP COLUMN \"NOTE\"").unwrap();
+        let restore_old = rollback.find("ADD COLUMN \"OLD\"").unwrap();
+        assert!(drop_new < restore_old);
+    }
+
+    #[test]
+    fn steps_are_ordered_by_dependency_phase() {
+        let left = "CREATE TABLE \"S\".\"P\" (\n    \"ID\" INT NOT NULL\n);\nALTER TABLE \"S\".\"C\" ADD CONSTRAINT \"FK_C_P\" FOREIGN KEY (\"P_ID\") REFERENCES \"S\".\"P\" (\"ID\");\nCREATE INDEX \"S\".\"I_C\" ON \"S\".\"C\" (\"P_ID\");";
+        let right = "CREATE INDEX \"S\".\"I_C\" ON \"S\".\"C\" (\"P_ID\", \"ID\");\nCREATE SEQUENCE \"S\".\"SEQ_C\" START WITH 1;";
+
+        let plan = build_apply_plan(&compare_ddl_scripts(left, right));
+        let statements: Vec<&str> = plan.steps.iter().map(|s| s.statement.as_str()).collect();
+        assert_eq!(
+            statements,
+            vec![
+                "ALTER TABLE \"S\".\"C\" DROP CONSTRAINT \"FK_C_P\";",
+                "DROP INDEX \"S\".\"I_C\";",
+                "CREATE SEQUENCE \"S\".\"SEQ_C\" START WITH 1;",
+                "CREATE INDEX \"S\".\"I_C\" ON \"S\".\"C\" (\"P_ID\", \"ID\");",
+                "DROP TABLE \"S\".\"P\";",
+            ]
+        );
+        assert_eq!(plan.steps[4].risk, PlanRisk::Destructive);
+        assert_eq!(
+            plan.steps[2].rollback.as_deref(),
+            Some("DROP SEQUENCE \"S\".\"SEQ_C\";")
+        );
+        assert_eq!(
+            plan.steps.iter().map(|s| s.number).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+    }
+}
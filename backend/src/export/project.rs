@@ -0,0 +1,252 @@
+use std::{fmt::Write as FmtWrite, fs, path::Path};
+
+use anyhow::{ensure, Context, Result};
+use serde_json::Value;
+
+use crate::db::backend::DatabaseKind;
+use crate::models::{ConnectionConfig, ExportProfile, ExportProject, ExportRequest, ProjectMember};
+
+/// Index of a project run, written to the job directory.
+pub const PROJECT_ORDER_FILE: &str = "project_order.txt";
+
+/// Export request for one project member: the shared options with the member's connection,
+/// tables and target schema filled in. Defaults of `ExportRequest` apply to omitted options.
+pub fn member_request(project: &ExportProject, member: &ProjectMember) -> Result<ExportRequest> {
+    let mut fields = project.options.clone();
+    fields.insert("config".to_string(), serde_json::to_value(&member.config)?);
+    fields.insert("tables".to_string(), serde_json::to_value(&member.tables)?);
+    fields.insert(
+        "export_schema".to_string(),
+        serde_json::to_value(&member.export_schema)?,
+    );
+    fields.insert(
+        "include_data".to_string(),
+        Value::Bool(project.include_data),
+    );
+    serde_json::from_value(Value::Object(fields)).with_context(|| {
+        format!(
+            "Invalid shared options in project '{}' for schema {}",
+            project.name, member.config.schema
+        )
+    })
+}
+
+/// Checks a project before it is saved or run, so a bad member fails before anything is
+/// exported.
+pub fn validate_project(project: &ExportProject) -> Result<()> {
+    ensure!(
+        !project.name.trim().is_empty(),
+        "Project name must not be empty"
+    );
+    ensure!(
+        !project.members.is_empty(),
+        "Project '{}' has no schemas",
+        project.name
+    );
+    for member in &project.members {
+        member
+            .config
+            .validate()
+            .with_context(|| format!("Invalid connection for schema {}", member.config.schema))?;
+        let req = member_request(project, member)?;
+        ensure!(
+            !req.tables.is_empty() || req.all_tables,
+            "Schema {} in project '{}' selects no tables",
+            member.config.schema,
+            project.name
+        );
+    }
+    Ok(())
+}
+
+/// One-member project running `profile` over `config`, whose schema is replaced by the
+/// profile's.
+pub fn profile_project(profile: &ExportProfile, config: &ConnectionConfig) -> ExportProject {
+    ExportProject {
+        name: profile.name.clone(),
+        members: vec![ProjectMember {
+            config: ConnectionConfig {
+                schema: profile.schema.clone(),
+                ..config.clone()
+            },
+            tables: profile.tables.clone(),
+            export_schema: profile.export_schema.clone(),
+        }],
+        include_data: profile.include_data,
+        options: profile.options.clone(),
+    }
+}
+
+/// Checks a profile before it is saved. The connection is only known when the profile runs,
+/// so the options are checked against a placeholder one.
+pub fn validate_profile(profile: &ExportProfile) -> Result<()> {
+    ensure!(
+        !profile.name.trim().is_empty(),
+        "Profile name must not be empty"
+    );
+    ensure!(
+        !profile.schema.trim().is_empty(),
+        "Profile '{}' names no schema",
+        profile.name
+    );
+    let placeholder = ConnectionConfig {
+        db_type: DatabaseKind::Dm8,
+        host: String::new(),
+        port: 0,
+        username: String::new(),
+        password: String::new(),
+        schema: String::new(),
+        export_schema: None,
+        dsn: None,
+        ssl: false,
+        ssl_cert_path: None,
+        ssl_cert_password: None,
+        ssl_cipher: None,
+        login_timeout_secs: None,
+        query_timeout_secs: None,
+    };
+    let project = profile_project(profile, &placeholder);
+    let req = member_request(&project, &project.members[0])?;
+    ensure!(
+        !req.tables.is_empty() || req.all_tables,
+        "Profile '{}' selects no tables",
+        profile.name
+    );
+    Ok(())
+}
+
+/// Artifact directory of the member at `index` (zero-based) inside the job directory, e.g.
+/// `02_FINANCE`; the prefix keeps directory listings in export order.
+pub fn member_dir_name(index: usize, schema: &str) -> String {
+    format!("{:02}_{}", index + 1, schema.trim())
+}
+
+/// Writes the execution order of a project's files: every member's DDL first, so cross-schema
+/// references resolve, then every member's data, both in member order.
+pub fn write_project_order(
+    output_dir: &Path,
+    project: &ExportProject,
+    ddl_files: &[String],
+    data_files: &[String],
+) -> Result<()> {
+    let mut out = String::new();
+    let _ = writeln!(out, "# 项目 {} 执行顺序", project.name);
+    let _ = writeln!(out, "# 按步骤号依次执行，路径相对于本目录");
+    let _ = writeln!(out, "# 步骤\t类型\t文件");
+    let files = ddl_files
+        .iter()
+        .map(|file| ("ddl", file))
+        .chain(data_files.iter().map(|file| ("data", file)));
+    for (i, (kind, file)) in files.enumerate() {
+        let _ = writeln!(out, "{:04}\t{}\t{}", i + 1, kind, file);
+    }
+
+    let path = output_dir.join(PROJECT_ORDER_FILE);
+    fs::write(&path, out)
+        .with_context(|| format!("Failed to write project order {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        member_dir_name, member_request, profile_project, validate_profile, validate_project,
+    };
+    use crate::db::backend::DatabaseKind;
+    use crate::models::{
+        ConnectionConfig, DataFormat, ExportEncoding, ExportProfile, ExportProject, ProjectMember,
+    };
+    use serde_json::json;
+
+    fn member(schema: &str) -> ProjectMember {
+        ProjectMember {
+            config: ConnectionConfig {
+                db_type: DatabaseKind::Dm8,
+                host: "localhost".into(),
+                port: 5236,
+                username: "SYSDBA".into(),
+                password: "SYSDBA".into(),
+                schema: schema.into(),
+                export_schema: None,
+                dsn: None,
+                ssl: false,
+                ssl_cert_path: None,
+                ssl_cert_password: None,
+                ssl_cipher: None,
+                login_timeout_secs: None,
+                query_timeout_secs: None,
+            },
+            tables: vec!["ORDERS".to_string()],
+            export_schema: Some(format!("{}_NEW", schema)),
+        }
+    }
+
+    fn project(options: serde_json::Value) -> ExportProject {
+        ExportProject {
+            name: "erp".to_string(),
+            members: vec![member("SALES"), member("FINANCE")],
+            include_data: true,
+            options: options.as_object().cloned().unwrap_or_default(),
+        }
+    }
+
+    #[test]
+    fn member_request_combines_shared_options_with_member_fields() {
+        let project = project(json!({ "encoding": "gbk", "batch_size": 500, "tables": ["X"] }));
+        let req = member_request(&project, &project.members[1]).unwrap();
+
+        assert_eq!(req.config.schema, "FINANCE");
+        assert_eq!(req.tables, vec!["ORDERS".to_string()]);
+        assert_eq!(req.export_schema.as_deref(), Some("FINANCE_NEW"));
+        assert_eq!(req.encoding, ExportEncoding::Gbk);
+        assert_eq!(req.batch_size, Some(500));
+        assert!(req.include_data);
+        assert!(req.drop_existing);
+        assert_eq!(member_dir_name(1, "FINANCE"), "02_FINANCE");
+    }
+
+    #[test]
+    fn validation_rejects_bad_options_and_empty_members() {
+        assert!(validate_project(&project(json!({}))).is_ok());
+        assert!(validate_project(&project(json!({ "encoding": "latin1" }))).is_err());
+
+        let mut empty = project(json!({}));
+        empty.members[0].tables.clear();
+        assert!(validate_project(&empty).is_err());
+        empty.options.insert("all_tables".to_string(), json!(true));
+        assert!(validate_project(&empty).is_ok());
+    }
+
+    #[test]
+    fn profiles_run_as_one_member_projects_on_the_given_connection() {
+        let profile = ExportProfile {
+            name: "nightly".to_string(),
+            schema: "FINANCE".to_string(),
+            export_schema: None,
+            tables: vec!["LEDGER".to_string()],
+            include_data: true,
+            options: json!({ "data_format": "jsonl" })
+                .as_object()
+                .cloned()
+                .unwrap(),
+        };
+        assert!(validate_profile(&profile).is_ok());
+
+        let project = profile_project(&profile, &member("SALES").config);
+        assert!(validate_project(&project).is_ok());
+        let req = member_request(&project, &project.members[0]).unwrap();
+        assert_eq!(req.config.schema, "FINANCE");
+        assert_eq!(req.config.host, "localhost");
+        assert_eq!(req.tables, vec!["LEDGER".to_string()]);
+        assert_eq!(req.data_format, DataFormat::Jsonl);
+        assert!(req.include_data);
+
+        let mut bad = profile.clone();
+        bad.options.insert("data_format".to_string(), json!("pdf"));
+        assert!(validate_profile(&bad).is_err());
+        bad = ExportProfile {
+            tables: Vec::new(),
+            ..profile
+        };
+        assert!(validate_profile(&bad).is_err());
+    }
+}
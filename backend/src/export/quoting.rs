@@ -0,0 +1,161 @@
+//! Identifier quoting per `quote_style`. Statements are generated with every identifier quoted;
+//! [`apply_quote_style`] then drops the quotes the style does not want, leaving string literals
+//! and comments untouched.
+
+use crate::models::QuoteStyle;
+
+/// DM8 reserved words, which must stay quoted when used as names.
+const RESERVED_WORDS: &str =
+    "ABORT ABSOLUTE ADD AFTER ALL ALTER AND ANY ARRAY AS ASC ASSIGN AUDIT AUTHORIZATION BEFORE \
+     BEGIN BETWEEN BIGINT BINARY BIT BLOB BOOLEAN BOTH BREAK BY BYTE CALL CASCADE CASE CAST CATCH \
+     CHAR CHARACTER CHECK CLOB CLUSTER COLUMN COMMENT COMMIT CONNECT CONNECT_BY_ROOT CONSTRAINT \
+     CONTAINS CONTEXT CONTINUE CONVERT CREATE CROSS CURRENT CURSOR DATE DATETIME DEC DECIMAL \
+     DECLARE DEFAULT DELETE DESC DISTINCT DOMAIN DOUBLE DROP ELSE ELSEIF ELSIF END EXCEPT \
+     EXCEPTION EXEC EXECUTE EXISTS EXIT EXPLAIN EXTERNAL EXTRACT FALSE FETCH FLOAT FOR FOREIGN \
+     FROM FULL FUNCTION GOTO GRANT GROUP HAVING IDENTITY IF IMAGE IMMEDIATE IN INDEX INNER INSERT \
+     INT INTEGER INTERSECT INTERVAL INTO IS JOIN LEADING LEFT LEVEL LIKE LIMIT LOOP MINUS NATURAL \
+     NEW NEXT NOCYCLE NOT NULL NUMBER NUMERIC OF OFFSET ON OR ORDER OUT OUTER OVERLAPS PACKAGE \
+     PARTITION PERCENT PRIMARY PRIOR PRIVILEGES PROCEDURE PUBLIC RAISE REAL REFERENCES RETURN \
+     REVOKE RIGHT ROLLBACK ROW ROWID ROWNUM ROWS SAVEPOINT SCHEMA SELECT SET SMALLINT SOME \
+     SYNONYM SYSDATE TABLE THEN TIME TIMESTAMP TINYINT TO TOP TRAILING TRIGGER TRUE TRUNCATE TYPE \
+     UNION UNIQUE UNTIL UPDATE USER USING VALUES VARCHAR VIEW WHEN WHERE WHILE WITH";
+
+fn is_reserved_word(name: &str) -> bool {
+    RESERVED_WORDS.split_whitespace().any(|word| word == name)
+}
+
+/// Whether `name` only survives as a quoted identifier: a reserved word, or anything besides
+/// an upper-case letter followed by upper-case letters, digits, `_`, `$` and `#` (DM8 folds
+/// unquoted names to upper case).
+pub fn needs_quotes(name: &str) -> bool {
+    let mut chars = name.chars();
+    let plain = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || "_$#".contains(c));
+    !plain || is_reserved_word(name)
+}
+
+fn quote_part(part: &str, style: QuoteStyle) -> String {
+    let quoted = match style {
+        QuoteStyle::Always => true,
+        QuoteStyle::WhenNeeded => needs_quotes(part),
+        QuoteStyle::Never => false,
+    };
+    if quoted {
+        format!("\"{}\"", part.replace('"', "\"\""))
+    } else {
+        part.to_string()
+    }
+}
+
+/// Quotes each dot-separated part of `identifier` as `style` asks.
+pub fn quote_name(identifier: &str, style: QuoteStyle) -> String {
+    identifier
+        .split('.')
+        .map(|part| quote_part(part, style))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Rewrites the quoted identifiers of `sql` for `style`. String literals, comments and, with
+/// `when_needed`, identifiers that need their quotes are copied unchanged.
+pub fn apply_quote_style(sql: &str, style: QuoteStyle) -> String {
+    if style == QuoteStyle::Always {
+        return sql.to_string();
+    }
+
+    let mut out = String::with_capacity(sql.len());
+    let mut rest = sql;
+    while let Some(pos) = rest.find(['\'', '"', '-', '/']) {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let skipped = if rest.starts_with('\'') {
+            literal_len(rest)
+        } else if rest.starts_with("--") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            rest.find("*/").map_or(rest.len(), |end| end + 2)
+        } else if rest.starts_with('"') {
+            let len = literal_len(rest);
+            let name = &rest[1..len.max(2) - 1];
+            if len > 1 && rest[..len].ends_with('"') {
+                let name = name.replace("\"\"", "\"");
+                if style == QuoteStyle::Never || !needs_quotes(&name) {
+                    out.push_str(&name);
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+            len
+        } else {
+            1
+        };
+        out.push_str(&rest[..skipped]);
+        rest = &rest[skipped..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Length of the `'...'` or `"..."` token at the start of `text`, doubled delimiters included;
+/// the whole text when it is not closed.
+pub fn literal_len(text: &str) -> usize {
+    let delimiter = &text[..1];
+    let mut pos = 1;
+    while let Some(offset) = text[pos..].find(delimiter) {
+        pos += offset + 1;
+        if !text[pos..].starts_with(delimiter) {
+            return pos;
+        }
+        pos += 1;
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_need_quotes_when_reserved_or_not_plain() {
+        assert!(!needs_quotes("ORDERS"));
+        assert!(!needs_quotes("ORDER_ITEMS_2"));
+        assert!(!needs_quotes("TMP$LOG#1"));
+        assert!(needs_quotes("ORDER"));
+        assert!(needs_quotes("USER"));
+        assert!(needs_quotes("Orders"));
+        assert!(needs_quotes("ORDER ITEMS"));
+        assert!(needs_quotes("1ST"));
+        assert!(needs_quotes("订单"));
+        assert!(needs_quotes(""));
+    }
+
+    #[test]
+    fn names_are_quoted_per_style() {
+        let always = quote_name("APP.ORDERS", QuoteStyle::Always);
+        assert_eq!(always, "\"APP\".\"ORDERS\"");
+        let when_needed = quote_name("APP.ORDER", QuoteStyle::WhenNeeded);
+        assert_eq!(when_needed, "APP.\"ORDER\"");
+        assert_eq!(quote_name("APP.Orders", QuoteStyle::Never), "APP.Orders");
+    }
+
+    #[test]
+    fn statements_drop_quotes_outside_literals_and_comments() {
+        let sql = "-- \"APP\".\"T\"\nCOMMENT ON COLUMN \"APP\".\"T\".\"ORDER\" IS 'say \"HI\"'; \
+                   /* \"X\" */ SELECT \"a\"\"b\", \"Mixed\" FROM \"APP\".\"T\"";
+        assert_eq!(apply_quote_style(sql, QuoteStyle::Always), sql);
+        assert_eq!(
+            apply_quote_style(sql, QuoteStyle::WhenNeeded),
+            "-- \"APP\".\"T\"\nCOMMENT ON COLUMN APP.T.\"ORDER\" IS 'say \"HI\"'; \
+             /* \"X\" */ SELECT \"a\"\"b\", \"Mixed\" FROM APP.T"
+        );
+        assert_eq!(
+            apply_quote_style(sql, QuoteStyle::Never),
+            "-- \"APP\".\"T\"\nCOMMENT ON COLUMN APP.T.ORDER IS 'say \"HI\"'; \
+             /* \"X\" */ SELECT a\"b, Mixed FROM APP.T"
+        );
+        assert_eq!(
+            apply_quote_style("x - y / 2 'it''s' \"OPEN", QuoteStyle::Never),
+            "x - y / 2 'it''s' \"OPEN"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use crate::models::TableDetails;
+
+/// Target name for `table` (case-insensitive match on the source name), or the name itself when
+/// the table is not renamed.
+pub fn renamed_table(renames: &HashMap<String, String>, table: &str) -> String {
+    lookup(renames, table).unwrap_or(table).to_string()
+}
+
+fn lookup<'a>(renames: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    renames
+        .iter()
+        .find(|(from, to)| from.trim().eq_ignore_ascii_case(name) && !to.trim().is_empty())
+        .map(|(_, to)| to.trim())
+}
+
+/// Renames tables (source name -> target name) so the DDL can create them next to the originals
+/// in the same schema.
+///
+/// Besides the table name this rewrites foreign keys referencing a renamed table of `schema`,
+/// trigger targets, and index, constraint and trigger names that embed the source table name,
+/// since those names must be unique per schema as well.
+pub fn apply_table_renames(
+    tables: &mut [TableDetails],
+    schema: &str,
+    renames: &HashMap<String, String>,
+) {
+    if renames.is_empty() {
+        return;
+    }
+
+    for table in tables.iter_mut() {
+        if let Some(to) = lookup(renames, &table.name) {
+            let from = table.name.clone();
+            for index in &mut table.indexes {
+                index.name = replace_table_name(&index.name, &from, to);
+            }
+            for uc in &mut table.unique_constraints {
+                uc.name = replace_table_name(&uc.name, &from, to);
+            }
+            for ck in &mut table.check_constraints {
+                ck.name = replace_table_name(&ck.name, &from, to);
+            }
+            for fk in &mut table.foreign_keys {
+                fk.name = replace_table_name(&fk.name, &from, to);
+            }
+            for trigger in &mut table.triggers {
+                trigger.name = replace_table_name(&trigger.name, &from, to);
+                trigger.table_name = to.to_string();
+            }
+            table.name = to.to_string();
+        }
+
+        for fk in &mut table.foreign_keys {
+            let Some((owner, referenced)) = fk.referenced_table.split_once('.') else {
+                continue;
+            };
+            if !owner.eq_ignore_ascii_case(schema) {
+                continue;
+            }
+            if let Some(to) = lookup(renames, referenced) {
+                fk.referenced_table = format!("{}.{}", owner, to);
+            }
+        }
+    }
+}
+
+/// Replaces the first case-insensitive occurrence of `table` in an object name.
+fn replace_table_name(name: &str, table: &str, to: &str) -> String {
+    // ASCII upper-casing keeps byte offsets aligned with `name`.
+    match name.to_ascii_uppercase().find(&table.to_ascii_uppercase()) {
+        Some(start) if !table.is_empty() => {
+            format!("{}{}{}", &name[..start], to, &name[start + table.len()..])
+        }
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_table_renames, renamed_table};
+    use crate::models::{ForeignKey, Index, TableDetails, TriggerDefinition};
+    use std::collections::HashMap;
+
+    fn table(name: &str, references: Option<&str>) -> TableDetails {
+        TableDetails {
+            indexes: vec![Index {
+                name: format!("IDX_{}_CODE", name),
+                columns: vec!["CODE".to_string()],
+                column_specs: Vec::new(),
+                unique: false,
+                tablespace: None,
+            }],
+            foreign_keys: references
+                .map(|referenced| ForeignKey {
+                    name: format!("FK_{}_PARENT", name),
+                    columns: vec!["PARENT_ID".to_string()],
+                    referenced_table: referenced.to_string(),
+                    referenced_columns: vec!["ID".to_string()],
+                    delete_rule: None,
+                    update_rule: None,
+                })
+                .into_iter()
+                .collect(),
+            triggers: vec![TriggerDefinition {
+                name: format!("TRG_{}_BI", name),
+                table_name: name.to_string(),
+                timing: "BEFORE".to_string(),
+                events: vec!["INSERT".to_string()],
+                each_row: true,
+                body: "NULL;".to_string(),
+            }],
+            ..TableDetails::named(name, Vec::new(), &[])
+        }
+    }
+
+    #[test]
+    fn renames_table_and_derived_object_names() {
+        let mut tables = vec![table("ORDERS", Some("APP.CUSTOMERS"))];
+        let renames = HashMap::from([("orders".to_string(), "ORDERS_V2".to_string())]);
+
+        apply_table_renames(&mut tables, "APP", &renames);
+
+        let orders = &tables[0];
+        assert_eq!(orders.name, "ORDERS_V2");
+        assert_eq!(orders.indexes[0].name, "IDX_ORDERS_V2_CODE");
+        assert_eq!(orders.foreign_keys[0].name, "FK_ORDERS_V2_PARENT");
+        assert_eq!(orders.foreign_keys[0].referenced_table, "APP.CUSTOMERS");
+        assert_eq!(orders.triggers[0].name, "TRG_ORDERS_V2_BI");
+        assert_eq!(orders.triggers[0].table_name, "ORDERS_V2");
+    }
+
+    #[test]
+    fn rewrites_references_only_within_the_schema() {
+        let mut tables = vec![
+            table("ITEMS", Some("APP.ORDERS")),
+            table("AUDIT", Some("HIST.ORDERS")),
+        ];
+        let renames = HashMap::from([("ORDERS".to_string(), "ORDERS_V2".to_string())]);
+
+        apply_table_renames(&mut tables, "app", &renames);
+
+        assert_eq!(tables[0].name, "ITEMS");
+        assert_eq!(tables[0].foreign_keys[0].referenced_table, "APP.ORDERS_V2");
+        assert_eq!(tables[1].foreign_keys[0].referenced_table, "HIST.ORDERS");
+        assert_eq!(renamed_table(&renames, "orders"), "ORDERS_V2");
+        assert_eq!(renamed_table(&renames, "ITEMS"), "ITEMS");
+    }
+}
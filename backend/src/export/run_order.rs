@@ -0,0 +1,305 @@
+use std::{fmt::Write as FmtWrite, fs, path::Path};
+
+use anyhow::{Context, Result};
+
+/// Execution phase of a generated statement, written next to its step number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Schema,
+    Table,
+    Comment,
+    Constraint,
+    Index,
+    ForeignKey,
+    Sequence,
+    Trigger,
+    View,
+    Grant,
+}
+
+impl Phase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::Schema => "schema",
+            Phase::Table => "table",
+            Phase::Comment => "comment",
+            Phase::Constraint => "constraint",
+            Phase::Index => "index",
+            Phase::ForeignKey => "foreign_key",
+            Phase::Sequence => "sequence",
+            Phase::Trigger => "trigger",
+            Phase::View => "view",
+            Phase::Grant => "grant",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [
+            Phase::Schema,
+            Phase::Table,
+            Phase::Comment,
+            Phase::Constraint,
+            Phase::Index,
+            Phase::ForeignKey,
+            Phase::Sequence,
+            Phase::Trigger,
+            Phase::View,
+            Phase::Grant,
+        ]
+        .into_iter()
+        .find(|phase| phase.as_str() == value)
+    }
+}
+
+struct Step {
+    number: usize,
+    phase: Phase,
+    file: String,
+    summary: String,
+}
+
+/// Numbers statements in execution order across every file of one export, so DBAs running
+/// parts of it in different tools can tell what has already been applied.
+#[derive(Default)]
+pub struct RunOrder {
+    steps: Vec<Step>,
+}
+
+impl RunOrder {
+    /// Registers the statement as the next step and returns it prefixed with its annotation,
+    /// with its blank lines removed (see [`without_blank_lines`]).
+    pub fn annotate(&mut self, phase: Phase, file: &str, statement: &str) -> String {
+        let number = self.steps.len() + 1;
+        self.steps.push(Step {
+            number,
+            phase,
+            file: file.to_string(),
+            summary: summarize(statement),
+        });
+        format!(
+            "{}\n{}",
+            step_comment(number, phase),
+            without_blank_lines(statement)
+        )
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# DM8 DDL 执行顺序索引");
+        let _ = writeln!(out, "# 按步骤号依次执行，每条语句前的注释标有相同的步骤号");
+        let _ = writeln!(out, "# 步骤\t阶段\t文件\t语句");
+        for step in &self.steps {
+            let _ = writeln!(
+                out,
+                "{:04}\t{}\t{}\t{}",
+                step.number,
+                step.phase.as_str(),
+                step.file,
+                step.summary
+            );
+        }
+        out
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Appends the steps of a script run after this one, numbered on from the last step here.
+    pub fn append(&mut self, other: RunOrder) {
+        let offset = self.steps.len();
+        self.steps.extend(other.steps.into_iter().map(|step| Step {
+            number: step.number + offset,
+            ..step
+        }));
+    }
+
+    /// Reads an index written by [`RunOrder::write_index`].
+    pub fn read_index(index_path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(index_path)
+            .with_context(|| format!("Failed to read run order index {}", index_path.display()))?;
+        let steps = content
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .map(|line| {
+                let mut fields = line.splitn(4, '\t');
+                let mut next = || fields.next().unwrap_or_default();
+                let (number, phase, file, summary) = (next(), next(), next(), next());
+                Some(Step {
+                    number: number.parse().ok()?,
+                    phase: Phase::parse(phase)?,
+                    file: file.to_string(),
+                    summary: summary.to_string(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .with_context(|| format!("Run order index {} is corrupt", index_path.display()))?;
+        Ok(Self { steps })
+    }
+
+    /// Writes the index as `<export>.run_order.txt` next to the main export file.
+    pub fn write_index(&self, output_path: &Path) -> Result<()> {
+        let index_path = output_path.with_extension("run_order.txt");
+        fs::write(&index_path, self.render())
+            .with_context(|| format!("Failed to write run order index {}", index_path.display()))
+    }
+}
+
+const STEP_COMMENT: &str = "-- 步骤 ";
+
+fn step_comment(number: usize, phase: Phase) -> String {
+    format!("{}{:04} [{}]", STEP_COMMENT, number, phase.as_str())
+}
+
+/// `line` with the number of its step comment moved up by `offset`, for a script run after
+/// `offset` steps of others; `None` when it is not a step comment.
+pub fn shift_step_comment(line: &str, offset: usize) -> Option<String> {
+    let rest = line.strip_prefix(STEP_COMMENT)?;
+    let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+    let number: usize = rest[..digits].parse().ok()?;
+    Some(format!(
+        "{}{:04}{}",
+        STEP_COMMENT,
+        number + offset,
+        &rest[digits..]
+    ))
+}
+
+/// Drops the blank lines of a statement, e.g. of a multi-line CHECK condition or view query
+/// copied from the catalog: DataGrip's run-as-script treats a blank line as the end of a
+/// statement. Blank lines inside string literals and quoted identifiers are part of the value
+/// and stay.
+pub fn without_blank_lines(statement: &str) -> String {
+    let mut out = String::with_capacity(statement.len());
+    let mut quote: Option<char> = None;
+    let mut in_block_comment = false;
+    for line in statement.split_inclusive('\n') {
+        if quote.is_none() && line.trim().is_empty() {
+            continue;
+        }
+        out.push_str(line);
+
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match quote {
+                Some(open) => {
+                    // A doubled quote closes and reopens, which leaves the state unchanged.
+                    if c == open {
+                        quote = None;
+                    }
+                }
+                None if in_block_comment => {
+                    if c == '*' && chars.next_if_eq(&'/').is_some() {
+                        in_block_comment = false;
+                    }
+                }
+                None => match c {
+                    '\'' | '"' => quote = Some(c),
+                    '-' if chars.peek() == Some(&'-') => break,
+                    '/' if chars.next_if_eq(&'*').is_some() => in_block_comment = true,
+                    _ => {}
+                },
+            }
+        }
+    }
+    out
+}
+
+/// First non-empty line of the statement, shortened for the index.
+fn summarize(statement: &str) -> String {
+    const MAX_CHARS: usize = 100;
+    let line = statement
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    if line.chars().count() > MAX_CHARS {
+        let truncated: String = line.chars().take(MAX_CHARS).collect();
+        format!("{}...", truncated)
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shift_step_comment, without_blank_lines, Phase, RunOrder};
+    use tempfile::TempDir;
+
+    #[test]
+    fn annotate_numbers_statements_across_files() {
+        let mut order = RunOrder::default();
+        let first = order.annotate(
+            Phase::Table,
+            "a.sql",
+            "CREATE TABLE \"S\".\"T\" (\n    \"ID\" INT\n);",
+        );
+        let second = order.annotate(
+            Phase::Trigger,
+            "a.triggers.sql",
+            "CREATE OR REPLACE TRIGGER X",
+        );
+
+        assert!(first.starts_with("-- 步骤 0001 [table]\nCREATE TABLE"));
+        assert!(second.starts_with("-- 步骤 0002 [trigger]\n"));
+
+        let index = order.render();
+        assert!(index.contains("0001\ttable\ta.sql\tCREATE TABLE \"S\".\"T\" ("));
+        assert!(index.contains("0002\ttrigger\ta.triggers.sql\tCREATE OR REPLACE TRIGGER X"));
+    }
+
+    #[test]
+    fn appended_orders_number_on_and_read_back_from_their_index() {
+        let dir = TempDir::new().unwrap();
+        let output_path = dir.path().join("a.sql");
+        let mut before = RunOrder::default();
+        before.annotate(Phase::Table, "a.sql", "CREATE TABLE T");
+        let mut after = RunOrder::default();
+        after.annotate(Phase::Index, "a.sql", "CREATE INDEX I");
+        after.annotate(Phase::ForeignKey, "a.sql", "ALTER TABLE T ADD FOREIGN KEY");
+        after.write_index(&output_path).unwrap();
+
+        let after = RunOrder::read_index(&output_path.with_extension("run_order.txt")).unwrap();
+        before.append(after);
+        assert_eq!(before.len(), 3);
+        let index = before.render();
+        assert!(index.contains("0001\ttable\ta.sql\tCREATE TABLE T"));
+        assert!(index.contains("0003\tforeign_key\ta.sql\tALTER TABLE T ADD FOREIGN KEY"));
+
+        assert_eq!(
+            shift_step_comment("-- 步骤 0002 [index]\r\n", 7).as_deref(),
+            Some("-- 步骤 0009 [index]\r\n")
+        );
+        assert_eq!(shift_step_comment("-- 执行顺序: 步骤 0001", 7), None);
+    }
+
+    #[test]
+    fn blank_lines_are_removed_outside_literals() {
+        let statement = concat!(
+            "ALTER TABLE \"S\".\"T\" ADD CONSTRAINT \"CK\" CHECK (\n\n",
+            "    \"A\" > 0\n   \n",
+            "    -- it's positive\n\n",
+            "    AND \"B\" <> 'x\n\ny'\n);",
+        );
+        assert_eq!(
+            without_blank_lines(statement),
+            concat!(
+                "ALTER TABLE \"S\".\"T\" ADD CONSTRAINT \"CK\" CHECK (\n",
+                "    \"A\" > 0\n",
+                "    -- it's positive\n",
+                "    AND \"B\" <> 'x\n\ny'\n);",
+            )
+        );
+
+        let mut order = RunOrder::default();
+        let annotated = order.annotate(Phase::View, "a.sql", "CREATE VIEW V AS\n\nSELECT 1;");
+        assert_eq!(
+            annotated,
+            "-- 步骤 0001 [view]\nCREATE VIEW V AS\nSELECT 1;"
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! Sampled data exports (`sample` in the request), for seeding test environments with a
+//! representative subset instead of full production volumes. A seeded sample orders or filters
+//! the rows by `ORA_HASH` of the primary key, so the same seed picks the same rows as long as
+//! they exist; unseeded samples and tables without a primary key use `SAMPLE` or `RAND()`.
+
+use anyhow::{bail, Result};
+
+use crate::export::data::quote_identifier;
+use crate::models::{SampleSpec, TableDetails};
+
+/// Buckets of the hash a seeded percentage sample compares against, one per 0.01 %.
+const PERCENT_BUCKETS: u32 = 10_000;
+
+/// Rejects a sample that sets neither or both of `percent` and `rows`, or an empty one.
+pub fn check_sample(spec: &SampleSpec) -> Result<()> {
+    match (spec.percent, spec.rows) {
+        (Some(percent), None) => {
+            if !(percent > 0.0 && percent <= 100.0) {
+                bail!(
+                    "sample.percent must be greater than 0 and at most 100, got {}",
+                    percent
+                );
+            }
+        }
+        (None, Some(0)) => bail!("sample.rows must be greater than zero"),
+        (None, Some(_)) => {}
+        _ => bail!("sample must set exactly one of percent and rows"),
+    }
+    if spec.consistent && spec.seed.is_none() {
+        bail!("sample.consistent needs a seed, so every table's query sees the same sample");
+    }
+    Ok(())
+}
+
+/// Whether `spec` picks the same rows of `details` in every run: unseeded samples do not
+/// promise to, seeded ones need a primary key to hash.
+pub fn is_reproducible(spec: &SampleSpec, details: &TableDetails) -> bool {
+    spec.seed.is_none() || !details.primary_keys.is_empty()
+}
+
+/// `-- Sample:` header text, e.g. `10% of the rows of each table (seed 42)`.
+pub fn sample_label(spec: &SampleSpec) -> String {
+    let share = match (spec.percent, spec.rows) {
+        (Some(percent), _) => format!("{}% of the rows", percent),
+        (None, Some(rows)) => format!("{} rows", rows),
+        (None, None) => "all rows".to_string(),
+    };
+    match spec.seed {
+        Some(seed) if spec.consistent => {
+            let roots = if spec.roots.is_empty() {
+                "each root table".to_string()
+            } else {
+                spec.roots.join(", ")
+            };
+            format!(
+                "{} of {} (seed {}) and the rows related to them through foreign keys",
+                share, roots, seed
+            )
+        }
+        Some(seed) => format!("{} of each table (seed {})", share, seed),
+        None => format!("{} of each table, picked at random", share),
+    }
+}
+
+/// Condition selecting the seeded sample of `table` (quoted, schema-qualified) by a hash of its
+/// primary key, for composing into other queries; `None` without a seed or primary key.
+pub fn sample_condition(table: &str, details: &TableDetails, spec: &SampleSpec) -> Option<String> {
+    let seed = spec.seed?;
+    if details.primary_keys.is_empty() {
+        return None;
+    }
+    let key = hash_key(details);
+    match (spec.percent, spec.rows) {
+        (Some(percent), _) if percent >= 100.0 => Some("1 = 1".to_string()),
+        (Some(percent), _) => Some(percent_filter(&key, seed, percent)),
+        (None, Some(rows)) => Some(format!(
+            "{} IN (SELECT TOP {} {} FROM {} ORDER BY ORA_HASH({}, {}, {}), {})",
+            column_tuple(&details.primary_keys),
+            rows,
+            primary_key_list(details),
+            table,
+            key,
+            u32::MAX,
+            seed,
+            primary_key_list(details)
+        )),
+        (None, None) => Some("1 = 1".to_string()),
+    }
+}
+
+/// `column IN` / `(a, b) IN` left-hand side for `columns`.
+pub fn column_tuple(columns: &[String]) -> String {
+    let quoted: Vec<String> = columns
+        .iter()
+        .map(|column| quote_identifier(column))
+        .collect();
+    match quoted.as_slice() {
+        [single] => single.clone(),
+        _ => format!("({})", quoted.join(", ")),
+    }
+}
+
+/// Rows whose key hashes into the first `percent` % of the buckets.
+fn percent_filter(key: &str, seed: u32, percent: f64) -> String {
+    let threshold = ((percent * f64::from(PERCENT_BUCKETS) / 100.0).round() as u32).max(1);
+    format!(
+        "ORA_HASH({}, {}, {}) < {}",
+        key,
+        PERCENT_BUCKETS - 1,
+        seed,
+        threshold
+    )
+}
+
+/// Query reading the sampled rows of `table` (quoted, schema-qualified) as `columns`.
+pub fn sample_query(
+    columns: &str,
+    table: &str,
+    details: &TableDetails,
+    spec: &SampleSpec,
+) -> String {
+    let seeded_key = spec
+        .seed
+        .filter(|_| !details.primary_keys.is_empty())
+        .map(|seed| (hash_key(details), seed));
+    match (spec.percent, spec.rows, seeded_key) {
+        (Some(percent), _, _) if percent >= 100.0 => {
+            format!("SELECT {} FROM {}", columns, table)
+        }
+        (Some(percent), _, Some((key, seed))) => format!(
+            "SELECT {} FROM {} WHERE {}",
+            columns,
+            table,
+            percent_filter(&key, seed, percent)
+        ),
+        (Some(percent), _, None) => {
+            format!("SELECT {} FROM {} SAMPLE({})", columns, table, percent)
+        }
+        (None, Some(rows), Some((key, seed))) => format!(
+            "SELECT TOP {} {} FROM {} ORDER BY ORA_HASH({}, {}, {}), {}",
+            rows,
+            columns,
+            table,
+            key,
+            u32::MAX,
+            seed,
+            primary_key_list(details)
+        ),
+        (None, Some(rows), None) => {
+            format!(
+                "SELECT TOP {} {} FROM {} ORDER BY RAND()",
+                rows, columns, table
+            )
+        }
+        (None, None, _) => format!("SELECT {} FROM {}", columns, table),
+    }
+}
+
+/// The primary key as one hashable expression; key columns are joined with `|` between them.
+fn hash_key(details: &TableDetails) -> String {
+    details
+        .primary_keys
+        .iter()
+        .map(|key| quote_identifier(key))
+        .collect::<Vec<_>>()
+        .join(" || '|' || ")
+}
+
+fn primary_key_list(details: &TableDetails) -> String {
+    details
+        .primary_keys
+        .iter()
+        .map(|key| quote_identifier(key))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(primary_keys: &[&str]) -> TableDetails {
+        TableDetails::named("ORDERS", Vec::new(), primary_keys)
+    }
+
+    fn spec(percent: Option<f64>, rows: Option<usize>, seed: Option<u32>) -> SampleSpec {
+        SampleSpec {
+            percent,
+            rows,
+            seed,
+            ..SampleSpec::default()
+        }
+    }
+
+    #[test]
+    fn samples_set_exactly_one_non_empty_share() {
+        assert!(check_sample(&spec(Some(10.0), None, Some(1))).is_ok());
+        assert!(check_sample(&spec(Some(100.0), None, None)).is_ok());
+        assert!(check_sample(&spec(None, Some(500), None)).is_ok());
+        assert!(check_sample(&spec(Some(0.0), None, None)).is_err());
+        assert!(check_sample(&spec(Some(120.0), None, None)).is_err());
+        assert!(check_sample(&spec(Some(f64::NAN), None, None)).is_err());
+        assert!(check_sample(&spec(None, Some(0), None)).is_err());
+        assert!(check_sample(&spec(Some(10.0), Some(5), None)).is_err());
+        assert!(check_sample(&spec(None, None, Some(1))).is_err());
+
+        let mut consistent = spec(Some(10.0), None, None);
+        consistent.consistent = true;
+        assert!(check_sample(&consistent).is_err());
+        consistent.seed = Some(1);
+        assert!(check_sample(&consistent).is_ok());
+    }
+
+    #[test]
+    fn seeded_samples_hash_the_primary_key() {
+        let composite = table(&["REGION", "ID"]);
+        assert_eq!(
+            sample_query(
+                "\"ID\"",
+                "\"APP\".\"ORDERS\"",
+                &composite,
+                &spec(Some(2.5), None, Some(7))
+            ),
+            "SELECT \"ID\" FROM \"APP\".\"ORDERS\" \
+             WHERE ORA_HASH(\"REGION\" || '|' || \"ID\", 9999, 7) < 250"
+        );
+        assert_eq!(
+            sample_query(
+                "\"ID\"",
+                "\"APP\".\"ORDERS\"",
+                &table(&["ID"]),
+                &spec(None, Some(50), Some(7))
+            ),
+            "SELECT TOP 50 \"ID\" FROM \"APP\".\"ORDERS\" \
+             ORDER BY ORA_HASH(\"ID\", 4294967295, 7), \"ID\""
+        );
+        assert!(is_reproducible(&spec(None, Some(50), Some(7)), &composite));
+
+        assert_eq!(
+            sample_condition("T", &composite, &spec(None, Some(50), Some(7))).unwrap(),
+            "(\"REGION\", \"ID\") IN (SELECT TOP 50 \"REGION\", \"ID\" FROM T \
+             ORDER BY ORA_HASH(\"REGION\" || '|' || \"ID\", 4294967295, 7), \"REGION\", \"ID\")"
+        );
+        assert_eq!(
+            sample_condition("T", &table(&[]), &spec(Some(10.0), None, Some(7))),
+            None
+        );
+    }
+
+    #[test]
+    fn unseeded_samples_and_tables_without_key_sample_at_random() {
+        let heap = table(&[]);
+        let seeded = spec(Some(10.0), None, Some(7));
+        assert_eq!(
+            sample_query("*", "T", &heap, &seeded),
+            "SELECT * FROM T SAMPLE(10)"
+        );
+        assert!(!is_reproducible(&seeded, &heap));
+        assert_eq!(
+            sample_query("*", "T", &table(&["ID"]), &spec(None, Some(5), None)),
+            "SELECT TOP 5 * FROM T ORDER BY RAND()"
+        );
+        assert_eq!(
+            sample_query("*", "T", &heap, &spec(Some(100.0), None, None)),
+            "SELECT * FROM T"
+        );
+        assert_eq!(
+            sample_label(&spec(Some(10.0), None, Some(42))),
+            "10% of the rows of each table (seed 42)"
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Table selection by exclusion. `exclude_patterns` drops matching tables from the requested
+//! list or, with `all_tables`, from every table of the schema. Patterns are matched against
+//! the whole name, ignoring case: globs where `*` and `%` match any run of characters and `?`
+//! one character, or regular expressions written as `re:<regex>`.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+
+const REGEX_PREFIX: &str = "re:";
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' | '%' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    pattern
+}
+
+/// Compiles `exclude_patterns`; blank entries are skipped and an invalid regex is an error
+/// naming the pattern.
+pub fn compile_exclude_patterns(patterns: &[String]) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|pattern| pattern.trim())
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| {
+            let source = match pattern.strip_prefix(REGEX_PREFIX) {
+                Some(regex) => format!("^(?:{})$", regex),
+                None => glob_to_regex(pattern),
+            };
+            Regex::new(&format!("(?i){}", source))
+                .with_context(|| format!("Invalid exclude pattern '{}'", pattern))
+        })
+        .collect()
+}
+
+/// `tables` without the names matching any of `patterns`, in their original order.
+pub fn exclude_tables(tables: Vec<String>, patterns: &[Regex]) -> Vec<String> {
+    tables
+        .into_iter()
+        .filter(|table| !patterns.iter().any(|pattern| pattern.is_match(table)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn globs_and_regexes_exclude_whole_names() {
+        let patterns =
+            compile_exclude_patterns(&names(&["TMP_%", "*_bak", " ", "re:LOG_\\d+"])).unwrap();
+        assert_eq!(patterns.len(), 3);
+        let tables = names(&[
+            "ORDERS",
+            "TMP_IMPORT",
+            "ORDERS_BAK",
+            "LOG_2024",
+            "LOG_ARCHIVE",
+            "MY_TMP_X",
+        ]);
+        assert_eq!(
+            exclude_tables(tables, &patterns),
+            names(&["ORDERS", "LOG_ARCHIVE", "MY_TMP_X"])
+        );
+    }
+
+    #[test]
+    fn glob_characters_other_than_wildcards_are_literal() {
+        let patterns = compile_exclude_patterns(&names(&["A.B?"])).unwrap();
+        let tables = names(&["A.BC", "AXBC", "A.BCD"]);
+        assert_eq!(exclude_tables(tables, &patterns), names(&["AXBC", "A.BCD"]));
+    }
+
+    #[test]
+    fn invalid_regex_names_the_pattern() {
+        let err = compile_exclude_patterns(&names(&["re:(unclosed"])).unwrap_err();
+        assert!(format!("{:#}", err).contains("re:(unclosed"));
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{Sequence, TableDetails};
+
+/// Keeps only the sequences referenced as `SEQ.NEXTVAL` / `SEQ.CURRVAL` by the column defaults
+/// or trigger bodies of the given tables.
+pub fn filter_referenced_sequences(
+    sequences: Vec<Sequence>,
+    tables: &[TableDetails],
+) -> Vec<Sequence> {
+    let referenced = referenced_sequence_names(tables);
+    sequences
+        .into_iter()
+        .filter(|seq| referenced.contains(&seq.name.to_uppercase()))
+        .collect()
+}
+
+/// Upper-cased names of every sequence referenced by the tables, without schema qualifiers.
+pub fn referenced_sequence_names(tables: &[TableDetails]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for table in tables {
+        for column in &table.columns {
+            if let Some(default) = column.default_value.as_deref() {
+                names.extend(sequence_refs(default).into_iter().map(|r| r.name));
+            }
+        }
+        for trigger in &table.triggers {
+            names.extend(sequence_refs(&trigger.body).into_iter().map(|r| r.name));
+        }
+    }
+    names
+}
+
+/// Columns filled from each sequence's `NEXTVAL`, keyed by upper-cased sequence name: columns
+/// whose default calls it, and the `:NEW.COL` a trigger statement assigns it to. Table and
+/// column names are the source names, in catalog case.
+pub fn sequence_fed_columns(tables: &[TableDetails]) -> HashMap<String, Vec<(String, String)>> {
+    let mut fed: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    let mut add = |sequence: String, table: &TableDetails, column: &str| {
+        let entry = (table.name.clone(), column.to_string());
+        let columns = fed.entry(sequence).or_default();
+        if !columns.contains(&entry) {
+            columns.push(entry);
+        }
+    };
+    for table in tables {
+        for column in &table.columns {
+            let Some(default) = column.default_value.as_deref() else {
+                continue;
+            };
+            for reference in sequence_refs(default) {
+                if reference.nextval {
+                    add(reference.name, table, &column.name);
+                }
+            }
+        }
+        for trigger in &table.triggers {
+            for reference in sequence_refs(&trigger.body) {
+                if !reference.nextval {
+                    continue;
+                }
+                let target = assigned_new_column(&trigger.body, reference.start).and_then(|name| {
+                    table
+                        .columns
+                        .iter()
+                        .find(|col| col.name.eq_ignore_ascii_case(&name))
+                });
+                if let Some(column) = target {
+                    add(reference.name, table, &column.name);
+                }
+            }
+        }
+    }
+    fed
+}
+
+/// The `:NEW.<column>` named in the trigger statement around byte `at`, as in
+/// `:NEW.ID := SEQ.NEXTVAL;` or `SELECT SEQ.NEXTVAL INTO :NEW.ID FROM DUAL;`.
+fn assigned_new_column(body: &str, at: usize) -> Option<String> {
+    let start = body[..at].rfind(';').map_or(0, |i| i + 1);
+    let end = body[at..].find(';').map_or(body.len(), |i| at + i);
+    let statement = &body[start..end];
+    let offset = statement.to_ascii_uppercase().find(":NEW.")?;
+    let rest = statement[offset + ":NEW.".len()..].trim_start();
+    let name = match rest.strip_prefix('"') {
+        Some(quoted) => &quoted[..quoted.find('"')?],
+        None => {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$' || c == '#'))
+                .unwrap_or(rest.len());
+            &rest[..len]
+        }
+    };
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Renames sequences (source name -> target name, case-insensitive) in the sequence list and in
+/// every `NEXTVAL`/`CURRVAL` reference of the tables' column defaults and trigger bodies.
+pub fn apply_sequence_renames(
+    sequences: &mut [Sequence],
+    tables: &mut [TableDetails],
+    renames: &HashMap<String, String>,
+) {
+    if renames.is_empty() {
+        return;
+    }
+    let renames: HashMap<String, &str> = renames
+        .iter()
+        .map(|(from, to)| (from.trim().to_uppercase(), to.trim()))
+        .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+        .collect();
+
+    for seq in sequences.iter_mut() {
+        if let Some(to) = renames.get(&seq.name.to_uppercase()) {
+            seq.name = to.to_string();
+        }
+    }
+    for table in tables.iter_mut() {
+        for column in &mut table.columns {
+            if let Some(default) = column.default_value.as_mut() {
+                *default = rename_sequence_refs(default, &renames);
+            }
+        }
+        for trigger in &mut table.triggers {
+            trigger.body = rename_sequence_refs(&trigger.body, &renames);
+        }
+    }
+}
+
+fn rename_sequence_refs(text: &str, renames: &HashMap<String, &str>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    for reference in sequence_refs(text) {
+        let Some(to) = renames.get(&reference.name) else {
+            continue;
+        };
+        out.push_str(&text[copied..reference.start]);
+        if reference.quoted {
+            out.push('"');
+            out.push_str(to);
+            out.push('"');
+        } else {
+            out.push_str(to);
+        }
+        copied = reference.end;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// A sequence name in front of `.NEXTVAL` / `.CURRVAL`; `start..end` covers the name including
+/// its quotes, but not a schema qualifier.
+struct SequenceRef {
+    start: usize,
+    end: usize,
+    name: String,
+    quoted: bool,
+    /// `.NEXTVAL` rather than `.CURRVAL`.
+    nextval: bool,
+}
+
+fn sequence_refs(text: &str) -> Vec<SequenceRef> {
+    // ASCII upper-casing keeps byte offsets aligned with `text`.
+    let upper = text.to_ascii_uppercase();
+    let mut refs = Vec::new();
+    for pseudo_column in [".NEXTVAL", ".CURRVAL"] {
+        let mut search_from = 0;
+        while let Some(offset) = upper[search_from..].find(pseudo_column) {
+            let end = search_from + offset;
+            search_from = end + pseudo_column.len();
+
+            let after = upper[search_from..].chars().next();
+            if after.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+            if let Some(mut reference) = identifier_before(&upper, end) {
+                reference.nextval = pseudo_column == ".NEXTVAL";
+                refs.push(reference);
+            }
+        }
+    }
+    refs.sort_by_key(|r| r.start);
+    refs
+}
+
+/// The identifier ending at byte `end`. Handles `SEQ`, `"SEQ"` and the last part of
+/// `SCHEMA.SEQ`.
+fn identifier_before(text: &str, end: usize) -> Option<SequenceRef> {
+    let head = text[..end].trim_end();
+    let end = head.len();
+    let (start, name, quoted) = if let Some(quoted) = head.strip_suffix('"') {
+        let start = quoted.rfind('"')?;
+        (start, &quoted[start + 1..], true)
+    } else {
+        let start = head
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$' || c == '#'))
+            .map(|i| i + head[i..].chars().next().map_or(1, char::len_utf8))
+            .unwrap_or(0);
+        (start, &head[start..], false)
+    };
+    (!name.is_empty()).then(|| SequenceRef {
+        start,
+        end,
+        name: name.to_string(),
+        quoted,
+        nextval: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_sequence_renames, referenced_sequence_names, sequence_fed_columns};
+    use crate::models::{Column, Sequence, TableDetails, TriggerDefinition};
+    use std::collections::HashMap;
+
+    fn table(default: Option<&str>, trigger_body: Option<&str>) -> TableDetails {
+        let id = Column {
+            nullable: false,
+            default_value: default.map(str::to_string),
+            ..Column::named("ID", "INT")
+        };
+        TableDetails {
+            triggers: trigger_body
+                .map(|body| TriggerDefinition {
+                    name: "TRG".to_string(),
+                    table_name: "T".to_string(),
+                    timing: "BEFORE".to_string(),
+                    events: vec!["INSERT".to_string()],
+                    each_row: true,
+                    body: body.to_string(),
+                })
+                .into_iter()
+                .collect(),
+            ..TableDetails::named("T", vec![id], &[])
+        }
+    }
+
+    #[test]
+    fn finds_sequences_in_defaults_and_trigger_bodies() {
+        let tables = vec![
+            table(Some("\"APP\".\"SEQ_USERS\".NEXTVAL"), None),
+            table(
+                None,
+                Some("BEGIN SELECT seq_orders.nextval INTO :NEW.ID FROM DUAL; END;"),
+            ),
+        ];
+
+        let names = referenced_sequence_names(&tables);
+        assert!(names.contains("SEQ_USERS"));
+        assert!(names.contains("SEQ_ORDERS"));
+        assert_eq!(names.len(), 2);
+    }
+
+    #[test]
+    fn ignores_lookalike_columns() {
+        let tables = vec![table(None, Some(":NEW.NEXTVAL_FLAG := 1;"))];
+        assert!(referenced_sequence_names(&tables).is_empty());
+    }
+
+    #[test]
+    fn finds_columns_fed_by_defaults_and_trigger_assignments() {
+        let mut assigned = table(None, Some("BEGIN :new.\"ID\" := seq_b.NEXTVAL; END;"));
+        assigned.name = "U".to_string();
+        let mut selected = table(
+            None,
+            Some("BEGIN SELECT SEQ_C.NEXTVAL INTO :NEW.id FROM DUAL; x := SEQ_B.CURRVAL; END;"),
+        );
+        selected.name = "V".to_string();
+        let tables = vec![table(Some("SEQ_A.NEXTVAL"), None), assigned, selected];
+
+        let fed = sequence_fed_columns(&tables);
+        let column = |table: &str| vec![(table.to_string(), "ID".to_string())];
+        assert_eq!(fed.get("SEQ_A"), Some(&column("T")));
+        assert_eq!(fed.get("SEQ_B"), Some(&column("U")));
+        assert_eq!(fed.get("SEQ_C"), Some(&column("V")));
+        assert_eq!(fed.len(), 3);
+    }
+
+    #[test]
+    fn renames_sequences_in_list_defaults_and_triggers() {
+        let mut sequences = vec![Sequence {
+            name: "SEQ_A".to_string(),
+            min_value: None,
+            max_value: None,
+            increment_by: 1,
+            cache_size: None,
+            cycle: false,
+            order: false,
+            start_with: Some(1),
+        }];
+        let mut tables = vec![
+            table(Some("\"SRC\".\"SEQ_A\".NEXTVAL"), None),
+            table(
+                None,
+                Some("SELECT seq_a.NEXTVAL, SEQ_OTHER.NEXTVAL INTO :NEW.ID FROM DUAL;"),
+            ),
+        ];
+        let renames = HashMap::from([("seq_a".to_string(), "SEQ_B".to_string())]);
+
+        apply_sequence_renames(&mut sequences, &mut tables, &renames);
+
+        assert_eq!(sequences[0].name, "SEQ_B");
+        assert_eq!(
+            tables[0].columns[0].default_value.as_deref(),
+            Some("\"SRC\".\"SEQ_B\".NEXTVAL")
+        );
+        assert_eq!(
+            tables[1].triggers[0].body,
+            "SELECT SEQ_B.NEXTVAL, SEQ_OTHER.NEXTVAL INTO :NEW.ID FROM DUAL;"
+        );
+    }
+}
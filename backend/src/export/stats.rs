@@ -0,0 +1,54 @@
+//! Per-table volume and timing of an export, returned as `ExportResponse.stats`.
+
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+/// Passes writes through to `inner` and counts the bytes accepted.
+pub struct CountingWriter<'a, W: Write> {
+    inner: &'a mut W,
+    bytes: u64,
+}
+
+impl<'a, W: Write> CountingWriter<'a, W> {
+    pub fn new(inner: &'a mut W) -> Self {
+        Self { inner, bytes: 0 }
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl<W: Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub fn duration_ms(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting_writer_counts_bytes_passed_through() {
+        let mut out = Vec::new();
+        let mut writer = CountingWriter::new(&mut out);
+        writeln!(writer, "INSERT INTO \"T\" VALUES ('é');").unwrap();
+        write!(writer, "COMMIT;").unwrap();
+        let bytes = writer.bytes();
+        assert_eq!(bytes, out.len() as u64);
+        assert_eq!(bytes, 38);
+    }
+}
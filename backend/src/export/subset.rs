@@ -0,0 +1,341 @@
+//! Referentially consistent sampling (`sample.consistent`): the root tables are sampled by a
+//! seeded hash of their primary key, then foreign keys are followed down to the rows that
+//! reference sampled rows and back up to every row those rows reference, so the subset loads
+//! without constraint violations. Every table's subset is a `WHERE` condition of nested
+//! `IN (SELECT ...)` queries over the source, as exports only read; the seed makes each query
+//! see the same sample.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::export::data::{quote_identifier, DataExportOptions};
+use crate::export::dependency::{load_order, same_schema_table};
+use crate::export::job::JobContext;
+use crate::export::sample::{column_tuple, sample_condition};
+use crate::models::{ForeignKey, IssueCode, SampleSpec, TableDetails};
+
+/// Longest subset condition of one table; nested conditions grow with every foreign key level.
+const MAX_FILTER_BYTES: usize = 1024 * 1024;
+
+/// Subset of every table in a consistent sample.
+#[derive(Debug, Default)]
+pub struct SubsetPlan {
+    /// `WHERE` condition per table (catalog name). Tables without one are sampled on their
+    /// own: roots without a primary key that no followed foreign key connects.
+    pub filters: HashMap<String, String>,
+    /// Foreign keys (table, constraint) the subset does not follow because they close a cycle.
+    pub unfollowed: Vec<(String, String)>,
+}
+
+/// A followed foreign key: `child` references `parent`, which loads first.
+struct Edge<'a> {
+    child: usize,
+    parent: usize,
+    fk: &'a ForeignKey,
+}
+
+/// Row filters of the consistent sample over `tables` (all tables of the export, of `schema`),
+/// by table name; empty unless the options ask for a consistent sample. Foreign keys the subset
+/// cannot follow are recorded as warnings.
+pub fn consistent_subset(
+    job: &mut JobContext,
+    schema: &str,
+    tables: &[TableDetails],
+    options: &DataExportOptions,
+) -> Result<HashMap<String, String>> {
+    let Some(spec) = options.sample.as_ref().filter(|spec| spec.consistent) else {
+        return Ok(HashMap::new());
+    };
+    let plan = plan_subset(schema, tables, spec)?;
+    for (table, constraint) in &plan.unfollowed {
+        job.warn(
+            IssueCode::SubsetForeignKeyUnfollowed,
+            Some(table),
+            format!(
+                "Foreign key {} of {} is on a cycle and not followed by the consistent sample; \
+                 its rows may reference rows left out",
+                constraint, table
+            ),
+        );
+    }
+    Ok(plan.filters)
+}
+
+/// Computes the subset of every table of `tables` for `spec`.
+pub fn plan_subset(schema: &str, tables: &[TableDetails], spec: &SampleSpec) -> Result<SubsetPlan> {
+    let positions: HashMap<String, usize> = tables
+        .iter()
+        .enumerate()
+        .map(|(i, table)| (table.name.to_uppercase(), i))
+        .collect();
+    let order = load_order(schema, tables).order;
+    let mut rank = vec![0; tables.len()];
+    for (position, &index) in order.iter().enumerate() {
+        rank[index] = position;
+    }
+
+    // Only keys pointing at tables that load earlier are followed; the rest close a cycle.
+    let mut edges = Vec::new();
+    let mut unfollowed = Vec::new();
+    for (child, table) in tables.iter().enumerate() {
+        for fk in &table.foreign_keys {
+            let Some(parent) = same_schema_table(schema, &fk.referenced_table)
+                .and_then(|name| positions.get(&name.to_uppercase()).copied())
+            else {
+                continue;
+            };
+            let columns_match =
+                !fk.columns.is_empty() && fk.columns.len() == fk.referenced_columns.len();
+            if rank[parent] < rank[child] && columns_match {
+                edges.push(Edge { child, parent, fk });
+            } else {
+                unfollowed.push((table.name.clone(), fk.name.clone()));
+            }
+        }
+    }
+
+    let mut roots = vec![false; tables.len()];
+    if spec.roots.is_empty() {
+        for (index, root) in roots.iter_mut().enumerate() {
+            *root = !edges.iter().any(|edge| edge.child == index);
+        }
+    } else {
+        for name in &spec.roots {
+            let Some(&index) = positions.get(&name.to_uppercase()) else {
+                bail!("Sample root {} is not among the exported tables", name);
+            };
+            roots[index] = true;
+        }
+    }
+
+    let idents: Vec<String> = tables
+        .iter()
+        .map(|table| quote_identifier(&format!("{}.{}", schema, table.name)))
+        .collect();
+    let connected = |index: usize| {
+        edges
+            .iter()
+            .any(|edge| edge.child == index || edge.parent == index)
+    };
+
+    // Down, parents first: a root's sample, or the rows referencing a parent's subset.
+    let mut own_sample = vec![false; tables.len()];
+    let mut down: Vec<Option<String>> = vec![None; tables.len()];
+    for &index in &order {
+        down[index] = if roots[index] {
+            match sample_condition(&idents[index], &tables[index], spec) {
+                Some(condition) => Some(condition),
+                None if connected(index) => bail!(
+                    "Consistent sampling needs a primary key on root table {}",
+                    tables[index].name
+                ),
+                None => {
+                    own_sample[index] = true;
+                    continue;
+                }
+            }
+        } else {
+            any_of(
+                edges
+                    .iter()
+                    .filter(|edge| edge.child == index)
+                    .filter_map(|edge| {
+                        let parent = down[edge.parent].as_deref()?;
+                        Some(reference_filter(
+                            &edge.fk.columns,
+                            &edge.fk.referenced_columns,
+                            &idents[edge.parent],
+                            parent,
+                        ))
+                    }),
+            )
+        };
+        check_size(&tables[index], down[index].as_deref())?;
+    }
+
+    // Up, children first: every row a row of the subset references joins the subset too.
+    let mut filters = HashMap::new();
+    let mut full: Vec<Option<String>> = vec![None; tables.len()];
+    for &index in order.iter().rev() {
+        if own_sample[index] {
+            continue;
+        }
+        let referenced = edges
+            .iter()
+            .filter(|edge| edge.parent == index)
+            .filter_map(|edge| {
+                let child = full[edge.child].as_deref()?;
+                Some(reference_filter(
+                    &edge.fk.referenced_columns,
+                    &edge.fk.columns,
+                    &idents[edge.child],
+                    child,
+                ))
+            });
+        let condition = any_of(down[index].clone().into_iter().chain(referenced));
+        check_size(&tables[index], condition.as_deref())?;
+        filters.insert(
+            tables[index].name.clone(),
+            condition.clone().unwrap_or_else(|| "1 = 0".to_string()),
+        );
+        full[index] = condition;
+    }
+
+    Ok(SubsetPlan {
+        filters,
+        unfollowed,
+    })
+}
+
+/// Rows whose `columns` match `other_columns` of the rows of `other` meeting `condition`.
+fn reference_filter(
+    columns: &[String],
+    other_columns: &[String],
+    other: &str,
+    condition: &str,
+) -> String {
+    let selected: Vec<String> = other_columns
+        .iter()
+        .map(|column| quote_identifier(column))
+        .collect();
+    format!(
+        "{} IN (SELECT {} FROM {} WHERE {})",
+        column_tuple(columns),
+        selected.join(", "),
+        other,
+        condition
+    )
+}
+
+/// Disjunction of `conditions`; `None` (no rows) when there are none.
+fn any_of(conditions: impl Iterator<Item = String>) -> Option<String> {
+    let conditions: Vec<String> = conditions.collect();
+    match conditions.len() {
+        0 => None,
+        1 => conditions.into_iter().next(),
+        _ => Some(format!("({})", conditions.join(") OR ("))),
+    }
+}
+
+fn check_size(table: &TableDetails, condition: Option<&str>) -> Result<()> {
+    if condition.is_some_and(|condition| condition.len() > MAX_FILTER_BYTES) {
+        bail!(
+            "The consistent sample of {} needs a query longer than {} bytes; \
+             export fewer related tables or name fewer sample roots",
+            table.name,
+            MAX_FILTER_BYTES
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(name: &str, primary_keys: &[&str], references: &[(&str, &str, &str)]) -> TableDetails {
+        TableDetails {
+            foreign_keys: references
+                .iter()
+                .map(|(column, parent, parent_column)| ForeignKey {
+                    name: format!("FK_{}_{}", name, column),
+                    columns: vec![column.to_string()],
+                    referenced_table: parent.to_string(),
+                    referenced_columns: vec![parent_column.to_string()],
+                    delete_rule: None,
+                    update_rule: None,
+                })
+                .collect(),
+            ..TableDetails::named(name, Vec::new(), primary_keys)
+        }
+    }
+
+    fn spec(roots: &[&str]) -> SampleSpec {
+        SampleSpec {
+            percent: Some(10.0),
+            seed: Some(7),
+            consistent: true,
+            roots: roots.iter().map(|root| root.to_string()).collect(),
+            ..SampleSpec::default()
+        }
+    }
+
+    fn shop() -> Vec<TableDetails> {
+        vec![
+            table(
+                "ITEMS",
+                &["ID"],
+                &[
+                    ("ORDER_ID", "ORDERS", "ID"),
+                    ("PRODUCT_ID", "PRODUCTS", "ID"),
+                ],
+            ),
+            table("ORDERS", &["ID"], &[("CUSTOMER_ID", "CUSTOMERS", "ID")]),
+            table("CUSTOMERS", &["ID"], &[]),
+            table("PRODUCTS", &["ID"], &[]),
+        ]
+    }
+
+    #[test]
+    fn subset_follows_children_down_and_parents_up() {
+        let plan = plan_subset("APP", &shop(), &spec(&["CUSTOMERS"])).unwrap();
+        let customers_sample = "ORA_HASH(\"ID\", 9999, 7) < 1000";
+        let orders_down = format!(
+            "\"CUSTOMER_ID\" IN (SELECT \"ID\" FROM \"APP\".\"CUSTOMERS\" WHERE {})",
+            customers_sample
+        );
+        let items = format!(
+            "\"ORDER_ID\" IN (SELECT \"ID\" FROM \"APP\".\"ORDERS\" WHERE {})",
+            orders_down
+        );
+        assert_eq!(plan.filters["ITEMS"], items);
+        assert_eq!(
+            plan.filters["PRODUCTS"],
+            format!(
+                "\"ID\" IN (SELECT \"PRODUCT_ID\" FROM \"APP\".\"ITEMS\" WHERE {})",
+                items
+            )
+        );
+        assert!(plan.filters["ORDERS"].starts_with(&format!("({}) OR (\"ID\" IN", orders_down)));
+        assert!(plan.filters["CUSTOMERS"].starts_with(&format!("({}) OR (", customers_sample)));
+        assert!(plan.unfollowed.is_empty());
+    }
+
+    #[test]
+    fn tables_referencing_no_exported_table_are_the_default_roots() {
+        let plan = plan_subset("APP", &shop(), &spec(&[])).unwrap();
+        assert!(plan.filters["ITEMS"]
+            .contains("\"PRODUCT_ID\" IN (SELECT \"ID\" FROM \"APP\".\"PRODUCTS\""));
+        assert!(plan.filters["ITEMS"]
+            .contains("\"ORDER_ID\" IN (SELECT \"ID\" FROM \"APP\".\"ORDERS\""));
+
+        let mut tables = shop();
+        tables.push(table("AUDIT_LOG", &[], &[]));
+        let plan = plan_subset("APP", &tables, &spec(&[])).unwrap();
+        assert!(!plan.filters.contains_key("AUDIT_LOG"));
+    }
+
+    #[test]
+    fn cycles_and_roots_without_keys_are_reported() {
+        let tables = vec![
+            table("EMPLOYEES", &["ID"], &[("MANAGER_ID", "EMPLOYEES", "ID")]),
+            table("BADGES", &["ID"], &[("EMPLOYEE_ID", "EMPLOYEES", "ID")]),
+        ];
+        let plan = plan_subset("APP", &tables, &spec(&[])).unwrap();
+        assert_eq!(
+            plan.unfollowed,
+            vec![(
+                "EMPLOYEES".to_string(),
+                "FK_EMPLOYEES_MANAGER_ID".to_string()
+            )]
+        );
+
+        assert!(plan_subset("APP", &tables, &spec(&["MISSING"])).is_err());
+        let keyless = vec![
+            table("CODES", &[], &[]),
+            table("USES", &["ID"], &[("CODE", "CODES", "CODE")]),
+        ];
+        assert!(plan_subset("APP", &keyless, &spec(&[])).is_err());
+    }
+}
@@ -0,0 +1,13 @@
+CREATE TABLE "APP"."CUSTOMERS" (
+    "ID" INT IDENTITY(1, 1) NOT NULL,
+    "NAME" VARCHAR(100 CHAR) NOT NULL,
+    "BALANCE" DECIMAL(12,2) DEFAULT 0 NULL,
+    "CREATED_AT" TIMESTAMP DEFAULT SYSDATE NOT NULL
+);
+
+COMMENT ON TABLE "APP"."CUSTOMERS" IS 'Registered customers';
+COMMENT ON COLUMN "APP"."CUSTOMERS"."NAME" IS 'Customer''s display name';
+
+ALTER TABLE "APP"."CUSTOMERS" ADD CONSTRAINT "PK_CUSTOMERS" PRIMARY KEY ("ID");
+
+CREATE INDEX "IDX_CUSTOMERS_NAME" ON "APP"."CUSTOMERS" ("NAME");
@@ -0,0 +1,19 @@
+CREATE TABLE "APP"."ORDERS" (
+    "ORDER_ID" BIGINT NOT NULL,
+    "CUSTOMER_ID" INT NOT NULL,
+    "ORDER_NO" VARCHAR(32 BYTE) COLLATE BINARY_CI NOT NULL,
+    "STATUS" CHAR(1 BYTE) DEFAULT 'N' NOT NULL,
+    "ORDERED_ON" DATE DEFAULT TO_DATE('2024-01-01','YYYY-MM-DD') NULL,
+    "NOTE" CLOB NULL
+) DEFAULT COLLATION BINARY_CS TABLESPACE "MAIN";
+
+ALTER TABLE "APP"."ORDERS" ADD CONSTRAINT "PK_ORDERS" PRIMARY KEY ("ORDER_ID");
+
+ALTER TABLE "APP"."ORDERS" ADD CONSTRAINT "UK_ORDERS_NO" UNIQUE ("ORDER_NO");
+
+ALTER TABLE "APP"."ORDERS" ADD CONSTRAINT "CK_ORDERS_STATUS" CHECK (STATUS IN ('N', 'P', 'D'));
+
+CREATE INDEX "IDX_ORDERS_RECENT" ON "APP"."ORDERS" ("CUSTOMER_ID", "ORDERED_ON" DESC) TABLESPACE "IDX_TS";
+CREATE INDEX "IDX_ORDERS_NO_UPPER" ON "APP"."ORDERS" (UPPER("ORDER_NO"));
+
+ALTER TABLE "APP"."ORDERS" ADD CONSTRAINT "FK_ORDERS_CUSTOMER" FOREIGN KEY ("CUSTOMER_ID") REFERENCES "APP"."CUSTOMERS" ("ID") ON DELETE CASCADE;
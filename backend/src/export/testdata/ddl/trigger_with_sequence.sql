@@ -0,0 +1,16 @@
+CREATE TABLE "APP"."AUDIT_LOG" (
+    "LOG_ID" NUMBER(18,0) NOT NULL,
+    "ACTION" VARCHAR2(20 BYTE) DEFAULT USER NOT NULL,
+    "PAYLOAD" BLOB NULL,
+    "LOGGED_AT" TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+ALTER TABLE "APP"."AUDIT_LOG" ADD CONSTRAINT "PK_AUDIT_LOG" PRIMARY KEY ("LOG_ID");
+
+CREATE OR REPLACE TRIGGER "APP"."TRG_AUDIT_LOG_ID"
+BEFORE INSERT ON "APP"."AUDIT_LOG" REFERENCING OLD AS OLD NEW AS NEW
+FOR EACH ROW
+WHEN (:NEW.LOG_ID IS NULL)
+BEGIN
+SELECT SEQ_AUDIT_LOG.NEXTVAL INTO :NEW.LOG_ID FROM DUAL;
+END;
@@ -0,0 +1,343 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::{Context, Result};
+use rust_xlsxwriter::{ColNum, ExcelDateTime, Format, RowNum, Workbook, Worksheet};
+
+use crate::db::backend::RowFetch;
+use crate::db::retry::{PartialOutput, RetryingConnection};
+use crate::db::schema::catalog_name;
+use crate::export::data::{
+    is_date_type, is_numeric_type, is_timestamp_type, load_table_metadata, preview_query,
+    quote_identifier, wide_fetch_lengths, DataExportOptions, FetchProgress,
+};
+use crate::export::job::JobContext;
+use crate::models::{IssueCode, JobEventKind, TableMemoryStats};
+
+/// Rows per sheet when the request does not set `xlsx_row_limit`.
+pub const DEFAULT_XLSX_ROW_LIMIT: usize = 100_000;
+/// Data rows an Excel sheet can hold below the header row.
+pub const MAX_SHEET_ROWS: usize = 1_048_575;
+/// Longest text Excel stores in a cell.
+const MAX_CELL_CHARS: usize = 32_767;
+const MAX_SHEET_NAME_CHARS: usize = 31;
+/// Column width bounds, in characters.
+const MIN_COLUMN_WIDTH: usize = 8;
+const MAX_COLUMN_WIDTH: usize = 60;
+
+/// How one fetched value is written into the sheet.
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Number(f64),
+    /// Parsed date or timestamp with the Excel number format to show it with.
+    DateTime(String, &'static str),
+    Text(String),
+}
+
+/// Numbers and dates become typed cells; everything else, and numbers Excel cannot hold
+/// exactly (more than 15 significant digits, e.g. long IDs), stays text.
+fn cell_value(data_type: &str, raw: &str) -> Cell {
+    let upper = data_type.to_uppercase();
+    if is_numeric_type(&upper) {
+        let digits = raw
+            .trim_start_matches(['-', '+'])
+            .trim_start_matches(['0', '.'])
+            .chars()
+            .filter(char::is_ascii_digit)
+            .count();
+        if digits <= 15 {
+            if let Ok(number) = raw.trim().parse::<f64>() {
+                return Cell::Number(number);
+            }
+        }
+    } else if is_date_type(&upper) || (is_timestamp_type(&upper) && !upper.contains("TIME ZONE")) {
+        let value = raw.trim();
+        let format = if value.contains(':') {
+            "yyyy-mm-dd hh:mm:ss"
+        } else {
+            "yyyy-mm-dd"
+        };
+        return Cell::DateTime(value.to_string(), format);
+    }
+    Cell::Text(raw.to_string())
+}
+
+/// A valid, unique sheet name for `table`: Excel forbids `[]:*?/\` and allows 31 characters.
+fn sheet_name(table: &str, used: &mut HashSet<String>) -> String {
+    let base: String = table
+        .chars()
+        .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+        .take(MAX_SHEET_NAME_CHARS)
+        .collect();
+    let mut name = base.clone();
+    let mut suffix = 2;
+    // Excel compares sheet names case-insensitively.
+    while !used.insert(name.to_uppercase()) {
+        let tag = format!("~{}", suffix);
+        let keep = MAX_SHEET_NAME_CHARS - tag.chars().count();
+        name = format!("{}{}", base.chars().take(keep).collect::<String>(), tag);
+        suffix += 1;
+    }
+    name
+}
+
+/// Writes one data row at `row`; returns how many text values were cut to Excel's cell limit.
+fn write_row(
+    sheet: &mut Worksheet,
+    row: RowNum,
+    values: &[Option<String>],
+    data_types: &[&str],
+    formats: &CellFormats,
+    widths: &mut [usize],
+) -> Result<usize> {
+    let mut truncated = 0;
+    for (col, (value, data_type)) in values.iter().zip(data_types).enumerate() {
+        let Some(raw) = value else {
+            continue;
+        };
+        let col_num = col as ColNum;
+        let shown = match cell_value(data_type, raw) {
+            Cell::Number(number) => {
+                sheet.write_number(row, col_num, number)?;
+                raw.len()
+            }
+            Cell::DateTime(value, format) => {
+                match ExcelDateTime::parse_from_str(&value) {
+                    Ok(datetime) => {
+                        let format = if format == "yyyy-mm-dd" {
+                            &formats.date
+                        } else {
+                            &formats.timestamp
+                        };
+                        sheet.write_datetime_with_format(row, col_num, &datetime, format)?;
+                    }
+                    Err(_) => {
+                        sheet.write_string(row, col_num, value.as_str())?;
+                    }
+                }
+                format.len()
+            }
+            Cell::Text(text) => {
+                let chars = text.chars().count();
+                if chars > MAX_CELL_CHARS {
+                    truncated += 1;
+                    let cut: String = text.chars().take(MAX_CELL_CHARS).collect();
+                    sheet.write_string(row, col_num, cut)?;
+                } else {
+                    sheet.write_string(row, col_num, text)?;
+                }
+                chars
+            }
+        };
+        widths[col] = widths[col].max(shown);
+    }
+    Ok(truncated)
+}
+
+struct CellFormats {
+    header: Format,
+    date: Format,
+    timestamp: Format,
+}
+
+/// Writes one sheet per table into an XLSX workbook: a bold, frozen header row, typed number
+/// and date cells and columns sized to their content. Each sheet holds at most `row_limit`
+/// rows (primary key order when there is one); cut tables are reported as job warnings.
+pub fn export_schema_xlsx(
+    session: &mut RetryingConnection<'_>,
+    job: &mut JobContext,
+    source_schema: &str,
+    tables: &[String],
+    output_path: &Path,
+    options: &DataExportOptions,
+    row_limit: usize,
+) -> Result<usize> {
+    let source_schema_name = catalog_name(source_schema, options.preserve_case);
+    let row_limit = row_limit.clamp(1, MAX_SHEET_ROWS);
+    let (table_details_list, _) =
+        load_table_metadata(session, job, &source_schema_name, tables, options)?;
+
+    let formats = CellFormats {
+        header: Format::new().set_bold(),
+        date: Format::new().set_num_format("yyyy-mm-dd"),
+        timestamp: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+    };
+    let mut workbook = Workbook::new();
+    let mut sheet_names = HashSet::new();
+    let mut exported_total = 0;
+
+    for details in &table_details_list {
+        let table = details.name.clone();
+        job.record(JobEventKind::TableStarted, Some(&table), None);
+
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(sheet_name(&table, &mut sheet_names))?;
+        let mut widths: Vec<usize> = details
+            .columns
+            .iter()
+            .map(|column| column.name.chars().count())
+            .collect();
+        for (col, column) in details.columns.iter().enumerate() {
+            sheet.write_string_with_format(
+                0,
+                col as ColNum,
+                column.name.as_str(),
+                &formats.header,
+            )?;
+        }
+        sheet.set_freeze_panes(1, 0)?;
+
+        let data_types: Vec<&str> = details
+            .columns
+            .iter()
+            .map(|column| column.data_type.as_str())
+            .collect();
+        let columns: Vec<String> = details
+            .columns
+            .iter()
+            .map(|column| quote_identifier(&column.name))
+            .collect();
+        let source_ident = quote_identifier(&format!("{}.{}", source_schema_name, table));
+        // One row more than the sheet takes tells whether the table was cut.
+        let query = preview_query(&columns.join(", "), &source_ident, details, row_limit + 1);
+
+        let mut stats = TableMemoryStats {
+            table: table.clone(),
+            requested_batch_size: options.batch_size,
+            batch_size: options.batch_size,
+            ..TableMemoryStats::default()
+        };
+        let wide_lengths = wide_fetch_lengths(details);
+        let mut progress = FetchProgress::default();
+        let mut written = 0;
+        let mut truncated_cells = 0;
+        let backend = session.backend();
+        session
+            .run(
+                &format!("Reading {}", source_ident),
+                &mut |message| job.record(JobEventKind::Retry, Some(&table), Some(message)),
+                |connection| {
+                    let fetch = RowFetch {
+                        batch_size: options.batch_size,
+                        column_count: columns.len(),
+                        key_index: None,
+                        wide_lengths: wide_lengths.as_deref(),
+                        stats: &mut stats,
+                        progress: &mut progress,
+                    };
+                    backend
+                        .stream_rows(connection, &query, fetch, &mut |rows| {
+                            for row in rows.iter().take(row_limit - written) {
+                                written += 1;
+                                truncated_cells += write_row(
+                                    sheet,
+                                    written as RowNum,
+                                    row,
+                                    &data_types,
+                                    &formats,
+                                    &mut widths,
+                                )?;
+                            }
+                            Ok(())
+                        })
+                        .map_err(|e| {
+                            if progress.rows > 0 {
+                                e.context(PartialOutput)
+                            } else {
+                                e
+                            }
+                        })
+                },
+            )
+            .with_context(|| format!("Failed to export table '{}' to XLSX", table))?;
+
+        for (col, width) in widths.iter().enumerate() {
+            let width = (*width).clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH) + 2;
+            sheet.set_column_width(col as ColNum, width as f64)?;
+        }
+        if progress.rows > written {
+            job.warn(
+                IssueCode::RowLimitReached,
+                Some(&table),
+                format!(
+                    "{} has more than {} rows; only the first {} were written to the sheet",
+                    table, row_limit, row_limit
+                ),
+            );
+        }
+        if truncated_cells > 0 {
+            job.warn(
+                IssueCode::CellTruncated,
+                Some(&table),
+                format!(
+                    "{} value(s) of {} exceed Excel's {} character cell limit and were cut",
+                    truncated_cells, table, MAX_CELL_CHARS
+                ),
+            );
+        }
+        job.record(
+            JobEventKind::TableFinished,
+            Some(&table),
+            Some(format!("{} rows", written)),
+        );
+        job.memory.push(stats);
+        exported_total += written;
+    }
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "Failed to create parent directory for {}",
+                output_path.display()
+            )
+        })?;
+    }
+    workbook
+        .save(output_path)
+        .with_context(|| format!("Failed to write workbook {}", output_path.display()))?;
+    Ok(exported_total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cell_value, sheet_name, Cell};
+    use std::collections::HashSet;
+
+    #[test]
+    fn numbers_and_dates_become_typed_cells() {
+        assert_eq!(cell_value("NUMBER", "12.50"), Cell::Number(12.5));
+        assert_eq!(cell_value("int", "-7"), Cell::Number(-7.0));
+        // More significant digits than a double keeps exactly.
+        assert_eq!(
+            cell_value("DECIMAL", "1234567890123456789"),
+            Cell::Text("1234567890123456789".to_string())
+        );
+        assert_eq!(
+            cell_value("DATE", "2026-01-30"),
+            Cell::DateTime("2026-01-30".to_string(), "yyyy-mm-dd")
+        );
+        assert_eq!(
+            cell_value("TIMESTAMP", "2026-01-30 08:15:00.5"),
+            Cell::DateTime("2026-01-30 08:15:00.5".to_string(), "yyyy-mm-dd hh:mm:ss")
+        );
+        assert_eq!(
+            cell_value("TIMESTAMP WITH TIME ZONE", "2026-01-30 08:15:00 +08:00"),
+            Cell::Text("2026-01-30 08:15:00 +08:00".to_string())
+        );
+        assert_eq!(cell_value("VARCHAR", "42"), Cell::Text("42".to_string()));
+    }
+
+    #[test]
+    fn sheet_names_are_valid_and_unique() {
+        let mut used = HashSet::new();
+        assert_eq!(sheet_name("ORDERS", &mut used), "ORDERS");
+        assert_eq!(sheet_name("orders", &mut used), "orders~2");
+        assert_eq!(sheet_name("A/B[1]", &mut used), "A_B_1_");
+
+        let long = "T".repeat(40);
+        assert_eq!(sheet_name(&long, &mut used), "T".repeat(31));
+        assert_eq!(
+            sheet_name(&long, &mut used),
+            format!("{}~2", "T".repeat(29))
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Lets app instances share one embedded backend: the process that binds the server records
+//! its pid and port in `~/.amarone/backend.json`, and later starts reuse that server while it
+//! still answers instead of binding a second one on top of the same config store.
+
+use std::{
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const INSTANCE_FILE: &str = "backend.json";
+
+/// How long a recorded backend gets to answer its health check.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceRecord {
+    pub pid: u32,
+    pub port: u16,
+}
+
+/// `~/.amarone/backend.json`, next to the config database.
+pub fn default_path() -> Result<PathBuf> {
+    let home_dir = dirs::home_dir().ok_or_else(|| anyhow!("Unable to determine home directory"))?;
+    Ok(home_dir.join(".amarone").join(INSTANCE_FILE))
+}
+
+/// Address of the backend recorded at `path` when it belongs to another process and still
+/// answers `/api/health`; a missing, unreadable or stale record yields `None`.
+pub fn find_running(path: &Path) -> Option<SocketAddr> {
+    let content = fs::read_to_string(path).ok()?;
+    let record: InstanceRecord = serde_json::from_str(&content).ok()?;
+    if record.pid == std::process::id() {
+        return None;
+    }
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], record.port));
+    is_backend(addr).then_some(addr)
+}
+
+/// Records this process as the one serving the backend on `port`.
+pub fn record(path: &Path, port: u16) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let record = InstanceRecord {
+        pid: std::process::id(),
+        port,
+    };
+    fs::write(path, serde_json::to_string(&record)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// A recorded port may have been taken by an unrelated program since, so only a health
+/// response shaped like ours counts.
+fn is_backend(addr: SocketAddr) -> bool {
+    ureq::get(&format!("http://{addr}/api/health"))
+        .timeout(PROBE_TIMEOUT)
+        .call()
+        .ok()
+        .and_then(|response| response.into_json::<serde_json::Value>().ok())
+        .is_some_and(|body| body.pointer("/data/active_jobs").is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Json, Router};
+    use serde_json::json;
+    use tempfile::TempDir;
+    use tokio::net::TcpListener;
+
+    fn write_record(path: &Path, port: u16) {
+        // pid 0 is never the test process, so the record counts as another instance.
+        let record = InstanceRecord { pid: 0, port };
+        fs::write(path, serde_json::to_string(&record).unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn find_running_reuses_live_backend_and_skips_stale_records() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(INSTANCE_FILE);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = Router::new().route(
+            "/api/health",
+            get(|| async { Json(json!({ "success": true, "data": { "active_jobs": 0 } })) }),
+        );
+        let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+        write_record(&path, port);
+        let probe_path = path.clone();
+        let found = tokio::task::spawn_blocking(move || find_running(&probe_path))
+            .await
+            .unwrap();
+        assert_eq!(found, Some(SocketAddr::from(([127, 0, 0, 1], port))));
+
+        server.abort();
+        let _ = server.await;
+        let probe_path = path.clone();
+        let found = tokio::task::spawn_blocking(move || find_running(&probe_path))
+            .await
+            .unwrap();
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn record_writes_own_pid_which_is_never_reused() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join(INSTANCE_FILE);
+
+        record(&path, 4321).unwrap();
+        let stored: InstanceRecord =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            stored,
+            InstanceRecord {
+                pid: std::process::id(),
+                port: 4321
+            }
+        );
+        assert_eq!(find_running(&path), None);
+        assert_eq!(find_running(&dir.path().join("missing.json")), None);
+    }
+}
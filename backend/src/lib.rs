@@ -2,7 +2,10 @@ pub mod api;
 pub mod config_store;
 pub mod db;
 pub mod export;
+pub mod instance;
+pub mod logging;
 pub mod models;
+pub mod secrets;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -11,8 +14,6 @@ use anyhow::{Context, Result};
 use tokio::net::TcpListener;
 
 use config_store::ConfigStore;
-use std::sync::Once;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 /// Start the Axum server on the given port (or default from env/3000). Returns the bound address.
 pub async fn start_server(port: Option<u16>) -> Result<SocketAddr> {
@@ -21,8 +22,17 @@ pub async fn start_server(port: Option<u16>) -> Result<SocketAddr> {
     let config_store = Arc::new(
         ConfigStore::ensure_default_path().context("Failed to initialize config store")?,
     );
+    let driver = db::driver::driver_override();
+    db::driver::restore_driver_path(&config_store, driver);
 
-    let app_state = api::AppState { config_store };
+    let metadata_cache = Arc::new(db::metadata_cache::MetadataCache::from_env());
+    let backend = db::backend::DatabaseKind::default().backend();
+    let app_state = api::AppState {
+        config_store,
+        driver,
+        schema_provider: backend.schema_provider(Arc::clone(&metadata_cache)),
+        metadata_cache,
+    };
     let app = api::create_router(app_state);
 
     let port = port
@@ -36,7 +46,8 @@ pub async fn start_server(port: Option<u16>) -> Result<SocketAddr> {
     let bound = listener.local_addr().context("Unable to read bound address")?;
 
     tokio::spawn(async move {
-        if let Err(err) = axum::serve(listener, app).await {
+        let service = app.into_make_service_with_connect_info::<SocketAddr>();
+        if let Err(err) = axum::serve(listener, service).await {
             tracing::error!(error = ?err, "Server exited with error");
         }
     });
@@ -44,16 +55,29 @@ pub async fn start_server(port: Option<u16>) -> Result<SocketAddr> {
     Ok(bound)
 }
 
-/// Initialize tracing with env filter defaults. Safe to call multiple times.
+/// Like [`start_server`], but when another process already serves the backend recorded in
+/// `~/.amarone/backend.json` that server's address is returned instead of binding a second
+/// one, so a single process writes the config store.
+pub async fn start_or_reuse_server(port: Option<u16>) -> Result<SocketAddr> {
+    let record_path = instance::default_path()?;
+    let probe_path = record_path.clone();
+    let running = tokio::task::spawn_blocking(move || instance::find_running(&probe_path))
+        .await
+        .context("Backend instance check panicked")?;
+    if let Some(addr) = running {
+        tracing::info!("Reusing backend already running on {}", addr);
+        return Ok(addr);
+    }
+
+    let bound = start_server(port).await?;
+    if let Err(e) = instance::record(&record_path, bound.port()) {
+        tracing::warn!("Failed to record backend instance: {:#}", e);
+    }
+    Ok(bound)
+}
+
+/// Initialize tracing to stderr and `~/.amarone/logs` (see [`logging`]). Safe to call multiple
+/// times.
 pub fn init_tracing() {
-    static INIT: Once = Once::new();
-    INIT.call_once(|| {
-        let _ = tracing_subscriber::registry()
-            .with(
-                tracing_subscriber::EnvFilter::try_from_default_env()
-                    .unwrap_or_else(|_| "dm8_export_backend=debug,tower_http=debug".into()),
-            )
-            .with(tracing_subscriber::fmt::layer())
-            .try_init();
-    });
+    logging::init();
 }
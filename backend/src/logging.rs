@@ -0,0 +1,224 @@
+//! Backend logging: stderr plus a daily-rotated file under `~/.amarone/logs`, since stderr is
+//! invisible in packaged desktop builds. The filter comes from `RUST_LOG`, else the
+//! `log_level` setting of the config store, else [`DEFAULT_FILTER`]; [`set_level`] changes it
+//! while running.
+
+use std::{
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{Once, OnceLock},
+};
+
+use anyhow::{anyhow, Context, Result};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry,
+};
+
+use crate::{config_store::ConfigStore, models::LogTailResponse};
+
+/// Config store setting holding the level chosen through `POST /api/config/log-level`.
+pub const LOG_LEVEL_SETTING: &str = "log_level";
+/// `tower_http` stays at `info`: its debug request spans would put request details in the
+/// persisted log files.
+pub const DEFAULT_FILTER: &str = "dm8_export_backend=debug,tower_http=info";
+
+const LOG_FILE_PREFIX: &str = "backend";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Daily files kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 14;
+/// Bytes read per step when scanning a log file backwards for its last lines.
+const TAIL_CHUNK: u64 = 64 * 1024;
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// `~/.amarone/logs`, next to the config database.
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".amarone").join("logs"))
+}
+
+/// Installs the global subscriber. Safe to call multiple times.
+pub fn init() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let (filter, handle) = reload::Layer::new(initial_filter());
+        let file_layer = log_dir()
+            .and_then(|dir| match file_appender(&dir) {
+                Ok(appender) => Some(appender),
+                Err(e) => {
+                    eprintln!("File logging disabled: {e:#}");
+                    None
+                }
+            })
+            .map(|appender| fmt::layer().with_ansi(false).with_writer(appender));
+
+        let installed = tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer())
+            .with(file_layer)
+            .try_init();
+        if installed.is_ok() {
+            let _ = FILTER_HANDLE.set(handle);
+        }
+    });
+}
+
+fn initial_filter() -> EnvFilter {
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        return filter;
+    }
+    ConfigStore::ensure_default_path()
+        .ok()
+        .and_then(|store| store.get_setting(LOG_LEVEL_SETTING).ok().flatten())
+        .and_then(|level| filter_for_level(&level).ok())
+        .map(|(filter, _)| filter)
+        .unwrap_or_else(|| EnvFilter::new(DEFAULT_FILTER))
+}
+
+fn file_appender(dir: &Path) -> Result<RollingFileAppender> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(dir)
+        .with_context(|| format!("Failed to open log file in {}", dir.display()))
+}
+
+/// Normalizes `level` (`trace` … `error`, any case) and builds the filter applying it to the
+/// backend and, at `info` or lower, its HTTP layer.
+pub fn filter_for_level(level: &str) -> Result<(EnvFilter, String)> {
+    let level = level.trim().to_ascii_lowercase();
+    if !matches!(
+        level.as_str(),
+        "trace" | "debug" | "info" | "warn" | "error"
+    ) {
+        return Err(anyhow!(
+            "Unknown log level '{level}', expected trace, debug, info, warn or error"
+        ));
+    }
+    let http_level = if matches!(level.as_str(), "trace" | "debug") {
+        "info"
+    } else {
+        &level
+    };
+    let filter = EnvFilter::try_new(format!(
+        "dm8_export_backend={level},tower_http={http_level}"
+    ))?;
+    Ok((filter, level))
+}
+
+/// Applies `level` to the running subscriber and returns its normalized name. Without an
+/// installed subscriber (tests, embedders with their own) only the validation happens.
+pub fn set_level(level: &str) -> Result<String> {
+    let (filter, level) = filter_for_level(level)?;
+    if let Some(handle) = FILTER_HANDLE.get() {
+        handle.reload(filter).context("Failed to apply log level")?;
+    }
+    Ok(level)
+}
+
+/// The last `count` lines logged to `dir`, oldest first, reaching into earlier days' files
+/// when the newest one is shorter.
+pub fn tail(dir: &Path, count: usize) -> Result<LogTailResponse> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| is_log_file(path))
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to list {}", dir.display())),
+    };
+    // Names carry the date as YYYY-MM-DD, so they sort chronologically.
+    files.sort();
+    files.reverse();
+
+    let mut lines: Vec<String> = Vec::new();
+    for file in &files {
+        if lines.len() >= count {
+            break;
+        }
+        let mut earlier = last_lines(file, count - lines.len())
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        earlier.append(&mut lines);
+        lines = earlier;
+    }
+
+    Ok(LogTailResponse {
+        file: files.first().map(|file| file.display().to_string()),
+        lines,
+    })
+}
+
+fn is_log_file(path: &Path) -> bool {
+    path.is_file()
+        && path.file_name().is_some_and(|name| {
+            let name = name.to_string_lossy();
+            name.starts_with(&format!("{LOG_FILE_PREFIX}."))
+                && name.ends_with(&format!(".{LOG_FILE_SUFFIX}"))
+        })
+}
+
+/// Reads `path` backwards in chunks, so tailing a large debug log stays cheap.
+fn last_lines(path: &Path, count: usize) -> io::Result<Vec<String>> {
+    let mut file = File::open(path)?;
+    let mut pos = file.metadata()?.len();
+    let mut buf: Vec<u8> = Vec::new();
+    let mut newlines = 0;
+    // One line break more than needed guarantees `count` complete lines after the first,
+    // possibly partial, one.
+    while pos > 0 && newlines <= count {
+        let step = TAIL_CHUNK.min(pos);
+        pos -= step;
+        file.seek(SeekFrom::Start(pos))?;
+        let mut chunk = vec![0; step as usize];
+        file.read_exact(&mut chunk)?;
+        newlines += chunk.iter().filter(|&&b| b == b'\n').count();
+        chunk.extend_from_slice(&buf);
+        buf = chunk;
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    if pos > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    Ok(lines.split_off(lines.len().saturating_sub(count)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn tail_returns_last_lines_across_daily_files() {
+        let dir = TempDir::new().unwrap();
+        let older: String = (1..=3).map(|i| format!("old {i}\n")).collect();
+        fs::write(dir.path().join("backend.2026-01-01.log"), older).unwrap();
+        let newer: String = (1..=20_000).map(|i| format!("new {i}\n")).collect();
+        fs::write(dir.path().join("backend.2026-01-02.log"), newer).unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored\n").unwrap();
+
+        let recent = tail(dir.path(), 3).unwrap();
+        assert_eq!(recent.lines, vec!["new 19998", "new 19999", "new 20000"]);
+        assert!(recent.file.unwrap().ends_with("backend.2026-01-02.log"));
+
+        let all = tail(dir.path(), 20_002).unwrap();
+        assert_eq!(all.lines.len(), 20_002);
+        assert_eq!(all.lines[0], "old 2");
+        assert_eq!(all.lines[2], "new 1");
+
+        let missing = tail(&dir.path().join("missing"), 10).unwrap();
+        assert!(missing.lines.is_empty() && missing.file.is_none());
+    }
+
+    #[test]
+    fn filter_for_level_accepts_only_level_names() {
+        assert_eq!(filter_for_level(" INFO ").unwrap().1, "info");
+        assert!(filter_for_level("verbose").is_err());
+        assert!(filter_for_level("dm8_export_backend=trace").is_err());
+    }
+}
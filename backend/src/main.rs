@@ -3,6 +3,8 @@ mod export;
 mod api;
 mod models;
 mod config_store;
+mod secrets;
+mod logging;
 
 #[tokio::main]
 async fn main() {
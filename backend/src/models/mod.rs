@@ -1,20 +1,68 @@
+use std::collections::{BTreeSet, HashMap};
+
 use serde::{Deserialize, Serialize};
 
+use crate::db::backend::DatabaseKind;
+
+/// Where connection settings come from, highest precedence first: the request body, the
+/// saved connection, the `DATABASE_*` environment variables and the built-in defaults.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum ConfigSource {
-    Env,
+    Request,
     Sqlite,
+    Env,
+    Default,
+}
+
+impl ConfigSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigSource::Request => "request",
+            ConfigSource::Sqlite => "sqlite",
+            ConfigSource::Env => "env",
+            ConfigSource::Default => "default",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ConnectionConfig {
+    /// Kind of source database, `dm8` unless set.
+    #[serde(default)]
+    pub db_type: DatabaseKind,
+    /// Server address; not needed when connecting through `dsn`.
+    #[serde(default)]
     pub host: String,
+    #[serde(default)]
     pub port: u16,
     pub username: String,
     pub password: String,
     pub schema: String,
     pub export_schema: Option<String>,
+    /// Name of a pre-configured ODBC data source; replaces driver, host and port when set.
+    #[serde(default)]
+    pub dsn: Option<String>,
+    /// Encrypt the connection with SSL; requires `ssl_cert_path`.
+    #[serde(default)]
+    pub ssl: bool,
+    /// Directory holding the client certificate and key (`SSL_PATH`).
+    #[serde(default)]
+    pub ssl_cert_path: Option<String>,
+    /// Password of the client key (`SSL_PWD`); may be a `${scheme:reference}` secret.
+    #[serde(default)]
+    pub ssl_cert_password: Option<String>,
+    /// Cipher the driver should negotiate (`CIPHER_NAME`), e.g. `AES256_CBC`.
+    #[serde(default)]
+    pub ssl_cipher: Option<String>,
+    /// Seconds to wait for the server when connecting; defaults to 15, `0` waits as long as
+    /// the driver does.
+    #[serde(default)]
+    pub login_timeout_secs: Option<u32>,
+    /// Seconds a connection setup or health check statement may run; defaults to 30, `0`
+    /// disables the limit. Export reads are not limited.
+    #[serde(default)]
+    pub query_timeout_secs: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +72,30 @@ pub struct StoredConnectionResponse {
     pub updated_at: Option<String>,
 }
 
+/// Response of `GET /api/config/effective`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveConfigResponse {
+    /// Source supplying the connection in use.
+    pub source: ConfigSource,
+    /// The connection in use; passwords are masked unless they are secret references.
+    pub config: ConnectionConfig,
+    pub updated_at: Option<String>,
+    /// Sources consulted, highest precedence first.
+    pub precedence: Vec<ConfigSource>,
+    pub fields: Vec<EffectiveField>,
+    /// Problems of lower-precedence sources, such as an invalid `DATABASE_PORT`.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveField {
+    pub name: String,
+    /// `default` when the source in use leaves the field unset.
+    pub source: ConfigSource,
+    /// Lower-precedence sources that set the field too but are ignored.
+    pub shadowed: Vec<ConfigSource>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Table {
     pub name: String,
@@ -31,7 +103,7 @@ pub struct Table {
     pub row_count: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Column {
     pub name: String,
     pub data_type: String,
@@ -46,6 +118,48 @@ pub struct Column {
     pub identity: bool,
     pub identity_start: Option<i64>,
     pub identity_increment: Option<i64>,
+    /// Generation mode from `ALL_TAB_IDENTITY_COLS`; `None` writes the native `IDENTITY(s, i)`.
+    #[serde(default)]
+    pub identity_generation: Option<IdentityGeneration>,
+    /// Last value the identity handed out (`IDENT_CURRENT`), when the source reported one.
+    #[serde(default)]
+    pub identity_current: Option<i64>,
+    /// Expression of a virtual (computed) column; the target computes its values, so data
+    /// exports leave the column out.
+    #[serde(default)]
+    pub virtual_expression: Option<String>,
+    /// SRID of a DMGEO geometry column, read from a stored value; `None` for other columns
+    /// and for geometry columns without values.
+    #[serde(default)]
+    pub geometry_srid: Option<i32>,
+    /// Explicit column collation such as `BINARY_CI`; `None` when inherited.
+    #[serde(default)]
+    pub collation: Option<String>,
+}
+
+/// Whether an identity column accepts explicit values on insert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityGeneration {
+    /// `GENERATED ALWAYS`: the column always takes the generated value.
+    Always,
+    /// `GENERATED BY DEFAULT`: an explicitly inserted value is kept.
+    ByDefault,
+}
+
+impl IdentityGeneration {
+    /// Parses `ALL_TAB_IDENTITY_COLS.GENERATION_TYPE` (`ALWAYS`, `BY DEFAULT`, `BY DEFAULT ON
+    /// NULL`).
+    pub fn from_catalog(value: &str) -> Option<Self> {
+        let value = value.trim().to_uppercase();
+        if value == "ALWAYS" {
+            Some(Self::Always)
+        } else if value.starts_with("BY DEFAULT") {
+            Some(Self::ByDefault)
+        } else {
+            None
+        }
+    }
 }
 
 fn default_true() -> bool {
@@ -56,6 +170,175 @@ fn default_false() -> bool {
     false
 }
 
+/// Object kinds a DDL export can be limited to, in the order the script emits them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DdlObjectType {
+    Tables,
+    Comments,
+    Constraints,
+    Indexes,
+    Sequences,
+    Triggers,
+    /// Views reading the exported tables; only when asked for, as a view may also read tables
+    /// the export leaves out.
+    Views,
+}
+
+impl DdlObjectType {
+    pub const ALL: [DdlObjectType; 7] = [
+        DdlObjectType::Tables,
+        DdlObjectType::Comments,
+        DdlObjectType::Constraints,
+        DdlObjectType::Indexes,
+        DdlObjectType::Sequences,
+        DdlObjectType::Triggers,
+        DdlObjectType::Views,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DdlObjectType::Tables => "tables",
+            DdlObjectType::Comments => "comments",
+            DdlObjectType::Constraints => "constraints",
+            DdlObjectType::Indexes => "indexes",
+            DdlObjectType::Sequences => "sequences",
+            DdlObjectType::Triggers => "triggers",
+            DdlObjectType::Views => "views",
+        }
+    }
+}
+
+fn default_ddl_objects() -> BTreeSet<DdlObjectType> {
+    DdlObjectType::ALL
+        .into_iter()
+        .filter(|kind| *kind != DdlObjectType::Views)
+        .collect()
+}
+
+/// Line terminator of the generated script files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    /// Windows line endings, for DIsql setups that reject LF-only trigger scripts.
+    Crlf,
+}
+
+/// How data scripts write text values that are empty strings. DM8 in Oracle-compatible mode
+/// stores `''` as NULL, so a round trip may not keep them apart either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmptyStringPolicy {
+    /// Write `''`.
+    #[default]
+    Empty,
+    /// Write `NULL`.
+    Null,
+}
+
+/// What a data script does with a row holding a value that cannot be written as a literal of
+/// its column type (e.g. a NUMBER the driver returned as non-numeric text).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RowErrorPolicy {
+    /// Fail the table, and with it the export.
+    #[default]
+    Abort,
+    /// Leave the row out.
+    Skip,
+    /// Write `NULL` for the offending values and keep the row.
+    Null,
+}
+
+/// Subset of each table's rows a data export writes (`sample`). Exactly one of `percent` and
+/// `rows` is set. With a `seed`, tables with a primary key are sampled by a seeded hash of the
+/// key, so the same seed picks the same rows again; without one every run picks anew.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SampleSpec {
+    /// Share of the rows, greater than 0 and at most 100.
+    #[serde(default)]
+    pub percent: Option<f64>,
+    /// Number of rows per table.
+    #[serde(default)]
+    pub rows: Option<usize>,
+    #[serde(default)]
+    pub seed: Option<u32>,
+    /// Keep the sample referentially consistent: sample the `roots`, then add the rows that
+    /// reference sampled rows and the rows those reference, so the subset loads without foreign
+    /// key violations. Needs a `seed`, as every table's query must see the same sample.
+    #[serde(default)]
+    pub consistent: bool,
+    /// Tables a consistent sample starts from; when empty, the exported tables that reference
+    /// no other exported table.
+    #[serde(default)]
+    pub roots: Vec<String>,
+}
+
+/// Which identifiers generated scripts put in double quotes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStyle {
+    /// Quote every identifier.
+    #[default]
+    Always,
+    /// Quote only DM8 reserved words and names that are not plain upper-case identifiers.
+    WhenNeeded,
+    /// Never quote; names that need quotes are written bare.
+    Never,
+}
+
+/// Where DDL scripts write the `COMMENT ON` statements of the exported tables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentsPlacement {
+    /// In each table's section, after its `CREATE TABLE`.
+    #[default]
+    Inline,
+    /// Grouped in one section at the end of the script.
+    SeparateSection,
+    /// Not written.
+    Omit,
+}
+
+/// Character encoding of the generated script files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportEncoding {
+    #[default]
+    Utf8,
+    /// For legacy DM8 installs whose tools expect GBK scripts.
+    Gbk,
+}
+
+impl ExportEncoding {
+    /// Value for DIsql's `SET CHAR_CODE`.
+    pub fn char_code(self) -> &'static str {
+        match self {
+            ExportEncoding::Utf8 => "UTF8",
+            ExportEncoding::Gbk => "GBK",
+        }
+    }
+}
+
+/// Output format of a data export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    /// One script of `INSERT` statements.
+    #[default]
+    Sql,
+    /// One length-prefixed binary file per table, loaded with `POST /api/import/binary`.
+    Binary,
+    /// A workbook with one sheet per table, for business users.
+    Xlsx,
+    /// One JSON Lines file per table (one object per row), for search and log pipelines.
+    Jsonl,
+    /// Delimiter-separated data files plus `.ctl` control files for DM8's `dmfldr` loader.
+    Dmfldr,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UniqueConstraint {
     pub name: String,
@@ -78,7 +361,28 @@ pub struct ForeignKey {
     pub update_rule: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One foreign key edge between two tables, seen from the table a dependency report is for.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TableReference {
+    pub schema: String,
+    pub table: String,
+    pub constraint: String,
+}
+
+/// Objects a table needs or is needed by, so a partial selection can be completed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TableDependencies {
+    pub table: String,
+    /// Tables this table's foreign keys reference.
+    pub references: Vec<TableReference>,
+    /// Tables whose foreign keys reference this table.
+    pub referenced_by: Vec<TableReference>,
+    pub triggers: Vec<String>,
+    /// Sequences used by column defaults or trigger bodies, without schema qualifier.
+    pub sequences: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TableDetails {
     pub name: String,
     pub comment: Option<String>,
@@ -89,28 +393,289 @@ pub struct TableDetails {
     pub foreign_keys: Vec<ForeignKey>,
     pub check_constraints: Vec<CheckConstraint>,
     pub triggers: Vec<TriggerDefinition>,
+    #[serde(default)]
+    pub tablespace: Option<String>,
+    /// Table default collation; `None` when the server default applies.
+    #[serde(default)]
+    pub default_collation: Option<String>,
+}
+
+#[cfg(test)]
+impl Column {
+    /// Nullable column with just a name and type, for tests.
+    pub(crate) fn named(name: &str, data_type: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+impl TableDetails {
+    /// Table with just a name, columns and primary key, for tests.
+    pub(crate) fn named(name: &str, columns: Vec<Column>, primary_keys: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            columns,
+            primary_keys: primary_keys.iter().map(|key| key.to_string()).collect(),
+            ..Self::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Index {
     pub name: String,
     pub columns: Vec<String>,
+    /// Per-column sort order and expression, aligned with `columns`. Empty means all
+    /// columns are plain ascending.
+    #[serde(default)]
+    pub column_specs: Vec<IndexColumn>,
     pub unique: bool,
+    #[serde(default)]
+    pub tablespace: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexColumn {
+    pub descending: bool,
+    /// Expression text for function-based index columns, as reported by `ALL_IND_EXPRESSIONS`.
+    pub expression: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExportRequest {
     pub config: ConnectionConfig,
     pub export_schema: Option<String>,
+    /// Trigger terminator mode (`datagrip`, `script`, `datagrip-script`) or target dialect
+    /// for column defaults (`dm8`, `oracle`, `postgresql`, `mysql`).
     pub export_compat: Option<String>,
+    /// Tables to export; ignored with `all_tables`.
+    #[serde(default)]
     pub tables: Vec<String>,
-    pub include_ddl: bool,
+    /// Export every table of the schema instead of `tables` ("all except" with
+    /// `exclude_patterns`).
+    #[serde(default)]
+    pub all_tables: bool,
+    /// Tables left out of the selection: case-insensitive globs (`*`/`%`, `?`) or `re:<regex>`.
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+    /// Object kinds written by the DDL export; every kind but views when omitted.
+    #[serde(default = "default_ddl_objects")]
+    pub ddl_objects: BTreeSet<DdlObjectType>,
     pub include_data: bool,
     pub batch_size: Option<usize>,
+    /// Rows per generated INSERT statement, independent of `batch_size` (rows per fetch);
+    /// unlimited when omitted.
+    #[serde(default)]
+    pub max_rows_per_insert: Option<usize>,
+    /// `sql` (INSERT script, default), `binary`, `xlsx`, `jsonl` or `dmfldr`.
+    #[serde(default)]
+    pub data_format: DataFormat,
+    /// Rows per sheet of an `xlsx` export; defaults to 100000, at most 1048575.
+    #[serde(default)]
+    pub xlsx_row_limit: Option<usize>,
+    /// Field separator of `dmfldr` data files; `|` when omitted.
+    #[serde(default)]
+    pub dmfldr_delimiter: Option<String>,
+    /// Text standing for NULL in `dmfldr` data files; `\N` when omitted.
+    #[serde(default)]
+    pub dmfldr_null_marker: Option<String>,
+    /// Longest generated INSERT statement in bytes; a batch is split into more statements
+    /// before one grows past it.
+    #[serde(default)]
+    pub max_statement_bytes: Option<usize>,
+    /// Interleave `COMMIT;` in data scripts after this many rows and after every table, so the
+    /// target does not load the whole script in one transaction.
+    #[serde(default)]
+    pub commit_every_rows: Option<usize>,
     #[serde(default = "default_true")]
     pub drop_existing: bool,
     #[serde(default = "default_false")]
     pub include_row_counts: bool,
+    /// Append `TABLESPACE` clauses to table and index DDL.
+    #[serde(default)]
+    pub include_storage: bool,
+    /// Source tablespace -> target tablespace, applied when `include_storage` is set.
+    #[serde(default)]
+    pub tablespace_map: HashMap<String, String>,
+    /// Emit `GRANT` statements for object privileges on the exported tables.
+    #[serde(default)]
+    pub include_grants: bool,
+    /// Export only sequences referenced by the selected tables' defaults and triggers.
+    #[serde(default = "default_true")]
+    pub only_referenced_sequences: bool,
+    /// Source sequence name -> target name (case-insensitive on the source name).
+    #[serde(default)]
+    pub sequence_renames: HashMap<String, String>,
+    /// Source table name -> target table name (case-insensitive on the source name), for
+    /// creating a renamed copy next to the original tables.
+    #[serde(default)]
+    pub table_renames: HashMap<String, String>,
+    /// Also export tables the selected tables reference through foreign keys (transitively)
+    /// and the sequences they use, listing the auto-added objects in the script header.
+    #[serde(default)]
+    pub include_dependencies: bool,
+    /// Leave out check constraints that only repeat a column's NOT NULL (DM8 generates them
+    /// for NOT NULL columns) and quote column names in the other check conditions.
+    #[serde(default)]
+    pub skip_not_null_checks: bool,
+    /// In data scripts, restart each identity column after its table's rows past the source's
+    /// last generated value.
+    #[serde(default)]
+    pub restart_identities: bool,
+    /// Data scripts move sequences to the MAX of the columns they feed after the inserts
+    /// instead of resetting them to their START value.
+    #[serde(default)]
+    pub reseed_sequences: bool,
+    /// Data scripts write CR, LF and TAB inside text values as `CHR(n)` concatenations instead
+    /// of raw characters, keeping every INSERT literal on one line.
+    #[serde(default)]
+    pub escape_line_breaks: bool,
+    /// Whether empty text values are written as `''` (default) or `NULL`.
+    #[serde(default)]
+    pub empty_string_as: EmptyStringPolicy,
+    /// Rows with a value unfit for its column type abort the export (default), are skipped or
+    /// get `NULL` for that value; skipped and changed rows are listed in `<script>.errors.log`.
+    #[serde(default)]
+    pub on_row_error: RowErrorPolicy,
+    /// Data scripts disable the triggers of each table that has any before its INSERTs and
+    /// re-enable them after, so sequence-filling triggers leave the exported IDs alone.
+    #[serde(default)]
+    pub disable_triggers_during_load: bool,
+    /// Export a sample of each table's rows instead of all of them: a percentage or a number of
+    /// rows, picked reproducibly by a hash of the primary key when a seed is given. Spreadsheet
+    /// exports keep their own row cap and ignore it.
+    #[serde(default)]
+    pub sample: Option<SampleSpec>,
+    /// SQL written before each table's rows in data scripts, e.g. to disable its triggers;
+    /// `{schema}` and `{table}` stand for the target names, quoted per `quote_style`.
+    #[serde(default)]
+    pub pre_table_sql: Option<String>,
+    /// SQL written after each table's rows in data scripts, with the same placeholders.
+    #[serde(default)]
+    pub post_table_sql: Option<String>,
+    /// Zone TIMESTAMP WITH TIME ZONE values are converted to: `UTC` or a fixed offset such as
+    /// `+08:00`. Unset keeps each value's own offset.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Take schema and table names exactly as given instead of upper-casing them.
+    #[serde(default)]
+    pub preserve_case: bool,
+    /// Which identifiers the DDL and data scripts quote: `always` (default), `when_needed` or
+    /// `never`.
+    #[serde(default)]
+    pub quote_style: QuoteStyle,
+    /// Where the DDL script writes table and column comments: `inline` (default),
+    /// `separate_section` at the end of the script, or `omit`.
+    #[serde(default)]
+    pub comments_placement: CommentsPlacement,
+    /// Start the DDL script with `CREATE SCHEMA "<target>" AUTHORIZATION <schema_owner>`.
+    #[serde(default)]
+    pub include_schema_ddl: bool,
+    /// User the schema created by `include_schema_ddl` belongs to; `SYSDBA` when unset.
+    #[serde(default)]
+    pub schema_owner: Option<String>,
+    /// In a full export (`/api/export/full`), create the indexes after the INSERT sections
+    /// instead of with their tables, so bulk loads do not maintain them row by row.
+    #[serde(default)]
+    pub indexes_after_data: bool,
+    /// Encoding of the written files (`utf8` or `gbk`); defaults to UTF-8.
+    #[serde(default)]
+    pub encoding: ExportEncoding,
+    /// Line terminator of the written files (`lf` or `crlf`); defaults to LF.
+    #[serde(default)]
+    pub line_ending: LineEnding,
+    /// Rows per query when paging tables with a single integer primary key by key range
+    /// (keyset pagination) in data exports; one cursor per table when omitted.
+    #[serde(default)]
+    pub keyset_page_size: Option<usize>,
+    /// Source table name -> last key written by an earlier keyset export that failed; those
+    /// tables are read from the next key on. The failed job's error names the key to resume
+    /// after.
+    #[serde(default)]
+    pub keyset_resume_after: HashMap<String, String>,
+    /// Shrink the data export batch size for tables whose rows are wide (e.g. many LOB
+    /// columns) so one batch stays within `max_batch_bytes`.
+    #[serde(default)]
+    pub adaptive_batch: bool,
+    /// Memory budget of one fetched batch for `adaptive_batch`; defaults to 32 MiB.
+    #[serde(default)]
+    pub max_batch_bytes: Option<usize>,
+    /// Attempts per metadata query or table read when the link drops or times out (including
+    /// the first); defaults to 3, `1` disables retries.
+    #[serde(default)]
+    pub retry_attempts: Option<u32>,
+    /// Wait before the first retry in milliseconds, doubled for each further retry; defaults
+    /// to 1000.
+    #[serde(default)]
+    pub retry_backoff_ms: Option<u64>,
+    /// Seconds a connection may sit idle before it gets a heartbeat (and is re-opened when the
+    /// server closed the session); defaults to 60, `0` disables the heartbeat.
+    #[serde(default)]
+    pub keepalive_secs: Option<u64>,
+    /// In SQL data scripts, leave out tables the login cannot SELECT from (recorded as
+    /// `W-TABLE-UNREADABLE` with a commented placeholder) instead of failing the export.
+    #[serde(default)]
+    pub skip_unreadable: bool,
+    /// Rows per table in a review bundle (`/api/export/review`); defaults to 100.
+    #[serde(default)]
+    pub preview_rows: Option<usize>,
+    /// In a size estimate (`/api/export/estimate`), count the rows of tables without statistics
+    /// with `COUNT(*)`, which reads every row, instead of scaling up the count of a sample.
+    #[serde(default)]
+    pub exact_counts: bool,
+    /// Directory the job's `<job_id>/` folder is created in, e.g. one chosen in the desktop
+    /// shell's folder picker; `exports/` under the working directory when omitted.
+    #[serde(default)]
+    pub output_dir: Option<String>,
+}
+
+/// A named group of schema exports that run together, in member order, as one job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProject {
+    pub name: String,
+    /// Schemas in export order.
+    pub members: Vec<ProjectMember>,
+    /// Also export table data after each member's DDL.
+    #[serde(default)]
+    pub include_data: bool,
+    /// Export options shared by every member, using the `ExportRequest` field names
+    /// (e.g. `encoding`, `ddl_objects`, `batch_size`); `config`, `tables` and `export_schema`
+    /// come from the member.
+    #[serde(default)]
+    pub options: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMember {
+    pub config: ConnectionConfig,
+    pub tables: Vec<String>,
+    pub export_schema: Option<String>,
+}
+
+/// A saved single-schema export: the tables of one schema and the options to export them
+/// with. It runs against the saved connection (or the `DATABASE_*` environment), so no
+/// credentials are kept with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProfile {
+    pub name: String,
+    pub schema: String,
+    #[serde(default)]
+    pub export_schema: Option<String>,
+    /// Tables to export; may be empty when `options` sets `all_tables`.
+    #[serde(default)]
+    pub tables: Vec<String>,
+    /// Also export table data after the DDL.
+    #[serde(default)]
+    pub include_data: bool,
+    /// Export options using the `ExportRequest` field names (e.g. `data_format`,
+    /// `exclude_patterns`, `batch_size`).
+    #[serde(default)]
+    pub options: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,6 +690,20 @@ pub struct Sequence {
     pub start_with: Option<i64>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewDefinition {
+    pub name: String,
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectGrant {
+    pub table_name: String,
+    pub grantee: String,
+    pub privilege: String,
+    pub grantable: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TriggerDefinition {
     pub name: String,
@@ -135,11 +714,765 @@ pub struct TriggerDefinition {
     pub body: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CompareRequest {
+    pub left_path: String,
+    pub right_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StatementEntry {
+    pub key: String,
+    pub statement: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChangedStatement {
+    pub key: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareResponse {
+    pub added: Vec<StatementEntry>,
+    pub removed: Vec<StatementEntry>,
+    pub changed: Vec<ChangedStatement>,
+    pub unchanged_count: usize,
+}
+
+/// How dangerous an apply plan step is for existing data.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanRisk {
+    /// Creates or documents objects; nothing existing is touched.
+    Safe,
+    /// Replaces objects or may fail on existing data; no data is lost.
+    Review,
+    /// Drops tables, columns or sequence state, or changes column types.
+    Destructive,
+}
+
+impl PlanRisk {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlanRisk::Safe => "safe",
+            PlanRisk::Review => "review",
+            PlanRisk::Destructive => "destructive",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanStep {
+    pub number: usize,
+    pub risk: PlanRisk,
+    pub description: String,
+    pub statement: String,
+    /// Statement undoing this step; `None` when there is no automatic inverse.
+    pub rollback: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyPlan {
+    pub steps: Vec<PlanStep>,
+    /// Differences that need manual attention and are not part of the steps.
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyPlanResponse {
+    pub plan: ApplyPlan,
+    pub apply_path: String,
+    pub rollback_path: String,
+}
+
+/// `manifest.json` written next to a data export: what was exported, for later verification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub job_id: String,
+    pub generated_at: String,
+    pub source_schema: String,
+    pub target_schema: String,
+    #[serde(default)]
+    pub format: DataFormat,
+    /// File name of the data script in the same directory; empty for binary dumps, whose
+    /// tables name their own files.
+    pub data_file: String,
+    /// Set for preview exports, whose counts cannot prove a complete migration.
+    pub row_limit: Option<usize>,
+    /// Set for sampled exports, which cannot prove a complete migration either.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample: Option<SampleSpec>,
+    pub tables: Vec<ManifestTable>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestTable {
+    /// Table name on the target (after renames).
+    pub table: String,
+    pub source_table: String,
+    pub rows: usize,
+    /// SHA-256 (hex) of the table's generated INSERT statements as UTF-8 with LF line endings,
+    /// or of its binary file.
+    pub checksum: String,
+    /// Binary dumps: file holding the table's rows, relative to the manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Binary dumps: the columns of every row, in file order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<ManifestColumn>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ManifestColumn {
+    pub name: String,
+    pub data_type: String,
+    #[serde(default)]
+    pub identity: bool,
+    /// Longest value written for the column, in bytes; sizes the loader's bind buffers.
+    pub max_bytes: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BinaryImportRequest {
+    /// Connection to the target database.
+    pub config: ConnectionConfig,
+    /// `manifest.json` of a binary dump; the table files are read from its directory.
+    pub manifest_path: String,
+    /// Schema to load into; the manifest's target schema when omitted.
+    pub schema: Option<String>,
+    /// Rows bound per ODBC array insert; defaults to 1000.
+    pub batch_size: Option<usize>,
+}
+
+/// Body of `POST /api/cache/invalidate`; every omitted filter matches all entries.
+#[derive(Debug, Default, Deserialize)]
+pub struct CacheInvalidateRequest {
+    /// Only entries read with this connection's server and login.
+    #[serde(default)]
+    pub config: Option<ConnectionConfig>,
+    #[serde(default)]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub tables: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheInvalidateResponse {
+    /// Number of cached tables dropped.
+    pub invalidated: usize,
+}
+
+/// Where the ODBC driver used for new connections was found; serialized like the desktop
+/// shell's own driver discovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DriverSource {
+    /// Chosen through `POST /api/config/driver`, now or at an earlier run.
+    Selected,
+    /// `DM8_DRIVER_PATH`.
+    Env,
+    /// The driver shipped under `drivers/dm8/`.
+    Bundled,
+    /// The driver registered with the ODBC driver manager by name.
+    System,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriverInfo {
+    /// Library path, or the registered driver name for `System`.
+    pub path: String,
+    pub source: DriverSource,
+    /// Whether the library file exists; unknown for a registered driver.
+    pub exists: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigDbInfo {
+    pub path: String,
+    pub writable: bool,
+    /// Why the database cannot be written, when it cannot.
+    pub error: Option<String>,
+}
+
+/// Body of `POST /api/config/driver`.
+#[derive(Debug, Deserialize)]
+pub struct DriverPathRequest {
+    /// Driver library to use for new connections.
+    pub path: String,
+}
+
+/// Body and response of `POST /api/config/log-level`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLevelRequest {
+    /// `trace`, `debug`, `info`, `warn` or `error`.
+    pub level: String,
+}
+
+/// A set of saved settings kept apart from the others: its own default connection, export
+/// profiles and export history. The `default` workspace always exists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+    /// Whether saved settings are currently read from and written to this workspace.
+    pub active: bool,
+}
+
+/// Body of `POST /api/workspaces`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Response of `GET /api/config/export` and body part of `POST /api/config/import`: the saved
+/// settings as JSON, encrypted with AES-256-GCM under a key derived from a passphrase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedConfigBundle {
+    /// Always `amarone-config`.
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    /// Key derivation, `pbkdf2-sha256`.
+    pub kdf: String,
+    pub iterations: u32,
+    /// Base64 of the key derivation salt, the AES-GCM nonce and the encrypted settings.
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Body of `POST /api/config/import`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigImportRequest {
+    pub passphrase: String,
+    pub bundle: EncryptedConfigBundle,
+}
+
+/// What `POST /api/config/import` restored. Items already saved under the same name were
+/// replaced.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigImportResponse {
+    pub workspaces: usize,
+    pub connections: usize,
+    pub profiles: usize,
+    pub projects: usize,
+    pub settings: usize,
+}
+
+/// Response of `GET /api/logs/tail`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogTailResponse {
+    /// Newest log file, absent when nothing has been logged to disk yet.
+    pub file: Option<String>,
+    /// Oldest first.
+    pub lines: Vec<String>,
+}
+
+/// Body of `GET /api/driver/check`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriverCheckResponse {
+    #[serde(flatten)]
+    pub driver: DriverInfo,
+    /// Library that was loaded: the resolved path, or the file registered under the driver
+    /// name.
+    pub library: Option<String>,
+    pub loaded: bool,
+    /// Version from the driver manager's entry for the driver.
+    pub version: Option<String>,
+    /// Why the library could not be loaded.
+    pub error: Option<String>,
+}
+
+/// Stage of the connection diagnostics of `POST /api/connection/test`, in the order they run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStepKind {
+    /// Loading the ODBC driver library.
+    Driver,
+    /// Opening a TCP connection to `host:port`.
+    Tcp,
+    /// Logging in through the driver.
+    Login,
+    /// Running `SELECT 1`.
+    Query,
+    /// Finding the schema among the visible ones.
+    Schema,
+    /// Counting the tables of the schema.
+    Tables,
+}
+
+impl DiagnosticStepKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DiagnosticStepKind::Driver => "driver",
+            DiagnosticStepKind::Tcp => "tcp",
+            DiagnosticStepKind::Login => "login",
+            DiagnosticStepKind::Query => "query",
+            DiagnosticStepKind::Schema => "schema",
+            DiagnosticStepKind::Tables => "tables",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStatus {
+    Passed,
+    Failed,
+    /// Not run, because an earlier step failed or it does not apply (TCP with a DSN).
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticStep {
+    pub step: DiagnosticStepKind,
+    pub status: DiagnosticStatus,
+    pub duration_ms: u64,
+    pub message: String,
+    /// Tables visible in the schema, for the `tables` step.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// Body of `GET /api/health`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthResponse {
+    /// `ok`, or `degraded` when the driver file or the config database is unusable.
+    pub status: String,
+    pub version: String,
+    pub uptime_secs: u64,
+    pub driver: DriverInfo,
+    pub config_db: ConfigDbInfo,
+    /// Export jobs currently running in this process.
+    pub active_jobs: usize,
+}
+
+/// Body of `POST /api/tables/details/batch`.
+#[derive(Debug, Deserialize)]
+pub struct TableDetailsBatchRequest {
+    pub config: ConnectionConfig,
+    pub tables: Vec<String>,
+}
+
+/// Body of `POST /api/import/csv`; the upload variant sends it without `file_path`.
+#[derive(Debug, Deserialize)]
+pub struct CsvImportRequest {
+    /// Connection to the target database.
+    pub config: ConnectionConfig,
+    /// Schema to load into; the connection's schema when omitted.
+    pub schema: Option<String>,
+    pub table: String,
+    /// CSV file on the server; required unless the file is uploaded.
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// CSV column -> table column; every header column into the column of the same name
+    /// when empty.
+    #[serde(default)]
+    pub columns: Vec<CsvColumnMapping>,
+    #[serde(default = "default_true")]
+    pub has_header: bool,
+    /// Single-byte field separator; `,` when omitted.
+    pub delimiter: Option<String>,
+    /// Field text loaded as NULL; empty fields when omitted.
+    pub null_value: Option<String>,
+    /// Rows bound per ODBC array insert; defaults to 1000.
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumnMapping {
+    /// Header name (case-insensitive) or 1-based field position.
+    pub source: String,
+    pub target: String,
+}
+
+/// Rows loaded into each table by an import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub tables: Vec<TableImport>,
+    pub total_rows: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableImport {
+    pub table: String,
+    pub rows: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyRequest {
+    /// Connection to the target database.
+    pub config: ConnectionConfig,
+    pub manifest_path: String,
+    /// Schema to count in; the manifest's target schema when omitted.
+    pub schema: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableVerification {
+    pub table: String,
+    pub expected: usize,
+    pub actual: Option<i64>,
+    pub matches: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// Every table matches and the manifest describes a full export.
+    pub complete: bool,
+    pub tables: Vec<TableVerification>,
+}
+
+/// Where the figures of a table estimate come from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EstimateSource {
+    /// `NUM_ROWS` and `AVG_ROW_LEN` of an analyzed table.
+    Statistics,
+    /// A row count scaled up from a `SAMPLE` of the table and the average length of a sample
+    /// of rows.
+    Sampled,
+    /// An exact row count (`exact_counts`) and the average length of a sample of rows.
+    Counted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableEstimate {
+    pub table: String,
+    pub rows: u64,
+    /// Approximate data volume in bytes.
+    pub bytes: u64,
+    pub duration_ms: u64,
+    /// `None` when the table could not be estimated; `error` says why.
+    pub source: Option<EstimateSource>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportEstimate {
+    pub tables: Vec<TableEstimate>,
+    pub total_rows: u64,
+    pub total_bytes: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LintRequest {
+    pub config: ConnectionConfig,
+    pub tables: Vec<String>,
+}
+
+/// Construct checked by the export readiness linter.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    MissingPrimaryKey,
+    ReservedWord,
+    UnsupportedType,
+    CrossSchemaTrigger,
+    OversizedLob,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub severity: LintSeverity,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableLint {
+    pub table: String,
+    /// 0-100, lowered by every finding according to its severity.
+    pub score: u8,
+    pub findings: Vec<LintFinding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintReport {
+    /// Average of the table scores.
+    pub score: u8,
+    /// No table has an error-level finding.
+    pub ready: bool,
+    pub tables: Vec<TableLint>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExportResponse {
     pub success: bool,
     pub message: String,
     pub file_path: Option<String>,
+    pub job_id: Option<String>,
+    /// Per-job directory (`exports/<job_id>/`) holding every artifact of the run.
+    pub output_dir: Option<String>,
+    /// Batch sizing and buffer usage of every table the data export read.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory: Vec<TableMemoryStats>,
+    /// Everything the export skipped, cut or could not read, in the order it happened.
+    #[serde(default)]
+    pub warnings: Vec<ExportWarning>,
+    /// Rows, bytes and time per exported table, for migration sign-off reports.
+    #[serde(default)]
+    pub stats: ExportStats,
+}
+
+/// Volume and timing of an export run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExportStats {
+    /// One entry per table and script; a run writing DDL and data lists each table twice.
+    pub tables: Vec<TableExportStats>,
+    /// Wall time of all DDL and data exports of the run, metadata reads included.
+    pub duration_ms: u64,
+    /// Empty text values the data exports wrote, as `''` or `NULL` per `empty_string_as`.
+    #[serde(default)]
+    pub empty_strings: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TableExportStats {
+    /// Table name in the script, after renames.
+    pub table: String,
+    /// `ddl` or `data`.
+    pub kind: String,
+    /// Rows written; always 0 for DDL.
+    pub rows: usize,
+    /// Script bytes of the table's section, before re-encoding and line ending conversion.
+    pub bytes: u64,
+    pub duration_ms: u64,
+}
+
+/// One warning of an export run, as recorded on the job's timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExportWarning {
+    pub code: IssueCode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    pub message: String,
+}
+
+/// Memory use of one table's data export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TableMemoryStats {
+    pub table: String,
+    pub requested_batch_size: usize,
+    /// Rows per fetch actually used; smaller than requested when adaptive sizing shrank it.
+    pub batch_size: usize,
+    /// Fetch buffer bytes reserved per row (declared column widths, capped per value).
+    pub row_buffer_bytes: usize,
+    /// Capacity of the bound fetch buffer: `batch_size * row_buffer_bytes`.
+    pub fetch_buffer_bytes: usize,
+    /// Largest text payload of a single fetched batch.
+    pub peak_batch_bytes: usize,
+    /// Largest text payload of a single row.
+    pub peak_row_bytes: usize,
+    /// Batches the reader had to hold back because formatting or writing lagged behind.
+    pub backpressure_stalls: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJob {
+    pub id: String,
+    pub kind: String,
+    pub status: JobStatus,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub file_path: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobEventKind {
+    Started,
+    TableStarted,
+    TableFinished,
+    Warning,
+    Retry,
+    Finished,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobEvent {
+    pub occurred_at: String,
+    pub kind: JobEventKind,
+    /// Stable code of a warning or failure event.
+    #[serde(default)]
+    pub code: Option<IssueCode>,
+    pub table: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobTimeline {
+    pub job: ExportJob,
+    pub events: Vec<JobEvent>,
+}
+
+/// Machine-readable error category so the frontend can show targeted remediation hints.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    ConnectionFailed,
+    SchemaNotFound,
+    TableNotFound,
+    OdbcError,
+    IoError,
+    InvalidRequest,
+    ConfigStoreError,
+    NotImplemented,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn issue_code(self) -> IssueCode {
+        match self {
+            ErrorCode::ConnectionFailed => IssueCode::ConnectionFailed,
+            ErrorCode::SchemaNotFound => IssueCode::SchemaNotFound,
+            ErrorCode::TableNotFound => IssueCode::TableNotFound,
+            ErrorCode::OdbcError => IssueCode::OdbcError,
+            ErrorCode::IoError => IssueCode::IoError,
+            ErrorCode::InvalidRequest => IssueCode::InvalidRequest,
+            ErrorCode::ConfigStoreError => IssueCode::ConfigStoreError,
+            ErrorCode::NotImplemented => IssueCode::NotImplemented,
+            ErrorCode::Internal => IssueCode::Internal,
+        }
+    }
+}
+
+/// Stable identifier of every warning (`W-`) and error (`E-`) an export can report, so
+/// automation can gate deployments on specific classes of issues. Codes are never renamed or
+/// reused; new issues get new codes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum IssueCode {
+    /// Sequence metadata could not be read; sequences are left out.
+    #[serde(rename = "W-SEQ-UNAVAILABLE")]
+    SequencesUnavailable,
+    /// Row count for the header could not be computed.
+    #[serde(rename = "W-ROWCOUNT-UNAVAILABLE")]
+    RowCountUnavailable,
+    /// Tables on a foreign key cycle keep their requested order.
+    #[serde(rename = "W-FK-CYCLE")]
+    ForeignKeyCycle,
+    /// The source instance's case sensitivity could not be read.
+    #[serde(rename = "W-CASE-UNKNOWN")]
+    CaseSensitivityUnknown,
+    /// The source instance is case-insensitive; the target must be initialized the same way.
+    #[serde(rename = "W-CASE-INSENSITIVE")]
+    CaseInsensitiveSource,
+    /// View definitions could not be read; views are left out.
+    #[serde(rename = "W-VIEW-UNAVAILABLE")]
+    ViewsUnavailable,
+    /// Object grants could not be read; grants are left out.
+    #[serde(rename = "W-GRANT-UNAVAILABLE")]
+    GrantsUnavailable,
+    /// The written file could not be re-read for the charset check.
+    #[serde(rename = "W-CHARSET-CHECK-SKIPPED")]
+    CharsetCheckSkipped,
+    /// Invalid byte sequences or U+FFFD found in the written file.
+    #[serde(rename = "W-CHARSET-CORRUPT")]
+    CharsetCorruption,
+    /// Characters without a mapping in the file encoding were written as `?`.
+    #[serde(rename = "W-ENCODING-UNMAPPABLE")]
+    EncodingUnmappable,
+    /// A foreign key references a table in another schema, which is not exported.
+    #[serde(rename = "W-FK-EXTERNAL")]
+    ExternalForeignKey,
+    /// A table has more rows than the export's row limit; only the first rows were written.
+    #[serde(rename = "W-ROW-LIMIT")]
+    RowLimitReached,
+    /// Values longer than a spreadsheet cell can hold were cut.
+    #[serde(rename = "W-CELL-TRUNCATED")]
+    CellTruncated,
+    /// Values longer than the data export's per-column fetch limit were cut.
+    #[serde(rename = "W-VALUE-TRUNCATED")]
+    ValueTruncated,
+    /// An index without columns or duplicating another index was left out of the DDL.
+    #[serde(rename = "W-INDEX-SKIPPED")]
+    IndexSkipped,
+    /// A trigger's body is missing from the catalog; the trigger is left out.
+    #[serde(rename = "W-TRIGGER-INCOMPLETE")]
+    TriggerIncomplete,
+    /// The MAX of a column fed by a sequence could not be read; the sequence is reset to its
+    /// START value instead of being reseeded.
+    #[serde(rename = "W-SEQ-RESEED-SKIPPED")]
+    SequenceReseedSkipped,
+    /// A table has empty text values, which the target may store as NULL or which were written
+    /// as NULL per `empty_string_as`.
+    #[serde(rename = "W-EMPTY-STRING")]
+    EmptyStrings,
+    /// Rows with values unfit for their column type were skipped or written with NULLs
+    /// (`on_row_error`).
+    #[serde(rename = "W-ROW-ERROR")]
+    RowErrors,
+    /// The login cannot read a table; its data was left out (`skip_unreadable`).
+    #[serde(rename = "W-TABLE-UNREADABLE")]
+    TableUnreadable,
+    /// A table without a primary key was sampled at random although the `sample` has a seed;
+    /// another run picks other rows.
+    #[serde(rename = "W-SAMPLE-NOT-REPRODUCIBLE")]
+    SampleNotReproducible,
+    /// A foreign key on a cycle (or referencing its own table) is not followed by a consistent
+    /// sample; its rows may reference rows left out of the subset.
+    #[serde(rename = "W-SUBSET-FK-UNFOLLOWED")]
+    SubsetForeignKeyUnfollowed,
+    #[serde(rename = "E-CONN-FAILED")]
+    ConnectionFailed,
+    #[serde(rename = "E-SCHEMA-NOT-FOUND")]
+    SchemaNotFound,
+    #[serde(rename = "E-TABLE-NOT-FOUND")]
+    TableNotFound,
+    #[serde(rename = "E-ODBC")]
+    OdbcError,
+    #[serde(rename = "E-IO")]
+    IoError,
+    #[serde(rename = "E-INVALID-REQUEST")]
+    InvalidRequest,
+    #[serde(rename = "E-CONFIG-STORE")]
+    ConfigStoreError,
+    #[serde(rename = "E-NOT-IMPLEMENTED")]
+    NotImplemented,
+    #[serde(rename = "E-INTERNAL")]
+    Internal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: Option<i64>,
+    pub occurred_at: String,
+    pub client_ip: Option<String>,
+    pub username: String,
+    pub host: String,
+    pub port: u16,
+    pub kind: String,
+    pub source_schema: String,
+    pub target_schema: Option<String>,
+    pub tables: Vec<String>,
+    pub success: bool,
+    pub file_path: Option<String>,
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -147,6 +1480,10 @@ pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    pub code: Option<ErrorCode>,
+    /// Stable taxonomy code matching `code` (see `IssueCode`).
+    pub issue_code: Option<IssueCode>,
+    pub details: Option<String>,
 }
 
 impl<T> ApiResponse<T> {
@@ -155,14 +1492,20 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            code: None,
+            issue_code: None,
+            details: None,
         }
     }
 
-    pub fn error(message: String) -> Self {
+    pub fn failure(code: ErrorCode, message: String, details: Option<String>) -> Self {
         Self {
             success: false,
             data: None,
             error: Some(message),
+            code: Some(code),
+            issue_code: Some(code.issue_code()),
+            details,
         }
     }
 }
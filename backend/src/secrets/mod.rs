@@ -0,0 +1,343 @@
+use std::{
+    collections::HashSet,
+    fs,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// References the server resolves: those of connections saved in the config store or set in
+/// the `DATABASE_*` environment. A reference that only arrives in a request body is refused,
+/// so a client cannot read the server's files, environment variables or Vault secrets by
+/// naming them.
+static TRUSTED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn trusted() -> &'static Mutex<HashSet<String>> {
+    TRUSTED.get_or_init(Mutex::default)
+}
+
+/// Allows the references among `values` to be resolved; other values are ignored.
+pub fn trust_references<'a>(values: impl IntoIterator<Item = &'a str>) {
+    let mut trusted = trusted().lock().unwrap_or_else(|e| e.into_inner());
+    for value in values {
+        if is_secret_reference(value) {
+            trusted.insert(value.trim().to_string());
+        }
+    }
+}
+
+/// Whether `value` is a reference [`trust_references`] allowed.
+pub fn is_trusted_reference(value: &str) -> bool {
+    trusted()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains(value.trim())
+}
+
+/// Resolves a credential reference such as `env:DM8_PASSWORD` into its secret value.
+pub trait SecretProvider: Send + Sync {
+    /// Scheme handled by this provider, e.g. `env` for `${env:NAME}`.
+    fn scheme(&self) -> &'static str;
+
+    fn fetch(&self, reference: &str) -> Result<String>;
+}
+
+/// Reads the secret from an environment variable: `${env:DM8_PASSWORD}`.
+pub struct EnvSecretProvider;
+
+impl SecretProvider for EnvSecretProvider {
+    fn scheme(&self) -> &'static str {
+        "env"
+    }
+
+    fn fetch(&self, reference: &str) -> Result<String> {
+        std::env::var(reference)
+            .with_context(|| format!("Environment variable '{}' is not set", reference))
+    }
+}
+
+/// Reads the secret from a file, trimming the trailing newline: `${file:/run/secrets/dm8}`.
+pub struct FileSecretProvider;
+
+impl SecretProvider for FileSecretProvider {
+    fn scheme(&self) -> &'static str {
+        "file"
+    }
+
+    fn fetch(&self, reference: &str) -> Result<String> {
+        let content = fs::read_to_string(reference)
+            .with_context(|| format!("Failed to read secret file '{}'", reference))?;
+        Ok(content.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// Reads a field from a HashiCorp Vault KV secret: `${vault:secret/data/dm8#password}`.
+///
+/// The server and token come from `VAULT_ADDR` and `VAULT_TOKEN`; `VAULT_NAMESPACE` is sent
+/// when set. The configured address is the only one ever contacted: the reference is a path
+/// below it. Both KV v1 (`data.<field>`) and KV v2 (`data.data.<field>`) responses are accepted.
+pub struct VaultSecretProvider {
+    address: Option<String>,
+    token: Option<String>,
+    namespace: Option<String>,
+}
+
+impl VaultSecretProvider {
+    pub fn from_env() -> Self {
+        let read = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            address: read("VAULT_ADDR"),
+            token: read("VAULT_TOKEN"),
+            namespace: read("VAULT_NAMESPACE"),
+        }
+    }
+}
+
+impl SecretProvider for VaultSecretProvider {
+    fn scheme(&self) -> &'static str {
+        "vault"
+    }
+
+    fn fetch(&self, reference: &str) -> Result<String> {
+        let (path, field) = reference
+            .rsplit_once('#')
+            .ok_or_else(|| anyhow!("Vault reference must look like 'path#field'"))?;
+        ensure!(!path.trim().is_empty(), "Vault secret path is empty");
+        ensure!(!field.trim().is_empty(), "Vault secret field is empty");
+
+        let address = self
+            .address
+            .as_deref()
+            .ok_or_else(|| anyhow!("VAULT_ADDR is not set"))?;
+        let token = self
+            .token
+            .as_deref()
+            .ok_or_else(|| anyhow!("VAULT_TOKEN is not set"))?;
+
+        let url = vault_url(address, path)?;
+        let mut request = ureq::get(&url)
+            .timeout(Duration::from_secs(10))
+            .set("X-Vault-Token", token);
+        if let Some(namespace) = &self.namespace {
+            request = request.set("X-Vault-Namespace", namespace);
+        }
+
+        let body: serde_json::Value = blocking(|| {
+            request
+                .call()
+                .with_context(|| format!("Vault request to '{}' failed", path))?
+                .into_json()
+                .context("Vault returned an invalid JSON body")
+        })?;
+
+        vault_field(&body, field.trim())
+            .ok_or_else(|| anyhow!("Vault secret '{}' has no string field '{}'", path, field))
+    }
+}
+
+/// URL of the secret at `path` on the Vault server at `address`. The path may not leave the
+/// server's `/v1/` API or carry a query.
+fn vault_url(address: &str, path: &str) -> Result<String> {
+    let path = path.trim().trim_start_matches('/');
+    ensure!(
+        !path.contains("://")
+            && !path.contains(['?', '\\', '@'])
+            && !path
+                .split('/')
+                .any(|segment| segment == ".." || segment == "."),
+        "Vault secret path '{}' must be a plain path below the configured VAULT_ADDR",
+        path
+    );
+    Ok(format!("{}/v1/{}", address.trim_end_matches('/'), path))
+}
+
+/// Runs the blocking `call` so it does not stall the async worker thread it is called from:
+/// inside the multi-threaded runtime the worker hands its other tasks off first.
+fn blocking<T>(call: impl FnOnce() -> T) -> T {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(call)
+        }
+        _ => call(),
+    }
+}
+
+fn vault_field(body: &serde_json::Value, field: &str) -> Option<String> {
+    let data = body.get("data")?;
+    data.get("data")
+        .and_then(|inner| inner.get(field))
+        .or_else(|| data.get(field))
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+/// Dispatches `${scheme:reference}` values to the registered providers.
+pub struct SecretResolver {
+    providers: Vec<Box<dyn SecretProvider>>,
+}
+
+impl Default for SecretResolver {
+    fn default() -> Self {
+        Self::new()
+            .with_provider(EnvSecretProvider)
+            .with_provider(FileSecretProvider)
+            .with_provider(VaultSecretProvider::from_env())
+    }
+}
+
+impl SecretResolver {
+    pub fn new() -> Self {
+        Self {
+            providers: Vec::new(),
+        }
+    }
+
+    pub fn with_provider(mut self, provider: impl SecretProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Like [`SecretResolver::resolve`], but refuses references that are not saved on this
+    /// server (see [`trust_references`]).
+    pub fn resolve_trusted(&self, value: &str) -> Result<String> {
+        ensure!(
+            !is_secret_reference(value) || is_trusted_reference(value),
+            "Secret reference '{}' is not saved on this server; only saved connections and the \
+             DATABASE_* environment may use secret references",
+            value.trim()
+        );
+        self.resolve(value)
+    }
+
+    /// Returns the secret for a reference, or the value unchanged when it is not a reference.
+    pub fn resolve(&self, value: &str) -> Result<String> {
+        let Some((scheme, reference)) = parse_reference(value) else {
+            return Ok(value.to_string());
+        };
+
+        let provider = self
+            .providers
+            .iter()
+            .find(|provider| provider.scheme().eq_ignore_ascii_case(scheme));
+        match provider {
+            Some(provider) => provider
+                .fetch(reference)
+                .with_context(|| format!("Failed to resolve secret '{}'", value)),
+            None => bail!("No secret provider registered for scheme '{}'", scheme),
+        }
+    }
+}
+
+/// Returns true when the value is a `${scheme:reference}` secret reference.
+pub fn is_secret_reference(value: &str) -> bool {
+    parse_reference(value).is_some()
+}
+
+fn parse_reference(value: &str) -> Option<(&str, &str)> {
+    let inner = value.trim().strip_prefix("${")?.strip_suffix('}')?;
+    let (scheme, reference) = inner.split_once(':')?;
+    let scheme = scheme.trim();
+    if scheme.is_empty()
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return None;
+    }
+    Some((scheme, reference.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_secret_reference, trust_references, vault_field, vault_url, SecretProvider,
+        SecretResolver,
+    };
+    use std::io::Write;
+
+    struct StaticProvider;
+
+    impl SecretProvider for StaticProvider {
+        fn scheme(&self) -> &'static str {
+            "static"
+        }
+
+        fn fetch(&self, reference: &str) -> anyhow::Result<String> {
+            Ok(format!("secret-for-{}", reference))
+        }
+    }
+
+    #[test]
+    fn resolve_passes_plain_values_through() {
+        let resolver = SecretResolver::new();
+        assert_eq!(resolver.resolve("SYSDBA").unwrap(), "SYSDBA");
+        assert_eq!(resolver.resolve("${not closed").unwrap(), "${not closed");
+        assert!(!is_secret_reference("pa${ss}"));
+    }
+
+    #[test]
+    fn resolve_dispatches_to_registered_provider() {
+        let resolver = SecretResolver::new().with_provider(StaticProvider);
+        assert_eq!(resolver.resolve("${static:db}").unwrap(), "secret-for-db");
+        assert!(resolver.resolve("${missing:db}").is_err());
+    }
+
+    #[test]
+    fn file_provider_trims_trailing_newline() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "s3cret").unwrap();
+        let reference = format!("${{file:{}}}", file.path().display());
+        let resolved = SecretResolver::default().resolve(&reference).unwrap();
+        assert_eq!(resolved, "s3cret");
+    }
+
+    #[test]
+    fn only_trusted_references_resolve() {
+        let resolver = SecretResolver::new().with_provider(StaticProvider);
+        let err = resolver.resolve_trusted("${static:request}").unwrap_err();
+        assert!(err.to_string().contains("is not saved on this server"));
+        assert_eq!(resolver.resolve_trusted("plain").unwrap(), "plain");
+
+        trust_references(["${static:saved}", "SYSDBA"]);
+        assert_eq!(
+            resolver.resolve_trusted(" ${static:saved} ").unwrap(),
+            "secret-for-saved"
+        );
+    }
+
+    #[test]
+    fn vault_paths_stay_below_the_configured_server() {
+        assert_eq!(
+            vault_url("https://vault.local:8200/", "/secret/data/dm8").unwrap(),
+            "https://vault.local:8200/v1/secret/data/dm8"
+        );
+        for path in [
+            "https://evil.example/v1/x",
+            "secret/../../sys/raw",
+            "secret?x=1",
+            "@evil.example/x",
+        ] {
+            assert!(
+                vault_url("https://vault.local:8200", path).is_err(),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn vault_field_supports_kv_v1_and_v2() {
+        let v2 = serde_json::json!({ "data": { "data": { "password": "v2" } } });
+        let v1 = serde_json::json!({ "data": { "password": "v1" } });
+        assert_eq!(vault_field(&v2, "password").as_deref(), Some("v2"));
+        assert_eq!(vault_field(&v1, "password").as_deref(), Some("v1"));
+        assert_eq!(vault_field(&v1, "missing"), None);
+    }
+}
@@ -1,13 +1,29 @@
 import { useEffect, useState } from 'react'
-import { Form, Input, message, Space, Row, Col } from 'antd'
+import { Form, Input, message, Space, Row, Col, Switch, Select } from 'antd'
 import { CheckCircleOutlined, CloseCircleOutlined, ArrowRightOutlined, CloudDownloadOutlined, SaveOutlined, ThunderboltOutlined } from '@ant-design/icons'
-import type { ConnectionConfig, DriverInfo } from '@/types'
-import { testConnection, getSavedConnection, saveConnection, getDriverInfo } from '@/services/api'
+import type { ConnectionConfig, DriverCandidate, DriverInfo } from '@/types'
+import { testConnection, getSavedConnection, saveConnection, getDriverInfo, listDrivers, selectDriver } from '@/services/api'
 import { useExportStore } from '@/store/useExportStore'
 import { TechCard } from './common/TechCard'
 import { TechButton } from './common/TechButton'
 import { SectionHeader } from './common/SectionHeader'
 
+interface ConnectionFormValues {
+  host?: string
+  port?: string
+  username: string
+  password: string
+  schema: string
+  export_schema?: string
+  dsn?: string
+  ssl?: boolean
+  ssl_cert_path?: string
+  ssl_cert_password?: string
+  ssl_cipher?: string
+  login_timeout_secs?: string
+  query_timeout_secs?: string
+}
+
 export default function ConnectionForm() {
   const [form] = Form.useForm()
   const [loading, setLoading] = useState(false)
@@ -15,6 +31,7 @@ export default function ConnectionForm() {
   const [saving, setSaving] = useState(false)
   const [hasUnsavedChanges, setHasUnsavedChanges] = useState(false)
   const [connectionStatus, setConnectionStatus] = useState<'success' | 'error' | null>(null)
+  const [driverCandidates, setDriverCandidates] = useState<DriverCandidate[]>([])
   
   const setConnectionConfig = useExportStore((state) => state.setConnectionConfig)
   const nextStep = useExportStore((state) => state.nextStep)
@@ -24,25 +41,44 @@ export default function ConnectionForm() {
   const driverInfo = useExportStore((state) => state.driverInfo)
   const setDriverInfo = useExportStore((state) => state.setDriverInfo)
 
-  const normalizeExportSchema = (value?: string) => {
+  const normalizeOptional = (value?: string) => {
     const trimmed = value?.trim()
     return trimmed ? trimmed : undefined
   }
 
+  // With a DSN the data source supplies driver, host and port
+  const dsnValue = Form.useWatch('dsn', form)
+  const usesDsn = Boolean(normalizeOptional(dsnValue))
+  const sslEnabled = Boolean(Form.useWatch('ssl', form))
+
+  const parseSeconds = (value?: string) => {
+    const trimmed = value?.trim()
+    return trimmed ? parseInt(trimmed) : undefined
+  }
+
+  const toConfig = (values: ConnectionFormValues): ConnectionConfig => ({
+    host: values.host ?? '',
+    port: values.port ? parseInt(values.port) : 0,
+    username: values.username,
+    password: values.password,
+    schema: values.schema,
+    export_schema: normalizeOptional(values.export_schema),
+    dsn: normalizeOptional(values.dsn),
+    ssl: Boolean(values.ssl),
+    ssl_cert_path: normalizeOptional(values.ssl_cert_path),
+    ssl_cert_password: normalizeOptional(values.ssl_cert_password),
+    ssl_cipher: normalizeOptional(values.ssl_cipher),
+    login_timeout_secs: parseSeconds(values.login_timeout_secs),
+    query_timeout_secs: parseSeconds(values.query_timeout_secs),
+  })
+
   const handleTest = async () => {
     try {
       const values = await form.validateFields()
       setLoading(true)
       setConnectionStatus(null)
 
-      const config: ConnectionConfig = {
-        host: values.host,
-        port: parseInt(values.port),
-        username: values.username,
-        password: values.password,
-        schema: values.schema,
-        export_schema: normalizeExportSchema(values.export_schema),
-      }
+      const config = toConfig(values)
 
       const result = await testConnection(config)
 
@@ -70,7 +106,14 @@ export default function ConnectionForm() {
         const { config, source, updated_at } = result.data
         form.setFieldsValue({
           ...config,
-          port: config.port?.toString(),
+          port: config.port ? config.port.toString() : '',
+          dsn: config.dsn ?? '',
+          ssl: config.ssl ?? false,
+          ssl_cert_path: config.ssl_cert_path ?? '',
+          ssl_cert_password: config.ssl_cert_password ?? '',
+          ssl_cipher: config.ssl_cipher ?? '',
+          login_timeout_secs: config.login_timeout_secs?.toString() ?? '',
+          query_timeout_secs: config.query_timeout_secs?.toString() ?? '',
         })
         setConnectionConfig(
           { ...config, source, updated_at },
@@ -100,14 +143,7 @@ export default function ConnectionForm() {
     try {
       const values = await form.validateFields()
       setSaving(true)
-      const payload: ConnectionConfig = {
-        host: values.host,
-        port: parseInt(values.port),
-        username: values.username,
-        password: values.password,
-        schema: values.schema,
-        export_schema: normalizeExportSchema(values.export_schema),
-      }
+      const payload = toConfig(values)
       const result = await saveConnection(payload)
       if (result.success && result.data) {
         const { config, source, updated_at } = result.data
@@ -131,6 +167,21 @@ export default function ConnectionForm() {
     }
   }
 
+  // Several DM installations: let the user choose which driver new connections load
+  const handleDriverChange = async (path: string) => {
+    try {
+      const info = await selectDriver(path)
+      setDriverInfo(info)
+      setDriverCandidates((candidates) =>
+        candidates.map((candidate) => ({ ...candidate, selected: candidate.path === info.path }))
+      )
+      setConnectionStatus(null)
+      message.success('驱动已切换')
+    } catch (error) {
+      message.error(typeof error === 'string' ? error : '切换驱动失败')
+    }
+  }
+
   const handleNext = async () => {
     if (connectionStatus === 'success') {
       nextStep()
@@ -148,6 +199,7 @@ export default function ConnectionForm() {
         }
       })
       .catch(() => {})
+    listDrivers().then(setDriverCandidates)
   }, [])
 
   return (
@@ -169,6 +221,20 @@ export default function ConnectionForm() {
         )}
       </div>
 
+      {driverCandidates.length > 1 && (
+        <Form.Item label={<span style={{ fontFamily: 'JetBrains Mono' }}>ODBC 驱动 (DRIVER)</span>}>
+          <Select
+            value={driverCandidates.find((candidate) => candidate.selected)?.path}
+            onChange={handleDriverChange}
+            options={driverCandidates.map((candidate) => ({
+              value: candidate.path,
+              label: `${candidate.source} // ${candidate.path}`,
+            }))}
+            style={{ fontFamily: 'JetBrains Mono' }}
+          />
+        </Form.Item>
+      )}
+
       <Form
         form={form}
         layout="vertical"
@@ -179,6 +245,13 @@ export default function ConnectionForm() {
           password: '',
           schema: 'SYSDBA',
           export_schema: '',
+          dsn: '',
+          ssl: false,
+          ssl_cert_path: '',
+          ssl_cert_password: '',
+          ssl_cipher: '',
+          login_timeout_secs: '',
+          query_timeout_secs: '',
         }}
         onValuesChange={() => {
           setHasUnsavedChanges(true)
@@ -186,23 +259,30 @@ export default function ConnectionForm() {
           setConnectionStatus(null)
         }}
       >
+        <Form.Item
+          label={<span style={{ fontFamily: 'JetBrains Mono' }}>数据源 (DSN)</span>}
+          name="dsn"
+        >
+          <Input placeholder="留空则按主机与端口直连" style={{ fontFamily: 'JetBrains Mono' }} />
+        </Form.Item>
+
         <Row gutter={24}>
           <Col span={16}>
             <Form.Item
               label={<span style={{ fontFamily: 'JetBrains Mono' }}>主机地址 (HOST)</span>}
               name="host"
-              rules={[{ required: true, message: '请输入主机地址' }]}
+              rules={[{ required: !usesDsn, message: '请输入主机地址' }]}
             >
-              <Input placeholder="localhost" style={{ fontFamily: 'JetBrains Mono' }} />
+              <Input placeholder="localhost" disabled={usesDsn} style={{ fontFamily: 'JetBrains Mono' }} />
             </Form.Item>
           </Col>
           <Col span={8}>
             <Form.Item
               label={<span style={{ fontFamily: 'JetBrains Mono' }}>端口 (PORT)</span>}
               name="port"
-              rules={[{ required: true, message: '请输入端口' }]}
+              rules={[{ required: !usesDsn, message: '请输入端口' }]}
             >
-              <Input placeholder="5236" style={{ fontFamily: 'JetBrains Mono' }} />
+              <Input placeholder="5236" disabled={usesDsn} style={{ fontFamily: 'JetBrains Mono' }} />
             </Form.Item>
           </Col>
         </Row>
@@ -243,6 +323,63 @@ export default function ConnectionForm() {
           <Input placeholder="留空默认同 SCHEMA" style={{ fontFamily: 'JetBrains Mono' }} />
         </Form.Item>
 
+        <Row gutter={24}>
+          <Col span={12}>
+            <Form.Item
+              label={<span style={{ fontFamily: 'JetBrains Mono' }}>登录超时 (秒)</span>}
+              name="login_timeout_secs"
+            >
+              <Input placeholder="15，0 表示不限制" style={{ fontFamily: 'JetBrains Mono' }} />
+            </Form.Item>
+          </Col>
+          <Col span={12}>
+            <Form.Item
+              label={<span style={{ fontFamily: 'JetBrains Mono' }}>语句超时 (秒)</span>}
+              name="query_timeout_secs"
+            >
+              <Input placeholder="30，0 表示不限制" style={{ fontFamily: 'JetBrains Mono' }} />
+            </Form.Item>
+          </Col>
+        </Row>
+
+        <Form.Item
+          label={<span style={{ fontFamily: 'JetBrains Mono' }}>加密连接 (SSL)</span>}
+          name="ssl"
+          valuePropName="checked"
+        >
+          <Switch />
+        </Form.Item>
+
+        {sslEnabled && (
+          <Row gutter={24}>
+            <Col span={12}>
+              <Form.Item
+                label={<span style={{ fontFamily: 'JetBrains Mono' }}>证书目录 (SSL_PATH)</span>}
+                name="ssl_cert_path"
+                rules={[{ required: true, message: '请输入证书目录' }]}
+              >
+                <Input placeholder="/opt/dmdbms/client_ssl/SYSDBA" style={{ fontFamily: 'JetBrains Mono' }} />
+              </Form.Item>
+            </Col>
+            <Col span={6}>
+              <Form.Item
+                label={<span style={{ fontFamily: 'JetBrains Mono' }}>证书密码 (SSL_PWD)</span>}
+                name="ssl_cert_password"
+              >
+                <Input.Password placeholder="可选" style={{ fontFamily: 'JetBrains Mono' }} />
+              </Form.Item>
+            </Col>
+            <Col span={6}>
+              <Form.Item
+                label={<span style={{ fontFamily: 'JetBrains Mono' }}>加密算法 (CIPHER)</span>}
+                name="ssl_cipher"
+              >
+                <Input placeholder="可选" style={{ fontFamily: 'JetBrains Mono' }} />
+              </Form.Item>
+            </Col>
+          </Row>
+        )}
+
         <div style={{ marginTop: 32, display: 'flex', justifyContent: 'space-between', alignItems: 'center', borderTop: '1px solid rgba(255,255,255,0.1)', paddingTop: 24 }}>
           <Space>
             <TechButton onClick={() => loadSaved(true)} loading={loadingSaved} icon={<CloudDownloadOutlined />}>
@@ -1,9 +1,9 @@
 import { useState, useEffect } from 'react'
 import { Form, Checkbox, Input, InputNumber, Space, message, Progress, Typography, Row, Col, Select } from 'antd'
-import { ClockCircleOutlined, FileTextOutlined, DatabaseOutlined, RocketOutlined } from '@ant-design/icons'
+import { ClockCircleOutlined, FileTextOutlined, DatabaseOutlined, RocketOutlined, FolderOpenOutlined } from '@ant-design/icons'
 import { animate } from 'animejs'
-import type { ExportRequest } from '@/types'
-import { exportDDL, exportData } from '@/services/api'
+import type { DdlObjectType, ExportRequest, ExportWarning, TableExportStats } from '@/types'
+import { exportDDL, exportData, isTauri, chooseExportDirectory, revealExportFile } from '@/services/api'
 import { calcProgress } from '@/utils/exportProgress'
 import { useExportStore } from '@/store/useExportStore'
 import { TechCard } from './common/TechCard'
@@ -12,6 +12,16 @@ import { SectionHeader } from './common/SectionHeader'
 
 const { Text } = Typography;
 
+const DDL_OBJECT_OPTIONS: { value: DdlObjectType; label: string }[] = [
+  { value: 'tables', label: '表 (CREATE TABLE)' },
+  { value: 'comments', label: '注释 (COMMENT)' },
+  { value: 'constraints', label: '约束 (PK/UNIQUE/CHECK/FK)' },
+  { value: 'indexes', label: '索引 (INDEX)' },
+  { value: 'sequences', label: '序列 (SEQUENCE)' },
+  { value: 'triggers', label: '触发器 (TRIGGER)' },
+  { value: 'views', label: '视图 (VIEW)' },
+]
+
 export default function ExportConfig() {
   const config = useExportStore((state) => state.connectionConfig)
   const selectedTables = useExportStore((state) => state.selectedTables)
@@ -23,7 +33,7 @@ export default function ExportConfig() {
   const [progressStatus, setProgressStatus] = useState<'normal' | 'active' | 'success' | 'exception'>('normal')
   const [hasError, setHasError] = useState(false)
   const [elapsedTime, setElapsedTime] = useState(0)
-  const [exportResult, setExportResult] = useState<{ ddl?: string; data?: string } | null>(null)
+  const [exportResult, setExportResult] = useState<{ ddl?: string; data?: string; warnings: ExportWarning[]; stats: TableExportStats[] } | null>(null)
 
   // Animate progress bar using anime.js easing
   useEffect(() => {
@@ -69,6 +79,12 @@ export default function ExportConfig() {
     return `${m.toString().padStart(2, '0')}:${s.toString().padStart(2, '0')}`
   }
 
+  const formatBytes = (bytes: number) => {
+    if (bytes < 1024) return `${bytes} B`
+    if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`
+    return `${(bytes / 1024 / 1024).toFixed(1)} MB`
+  }
+
   const handleExport = async () => {
     if (!config || selectedTables.length === 0) {
       message.warning('序列中止: 前置条件未满足')
@@ -89,14 +105,19 @@ export default function ExportConfig() {
         export_schema: values.export_schema?.trim() || undefined,
         export_compat: values.export_compat,
         tables: selectedTables,
-        include_ddl: values.include_ddl,
+        ddl_objects: values.ddl_objects,
         include_data: values.include_data,
         batch_size: values.batch_size || 1000,
         drop_existing: values.drop_existing,
         include_row_counts: values.include_row_counts,
+        include_storage: values.include_storage,
+        include_grants: values.include_grants,
+        encoding: values.encoding,
+        line_ending: values.line_ending,
+        output_dir: values.output_dir?.trim() || undefined,
       }
 
-      const results: { ddl?: string; data?: string } = {}
+      const results: { ddl?: string; data?: string; warnings: ExportWarning[]; stats: TableExportStats[] } = { warnings: [], stats: [] }
       const totalSteps = (values.include_ddl ? 1 : 0) + (values.include_data ? 1 : 0)
       let completedSteps = 0
       let hadError = false
@@ -105,6 +126,8 @@ export default function ExportConfig() {
         const ddlResult = await exportDDL(request)
         if (ddlResult.success && ddlResult.data) {
           results.ddl = ddlResult.data.file_path
+          results.warnings.push(...(ddlResult.data.warnings ?? []))
+          results.stats.push(...(ddlResult.data.stats?.tables ?? []))
           message.success('DDL 已提取')
           completedSteps += 1
           const ddlProgress = calcProgress({
@@ -134,6 +157,8 @@ export default function ExportConfig() {
         const dataResult = await exportData(request)
         if (dataResult.success && dataResult.data) {
           results.data = dataResult.data.file_path
+          results.warnings.push(...(dataResult.data.warnings ?? []))
+          results.stats.push(...(dataResult.data.stats?.tables ?? []))
           message.success('数据已迁移')
           completedSteps += 1
           const dataProgress = calcProgress({
@@ -184,6 +209,32 @@ export default function ExportConfig() {
     }
   }
 
+  const handleChooseDirectory = async () => {
+    const dir = await chooseExportDirectory()
+    if (dir) {
+      form.setFieldsValue({ output_dir: dir })
+    }
+  }
+
+  const handleReveal = async (path: string) => {
+    try {
+      await revealExportFile(path)
+    } catch (error) {
+      message.error(typeof error === 'string' ? error : '无法打开文件位置')
+    }
+  }
+
+  const renderResultFile = (label: string, path: string) => (
+    <div>
+      &gt; {label}: <span style={{ color: '#fff' }}>{path}</span>
+      {isTauri() && (
+        <a style={{ marginLeft: 12 }} onClick={() => handleReveal(path)}>
+          <FolderOpenOutlined /> 打开位置
+        </a>
+      )}
+    </div>
+  )
+
   return (
     <TechCard>
       <SectionHeader title="导出控制台" subtitle="初始化数据传输序列" />
@@ -193,10 +244,17 @@ export default function ExportConfig() {
         layout="vertical"
         initialValues={{
           include_ddl: true,
+          ddl_objects: DDL_OBJECT_OPTIONS.map((option) => option.value).filter(
+            (value) => value !== 'views'
+          ),
           include_data: true,
           batch_size: 1000,
           drop_existing: true,
           include_row_counts: false,
+          include_storage: false,
+          include_grants: false,
+          encoding: 'utf8',
+          line_ending: 'lf',
         }}
       >
         <Row gutter={24}>
@@ -218,6 +276,18 @@ export default function ExportConfig() {
           </Col>
         </Row>
 
+        <Form.Item
+          label={<span style={{ fontFamily: 'JetBrains Mono' }}>DDL 对象类型</span>}
+          name="ddl_objects"
+          rules={[{ required: true, type: 'array', min: 1, message: '请至少选择一种对象类型' }]}
+        >
+          <Select
+            mode="multiple"
+            style={{ width: '100%', fontFamily: 'JetBrains Mono' }}
+            options={DDL_OBJECT_OPTIONS}
+          />
+        </Form.Item>
+
         <Row gutter={24}>
           <Col span={12}>
             <Form.Item name="drop_existing" valuePropName="checked">
@@ -235,6 +305,23 @@ export default function ExportConfig() {
           </Col>
         </Row>
 
+        <Row gutter={24}>
+          <Col span={12}>
+            <Form.Item name="include_storage" valuePropName="checked">
+              <Checkbox style={{ fontFamily: 'Orbitron', color: '#fff' }}>
+                保留表空间 (TABLESPACE)
+              </Checkbox>
+            </Form.Item>
+          </Col>
+          <Col span={12}>
+            <Form.Item name="include_grants" valuePropName="checked">
+              <Checkbox style={{ fontFamily: 'Orbitron', color: '#fff' }}>
+                导出权限 (GRANT)
+              </Checkbox>
+            </Form.Item>
+          </Col>
+        </Row>
+
         <Form.Item
           label={<span style={{ fontFamily: 'JetBrains Mono' }}>批次大小 (行/插入)</span>}
           name="batch_size"
@@ -257,6 +344,35 @@ export default function ExportConfig() {
               { value: 'datagrip', label: 'DataGrip 逐语句运行 (END; 不含 /)' },
               { value: 'datagrip-script', label: 'DataGrip 脚本模式 (触发器单独导出)' },
               { value: 'script', label: 'DBeaver/SQLark/DIsql (END; + /)' },
+              { value: 'oracle', label: 'Oracle 默认值方言' },
+              { value: 'postgresql', label: 'PostgreSQL 默认值方言' },
+              { value: 'mysql', label: 'MySQL 默认值方言' },
+            ]}
+          />
+        </Form.Item>
+
+        <Form.Item
+          label={<span style={{ fontFamily: 'JetBrains Mono' }}>文件编码</span>}
+          name="encoding"
+        >
+          <Select
+            style={{ width: '100%', fontFamily: 'JetBrains Mono' }}
+            options={[
+              { value: 'utf8', label: 'UTF-8' },
+              { value: 'gbk', label: 'GBK (旧版 DM8 工具)' },
+            ]}
+          />
+        </Form.Item>
+
+        <Form.Item
+          label={<span style={{ fontFamily: 'JetBrains Mono' }}>换行符</span>}
+          name="line_ending"
+        >
+          <Select
+            style={{ width: '100%', fontFamily: 'JetBrains Mono' }}
+            options={[
+              { value: 'lf', label: 'LF (Unix)' },
+              { value: 'crlf', label: 'CRLF (Windows DIsql)' },
             ]}
           />
         </Form.Item>
@@ -268,6 +384,19 @@ export default function ExportConfig() {
           <Input placeholder="留空默认同 SCHEMA" style={{ width: '100%', fontFamily: 'JetBrains Mono' }} />
         </Form.Item>
 
+        {isTauri() && (
+          <Form.Item label={<span style={{ fontFamily: 'JetBrains Mono' }}>导出目录 (OUTPUT DIR)</span>}>
+            <Space.Compact style={{ width: '100%' }}>
+              <Form.Item name="output_dir" noStyle>
+                <Input placeholder="留空默认 exports/" style={{ fontFamily: 'JetBrains Mono' }} />
+              </Form.Item>
+              <TechButton icon={<FolderOpenOutlined />} onClick={handleChooseDirectory}>
+                选择
+              </TechButton>
+            </Space.Compact>
+          </Form.Item>
+        )}
+
         <Form.Item>
           <Space direction="vertical" style={{ width: '100%', marginTop: 24 }}>
             <TechButton
@@ -306,8 +435,29 @@ export default function ExportConfig() {
                   任务完成
                 </div>
                 <Space direction="vertical" style={{ width: '100%', fontFamily: 'JetBrains Mono', fontSize: 12 }}>
-                  {exportResult.ddl && <div>&gt; DDL 文件: <span style={{ color: '#fff' }}>{exportResult.ddl}</span></div>}
-                  {exportResult.data && <div>&gt; 数据文件: <span style={{ color: '#fff' }}>{exportResult.data}</span></div>}
+                  {exportResult.ddl && renderResultFile('DDL 文件', exportResult.ddl)}
+                  {exportResult.data && renderResultFile('数据文件', exportResult.data)}
+                  {exportResult.stats.some((entry) => entry.kind === 'data') && (
+                    <div style={{ marginTop: 8, color: '#aaa' }}>
+                      {exportResult.stats
+                        .filter((entry) => entry.kind === 'data')
+                        .map((entry) => (
+                          <div key={entry.table}>
+                            {entry.table}: {entry.rows} 行 · {formatBytes(entry.bytes)} · {entry.duration_ms} ms
+                          </div>
+                        ))}
+                    </div>
+                  )}
+                  {exportResult.warnings.length > 0 && (
+                    <div style={{ marginTop: 8 }}>
+                      <div style={{ color: '#faad14', marginBottom: 4 }}>// 警告 ({exportResult.warnings.length})</div>
+                      {exportResult.warnings.map((warning, index) => (
+                        <div key={index} style={{ color: '#faad14' }}>
+                          [{warning.code}] {warning.table ? `${warning.table}: ` : ''}{warning.message}
+                        </div>
+                      ))}
+                    </div>
+                  )}
                   <div style={{ marginTop: 8, color: '#aaa' }}>// 总耗时: {formatTime(elapsedTime)}</div>
                 </Space>
               </div>
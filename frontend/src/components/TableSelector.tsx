@@ -2,7 +2,7 @@ import { useEffect, useState } from 'react'
 import { Collapse, Button, Space, Spin, Tag } from 'antd'
 import { DeleteOutlined, ReloadOutlined, CodeOutlined } from '@ant-design/icons'
 import { useExportStore } from '@/store/useExportStore'
-import { getTableDetails } from '@/services/api'
+import { getTableDetails, getTableDetailsBatch } from '@/services/api'
 import type { TableDetails } from '@/types'
 import { TechCard } from './common/TechCard'
 import { SectionHeader } from './common/SectionHeader'
@@ -35,12 +35,34 @@ export default function TableSelector() {
     }
   }
 
-  useEffect(() => {
-    selectedTables.forEach((name) => {
-      if (!detailsMap[name]) {
-        fetchDetails(name)
+  const fetchMissingDetails = async (tableNames: string[]) => {
+    const config = useExportStore.getState().connectionConfig
+    if (!config || tableNames.length === 0) return
+    const flags = (value: boolean) => Object.fromEntries(tableNames.map((name) => [name, value]))
+    setLoadingMap((prev) => ({ ...prev, ...flags(true) }))
+    try {
+      const res = await getTableDetailsBatch(config, tableNames)
+      const loaded: Record<string, TableDetails | null> = Object.fromEntries(
+        tableNames.map((name) => [name, null])
+      )
+      if (res.success && res.data) {
+        tableNames.forEach((name, index) => {
+          loaded[name] = res.data?.[index] ?? null
+        })
       }
-    })
+      setDetailsMap((prev) => ({ ...prev, ...loaded }))
+    } catch {
+      setDetailsMap((prev) => ({
+        ...prev,
+        ...Object.fromEntries(tableNames.map((name) => [name, null])),
+      }))
+    } finally {
+      setLoadingMap((prev) => ({ ...prev, ...flags(false) }))
+    }
+  }
+
+  useEffect(() => {
+    fetchMissingDetails(selectedTables.filter((name) => !detailsMap[name]))
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [selectedTables])
 
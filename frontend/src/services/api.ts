@@ -4,15 +4,47 @@ import type {
   ConnectionConfig,
   Table,
   TableDetails,
+  TableDependencies,
+  TableDetailsBatchRequest,
+  CacheInvalidateRequest,
+  CacheInvalidateResponse,
   ExportRequest,
   ExportResponse,
+  ExportEstimate,
   ApiResponse,
   TestConnectionResponse,
+  ServerInfo,
   StoredConnectionResponse,
+  EffectiveConfigResponse,
   DriverInfo,
+  DriverCandidate,
+  HealthResponse,
+  DriverCheckResponse,
+  DriverPathRequest,
+  LogLevel,
+  LogLevelRequest,
+  LogTailResponse,
+  CompareRequest,
+  CompareResponse,
+  ApplyPlanResponse,
+  ExportProfile,
+  Workspace,
+  WorkspaceRequest,
+  EncryptedConfigBundle,
+  ConfigImportResponse,
+  ExportProject,
+  AuditEntry,
+  JobTimeline,
+  LintRequest,
+  LintReport,
+  VerifyRequest,
+  VerifyReport,
+  BinaryImportRequest,
+  CsvImportRequest,
+  ImportReport,
 } from '../types';
 
-const isTauri = () => typeof window !== 'undefined' && '__TAURI_IPC__' in window;
+export const isTauri = () => typeof window !== 'undefined' && '__TAURI_IPC__' in window;
 
 async function resolveBaseUrl() {
   if (isTauri()) {
@@ -40,13 +72,14 @@ async function getApi() {
 }
 
 export const testConnection = async (
-  config: ConnectionConfig
+  config: ConnectionConfig,
+  diagnostics = false
 ): Promise<ApiResponse<TestConnectionResponse>> => {
   try {
     const api = await getApi();
     const response = await api.post<ApiResponse<TestConnectionResponse>>(
       '/connection/test',
-      config
+      { ...config, diagnostics }
     );
     return response.data;
   } catch (error) {
@@ -57,6 +90,140 @@ export const testConnection = async (
   }
 };
 
+export const getServerInfo = async (): Promise<ApiResponse<ServerInfo>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<ServerInfo>>('/server/info');
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '获取服务器信息失败',
+    };
+  }
+};
+
+export const getHealth = async (): Promise<ApiResponse<HealthResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<HealthResponse>>('/health');
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '后端无响应',
+    };
+  }
+};
+
+export const checkDriver = async (): Promise<ApiResponse<DriverCheckResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<DriverCheckResponse>>('/driver/check');
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '驱动检查失败',
+    };
+  }
+};
+
+export const saveDriverPath = async (
+  request: DriverPathRequest
+): Promise<ApiResponse<DriverCheckResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<DriverCheckResponse>>('/config/driver', request);
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '保存驱动路径失败',
+    };
+  }
+};
+
+// 最近的后端日志（默认 500 行，上限 5000），用于问题反馈
+export const tailLogs = async (lines = 500): Promise<ApiResponse<LogTailResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<LogTailResponse>>('/logs/tail', {
+      params: { lines },
+    });
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '读取后端日志失败',
+    };
+  }
+};
+
+export const saveLogLevel = async (level: LogLevel): Promise<ApiResponse<LogLevelRequest>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<LogLevelRequest>>('/config/log-level', { level });
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '设置日志级别失败',
+    };
+  }
+};
+
+/** Encrypted dump of every saved setting; the passphrase travels in a header, not the URL. */
+export const exportConfig = async (
+  passphrase: string
+): Promise<ApiResponse<EncryptedConfigBundle>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<EncryptedConfigBundle>>('/config/export', {
+      headers: { 'X-Config-Passphrase': passphrase },
+    });
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '导出配置失败',
+    };
+  }
+};
+
+export const importConfig = async (
+  passphrase: string,
+  bundle: EncryptedConfigBundle
+): Promise<ApiResponse<ConfigImportResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ConfigImportResponse>>('/config/import', {
+      passphrase,
+      bundle,
+    });
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '导入配置失败',
+    };
+  }
+};
+
+/** Which source (saved, environment or default) each connection field comes from. */
+export const getEffectiveConfig = async (): Promise<ApiResponse<EffectiveConfigResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<EffectiveConfigResponse>>('/config/effective');
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '获取生效配置失败',
+    };
+  }
+};
+
 export const getSavedConnection = async (): Promise<
   ApiResponse<StoredConnectionResponse>
 > => {
@@ -130,6 +297,65 @@ export const getTableDetails = async (
   }
 };
 
+export const getTableDetailsBatch = async (
+  config: ConnectionConfig,
+  tables: string[]
+): Promise<ApiResponse<TableDetails[]>> => {
+  try {
+    const api = await getApi();
+    const request: TableDetailsBatchRequest = { config, tables };
+    const response = await api.post<ApiResponse<TableDetails[]>>(
+      '/tables/details/batch',
+      request
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '批量获取表详情失败',
+    };
+  }
+};
+
+export const invalidateMetadataCache = async (
+  request: CacheInvalidateRequest = {}
+): Promise<ApiResponse<CacheInvalidateResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<CacheInvalidateResponse>>(
+      '/cache/invalidate',
+      request
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '清除元数据缓存失败',
+    };
+  }
+};
+
+export const getTableDependencies = async (
+  config: ConnectionConfig,
+  tableName: string
+): Promise<ApiResponse<TableDependencies>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<TableDependencies>>(
+      `/tables/${tableName}/dependencies`,
+      {
+        params: config,
+      }
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '获取表依赖失败',
+    };
+  }
+};
+
 export const exportDDL = async (
   request: ExportRequest
 ): Promise<ApiResponse<ExportResponse>> => {
@@ -166,6 +392,383 @@ export const exportData = async (
   }
 };
 
+export const exportReview = async (
+  request: ExportRequest
+): Promise<ApiResponse<ExportResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ExportResponse>>(
+      '/export/review',
+      request
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '导出预览包失败',
+    };
+  }
+};
+
+export const exportFull = async (
+  request: ExportRequest
+): Promise<ApiResponse<ExportResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ExportResponse>>(
+      '/export/full',
+      request
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '完整导出失败',
+    };
+  }
+};
+
+export const lintTables = async (
+  request: LintRequest
+): Promise<ApiResponse<LintReport>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<LintReport>>('/lint', request);
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '导出就绪检查失败',
+    };
+  }
+};
+
+export const estimateExport = async (
+  request: ExportRequest
+): Promise<ApiResponse<ExportEstimate>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ExportEstimate>>(
+      '/export/estimate',
+      request
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '估算导出规模失败',
+    };
+  }
+};
+
+export const verifyExport = async (
+  request: VerifyRequest
+): Promise<ApiResponse<VerifyReport>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<VerifyReport>>('/verify', request);
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '校验导出结果失败',
+    };
+  }
+};
+
+export const importBinaryDump = async (
+  request: BinaryImportRequest
+): Promise<ApiResponse<ImportReport>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ImportReport>>('/import/binary', request);
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '导入二进制数据失败',
+    };
+  }
+};
+
+export const importCsv = async (
+  request: CsvImportRequest
+): Promise<ApiResponse<ImportReport>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ImportReport>>('/import/csv', request);
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '导入 CSV 失败',
+    };
+  }
+};
+
+export const uploadCsv = async (
+  request: Omit<CsvImportRequest, 'file_path'>,
+  file: Blob
+): Promise<ApiResponse<ImportReport>> => {
+  try {
+    const api = await getApi();
+    const form = new FormData();
+    form.append('request', JSON.stringify(request));
+    form.append('file', file);
+    const response = await api.post<ApiResponse<ImportReport>>('/import/csv/upload', form);
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '上传 CSV 失败',
+    };
+  }
+};
+
+export const compareExports = async (
+  request: CompareRequest
+): Promise<ApiResponse<CompareResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<CompareResponse>>(
+      '/export/compare',
+      request
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '对比导出文件失败',
+    };
+  }
+};
+
+export const planExports = async (
+  request: CompareRequest
+): Promise<ApiResponse<ApplyPlanResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ApplyPlanResponse>>(
+      '/export/plan',
+      request
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '生成变更计划失败',
+    };
+  }
+};
+
+export const getJobTimeline = async (
+  jobId: string
+): Promise<ApiResponse<JobTimeline>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<JobTimeline>>(
+      `/export/jobs/${encodeURIComponent(jobId)}/timeline`
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '获取任务时间线失败',
+    };
+  }
+};
+
+export const getAuditLog = async (
+  limit?: number
+): Promise<ApiResponse<AuditEntry[]>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<AuditEntry[]>>('/audit', {
+      params: { limit },
+    });
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '获取审计日志失败',
+    };
+  }
+};
+
+export const listProjects = async (): Promise<ApiResponse<ExportProject[]>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<ExportProject[]>>('/projects');
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '获取导出项目失败',
+    };
+  }
+};
+
+export const saveProject = async (
+  project: ExportProject
+): Promise<ApiResponse<ExportProject>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ExportProject>>('/projects', project);
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '保存导出项目失败',
+    };
+  }
+};
+
+export const deleteProject = async (name: string): Promise<ApiResponse<boolean>> => {
+  try {
+    const api = await getApi();
+    const response = await api.delete<ApiResponse<boolean>>(
+      `/projects/${encodeURIComponent(name)}`
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '删除导出项目失败',
+    };
+  }
+};
+
+export const runProject = async (name: string): Promise<ApiResponse<ExportResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ExportResponse>>(
+      `/projects/${encodeURIComponent(name)}/run`
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '运行导出项目失败',
+    };
+  }
+};
+
+export const listProfiles = async (): Promise<ApiResponse<ExportProfile[]>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<ExportProfile[]>>('/config/profiles');
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '获取导出配置档失败',
+    };
+  }
+};
+
+export const saveProfile = async (
+  profile: ExportProfile
+): Promise<ApiResponse<ExportProfile>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ExportProfile>>('/config/profiles', profile);
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '保存导出配置档失败',
+    };
+  }
+};
+
+export const deleteProfile = async (name: string): Promise<ApiResponse<boolean>> => {
+  try {
+    const api = await getApi();
+    const response = await api.delete<ApiResponse<boolean>>(
+      `/config/profiles/${encodeURIComponent(name)}`
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '删除导出配置档失败',
+    };
+  }
+};
+
+export const runProfile = async (name: string): Promise<ApiResponse<ExportResponse>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<ExportResponse>>(
+      `/config/profiles/${encodeURIComponent(name)}/run`
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '运行导出配置档失败',
+    };
+  }
+};
+
+export const listWorkspaces = async (): Promise<ApiResponse<Workspace[]>> => {
+  try {
+    const api = await getApi();
+    const response = await api.get<ApiResponse<Workspace[]>>('/workspaces');
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '获取工作区失败',
+    };
+  }
+};
+
+export const saveWorkspace = async (
+  request: WorkspaceRequest
+): Promise<ApiResponse<Workspace>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<Workspace>>('/workspaces', request);
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '保存工作区失败',
+    };
+  }
+};
+
+export const activateWorkspace = async (name: string): Promise<ApiResponse<boolean>> => {
+  try {
+    const api = await getApi();
+    const response = await api.post<ApiResponse<boolean>>(
+      `/workspaces/${encodeURIComponent(name)}/activate`
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '切换工作区失败',
+    };
+  }
+};
+
+export const deleteWorkspace = async (name: string): Promise<ApiResponse<boolean>> => {
+  try {
+    const api = await getApi();
+    const response = await api.delete<ApiResponse<boolean>>(
+      `/workspaces/${encodeURIComponent(name)}`
+    );
+    return response.data;
+  } catch (error) {
+    return {
+      success: false,
+      error: error instanceof Error ? error.message : '删除工作区失败',
+    };
+  }
+};
+
 export const getDriverInfo = async (): Promise<DriverInfo | null> => {
   if (!isTauri()) return null;
   try {
@@ -175,3 +778,26 @@ export const getDriverInfo = async (): Promise<DriverInfo | null> => {
     return null;
   }
 };
+
+export const listDrivers = async (): Promise<DriverCandidate[]> => {
+  if (!isTauri()) return [];
+  try {
+    return await invoke<DriverCandidate[]>('list_drivers');
+  } catch (error) {
+    console.warn('Failed to list drivers', error);
+    return [];
+  }
+};
+
+/** Switches to one of the drivers from `listDrivers`; the choice survives restarts. */
+export const selectDriver = async (path: string): Promise<DriverInfo> =>
+  invoke<DriverInfo>('select_driver', { path });
+
+/** Native folder picker of the desktop shell; null when cancelled or in a browser. */
+export const chooseExportDirectory = async (): Promise<string | null> => {
+  if (!isTauri()) return null;
+  return invoke<string | null>('choose_export_directory');
+};
+
+export const revealExportFile = async (path: string): Promise<void> =>
+  invoke<void>('reveal_export_file', { path });
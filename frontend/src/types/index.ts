@@ -1,13 +1,28 @@
-export type ConfigSource = 'sqlite' | 'env';
-export type DriverSource = 'Bundled' | 'Env' | 'System';
+// Highest precedence first; `default` marks fields left to built-in defaults
+export type ConfigSource = 'request' | 'sqlite' | 'env' | 'default';
+export type DriverSource = 'Selected' | 'Bundled' | 'Env' | 'System';
+
+export type DatabaseKind = 'dm8';
 
 export interface ConnectionConfig {
+  // Source database kind; defaults to dm8
+  db_type?: DatabaseKind;
   host: string;
   port: number;
   username: string;
   password: string;
   schema: string;
   export_schema?: string;
+  // Pre-configured ODBC data source; host/port are ignored when set
+  dsn?: string;
+  // Encrypted connection: certificate directory (SSL_PATH), key password and cipher
+  ssl?: boolean;
+  ssl_cert_path?: string;
+  ssl_cert_password?: string;
+  ssl_cipher?: string;
+  // Seconds; login defaults to 15, setup/health statements to 30, 0 disables
+  login_timeout_secs?: number;
+  query_timeout_secs?: number;
   source?: ConfigSource;
   updated_at?: string;
 }
@@ -18,12 +33,31 @@ export interface StoredConnectionResponse {
   updated_at?: string;
 }
 
+export interface EffectiveField {
+  name: string;
+  source: ConfigSource;
+  // Lower-precedence sources that set the field too but are ignored
+  shadowed: ConfigSource[];
+}
+
+// Response of GET /api/config/effective; passwords are masked unless secret references
+export interface EffectiveConfigResponse {
+  source: ConfigSource;
+  config: ConnectionConfig;
+  updated_at?: string;
+  precedence: ConfigSource[];
+  fields: EffectiveField[];
+  warnings: string[];
+}
+
 export interface Table {
   name: string;
   comment?: string;
   row_count?: number;
 }
 
+export type IdentityGeneration = 'always' | 'by_default';
+
 export interface Column {
   name: string;
   data_type: string;
@@ -37,12 +71,24 @@ export interface Column {
   identity?: boolean;
   identity_start?: number;
   identity_increment?: number;
+  identity_generation?: IdentityGeneration | null;
+  identity_current?: number | null;
+  virtual_expression?: string | null;
+  geometry_srid?: number | null;
+  collation?: string;
+}
+
+export interface IndexColumn {
+  descending: boolean;
+  expression?: string;
 }
 
 export interface Index {
   name: string;
   columns: string[];
+  column_specs?: IndexColumn[];
   unique: boolean;
+  tablespace?: string;
 }
 
 export interface TableDetails {
@@ -55,6 +101,38 @@ export interface TableDetails {
   foreign_keys: ForeignKey[];
   check_constraints: CheckConstraint[];
   triggers: TriggerDefinition[];
+  tablespace?: string;
+  default_collation?: string;
+}
+
+export interface TableReference {
+  schema: string;
+  table: string;
+  constraint: string;
+}
+
+export interface CacheInvalidateRequest {
+  // Only entries read with this connection's server and login; all when omitted
+  config?: ConnectionConfig;
+  schema?: string;
+  tables?: string[];
+}
+
+export interface CacheInvalidateResponse {
+  invalidated: number;
+}
+
+export interface TableDetailsBatchRequest {
+  config: ConnectionConfig;
+  tables: string[];
+}
+
+export interface TableDependencies {
+  table: string;
+  references: TableReference[];
+  referenced_by: TableReference[];
+  triggers: string[];
+  sequences: string[];
 }
 
 export interface UniqueConstraint {
@@ -84,36 +162,607 @@ export interface TriggerDefinition {
   body: string;
 }
 
+export type ExportEncoding = 'utf8' | 'gbk';
+
+export type LineEnding = 'lf' | 'crlf';
+export type EmptyStringPolicy = 'empty' | 'null';
+
+export type RowErrorPolicy = 'abort' | 'skip' | 'null';
+
+// Exactly one of percent (0-100] and rows; a seed picks the same rows again via a hash of the primary key
+export interface SampleSpec {
+  percent?: number;
+  rows?: number;
+  seed?: number;
+  // Sample the roots, then add the rows related through foreign keys so the subset loads without violations (needs seed)
+  consistent?: boolean;
+  // Tables a consistent sample starts from; default: exported tables referencing no other exported table
+  roots?: string[];
+}
+
+export type QuoteStyle = 'always' | 'when_needed' | 'never';
+
+export type CommentsPlacement = 'inline' | 'separate_section' | 'omit';
+
+export type DataFormat = 'sql' | 'binary' | 'xlsx' | 'jsonl' | 'dmfldr';
+
+export type DdlObjectType =
+  | 'tables'
+  | 'comments'
+  | 'constraints'
+  | 'indexes'
+  | 'sequences'
+  | 'triggers'
+  | 'views';
+
+export interface ProjectMember {
+  config: ConnectionConfig;
+  tables: string[];
+  export_schema?: string;
+}
+
+export interface ExportProject {
+  name: string;
+  members: ProjectMember[];
+  include_data?: boolean;
+  // Shared export options using ExportRequest field names
+  options?: Partial<Omit<ExportRequest, 'config' | 'tables' | 'export_schema'>>;
+}
+
+export interface ExportProfile {
+  name: string;
+  schema: string;
+  export_schema?: string;
+  // May be empty when options.all_tables is set
+  tables?: string[];
+  include_data?: boolean;
+  // Export options using ExportRequest field names; the saved connection is used at run time
+  options?: Partial<Omit<ExportRequest, 'config' | 'tables' | 'export_schema'>>;
+}
+
 export interface ExportRequest {
   config: ConnectionConfig;
   export_schema?: string;
+  // Trigger terminator mode, or dm8/oracle/postgresql/mysql to translate column defaults
   export_compat?: string;
   tables: string[];
-  include_ddl: boolean;
+  // Export every table of the schema instead of `tables`
+  all_tables?: boolean;
+  // Tables to leave out: case-insensitive globs (* or %, ?) or re:<regex>, e.g. TMP_%, *_BAK
+  exclude_patterns?: string[];
+  // Object kinds to export; every kind but 'views' when omitted
+  ddl_objects?: DdlObjectType[];
   include_data: boolean;
   batch_size?: number;
+  // Split generated INSERTs into statements of at most this many rows / bytes
+  max_rows_per_insert?: number;
+  max_statement_bytes?: number;
+  // 'binary' writes per-table dump files for POST /api/import/binary instead of a script
+  data_format?: DataFormat;
+  // Rows per sheet for 'xlsx' (default 100000)
+  xlsx_row_limit?: number;
+  // Field separator and NULL text of 'dmfldr' data files (defaults '|' and '\\N')
+  dmfldr_delimiter?: string;
+  dmfldr_null_marker?: string;
+  // Interleave COMMIT; every N rows and after each table in data scripts
+  commit_every_rows?: number;
   drop_existing?: boolean;
   include_row_counts?: boolean;
+  include_storage?: boolean;
+  tablespace_map?: Record<string, string>;
+  include_grants?: boolean;
+  only_referenced_sequences?: boolean;
+  sequence_renames?: Record<string, string>;
+  table_renames?: Record<string, string>;
+  // Also export FK parent tables and the sequences they use when not selected
+  include_dependencies?: boolean;
+  skip_not_null_checks?: boolean;
+  // Data scripts restart identity columns past the source's last generated value
+  restart_identities?: boolean;
+  // Data scripts reseed sequences to the MAX of the columns they feed after the inserts
+  reseed_sequences?: boolean;
+  // Data scripts write CR/LF/TAB inside text values as CHR(n) concatenations
+  escape_line_breaks?: boolean;
+  // Empty text values are written as '' (default) or NULL
+  empty_string_as?: EmptyStringPolicy;
+  // Rows with values unfit for their column type abort (default), are skipped or get NULL; see <script>.errors.log
+  on_row_error?: RowErrorPolicy;
+  // Export only a sample of each table's rows (not applied to xlsx workbooks)
+  sample?: SampleSpec;
+  // Data scripts wrap each table's INSERTs in ALTER TABLE ... DISABLE/ENABLE ALL TRIGGERS (tables with triggers)
+  disable_triggers_during_load?: boolean;
+  // Written before/after each table's rows in data scripts; {schema} and {table} are the quoted target names
+  pre_table_sql?: string;
+  post_table_sql?: string;
+  // Convert TIMESTAMP WITH TIME ZONE values to UTC or a fixed offset such as +08:00
+  timezone?: string;
+  // Match schema and table names as spelled instead of upper-casing them (quoted mixed-case names)
+  preserve_case?: boolean;
+  // Quote every identifier (default), only reserved words and non-plain names, or none
+  quote_style?: QuoteStyle;
+  // DDL comments after each CREATE TABLE (default), grouped at the end of the file, or dropped
+  comments_placement?: CommentsPlacement;
+  // Start the DDL script with CREATE SCHEMA "<target>" AUTHORIZATION <schema_owner>
+  include_schema_ddl?: boolean;
+  // Owner of the created schema (default SYSDBA)
+  schema_owner?: string;
+  // Full export only: create indexes after the INSERT sections instead of with their tables
+  indexes_after_data?: boolean;
+  encoding?: ExportEncoding;
+  line_ending?: LineEnding;
+  // Rows per page when paging single integer-PK tables by key range
+  keyset_page_size?: number;
+  // Source table -> last key written by a failed keyset export, to continue after it
+  keyset_resume_after?: Record<string, string>;
+  // Shrink batch_size for wide rows so one batch fits max_batch_bytes (default 32 MiB)
+  adaptive_batch?: boolean;
+  max_batch_bytes?: number;
+  // Retries after link failures/timeouts (default 3 attempts, 1000 ms doubling backoff)
+  retry_attempts?: number;
+  retry_backoff_ms?: number;
+  // Idle seconds before the export connection gets a heartbeat (default 60, 0 disables)
+  keepalive_secs?: number;
+  // SQL data scripts skip tables the login cannot SELECT from (W-TABLE-UNREADABLE) instead of failing
+  skip_unreadable?: boolean;
+  preview_rows?: number;
+  // Estimate only: COUNT(*) tables without statistics instead of scaling up a 1% SAMPLE
+  exact_counts?: boolean;
+  // Folder the job directory is created in (desktop folder picker); exports/ when omitted
+  output_dir?: string;
 }
 
 export interface ExportResponse {
   success: boolean;
   message: string;
   file_path?: string;
+  job_id?: string;
+  output_dir?: string;
+  memory?: TableMemoryStats[];
+  warnings?: ExportWarning[];
+  stats?: ExportStats;
+}
+
+export interface TableExportStats {
+  table: string;
+  kind: 'ddl' | 'data';
+  rows: number;
+  bytes: number;
+  duration_ms: number;
+}
+
+export interface ExportStats {
+  tables: TableExportStats[];
+  duration_ms: number;
+  empty_strings?: number;
+}
+
+export interface ExportWarning {
+  code: IssueCode;
+  table?: string;
+  message: string;
+}
+
+export interface TableMemoryStats {
+  table: string;
+  requested_batch_size: number;
+  batch_size: number;
+  row_buffer_bytes: number;
+  fetch_buffer_bytes: number;
+  peak_batch_bytes: number;
+  peak_row_bytes: number;
+  backpressure_stalls: number;
+}
+
+export type JobStatus = 'running' | 'succeeded' | 'failed';
+
+export type JobEventKind =
+  | 'started'
+  | 'table_started'
+  | 'table_finished'
+  | 'warning'
+  | 'retry'
+  | 'finished'
+  | 'failed';
+
+export interface ExportJob {
+  id: string;
+  kind: string;
+  status: JobStatus;
+  started_at: string;
+  finished_at?: string;
+  file_path?: string;
+  message?: string;
+}
+
+export interface JobEvent {
+  occurred_at: string;
+  kind: JobEventKind;
+  code?: IssueCode;
+  table?: string;
+  message?: string;
+}
+
+export interface JobTimeline {
+  job: ExportJob;
+  events: JobEvent[];
+}
+
+export type ErrorCode =
+  | 'connection_failed'
+  | 'schema_not_found'
+  | 'table_not_found'
+  | 'odbc_error'
+  | 'io_error'
+  | 'invalid_request'
+  | 'config_store_error'
+  | 'not_implemented'
+  | 'internal';
+
+// 稳定的警告 (W-) / 错误 (E-) 代码，见后端 models::IssueCode
+export type IssueCode =
+  | 'W-SEQ-UNAVAILABLE'
+  | 'W-ROWCOUNT-UNAVAILABLE'
+  | 'W-FK-CYCLE'
+  | 'W-CASE-UNKNOWN'
+  | 'W-CASE-INSENSITIVE'
+  | 'W-VIEW-UNAVAILABLE'
+  | 'W-GRANT-UNAVAILABLE'
+  | 'W-CHARSET-CHECK-SKIPPED'
+  | 'W-CHARSET-CORRUPT'
+  | 'W-ENCODING-UNMAPPABLE'
+  | 'W-FK-EXTERNAL'
+  | 'W-ROW-LIMIT'
+  | 'W-CELL-TRUNCATED'
+  | 'W-VALUE-TRUNCATED'
+  | 'W-INDEX-SKIPPED'
+  | 'W-TRIGGER-INCOMPLETE'
+  | 'W-SEQ-RESEED-SKIPPED'
+  | 'W-EMPTY-STRING'
+  | 'W-ROW-ERROR'
+  | 'W-TABLE-UNREADABLE'
+  | 'W-SAMPLE-NOT-REPRODUCIBLE'
+  | 'W-SUBSET-FK-UNFOLLOWED'
+  | 'E-CONN-FAILED'
+  | 'E-SCHEMA-NOT-FOUND'
+  | 'E-TABLE-NOT-FOUND'
+  | 'E-ODBC'
+  | 'E-IO'
+  | 'E-INVALID-REQUEST'
+  | 'E-CONFIG-STORE'
+  | 'E-NOT-IMPLEMENTED'
+  | 'E-INTERNAL';
+
+export interface LintRequest {
+  config: ConnectionConfig;
+  tables: string[];
+}
+
+export type LintRule =
+  | 'missing_primary_key'
+  | 'reserved_word'
+  | 'unsupported_type'
+  | 'cross_schema_trigger'
+  | 'oversized_lob';
+
+export type LintSeverity = 'info' | 'warning' | 'error';
+
+export interface LintFinding {
+  rule: LintRule;
+  severity: LintSeverity;
+  column?: string;
+  message: string;
+}
+
+export interface TableLint {
+  table: string;
+  score: number;
+  findings: LintFinding[];
+}
+
+export interface LintReport {
+  score: number;
+  ready: boolean;
+  tables: TableLint[];
+}
+
+export interface ManifestTable {
+  table: string;
+  source_table: string;
+  rows: number;
+  checksum: string;
+  // Binary dumps only
+  file?: string;
+  columns?: ManifestColumn[];
+}
+
+export interface ManifestColumn {
+  name: string;
+  data_type: string;
+  identity: boolean;
+  max_bytes: number;
+}
+
+export interface ExportManifest {
+  job_id: string;
+  generated_at: string;
+  source_schema: string;
+  target_schema: string;
+  format?: DataFormat;
+  data_file: string;
+  row_limit?: number | null;
+  sample?: SampleSpec;
+  tables: ManifestTable[];
+}
+
+export interface VerifyRequest {
+  config: ConnectionConfig;
+  manifest_path: string;
+  schema?: string;
+}
+
+export interface TableVerification {
+  table: string;
+  expected: number;
+  actual?: number | null;
+  matches: boolean;
+  error?: string | null;
+}
+
+export interface VerifyReport {
+  complete: boolean;
+  tables: TableVerification[];
+}
+
+// Where a table estimate comes from: optimizer statistics or a row count plus sampled rows
+export type EstimateSource = 'statistics' | 'sampled' | 'counted';
+
+export interface TableEstimate {
+  table: string;
+  rows: number;
+  bytes: number;
+  duration_ms: number;
+  source?: EstimateSource | null;
+  error?: string | null;
+}
+
+export interface ExportEstimate {
+  tables: TableEstimate[];
+  total_rows: number;
+  total_bytes: number;
+  duration_ms: number;
+}
+
+export interface BinaryImportRequest {
+  config: ConnectionConfig;
+  manifest_path: string;
+  schema?: string;
+  batch_size?: number;
+}
+
+export interface TableImport {
+  table: string;
+  rows: number;
+}
+
+export interface CsvColumnMapping {
+  // Header name (case-insensitive) or 1-based field position
+  source: string;
+  target: string;
+}
+
+export interface CsvImportRequest {
+  config: ConnectionConfig;
+  schema?: string;
+  table: string;
+  // CSV file on the server; omitted when the file is uploaded
+  file_path?: string;
+  columns?: CsvColumnMapping[];
+  has_header?: boolean;
+  delimiter?: string;
+  null_value?: string;
+  batch_size?: number;
+}
+
+export interface ImportReport {
+  tables: TableImport[];
+  total_rows: number;
+}
+
+export interface CompareRequest {
+  left_path: string;
+  right_path: string;
+}
+
+export interface StatementEntry {
+  key: string;
+  statement: string;
+}
+
+export interface ChangedStatement {
+  key: string;
+  before: string;
+  after: string;
+}
+
+export interface CompareResponse {
+  added: StatementEntry[];
+  removed: StatementEntry[];
+  changed: ChangedStatement[];
+  unchanged_count: number;
+}
+
+export type PlanRisk = 'safe' | 'review' | 'destructive';
+
+export interface PlanStep {
+  number: number;
+  risk: PlanRisk;
+  description: string;
+  statement: string;
+  rollback?: string | null;
+}
+
+export interface ApplyPlan {
+  steps: PlanStep[];
+  notes: string[];
+}
+
+export interface ApplyPlanResponse {
+  plan: ApplyPlan;
+  apply_path: string;
+  rollback_path: string;
+}
+
+export interface AuditEntry {
+  id?: number;
+  occurred_at: string;
+  client_ip?: string;
+  username: string;
+  host: string;
+  port: number;
+  kind: string;
+  source_schema: string;
+  target_schema?: string;
+  tables: string[];
+  success: boolean;
+  file_path?: string;
+  message?: string;
 }
 
 export interface ApiResponse<T> {
   success: boolean;
   data?: T;
   error?: string;
+  code?: ErrorCode;
+  issue_code?: IssueCode;
+  details?: string;
+}
+
+export type DiagnosticStepKind = 'driver' | 'tcp' | 'login' | 'query' | 'schema' | 'tables';
+
+export interface DiagnosticStep {
+  step: DiagnosticStepKind;
+  status: 'passed' | 'failed' | 'skipped';
+  duration_ms: number;
+  message: string;
+  // Tables visible in the schema, for the `tables` step
+  count?: number;
 }
 
 export interface TestConnectionResponse {
   success: boolean;
   message: string;
+  // Present when the test ran with diagnostics
+  diagnostics?: DiagnosticStep[];
+}
+
+// Optional catalog columns and views, probed once per connection
+export interface ServerCapabilities {
+  trigger_type: boolean;
+  trigger_description: boolean;
+  trigger_when_clause: boolean;
+  constraint_update_rule: boolean;
+  index_descend: boolean;
+  index_expressions: boolean;
+  column_collation: boolean;
+  table_default_collation: boolean;
+  identity_generation: boolean;
+  virtual_columns: boolean;
+}
+
+// Returned by GET /api/server/info; null where the server could not answer
+export interface ServerInfo {
+  version: string | null;
+  banner: string[];
+  charset: 'GB18030' | 'UTF-8' | 'EUC-KR' | null;
+  case_sensitive: boolean | null;
+  page_size: number | null;
+  capabilities: ServerCapabilities;
 }
 
 export interface DriverInfo {
   path: string;
   source: DriverSource;
 }
+
+export interface DriverCandidate extends DriverInfo {
+  selected: boolean;
+}
+
+export interface DriverCheckResponse {
+  path: string;
+  source: DriverSource;
+  exists: boolean | null;
+  library: string | null;
+  loaded: boolean;
+  version: string | null;
+  error?: string | null;
+}
+
+export interface DriverPathRequest {
+  path: string;
+}
+
+export type LogLevel = 'trace' | 'debug' | 'info' | 'warn' | 'error';
+
+export interface LogLevelRequest {
+  level: LogLevel;
+}
+
+export interface Workspace {
+  name: string;
+  description?: string;
+  created_at?: string;
+  // Saved connection, profiles and history currently come from this workspace
+  active: boolean;
+}
+
+export interface WorkspaceRequest {
+  name: string;
+  description?: string;
+}
+
+// Written by GET /api/config/export; only `ciphertext` holds settings
+export interface EncryptedConfigBundle {
+  format: string;
+  version: number;
+  exported_at: string;
+  kdf: string;
+  iterations: number;
+  salt: string;
+  nonce: string;
+  ciphertext: string;
+}
+
+export interface ConfigImportRequest {
+  passphrase: string;
+  bundle: EncryptedConfigBundle;
+}
+
+// Number of entries restored, per kind
+export interface ConfigImportResponse {
+  workspaces: number;
+  connections: number;
+  profiles: number;
+  projects: number;
+  settings: number;
+}
+
+export interface LogTailResponse {
+  // Newest log file; null until something has been logged to disk
+  file: string | null;
+  // Oldest first
+  lines: string[];
+}
+
+export interface HealthResponse {
+  status: 'ok' | 'degraded';
+  version: string;
+  uptime_secs: number;
+  driver: DriverInfo & { exists: boolean | null };
+  config_db: {
+    path: string;
+    writable: boolean;
+    error?: string | null;
+  };
+  active_jobs: number;
+}
@@ -1,5 +1,8 @@
 use anyhow::{anyhow, Context, Result};
+use dm8_export_backend::config_store::ConfigStore;
+use dm8_export_backend::db::driver::{validate_driver_path, DRIVER_PATH_SETTING};
 use std::env;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -17,6 +20,17 @@ pub struct ResolvedDriver {
     pub source: DriverSource,
 }
 
+impl ResolvedDriver {
+    fn at(driver_path: PathBuf, source: DriverSource) -> Option<Self> {
+        let search_dir = driver_path.parent()?.to_path_buf();
+        Some(Self {
+            driver_path,
+            search_dir,
+            source,
+        })
+    }
+}
+
 /// Discover an available DM8 ODBC driver and set environment variables for loading it.
 pub fn discover_and_apply(app: &tauri::AppHandle) -> Result<ResolvedDriver> {
     let driver = discover_driver(app)?;
@@ -24,109 +38,162 @@ pub fn discover_and_apply(app: &tauri::AppHandle) -> Result<ResolvedDriver> {
     Ok(driver)
 }
 
+/// Makes the discovered driver at `path` the one new connections load, and remembers the
+/// choice in the config store for the next start.
+pub fn select_driver(app: &tauri::AppHandle, path: &Path) -> Result<ResolvedDriver> {
+    let driver = discover_candidates(app)
+        .into_iter()
+        .find(|candidate| same_path(&candidate.driver_path, path))
+        .ok_or_else(|| anyhow!("{} is not a discovered DM8 ODBC driver", path.display()))?;
+    let driver_path = driver.driver_path.display().to_string();
+    validate_driver_path(&driver_path)?;
+
+    ConfigStore::ensure_default_path()
+        .and_then(|store| store.set_setting(DRIVER_PATH_SETTING, &driver_path))
+        .context("failed to save driver choice")?;
+    apply_env(&driver)?;
+    Ok(driver)
+}
+
 fn driver_filename() -> &'static str {
     if cfg!(target_os = "windows") {
         "dmodbc.dll"
+    } else if cfg!(target_os = "macos") {
+        "libdodbc.dylib"
     } else {
         "libdodbc.so"
     }
 }
 
+/// Uses the driver saved by [`select_driver`] while it is still installed, otherwise the
+/// first candidate in fallback order.
 fn discover_driver(app: &tauri::AppHandle) -> Result<ResolvedDriver> {
-    // 1) Bundled resource (works in dev and packaged)
-    if let Some(resolved) = bundled_driver(app) {
-        return Ok(resolved);
+    let candidates = discover_candidates(app);
+    if let Some(saved) = saved_driver_path() {
+        if let Some(driver) = candidates
+            .iter()
+            .find(|candidate| same_path(&candidate.driver_path, &saved))
+        {
+            return Ok(driver.clone());
+        }
     }
 
-    // 2) User-specified env
-    if let Some(path) = env_driver() {
-        return Ok(path);
+    candidates.into_iter().next().ok_or_else(|| {
+        anyhow!(
+            "No DM8 ODBC driver found. Checked bundled resources, DM8_DRIVER_PATH, and system ODBC registry/ini."
+        )
+    })
+}
+
+/// Every DM8 ODBC driver found, in fallback order: bundled resources (works in dev and
+/// packaged), the user-specified `DM8_DRIVER_PATH`, then each system registration. A library
+/// reachable several ways is listed once.
+pub fn discover_candidates(app: &tauri::AppHandle) -> Vec<ResolvedDriver> {
+    let mut candidates: Vec<ResolvedDriver> = Vec::new();
+    for driver in bundled_drivers(app)
+        .into_iter()
+        .chain(env_driver())
+        .chain(system_drivers())
+    {
+        if !candidates
+            .iter()
+            .any(|known| same_path(&known.driver_path, &driver.driver_path))
+        {
+            candidates.push(driver);
+        }
     }
+    candidates
+}
 
-    // 3) System-installed driver
-    if let Some(path) = system_driver() {
-        return Ok(path);
+fn saved_driver_path() -> Option<PathBuf> {
+    let store = ConfigStore::ensure_default_path().ok()?;
+    store
+        .get_setting(DRIVER_PATH_SETTING)
+        .ok()
+        .flatten()
+        .map(PathBuf::from)
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
     }
+}
 
-    Err(anyhow!(
-        "No DM8 ODBC driver found. Checked bundled resources, DM8_DRIVER_PATH, and system ODBC registry/ini."
-    ))
+fn is_driver_file(path: &Path) -> bool {
+    path.exists()
+        && path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy() == driver_filename())
 }
 
-fn bundled_driver(app: &tauri::AppHandle) -> Option<ResolvedDriver> {
+fn bundled_drivers(app: &tauri::AppHandle) -> Vec<ResolvedDriver> {
     let filename = driver_filename();
     // Packaged or dev mode via path resolver
-    if let Some(path) = app
+    let resource = app
         .path_resolver()
-        .resolve_resource(format!("drivers/dm8/{}", filename))
-    {
-        if path.exists() {
-            let search_dir = path.parent()?.to_path_buf();
-            return Some(ResolvedDriver {
-                driver_path: path,
-                search_dir,
-                source: DriverSource::Bundled,
-            });
-        }
-    }
-
+        .resolve_resource(format!("drivers/dm8/{}", filename));
     // Dev fallback: relative to repo root
     let dev_path = std::env::current_dir()
         .ok()
         .map(|pwd| pwd.join(format!("../drivers/dm8/{}", filename)));
-    if let Some(path) = dev_path {
-        if path.exists() {
-            let search_dir = path.parent()?.to_path_buf();
-            return Some(ResolvedDriver {
-                driver_path: path,
-                search_dir,
-                source: DriverSource::Bundled,
-            });
-        }
-    }
-    None
+
+    resource
+        .into_iter()
+        .chain(dev_path)
+        .filter(|path| path.exists())
+        .filter_map(|path| ResolvedDriver::at(path, DriverSource::Bundled))
+        .collect()
 }
 
 fn env_driver() -> Option<ResolvedDriver> {
-    let filename = driver_filename();
-    if let Ok(raw) = env::var("DM8_DRIVER_PATH") {
-        let path = PathBuf::from(raw.trim());
-        if path.exists() {
-            let search_dir = path.parent()?.to_path_buf();
-            if path.file_name()?.to_string_lossy() == filename {
-                return Some(ResolvedDriver {
-                    driver_path: path,
-                    search_dir,
-                    source: DriverSource::Env,
-                });
-            }
-        }
+    let raw = env::var("DM8_DRIVER_PATH").ok()?;
+    let path = PathBuf::from(raw.trim());
+    if is_driver_file(&path) {
+        return ResolvedDriver::at(path, DriverSource::Env);
     }
     None
 }
 
-fn system_driver() -> Option<ResolvedDriver> {
+fn system_drivers() -> Vec<ResolvedDriver> {
     #[cfg(target_os = "linux")]
     {
-        linux_system_driver()
+        linux_system_drivers()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos_system_drivers()
     }
     #[cfg(target_os = "windows")]
     {
-        windows_system_driver()
+        windows_system_drivers()
     }
-    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
     {
-        None
+        Vec::new()
     }
 }
 
 #[cfg(target_os = "linux")]
-fn linux_system_driver() -> Option<ResolvedDriver> {
-    let filename = driver_filename();
-    let candidates = [
-        "/etc/odbcinst.ini",
+fn linux_system_drivers() -> Vec<ResolvedDriver> {
+    odbcinst_system_drivers(&["/etc/odbcinst.ini", "~/.odbcinst.ini"])
+}
+
+/// unixODBC as installed by Homebrew keeps its ini under `/usr/local/etc` on Intel Macs and
+/// `/opt/homebrew/etc` on Apple silicon.
+#[cfg(target_os = "macos")]
+fn macos_system_drivers() -> Vec<ResolvedDriver> {
+    odbcinst_system_drivers(&[
+        "/usr/local/etc/odbcinst.ini",
+        "/opt/homebrew/etc/odbcinst.ini",
         "~/.odbcinst.ini",
-    ];
+    ])
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn odbcinst_system_drivers(candidates: &[&str]) -> Vec<ResolvedDriver> {
+    let mut drivers = Vec::new();
 
     for candidate in candidates {
         let expanded = if candidate.starts_with("~") {
@@ -137,24 +204,20 @@ fn linux_system_driver() -> Option<ResolvedDriver> {
 
         if let Some(path) = expanded {
             if let Ok(content) = fs::read_to_string(&path) {
-                if let Some(found) = parse_odbcinst_for_dm8(&content) {
-                    if found.exists() && found.file_name()?.to_string_lossy() == filename {
-                        let search_dir = found.parent()?.to_path_buf();
-                        return Some(ResolvedDriver {
-                            driver_path: found,
-                            search_dir,
-                            source: DriverSource::System,
-                        });
-                    }
-                }
+                drivers.extend(
+                    parse_odbcinst_drivers(&content)
+                        .into_iter()
+                        .filter(|found| is_driver_file(found))
+                        .filter_map(|found| ResolvedDriver::at(found, DriverSource::System)),
+                );
             }
         }
     }
-    None
+    drivers
 }
 
-#[cfg(target_os = "linux")]
-pub(crate) fn parse_odbcinst_for_dm8(content: &str) -> Option<PathBuf> {
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn parse_odbcinst_for_dm8(content: &str) -> Option<PathBuf> {
     let mut current_section: Option<String> = None;
     for line in content.lines() {
         let trimmed = line.trim();
@@ -176,33 +239,65 @@ pub(crate) fn parse_odbcinst_for_dm8(content: &str) -> Option<PathBuf> {
     None
 }
 
+/// The driver path of every section, the `[DM8 ODBC DRIVER]` section first; sites with
+/// several DM installations register each under its own name.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn parse_odbcinst_drivers(content: &str) -> Vec<PathBuf> {
+    let mut drivers: Vec<PathBuf> = parse_odbcinst_for_dm8(content).into_iter().collect();
+    let mut in_section = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            in_section = true;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+
+        if let Some((key, value)) = trimmed.split_once('=') {
+            if key.trim().to_ascii_lowercase().starts_with("driver") {
+                // Only the first driver key of a section, as in the DM8 lookup.
+                in_section = false;
+                let path = PathBuf::from(value.trim());
+                if !drivers.contains(&path) {
+                    drivers.push(path);
+                }
+            }
+        }
+    }
+    drivers
+}
+
 #[cfg(target_os = "windows")]
-fn windows_system_driver() -> Option<ResolvedDriver> {
+fn windows_system_drivers() -> Vec<ResolvedDriver> {
     use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ};
     use winreg::RegKey;
 
-    let hives = [
-        (HKEY_LOCAL_MACHINE, "SOFTWARE\\ODBC\\ODBCINST.INI\\DM8 ODBC DRIVER"),
-        (HKEY_CURRENT_USER, "SOFTWARE\\ODBC\\ODBCINST.INI\\DM8 ODBC DRIVER"),
-    ];
-    let filename = driver_filename();
+    let mut drivers = Vec::new();
+    for hive in [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER] {
+        let Ok(odbcinst) =
+            RegKey::predef(hive).open_subkey_with_flags("SOFTWARE\\ODBC\\ODBCINST.INI", KEY_READ)
+        else {
+            continue;
+        };
+        // The DM8 entry first, then every other registered driver.
+        let mut names: Vec<String> = odbcinst.enum_keys().filter_map(|name| name.ok()).collect();
+        names.sort_by_key(|name| !name.eq_ignore_ascii_case("DM8 ODBC DRIVER"));
 
-    for (hive, path) in hives {
-        if let Ok(key) = RegKey::predef(hive).open_subkey_with_flags(path, KEY_READ) {
-            if let Ok(value): Result<String, _> = key.get_value("Driver") {
+        for name in names {
+            let Ok(key) = odbcinst.open_subkey_with_flags(&name, KEY_READ) else {
+                continue;
+            };
+            if let Ok(value) = key.get_value::<String, _>("Driver") {
                 let driver_path = PathBuf::from(value.trim());
-                if driver_path.exists() && driver_path.file_name()?.to_string_lossy() == filename {
-                    let search_dir = driver_path.parent()?.to_path_buf();
-                    return Some(ResolvedDriver {
-                        driver_path,
-                        search_dir,
-                        source: DriverSource::System,
-                    });
+                if is_driver_file(&driver_path) {
+                    drivers.extend(ResolvedDriver::at(driver_path, DriverSource::System));
                 }
             }
         }
     }
-    None
+    drivers
 }
 
 fn apply_env(driver: &ResolvedDriver) -> Result<()> {
@@ -210,6 +305,8 @@ fn apply_env(driver: &ResolvedDriver) -> Result<()> {
 
     if cfg!(target_os = "windows") {
         prepend_path("PATH", &driver.search_dir)?;
+    } else if cfg!(target_os = "macos") {
+        prepend_path("DYLD_LIBRARY_PATH", &driver.search_dir)?;
     } else {
         prepend_path("LD_LIBRARY_PATH", &driver.search_dir)?;
     }
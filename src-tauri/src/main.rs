@@ -1,8 +1,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod driver;
+mod reveal;
+mod single_instance;
+mod tray;
 
-use driver::{discover_and_apply, DriverSource, ResolvedDriver};
+use std::path::Path;
+use std::sync::Mutex;
+
+use driver::{discover_and_apply, discover_candidates, DriverSource, ResolvedDriver};
 use tauri::{Manager, State};
 
 #[derive(Clone, serde::Serialize)]
@@ -11,9 +17,25 @@ struct DriverInfo {
     source: DriverSource,
 }
 
-#[derive(Clone)]
+impl From<&ResolvedDriver> for DriverInfo {
+    fn from(driver: &ResolvedDriver) -> Self {
+        DriverInfo {
+            path: driver.driver_path.display().to_string(),
+            source: driver.source.clone(),
+        }
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+struct DriverCandidate {
+    #[serde(flatten)]
+    info: DriverInfo,
+    /// Whether new connections currently use this driver.
+    selected: bool,
+}
+
 struct AppState {
-    driver: ResolvedDriver,
+    driver: Mutex<ResolvedDriver>,
     backend_url: String,
 }
 
@@ -24,16 +46,97 @@ fn backend_base_url(state: State<'_, AppState>) -> String {
 
 #[tauri::command]
 fn driver_info(state: State<'_, AppState>) -> DriverInfo {
-    DriverInfo {
-        path: state.driver.driver_path.display().to_string(),
-        source: state.driver.source.clone(),
-    }
+    DriverInfo::from(&*state.driver.lock().unwrap())
+}
+
+/// Every DM8 driver found on this machine, in fallback order.
+#[tauri::command]
+fn list_drivers(app: tauri::AppHandle, state: State<'_, AppState>) -> Vec<DriverCandidate> {
+    let current = state.driver.lock().unwrap().driver_path.clone();
+    discover_candidates(&app)
+        .iter()
+        .map(|candidate| DriverCandidate {
+            info: DriverInfo::from(candidate),
+            selected: candidate.driver_path == current,
+        })
+        .collect()
+}
+
+/// Switches to one of the listed drivers and keeps it for later starts.
+#[tauri::command]
+fn select_driver(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<DriverInfo, String> {
+    let driver = driver::select_driver(&app, Path::new(&path)).map_err(|err| format!("{err:#}"))?;
+    let info = DriverInfo::from(&driver);
+    *state.driver.lock().unwrap() = driver;
+    Ok(info)
+}
+
+/// Native folder picker for the export destination; `None` when the user cancels. The path
+/// goes to the backend as `ExportRequest.output_dir`.
+#[tauri::command]
+async fn choose_export_directory() -> Option<String> {
+    tauri::api::dialog::blocking::FileDialogBuilder::new()
+        .set_title("选择导出目录")
+        .pick_folder()
+        .map(|path| path.display().to_string())
+}
+
+/// Shows a generated export file in Explorer, Finder or the desktop's file manager.
+#[tauri::command]
+fn reveal_export_file(path: String) -> Result<(), String> {
+    reveal::reveal(Path::new(&path)).map_err(|err| format!("{err:#}"))
 }
 
 fn main() {
+    // A second launch only brings the running window forward and exits. The lock is taken
+    // first, so of two launches at once only one goes on to start the app.
+    let instance_path = single_instance::default_path();
+    let instance_lock = match instance_path.as_deref().map(single_instance::acquire) {
+        Some(Ok(Some(lock))) => Some(lock),
+        Some(Ok(None)) => {
+            if !instance_path
+                .as_deref()
+                .is_some_and(single_instance::wake_holder)
+            {
+                eprintln!("Another instance is running but did not answer");
+            }
+            return;
+        }
+        Some(Err(err)) => {
+            eprintln!("Failed to take the single instance lock: {err}");
+            None
+        }
+        None => None,
+    };
+
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![backend_base_url, driver_info])
-        .setup(|app| {
+        .system_tray(tray::build())
+        .on_system_tray_event(tray::handle_event)
+        .on_window_event(|event| tray::handle_window_event(event.window(), event.event()))
+        .invoke_handler(tauri::generate_handler![
+            backend_base_url,
+            driver_info,
+            list_drivers,
+            select_driver,
+            choose_export_directory,
+            reveal_export_file
+        ])
+        .setup(move |app| {
+            if let (Some(path), Some(lock)) = (&instance_path, instance_lock) {
+                let handle = app.handle();
+                if let Err(err) =
+                    single_instance::listen(&lock, path, move || tray::show_main_window(&handle))
+                {
+                    eprintln!("Failed to listen for later app launches: {err}");
+                }
+                // Managed state lives as long as the app, and the lock with it.
+                app.manage(lock);
+            }
+
             let resolved = match discover_and_apply(app) {
                 Ok(driver) => driver,
                 Err(err) => {
@@ -47,10 +150,15 @@ fn main() {
             };
 
             dm8_export_backend::init_tracing();
-            let bound = tauri::async_runtime::block_on(dm8_export_backend::start_server(Some(0)))?;
+            let bound =
+                tauri::async_runtime::block_on(dm8_export_backend::start_or_reuse_server(Some(0)))?;
             let backend_url = format!("http://127.0.0.1:{}", bound.port());
 
-            app.manage(AppState { driver: resolved, backend_url });
+            app.manage(AppState {
+                driver: Mutex::new(resolved),
+                backend_url,
+            });
+            tray::start_refresh(&app.handle());
             Ok(())
         })
         .run(tauri::generate_context!())
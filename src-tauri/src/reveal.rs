@@ -0,0 +1,77 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Shows `path` in the platform file manager: selected in Explorer or Finder, or its folder
+/// opened through `xdg-open` elsewhere. Relative paths resolve against the working directory,
+/// which is also the embedded backend's.
+pub fn reveal(path: &Path) -> Result<()> {
+    let path = path
+        .canonicalize()
+        .map(without_verbatim_prefix)
+        .with_context(|| format!("{} does not exist", path.display()))?;
+
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("explorer");
+        command.arg(format!("/select,{}", path.display()));
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(&path);
+        command
+    } else {
+        let folder = if path.is_dir() {
+            path.as_path()
+        } else {
+            path.parent().unwrap_or(&path)
+        };
+        let mut command = Command::new("xdg-open");
+        command.arg(folder);
+        command
+    };
+
+    let status = command
+        .status()
+        .with_context(|| format!("failed to start file manager for {}", path.display()))?;
+    // Explorer reports a non-zero status even when it opened the window.
+    if !status.success() && !cfg!(target_os = "windows") {
+        bail!("file manager exited with {status}");
+    }
+    Ok(())
+}
+
+/// `canonicalize` returns `\\?\C:\...` and `\\?\UNC\server\share\...` on Windows, which
+/// Explorer's `/select,` does not understand; this gives back the usual `C:\...` and
+/// `\\server\share\...` forms. Other paths are returned unchanged.
+fn without_verbatim_prefix(path: PathBuf) -> PathBuf {
+    let Some(text) = path.to_str() else {
+        return path;
+    };
+    if let Some(unc) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{unc}"))
+    } else if let Some(local) = text.strip_prefix(r"\\?\") {
+        PathBuf::from(local)
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::without_verbatim_prefix;
+    use std::path::PathBuf;
+
+    #[test]
+    fn verbatim_prefixes_are_removed() {
+        let strip = |path: &str| without_verbatim_prefix(PathBuf::from(path));
+        assert_eq!(
+            strip(r"\\?\C:\exports\a.sql"),
+            PathBuf::from(r"C:\exports\a.sql")
+        );
+        assert_eq!(
+            strip(r"\\?\UNC\nas\share\a.sql"),
+            PathBuf::from(r"\\nas\share\a.sql")
+        );
+        assert_eq!(strip("/home/u/a.sql"), PathBuf::from("/home/u/a.sql"));
+    }
+}
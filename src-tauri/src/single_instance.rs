@@ -0,0 +1,164 @@
+//! One app window per user: the first instance holds an exclusive lock on `~/.amarone/app.lock`
+//! and listens on a loopback port recorded in `~/.amarone/app.json`. A later launch that cannot
+//! take the lock asks the holder to show its window and exits instead of starting a second
+//! tray icon. Taking the lock is the check, so two launches at once cannot both go on.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use fs2::FileExt;
+
+const INSTANCE_FILE: &str = "app.json";
+const LOCK_FILE: &str = "app.lock";
+/// How long a later launch keeps asking a lock holder that is still starting up.
+const WAKE_WAIT: Duration = Duration::from_secs(5);
+/// Sent by a later launch and echoed by the running instance, so an unrelated program that
+/// took over a recorded port is not mistaken for it.
+const WAKE: &[u8] = b"dm8-export-tool:show";
+const TIMEOUT: Duration = Duration::from_millis(500);
+
+/// `~/.amarone/app.json`, next to the config database and the backend record.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".amarone").join(INSTANCE_FILE))
+}
+
+/// Held by the first instance for as long as it runs. The lock belongs to the open file, so
+/// the OS releases it when the process exits, crashed or not.
+pub struct InstanceLock {
+    _file: File,
+}
+
+/// Takes the instance lock next to the record at `path`; `None` when another instance holds it.
+pub fn acquire(path: &Path) -> io::Result<Option<InstanceLock>> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_FILE))?;
+    match file.try_lock_exclusive() {
+        Ok(()) => Ok(Some(InstanceLock { _file: file })),
+        Err(e) if e.raw_os_error() == fs2::lock_contended_error().raw_os_error() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Asks the lock holder to show its window. It may not listen yet when both were launched
+/// together, so this retries for a few seconds; `false` when it never answered.
+pub fn wake_holder(path: &Path) -> bool {
+    let deadline = Instant::now() + WAKE_WAIT;
+    loop {
+        if wake_running(path) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(TIMEOUT);
+    }
+}
+
+/// Asks the instance recorded at `path` to show its window; `true` when it answered.
+pub fn wake_running(path: &Path) -> bool {
+    let Some(port) = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| content.trim().parse::<u16>().ok())
+    else {
+        return false;
+    };
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, TIMEOUT) else {
+        return false;
+    };
+    let mut reply = [0u8; WAKE.len()];
+    stream.set_read_timeout(Some(TIMEOUT)).is_ok()
+        && stream.write_all(WAKE).is_ok()
+        && stream.read_exact(&mut reply).is_ok()
+        && reply == WAKE
+}
+
+/// Records this instance at `path` and calls `on_wake` for every later launch that asks.
+/// Only the holder of the [`InstanceLock`] listens.
+pub fn listen(
+    _lock: &InstanceLock,
+    path: &Path,
+    on_wake: impl Fn() + Send + 'static,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, listener.local_addr()?.port().to_string())?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if answer(stream) {
+                on_wake();
+            }
+        }
+    });
+    Ok(())
+}
+
+fn answer(mut stream: TcpStream) -> bool {
+    let mut request = [0u8; WAKE.len()];
+    stream.set_read_timeout(Some(TIMEOUT)).is_ok()
+        && stream.read_exact(&mut request).is_ok()
+        && request == WAKE
+        && stream.write_all(WAKE).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn only_one_launch_takes_the_lock_until_it_exits() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(INSTANCE_FILE);
+
+        let first = acquire(&path).unwrap();
+        assert!(first.is_some());
+        assert!(acquire(&path).unwrap().is_none());
+
+        drop(first);
+        assert!(acquire(&path).unwrap().is_some());
+    }
+
+    #[test]
+    fn later_launch_wakes_the_lock_holder() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(INSTANCE_FILE);
+        assert!(!wake_running(&path));
+
+        let lock = acquire(&path).unwrap().unwrap();
+        let (woken, wakes) = mpsc::channel();
+        listen(&lock, &path, move || woken.send(()).unwrap()).unwrap();
+
+        assert!(wake_holder(&path));
+        wakes.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    #[test]
+    fn unrelated_program_on_the_recorded_port_is_not_an_instance() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(INSTANCE_FILE);
+        let other = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        fs::write(&path, other.local_addr().unwrap().port().to_string()).unwrap();
+        thread::spawn(move || {
+            for mut stream in other.incoming().flatten() {
+                let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n");
+            }
+        });
+
+        assert!(!wake_running(&path));
+    }
+}
@@ -0,0 +1,173 @@
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use dm8_export_backend::config_store::ConfigStore;
+use dm8_export_backend::export::job::active_jobs;
+use dm8_export_backend::models::ExportJob;
+use tauri::{
+    AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    SystemTrayMenuItem, SystemTraySubmenu, Window, WindowEvent,
+};
+
+use crate::reveal;
+
+const OPEN: &str = "open";
+const QUIT: &str = "quit";
+/// Menu ids of the "Recent exports" entries are this prefix followed by the job id.
+const RECENT_PREFIX: &str = "recent:";
+const RECENT_LIMIT: usize = 8;
+/// How often the recent list is reread, so exports finished while the window is hidden
+/// show up without opening it.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+pub fn build() -> SystemTray {
+    SystemTray::new().with_menu(menu(&[]))
+}
+
+fn menu(recent: &[ExportJob]) -> SystemTrayMenu {
+    let mut recent_menu = SystemTrayMenu::new();
+    if recent.is_empty() {
+        recent_menu =
+            recent_menu.add_item(CustomMenuItem::new("recent-empty", "暂无导出").disabled());
+    }
+    for job in recent {
+        recent_menu = recent_menu.add_item(CustomMenuItem::new(
+            format!("{RECENT_PREFIX}{}", job.id),
+            recent_label(job),
+        ));
+    }
+
+    SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(OPEN, "打开"))
+        .add_submenu(SystemTraySubmenu::new("最近导出", recent_menu))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(QUIT, "退出"))
+}
+
+/// `ddl · <file name>`; the file names already carry the export timestamp.
+fn recent_label(job: &ExportJob) -> String {
+    let file = job
+        .file_path
+        .as_deref()
+        .map(|path| {
+            Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string())
+        })
+        .unwrap_or_default();
+    format!("{} · {}", job.kind, file)
+}
+
+fn recent_jobs(store: &ConfigStore) -> Vec<ExportJob> {
+    match store.recent_jobs(RECENT_LIMIT) {
+        Ok(jobs) => jobs,
+        Err(err) => {
+            eprintln!("Failed to read recent exports: {err:#}");
+            Vec::new()
+        }
+    }
+}
+
+/// Fills in the recent exports now and keeps them current from a background thread. The
+/// config store is opened once here and managed by the app, so the refresh and the recent
+/// entries share its connection.
+pub fn start_refresh(app: &AppHandle) {
+    let store = match ConfigStore::ensure_default_path() {
+        Ok(store) => store,
+        Err(err) => {
+            eprintln!("Failed to open the config store for recent exports: {err:#}");
+            return;
+        }
+    };
+    app.manage(store.clone());
+    let app = app.clone();
+    thread::spawn(move || {
+        let mut shown: Option<Vec<String>> = None;
+        loop {
+            let jobs = recent_jobs(&store);
+            let ids: Vec<String> = jobs.iter().map(|job| job.id.clone()).collect();
+            if shown.as_ref() != Some(&ids) {
+                if let Err(err) = app.tray_handle().set_menu(menu(&jobs)) {
+                    eprintln!("Failed to update tray menu: {err}");
+                }
+                shown = Some(ids);
+            }
+            thread::sleep(REFRESH_INTERVAL);
+        }
+    });
+}
+
+pub fn handle_event(app: &AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } => show_main_window(app),
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            OPEN => show_main_window(app),
+            QUIT => quit(app),
+            other => {
+                if let Some(job_id) = other.strip_prefix(RECENT_PREFIX) {
+                    reveal_job(app, job_id);
+                }
+            }
+        },
+        _ => {}
+    }
+}
+
+/// Closing the window only hides it, so the embedded backend and any export it is running
+/// keep going; "退出" in the tray menu ends the app.
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+    if let WindowEvent::CloseRequested { api, .. } = event {
+        api.prevent_close();
+        if let Err(err) = window.hide() {
+            eprintln!("Failed to hide window: {err}");
+        }
+    }
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn reveal_job(app: &AppHandle, job_id: &str) {
+    let Some(store) = app.try_state::<ConfigStore>() else {
+        return;
+    };
+    let file_path = store
+        .get_job_timeline(job_id)
+        .ok()
+        .flatten()
+        .and_then(|timeline| timeline.job.file_path);
+    let Some(file_path) = file_path else {
+        return;
+    };
+    if let Err(err) = reveal::reveal(Path::new(&file_path)) {
+        tauri::api::dialog::message(None::<&Window>, "无法打开文件位置", format!("{err:#}"));
+    }
+}
+
+/// Asks before quitting while exports are still running, since they stop with the app.
+fn quit(app: &AppHandle) {
+    let running = active_jobs();
+    if running == 0 {
+        app.exit(0);
+        return;
+    }
+
+    let app = app.clone();
+    tauri::api::dialog::ask(
+        None::<&Window>,
+        "DM8 Export Tool",
+        format!("还有 {running} 个导出任务正在运行，退出将中断它们。确定退出？"),
+        move |confirmed| {
+            if confirmed {
+                app.exit(0);
+            }
+        },
+    );
+}
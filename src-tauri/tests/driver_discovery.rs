@@ -1,6 +1,6 @@
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "linux", target_os = "macos"))]
 mod tests {
-    use dm8_export_tauri::driver::parse_odbcinst_for_dm8;
+    use dm8_export_tauri::driver::{parse_odbcinst_drivers, parse_odbcinst_for_dm8};
 
     #[test]
     fn parses_dm8_section_driver_value() {
@@ -15,4 +15,48 @@ Driver = /opt/dm/libdodbc.so
             "/opt/dm/libdodbc.so"
         );
     }
+
+    #[test]
+    fn parses_homebrew_style_dylib_entry() {
+        let ini = r#"
+[ODBC Drivers]
+DM8 ODBC DRIVER = Installed
+
+[dm8 odbc driver]
+Driver=/usr/local/opt/dm8/lib/libdodbc.dylib
+"#;
+        let parsed = parse_odbcinst_for_dm8(ini);
+        assert_eq!(
+            parsed.unwrap().display().to_string(),
+            "/usr/local/opt/dm8/lib/libdodbc.dylib"
+        );
+    }
+
+    #[test]
+    fn lists_every_registered_driver_dm8_section_first() {
+        let ini = r#"
+[DM7 ODBC DRIVER]
+Driver = /opt/dm7/bin/libdodbc.so
+
+[DM8 ODBC DRIVER]
+Description = DM8 Driver
+Driver = /opt/dm8/bin/libdodbc.so
+Driver64 = /opt/dm8/bin64/libdodbc.so
+
+[PostgreSQL]
+Driver = /usr/lib/psqlodbcw.so
+"#;
+        let parsed: Vec<String> = parse_odbcinst_drivers(ini)
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                "/opt/dm8/bin/libdodbc.so",
+                "/opt/dm7/bin/libdodbc.so",
+                "/usr/lib/psqlodbcw.so",
+            ]
+        );
+    }
 }